@@ -17,31 +17,111 @@ Usage:
     value = cache.get("key")
 """
 
+import bisect
 import hashlib
 import logging
+import math
+import os
+import re
+import socket
+import struct
+import threading
+import time
 from functools import lru_cache
-from typing import Any, Dict, List, Optional
+from typing import Any, Callable, Dict, List, Optional, Union
 
 logger = logging.getLogger(__name__)
 
 # Try to import native Rust module
 NATIVE_AVAILABLE = False
 try:
+    from cirkelline_native import (
+        CacheManager as _RustCacheManager,
+    )
     from cirkelline_native import (
         NativeCache as _RustCache,
     )
+    from cirkelline_native import (
+        SchemaValidator as _RustSchemaValidator,
+    )
+    from cirkelline_native import (
+        ShardedCache as _RustShardedCache,
+    )
     from cirkelline_native import (
         batch_hash as _rust_batch_hash,
     )
+    from cirkelline_native import (
+        batch_hash128 as _rust_batch_hash128,
+    )
+    from cirkelline_native import (
+        batch_hash128_seeded as _rust_batch_hash128_seeded,
+    )
+    from cirkelline_native import (
+        batch_hash_seeded as _rust_batch_hash_seeded,
+    )
+    from cirkelline_native import (
+        batch_cosine_similarity as _rust_batch_cosine_similarity,
+    )
     from cirkelline_native import (
         build_cache_key as _rust_build_key,
     )
+    from cirkelline_native import (
+        canonical_hash as _rust_canonical_hash,
+    )
+    from cirkelline_native import (
+        canonical_json as _rust_canonical_json,
+    )
+    from cirkelline_native import (
+        canonical_json_hash as _rust_canonical_json_hash,
+    )
+    from cirkelline_native import (
+        canonical_text as _rust_canonical_text,
+    )
+    from cirkelline_native import (
+        chunk_text as _rust_chunk_text,
+    )
+    from cirkelline_native import (
+        chunk_texts as _rust_chunk_texts,
+    )
+    from cirkelline_native import (
+        cosine_similarity as _rust_cosine_similarity,
+    )
+    from cirkelline_native import (
+        count_tokens as _rust_count_tokens,
+    )
+    from cirkelline_native import (
+        cosine_topk as _rust_cosine_topk,
+    )
+    from cirkelline_native import (
+        dequantize_embeddings as _rust_dequantize_embeddings,
+    )
     from cirkelline_native import (
         extract_json_keys as _rust_extract_keys,
     )
     from cirkelline_native import (
         fast_hash as _rust_hash,
     )
+    from cirkelline_native import (
+        fast_hash128 as _rust_fast_hash128,
+    )
+    from cirkelline_native import (
+        fast_hash128_seeded as _rust_fast_hash128_seeded,
+    )
+    from cirkelline_native import (
+        fast_hash_seeded as _rust_fast_hash_seeded,
+    )
+    from cirkelline_native import (
+        parse_ndjson as _rust_parse_ndjson,
+    )
+    from cirkelline_native import (
+        quantize_embeddings as _rust_quantize_embeddings,
+    )
+    from cirkelline_native import (
+        top_k_similar as _rust_top_k_similar,
+    )
+    from cirkelline_native import (
+        validate_json_schema as _rust_validate_json_schema,
+    )
 
     NATIVE_AVAILABLE = True
     logger.info("Native Rust extensions loaded - performance mode enabled")
@@ -53,58 +133,916 @@ except ImportError:
 
 
 # Python fallback implementations
+
+# Partition keys are stored as "name:key"
+_PARTITION_SEPARATOR = ":"
+
+
+class _Computation:
+    """A get_or_compute callback in progress, awaited by other callers of the key."""
+
+    def __init__(self):
+        self.owner = threading.get_ident()
+        self.done = threading.Event()
+        self.value: Optional[str] = None
+        self.error: Optional[BaseException] = None
+
+
 class _PythonCache:
     """Pure Python LRU cache fallback."""
 
-    def __init__(self, max_size: int = 10000, ttl_seconds: int = 300):
+    def __init__(
+        self,
+        max_size: int = 10000,
+        ttl_seconds: int = 300,
+        max_bytes: Optional[int] = None,
+        on_evict: Optional[Callable[[str, str, str], Any]] = None,
+    ):
         self.max_size = max_size
         self.ttl_seconds = ttl_seconds
+        self.max_bytes = max_bytes
+        self.on_evict = on_evict
         self._cache: Dict[str, str] = {}
-        self._stats = {"hits": 0, "misses": 0}
+        # Deadlines of entries with a per-key TTL
+        self._expires: Dict[str, float] = {}
+        self._bytes = 0
+        self._stats = {"hits": 0, "misses": 0, "evictions": 0}
+        self._partitions: Dict[str, Dict[str, int]] = {}
+        self._computing: Dict[str, _Computation] = {}
+        self._computing_lock = threading.Lock()
+        # Held while a batch commits and by multi-key calls, so those never see a partial batch
+        self._batch_lock = threading.RLock()
+
+    @staticmethod
+    def _weight(key: str, value: str) -> int:
+        return len(key.encode()) + len(value.encode())
+
+    def _over_capacity(self) -> bool:
+        if self.max_bytes is not None:
+            return self._bytes > self.max_bytes
+        return len(self._cache) > self.max_size
+
+    def _evict(self) -> None:
+        while self._cache and self._over_capacity():
+            self._evicted(next(iter(self._cache)), "size")
+
+    def _drop_expired(self, key: str) -> None:
+        deadline = self._expires.get(key)
+        if deadline is not None and time.monotonic() >= deadline:
+            self._evicted(key, "expired")
+
+    def _evicted(self, key: str, cause: str) -> None:
+        value = self._cache[key]
+        self.delete(key)
+        self._stats["evictions"] += 1
+        name, sep, _ = key.partition(_PARTITION_SEPARATOR)
+        if sep and name in self._partitions:
+            self._partitions[name]["evictions"] += 1
+        if self.on_evict is not None:
+            try:
+                self.on_evict(key, value, cause)
+            except Exception:
+                logger.exception("on_evict callback failed")
 
     def get(self, key: str) -> Optional[str]:
+        self._drop_expired(key)
         if key in self._cache:
             self._stats["hits"] += 1
             return self._cache[key]
         self._stats["misses"] += 1
         return None
 
+    def get_bytes(self, key: str) -> Optional[memoryview]:
+        value = self.get(key)
+        return memoryview(value.encode()) if value is not None else None
+
     def set(self, key: str, value: str) -> None:
-        if len(self._cache) >= self.max_size:
-            # Simple eviction: remove first item
-            first_key = next(iter(self._cache))
-            del self._cache[first_key]
+        self.delete(key)
+        if self.max_bytes is not None and self._weight(key, value) > self.max_bytes:
+            return
         self._cache[key] = value
+        self._bytes += self._weight(key, value)
+        # Simple eviction: remove oldest items
+        self._evict()
+
+    def set_with_ttl(self, key: str, value: str, ttl_seconds: int) -> None:
+        self.set(key, value)
+        if key in self._cache:
+            self._expires[key] = time.monotonic() + ttl_seconds
+
+    def get_or_compute(
+        self, key: str, callback: Callable[[], str], ttl_seconds: Optional[int] = None
+    ) -> str:
+        value = self.get(key)
+        if value is not None:
+            return value
+        with self._computing_lock:
+            computation = self._computing.get(key)
+            leader = computation is None
+            if leader:
+                if key in self._cache:
+                    return self._cache[key]
+                computation = self._computing[key] = _Computation()
+
+        if not leader:
+            if computation.owner == threading.get_ident():
+                raise RuntimeError(f"get_or_compute({key!r}) called from its own callback")
+            computation.done.wait()
+            if computation.error is not None:
+                raise computation.error
+            return computation.value
+
+        try:
+            value = callback()
+            if not isinstance(value, str):
+                raise TypeError(f"get_or_compute callback must return str, got {type(value).__name__}")
+            if ttl_seconds is None:
+                self.set(key, value)
+            else:
+                self.set_with_ttl(key, value, ttl_seconds)
+            computation.value = value
+            return value
+        except BaseException as e:
+            computation.error = e
+            raise
+        finally:
+            with self._computing_lock:
+                del self._computing[key]
+            computation.done.set()
+
+    def expire(self, key: str, ttl_seconds: int) -> bool:
+        if not self.exists(key):
+            return False
+        self._expires[key] = time.monotonic() + ttl_seconds
+        return True
 
     def delete(self, key: str) -> bool:
+        self._expires.pop(key, None)
         if key in self._cache:
-            del self._cache[key]
+            self._bytes -= self._weight(key, self._cache.pop(key))
             return True
         return False
 
+    def get_many(self, keys: List[str]) -> Dict[str, str]:
+        found = {}
+        with self._batch_lock:
+            for key in keys:
+                value = self.get(key)
+                if value is not None:
+                    found[key] = value
+        return found
+
+    def set_many(self, items: Dict[str, str]) -> None:
+        with self._batch_lock:
+            for key, value in items.items():
+                self.set(key, value)
+
+    def delete_many(self, keys: List[str]) -> int:
+        with self._batch_lock:
+            return sum(self.delete(key) for key in keys)
+
     def exists(self, key: str) -> bool:
+        self._drop_expired(key)
         return key in self._cache
 
     def clear(self) -> None:
         self._cache.clear()
+        self._expires.clear()
+        self._bytes = 0
+
+    def reconfigure(
+        self,
+        max_size: Optional[int] = None,
+        ttl_seconds: Optional[int] = None,
+        max_bytes: Optional[int] = None,
+    ) -> None:
+        if max_size is not None:
+            self.max_size = max_size
+        if max_bytes is not None:
+            self.max_bytes = max_bytes or None
+        self._evict()
+        if ttl_seconds is not None:
+            self.ttl_seconds = ttl_seconds
+
+    def partition(self, name: str) -> "_PythonCachePartition":
+        if not name or _PARTITION_SEPARATOR in name:
+            raise ValueError(
+                f"Invalid partition name {name!r}: must be non-empty and not contain "
+                f"'{_PARTITION_SEPARATOR}'"
+            )
+        stats = self._partitions.setdefault(name, {"hits": 0, "misses": 0, "evictions": 0})
+        return _PythonCachePartition(self, name, stats)
+
+    def clear_partition(self, name: str) -> int:
+        prefix = name + _PARTITION_SEPARATOR
+        with self._batch_lock:
+            return self.delete_many([key for key in self._cache if key.startswith(prefix)])
+
+    def partitions(self) -> List[str]:
+        return sorted(self._partitions)
+
+    def batch(self) -> "_PythonCacheBatch":
+        return _PythonCacheBatch(self)
 
     def get_stats(self) -> Dict[str, Any]:
+        # Apply pending expiries first, like the Rust cache
+        for key in list(self._expires):
+            self._drop_expired(key)
         total = self._stats["hits"] + self._stats["misses"]
         hit_rate = self._stats["hits"] / total if total > 0 else 0.0
         return {
             "hits": self._stats["hits"],
             "misses": self._stats["misses"],
+            "evictions": self._stats["evictions"],
             "size": len(self._cache),
+            "max_size": self.max_size,
+            "ttl_seconds": self.ttl_seconds,
+            "bytes": self._bytes,
+            "max_bytes": self.max_bytes,
             "hit_rate": hit_rate,
         }
 
+    def metrics_prometheus(self, name: str = "default", prefix: str = "cirkelline_cache") -> str:
+        return _prometheus_text(prefix, [(name, self.get_stats())])
+
     def size(self) -> int:
         return len(self._cache)
 
 
-def _python_hash(data: str) -> int:
+class _PythonCachePartition:
+    """Namespaced view whose keys are stored as "name:key"."""
+
+    def __init__(self, cache: _PythonCache, name: str, stats: Dict[str, int]):
+        self._cache = cache
+        self.name = name
+        self._stats = stats
+
+    def _key(self, key: str) -> str:
+        return self.name + _PARTITION_SEPARATOR + key
+
+    def get(self, key: str) -> Optional[str]:
+        value = self._cache.get(self._key(key))
+        self._stats["hits" if value is not None else "misses"] += 1
+        return value
+
+    def set(self, key: str, value: str) -> None:
+        self._cache.set(self._key(key), value)
+
+    def set_with_ttl(self, key: str, value: str, ttl_seconds: int) -> None:
+        self._cache.set_with_ttl(self._key(key), value, ttl_seconds)
+
+    def get_or_compute(
+        self, key: str, callback: Callable[[], str], ttl_seconds: Optional[int] = None
+    ) -> str:
+        hit = self._cache.exists(self._key(key))
+        self._stats["hits" if hit else "misses"] += 1
+        return self._cache.get_or_compute(self._key(key), callback, ttl_seconds)
+
+    def expire(self, key: str, ttl_seconds: int) -> bool:
+        return self._cache.expire(self._key(key), ttl_seconds)
+
+    def delete(self, key: str) -> bool:
+        return self._cache.delete(self._key(key))
+
+    def exists(self, key: str) -> bool:
+        return self._cache.exists(self._key(key))
+
+    def get_many(self, keys: List[str]) -> Dict[str, str]:
+        found = {}
+        for key in keys:
+            value = self.get(key)
+            if value is not None:
+                found[key] = value
+        return found
+
+    def set_many(self, items: Dict[str, str]) -> None:
+        for key, value in items.items():
+            self.set(key, value)
+
+    def delete_many(self, keys: List[str]) -> int:
+        return sum(self.delete(key) for key in keys)
+
+    def clear(self) -> int:
+        return self._cache.clear_partition(self.name)
+
+    def get_stats(self) -> Dict[str, Any]:
+        prefix = self.name + _PARTITION_SEPARATOR
+        entries = [(k, v) for k, v in self._cache._cache.items() if k.startswith(prefix)]
+        total = self._stats["hits"] + self._stats["misses"]
+        return {
+            "name": self.name,
+            "hits": self._stats["hits"],
+            "misses": self._stats["misses"],
+            "evictions": self._stats["evictions"],
+            "size": len(entries),
+            "bytes": sum(self._cache._weight(k, v) for k, v in entries),
+            "hit_rate": self._stats["hits"] / total if total > 0 else 0.0,
+        }
+
+
+class _PythonCacheBatch:
+    """Pending mutations applied when the with-block exits without error."""
+
+    _DELETED = object()
+
+    def __init__(self, cache: _PythonCache):
+        self._cache = cache
+        self._ops: List[tuple] = []
+
+    def set(self, key: str, value: str) -> None:
+        self._ops.append((key, value))
+
+    def delete(self, key: str) -> None:
+        self._ops.append((key, self._DELETED))
+
+    def get(self, key: str) -> Optional[str]:
+        for k, v in reversed(self._ops):
+            if k == key:
+                return None if v is self._DELETED else v
+        return self._cache._cache.get(key)
+
+    def commit(self) -> None:
+        with self._cache._batch_lock:
+            for key, value in self._ops:
+                if value is self._DELETED:
+                    self._cache.delete(key)
+                else:
+                    self._cache.set(key, value)
+        self._ops.clear()
+
+    def rollback(self) -> None:
+        self._ops.clear()
+
+    def __len__(self) -> int:
+        return len(self._ops)
+
+    def __enter__(self) -> "_PythonCacheBatch":
+        return self
+
+    def __exit__(self, exc_type, exc_value, traceback) -> bool:
+        if exc_type is None:
+            self.commit()
+        else:
+            self.rollback()
+        return False
+
+
+class _PythonCacheManager:
+    """Pure Python fallback for the named cache manager."""
+
+    def __init__(self):
+        self._caches: Dict[str, _PythonCache] = {}
+
+    def cache(
+        self,
+        name: str,
+        max_size: int = 10000,
+        ttl_seconds: int = 300,
+        max_bytes: Optional[int] = None,
+        on_evict: Optional[Callable[[str, str, str], Any]] = None,
+    ) -> _PythonCache:
+        if name not in self._caches:
+            self._caches[name] = _PythonCache(max_size, ttl_seconds, max_bytes, on_evict)
+        return self._caches[name]
+
+    def get(self, name: str) -> Optional[_PythonCache]:
+        return self._caches.get(name)
+
+    def remove(self, name: str) -> bool:
+        return self._caches.pop(name, None) is not None
+
+    def names(self) -> List[str]:
+        return sorted(self._caches)
+
+    def clear_all(self) -> None:
+        for cache in self._caches.values():
+            cache.clear()
+
+    def get_stats(self) -> Dict[str, Any]:
+        per_cache = {name: c.get_stats() for name, c in list(self._caches.items())}
+        hits = sum(s["hits"] for s in per_cache.values())
+        misses = sum(s["misses"] for s in per_cache.values())
+        total = hits + misses
+        return {
+            "caches": per_cache,
+            "cache_count": len(per_cache),
+            "hits": hits,
+            "misses": misses,
+            "evictions": sum(s["evictions"] for s in per_cache.values()),
+            "size": sum(s["size"] for s in per_cache.values()),
+            "bytes": sum(s["bytes"] for s in per_cache.values()),
+            "hit_rate": hits / total if total > 0 else 0.0,
+        }
+
+    def metrics_prometheus(self, prefix: str = "cirkelline_cache") -> str:
+        return _prometheus_text(prefix, [(name, self._caches[name].get_stats()) for name in self.names()])
+
+
+_PROMETHEUS_METRICS = [
+    ("hits_total", "counter", "Cache lookups that found a value", "hits"),
+    ("misses_total", "counter", "Cache lookups that found nothing", "misses"),
+    ("evictions_total", "counter", "Entries evicted by size or expiry", "evictions"),
+    ("entries", "gauge", "Entries currently cached", "size"),
+    ("hit_ratio", "gauge", "Hits divided by lookups", "hit_rate"),
+]
+
+
+def _prometheus_text(prefix: str, caches: List[tuple]) -> str:
+    if not re.fullmatch(r"[A-Za-z_:][A-Za-z0-9_:]*", prefix):
+        raise ValueError(f"Invalid metric prefix {prefix!r}")
+    lines = []
+    for suffix, kind, help_text, field in _PROMETHEUS_METRICS:
+        lines.append(f"# HELP {prefix}_{suffix} {help_text}")
+        lines.append(f"# TYPE {prefix}_{suffix} {kind}")
+        for name, stats in caches:
+            value = stats[field]
+            # Match Rust's float formatting: whole numbers without ".0"
+            text = str(int(value)) if float(value).is_integer() else repr(value)
+            label = name.replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n")
+            lines.append(f'{prefix}_{suffix}{{cache="{label}"}} {text}')
+    return "\n".join(lines) + "\n"
+
+
+# Cross-process sharded cache; the wire format matches the Rust ShardedCache
+_RING_VNODES = 64
+_SHARD_TIMEOUT = 2.0
+_SHARD_DEFAULT_TTL = 2**64 - 1
+_OP_GET, _OP_SET, _OP_DELETE, _OP_EXISTS, _OP_CLEAR, _OP_SIZE = range(1, 7)
+
+
+def _shard_text(text: str) -> bytes:
+    data = text.encode()
+    return struct.pack("<I", len(data)) + data
+
+
+def _read_exact(reader: Any, size: int) -> bytes:
+    data = reader.read(size)
+    if len(data) < size:
+        raise EOFError("cache shard connection closed")
+    return data
+
+
+def _read_shard_text(reader: Any) -> str:
+    (size,) = struct.unpack("<I", _read_exact(reader, 4))
+    return _read_exact(reader, size).decode()
+
+
+class _PythonShard:
+    """One shard as seen from this process: served here, or reached over its socket."""
+
+    def __init__(self, directory: str, index: int, max_size: int, ttl_seconds: int):
+        self.index = index
+        self.socket_path = os.path.join(directory, f"shard-{index}.sock")
+        self.lock_path = os.path.join(directory, f"shard-{index}.lock")
+        self.max_size = max_size
+        self.ttl_seconds = ttl_seconds
+        # Set while this process owns the shard
+        self.cache: Optional[_PythonCache] = None
+        self.pid: Optional[int] = None
+        self._cache_lock = threading.Lock()
+        self._lock_file: Any = None
+        self._closed = False
+        self._conn: Optional[tuple] = None
+        self._conn_lock = threading.Lock()
+
+    def owned(self) -> bool:
+        # A forked child sees its parent's shards but must use the socket
+        return self.cache is not None and self.pid == os.getpid()
+
+    def claim(self) -> bool:
+        import fcntl
+
+        if self.cache is not None:
+            return self.owned()
+        lock_file = open(self.lock_path, "a")
+        try:
+            fcntl.flock(lock_file, fcntl.LOCK_EX | fcntl.LOCK_NB)
+        except BlockingIOError:
+            lock_file.close()
+            return False
+        try:
+            # Left behind by an owner that exited without cleaning up
+            if os.path.exists(self.socket_path):
+                os.unlink(self.socket_path)
+            listener = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+            listener.bind(self.socket_path)
+            listener.listen()
+        except BaseException:
+            lock_file.close()
+            raise
+        self._lock_file = lock_file
+        self.cache = _PythonCache(self.max_size, self.ttl_seconds)
+        self.pid = os.getpid()
+        threading.Thread(target=self._serve, args=(listener,), name="cirkelline-cache-shard", daemon=True).start()
+        return True
+
+    def close(self) -> None:
+        """Stop serving an owned shard and free it for another process."""
+        if not self.owned() or self._closed:
+            return
+        self._closed = True
+        # Wake the accept loop so it sees _closed, then free the path for the next owner
+        try:
+            with socket.socket(socket.AF_UNIX, socket.SOCK_STREAM) as wake:
+                wake.connect(self.socket_path)
+        except OSError:
+            pass
+        try:
+            os.unlink(self.socket_path)
+        except OSError:
+            pass
+        self._lock_file.close()
+
+    def apply(self, op: int, ttl: int, key: str, value: str) -> tuple:
+        """Run a request on the owned cache; returns (found, count, value)."""
+        with self._cache_lock:
+            cache = self.cache
+            if op == _OP_GET:
+                found = cache.get(key)
+                return (found is not None, 0, found or "")
+            if op == _OP_SET:
+                if ttl == _SHARD_DEFAULT_TTL:
+                    cache.set(key, value)
+                else:
+                    cache.set_with_ttl(key, value, ttl)
+                return (True, 0, "")
+            if op == _OP_DELETE:
+                return (cache.delete(key), 0, "")
+            if op == _OP_EXISTS:
+                return (cache.exists(key), 0, "")
+            if op == _OP_CLEAR:
+                cache.clear()
+                return (True, 0, "")
+            if op == _OP_SIZE:
+                return (False, cache.size(), "")
+        raise ValueError(f"Unknown shard operation {op}")
+
+    def _serve(self, listener: socket.socket) -> None:
+        with listener:
+            while True:
+                try:
+                    conn, _ = listener.accept()
+                except OSError:
+                    time.sleep(0.01)
+                    continue
+                if self._closed:
+                    conn.close()
+                    return
+                threading.Thread(target=self._serve_connection, args=(conn,), daemon=True).start()
+
+    def _serve_connection(self, conn: socket.socket) -> None:
+        with conn, conn.makefile("rb") as reader:
+            while True:
+                try:
+                    op, ttl = struct.unpack("<BQ", _read_exact(reader, 9))
+                    key = _read_shard_text(reader)
+                    value = _read_shard_text(reader)
+                    # Hanging up sends the client on to the shard's next owner
+                    if self._closed:
+                        return
+                    found, count, data = self.apply(op, ttl, key, value)
+                    conn.sendall(struct.pack("<?Q", found, count) + _shard_text(data))
+                except (OSError, EOFError, ValueError):
+                    return
+
+    def _exchange(self, frame: bytes) -> tuple:
+        conn, reader = self._conn
+        conn.sendall(frame)
+        found, count = struct.unpack("<?Q", _read_exact(reader, 9))
+        return (found, count, _read_shard_text(reader))
+
+    def _disconnect(self) -> None:
+        if self._conn is not None:
+            self._conn[1].close()
+            self._conn[0].close()
+            self._conn = None
+
+    def send(self, op: int, key: str = "", value: str = "", ttl: int = _SHARD_DEFAULT_TTL) -> tuple:
+        if self.owned():
+            return self.apply(op, ttl, key, value)
+        frame = struct.pack("<BQ", op, ttl) + _shard_text(key) + _shard_text(value)
+        with self._conn_lock:
+            # The connection goes dead when the owner exits; then find the new one
+            if self._conn is not None:
+                try:
+                    return self._exchange(frame)
+                except (OSError, EOFError):
+                    self._disconnect()
+
+            deadline = time.monotonic() + _SHARD_TIMEOUT
+            while True:
+                conn = socket.socket(socket.AF_UNIX, socket.SOCK_STREAM)
+                conn.settimeout(_SHARD_TIMEOUT)
+                try:
+                    try:
+                        conn.connect(self.socket_path)
+                    except OSError:
+                        conn.close()
+                        # Nobody serves the shard: take it over, unless its owner is still starting
+                        if self.claim() and self.owned():
+                            return self.apply(op, ttl, key, value)
+                        raise
+                    self._conn = (conn, conn.makefile("rb"))
+                    return self._exchange(frame)
+                except (OSError, EOFError) as e:
+                    self._disconnect()
+                    if time.monotonic() >= deadline:
+                        raise ConnectionError(f"Cache shard {self.index} unreachable: {e}") from e
+                    time.sleep(0.01)
+
+
+class _PythonShardedCache:
+    """Pure Python fallback for the cross-process sharded cache."""
+
+    def __init__(self, path: str, shards: int = 8, max_size: int = 10000, ttl_seconds: int = 300):
+        if shards < 1:
+            raise ValueError("shards must be at least 1")
+        os.makedirs(path, exist_ok=True)
+        shard_size = -(-max_size // shards)
+        self._shards = [_PythonShard(os.fspath(path), index, shard_size, ttl_seconds) for index in range(shards)]
+        # Claim one free shard up front so the shards spread over the workers
+        for shard in self._shards:
+            if shard.claim():
+                break
+        ring = sorted(
+            (_python_hash(f"shard-{index}-{vnode}"), index) for index in range(shards) for vnode in range(_RING_VNODES)
+        )
+        self._points = [point for point, _ in ring]
+        self._ring_shards = [index for _, index in ring]
+        self._stats = {"hits": 0, "misses": 0}
+
+    def __del__(self):
+        for shard in getattr(self, "_shards", []):
+            shard.close()
+
+    def _send(self, key: str, op: int, value: str = "", ttl: int = _SHARD_DEFAULT_TTL) -> tuple:
+        return self._shards[self.shard_for(key)].send(op, key, value, ttl)
+
+    def get(self, key: str) -> Optional[str]:
+        found, _, value = self._send(key, _OP_GET)
+        self._stats["hits" if found else "misses"] += 1
+        return value if found else None
+
+    def set(self, key: str, value: str) -> None:
+        self._send(key, _OP_SET, value)
+
+    def set_with_ttl(self, key: str, value: str, ttl_seconds: int) -> None:
+        self._send(key, _OP_SET, value, ttl_seconds)
+
+    def delete(self, key: str) -> bool:
+        return self._send(key, _OP_DELETE)[0]
+
+    def exists(self, key: str) -> bool:
+        return self._send(key, _OP_EXISTS)[0]
+
+    def clear(self) -> None:
+        for shard in self._shards:
+            shard.send(_OP_CLEAR)
+
+    def size(self) -> int:
+        return sum(shard.send(_OP_SIZE)[1] for shard in self._shards)
+
+    def shard_for(self, key: str) -> int:
+        point = bisect.bisect_left(self._points, _python_hash(key))
+        return self._ring_shards[point % len(self._points)]
+
+    def owned_shards(self) -> List[int]:
+        return [shard.index for shard in self._shards if shard.owned()]
+
+    def get_stats(self) -> Dict[str, Any]:
+        size = self.size()
+        total = self._stats["hits"] + self._stats["misses"]
+        return {
+            "hits": self._stats["hits"],
+            "misses": self._stats["misses"],
+            "size": size,
+            "shards": len(self._shards),
+            "owned_shards": self.owned_shards(),
+            "hit_rate": self._stats["hits"] / total if total > 0 else 0.0,
+        }
+
+
+def _as_bytes(data: Union[str, bytes]) -> bytes:
+    return data if isinstance(data, bytes) else data.encode()
+
+
+def _python_hash(data: Union[str, bytes]) -> int:
     """Python fallback for fast_hash."""
-    return int(hashlib.md5(data.encode()).hexdigest()[:16], 16)
+    return int(hashlib.md5(_as_bytes(data)).hexdigest()[:16], 16)
+
+
+def _python_hash128(data: Union[str, bytes], seed: int = 0) -> int:
+    """Python fallback for the 128-bit hashes."""
+    digest = hashlib.blake2b(
+        _as_bytes(data), digest_size=16, salt=seed.to_bytes(8, "little")
+    ).digest()
+    return int.from_bytes(digest, "little")
+
+
+def _python_hash_seeded(data: Union[str, bytes], seed: int) -> int:
+    """Python fallback for fast_hash_seeded."""
+    return _python_hash128(data, seed) >> 64
+
+
+def _python_canonical_text(text: str) -> str:
+    """Python fallback for canonical_text (must match the Rust normalization)."""
+    kept = "".join(c for c in text.lower() if c.isalnum() or c.isspace())
+    return " ".join(kept.split())
+
+
+def _python_canonical_hash(text: str) -> int:
+    """Python fallback for canonical_hash; needs the xxhash package for parity."""
+    try:
+        import xxhash
+    except ImportError as e:
+        raise RuntimeError(
+            "canonical_hash requires the native module or the xxhash package"
+        ) from e
+    return xxhash.xxh3_64_intdigest(_python_canonical_text(text).encode())
+
+
+def _reject_constant(name: str) -> Any:
+    raise ValueError(f"Invalid JSON: {name} is not allowed")
+
+
+def _canonical_float(value: float) -> str:
+    """Shortest round-trip digits laid out as in ECMAScript's Number::toString."""
+    if value == 0:
+        return "0"
+    sign = "-" if value < 0 else ""
+    mantissa, _, exponent = repr(abs(value)).partition("e")
+    whole, _, fraction = mantissa.partition(".")
+    digits = (whole + fraction).lstrip("0")
+    # value = 0.digits * 10^n
+    n = len(whole) + int(exponent or 0) - (len(whole + fraction) - len(digits))
+    digits = digits.rstrip("0")
+    k = len(digits)
+
+    if k <= n <= 21:
+        body = digits + "0" * (n - k)
+    elif 0 < n <= 21:
+        body = f"{digits[:n]}.{digits[n:]}"
+    elif -6 < n <= 0:
+        body = "0." + "0" * -n + digits
+    else:
+        e = n - 1
+        exp = f"+{e}" if e >= 0 else str(e)
+        body = f"{digits}e{exp}" if k == 1 else f"{digits[0]}.{digits[1:]}e{exp}"
+    return sign + body
+
+
+def _write_canonical(value: Any) -> str:
+    import json
+
+    if isinstance(value, bool) or value is None:
+        return json.dumps(value)
+    if isinstance(value, int):
+        # Like the native module: exact within 64 bits, a float beyond
+        if -(2**63) <= value < 2**64:
+            return str(value)
+        return _canonical_float(float(value))
+    if isinstance(value, float):
+        return _canonical_float(value)
+    if isinstance(value, str):
+        return json.dumps(value, ensure_ascii=False)
+    if isinstance(value, list):
+        return "[" + ",".join(_write_canonical(v) for v in value) + "]"
+    return (
+        "{"
+        + ",".join(
+            json.dumps(k, ensure_ascii=False) + ":" + _write_canonical(value[k])
+            for k in sorted(value)
+        )
+        + "}"
+    )
+
+
+def _python_canonical_json(json_str: str) -> str:
+    """Python fallback for canonical_json (must match the Rust output)."""
+    import json
+
+    try:
+        value = json.loads(json_str, parse_constant=_reject_constant)
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Invalid JSON: {e}") from e
+    return _write_canonical(value)
+
+
+def _python_canonical_json_hash(json_str: str) -> int:
+    """Python fallback for canonical_json_hash; needs the xxhash package for parity."""
+    try:
+        import xxhash
+    except ImportError as e:
+        raise RuntimeError(
+            "canonical_json_hash requires the native module or the xxhash package"
+        ) from e
+    return xxhash.xxh3_64_intdigest(_python_canonical_json(json_str).encode())
+
+
+# Characters of a word counted as one token (must match TOKEN_CHARS in Rust)
+_TOKEN_CHARS = 4
+
+# Closing quotes and brackets skipped when looking for a sentence end
+_CLOSERS = "\"')]»”’"
+
+
+def _token_cost(c: str, run: int) -> tuple:
+    """Tokens added by c and the new letter/digit run length."""
+    if c.isspace():
+        return 0, 0
+    if c.isalnum():
+        return int(run % _TOKEN_CHARS == 0), run + 1
+    return 1, 0
+
+
+def _python_count_tokens(text: str) -> int:
+    """Python fallback for count_tokens."""
+    tokens = run = 0
+    for c in text:
+        cost, run = _token_cost(c, run)
+        tokens += cost
+    return tokens
+
+
+def _check_chunk_args(max_tokens: int, overlap: int) -> None:
+    if max_tokens <= 0 or not 0 <= overlap < max_tokens:
+        raise ValueError(
+            f"Need max_tokens > overlap >= 0, got max_tokens={max_tokens} overlap={overlap}"
+        )
+
+
+def _text_units(text: str, max_tokens: int) -> List[list]:
+    """Words (or pieces of words longer than a chunk) as [start, end, tokens, boundary]."""
+    units: List[list] = []
+    current: Optional[list] = None
+    run = 0
+
+    def close(unit: list) -> None:
+        word = text[unit[0] : unit[1]].rstrip(_CLOSERS)
+        unit[3] = word[-1:] in (".", "!", "?")
+        units.append(unit)
+
+    for index, c in enumerate(text):
+        if c.isspace():
+            run = 0
+            if current is not None:
+                close(current)
+                current = None
+            if c == "\n" and units:
+                units[-1][3] = True
+            continue
+        cost, run = _token_cost(c, run)
+        if current is not None and current[2] + cost > max_tokens:
+            close(current)
+            current = None
+            cost, run = _token_cost(c, 0)
+        if current is None:
+            current = [index, index, 0, False]
+        current[1] = index + 1
+        current[2] += cost
+    if current is not None:
+        close(current)
+    return units
+
+
+def _python_chunk_text(text: str, max_tokens: int, overlap: int = 0) -> List[str]:
+    """Python fallback for chunk_text (must match the Rust splitting)."""
+    _check_chunk_args(max_tokens, overlap)
+    units = _text_units(text, max_tokens)
+    chunks = []
+    start, min_end = 0, 1
+    while start < len(units):
+        end, tokens = start, 0
+        while end < len(units) and tokens + units[end][2] <= max_tokens:
+            tokens += units[end][2]
+            end += 1
+
+        if end < len(units):
+            seen, best = 0, None
+            for index in range(start, end):
+                seen += units[index][2]
+                if units[index][3] and seen * 2 >= tokens and index + 1 >= min_end:
+                    best = index + 1
+            end = best or end
+        chunks.append(text[units[start][0] : units[end - 1][1]])
+        if end == len(units):
+            break
+
+        next_start, carried = end, 0
+        while next_start > start + 1 and carried + units[next_start - 1][2] <= overlap:
+            carried += units[next_start - 1][2]
+            next_start -= 1
+        while carried + units[end][2] > max_tokens:
+            carried -= units[next_start][2]
+            next_start += 1
+        start, min_end = next_start, end + 1
+    return chunks
+
+
+def _python_chunk_texts(texts: List[str], max_tokens: int, overlap: int = 0) -> List[List[str]]:
+    """Python fallback for chunk_texts."""
+    _check_chunk_args(max_tokens, overlap)
+    return [_python_chunk_text(text, max_tokens, overlap) for text in texts]
 
 
 def _python_build_key(parts: List[str]) -> str:
@@ -115,11 +1053,26 @@ def _python_build_key(parts: List[str]) -> str:
     return combined
 
 
-def _python_batch_hash(items: List[str]) -> List[int]:
+def _python_batch_hash(items: List[Union[str, bytes]]) -> List[int]:
     """Python fallback for batch_hash."""
     return [_python_hash(item) for item in items]
 
 
+def _python_batch_hash128(items: List[Union[str, bytes]]) -> List[int]:
+    """Python fallback for batch_hash128."""
+    return [_python_hash128(item) for item in items]
+
+
+def _python_batch_hash_seeded(items: List[Union[str, bytes]], seed: int) -> List[int]:
+    """Python fallback for batch_hash_seeded."""
+    return [_python_hash_seeded(item, seed) for item in items]
+
+
+def _python_batch_hash128_seeded(items: List[Union[str, bytes]], seed: int) -> List[int]:
+    """Python fallback for batch_hash128_seeded."""
+    return [_python_hash128(item, seed) for item in items]
+
+
 def _python_extract_keys(json_str: str, keys: List[str]) -> Dict[str, str]:
     """Python fallback for extract_json_keys."""
     import json
@@ -131,26 +1084,260 @@ def _python_extract_keys(json_str: str, keys: List[str]) -> Dict[str, str]:
         return {}
 
 
+class _PythonSchemaValidator:
+    """Python fallback for SchemaValidator (requires the jsonschema package)."""
+
+    def __init__(self, schema_str: str):
+        import json
+
+        import jsonschema
+
+        self._validator = jsonschema.validators.validator_for(
+            json.loads(schema_str)
+        )(json.loads(schema_str))
+
+    def validate(self, json_str: str) -> List[str]:
+        import json
+
+        return [
+            "".join(f"/{p}" for p in error.absolute_path) + f": {error.message}"
+            for error in self._validator.iter_errors(json.loads(json_str))
+        ]
+
+    def is_valid(self, json_str: str) -> bool:
+        return not self.validate(json_str)
+
+
+def _python_validate_json_schema(json_str: str, schema_str: str) -> List[str]:
+    """Python fallback for validate_json_schema."""
+    return _PythonSchemaValidator(schema_str).validate(json_str)
+
+
+def _python_parse_ndjson(data: bytes, keys: List[str]) -> Dict[str, List[Any]]:
+    """Python fallback for parse_ndjson."""
+    import json
+
+    columns: Dict[str, List[Any]] = {key: [] for key in keys}
+    for number, line in enumerate(data.split(b"\n"), start=1):
+        if not line.strip():
+            continue
+        try:
+            record = json.loads(line)
+        except json.JSONDecodeError as e:
+            raise ValueError(f"Invalid NDJSON on line {number}: {e}") from e
+        if not isinstance(record, dict):
+            raise ValueError(f"Invalid NDJSON on line {number}: expected an object")
+        for key in keys:
+            columns[key].append(record.get(key))
+    return columns
+
+
+def _python_cosine_similarity(a: List[float], b: List[float]) -> float:
+    """Python fallback for cosine_similarity."""
+    if len(a) != len(b):
+        raise ValueError(f"Dimension mismatch: {len(a)} vs {len(b)}")
+    dot = sum(x * y for x, y in zip(a, b))
+    norm_a = sum(x * x for x in a) ** 0.5
+    norm_b = sum(y * y for y in b) ** 0.5
+    if norm_a == 0.0 or norm_b == 0.0:
+        return 0.0
+    return dot / (norm_a * norm_b)
+
+
+def _python_matrix_rows(query: Any, matrix: Any) -> List[Any]:
+    """Rows of a matrix given as vectors or as a flat buffer of n * dim values."""
+    dim = len(query)
+    if dim == 0:
+        raise ValueError("Query vector is empty")
+    rows = matrix.tolist() if hasattr(matrix, "tolist") else list(matrix)
+    if rows and not isinstance(rows[0], (list, tuple)):
+        if len(rows) % dim:
+            raise ValueError(
+                f"Matrix buffer length {len(rows)} is not a multiple of "
+                f"the query dimension {dim}"
+            )
+        rows = [rows[i : i + dim] for i in range(0, len(rows), dim)]
+    for row in rows:
+        if len(row) != dim:
+            raise ValueError(
+                f"Dimension mismatch: query has {dim} values, "
+                f"matrix rows have {len(row)}"
+            )
+    return rows
+
+
+def _python_batch_cosine_similarity(query: Any, matrix: Any) -> List[float]:
+    """Python fallback for batch_cosine_similarity."""
+    return [_python_cosine_similarity(query, row) for row in _python_matrix_rows(query, matrix)]
+
+
+def _python_top_k_similar(query: Any, matrix: Any, k: int) -> List[tuple]:
+    """Python fallback for top_k_similar (accepts lists, memoryviews and arrays)."""
+    scored = list(enumerate(_python_batch_cosine_similarity(query, matrix)))
+    scored.sort(key=lambda item: (-item[1], item[0]))
+    return scored[:k]
+
+
+_QUANTIZATION_SCHEMES = {"float32": 0, "int8": 1}
+
+
+def _f32(x: float) -> float:
+    """Round a Python float to float32 precision."""
+    return struct.unpack("<f", struct.pack("<f", x))[0]
+
+
+def _scheme_id(scheme: str) -> int:
+    if scheme not in _QUANTIZATION_SCHEMES:
+        raise ValueError(
+            f"Unknown quantization scheme '{scheme}' (expected float32 or int8)"
+        )
+    return _QUANTIZATION_SCHEMES[scheme]
+
+
+def _python_quantize_embeddings(vectors: List[List[float]], scheme: str = "int8") -> bytes:
+    """Python fallback for quantize_embeddings."""
+    scheme_id = _scheme_id(scheme)
+    dim = len(vectors[0]) if vectors else 0
+    if any(len(v) != dim for v in vectors):
+        raise ValueError("All vectors must have the same dimension")
+
+    blob = bytearray(struct.pack("<BII", scheme_id, dim, len(vectors)))
+    for vector in vectors:
+        values = [_f32(x) for x in vector]
+        if scheme_id == 0:
+            blob += struct.pack(f"<{dim}f", *values)
+            continue
+        scale = _f32(max((abs(x) for x in values), default=0.0) / 127.0)
+        blob += struct.pack("<f", scale)
+        for x in values:
+            q = _f32(x / scale) if scale > 0.0 else 0.0
+            # Round half away from zero, like Rust's f32::round
+            q = math.copysign(math.floor(abs(q) + 0.5), q)
+            blob += struct.pack("<b", int(max(-127.0, min(127.0, q))))
+    return bytes(blob)
+
+
+def _python_dequantize_embeddings(blob: bytes, scheme: str = "int8") -> List[List[float]]:
+    """Python fallback for dequantize_embeddings."""
+    scheme_id = _scheme_id(scheme)
+    if len(blob) < 9:
+        raise ValueError("Quantized blob is truncated")
+    if blob[0] != scheme_id:
+        raise ValueError(f"Blob was written with scheme {blob[0]}, not {scheme}")
+    _, dim, count = struct.unpack_from("<BII", blob)
+    record = 4 * dim if scheme_id == 0 else 4 + dim
+    if len(blob) - 9 != count * record:
+        raise ValueError(
+            f"Quantized blob has {len(blob) - 9} bytes, "
+            f"expected {count * record} for {count} vectors of {dim}"
+        )
+
+    if record == 0:
+        return [[] for _ in range(count)]
+
+    vectors = []
+    for offset in range(9, len(blob), record):
+        if scheme_id == 0:
+            vectors.append(list(struct.unpack_from(f"<{dim}f", blob, offset)))
+            continue
+        (scale,) = struct.unpack_from("<f", blob, offset)
+        values = struct.unpack_from(f"<{dim}b", blob, offset + 4)
+        vectors.append([_f32(q * scale) for q in values])
+    return vectors
+
+
 # Export the appropriate implementation
 if NATIVE_AVAILABLE:
     NativeCache = _RustCache
+    CacheManager = _RustCacheManager
+    ShardedCache = _RustShardedCache
     fast_hash = _rust_hash
     build_cache_key = _rust_build_key
     batch_hash = _rust_batch_hash
+    fast_hash128 = _rust_fast_hash128
+    fast_hash_seeded = _rust_fast_hash_seeded
+    fast_hash128_seeded = _rust_fast_hash128_seeded
+    batch_hash128 = _rust_batch_hash128
+    batch_hash_seeded = _rust_batch_hash_seeded
+    batch_hash128_seeded = _rust_batch_hash128_seeded
+    canonical_text = _rust_canonical_text
+    canonical_hash = _rust_canonical_hash
+    canonical_json = _rust_canonical_json
+    canonical_json_hash = _rust_canonical_json_hash
     extract_json_keys = _rust_extract_keys
+    SchemaValidator = _RustSchemaValidator
+    validate_json_schema = _rust_validate_json_schema
+    parse_ndjson = _rust_parse_ndjson
+    cosine_similarity = _rust_cosine_similarity
+    cosine_topk = _rust_cosine_topk
+    batch_cosine_similarity = _rust_batch_cosine_similarity
+    top_k_similar = _rust_top_k_similar
+    count_tokens = _rust_count_tokens
+    chunk_text = _rust_chunk_text
+    chunk_texts = _rust_chunk_texts
+    quantize_embeddings = _rust_quantize_embeddings
+    dequantize_embeddings = _rust_dequantize_embeddings
 else:
     NativeCache = _PythonCache
+    CacheManager = _PythonCacheManager
+    ShardedCache = _PythonShardedCache
     fast_hash = _python_hash
     build_cache_key = _python_build_key
     batch_hash = _python_batch_hash
+    fast_hash128 = _python_hash128
+    fast_hash_seeded = _python_hash_seeded
+    fast_hash128_seeded = _python_hash128
+    batch_hash128 = _python_batch_hash128
+    batch_hash_seeded = _python_batch_hash_seeded
+    batch_hash128_seeded = _python_batch_hash128_seeded
+    canonical_text = _python_canonical_text
+    canonical_hash = _python_canonical_hash
+    canonical_json = _python_canonical_json
+    canonical_json_hash = _python_canonical_json_hash
     extract_json_keys = _python_extract_keys
+    SchemaValidator = _PythonSchemaValidator
+    validate_json_schema = _python_validate_json_schema
+    parse_ndjson = _python_parse_ndjson
+    cosine_similarity = _python_cosine_similarity
+    cosine_topk = _python_top_k_similar
+    batch_cosine_similarity = _python_batch_cosine_similarity
+    top_k_similar = _python_top_k_similar
+    count_tokens = _python_count_tokens
+    chunk_text = _python_chunk_text
+    chunk_texts = _python_chunk_texts
+    quantize_embeddings = _python_quantize_embeddings
+    dequantize_embeddings = _python_dequantize_embeddings
 
 
 __all__ = [
     "NativeCache",
+    "CacheManager",
+    "ShardedCache",
     "fast_hash",
     "build_cache_key",
     "batch_hash",
+    "fast_hash128",
+    "fast_hash_seeded",
+    "fast_hash128_seeded",
+    "batch_hash128",
+    "batch_hash_seeded",
+    "batch_hash128_seeded",
+    "canonical_text",
+    "canonical_hash",
+    "canonical_json",
+    "canonical_json_hash",
     "extract_json_keys",
+    "SchemaValidator",
+    "validate_json_schema",
+    "parse_ndjson",
+    "cosine_similarity",
+    "cosine_topk",
+    "batch_cosine_similarity",
+    "top_k_similar",
+    "count_tokens",
+    "chunk_text",
+    "chunk_texts",
+    "quantize_embeddings",
+    "dequantize_embeddings",
     "NATIVE_AVAILABLE",
 ]
@@ -0,0 +1,31 @@
+"""
+Compare batch_hash (parallel, GIL released) with hashing one item at a time.
+
+Run after `maturin develop --release`:
+    python benchmarks/bench_batch_hash.py
+"""
+
+import timeit
+
+from cirkelline_native import batch_hash, fast_hash
+
+SIZES = [1_000, 100_000, 1_000_000, 5_000_000]
+ITERATIONS = 3
+
+
+def main() -> None:
+    print(f"{'items':>12} {'loop (ms)':>12} {'batch str (ms)':>16} {'batch bytes (ms)':>18} {'speedup':>8}")
+    for size in SIZES:
+        items = [f"finding-{i}-{'x' * (i % 64)}" for i in range(size)]
+        as_bytes = [item.encode() for item in items]
+        loop = timeit.timeit(lambda: [fast_hash(item) for item in items], number=ITERATIONS)
+        batch = timeit.timeit(lambda: batch_hash(items), number=ITERATIONS)
+        batch_bytes = timeit.timeit(lambda: batch_hash(as_bytes), number=ITERATIONS)
+        print(
+            f"{size:>12} {loop / ITERATIONS * 1e3:>12.1f} {batch / ITERATIONS * 1e3:>16.1f} "
+            f"{batch_bytes / ITERATIONS * 1e3:>18.1f} {loop / batch:>7.1f}x"
+        )
+
+
+if __name__ == "__main__":
+    main()
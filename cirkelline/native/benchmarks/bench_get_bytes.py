@@ -0,0 +1,33 @@
+"""
+Compare NativeCache.get (copying str) with get_bytes (zero-copy buffer).
+
+Run after `maturin develop --release`:
+    python benchmarks/bench_get_bytes.py
+"""
+
+import timeit
+
+from cirkelline_native import NativeCache
+
+SIZES = [1_000, 100_000, 1_000_000, 10_000_000]
+ITERATIONS = 200
+
+
+def main() -> None:
+    cache = NativeCache(max_size=len(SIZES), ttl_seconds=3600)
+    for size in SIZES:
+        cache.set(f"value-{size}", "x" * size)
+
+    print(f"{'size':>12} {'get (us)':>12} {'get_bytes (us)':>16} {'speedup':>8}")
+    for size in SIZES:
+        key = f"value-{size}"
+        as_str = timeit.timeit(lambda: cache.get(key), number=ITERATIONS)
+        as_view = timeit.timeit(lambda: memoryview(cache.get_bytes(key)), number=ITERATIONS)
+        print(
+            f"{size:>12} {as_str / ITERATIONS * 1e6:>12.1f} "
+            f"{as_view / ITERATIONS * 1e6:>16.1f} {as_str / as_view:>7.1f}x"
+        )
+
+
+if __name__ == "__main__":
+    main()
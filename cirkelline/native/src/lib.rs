@@ -5,64 +5,253 @@
 //! Build: maturin develop --release
 //! Install: pip install .
 
+// pyo3 0.22's generated wrappers convert PyResult errors into themselves
+#![allow(clippy::useless_conversion)]
+// The shard wire protocol is only spoken over unix sockets
+#![cfg_attr(not(unix), allow(dead_code))]
+
+use moka::notification::RemovalCause;
 use moka::sync::Cache;
-use parking_lot::RwLock;
+use moka::Expiry;
+use parking_lot::{Condvar, Mutex, RwLock};
+use pyo3::exceptions::PyBufferError;
+use pyo3::ffi;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyString};
+use pyo3::types::{PyBytes, PyDict, PyList, PyString};
+use rayon::prelude::*;
 use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::raw::{c_int, c_void};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use xxhash_rust::xxh3::xxh3_64;
+use std::time::{Duration, Instant};
+#[cfg(unix)]
+use std::{
+    fs::{File, OpenOptions, TryLockError},
+    io::BufReader,
+    os::unix::net::{UnixListener, UnixStream},
+    sync::OnceLock,
+    thread,
+};
+use xxhash_rust::xxh3::{xxh3_128, xxh3_128_with_seed, xxh3_64, xxh3_64_with_seed};
 
-/// High-performance LRU cache with TTL support
+/// High-performance LRU cache with TTL support.
+/// Capacity is counted in entries, or in bytes of key + value when `max_bytes` is set.
 #[pyclass]
 pub struct NativeCache {
-    cache: Cache<String, String>,
+    // Values are shared buffers so hits can be handed out without copying
+    cache: Cache<String, CacheValue>,
     stats: Arc<RwLock<CacheStats>>,
+    evicted: Arc<EvictionLog>,
+    on_evict: Option<PyObject>,
+    partitions: Arc<Partitions>,
+    /// Keys whose value a `get_or_compute` callback is computing
+    computing: Mutex<HashMap<String, Arc<Computation>>>,
+    /// Held exclusively while a batch commits, and shared by multi-key calls that
+    /// run without the GIL, so those never see a partial batch
+    batch_lock: RwLock<()>,
+    max_size: u64,
+    ttl_seconds: u64,
+    max_bytes: Option<u64>,
 }
 
+#[derive(Default, Clone)]
 struct CacheStats {
     hits: u64,
     misses: u64,
     evictions: u64,
 }
 
+/// Stats of each partition that has been opened, by name
+type Partitions = RwLock<HashMap<String, Arc<RwLock<CacheStats>>>>;
+
+/// Keys of a partition are stored as "name:key", so names cannot contain ':'
+const PARTITION_SEPARATOR: char = ':';
+
+/// Filled by moka's eviction listener. The listener may run on any thread,
+/// with or without the GIL, so evicted entries are queued and handed to the
+/// Python callback by the next cache call instead.
+struct EvictionLog {
+    stats: Arc<RwLock<CacheStats>>,
+    partitions: Arc<Partitions>,
+    /// Queue entries only while a callback is set
+    notify: AtomicBool,
+    pending: Mutex<Vec<EvictedEntry>>,
+}
+
+impl CacheStats {
+    fn hit_rate(&self) -> f64 {
+        let total = self.hits + self.misses;
+        if total > 0 {
+            self.hits as f64 / total as f64
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Key, value and cause of an evicted entry
+type EvictedEntry = (Arc<String>, Arc<str>, &'static str);
+
+impl EvictionLog {
+    fn record(&self, key: Arc<String>, value: CacheValue, cause: RemovalCause) {
+        let cause = match cause {
+            RemovalCause::Expired => "expired",
+            RemovalCause::Size => "size",
+            // Deletes and overwrites are not evictions
+            RemovalCause::Explicit | RemovalCause::Replaced => return,
+        };
+        self.stats.write().evictions += 1;
+        if let Some((name, _)) = key.split_once(PARTITION_SEPARATOR) {
+            if let Some(stats) = self.partitions.read().get(name) {
+                stats.write().evictions += 1;
+            }
+        }
+        if self.notify.load(Ordering::Relaxed) {
+            self.pending.lock().push((key, value.data, cause));
+        }
+    }
+}
+
 #[pymethods]
 impl NativeCache {
-    /// Create a new cache with specified capacity and TTL
+    /// Create a new cache with specified capacity and TTL.
+    /// With `max_bytes`, entries are evicted by total size and `max_size` is not enforced.
+    /// `on_evict(key, value, cause)` is called for entries evicted by size or expiry,
+    /// with cause "size" or "expired".
     #[new]
-    #[pyo3(signature = (max_size=10000, ttl_seconds=300))]
-    fn new(max_size: u64, ttl_seconds: u64) -> Self {
-        let cache = Cache::builder()
-            .max_capacity(max_size)
-            .time_to_live(Duration::from_secs(ttl_seconds))
-            .build();
-
+    #[pyo3(signature = (max_size=10000, ttl_seconds=300, max_bytes=None, on_evict=None))]
+    fn new(max_size: u64, ttl_seconds: u64, max_bytes: Option<u64>, on_evict: Option<PyObject>) -> Self {
+        let stats = Arc::new(RwLock::new(CacheStats::default()));
+        let partitions = Arc::new(Partitions::default());
+        let evicted = Arc::new(EvictionLog {
+            stats: stats.clone(),
+            partitions: partitions.clone(),
+            notify: AtomicBool::new(on_evict.is_some()),
+            pending: Mutex::new(Vec::new()),
+        });
         NativeCache {
-            cache,
-            stats: Arc::new(RwLock::new(CacheStats {
-                hits: 0,
-                misses: 0,
-                evictions: 0,
-            })),
+            cache: build_cache(max_size, ttl_seconds, max_bytes, evicted.clone()),
+            stats,
+            evicted,
+            on_evict,
+            partitions,
+            computing: Mutex::new(HashMap::new()),
+            batch_lock: RwLock::new(()),
+            max_size,
+            ttl_seconds,
+            max_bytes,
         }
     }
 
-    /// Get a value from the cache
-    fn get(&self, key: &str) -> Option<String> {
-        let result = self.cache.get(key);
-        let mut stats = self.stats.write();
-        if result.is_some() {
-            stats.hits += 1;
-        } else {
-            stats.misses += 1;
+    /// Callback for evicted entries, or None
+    #[getter]
+    fn get_on_evict(&self, py: Python<'_>) -> Option<PyObject> {
+        self.on_evict.as_ref().map(|callback| callback.clone_ref(py))
+    }
+
+    #[setter]
+    fn set_on_evict(&mut self, on_evict: Option<PyObject>) {
+        self.evicted.notify.store(on_evict.is_some(), Ordering::Relaxed);
+        if on_evict.is_none() {
+            self.evicted.pending.lock().clear();
         }
-        result
+        self.on_evict = on_evict;
     }
 
-    /// Set a value in the cache
-    fn set(&self, key: &str, value: &str) {
-        self.cache.insert(key.to_string(), value.to_string());
+    /// Change capacity and/or TTL without losing entries.
+    /// Entries are carried over into a rebuilt cache; their TTL restarts, and
+    /// entries without their own TTL pick up the new one.
+    /// `max_bytes=0` switches back to counting entries.
+    #[pyo3(signature = (max_size=None, ttl_seconds=None, max_bytes=None))]
+    fn reconfigure(
+        &mut self,
+        py: Python<'_>,
+        max_size: Option<u64>,
+        ttl_seconds: Option<u64>,
+        max_bytes: Option<u64>,
+    ) {
+        let max_size = max_size.unwrap_or(self.max_size);
+        let ttl_seconds = ttl_seconds.unwrap_or(self.ttl_seconds);
+        let max_bytes = match max_bytes {
+            Some(0) => None,
+            Some(bytes) => Some(bytes),
+            None => self.max_bytes,
+        };
+        if max_size == self.max_size && ttl_seconds == self.ttl_seconds && max_bytes == self.max_bytes {
+            return;
+        }
+
+        let cache = build_cache(max_size, ttl_seconds, max_bytes, self.evicted.clone());
+        for (key, value) in self.cache.iter() {
+            cache.insert(key.as_ref().clone(), value);
+        }
+        // Apply the new capacity now so size() reflects it
+        cache.run_pending_tasks();
+
+        self.cache = cache;
+        self.max_size = max_size;
+        self.ttl_seconds = ttl_seconds;
+        self.max_bytes = max_bytes;
+        self.notify_evicted(py);
+    }
+
+    /// Get a value from the cache
+    fn get(&self, py: Python<'_>, key: &str) -> Option<String> {
+        let value = self.lookup(key).map(|value| value.to_string());
+        self.notify_evicted(py);
+        value
+    }
+
+    /// Get a value as a read-only buffer over the cached data (no copy).
+    /// Use `memoryview(...)` on the result; `bytes(...)` makes a copy.
+    fn get_bytes(&self, py: Python<'_>, key: &str) -> Option<CachedBuffer> {
+        let value = self.lookup(key).map(|data| CachedBuffer { data });
+        self.notify_evicted(py);
+        value
+    }
+
+    /// Set a value in the cache; values larger than `max_bytes` are not cached
+    fn set(&self, py: Python<'_>, key: &str, value: &str) {
+        self.insert(key.to_string(), CacheValue::new(value, None));
+        self.notify_evicted(py);
+    }
+
+    /// Set a value that expires after `ttl_seconds` instead of the cache-wide TTL
+    fn set_with_ttl(&self, py: Python<'_>, key: &str, value: &str, ttl_seconds: u64) {
+        self.insert(key.to_string(), CacheValue::new(value, Some(Duration::from_secs(ttl_seconds))));
+        self.notify_evicted(py);
+    }
+
+    /// Get a value, or compute it with `callback()` (which must return a str) and
+    /// cache it for `ttl_seconds` (default: the cache-wide TTL). Concurrent callers
+    /// for the same missing key wait for the first caller's result instead of
+    /// running `callback` themselves; if it raises, they all get its exception.
+    #[pyo3(signature = (key, callback, ttl_seconds=None))]
+    fn get_or_compute(
+        &self,
+        py: Python<'_>,
+        key: &str,
+        callback: &Bound<'_, PyAny>,
+        ttl_seconds: Option<u64>,
+    ) -> PyResult<String> {
+        let result = self.compute(py, key, callback, ttl_seconds);
+        self.notify_evicted(py);
+        Ok(result?.0.to_string())
+    }
+
+    /// Let an existing entry expire `ttl_seconds` from now. Returns false if the key is missing.
+    fn expire(&self, key: &str, ttl_seconds: u64) -> bool {
+        match self.cache.get(key) {
+            Some(value) => {
+                let ttl = Some(Duration::from_secs(ttl_seconds));
+                self.insert(key.to_string(), CacheValue { ttl, ..value });
+                true
+            }
+            None => false,
+        }
     }
 
     /// Delete a key from the cache
@@ -71,6 +260,42 @@ impl NativeCache {
         true
     }
 
+    /// Get several values in one call; missing keys are left out of the result
+    fn get_many(&self, py: Python<'_>, keys: Vec<String>) -> HashMap<String, String> {
+        let found = py.allow_threads(|| {
+            let _batch = self.batch_lock.read();
+            let found: HashMap<String, String> = keys
+                .iter()
+                .filter_map(|key| Some((key.clone(), self.cache.get(key)?.data.to_string())))
+                .collect();
+            let mut stats = self.stats.write();
+            stats.hits += found.len() as u64;
+            stats.misses += (keys.len() - found.len()) as u64;
+            found
+        });
+        self.notify_evicted(py);
+        found
+    }
+
+    /// Set several values in one call
+    fn set_many(&self, py: Python<'_>, items: HashMap<String, String>) {
+        py.allow_threads(|| {
+            let _batch = self.batch_lock.read();
+            for (key, value) in items {
+                self.insert(key, CacheValue::new(&value, None));
+            }
+        });
+        self.notify_evicted(py);
+    }
+
+    /// Delete several keys in one call; returns how many were present
+    fn delete_many(&self, py: Python<'_>, keys: Vec<String>) -> usize {
+        py.allow_threads(|| {
+            let _batch = self.batch_lock.read();
+            keys.iter().filter(|key| self.cache.remove(key.as_str()).is_some()).count()
+        })
+    }
+
     /// Check if key exists
     fn exists(&self, key: &str) -> bool {
         self.cache.contains_key(key)
@@ -81,85 +306,2089 @@ impl NativeCache {
         self.cache.invalidate_all();
     }
 
-    /// Get cache statistics
+    /// Namespaced view whose keys are stored as "name:key", with its own stats
+    fn partition(slf: Py<Self>, py: Python<'_>, name: &str) -> PyResult<CachePartition> {
+        if name.is_empty() || name.contains(PARTITION_SEPARATOR) {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Invalid partition name {:?}: must be non-empty and not contain '{}'",
+                name, PARTITION_SEPARATOR
+            )));
+        }
+        let stats = slf.borrow(py).partitions.write().entry(name.to_string()).or_default().clone();
+        Ok(CachePartition {
+            cache: slf,
+            name: name.to_string(),
+            stats,
+        })
+    }
+
+    /// Delete every entry of a partition, leaving other partitions alone; returns how many were removed
+    fn clear_partition(&self, py: Python<'_>, name: &str) -> usize {
+        let prefix = partition_key(name, "");
+        py.allow_threads(|| {
+            let _batch = self.batch_lock.read();
+            let keys: Vec<Arc<String>> = self
+                .cache
+                .iter()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, _)| key)
+                .collect();
+            keys.iter().filter(|key| self.cache.remove(key.as_str()).is_some()).count()
+        })
+    }
+
+    /// Names of partitions opened on this cache
+    fn partitions(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.partitions.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Start a batch of mutations applied together when the `with` block exits
+    fn batch(slf: Py<Self>) -> CacheBatch {
+        CacheBatch {
+            cache: slf,
+            ops: Vec::new(),
+        }
+    }
+
+    /// Get cache statistics; pending evictions are applied first
     fn get_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        self.cache.run_pending_tasks();
+        self.notify_evicted(py);
         let stats = self.stats.read();
-        let dict = PyDict::new(py);
+        let dict = PyDict::new_bound(py);
         dict.set_item("hits", stats.hits)?;
         dict.set_item("misses", stats.misses)?;
+        dict.set_item("evictions", stats.evictions)?;
         dict.set_item("size", self.cache.entry_count())?;
-
-        let total = stats.hits + stats.misses;
-        let hit_rate = if total > 0 {
-            stats.hits as f64 / total as f64
-        } else {
-            0.0
-        };
-        dict.set_item("hit_rate", hit_rate)?;
+        dict.set_item("max_size", self.max_size)?;
+        dict.set_item("ttl_seconds", self.ttl_seconds)?;
+        dict.set_item("bytes", self.bytes_used())?;
+        dict.set_item("max_bytes", self.max_bytes)?;
+        dict.set_item("hit_rate", stats.hit_rate())?;
 
         Ok(dict.into())
     }
 
+    /// Hits, misses, evictions, entry count and hit rate in the Prometheus text
+    /// exposition format, labelled `cache="<name>"`, ready to serve on /metrics
+    #[pyo3(signature = (name="default", prefix="cirkelline_cache"))]
+    fn metrics_prometheus(&self, py: Python<'_>, name: &str, prefix: &str) -> PyResult<String> {
+        prometheus_text(prefix, &[(name.to_string(), self.metrics_snapshot(py))])
+    }
+
     /// Get current size
     fn size(&self) -> u64 {
         self.cache.entry_count()
     }
 }
 
-/// Fast string hashing using xxHash3
-#[pyfunction]
-fn fast_hash(data: &str) -> u64 {
-    xxh3_64(data.as_bytes())
+impl NativeCache {
+    /// Counters and entry count; pending evictions are applied first
+    fn metrics_snapshot(&self, py: Python<'_>) -> (CacheStats, u64) {
+        self.cache.run_pending_tasks();
+        self.notify_evicted(py);
+        (self.stats.read().clone(), self.cache.entry_count())
+    }
+
+    /// Look up a value and record the hit or miss
+    fn lookup(&self, key: &str) -> Option<Arc<str>> {
+        let result = self.cache.get(key).map(|value| value.data);
+        let mut stats = self.stats.write();
+        if result.is_some() {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        result
+    }
+
+    /// `get_or_compute`; also returns whether the value was already cached
+    fn compute(
+        &self,
+        py: Python<'_>,
+        key: &str,
+        callback: &Bound<'_, PyAny>,
+        ttl_seconds: Option<u64>,
+    ) -> PyResult<(Arc<str>, bool)> {
+        if let Some(value) = self.lookup(key) {
+            return Ok((value, true));
+        }
+
+        let computation = {
+            let mut computing = self.computing.lock();
+            match computing.get(key) {
+                Some(computation) => Some(computation.clone()),
+                None => {
+                    // Stored by another caller between the lookup and taking the lock
+                    if let Some(value) = self.cache.get(key) {
+                        return Ok((value.data, false));
+                    }
+                    computing.insert(key.to_string(), Arc::new(Computation::new()));
+                    None
+                }
+            }
+        };
+        if let Some(computation) = computation {
+            if computation.owner == std::thread::current().id() {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "get_or_compute({:?}) called from its own callback",
+                    key
+                )));
+            }
+            py.allow_threads(|| computation.wait());
+            return computation.outcome(py).map(|value| (value, false));
+        }
+
+        let result = callback
+            .call0()
+            .and_then(|value| value.extract::<String>())
+            .map(Arc::<str>::from);
+        if let Ok(value) = &result {
+            let ttl = ttl_seconds.map(Duration::from_secs);
+            self.insert(key.to_string(), CacheValue { data: value.clone(), ttl });
+        }
+        if let Some(computation) = self.computing.lock().remove(key) {
+            computation.finish(match &result {
+                Ok(value) => Ok(value.clone()),
+                Err(err) => Err(err.clone_ref(py)),
+            });
+        }
+        result.map(|value| (value, false))
+    }
+
+    /// Insert unless the entry alone exceeds the byte capacity
+    fn insert(&self, key: String, value: CacheValue) {
+        if self.max_bytes.is_some_and(|max| entry_weight(&key, &value.data) as u64 > max) {
+            self.cache.invalidate(&key);
+            return;
+        }
+        self.cache.insert(key, value);
+    }
+
+    /// Hand queued evictions to the `on_evict` callback.
+    /// Callback errors are reported as unraisable so cache calls never fail because of them.
+    fn notify_evicted(&self, py: Python<'_>) {
+        let Some(callback) = &self.on_evict else {
+            return;
+        };
+        let evicted = std::mem::take(&mut *self.evicted.pending.lock());
+        for (key, value, cause) in evicted {
+            if let Err(err) = callback.call1(py, (key.as_str(), &*value, cause)) {
+                err.write_unraisable_bound(py, Some(callback.bind(py)));
+            }
+        }
+    }
+
+    /// Bytes held by keys and values; pending evictions are applied first
+    fn bytes_used(&self) -> u64 {
+        if self.max_bytes.is_some() {
+            self.cache.run_pending_tasks();
+            self.cache.weighted_size()
+        } else {
+            self.cache.iter().map(|(key, value)| entry_weight(&key, &value.data) as u64).sum()
+        }
+    }
 }
 
-/// Fast cache key builder
-#[pyfunction]
-fn build_cache_key(parts: Vec<&str>) -> String {
-    let combined = parts.join(":");
-    if combined.len() > 200 {
-        format!("hash:{:x}", xxh3_64(combined.as_bytes()))
-    } else {
-        combined
+/// Read-only view of a cached value exposed through the buffer protocol
+#[pyclass]
+pub struct CachedBuffer {
+    data: Arc<str>,
+}
+
+#[pymethods]
+impl CachedBuffer {
+    fn __len__(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Decode to `str` (copies)
+    fn decode(&self) -> String {
+        self.data.to_string()
     }
+
+    unsafe fn __getbuffer__(slf: Bound<'_, Self>, view: *mut ffi::Py_buffer, flags: c_int) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("View is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("Cached values are read-only"));
+        }
+
+        // The Arc keeps the bytes alive and unmoved for as long as `obj` holds this object
+        let bytes = slf.borrow().data.as_bytes().as_ptr();
+        let len = slf.borrow().data.len();
+
+        (*view).obj = slf.into_any().into_ptr();
+        (*view).buf = bytes as *mut c_void;
+        (*view).len = len as ffi::Py_ssize_t;
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            c"B".as_ptr() as *mut _
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).ndim = 1;
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            &mut (*view).len
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            std::ptr::null_mut()
+        };
+        (*view).suboffsets = std::ptr::null_mut();
+        (*view).internal = std::ptr::null_mut();
+
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
 }
 
-/// Batch hash multiple strings
-#[pyfunction]
-fn batch_hash(items: Vec<&str>) -> Vec<u64> {
-    items.iter().map(|s| xxh3_64(s.as_bytes())).collect()
+/// A `get_or_compute` callback in progress, awaited by other callers of the same key
+struct Computation {
+    /// Thread running the callback
+    owner: std::thread::ThreadId,
+    result: Mutex<Option<PyResult<Arc<str>>>>,
+    done: Condvar,
 }
 
-/// Fast JSON key extraction (for cache key building)
-#[pyfunction]
-fn extract_json_keys(json_str: &str, keys: Vec<&str>) -> PyResult<HashMap<String, String>> {
-    let value: serde_json::Value = serde_json::from_str(json_str)
-        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+impl Computation {
+    fn new() -> Self {
+        Computation {
+            owner: std::thread::current().id(),
+            result: Mutex::new(None),
+            done: Condvar::new(),
+        }
+    }
 
-    let mut result = HashMap::new();
+    fn finish(&self, result: PyResult<Arc<str>>) {
+        *self.result.lock() = Some(result);
+        self.done.notify_all();
+    }
 
-    if let serde_json::Value::Object(map) = value {
-        for key in keys {
-            if let Some(val) = map.get(key) {
-                let str_val = match val {
-                    serde_json::Value::String(s) => s.clone(),
-                    _ => val.to_string(),
-                };
-                result.insert(key.to_string(), str_val);
+    /// Block until the callback has finished; call without the GIL
+    fn wait(&self) {
+        let mut result = self.result.lock();
+        while result.is_none() {
+            self.done.wait(&mut result);
+        }
+    }
+
+    /// The callback's value or exception, once finished
+    fn outcome(&self, py: Python<'_>) -> PyResult<Arc<str>> {
+        match self.result.lock().as_ref() {
+            Some(Ok(value)) => Ok(value.clone()),
+            Some(Err(err)) => Err(err.clone_ref(py)),
+            None => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Computation not finished")),
+        }
+    }
+}
+
+/// Cached value with an optional TTL override
+#[derive(Clone)]
+struct CacheValue {
+    data: Arc<str>,
+    /// None uses the cache-wide TTL
+    ttl: Option<Duration>,
+}
+
+impl CacheValue {
+    fn new(data: &str, ttl: Option<Duration>) -> Self {
+        CacheValue {
+            data: Arc::from(data),
+            ttl,
+        }
+    }
+}
+
+/// Per-entry expiry: the entry's own TTL, else the cache-wide one.
+/// The TTL restarts whenever the entry is written.
+struct CacheExpiry {
+    default_ttl: Duration,
+}
+
+impl Expiry<String, CacheValue> for CacheExpiry {
+    fn expire_after_create(&self, _key: &String, value: &CacheValue, _created_at: Instant) -> Option<Duration> {
+        Some(value.ttl.unwrap_or(self.default_ttl))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        value: &CacheValue,
+        _updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(value.ttl.unwrap_or(self.default_ttl))
+    }
+}
+
+fn build_cache(
+    max_size: u64,
+    ttl_seconds: u64,
+    max_bytes: Option<u64>,
+    evicted: Arc<EvictionLog>,
+) -> Cache<String, CacheValue> {
+    let builder = Cache::builder()
+        .expire_after(CacheExpiry {
+            default_ttl: Duration::from_secs(ttl_seconds),
+        })
+        .eviction_listener(move |key, value, cause| evicted.record(key, value, cause));
+    match max_bytes {
+        Some(max_bytes) => builder
+            .weigher(|key: &String, value: &CacheValue| entry_weight(key, &value.data))
+            .max_capacity(max_bytes)
+            .build(),
+        None => builder.max_capacity(max_size).build(),
+    }
+}
+
+/// Size of an entry in bytes (UTF-8 key + value)
+fn entry_weight(key: &str, value: &str) -> u32 {
+    u32::try_from(key.len() + value.len()).unwrap_or(u32::MAX)
+}
+
+enum BatchOp {
+    Set(String, String),
+    Delete(String),
+}
+
+/// Pending cache mutations, applied on exit of a `with` block or discarded on exception.
+/// Applied while holding the GIL and the cache's batch lock, so neither other Python
+/// threads nor multi-key calls running without the GIL see a partial batch.
+#[pyclass]
+pub struct CacheBatch {
+    cache: Py<NativeCache>,
+    ops: Vec<BatchOp>,
+}
+
+#[pymethods]
+impl CacheBatch {
+    /// Queue a set
+    fn set(&mut self, key: &str, value: &str) {
+        self.ops.push(BatchOp::Set(key.to_string(), value.to_string()));
+    }
+
+    /// Queue a delete
+    fn delete(&mut self, key: &str) {
+        self.ops.push(BatchOp::Delete(key.to_string()));
+    }
+
+    /// Read through the batch: pending mutations win over cached values
+    fn get(&self, py: Python<'_>, key: &str) -> Option<String> {
+        for op in self.ops.iter().rev() {
+            match op {
+                BatchOp::Set(k, v) if k == key => return Some(v.clone()),
+                BatchOp::Delete(k) if k == key => return None,
+                _ => {}
             }
         }
+        self.cache.borrow(py).cache.get(key).map(|value| value.data.to_string())
     }
 
-    Ok(result)
+    /// Apply pending mutations now
+    fn commit(&mut self, py: Python<'_>) {
+        let cache = self.cache.borrow(py);
+        {
+            let _batch = cache.batch_lock.write();
+            for op in self.ops.drain(..) {
+                match op {
+                    BatchOp::Set(key, value) => cache.insert(key, CacheValue::new(&value, None)),
+                    BatchOp::Delete(key) => cache.cache.invalidate(&key),
+                }
+            }
+        }
+        cache.notify_evicted(py);
+    }
+
+    /// Discard pending mutations
+    fn rollback(&mut self) {
+        self.ops.clear();
+    }
+
+    fn __len__(&self) -> usize {
+        self.ops.len()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Commit on success, discard on exception; exceptions are never suppressed
+    #[pyo3(signature = (exc_type, _exc_value, _traceback))]
+    fn __exit__(
+        &mut self,
+        py: Python<'_>,
+        exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> bool {
+        if exc_type.is_none() {
+            self.commit(py);
+        } else {
+            self.rollback();
+        }
+        false
+    }
+}
+
+fn partition_key(name: &str, key: &str) -> String {
+    format!("{}{}{}", name, PARTITION_SEPARATOR, key)
+}
+
+/// Namespaced view of a cache. Entries share the cache's capacity and TTL;
+/// hits, misses and evictions are also counted separately per partition.
+#[pyclass]
+pub struct CachePartition {
+    cache: Py<NativeCache>,
+    name: String,
+    stats: Arc<RwLock<CacheStats>>,
+}
+
+#[pymethods]
+impl CachePartition {
+    #[getter]
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get(&self, py: Python<'_>, key: &str) -> Option<String> {
+        let cache = self.cache.borrow(py);
+        let value = cache.lookup(&self.key(key)).map(|value| value.to_string());
+        self.record(value.is_some());
+        cache.notify_evicted(py);
+        value
+    }
+
+    fn set(&self, py: Python<'_>, key: &str, value: &str) {
+        let cache = self.cache.borrow(py);
+        cache.insert(self.key(key), CacheValue::new(value, None));
+        cache.notify_evicted(py);
+    }
+
+    #[pyo3(signature = (key, callback, ttl_seconds=None))]
+    fn get_or_compute(
+        &self,
+        py: Python<'_>,
+        key: &str,
+        callback: &Bound<'_, PyAny>,
+        ttl_seconds: Option<u64>,
+    ) -> PyResult<String> {
+        let cache = self.cache.borrow(py);
+        let result = cache.compute(py, &self.key(key), callback, ttl_seconds);
+        cache.notify_evicted(py);
+        self.record(result.as_ref().is_ok_and(|(_, hit)| *hit));
+        Ok(result?.0.to_string())
+    }
+
+    fn set_with_ttl(&self, py: Python<'_>, key: &str, value: &str, ttl_seconds: u64) {
+        self.cache.borrow(py).set_with_ttl(py, &self.key(key), value, ttl_seconds);
+    }
+
+    fn expire(&self, py: Python<'_>, key: &str, ttl_seconds: u64) -> bool {
+        self.cache.borrow(py).expire(&self.key(key), ttl_seconds)
+    }
+
+    fn delete(&self, py: Python<'_>, key: &str) -> bool {
+        self.cache.borrow(py).delete(&self.key(key))
+    }
+
+    fn exists(&self, py: Python<'_>, key: &str) -> bool {
+        self.cache.borrow(py).exists(&self.key(key))
+    }
+
+    fn get_many(&self, py: Python<'_>, keys: Vec<String>) -> HashMap<String, String> {
+        let requested = keys.len();
+        let found: HashMap<String, String> = self
+            .cache
+            .borrow(py)
+            .get_many(py, keys.iter().map(|key| self.key(key)).collect())
+            .into_iter()
+            .map(|(key, value)| (key[self.name.len() + 1..].to_string(), value))
+            .collect();
+        let mut stats = self.stats.write();
+        stats.hits += found.len() as u64;
+        stats.misses += (requested - found.len()) as u64;
+        found
+    }
+
+    fn set_many(&self, py: Python<'_>, items: HashMap<String, String>) {
+        let items = items.into_iter().map(|(key, value)| (self.key(&key), value)).collect();
+        self.cache.borrow(py).set_many(py, items);
+    }
+
+    fn delete_many(&self, py: Python<'_>, keys: Vec<String>) -> usize {
+        let keys = keys.iter().map(|key| self.key(key)).collect();
+        self.cache.borrow(py).delete_many(py, keys)
+    }
+
+    /// Delete every entry of this partition
+    fn clear(&self, py: Python<'_>) -> usize {
+        self.cache.borrow(py).clear_partition(py, &self.name)
+    }
+
+    /// Statistics of this partition only
+    fn get_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let cache = self.cache.borrow(py);
+        cache.cache.run_pending_tasks();
+        cache.notify_evicted(py);
+        let prefix = partition_key(&self.name, "");
+        let (size, bytes) = cache
+            .cache
+            .iter()
+            .filter(|(key, _)| key.starts_with(&prefix))
+            .fold((0u64, 0u64), |(size, bytes), (key, value)| {
+                (size + 1, bytes + entry_weight(&key, &value.data) as u64)
+            });
+
+        let stats = self.stats.read();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("name", &self.name)?;
+        dict.set_item("hits", stats.hits)?;
+        dict.set_item("misses", stats.misses)?;
+        dict.set_item("evictions", stats.evictions)?;
+        dict.set_item("size", size)?;
+        dict.set_item("bytes", bytes)?;
+        dict.set_item("hit_rate", stats.hit_rate())?;
+        Ok(dict.into())
+    }
+}
+
+impl CachePartition {
+    fn key(&self, key: &str) -> String {
+        partition_key(&self.name, key)
+    }
+
+    fn record(&self, hit: bool) {
+        let mut stats = self.stats.write();
+        if hit {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+    }
+}
+
+/// Owner of named caches with per-name policies
+#[pyclass]
+pub struct CacheManager {
+    caches: RwLock<HashMap<String, Py<NativeCache>>>,
+}
+
+#[pymethods]
+impl CacheManager {
+    #[new]
+    fn new() -> Self {
+        CacheManager {
+            caches: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Get the named cache, creating it with the given policy if missing.
+    /// An existing cache keeps its policy and callback; use `reconfigure` to change it.
+    #[pyo3(signature = (name, max_size=10000, ttl_seconds=300, max_bytes=None, on_evict=None))]
+    fn cache(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        max_size: u64,
+        ttl_seconds: u64,
+        max_bytes: Option<u64>,
+        on_evict: Option<PyObject>,
+    ) -> PyResult<Py<NativeCache>> {
+        if let Some(cache) = self.caches.read().get(name) {
+            return Ok(cache.clone_ref(py));
+        }
+
+        let mut caches = self.caches.write();
+        if let Some(cache) = caches.get(name) {
+            return Ok(cache.clone_ref(py));
+        }
+        let cache = Py::new(py, NativeCache::new(max_size, ttl_seconds, max_bytes, on_evict))?;
+        caches.insert(name.to_string(), cache.clone_ref(py));
+        Ok(cache)
+    }
+
+    /// Get an existing cache by name
+    fn get(&self, py: Python<'_>, name: &str) -> Option<Py<NativeCache>> {
+        self.caches.read().get(name).map(|c| c.clone_ref(py))
+    }
+
+    /// Drop a named cache; outstanding handles keep working but are no longer managed
+    fn remove(&self, name: &str) -> bool {
+        self.caches.write().remove(name).is_some()
+    }
+
+    /// Names of managed caches
+    fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.caches.read().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Clear entries of every managed cache
+    fn clear_all(&self, py: Python<'_>) {
+        for cache in self.caches.read().values() {
+            cache.borrow(py).clear();
+        }
+    }
+
+    /// Aggregated statistics plus per-cache breakdown
+    fn get_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let per_cache = PyDict::new_bound(py);
+        let (mut hits, mut misses, mut evictions, mut size, mut bytes) = (0u64, 0u64, 0u64, 0u64, 0u64);
+
+        let caches = self.managed(py);
+        for (name, cache) in &caches {
+            let cache = cache.borrow(py);
+            per_cache.set_item(name, cache.get_stats(py)?)?;
+            let stats = cache.stats.read();
+            hits += stats.hits;
+            misses += stats.misses;
+            evictions += stats.evictions;
+            size += cache.cache.entry_count();
+            drop(stats);
+            bytes += cache.bytes_used();
+        }
+
+        let dict = PyDict::new_bound(py);
+        dict.set_item("caches", per_cache)?;
+        dict.set_item("cache_count", caches.len())?;
+        dict.set_item("hits", hits)?;
+        dict.set_item("misses", misses)?;
+        dict.set_item("evictions", evictions)?;
+        dict.set_item("size", size)?;
+        dict.set_item("bytes", bytes)?;
+        let total = CacheStats { hits, misses, evictions };
+        dict.set_item("hit_rate", total.hit_rate())?;
+
+        Ok(dict.into())
+    }
+
+    /// Metrics of every managed cache in the Prometheus text exposition format,
+    /// one sample per cache labelled `cache="<name>"`
+    #[pyo3(signature = (prefix="cirkelline_cache"))]
+    fn metrics_prometheus(&self, py: Python<'_>, prefix: &str) -> PyResult<String> {
+        let caches: Vec<(String, (CacheStats, u64))> = self
+            .managed(py)
+            .into_iter()
+            .map(|(name, cache)| {
+                let snapshot = cache.borrow(py).metrics_snapshot(py);
+                (name, snapshot)
+            })
+            .collect();
+        prometheus_text(prefix, &caches)
+    }
+}
+
+impl CacheManager {
+    /// Managed caches sorted by name. Taken as a snapshot so that `on_evict`
+    /// callbacks, which may call back into the manager, run without its lock.
+    fn managed(&self, py: Python<'_>) -> Vec<(String, Py<NativeCache>)> {
+        let mut caches: Vec<(String, Py<NativeCache>)> = self
+            .caches
+            .read()
+            .iter()
+            .map(|(name, cache)| (name.clone(), cache.clone_ref(py)))
+            .collect();
+        caches.sort_by(|a, b| a.0.cmp(&b.0));
+        caches
+    }
+}
+
+/// Metric families of the Prometheus export: name suffix, type and help text
+const PROMETHEUS_METRICS: [(&str, &str, &str); 5] = [
+    ("hits_total", "counter", "Cache lookups that found a value"),
+    ("misses_total", "counter", "Cache lookups that found nothing"),
+    ("evictions_total", "counter", "Entries evicted by size or expiry"),
+    ("entries", "gauge", "Entries currently cached"),
+    ("hit_ratio", "gauge", "Hits divided by lookups"),
+];
+
+fn prometheus_text(prefix: &str, caches: &[(String, (CacheStats, u64))]) -> PyResult<String> {
+    let valid = prefix.chars().enumerate().all(|(i, c)| {
+        c.is_ascii_alphabetic() || c == '_' || c == ':' || (i > 0 && c.is_ascii_digit())
+    });
+    if prefix.is_empty() || !valid {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Invalid metric prefix {:?}",
+            prefix
+        )));
+    }
+
+    let mut out = String::new();
+    for (index, (suffix, kind, help)) in PROMETHEUS_METRICS.iter().enumerate() {
+        out.push_str(&format!("# HELP {prefix}_{suffix} {help}\n# TYPE {prefix}_{suffix} {kind}\n"));
+        for (name, (stats, entries)) in caches {
+            let value = match index {
+                0 => stats.hits.to_string(),
+                1 => stats.misses.to_string(),
+                2 => stats.evictions.to_string(),
+                3 => entries.to_string(),
+                _ => stats.hit_rate().to_string(),
+            };
+            let label = name.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+            out.push_str(&format!("{prefix}_{suffix}{{cache=\"{label}\"}} {value}\n"));
+        }
+    }
+    Ok(out)
+}
+
+/// Virtual nodes per shard on the hash ring, so keys spread evenly
+const RING_VNODES: usize = 64;
+/// How long a request keeps retrying a shard whose owner is starting up or exiting
+#[cfg(unix)]
+const SHARD_TIMEOUT: Duration = Duration::from_secs(2);
+/// Wire value for "use the cache-wide TTL"
+const SHARD_DEFAULT_TTL: u64 = u64::MAX;
+
+const OP_GET: u8 = 1;
+const OP_SET: u8 = 2;
+const OP_DELETE: u8 = 3;
+const OP_EXISTS: u8 = 4;
+const OP_CLEAR: u8 = 5;
+const OP_SIZE: u8 = 6;
+
+/// Cache shared by every process that opens it on the same directory, e.g. the
+/// workers of one uvicorn server. Keys are spread over `shards` shards by
+/// consistent hashing. Each shard is held by the process that first claims its
+/// lock file and is served to the others over a unix socket in that directory,
+/// so a write or delete in one worker is seen by all of them. When the owner
+/// exits, the next process to reach the shard takes it over, emptied.
+///
+/// Every process must use the same `shards`. Create the cache in each worker,
+/// not in a parent that forks them.
+/// Unix only: elsewhere the constructor raises OSError.
+#[pyclass]
+pub struct ShardedCache {
+    /// (point, shard) sorted by point
+    ring: Vec<(u64, usize)>,
+    shards: Vec<Shard>,
+    /// Hits and misses seen by this process
+    stats: RwLock<CacheStats>,
+}
+
+#[pymethods]
+impl ShardedCache {
+    /// Open the cache shared through `path`. `max_size` is split evenly over the shards.
+    #[new]
+    #[pyo3(signature = (path, shards=8, max_size=10000, ttl_seconds=300))]
+    fn new(path: PathBuf, shards: usize, max_size: u64, ttl_seconds: u64) -> PyResult<Self> {
+        if shards == 0 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "shards must be at least 1",
+            ));
+        }
+        fs::create_dir_all(&path)?;
+        let shard_size = max_size.div_ceil(shards as u64);
+        let shards: Vec<Shard> = (0..shards)
+            .map(|index| Shard::new(&path, index, shard_size, ttl_seconds))
+            .collect();
+        // Claim one free shard up front so the shards spread over the workers
+        for shard in &shards {
+            if shard.claim()? {
+                break;
+            }
+        }
+
+        let mut ring: Vec<(u64, usize)> = (0..shards.len())
+            .flat_map(|index| {
+                (0..RING_VNODES).map(move |vnode| (xxh3_64(format!("shard-{index}-{vnode}").as_bytes()), index))
+            })
+            .collect();
+        ring.sort_unstable();
+
+        Ok(ShardedCache {
+            ring,
+            shards,
+            stats: RwLock::new(CacheStats::default()),
+        })
+    }
+
+    /// Get a value from the cache
+    fn get(&self, py: Python<'_>, key: &str) -> PyResult<Option<String>> {
+        let reply = self.send(py, self.shard_for(key), ShardRequest::Get(key.to_string()))?;
+        let mut stats = self.stats.write();
+        if reply.found {
+            stats.hits += 1;
+        } else {
+            stats.misses += 1;
+        }
+        Ok(reply.found.then_some(reply.value))
+    }
+
+    /// Set a value in the cache
+    fn set(&self, py: Python<'_>, key: &str, value: &str) -> PyResult<()> {
+        self.send(py, self.shard_for(key), ShardRequest::Set(key.to_string(), value.to_string(), None))?;
+        Ok(())
+    }
+
+    /// Set a value that expires after `ttl_seconds` instead of the cache-wide TTL
+    fn set_with_ttl(&self, py: Python<'_>, key: &str, value: &str, ttl_seconds: u64) -> PyResult<()> {
+        let request = ShardRequest::Set(key.to_string(), value.to_string(), Some(ttl_seconds));
+        self.send(py, self.shard_for(key), request)?;
+        Ok(())
+    }
+
+    /// Delete a key in every process; returns whether it was present
+    fn delete(&self, py: Python<'_>, key: &str) -> PyResult<bool> {
+        Ok(self.send(py, self.shard_for(key), ShardRequest::Delete(key.to_string()))?.found)
+    }
+
+    /// Check if key exists
+    fn exists(&self, py: Python<'_>, key: &str) -> PyResult<bool> {
+        Ok(self.send(py, self.shard_for(key), ShardRequest::Exists(key.to_string()))?.found)
+    }
+
+    /// Clear all entries of all shards
+    fn clear(&self, py: Python<'_>) -> PyResult<()> {
+        for index in 0..self.shards.len() {
+            self.send(py, index, ShardRequest::Clear)?;
+        }
+        Ok(())
+    }
+
+    /// Number of entries over all shards
+    fn size(&self, py: Python<'_>) -> PyResult<u64> {
+        let mut size = 0;
+        for index in 0..self.shards.len() {
+            size += self.send(py, index, ShardRequest::Size)?.count;
+        }
+        Ok(size)
+    }
+
+    /// Index of the shard that holds `key`
+    fn shard_for(&self, key: &str) -> usize {
+        let hash = xxh3_64(key.as_bytes());
+        let point = self.ring.partition_point(|&(point, _)| point < hash);
+        self.ring[point % self.ring.len()].1
+    }
+
+    /// Indexes of the shards served by this process
+    fn owned_shards(&self) -> Vec<usize> {
+        (0..self.shards.len()).filter(|&index| self.shards[index].owned().is_some()).collect()
+    }
+
+    /// Get cache statistics; hits and misses are this process's own
+    fn get_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let size = self.size(py)?;
+        let stats = self.stats.read();
+        let dict = PyDict::new_bound(py);
+        dict.set_item("hits", stats.hits)?;
+        dict.set_item("misses", stats.misses)?;
+        dict.set_item("size", size)?;
+        dict.set_item("shards", self.shards.len())?;
+        dict.set_item("owned_shards", self.owned_shards())?;
+        dict.set_item("hit_rate", stats.hit_rate())?;
+
+        Ok(dict.into())
+    }
+}
+
+impl ShardedCache {
+    /// Run a request on a shard with the GIL released
+    fn send(&self, py: Python<'_>, index: usize, request: ShardRequest) -> PyResult<ShardReply> {
+        let shard = &self.shards[index];
+        py.allow_threads(|| shard.send(&request)).map_err(|err| {
+            PyErr::new::<pyo3::exceptions::PyConnectionError, _>(format!(
+                "Cache shard {} unreachable: {}",
+                index, err
+            ))
+        })
+    }
+}
+
+/// One shard as seen from this process: served here, or reached over its socket
+#[cfg(unix)]
+struct Shard {
+    socket: PathBuf,
+    lock: PathBuf,
+    max_size: u64,
+    ttl_seconds: u64,
+    owned: OnceLock<OwnedShard>,
+    conn: Mutex<Option<BufReader<UnixStream>>>,
+}
+
+/// A shard served by this process. Dropping it stops the server and frees the
+/// shard for another process.
+#[cfg(unix)]
+struct OwnedShard {
+    cache: Cache<String, CacheValue>,
+    closed: Arc<AtomicBool>,
+    socket: PathBuf,
+    pid: u32,
+    // Held until drop; the OS releases it if the process dies
+    _lock: File,
+}
+
+#[cfg(unix)]
+impl Shard {
+    fn new(dir: &Path, index: usize, max_size: u64, ttl_seconds: u64) -> Self {
+        Shard {
+            socket: dir.join(format!("shard-{index}.sock")),
+            lock: dir.join(format!("shard-{index}.lock")),
+            max_size,
+            ttl_seconds,
+            owned: OnceLock::new(),
+            conn: Mutex::new(None),
+        }
+    }
+
+    /// The shard's cache if this process serves it. A forked child sees its
+    /// parent's shards here but must go through the socket like everyone else.
+    fn owned(&self) -> Option<&OwnedShard> {
+        self.owned.get().filter(|owned| owned.pid == std::process::id())
+    }
+
+    /// Become the shard's owner if no live process holds its lock
+    fn claim(&self) -> io::Result<bool> {
+        if self.owned.get().is_some() {
+            return Ok(self.owned().is_some());
+        }
+        let lock = OpenOptions::new().create(true).truncate(false).write(true).open(&self.lock)?;
+        match lock.try_lock() {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => return Ok(false),
+            Err(TryLockError::Error(err)) => return Err(err),
+        }
+        // Left behind by an owner that exited without cleaning up
+        let _ = fs::remove_file(&self.socket);
+        let listener = UnixListener::bind(&self.socket)?;
+
+        let cache = Cache::builder()
+            .expire_after(CacheExpiry {
+                default_ttl: Duration::from_secs(self.ttl_seconds),
+            })
+            .max_capacity(self.max_size)
+            .build();
+        let closed = Arc::new(AtomicBool::new(false));
+        let (served, stop) = (cache.clone(), closed.clone());
+        thread::Builder::new()
+            .name("cirkelline-cache-shard".to_string())
+            .spawn(move || serve_shard(listener, served, stop))?;
+
+        let _ = self.owned.set(OwnedShard {
+            cache,
+            closed,
+            socket: self.socket.clone(),
+            pid: std::process::id(),
+            _lock: lock,
+        });
+        Ok(true)
+    }
+
+    fn send(&self, request: &ShardRequest) -> io::Result<ShardReply> {
+        if let Some(owned) = self.owned() {
+            return Ok(request.apply(&owned.cache));
+        }
+        let mut conn = self.conn.lock();
+        // The connection goes dead when the owner exits; then find the new one
+        if let Some(stream) = conn.as_mut() {
+            if let Ok(reply) = request.exchange(stream) {
+                return Ok(reply);
+            }
+            *conn = None;
+        }
+
+        let deadline = Instant::now() + SHARD_TIMEOUT;
+        loop {
+            let err = match UnixStream::connect(&self.socket) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(SHARD_TIMEOUT))?;
+                    stream.set_write_timeout(Some(SHARD_TIMEOUT))?;
+                    let mut stream = BufReader::new(stream);
+                    match request.exchange(&mut stream) {
+                        Ok(reply) => {
+                            *conn = Some(stream);
+                            return Ok(reply);
+                        }
+                        Err(err) => err,
+                    }
+                }
+                // Nobody serves the shard: take it over, unless its owner is still starting
+                Err(err) => {
+                    if self.claim()? {
+                        if let Some(owned) = self.owned() {
+                            return Ok(request.apply(&owned.cache));
+                        }
+                    }
+                    err
+                }
+            };
+            if Instant::now() >= deadline {
+                return Err(err);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for OwnedShard {
+    fn drop(&mut self) {
+        if self.pid != std::process::id() {
+            return;
+        }
+        self.closed.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees `closed`, then free the path for the next owner
+        let _ = UnixStream::connect(&self.socket);
+        let _ = fs::remove_file(&self.socket);
+    }
+}
+
+/// Shards are served over unix sockets; elsewhere opening a ShardedCache fails
+#[cfg(not(unix))]
+struct Shard;
+
+#[cfg(not(unix))]
+impl Shard {
+    fn new(_dir: &Path, _index: usize, _max_size: u64, _ttl_seconds: u64) -> Self {
+        Shard
+    }
+
+    fn owned(&self) -> Option<&Self> {
+        None
+    }
+
+    fn claim(&self) -> io::Result<bool> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "ShardedCache needs unix domain sockets",
+        ))
+    }
+
+    fn send(&self, _request: &ShardRequest) -> io::Result<ShardReply> {
+        self.claim().map(|_| ShardReply::default())
+    }
+}
+
+/// Accept connections to an owned shard until it is closed
+#[cfg(unix)]
+fn serve_shard(listener: UnixListener, cache: Cache<String, CacheValue>, closed: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if closed.load(Ordering::SeqCst) {
+            break;
+        }
+        match stream {
+            Ok(stream) => {
+                let (cache, closed) = (cache.clone(), closed.clone());
+                thread::spawn(move || serve_connection(stream, &cache, &closed));
+            }
+            // e.g. out of file descriptors; don't spin
+            Err(_) => thread::sleep(Duration::from_millis(10)),
+        }
+    }
+}
+
+/// Answer one client's requests until it disconnects or the shard is closed
+#[cfg(unix)]
+fn serve_connection(stream: UnixStream, cache: &Cache<String, CacheValue>, closed: &AtomicBool) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    loop {
+        let request = ShardRequest::read_from(&mut reader)?;
+        // Hanging up sends the client on to the shard's next owner
+        if closed.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        request.apply(cache).write_to(&mut writer)?;
+    }
+}
+
+/// An operation on a shard, applied by its owner.
+/// Sent as: op u8, ttl u64, key, value; strings as u32 length + UTF-8, integers little-endian.
+enum ShardRequest {
+    Get(String),
+    Set(String, String, Option<u64>),
+    Delete(String),
+    Exists(String),
+    Clear,
+    Size,
+}
+
+/// Whether the key was found (or the write done), the entry count for Size and
+/// the value for Get. Sent as: found u8, count u64, value.
+#[derive(Default)]
+struct ShardReply {
+    found: bool,
+    count: u64,
+    value: String,
+}
+
+impl ShardRequest {
+    fn apply(&self, cache: &Cache<String, CacheValue>) -> ShardReply {
+        let found = match self {
+            ShardRequest::Get(key) => {
+                return match cache.get(key) {
+                    Some(value) => ShardReply {
+                        found: true,
+                        value: value.data.to_string(),
+                        ..ShardReply::default()
+                    },
+                    None => ShardReply::default(),
+                };
+            }
+            ShardRequest::Set(key, value, ttl) => {
+                cache.insert(key.clone(), CacheValue::new(value, ttl.map(Duration::from_secs)));
+                true
+            }
+            ShardRequest::Delete(key) => cache.remove(key).is_some(),
+            ShardRequest::Exists(key) => cache.contains_key(key),
+            ShardRequest::Clear => {
+                cache.invalidate_all();
+                true
+            }
+            ShardRequest::Size => {
+                cache.run_pending_tasks();
+                return ShardReply {
+                    count: cache.entry_count(),
+                    ..ShardReply::default()
+                };
+            }
+        };
+        ShardReply {
+            found,
+            ..ShardReply::default()
+        }
+    }
+
+    #[cfg(unix)]
+    fn exchange(&self, stream: &mut BufReader<UnixStream>) -> io::Result<ShardReply> {
+        self.write_to(stream.get_mut())?;
+        ShardReply::read_from(stream)
+    }
+
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let (op, key, value, ttl) = match self {
+            ShardRequest::Get(key) => (OP_GET, key.as_str(), "", None),
+            ShardRequest::Set(key, value, ttl) => (OP_SET, key.as_str(), value.as_str(), *ttl),
+            ShardRequest::Delete(key) => (OP_DELETE, key.as_str(), "", None),
+            ShardRequest::Exists(key) => (OP_EXISTS, key.as_str(), "", None),
+            ShardRequest::Clear => (OP_CLEAR, "", "", None),
+            ShardRequest::Size => (OP_SIZE, "", "", None),
+        };
+        let mut frame = vec![op];
+        frame.extend_from_slice(&ttl.unwrap_or(SHARD_DEFAULT_TTL).to_le_bytes());
+        write_text(&mut frame, key)?;
+        write_text(&mut frame, value)?;
+        out.write_all(&frame)
+    }
+
+    fn read_from(input: &mut impl Read) -> io::Result<Self> {
+        let mut op = [0u8];
+        input.read_exact(&mut op)?;
+        let ttl = read_u64(input)?;
+        let key = read_text(input)?;
+        let value = read_text(input)?;
+        Ok(match op[0] {
+            OP_GET => ShardRequest::Get(key),
+            OP_SET => ShardRequest::Set(key, value, (ttl != SHARD_DEFAULT_TTL).then_some(ttl)),
+            OP_DELETE => ShardRequest::Delete(key),
+            OP_EXISTS => ShardRequest::Exists(key),
+            OP_CLEAR => ShardRequest::Clear,
+            OP_SIZE => ShardRequest::Size,
+            other => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Unknown shard operation {}", other),
+                ))
+            }
+        })
+    }
+}
+
+impl ShardReply {
+    fn write_to(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut frame = vec![u8::from(self.found)];
+        frame.extend_from_slice(&self.count.to_le_bytes());
+        write_text(&mut frame, &self.value)?;
+        out.write_all(&frame)
+    }
+
+    fn read_from(input: &mut impl Read) -> io::Result<Self> {
+        let mut found = [0u8];
+        input.read_exact(&mut found)?;
+        Ok(ShardReply {
+            found: found[0] != 0,
+            count: read_u64(input)?,
+            value: read_text(input)?,
+        })
+    }
+}
+
+fn write_text(frame: &mut Vec<u8>, text: &str) -> io::Result<()> {
+    let len = u32::try_from(text.len())
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Value too large for a cache shard"))?;
+    frame.extend_from_slice(&len.to_le_bytes());
+    frame.extend_from_slice(text.as_bytes());
+    Ok(())
+}
+
+fn read_u64(input: &mut impl Read) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    input.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_text(input: &mut impl Read) -> io::Result<String> {
+    let mut len = [0u8; 4];
+    input.read_exact(&mut len)?;
+    let mut buf = vec![0u8; u32::from_le_bytes(len) as usize];
+    input.read_exact(&mut buf)?;
+    String::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+/// Fast string hashing using xxHash3
+#[pyfunction]
+fn fast_hash(data: &str) -> u64 {
+    xxh3_64(data.as_bytes())
+}
+
+/// 128-bit xxHash3 for keys where 64 bits collide too often
+#[pyfunction]
+fn fast_hash128(data: &str) -> u128 {
+    xxh3_128(data.as_bytes())
+}
+
+/// Seeded xxHash3 (e.g. one seed per shard ring)
+#[pyfunction]
+fn fast_hash_seeded(data: &str, seed: u64) -> u64 {
+    xxh3_64_with_seed(data.as_bytes(), seed)
+}
+
+/// Seeded 128-bit xxHash3
+#[pyfunction]
+fn fast_hash128_seeded(data: &str, seed: u64) -> u128 {
+    xxh3_128_with_seed(data.as_bytes(), seed)
+}
+
+/// Normalized form used for content hashing: lowercase, punctuation
+/// dropped, whitespace collapsed. Must match the CLA agent's `canonical_text`.
+#[pyfunction]
+fn canonical_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Content hash shared with the CLA agent for findings dedup
+#[pyfunction]
+fn canonical_hash(text: &str) -> u64 {
+    xxh3_64(canonical_text(text).as_bytes())
+}
+
+/// Deterministic JSON text for cache keys: object keys sorted by code point,
+/// no insignificant whitespace, floats written like JavaScript's
+/// `Number.toString` (so `1.0`, `1` and `1e0` are all `1`). Integers that fit
+/// in 64 bits are kept exact. Invalid JSON raises ValueError.
+#[pyfunction]
+fn canonical_json(json_str: &str) -> PyResult<String> {
+    let mut out = String::with_capacity(json_str.len());
+    write_canonical(&mut out, &parse_json(json_str)?);
+    Ok(out)
+}
+
+/// Hash of `canonical_json`, for semantically-equal payloads to share a cache key
+#[pyfunction]
+fn canonical_json_hash(json_str: &str) -> PyResult<u64> {
+    Ok(xxh3_64(canonical_json(json_str)?.as_bytes()))
+}
+
+fn write_canonical(out: &mut String, value: &serde_json::Value) {
+    match value {
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => out.push_str(&i.to_string()),
+            (None, Some(u)) => out.push_str(&u.to_string()),
+            _ => out.push_str(&canonical_float(n.as_f64().unwrap_or(0.0))),
+        },
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(out, item);
+            }
+            out.push(']');
+        }
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<(&String, &serde_json::Value)> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(out, item);
+            }
+            out.push('}');
+        }
+        // Null, booleans and strings already have a single compact form
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// Shortest round-trip digits laid out as in ECMAScript's Number::toString
+fn canonical_float(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    let sign = if value < 0.0 { "-" } else { "" };
+    // serde_json writes the shortest round-trip digits, e.g. "12345.678" or "1e-7"
+    let formatted = serde_json::Value::from(value.abs()).to_string();
+    let (mantissa, exponent) = formatted.split_once(['e', 'E']).unwrap_or((&formatted, "0"));
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let all_digits = format!("{}{}", whole, fraction);
+    let digits = all_digits.trim_start_matches('0');
+    // value = 0.digits * 10^n
+    let n = whole.len() as i32 + exponent.parse::<i32>().unwrap_or(0) - (all_digits.len() - digits.len()) as i32;
+    let digits = digits.trim_end_matches('0');
+    let k = digits.len() as i32;
+
+    let body = if k <= n && n <= 21 {
+        format!("{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let e = n - 1;
+        let e = if e >= 0 { format!("+{}", e) } else { e.to_string() };
+        if k == 1 {
+            format!("{}e{}", digits, e)
+        } else {
+            format!("{}.{}e{}", &digits[..1], &digits[1..], e)
+        }
+    };
+    format!("{}{}", sign, body)
+}
+
+/// Fast cache key builder
+#[pyfunction]
+fn build_cache_key(parts: Vec<String>) -> String {
+    let combined = parts.join(":");
+    if combined.len() > 200 {
+        format!("hash:{:x}", xxh3_64(combined.as_bytes()))
+    } else {
+        combined
+    }
+}
+
+/// Batch input item: str is hashed as UTF-8, bytes as-is
+enum HashInput<'py> {
+    Text(Bound<'py, PyString>),
+    Bytes(Bound<'py, PyBytes>),
+}
+
+// Not derived: the derive builds an error for every failed variant, which
+// makes bytes items several times slower than str
+impl<'py> FromPyObject<'py> for HashInput<'py> {
+    fn extract_bound(item: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(text) = item.downcast::<PyString>() {
+            return Ok(Self::Text(text.clone()));
+        }
+        if let Ok(bytes) = item.downcast::<PyBytes>() {
+            return Ok(Self::Bytes(bytes.clone()));
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+            "Expected str or bytes, got {}",
+            item.get_type().name()?
+        )))
+    }
+}
+
+/// Items hashed per rayon task; smaller batches are not worth splitting
+const HASH_CHUNK: usize = 4096;
+
+/// Hash every item in parallel with the GIL released
+fn hash_batch<T: Send>(py: Python<'_>, items: &[HashInput<'_>], hash: impl Fn(&[u8]) -> T + Sync) -> PyResult<Vec<T>> {
+    let data = items
+        .iter()
+        .map(|item| match item {
+            HashInput::Text(text) => text.to_str().map(str::as_bytes),
+            HashInput::Bytes(bytes) => Ok(bytes.as_bytes()),
+        })
+        .collect::<PyResult<Vec<&[u8]>>>()?;
+    Ok(py.allow_threads(|| data.par_iter().with_min_len(HASH_CHUNK).map(|d| hash(d)).collect()))
+}
+
+/// Batch hash multiple strings or bytes
+#[pyfunction]
+fn batch_hash(py: Python<'_>, items: Vec<HashInput<'_>>) -> PyResult<Vec<u64>> {
+    hash_batch(py, &items, xxh3_64)
+}
+
+/// Batch 128-bit hash
+#[pyfunction]
+fn batch_hash128(py: Python<'_>, items: Vec<HashInput<'_>>) -> PyResult<Vec<u128>> {
+    hash_batch(py, &items, xxh3_128)
+}
+
+/// Batch seeded hash
+#[pyfunction]
+fn batch_hash_seeded(py: Python<'_>, items: Vec<HashInput<'_>>, seed: u64) -> PyResult<Vec<u64>> {
+    hash_batch(py, &items, |d| xxh3_64_with_seed(d, seed))
+}
+
+/// Batch seeded 128-bit hash
+#[pyfunction]
+fn batch_hash128_seeded(py: Python<'_>, items: Vec<HashInput<'_>>, seed: u64) -> PyResult<Vec<u128>> {
+    hash_batch(py, &items, |d| xxh3_128_with_seed(d, seed))
+}
+
+/// Fast JSON key extraction (for cache key building)
+#[pyfunction]
+fn extract_json_keys(json_str: &str, keys: Vec<String>) -> PyResult<HashMap<String, String>> {
+    let value: serde_json::Value = serde_json::from_str(json_str)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+
+    let mut result = HashMap::new();
+
+    if let serde_json::Value::Object(map) = value {
+        for key in keys {
+            if let Some(val) = map.get(&key) {
+                let str_val = match val {
+                    serde_json::Value::String(s) => s.clone(),
+                    _ => val.to_string(),
+                };
+                result.insert(key, str_val);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Parse NDJSON in parallel and return the requested fields as columns:
+/// `{key: [value per record]}`, with None where a record lacks the key.
+/// Blank lines are skipped; a malformed line raises ValueError with its line number.
+#[pyfunction]
+fn parse_ndjson(py: Python<'_>, data: &[u8], keys: Vec<String>) -> PyResult<PyObject> {
+    let rows: Vec<Vec<Option<serde_json::Value>>> = py
+        .allow_threads(|| {
+            let lines: Vec<(usize, &[u8])> = data
+                .split(|b| *b == b'\n')
+                .enumerate()
+                .filter(|(_, line)| !line.trim_ascii().is_empty())
+                .collect();
+            lines
+                .into_par_iter()
+                .map(|(index, line)| {
+                    let mut record: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(line)
+                        .map_err(|e| format!("Invalid NDJSON on line {}: {}", index + 1, e))?;
+                    Ok(keys.iter().map(|key| record.remove(key)).collect())
+                })
+                .collect::<Result<_, String>>()
+        })
+        .map_err(PyErr::new::<pyo3::exceptions::PyValueError, _>)?;
+
+    let columns = PyDict::new_bound(py);
+    for (column, key) in keys.iter().enumerate() {
+        let values = PyList::empty_bound(py);
+        for row in &rows {
+            match &row[column] {
+                Some(value) => values.append(json_to_py(py, value)?)?,
+                None => values.append(py.None())?,
+            }
+        }
+        columns.set_item(key, values)?;
+    }
+    Ok(columns.into())
+}
+
+/// Convert a JSON value to the equivalent Python object
+fn json_to_py(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObject> {
+    Ok(match value {
+        serde_json::Value::Null => py.None(),
+        serde_json::Value::Bool(b) => b.into_py(py),
+        serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => i.into_py(py),
+            (None, Some(u)) => u.into_py(py),
+            _ => n.as_f64().unwrap_or(f64::NAN).into_py(py),
+        },
+        serde_json::Value::String(s) => s.into_py(py),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty_bound(py);
+            for item in items {
+                list.append(json_to_py(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new_bound(py);
+            for (key, item) in map {
+                dict.set_item(key, json_to_py(py, item)?)?;
+            }
+            dict.into()
+        }
+    })
+}
+
+/// Dot product and squared norm of `b`, accumulated in lanes of 8 so the
+/// compiler can vectorize the loop
+#[inline]
+fn dot_and_norm(a: &[f32], b: &[f32]) -> (f32, f32) {
+    const LANES: usize = 8;
+    let mut dot = [0.0f32; LANES];
+    let mut norm = [0.0f32; LANES];
+    let chunks_a = a.chunks_exact(LANES);
+    let chunks_b = b.chunks_exact(LANES);
+    let (rest_a, rest_b) = (chunks_a.remainder(), chunks_b.remainder());
+    for (x, y) in chunks_a.zip(chunks_b) {
+        for i in 0..LANES {
+            dot[i] += x[i] * y[i];
+            norm[i] += y[i] * y[i];
+        }
+    }
+    let mut dot_sum: f32 = dot.iter().sum();
+    let mut norm_sum: f32 = norm.iter().sum();
+    for (x, y) in rest_a.iter().zip(rest_b) {
+        dot_sum += x * y;
+        norm_sum += y * y;
+    }
+    (dot_sum, norm_sum)
+}
+
+/// Cosine similarity given the precomputed norm of `query`; 0.0 for zero vectors
+/// (same convention as the CLA vector search)
+#[inline]
+fn cosine_with_norm(query: &[f32], query_norm: f32, row: &[f32]) -> f32 {
+    let (dot, row_norm_sq) = dot_and_norm(query, row);
+    let row_norm = row_norm_sq.sqrt();
+    if query_norm == 0.0 || row_norm == 0.0 {
+        0.0
+    } else {
+        dot / (query_norm * row_norm)
+    }
+}
+
+/// Indices and scores of the `k` best rows, highest score first (ties by index)
+fn top_k(query: &[f32], rows: &[&[f32]], k: usize) -> Vec<(usize, f32)> {
+    let query_norm = dot_and_norm(query, query).1.sqrt();
+    let mut scored: Vec<(usize, f32)> = rows
+        .par_iter()
+        .enumerate()
+        .map(|(index, row)| (index, cosine_with_norm(query, query_norm, row)))
+        .collect();
+
+    let by_score = |a: &(usize, f32), b: &(usize, f32)| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    };
+    let k = k.min(scored.len());
+    if k == 0 {
+        return Vec::new();
+    }
+    if k < scored.len() {
+        scored.select_nth_unstable_by(k - 1, by_score);
+        scored.truncate(k);
+    }
+    scored.sort_unstable_by(by_score);
+    scored
+}
+
+/// Cosine similarity of two vectors (lists or float32 buffers)
+#[pyfunction]
+fn cosine_similarity(py: Python<'_>, a: &Bound<'_, PyAny>, b: &Bound<'_, PyAny>) -> PyResult<f32> {
+    let (a, b) = (read_vector(a)?, read_vector(b)?);
+    if a.len() != b.len() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Dimension mismatch: {} vs {}",
+            a.len(),
+            b.len()
+        )));
+    }
+    Ok(py.allow_threads(|| {
+        let query_norm = dot_and_norm(&a, &a).1.sqrt();
+        cosine_with_norm(&a, query_norm, &b)
+    }))
+}
+
+/// Cosine similarity of `query` to every row of `matrix`, in row order.
+/// `matrix` is read as in `top_k_similar`.
+#[pyfunction]
+fn batch_cosine_similarity(py: Python<'_>, query: &Bound<'_, PyAny>, matrix: &Bound<'_, PyAny>) -> PyResult<Vec<f32>> {
+    let query = read_vector(query)?;
+    with_rows(matrix, query.len(), |rows| {
+        py.allow_threads(|| {
+            let query_norm = dot_and_norm(&query, &query).1.sqrt();
+            rows.par_iter().map(|row| cosine_with_norm(&query, query_norm, row)).collect()
+        })
+    })
+}
+
+/// Top-k rows of `matrix` by cosine similarity to `query`, as `[(index, score)]`
+/// sorted by descending score.
+///
+/// `query` is a list or float32 buffer. `matrix` is a list of vectors or any
+/// C-contiguous float32 buffer (e.g. a numpy array of shape `(n, dim)` or a flat
+/// array of `n * dim` values), which is read without copying.
+#[pyfunction]
+fn top_k_similar(
+    py: Python<'_>,
+    query: &Bound<'_, PyAny>,
+    matrix: &Bound<'_, PyAny>,
+    k: usize,
+) -> PyResult<Vec<(usize, f32)>> {
+    let query = read_vector(query)?;
+    with_rows(matrix, query.len(), |rows| py.allow_threads(|| top_k(&query, rows, k)))
+}
+
+/// Same as `top_k_similar`; kept for existing callers
+#[pyfunction]
+fn cosine_topk(
+    py: Python<'_>,
+    query: &Bound<'_, PyAny>,
+    matrix: &Bound<'_, PyAny>,
+    k: usize,
+) -> PyResult<Vec<(usize, f32)>> {
+    top_k_similar(py, query, matrix, k)
+}
+
+/// A vector from a float32 buffer (e.g. a numpy array) or any sequence of numbers
+fn read_vector(vector: &Bound<'_, PyAny>) -> PyResult<Vec<f32>> {
+    match pyo3::buffer::PyBuffer::<f32>::get_bound(vector) {
+        Ok(buffer) => buffer.to_vec(vector.py()),
+        Err(_) => vector.extract(),
+    }
+}
+
+/// Call `f` with the rows of `matrix`, each `dim` values long. A C-contiguous
+/// float32 buffer is read in place; anything else is extracted as a list of vectors.
+fn with_rows<R>(matrix: &Bound<'_, PyAny>, dim: usize, f: impl FnOnce(&[&[f32]]) -> R) -> PyResult<R> {
+    if dim == 0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>("Query vector is empty"));
+    }
+    let mismatch = |len: usize| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Dimension mismatch: query has {} values, matrix rows have {}",
+            dim, len
+        ))
+    };
+
+    if let Ok(buffer) = pyo3::buffer::PyBuffer::<f32>::get_bound(matrix) {
+        if !buffer.is_c_contiguous() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Matrix buffer must be C-contiguous",
+            ));
+        }
+        match buffer.shape() {
+            [_, cols] if *cols != dim => return Err(mismatch(*cols)),
+            [len] if len % dim != 0 => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Matrix buffer length {} is not a multiple of the query dimension {}",
+                    len, dim
+                )))
+            }
+            [_, _] | [_] => {}
+            shape => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "Matrix buffer must have 1 or 2 dimensions, got {}",
+                    shape.len()
+                )))
+            }
+        }
+        // SAFETY: the buffer is C-contiguous float32 with item_count() elements and
+        // stays alive (and exported) for the duration of this call
+        let data = unsafe { std::slice::from_raw_parts(buffer.buf_ptr() as *const f32, buffer.item_count()) };
+        let rows: Vec<&[f32]> = data.chunks_exact(dim).collect();
+        return Ok(f(&rows));
+    }
+
+    let vectors: Vec<Vec<f32>> = matrix.extract()?;
+    if let Some(row) = vectors.iter().find(|row| row.len() != dim) {
+        return Err(mismatch(row.len()));
+    }
+    let rows: Vec<&[f32]> = vectors.iter().map(Vec::as_slice).collect();
+    Ok(f(&rows))
+}
+
+/// Quantization scheme of an embedding blob
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum QuantizationScheme {
+    Float32 = 0,
+    Int8 = 1,
+}
+
+impl QuantizationScheme {
+    fn parse(name: &str) -> PyResult<Self> {
+        match name {
+            "float32" => Ok(Self::Float32),
+            "int8" => Ok(Self::Int8),
+            other => Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "Unknown quantization scheme '{}' (expected float32 or int8)",
+                other
+            ))),
+        }
+    }
+
+    /// Bytes per stored vector
+    fn record_len(self, dim: usize) -> usize {
+        match self {
+            Self::Float32 => 4 * dim,
+            Self::Int8 => 4 + dim,
+        }
+    }
+}
+
+/// Scheme byte + dimension (u32 LE) + vector count (u32 LE)
+const QUANTIZED_HEADER_LEN: usize = 9;
+
+/// Encode embeddings in the blob format shared with the CLA knowledge store.
+///
+/// Layout: scheme byte (0 = float32, 1 = int8), dimension and count as u32 LE,
+/// then per vector either raw f32 LE values or, for int8, an f32 LE scale
+/// (max |x| / 127) followed by `round(x / scale)` as i8.
+#[pyfunction]
+#[pyo3(signature = (vectors, scheme="int8"))]
+fn quantize_embeddings(py: Python<'_>, vectors: Vec<Vec<f32>>, scheme: &str) -> PyResult<PyObject> {
+    let scheme = QuantizationScheme::parse(scheme)?;
+    let dim = vectors.first().map_or(0, Vec::len);
+    if vectors.iter().any(|v| v.len() != dim) {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "All vectors must have the same dimension",
+        ));
+    }
+
+    let mut blob = Vec::with_capacity(QUANTIZED_HEADER_LEN + vectors.len() * scheme.record_len(dim));
+    blob.push(scheme as u8);
+    blob.extend_from_slice(&(dim as u32).to_le_bytes());
+    blob.extend_from_slice(&(vectors.len() as u32).to_le_bytes());
+
+    for vector in &vectors {
+        match scheme {
+            QuantizationScheme::Float32 => {
+                for x in vector {
+                    blob.extend_from_slice(&x.to_le_bytes());
+                }
+            }
+            QuantizationScheme::Int8 => {
+                let max_abs = vector.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+                let scale = max_abs / 127.0;
+                blob.extend_from_slice(&scale.to_le_bytes());
+                for x in vector {
+                    let q = if scale > 0.0 { (x / scale).round().clamp(-127.0, 127.0) } else { 0.0 };
+                    blob.push(q as i8 as u8);
+                }
+            }
+        }
+    }
+    Ok(pyo3::types::PyBytes::new_bound(py, &blob).into())
+}
+
+/// Decode a blob produced by `quantize_embeddings` (or the CLA agent)
+#[pyfunction]
+#[pyo3(signature = (blob, scheme="int8"))]
+fn dequantize_embeddings(blob: &[u8], scheme: &str) -> PyResult<Vec<Vec<f32>>> {
+    let scheme = QuantizationScheme::parse(scheme)?;
+    let invalid = |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+    if blob.len() < QUANTIZED_HEADER_LEN {
+        return Err(invalid("Quantized blob is truncated".to_string()));
+    }
+    if blob[0] != scheme as u8 {
+        return Err(invalid(format!("Blob was written with scheme {}, not {}", blob[0], scheme as u8)));
+    }
+    let dim = u32::from_le_bytes([blob[1], blob[2], blob[3], blob[4]]) as usize;
+    let count = u32::from_le_bytes([blob[5], blob[6], blob[7], blob[8]]) as usize;
+    let record = scheme.record_len(dim);
+    let body = &blob[QUANTIZED_HEADER_LEN..];
+    if body.len() != count * record {
+        return Err(invalid(format!(
+            "Quantized blob has {} bytes, expected {} for {} vectors of {}",
+            body.len(),
+            count * record,
+            count,
+            dim
+        )));
+    }
+    if record == 0 {
+        return Ok(vec![Vec::new(); count]);
+    }
+
+    Ok(body
+        .chunks_exact(record)
+        .map(|record| match scheme {
+            QuantizationScheme::Float32 => record
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+            QuantizationScheme::Int8 => {
+                let scale = f32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+                record[4..].iter().map(|q| *q as i8 as f32 * scale).collect()
+            }
+        })
+        .collect())
+}
+
+/// Compiled JSON Schema for validating many documents
+#[pyclass]
+pub struct SchemaValidator {
+    validator: jsonschema::Validator,
+}
+
+#[pymethods]
+impl SchemaValidator {
+    #[new]
+    fn new(schema_str: &str) -> PyResult<Self> {
+        Ok(SchemaValidator {
+            validator: compile_schema(schema_str)?,
+        })
+    }
+
+    /// Validation errors as "<json pointer>: <message>"; empty when valid
+    fn validate(&self, json_str: &str) -> PyResult<Vec<String>> {
+        Ok(schema_errors(&self.validator, &parse_json(json_str)?))
+    }
+
+    fn is_valid(&self, json_str: &str) -> PyResult<bool> {
+        Ok(self.validator.is_valid(&parse_json(json_str)?))
+    }
+}
+
+/// Validate a JSON document against a JSON Schema; returns the errors (empty = valid).
+/// Use `SchemaValidator` to validate many documents against the same schema.
+#[pyfunction]
+fn validate_json_schema(json_str: &str, schema_str: &str) -> PyResult<Vec<String>> {
+    let validator = compile_schema(schema_str)?;
+    Ok(schema_errors(&validator, &parse_json(json_str)?))
+}
+
+fn parse_json(json_str: &str) -> PyResult<serde_json::Value> {
+    serde_json::from_str(json_str)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid JSON: {}", e)))
+}
+
+fn compile_schema(schema_str: &str) -> PyResult<jsonschema::Validator> {
+    let schema = parse_json(schema_str)?;
+    jsonschema::validator_for(&schema)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("Invalid schema: {}", e)))
+}
+
+fn schema_errors(validator: &jsonschema::Validator, instance: &serde_json::Value) -> Vec<String> {
+    validator
+        .iter_errors(instance)
+        .map(|e| format!("{}: {}", e.instance_path, e))
+        .collect()
+}
+
+/// Characters of a word counted as one token. Subword tokenizers average about
+/// four characters per English token, so this errs on the high side.
+const TOKEN_CHARS: usize = 4;
+
+/// Approximate token count without a model vocabulary: each started run of
+/// `TOKEN_CHARS` letters or digits is one token, as is every other
+/// non-whitespace character.
+#[pyfunction]
+fn count_tokens(text: &str) -> usize {
+    let mut tokens = 0;
+    let mut run = 0;
+    for c in text.chars() {
+        tokens += token_cost(c, &mut run);
+    }
+    tokens
+}
+
+/// Split text into chunks of at most `max_tokens` tokens (as `count_tokens`),
+/// each starting `overlap` tokens before the previous one ended. Chunks end at
+/// a sentence or line end when one falls in their second half, otherwise at a
+/// word boundary; only words longer than `max_tokens` are cut.
+#[pyfunction]
+#[pyo3(signature = (text, max_tokens, overlap=0))]
+fn chunk_text(py: Python<'_>, text: &str, max_tokens: usize, overlap: usize) -> PyResult<Vec<String>> {
+    check_chunk_args(max_tokens, overlap)?;
+    Ok(py.allow_threads(|| chunks(text, max_tokens, overlap)))
+}
+
+/// `chunk_text` for many documents, in parallel with the GIL released
+#[pyfunction]
+#[pyo3(signature = (texts, max_tokens, overlap=0))]
+fn chunk_texts(py: Python<'_>, texts: Vec<String>, max_tokens: usize, overlap: usize) -> PyResult<Vec<Vec<String>>> {
+    check_chunk_args(max_tokens, overlap)?;
+    Ok(py.allow_threads(|| texts.par_iter().map(|text| chunks(text, max_tokens, overlap)).collect()))
+}
+
+fn check_chunk_args(max_tokens: usize, overlap: usize) -> PyResult<()> {
+    if max_tokens == 0 || overlap >= max_tokens {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "Need max_tokens > overlap >= 0, got max_tokens={} overlap={}",
+            max_tokens, overlap
+        )));
+    }
+    Ok(())
+}
+
+/// Tokens added by `c`; `run` is the length of the current letter/digit run
+#[inline]
+fn token_cost(c: char, run: &mut usize) -> usize {
+    if c.is_whitespace() {
+        *run = 0;
+        0
+    } else if c.is_alphanumeric() {
+        *run += 1;
+        usize::from((*run - 1).is_multiple_of(TOKEN_CHARS))
+    } else {
+        *run = 0;
+        1
+    }
+}
+
+/// A word (or a piece of one longer than a chunk) as a byte range of the text
+struct TextUnit {
+    start: usize,
+    end: usize,
+    tokens: usize,
+    /// Ends a sentence or a line
+    boundary: bool,
+}
+
+fn text_units(text: &str, max_tokens: usize) -> Vec<TextUnit> {
+    let mut units: Vec<TextUnit> = Vec::new();
+    let mut current: Option<TextUnit> = None;
+    let mut run = 0;
+    let close = |unit: TextUnit, units: &mut Vec<TextUnit>| {
+        let last = text[unit.start..unit.end]
+            .chars()
+            .rev()
+            .find(|c| !matches!(c, '"' | '\'' | ')' | ']' | '»' | '”' | '’'));
+        units.push(TextUnit {
+            boundary: matches!(last, Some('.' | '!' | '?')),
+            ..unit
+        });
+    };
+
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() {
+            run = 0;
+            if let Some(unit) = current.take() {
+                close(unit, &mut units);
+            }
+            if c == '\n' {
+                if let Some(last) = units.last_mut() {
+                    last.boundary = true;
+                }
+            }
+            continue;
+        }
+
+        let mut cost = token_cost(c, &mut run);
+        if current.as_ref().is_some_and(|unit| unit.tokens + cost > max_tokens) {
+            // Cut a word that does not fit in a chunk on its own
+            close(current.take().unwrap(), &mut units);
+            run = 0;
+            cost = token_cost(c, &mut run);
+        }
+        let unit = current.get_or_insert(TextUnit {
+            start: index,
+            end: index,
+            tokens: 0,
+            boundary: false,
+        });
+        unit.end = index + c.len_utf8();
+        unit.tokens += cost;
+    }
+    if let Some(unit) = current {
+        close(unit, &mut units);
+    }
+    units
+}
+
+fn chunks(text: &str, max_tokens: usize, overlap: usize) -> Vec<String> {
+    let units = text_units(text, max_tokens);
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    // Every chunk must reach past the previous one, not just repeat its overlap
+    let mut min_end = 1;
+    while start < units.len() {
+        let mut end = start;
+        let mut tokens = 0;
+        while end < units.len() && tokens + units[end].tokens <= max_tokens {
+            tokens += units[end].tokens;
+            end += 1;
+        }
+
+        // Back up to the last sentence end in the second half of the chunk
+        if end < units.len() {
+            let mut seen = 0;
+            let mut best = None;
+            for (index, unit) in units[start..end].iter().enumerate() {
+                seen += unit.tokens;
+                if unit.boundary && seen * 2 >= tokens && start + index + 1 >= min_end {
+                    best = Some(start + index + 1);
+                }
+            }
+            end = best.unwrap_or(end);
+        }
+        chunks.push(text[units[start].start..units[end - 1].end].to_string());
+        if end == units.len() {
+            break;
+        }
+
+        let mut next = end;
+        let mut carried = 0;
+        while next > start + 1 && carried + units[next - 1].tokens <= overlap {
+            carried += units[next - 1].tokens;
+            next -= 1;
+        }
+        // Carry less when the next word would not fit after the overlap
+        while carried + units[end].tokens > max_tokens {
+            carried -= units[next].tokens;
+            next += 1;
+        }
+        start = next;
+        min_end = end + 1;
+    }
+    chunks
 }
 
 /// Python module definition
 #[pymodule]
 fn cirkelline_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<NativeCache>()?;
+    m.add_class::<CacheManager>()?;
+    m.add_class::<CacheBatch>()?;
+    m.add_class::<CachePartition>()?;
+    m.add_class::<CachedBuffer>()?;
+    m.add_class::<ShardedCache>()?;
+    m.add_class::<SchemaValidator>()?;
     m.add_function(wrap_pyfunction!(fast_hash, m)?)?;
     m.add_function(wrap_pyfunction!(build_cache_key, m)?)?;
     m.add_function(wrap_pyfunction!(batch_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_hash128, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_hash_seeded, m)?)?;
+    m.add_function(wrap_pyfunction!(fast_hash128_seeded, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_hash128, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_hash_seeded, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_hash128_seeded, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_text, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_hash, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_json, m)?)?;
+    m.add_function(wrap_pyfunction!(canonical_json_hash, m)?)?;
     m.add_function(wrap_pyfunction!(extract_json_keys, m)?)?;
+    m.add_function(wrap_pyfunction!(validate_json_schema, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_ndjson, m)?)?;
+    m.add_function(wrap_pyfunction!(cosine_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(cosine_topk, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_cosine_similarity, m)?)?;
+    m.add_function(wrap_pyfunction!(top_k_similar, m)?)?;
+    m.add_function(wrap_pyfunction!(count_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_text, m)?)?;
+    m.add_function(wrap_pyfunction!(chunk_texts, m)?)?;
+    m.add_function(wrap_pyfunction!(quantize_embeddings, m)?)?;
+    m.add_function(wrap_pyfunction!(dequantize_embeddings, m)?)?;
 
     // Module metadata
     m.add("__version__", "0.1.0")?;
@@ -0,0 +1,117 @@
+#!/usr/bin/env python3
+"""
+CLA Intent Model Training
+
+Fine-tunes a distilled multilingual BERT on the voice command intents in
+scripts/intent-data and exports it to ONNX for the inference engine.
+
+Output (src-tauri/models/intent-da/):
+- model.onnx  (inputs: input_ids, attention_mask; output: logits)
+- vocab.txt   (WordPiece vocabulary)
+- labels.txt  (intent per logit, same order as intent-data/labels.txt)
+
+Text is lowercased like the command parser does before classification.
+
+Requires: pip install torch transformers onnx
+Usage: ./scripts/train-intent-model.py [--base-model NAME] [--epochs N]
+"""
+
+import argparse
+import json
+import random
+import shutil
+from pathlib import Path
+
+import torch
+from transformers import AutoModelForSequenceClassification, AutoTokenizer
+
+SCRIPT_DIR = Path(__file__).resolve().parent
+DATA_DIR = SCRIPT_DIR / "intent-data"
+MODELS_DIR = SCRIPT_DIR.parent / "src-tauri" / "models" / "intent-da"
+
+# Must match MAX_TOKENS in src-tauri/src/inference/intent.rs
+MAX_TOKENS = 64
+
+
+def load_examples():
+    labels = (DATA_DIR / "labels.txt").read_text(encoding="utf-8").split()
+    examples = []
+    with open(DATA_DIR / "intents.jsonl", encoding="utf-8") as f:
+        for line in f:
+            if line.strip():
+                row = json.loads(line)
+                examples.append((row["text"].lower().strip(), labels.index(row["intent"])))
+    return labels, examples
+
+
+def train(model, tokenizer, examples, epochs, batch_size, lr):
+    optimizer = torch.optim.AdamW(model.parameters(), lr=lr)
+    model.train()
+    for epoch in range(epochs):
+        random.shuffle(examples)
+        total = 0.0
+        for i in range(0, len(examples), batch_size):
+            batch = examples[i:i + batch_size]
+            inputs = tokenizer(
+                [text for text, _ in batch],
+                padding=True,
+                truncation=True,
+                max_length=MAX_TOKENS,
+                return_tensors="pt",
+            )
+            labels = torch.tensor([label for _, label in batch])
+            loss = model(**inputs, labels=labels).loss
+            loss.backward()
+            optimizer.step()
+            optimizer.zero_grad()
+            total += loss.item()
+        print(f"epoch {epoch + 1}/{epochs}: loss {total:.3f}")
+
+
+def export(model, tokenizer, labels):
+    MODELS_DIR.mkdir(parents=True, exist_ok=True)
+    model.eval()
+    sample = tokenizer(["start arbejde"], return_tensors="pt")
+    torch.onnx.export(
+        model,
+        (sample["input_ids"], sample["attention_mask"]),
+        MODELS_DIR / "model.onnx",
+        input_names=["input_ids", "attention_mask"],
+        output_names=["logits"],
+        dynamic_axes={
+            "input_ids": {0: "batch", 1: "sequence"},
+            "attention_mask": {0: "batch", 1: "sequence"},
+            "logits": {0: "batch"},
+        },
+        opset_version=17,
+    )
+    tokenizer.save_pretrained(MODELS_DIR / "tokenizer")
+    shutil.move(MODELS_DIR / "tokenizer" / "vocab.txt", MODELS_DIR / "vocab.txt")
+    shutil.rmtree(MODELS_DIR / "tokenizer")
+    (MODELS_DIR / "labels.txt").write_text("\n".join(labels) + "\n", encoding="utf-8")
+    print(f"Exported intent model to {MODELS_DIR}")
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Train the CLA voice command intent model")
+    parser.add_argument("--base-model", default="distilbert-base-multilingual-cased")
+    parser.add_argument("--epochs", type=int, default=12)
+    parser.add_argument("--batch-size", type=int, default=16)
+    parser.add_argument("--lr", type=float, default=5e-5)
+    parser.add_argument("--seed", type=int, default=42)
+    args = parser.parse_args()
+
+    random.seed(args.seed)
+    torch.manual_seed(args.seed)
+
+    labels, examples = load_examples()
+    print(f"{len(examples)} examples, {len(labels)} intents")
+
+    tokenizer = AutoTokenizer.from_pretrained(args.base_model)
+    model = AutoModelForSequenceClassification.from_pretrained(args.base_model, num_labels=len(labels))
+    train(model, tokenizer, examples, args.epochs, args.batch_size, args.lr)
+    export(model, tokenizer, labels)
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,380 @@
+// Audio Input - Microphone enumeration, capture, selection and level metering
+// Devices are listed and captured through cpal on every platform; utterances
+// end with voice activity detection
+
+use super::noise_suppression::to_i16;
+use crate::inference::{resample_audio, Vad, VadEvent};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, Sample, SampleFormat, SizedSample};
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc as std_mpsc;
+use std::thread::JoinHandle;
+use tokio::sync::mpsc;
+
+/// Name of the system default input device
+pub const DEFAULT_DEVICE: &str = "default";
+
+/// Sample rate used for all voice capture
+pub const SAMPLE_RATE: u32 = 16000;
+
+/// Samples per meter update (100ms)
+const METER_CHUNK_SAMPLES: usize = SAMPLE_RATE as usize / 10;
+
+/// An audio input device
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InputDevice {
+    /// cpal device name, or `DEFAULT_DEVICE` for the system default
+    pub id: String,
+    /// Human readable description
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Input level of one chunk of audio
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct InputLevel {
+    /// RMS level (0.0 - 1.0)
+    pub rms: f32,
+    /// Peak level (0.0 - 1.0)
+    pub peak: f32,
+    /// RMS level in dBFS (-96.0 for silence)
+    pub dbfs: f32,
+}
+
+impl InputLevel {
+    /// Measure the level of 16-bit samples
+    pub fn from_samples(samples: &[i16]) -> Self {
+        if samples.is_empty() {
+            return Self { rms: 0.0, peak: 0.0, dbfs: -96.0 };
+        }
+
+        let sum_squares: f64 = samples.iter().map(|&s| (s as f64 / 32768.0).powi(2)).sum();
+        let rms = (sum_squares / samples.len() as f64).sqrt() as f32;
+        let peak = samples
+            .iter()
+            .map(|&s| (s as f32 / 32768.0).abs())
+            .fold(0.0, f32::max);
+        let dbfs = if rms > 0.0 { (20.0 * rms.log10()).max(-96.0) } else { -96.0 };
+
+        Self { rms, peak, dbfs }
+    }
+}
+
+/// List available capture devices, the system default first
+pub async fn list_input_devices() -> Vec<InputDevice> {
+    match tokio::task::spawn_blocking(enumerate_devices).await {
+        Ok(Ok(devices)) => devices,
+        Ok(Err(e)) => {
+            log::warn!("Could not enumerate input devices ({}), using default", e);
+            vec![default_device()]
+        }
+        Err(e) => {
+            log::warn!("Input device enumeration failed: {}", e);
+            vec![default_device()]
+        }
+    }
+}
+
+fn enumerate_devices() -> Result<Vec<InputDevice>, cpal::DevicesError> {
+    let host = cpal::default_host();
+    let names = host.input_devices()?.filter_map(|device| device.name().ok());
+    Ok(with_default(names))
+}
+
+/// Input devices for the names cpal reports, after the system default
+fn with_default(names: impl IntoIterator<Item = String>) -> Vec<InputDevice> {
+    let mut devices = vec![default_device()];
+    for name in names {
+        if name != DEFAULT_DEVICE && !devices.iter().any(|d| d.id == name) {
+            devices.push(InputDevice {
+                id: name.clone(),
+                name,
+                is_default: false,
+            });
+        }
+    }
+    devices
+}
+
+fn default_device() -> InputDevice {
+    InputDevice {
+        id: DEFAULT_DEVICE.to_string(),
+        name: "Standardmikrofon".to_string(),
+        is_default: true,
+    }
+}
+
+/// Resolve the device to record from, falling back to the default if the
+/// selected one is gone. Returns the device id and whether it fell back.
+pub fn resolve_device(selected: Option<&str>, available: &[InputDevice]) -> (String, bool) {
+    match selected {
+        None => (DEFAULT_DEVICE.to_string(), false),
+        Some(id) if available.iter().any(|d| d.id == id) => (id.to_string(), false),
+        Some(id) => {
+            log::warn!("Input device '{}' disappeared, falling back to default", id);
+            (DEFAULT_DEVICE.to_string(), true)
+        }
+    }
+}
+
+/// How an utterance recording ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingEnd {
+    /// The speaker paused after speaking
+    SpeechEnded,
+    /// Nobody spoke before the listen timeout
+    NoSpeech,
+    /// The maximum duration was reached while speaking
+    MaxDuration,
+}
+
+/// Record mono 16kHz audio until the speaker stops talking, reporting the input
+/// level every 100ms. Gives up after `listen_timeout_ms` without speech and stops
+/// at `max_seconds` regardless.
+pub async fn record_utterance<F>(
+    device: &str,
+    max_seconds: u32,
+    listen_timeout_ms: u64,
+    vad: &mut Vad,
+    mut on_level: F,
+) -> Result<(Vec<i16>, RecordingEnd), String>
+where
+    F: FnMut(InputLevel),
+{
+    let device = (device != DEFAULT_DEVICE).then(|| device.to_string());
+    let (capture, mut audio_rx) = MicrophoneCapture::start(device)?;
+
+    vad.reset();
+    let max_samples = (SAMPLE_RATE * max_seconds) as usize;
+    let mut samples = Vec::with_capacity(max_samples);
+    let mut pending: Vec<i16> = Vec::new();
+    let mut heard_speech = false;
+    let mut end = None;
+
+    'record: while let Some(captured) = audio_rx.recv().await {
+        pending.extend(captured.iter().map(|&s| to_i16(s)));
+        while pending.len() >= METER_CHUNK_SAMPLES {
+            let chunk: Vec<i16> = pending.drain(..METER_CHUNK_SAMPLES).collect();
+            on_level(InputLevel::from_samples(&chunk));
+            for event in vad.push_i16(&chunk) {
+                match event {
+                    VadEvent::SpeechStart { .. } => heard_speech = true,
+                    VadEvent::SpeechEnd { .. } => end = Some(RecordingEnd::SpeechEnded),
+                }
+            }
+            samples.extend(chunk);
+
+            let elapsed_ms = samples.len() as u64 * 1000 / SAMPLE_RATE as u64;
+            if end.is_none() && !heard_speech && elapsed_ms >= listen_timeout_ms {
+                end = Some(RecordingEnd::NoSpeech);
+            }
+            if end.is_none() && samples.len() >= max_samples {
+                end = Some(if heard_speech { RecordingEnd::MaxDuration } else { RecordingEnd::NoSpeech });
+            }
+            if end.is_some() {
+                break 'record;
+            }
+        }
+    }
+    drop(capture);
+
+    // The capture only ends on its own when no microphone is left
+    let end = end.ok_or("Mikrofonen blev afbrudt")?;
+    Ok((samples, end))
+}
+
+/// Why the capture thread was woken
+enum CaptureControl {
+    Stop,
+    DeviceLost,
+}
+
+/// Microphone capture on its own thread, since cpal streams cannot move between
+/// threads. When the selected device disappears, capture continues from the
+/// system default; when no device is left, the audio channel closes.
+pub(crate) struct MicrophoneCapture {
+    control: Option<std_mpsc::Sender<CaptureControl>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MicrophoneCapture {
+    /// Start capturing from the named device (None or missing = default) as
+    /// 16kHz mono chunks
+    pub(crate) fn start(device_name: Option<String>) -> Result<(Self, mpsc::UnboundedReceiver<Vec<f32>>), String> {
+        let (audio_tx, audio_rx) = mpsc::unbounded_channel();
+        let (ready_tx, ready_rx) = std_mpsc::channel();
+        let (control_tx, control_rx) = std_mpsc::channel();
+
+        let lost_tx = control_tx.clone();
+        let thread = std::thread::spawn(move || {
+            let mut device_name = device_name;
+            let mut ready_tx = Some(ready_tx);
+            loop {
+                let control = {
+                    let _stream = match open_stream(device_name.as_deref(), audio_tx.clone(), lost_tx.clone()) {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            match ready_tx.take() {
+                                Some(ready_tx) => {
+                                    let _ = ready_tx.send(Err(e));
+                                }
+                                None => log::warn!("Could not reopen the microphone: {}", e),
+                            }
+                            return;
+                        }
+                    };
+                    if let Some(ready_tx) = ready_tx.take() {
+                        let _ = ready_tx.send(Ok(()));
+                    }
+                    // Keep the stream alive until stopped or its device is gone
+                    control_rx.recv()
+                };
+                match control {
+                    Ok(CaptureControl::DeviceLost) if device_name.is_some() => {
+                        log::warn!("Input device {:?} disappeared, continuing with the default", device_name);
+                        device_name = None;
+                    }
+                    Ok(CaptureControl::DeviceLost) => {
+                        log::warn!("Default input device disappeared");
+                        return;
+                    }
+                    Ok(CaptureControl::Stop) | Err(_) => return,
+                }
+                // Losses reported by the old stream do not apply to the new one
+                while let Ok(control) = control_rx.try_recv() {
+                    if matches!(control, CaptureControl::Stop) {
+                        return;
+                    }
+                }
+            }
+        });
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok((
+                Self {
+                    control: Some(control_tx),
+                    thread: Some(thread),
+                },
+                audio_rx,
+            )),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err("Mikrofonoptagelsen stoppede uventet".to_string()),
+        }
+    }
+}
+
+impl Drop for MicrophoneCapture {
+    fn drop(&mut self) {
+        if let Some(control) = self.control.take() {
+            let _ = control.send(CaptureControl::Stop);
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+fn open_stream(
+    device_name: Option<&str>,
+    audio_tx: mpsc::UnboundedSender<Vec<f32>>,
+    lost_tx: std_mpsc::Sender<CaptureControl>,
+) -> Result<cpal::Stream, String> {
+    let host = cpal::default_host();
+    let named = device_name.and_then(|name| {
+        host.input_devices()
+            .ok()?
+            .find(|device| device.name().is_ok_and(|n| n == name))
+    });
+    if device_name.is_some() && named.is_none() {
+        log::warn!("Input device {:?} not found, using default", device_name);
+    }
+    let device = named
+        .or_else(|| host.default_input_device())
+        .ok_or("Ingen mikrofon fundet")?;
+
+    let supported = device
+        .default_input_config()
+        .map_err(|e| format!("Kunne ikke læse mikrofonens indstillinger: {}", e))?;
+    let config = supported.config();
+    log::info!(
+        "Capturing from '{}' at {} Hz, {} channels",
+        device.name().unwrap_or_default(),
+        config.sample_rate.0,
+        config.channels
+    );
+
+    let stream = match supported.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(&device, &config, audio_tx, lost_tx),
+        SampleFormat::I16 => build_stream::<i16>(&device, &config, audio_tx, lost_tx),
+        SampleFormat::U16 => build_stream::<u16>(&device, &config, audio_tx, lost_tx),
+        other => return Err(format!("Mikrofonens lydformat understøttes ikke ({:?})", other)),
+    }?;
+    stream.play().map_err(|e| format!("Kunne ikke starte mikrofonen: {}", e))?;
+    Ok(stream)
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    audio_tx: mpsc::UnboundedSender<Vec<f32>>,
+    lost_tx: std_mpsc::Sender<CaptureControl>,
+) -> Result<cpal::Stream, String>
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+    let rate = config.sample_rate.0;
+    device
+        .build_input_stream(
+            config,
+            move |data: &[T], _: &cpal::InputCallbackInfo| {
+                let mono: Vec<f32> = data
+                    .chunks(channels)
+                    .map(|frame| frame.iter().map(|&s| f32::from_sample(s)).sum::<f32>() / channels as f32)
+                    .collect();
+                let chunk = if rate == SAMPLE_RATE { mono } else { resample_audio(&mono, rate, SAMPLE_RATE) };
+                let _ = audio_tx.send(chunk);
+            },
+            move |e| match e {
+                cpal::StreamError::DeviceNotAvailable => {
+                    let _ = lost_tx.send(CaptureControl::DeviceLost);
+                }
+                e => log::warn!("Microphone stream error: {}", e),
+            },
+            None,
+        )
+        .map_err(|e| format!("Kunne ikke åbne mikrofonen: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_devices_start_with_the_default() {
+        let names = ["default", "USB Microphone", "Built-in Microphone", "USB Microphone"].map(String::from);
+        let devices = with_default(names);
+
+        assert_eq!(devices.len(), 3);
+        assert!(devices[0].is_default);
+        assert_eq!(devices[1].id, "USB Microphone");
+        assert!(!devices[1].is_default);
+    }
+
+    #[test]
+    fn test_resolve_device_falls_back() {
+        let devices = with_default(Vec::new());
+        assert_eq!(resolve_device(Some("default"), &devices), ("default".to_string(), false));
+        assert_eq!(resolve_device(Some("hw:2,0"), &devices), ("default".to_string(), true));
+    }
+
+    #[test]
+    fn test_input_level() {
+        assert_eq!(InputLevel::from_samples(&[0; 160]).dbfs, -96.0);
+
+        let level = InputLevel::from_samples(&[16384, -16384]);
+        assert!((level.peak - 0.5).abs() < 1e-6);
+        assert!((level.dbfs + 6.02).abs() < 0.1);
+    }
+}
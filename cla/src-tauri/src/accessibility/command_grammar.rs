@@ -0,0 +1,910 @@
+// Command Grammar - Rule grammar for spoken commands with slot extraction
+// Rules are patterns like "(opret|lav) [en] opgave {description}": (a|b) picks
+// one alternative, [a|b] is optional and {slot} captures words. Words match
+// fuzzily so inflections and small transcription errors still parse.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use std::ops::Range;
+
+use crate::accessibility::command_parser::VoiceCommand;
+use crate::accessibility::voice_settings::{SettingKey, SettingToggle};
+
+/// Matches below this confidence are treated as not understood
+pub const MIN_RULE_CONFIDENCE: f32 = 0.55;
+
+/// Words that may come before a voice macro's phrase ("kør morgenrutine")
+const MACRO_PREFIX: &[&str] = &["kør", "udfør", "start", "run", "do"];
+
+/// Time of day for a task given a day but no time
+const DEFAULT_DUE_HOUR: u32 = 9;
+
+/// Largest amount accepted in "om N timer/minutter/dage"; larger numbers are misheard
+const MAX_RELATIVE_AMOUNT: u32 = 10_000;
+
+/// Words that do not count against a match when left over ("kan du ...", "please")
+const FILLER: &[&str] = &[
+    "hej", "cirkelline", "kan", "du", "vil", "gerne", "venligst", "lige", "tak", "mig", "jeg", "så", "nu", "og",
+    "hey", "please", "can", "could", "would", "you", "just", "now", "and", "the",
+];
+
+const DANISH_NUMBERS: &[(&str, u32)] = &[
+    ("nul", 0), ("en", 1), ("et", 1), ("én", 1), ("to", 2), ("tre", 3), ("fire", 4), ("fem", 5),
+    ("seks", 6), ("syv", 7), ("otte", 8), ("ni", 9), ("ti", 10), ("elleve", 11), ("tolv", 12),
+    ("tretten", 13), ("fjorten", 14), ("femten", 15), ("seksten", 16), ("sytten", 17), ("atten", 18),
+    ("nitten", 19), ("tyve", 20), ("tredive", 30), ("fyrre", 40), ("halvtreds", 50), ("tres", 60),
+    ("halvfjerds", 70), ("firs", 80), ("halvfems", 90), ("hundrede", 100),
+];
+
+const ENGLISH_NUMBERS: &[(&str, u32)] = &[
+    ("zero", 0), ("a", 1), ("an", 1), ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5),
+    ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9), ("ten", 10), ("eleven", 11), ("twelve", 12),
+    ("thirteen", 13), ("fourteen", 14), ("fifteen", 15), ("sixteen", 16), ("seventeen", 17),
+    ("eighteen", 18), ("nineteen", 19), ("twenty", 20), ("thirty", 30), ("forty", 40), ("fifty", 50),
+    ("sixty", 60), ("seventy", 70), ("eighty", 80), ("ninety", 90), ("hundred", 100),
+];
+
+const WEEKDAYS: &[(&str, Weekday)] = &[
+    ("mandag", Weekday::Mon), ("tirsdag", Weekday::Tue), ("onsdag", Weekday::Wed),
+    ("torsdag", Weekday::Thu), ("fredag", Weekday::Fri), ("lørdag", Weekday::Sat), ("søndag", Weekday::Sun),
+    ("monday", Weekday::Mon), ("tuesday", Weekday::Tue), ("wednesday", Weekday::Wed),
+    ("thursday", Weekday::Thu), ("friday", Weekday::Fri), ("saturday", Weekday::Sat), ("sunday", Weekday::Sun),
+];
+
+const MONTHS: &[(&str, u32)] = &[
+    ("januar", 1), ("februar", 2), ("marts", 3), ("april", 4), ("maj", 5), ("juni", 6), ("juli", 7),
+    ("august", 8), ("september", 9), ("oktober", 10), ("november", 11), ("december", 12),
+    ("january", 1), ("february", 2), ("march", 3), ("may", 5), ("june", 6), ("july", 7),
+    ("october", 10),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SlotKind {
+    /// Free text
+    Text,
+    /// One number, as digits or a word
+    Number,
+    /// Numeric setting name ("CPU-grænse")
+    Setting,
+    /// On/off setting name ("synkronisering")
+    Toggle,
+}
+
+impl SlotKind {
+    fn max_tokens(self) -> usize {
+        match self {
+            Self::Text => usize::MAX,
+            Self::Number => 1,
+            Self::Setting | Self::Toggle => 3,
+        }
+    }
+
+    fn accepts(self, tokens: &[String], is_danish: bool) -> bool {
+        match self {
+            Self::Text => true,
+            Self::Number => parse_number(&tokens[0], is_danish).is_some(),
+            Self::Setting => setting_key(&tokens.join(" ")).is_some(),
+            Self::Toggle => setting_toggle(&tokens.join(" ")).is_some(),
+        }
+    }
+}
+
+enum Element {
+    Words { alternatives: Vec<Vec<String>>, optional: bool },
+    Slot(SlotKind),
+}
+
+type Span = (SlotKind, Range<usize>);
+
+/// Builds the command for a matched rule; `None` rejects the match
+type Builder = fn(&Slots) -> Option<VoiceCommand>;
+
+struct Rule {
+    elements: Vec<Element>,
+    build: Builder,
+}
+
+/// What a rule captured, for its builder
+pub struct Slots<'a> {
+    tokens: &'a [String],
+    spans: &'a [Span],
+    now: NaiveDateTime,
+    is_danish: bool,
+}
+
+impl Slots<'_> {
+    fn get(&self, kind: SlotKind) -> Option<&[String]> {
+        self.spans
+            .iter()
+            .find(|(k, _)| *k == kind)
+            .map(|(_, range)| &self.tokens[range.clone()])
+    }
+
+    fn text(&self) -> Option<String> {
+        self.get(SlotKind::Text).map(|tokens| tokens.join(" "))
+    }
+
+    fn number(&self) -> Option<u32> {
+        parse_number(&self.get(SlotKind::Number)?[0], self.is_danish)
+    }
+
+    fn setting(&self) -> Option<SettingKey> {
+        setting_key(&self.get(SlotKind::Setting)?.join(" "))
+    }
+
+    fn toggle(&self) -> Option<SettingToggle> {
+        setting_toggle(&self.get(SlotKind::Toggle)?.join(" "))
+    }
+
+    /// Whether any word of the utterance starts with one of `words`
+    fn mentions(&self, words: &[&str]) -> bool {
+        self.tokens.iter().any(|t| words.iter().any(|w| t.starts_with(w)))
+    }
+}
+
+/// Command matched by the grammar
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrammarMatch {
+    pub command: VoiceCommand,
+    /// How exactly the rule's words matched and how much of the utterance it explains
+    pub confidence: f32,
+}
+
+/// Command rules for one language
+pub struct Grammar {
+    rules: Vec<Rule>,
+    is_danish: bool,
+}
+
+impl Grammar {
+    pub fn new(is_danish: bool) -> Self {
+        let source = if is_danish { danish_rules() } else { english_rules() };
+        Self {
+            rules: source
+                .into_iter()
+                .map(|(pattern, build)| Rule { elements: compile(pattern), build })
+                .collect(),
+            is_danish,
+        }
+    }
+
+    /// Best matching command; `None` when no rule matches well enough
+    pub fn parse(&self, text: &str, now: NaiveDateTime) -> Option<GrammarMatch> {
+        let tokens = tokenize(text);
+        let mut best: Option<(GrammarMatch, usize)> = None;
+        let mut spans = Vec::new();
+
+        for rule in &self.rules {
+            for start in 0..tokens.len() {
+                let mut found = |end: usize, literal_score: f32, literals: usize, spans: &[Span]| {
+                    if literals == 0 {
+                        return;
+                    }
+                    let slot_tokens: usize = spans.iter().map(|(_, range)| range.len()).sum();
+                    let unexplained = tokens[..start]
+                        .iter()
+                        .chain(&tokens[end..])
+                        .filter(|t| !FILLER.contains(&t.as_str()))
+                        .count();
+                    let covered = literals + slot_tokens;
+                    let coverage = covered as f32 / (covered + unexplained) as f32;
+                    let confidence = literal_score / literals as f32 * (0.4 + 0.6 * coverage);
+
+                    // Ties go to the match with more literal words, then the earlier rule
+                    let better = best.as_ref().is_none_or(|(current, current_literals)| {
+                        confidence > current.confidence + 1e-6
+                            || ((confidence - current.confidence).abs() <= 1e-6 && literals > *current_literals)
+                    });
+                    if !better {
+                        return;
+                    }
+                    let slots = Slots { tokens: &tokens, spans, now, is_danish: self.is_danish };
+                    if let Some(command) = (rule.build)(&slots) {
+                        best = Some((GrammarMatch { command, confidence }, literals));
+                    }
+                };
+                walk(&rule.elements, &tokens, start, 0.0, 0, self.is_danish, &mut spans, &mut found);
+            }
+        }
+
+        best.map(|(found, _)| found)
+            .filter(|found| found.confidence >= MIN_RULE_CONFIDENCE)
+    }
+}
+
+/// Receives each complete match: end token, literal score, literal count and slot spans
+type MatchFound<'a> = dyn FnMut(usize, f32, usize, &[Span]) + 'a;
+
+/// Try every way `elements` can match `tokens` from `ti`, reporting complete matches
+#[allow(clippy::too_many_arguments)]
+fn walk(
+    elements: &[Element],
+    tokens: &[String],
+    ti: usize,
+    literal_score: f32,
+    literals: usize,
+    is_danish: bool,
+    spans: &mut Vec<Span>,
+    found: &mut MatchFound<'_>,
+) {
+    let Some((element, rest)) = elements.split_first() else {
+        found(ti, literal_score, literals, spans);
+        return;
+    };
+
+    match element {
+        Element::Words { alternatives, optional } => {
+            for words in alternatives {
+                if let Some(score) = match_words(words, &tokens[ti..]) {
+                    walk(rest, tokens, ti + words.len(), literal_score + score, literals + words.len(), is_danish, spans, found);
+                }
+            }
+            if *optional {
+                walk(rest, tokens, ti, literal_score, literals, is_danish, spans, found);
+            }
+        }
+        Element::Slot(kind) => {
+            let available = tokens.len() - ti;
+            // Free text at the end of a rule takes the rest of the utterance
+            let lengths = if *kind == SlotKind::Text && rest.is_empty() {
+                available..available + 1
+            } else {
+                1..available.min(kind.max_tokens()) + 1
+            };
+            for len in lengths.filter(|&len| len > 0) {
+                if kind.accepts(&tokens[ti..ti + len], is_danish) {
+                    spans.push((*kind, ti..ti + len));
+                    walk(rest, tokens, ti + len, literal_score, literals, is_danish, spans, found);
+                    spans.pop();
+                }
+            }
+        }
+    }
+}
+
+fn match_words(words: &[String], tokens: &[String]) -> Option<f32> {
+    if tokens.len() < words.len() {
+        return None;
+    }
+    words.iter().zip(tokens).map(|(word, token)| similarity(word, token)).sum()
+}
+
+/// How well a spoken word matches a rule word: exact, inflected ("opgaven" for
+/// "opgave") or within a small edit distance. Short words must match exactly.
+fn similarity(word: &str, token: &str) -> Option<f32> {
+    if word == token {
+        return Some(1.0);
+    }
+    let length = word.chars().count();
+    if length < 4 {
+        return None;
+    }
+    if token.starts_with(word) && token.chars().count() - length <= 3 {
+        return Some(0.9);
+    }
+    let allowed = if length >= 8 { 2 } else { 1 };
+    match edit_distance(word, token) {
+        1 => Some(0.85),
+        d if d <= allowed => Some(0.75),
+        _ => None,
+    }
+}
+
+/// How well an utterance names a fixed phrase, such as a voice macro's;
+/// filler and "kør"/"run" around the phrase are allowed
+pub fn match_phrase(phrase: &str, text: &str) -> Option<f32> {
+    let words = tokenize(phrase);
+    let tokens = tokenize(text);
+    if words.is_empty() {
+        return None;
+    }
+
+    (0..tokens.len())
+        .filter_map(|start| {
+            let score = match_words(&words, &tokens[start..])? / words.len() as f32;
+            let rest_is_filler = tokens[..start]
+                .iter()
+                .chain(&tokens[start + words.len()..])
+                .all(|t| FILLER.contains(&t.as_str()) || MACRO_PREFIX.contains(&t.as_str()));
+            rest_is_filler.then_some(score)
+        })
+        .fold(None, |best: Option<f32>, score| Some(best.map_or(score, |b| b.max(score))))
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Parse a rule pattern into elements
+fn compile(pattern: &str) -> Vec<Element> {
+    let alternatives = |group: &str| -> Vec<Vec<String>> {
+        group
+            .split('|')
+            .map(|alt| alt.split_whitespace().map(str::to_string).collect())
+            .collect()
+    };
+
+    let mut elements = Vec::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' => {
+                chars.next();
+            }
+            '(' | '[' => {
+                chars.next();
+                let close = if c == '(' { ')' } else { ']' };
+                let group: String = chars.by_ref().take_while(|&ch| ch != close).collect();
+                elements.push(Element::Words { alternatives: alternatives(&group), optional: c == '[' });
+            }
+            '{' => {
+                chars.next();
+                let name: String = chars.by_ref().take_while(|&ch| ch != '}').collect();
+                elements.push(Element::Slot(match name.as_str() {
+                    "number" => SlotKind::Number,
+                    "setting" => SlotKind::Setting,
+                    "toggle" => SlotKind::Toggle,
+                    _ => SlotKind::Text,
+                }));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch == ' ' {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                elements.push(Element::Words { alternatives: alternatives(&word), optional: false });
+            }
+        }
+    }
+    elements
+}
+
+/// Lowercase words, keeping the punctuation numbers, times and dates use
+fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || "-%:./'".contains(c) { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(|word| word.trim_end_matches('.').to_string())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// A number as digits ("50", "50%") or a word
+fn parse_number(token: &str, is_danish: bool) -> Option<u32> {
+    let token = token.trim_end_matches('%');
+    if let Ok(n) = token.parse() {
+        return Some(n);
+    }
+    let words = if is_danish { DANISH_NUMBERS } else { ENGLISH_NUMBERS };
+    words.iter().find(|(word, _)| *word == token).map(|(_, n)| *n)
+}
+
+/// Numeric setting named in text
+fn setting_key(text: &str) -> Option<SettingKey> {
+    let table: &[(&[&str], SettingKey)] = &[
+        (&["synkroniseringsinterval", "sync interval"], SettingKey::SyncInterval),
+        (&["batterigrænse", "battery limit", "minimum battery"], SettingKey::MinBattery),
+        (&["inaktiv", "idle"], SettingKey::IdleThreshold),
+        (&["cpu", "processor"], SettingKey::CpuLimit),
+        (&["ram", "hukommelse", "memory"], SettingKey::RamLimit),
+        (&["gpu", "grafikkort", "graphics"], SettingKey::GpuLimit),
+    ];
+    table
+        .iter()
+        .find(|(words, _)| words.iter().any(|w| text.contains(w)))
+        .map(|(_, key)| *key)
+}
+
+/// Toggle setting named in text
+fn setting_toggle(text: &str) -> Option<SettingToggle> {
+    let table: &[(&[&str], SettingToggle)] = &[
+        (&["synkronisering", "sync"], SettingToggle::Sync),
+        (&["transskription", "transcription"], SettingToggle::Transcription),
+        (&["tekstgenkendelse", "ocr", "text recognition"], SettingToggle::Ocr),
+        (&["embeddings"], SettingToggle::Embeddings),
+        (&["batteri", "battery"], SettingToggle::RunOnBattery),
+    ];
+    table
+        .iter()
+        .find(|(words, _)| words.iter().any(|w| text.contains(w)))
+        .map(|(_, toggle)| *toggle)
+}
+
+/// Find a due time ("i morgen kl 9", "on friday", "om 2 timer") in the words,
+/// returning it with the word ranges it was spoken in
+pub fn extract_when(tokens: &[String], now: NaiveDateTime) -> Option<(NaiveDateTime, Vec<Range<usize>>)> {
+    let mut date: Option<(NaiveDate, Range<usize>)> = None;
+    let mut time: Option<(NaiveTime, Range<usize>)> = None;
+
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some((at, len)) = parse_relative_time(&tokens[i..], now) {
+            return Some((at, std::iter::once(i..i + len).collect()));
+        }
+        if date.is_none() {
+            if let Some((day, len)) = parse_date(&tokens[i..], now.date()) {
+                date = Some((day, i..i + len));
+                i += len;
+                continue;
+            }
+        }
+        if time.is_none() {
+            if let Some((at, len)) = parse_time(&tokens[i..]) {
+                time = Some((at, i..i + len));
+                i += len;
+                continue;
+            }
+        }
+        i += 1;
+    }
+
+    match (date, time) {
+        (None, None) => None,
+        (Some((day, range)), None) => {
+            let at = NaiveTime::from_hms_opt(DEFAULT_DUE_HOUR, 0, 0)?;
+            Some((day.and_time(at), vec![range]))
+        }
+        (None, Some((at, range))) => {
+            // A time that has passed today means tomorrow
+            let mut due = now.date().and_time(at);
+            if due <= now {
+                due += Duration::days(1);
+            }
+            Some((due, vec![range]))
+        }
+        (Some((day, day_range)), Some((at, time_range))) => Some((day.and_time(at), vec![day_range, time_range])),
+    }
+}
+
+/// "om 2 timer", "in 30 minutes"
+fn parse_relative_time(tokens: &[String], now: NaiveDateTime) -> Option<(NaiveDateTime, usize)> {
+    let [preposition, amount, unit, ..] = tokens else {
+        return None;
+    };
+    if preposition != "om" && preposition != "in" {
+        return None;
+    }
+    let amount = parse_number(amount, true).or_else(|| parse_number(amount, false))?;
+    if amount > MAX_RELATIVE_AMOUNT {
+        return None;
+    }
+    let offset = if unit.starts_with("time") || unit.starts_with("hour") {
+        Duration::hours(amount as i64)
+    } else if unit.starts_with("minut") {
+        Duration::minutes(amount as i64)
+    } else {
+        return None;
+    };
+    Some((now.checked_add_signed(offset)?, 3))
+}
+
+/// A day: "i dag", "i morgen", "på fredag", "om 3 dage", "den 5. maj", "24/12"
+fn parse_date(tokens: &[String], today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    let words: Vec<&str> = tokens.iter().take(3).map(String::as_str).collect();
+    let offset = |days: i64| today.checked_add_signed(Duration::days(days));
+
+    match words.as_slice() {
+        ["i", "dag", ..] => return Some((today, 2)),
+        ["today", ..] => return Some((today, 1)),
+        ["i", "morgen", ..] => return Some((offset(1)?, 2)),
+        ["tomorrow", ..] => return Some((offset(1)?, 1)),
+        ["i", "overmorgen", ..] => return Some((offset(2)?, 2)),
+        ["overmorgen", ..] => return Some((offset(2)?, 1)),
+        ["day", "after", "tomorrow"] => return Some((offset(2)?, 3)),
+        _ => {}
+    }
+
+    // "om 3 dage", "in 2 weeks"
+    if let [preposition, amount, unit, ..] = words.as_slice() {
+        if *preposition == "om" || *preposition == "in" {
+            if let Some(amount) = parse_number(amount, true)
+                .or_else(|| parse_number(amount, false))
+                .filter(|amount| *amount <= MAX_RELATIVE_AMOUNT)
+            {
+                let days = if unit.starts_with("dag") || unit.starts_with("day") {
+                    Some(amount as i64)
+                } else if unit.starts_with("uge") || unit.starts_with("week") {
+                    Some(7 * amount as i64)
+                } else {
+                    None
+                };
+                if let Some(days) = days {
+                    return Some((offset(days)?, 3));
+                }
+            }
+        }
+    }
+
+    // "på fredag", "next monday", "fredag"
+    let (skip, rest) = match words.first() {
+        Some(&("på" | "on" | "næste" | "next" | "den" | "d")) => (1, &words[1..]),
+        _ => (0, &words[..]),
+    };
+    if let Some(weekday) = rest.first().and_then(|w| WEEKDAYS.iter().find(|(name, _)| name == w)) {
+        let ahead = (weekday.1.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64 + 6) % 7 + 1;
+        return Some((offset(ahead)?, skip + 1));
+    }
+
+    // "24/12", "den 5 maj", "may 5th"
+    if let Some(first) = rest.first() {
+        let mut parts = first.split('/');
+        if let (Some(day), Some(month)) = (parts.next(), parts.next()) {
+            if let (Ok(day), Ok(month)) = (day.parse(), month.parse()) {
+                return Some((next_date(today, month, day)?, skip + 1));
+            }
+        }
+    }
+    let day_number = |w: &str| w.trim_end_matches(|c: char| c.is_alphabetic()).parse::<u32>().ok();
+    let month_number = |w: &str| MONTHS.iter().find(|(name, _)| *name == w).map(|(_, m)| *m);
+    if let [first, second, ..] = rest {
+        if let (Some(day), Some(month)) = (day_number(first), month_number(second)) {
+            return Some((next_date(today, month, day)?, skip + 2));
+        }
+        if let (Some(month), Some(day)) = (month_number(first), day_number(second)) {
+            return Some((next_date(today, month, day)?, skip + 2));
+        }
+    }
+    None
+}
+
+/// The next time the day and month come around
+fn next_date(today: NaiveDate, month: u32, day: u32) -> Option<NaiveDate> {
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    if this_year >= today {
+        Some(this_year)
+    } else {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)
+    }
+}
+
+/// A time of day: "kl 9", "klokken 14.30", "at 9 pm", "9:30"
+fn parse_time(tokens: &[String]) -> Option<(NaiveTime, usize)> {
+    let first = tokens.first()?;
+    let (value, mut used) = if ["kl", "klokken", "at"].contains(&first.as_str()) {
+        (tokens.get(1)?.as_str(), 2)
+    } else if first.contains(':') {
+        (first.as_str(), 1)
+    } else {
+        return None;
+    };
+
+    // "9pm" or "9 pm"
+    let (value, mut meridiem) = match value.strip_suffix("pm").or_else(|| value.strip_suffix("am")) {
+        Some(number) => (number, Some(value.ends_with("pm"))),
+        None => (value, None),
+    };
+    if meridiem.is_none() {
+        if let Some(next) = tokens.get(used).map(String::as_str) {
+            if next == "pm" || next == "am" {
+                meridiem = Some(next == "pm");
+                used += 1;
+            }
+        }
+    }
+
+    let mut parts = value.split([':', '.']);
+    let mut hour: u32 = parts.next()?.parse().ok()?;
+    let minute: u32 = match parts.next() {
+        Some(minute) => minute.parse().ok()?,
+        None => 0,
+    };
+    match meridiem {
+        Some(true) if hour < 12 => hour += 12,
+        Some(false) if hour == 12 => hour = 0,
+        _ => {}
+    }
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, used))
+}
+
+/// Description, priority and due time of a spoken task
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskDetails {
+    /// What is left once priority and time are taken out; may be empty
+    pub description: String,
+    /// "high" or "critical" when spoken
+    pub priority: Option<&'static str>,
+    pub due: Option<NaiveDateTime>,
+}
+
+/// Take priority and due time out of a task description ("ring til mor i morgen
+/// med høj prioritet")
+pub fn task_details(text: &str, now: NaiveDateTime) -> TaskDetails {
+    split_task_details(tokenize(text), now)
+}
+
+fn split_task_details(mut words: Vec<String>, now: NaiveDateTime) -> TaskDetails {
+    let due = extract_when(&words, now).map(|(due, mut ranges)| {
+        ranges.sort_by_key(|range| std::cmp::Reverse(range.start));
+        for range in ranges {
+            words.drain(range);
+        }
+        due
+    });
+
+    let phrases: &[(&[&str], &str)] = &[
+        (&["kritisk"], "critical"), (&["haster"], "critical"),
+        (&["critical"], "critical"), (&["urgent"], "critical"),
+        (&["høj", "prioritet"], "high"), (&["vigtig"], "high"), (&["vigtigt"], "high"),
+        (&["high", "priority"], "high"), (&["important"], "high"),
+    ];
+    let mut priority = None;
+    for (phrase, level) in phrases {
+        let position = words.windows(phrase.len()).position(|w| w.iter().zip(phrase.iter()).all(|(a, b)| a == b));
+        let Some(mut start) = position else {
+            continue;
+        };
+        let mut end = start + phrase.len();
+        if start > 0 && ["med", "with", "som", "as"].contains(&words[start - 1].as_str()) {
+            start -= 1;
+        }
+        if end < words.len() && phrase.len() == 1 && ["prioritet", "priority"].contains(&words[end].as_str()) {
+            end += 1;
+        }
+        words.drain(start..end);
+        priority.get_or_insert(*level);
+    }
+
+    TaskDetails {
+        description: words.join(" "),
+        priority,
+        due,
+    }
+}
+
+/// Task from the spoken description; priority may also be said before it
+fn create_task(slots: &Slots) -> Option<VoiceCommand> {
+    let words = slots.get(SlotKind::Text).map(<[String]>::to_vec).unwrap_or_default();
+    let details = split_task_details(words, slots.now);
+    let priority = details.priority.or_else(|| {
+        if slots.mentions(&["kritisk", "haster", "critical", "urgent"]) {
+            Some("critical")
+        } else if slots.mentions(&["vigtig", "important"]) {
+            Some("high")
+        } else {
+            None
+        }
+    });
+
+    Some(VoiceCommand::CreateTask {
+        description: details.description,
+        priority: priority.unwrap_or("normal").to_string(),
+        due: details.due,
+    })
+}
+
+fn set_setting(slots: &Slots) -> Option<VoiceCommand> {
+    let setting = slots.setting()?;
+    let mut value = slots.number()?;
+    if setting == SettingKey::IdleThreshold && slots.mentions(&["minut"]) {
+        value = value.checked_mul(60)?;
+    }
+    Some(VoiceCommand::SetSetting { setting, value })
+}
+
+fn rule(pattern: &'static str, build: Builder) -> (&'static str, Builder) {
+    (pattern, build)
+}
+
+fn danish_rules() -> Vec<(&'static str, Builder)> {
+    vec![
+        // Privacy mode ("afslut privat tilstand" would otherwise match stop)
+        rule("(afslut|stop|forlad|sluk|deaktiver|slå fra) (privat tilstand|privattilstand|privat mode)", |_| {
+            Some(VoiceCommand::SetPrivacyMode { enabled: false })
+        }),
+        rule("slå (privat tilstand|privattilstand|privat mode) fra", |_| {
+            Some(VoiceCommand::SetPrivacyMode { enabled: false })
+        }),
+        rule("[gå i|aktiver|start|tænd|slå til|slå] (privat tilstand|privattilstand|privat mode) [til]", |_| {
+            Some(VoiceCommand::SetPrivacyMode { enabled: true })
+        }),
+        // Settings
+        rule("(sæt|skift|ændr|juster) [den|det] {setting} [til|på] {number} [procent|%|minut|minutter|sekund|sekunder]", set_setting),
+        rule("slå {toggle} fra", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: false })),
+        rule("(slå fra|sluk|deaktiver) [for] {toggle}", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: false })),
+        rule("slå {toggle} til", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: true })),
+        rule("(slå til|tænd|aktiver) [for] {toggle}", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: true })),
+        // Commander
+        rule("(start|begynd|kør|aktiver|sæt i gang) [arbejde|arbejdet|commander]", |_| Some(VoiceCommand::StartCommander)),
+        rule("(stop|stands|deaktiver|afslut|hold pause) [arbejde|arbejdet|commander]", |_| Some(VoiceCommand::StopCommander)),
+        rule("[hvad er|vis] (status|rapport)", |_| Some(VoiceCommand::GetStatus)),
+        rule("(hvordan går det|hvad sker der)", |_| Some(VoiceCommand::GetStatus)),
+        // Sync
+        rule("(synkroniser|synkronisér) [nu|data|alt]", |_| Some(VoiceCommand::SyncNow)),
+        rule("(start|kør|lav) [en] synkronisering [nu]", |_| Some(VoiceCommand::SyncNow)),
+        // Search and tasks
+        rule("(søg efter|søg|find|led efter|undersøg) {query}", |s| Some(VoiceCommand::Search { query: s.text()? })),
+        rule("(søg efter|søg)", |_| Some(VoiceCommand::Search { query: String::new() })),
+        rule("(opret|lav|tilføj|ny) [en|et] [ny] opgave [om] {description}", create_task),
+        rule("(opret|lav|tilføj|ny) [en|et] [ny] opgave", create_task),
+        rule("(skriv ned|husk mig på|husk) [at] {description}", create_task),
+        // Notifications
+        rule("[læs] [mine] [nye|ulæste] (notifikationer|beskeder)", |_| Some(VoiceCommand::ReadNotifications)),
+        rule("(hvad er nyt|ulæste)", |_| Some(VoiceCommand::ReadNotifications)),
+        // Conversation
+        rule("[vis] (hjælp|muligheder|kommandoer)", |_| Some(VoiceCommand::Help)),
+        rule("(hvad kan du|hvad kan du gøre|hvad kan jeg sige)", |_| Some(VoiceCommand::Help)),
+        rule("ja [tak]", |_| Some(VoiceCommand::Confirm)),
+        rule("(gør det|prøv igen|jep|okay|ok)", |_| Some(VoiceCommand::Confirm)),
+        rule("(annuller|afbryd|nej|glem det|fortryd)", |_| Some(VoiceCommand::Cancel)),
+        rule("(gentag|sig det igen|hvad sagde du|en gang til|igen|repeat)", |_| Some(VoiceCommand::Repeat)),
+    ]
+}
+
+fn english_rules() -> Vec<(&'static str, Builder)> {
+    vec![
+        // Privacy mode
+        rule("(exit|stop|leave|end|disable|turn off) (privacy mode|private mode)", |_| {
+            Some(VoiceCommand::SetPrivacyMode { enabled: false })
+        }),
+        rule("turn (privacy mode|private mode) off", |_| Some(VoiceCommand::SetPrivacyMode { enabled: false })),
+        rule("[enter|start|enable|turn on|activate] (privacy mode|private mode) [on]", |_| {
+            Some(VoiceCommand::SetPrivacyMode { enabled: true })
+        }),
+        // Settings
+        rule("(set|change|adjust) [the] {setting} [to] {number} [percent|%|minute|minutes|second|seconds]", set_setting),
+        rule("(turn|switch) {toggle} off", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: false })),
+        rule("(turn off|switch off|disable) {toggle}", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: false })),
+        rule("(turn|switch) {toggle} on", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: true })),
+        rule("(turn on|switch on|enable|activate) {toggle}", |s| Some(VoiceCommand::ToggleSetting { setting: s.toggle()?, enabled: true })),
+        // Commander
+        rule("(start|begin|activate|run|go) [working|work|commander]", |_| Some(VoiceCommand::StartCommander)),
+        rule("(stop|halt|deactivate|quit|pause|end) [working|work|commander]", |_| Some(VoiceCommand::StopCommander)),
+        rule("[what's|what is|show] [the] (status|report)", |_| Some(VoiceCommand::GetStatus)),
+        rule("(how's it going|how is it going|what's happening|what is happening)", |_| Some(VoiceCommand::GetStatus)),
+        // Sync
+        rule("(sync|synchronize|synchronise) [now|data|everything]", |_| Some(VoiceCommand::SyncNow)),
+        rule("(start|run|do) [a] sync [now]", |_| Some(VoiceCommand::SyncNow)),
+        // Search and tasks
+        rule("(search for|search|find|look for|look up|investigate) {query}", |s| Some(VoiceCommand::Search { query: s.text()? })),
+        rule("(search for|search)", |_| Some(VoiceCommand::Search { query: String::new() })),
+        rule("(create|add|make|new) [a] [new] task [to|for] {description}", create_task),
+        rule("(create|add|make|new) [a] [new] task", create_task),
+        rule("(note down|remind me to|remember to|remember) {description}", create_task),
+        // Notifications
+        rule("[read] [my] [new|unread] (notifications|messages)", |_| Some(VoiceCommand::ReadNotifications)),
+        rule("(what's new|unread)", |_| Some(VoiceCommand::ReadNotifications)),
+        // Conversation
+        rule("[show] (help|options|commands)", |_| Some(VoiceCommand::Help)),
+        rule("(what can you do|what can i say)", |_| Some(VoiceCommand::Help)),
+        rule("yes [please]", |_| Some(VoiceCommand::Confirm)),
+        rule("(do it|try again|yep|okay|ok)", |_| Some(VoiceCommand::Confirm)),
+        rule("(cancel|abort|nevermind|never mind|no|forget it|undo)", |_| Some(VoiceCommand::Cancel)),
+        rule("(repeat|say that again|what did you say|once more|again|pardon)", |_| Some(VoiceCommand::Repeat)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Saturday 17 October 2026, 10:00
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 17).unwrap().and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    fn at(day: u32, hour: u32, minute: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(2026, 10, day).unwrap().and_hms_opt(hour, minute, 0)
+    }
+
+    #[test]
+    fn test_task_with_date_and_time() {
+        let grammar = Grammar::new(true);
+        let found = grammar.parse("opret opgave i morgen kl 9", now()).unwrap();
+        assert_eq!(
+            found.command,
+            VoiceCommand::CreateTask {
+                description: String::new(),
+                priority: "normal".to_string(),
+                due: at(18, 9, 0),
+            }
+        );
+        assert!(found.confidence > 0.9);
+
+        let found = grammar.parse("tilføj en opgave om at ringe til tandlægen på fredag kl. 14.30 med høj prioritet", now()).unwrap();
+        assert_eq!(
+            found.command,
+            VoiceCommand::CreateTask {
+                description: "at ringe til tandlægen".to_string(),
+                priority: "high".to_string(),
+                due: at(23, 14, 30),
+            }
+        );
+
+        let grammar = Grammar::new(false);
+        let found = grammar.parse("remind me to water the plants in 2 hours", now()).unwrap();
+        assert_eq!(
+            found.command,
+            VoiceCommand::CreateTask {
+                description: "water the plants".to_string(),
+                priority: "normal".to_string(),
+                due: at(17, 12, 0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_dates() {
+        let words = |text: &str| tokenize(text);
+        assert_eq!(extract_when(&words("i overmorgen"), now()).unwrap().0, at(19, 9, 0).unwrap());
+        assert_eq!(extract_when(&words("next saturday at 9pm"), now()).unwrap().0, at(24, 21, 0).unwrap());
+        assert_eq!(extract_when(&words("den 20. oktober"), now()).unwrap().0, at(20, 9, 0).unwrap());
+        assert_eq!(extract_when(&words("kl 8"), now()).unwrap().0, at(18, 8, 0).unwrap());
+        let new_year = NaiveDate::from_ymd_opt(2027, 1, 2).unwrap().and_hms_opt(9, 0, 0).unwrap();
+        assert_eq!(extract_when(&words("2/1"), now()).unwrap().0, new_year);
+        assert!(extract_when(&words("ring til mor"), now()).is_none());
+    }
+
+    #[test]
+    fn test_fuzzy_words_and_fillers() {
+        let grammar = Grammar::new(true);
+        // Inflection and a transcription slip
+        assert_eq!(grammar.parse("kan du starte", now()).unwrap().command, VoiceCommand::StartCommander);
+        assert_eq!(
+            grammar.parse("læs notifikationerne", now()).unwrap().command,
+            VoiceCommand::ReadNotifications
+        );
+        assert_eq!(grammar.parse("anuller", now()).unwrap().command, VoiceCommand::Cancel);
+
+        // A stray word lowers confidence, an unrelated sentence does not match
+        let exact = grammar.parse("vis status", now()).unwrap().confidence;
+        let loose = grammar.parse("vis status for bilen", now()).unwrap().confidence;
+        assert!(loose < exact);
+        assert!(grammar.parse("jeg spiser en banan i haven", now()).is_none());
+    }
+
+    #[test]
+    fn test_sync_and_macro_phrases() {
+        let grammar = Grammar::new(true);
+        assert_eq!(grammar.parse("synkroniser nu", now()).unwrap().command, VoiceCommand::SyncNow);
+        assert_eq!(grammar.parse("start en synkronisering", now()).unwrap().command, VoiceCommand::SyncNow);
+        assert!(matches!(
+            grammar.parse("slå synkronisering fra", now()).unwrap().command,
+            VoiceCommand::ToggleSetting { enabled: false, .. }
+        ));
+
+        assert_eq!(match_phrase("morgenrutine", "kør morgenrutine"), Some(1.0));
+        assert!(match_phrase("morgenrutine", "hej cirkelline morgenrutinen").unwrap() > 0.8);
+        assert!(match_phrase("god nat", "god nat og sov godt").is_none());
+    }
+
+    #[test]
+    fn test_numbers_in_settings() {
+        let grammar = Grammar::new(true);
+        assert_eq!(
+            grammar.parse("juster hukommelse til tres procent", now()).unwrap().command,
+            VoiceCommand::SetSetting { setting: SettingKey::RamLimit, value: 60 }
+        );
+        let grammar = Grammar::new(false);
+        assert_eq!(
+            grammar.parse("set the gpu to 75%", now()).unwrap().command,
+            VoiceCommand::SetSetting { setting: SettingKey::GpuLimit, value: 75 }
+        );
+    }
+
+    #[test]
+    fn test_huge_amounts_do_not_overflow() {
+        let grammar = Grammar::new(true);
+        if let Some(found) = grammar.parse("opret opgave om 4000000000 timer", now()) {
+            assert!(matches!(found.command, VoiceCommand::CreateTask { due: None, .. }));
+        }
+        assert!(extract_when(&tokenize("om 4000000000 dage"), now()).is_none());
+        assert!(grammar.parse("sæt inaktiv til 4000000000 minutter", now()).is_none_or(|found| {
+            !matches!(found.command, VoiceCommand::SetSetting { .. })
+        }));
+    }
+}
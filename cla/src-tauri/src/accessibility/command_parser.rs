@@ -1,7 +1,20 @@
 // Command Parser - Parses natural language voice commands
-// Supports Danish and English commands
+// Supports Danish and English commands through the rule grammar in command_grammar
+// An optional intent model catches phrasings the rules miss
 
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::accessibility::command_grammar::{self, Grammar};
+use crate::accessibility::voice_settings::{SettingKey, SettingToggle};
+use crate::models::VoiceMacro;
+
+/// Actions one voice macro may run
+pub const MAX_MACRO_ACTIONS: usize = 10;
+
+/// How closely an utterance must match a macro's phrase
+const MIN_MACRO_CONFIDENCE: f32 = 0.8;
 
 /// Parsed voice command
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -14,12 +27,29 @@ pub enum VoiceCommand {
     GetStatus,
     /// Search for something
     Search { query: String },
-    /// Create a task
-    CreateTask { description: String, priority: String },
+    /// Create a task ("opret opgave i morgen kl 9"), due in local time
+    CreateTask {
+        description: String,
+        priority: String,
+        #[serde(default)]
+        due: Option<NaiveDateTime>,
+    },
     /// Read notifications
     ReadNotifications,
+    /// Set a numeric setting ("sæt CPU-grænse til 50 procent")
+    SetSetting { setting: SettingKey, value: u32 },
+    /// Turn a setting on or off ("slå synkronisering fra")
+    ToggleSetting { setting: SettingToggle, enabled: bool },
+    /// Suspend or resume all capture ("privat tilstand")
+    SetPrivacyMode { enabled: bool },
+    /// Synchronize with CKC now
+    SyncNow,
+    /// Run the commands of one of the user's voice macros in order
+    RunMacro { phrase: String, commands: Vec<VoiceCommand> },
     /// Get help
     Help,
+    /// Confirm the pending question (e.g. "Skal jeg prøve igen?")
+    Confirm,
     /// Cancel current operation
     Cancel,
     /// Repeat last response
@@ -28,9 +58,74 @@ pub enum VoiceCommand {
     Unknown(String),
 }
 
+impl VoiceCommand {
+    /// Intent label used by the intent model and its training data
+    pub fn intent(&self) -> &'static str {
+        match self {
+            Self::StartCommander => "start_commander",
+            Self::StopCommander => "stop_commander",
+            Self::GetStatus => "get_status",
+            Self::Search { .. } => "search",
+            Self::CreateTask { .. } => "create_task",
+            Self::ReadNotifications => "read_notifications",
+            Self::SetSetting { .. } => "set_setting",
+            Self::ToggleSetting { .. } => "toggle_setting",
+            Self::SetPrivacyMode { enabled: true } => "privacy_on",
+            Self::SetPrivacyMode { enabled: false } => "privacy_off",
+            Self::Help => "help",
+            Self::Confirm => "confirm",
+            Self::Cancel => "cancel",
+            Self::Repeat => "repeat",
+            // Not known to the intent model; only the grammar and macros produce these
+            Self::SyncNow | Self::RunMacro { .. } => "none",
+            Self::Unknown(_) => "none",
+        }
+    }
+}
+
+/// Below this confidence the grammar decides
+pub const MIN_INTENT_CONFIDENCE: f32 = 0.7;
+
+/// Intent predicted by a classifier
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IntentPrediction {
+    pub intent: String,
+    pub confidence: f32,
+}
+
+/// Source of intent predictions; `None` when no model is available
+#[async_trait::async_trait]
+pub trait IntentClassifier: Send + Sync {
+    async fn classify(&self, text: &str) -> Option<IntentPrediction>;
+}
+
+/// What decided a parse
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ParseSource {
+    /// A grammar rule matched
+    Grammar,
+    /// The intent model was more confident than the grammar
+    IntentModel,
+    /// The user's voice macro was named
+    Macro,
+    /// Nothing matched
+    Unrecognized,
+}
+
+/// A parsed command with how sure the parser is of it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ParsedCommand {
+    pub command: VoiceCommand,
+    /// 0.0 - 1.0
+    pub confidence: f32,
+    pub source: ParseSource,
+}
+
 /// Command Parser for natural language
 pub struct CommandParser {
     language: String,
+    grammar: Grammar,
+    classifier: Option<Arc<dyn IntentClassifier>>,
 }
 
 impl CommandParser {
@@ -38,218 +133,166 @@ impl CommandParser {
     pub fn new(language: &str) -> Self {
         Self {
             language: language.to_string(),
+            grammar: Grammar::new(language.starts_with("da")),
+            classifier: None,
         }
     }
 
-    /// Parse natural language text into a command
-    pub async fn parse(&self, text: &str) -> VoiceCommand {
-        let lower = text.to_lowercase().trim().to_string();
-
-        // Danish commands
-        if self.language.starts_with("da") {
-            return self.parse_danish(&lower);
-        }
-
-        // English commands
-        self.parse_english(&lower)
+    /// Rank an intent classifier's predictions against the grammar
+    pub fn with_classifier(mut self, classifier: Arc<dyn IntentClassifier>) -> Self {
+        self.classifier = Some(classifier);
+        self
     }
 
-    /// Parse Danish commands
-    fn parse_danish(&self, text: &str) -> VoiceCommand {
-        // Start commands
-        if self.matches_any(text, &[
-            "start", "begynd", "start arbejde", "begynd arbejde",
-            "start commander", "aktiver", "kør", "sæt i gang"
-        ]) {
-            return VoiceCommand::StartCommander;
-        }
-
-        // Stop commands
-        if self.matches_any(text, &[
-            "stop", "stands", "stop arbejde", "stands arbejde",
-            "stop commander", "deaktiver", "afslut", "hold pause"
-        ]) {
-            return VoiceCommand::StopCommander;
-        }
-
-        // Status commands
-        if self.matches_any(text, &[
-            "status", "hvad er status", "hvordan går det",
-            "vis status", "hvad sker der", "rapport"
-        ]) {
-            return VoiceCommand::GetStatus;
-        }
-
-        // Search commands
-        if let Some(query) = self.extract_after(text, &[
-            "søg efter", "find", "søg", "led efter", "undersøg"
-        ]) {
-            return VoiceCommand::Search { query };
-        }
-
-        // Create task commands
-        if let Some(description) = self.extract_after(text, &[
-            "opret opgave", "ny opgave", "tilføj opgave",
-            "lav en opgave", "skriv ned"
-        ]) {
-            let priority = if text.contains("vigtig") || text.contains("høj prioritet") {
-                "high"
-            } else if text.contains("kritisk") || text.contains("haster") {
-                "critical"
-            } else {
-                "normal"
-            };
-            return VoiceCommand::CreateTask {
-                description,
-                priority: priority.to_string(),
-            };
-        }
-
-        // Notifications
-        if self.matches_any(text, &[
-            "notifikationer", "læs notifikationer", "beskeder",
-            "hvad er nyt", "nye beskeder", "ulæste"
-        ]) {
-            return VoiceCommand::ReadNotifications;
-        }
-
-        // Help
-        if self.matches_any(text, &[
-            "hjælp", "hvad kan du", "muligheder", "kommandoer",
-            "hvad kan jeg sige", "vis hjælp"
-        ]) {
-            return VoiceCommand::Help;
-        }
-
-        // Cancel
-        if self.matches_any(text, &[
-            "annuller", "afbryd", "nej", "glem det", "fortryd"
-        ]) {
-            return VoiceCommand::Cancel;
-        }
-
-        // Repeat
-        if self.matches_any(text, &[
-            "gentag", "sig det igen", "hvad sagde du", "repeat",
-            "en gang til", "igen"
-        ]) {
-            return VoiceCommand::Repeat;
-        }
-
-        VoiceCommand::Unknown(text.to_string())
+    pub fn language(&self) -> &str {
+        &self.language
     }
 
-    /// Parse English commands
-    fn parse_english(&self, text: &str) -> VoiceCommand {
-        // Start commands
-        if self.matches_any(text, &[
-            "start", "begin", "start working", "begin working",
-            "start commander", "activate", "run", "go"
-        ]) {
-            return VoiceCommand::StartCommander;
-        }
+    /// Parse natural language text into a command
+    pub async fn parse(&self, text: &str) -> VoiceCommand {
+        self.parse_detailed(text).await.command
+    }
 
-        // Stop commands
-        if self.matches_any(text, &[
-            "stop", "halt", "stop working", "stop commander",
-            "deactivate", "quit", "pause", "end"
-        ]) {
-            return VoiceCommand::StopCommander;
-        }
+    /// Parse text into a command with its confidence
+    pub async fn parse_detailed(&self, text: &str) -> ParsedCommand {
+        self.parse_at(text, chrono::Local::now().naive_local()).await
+    }
 
-        // Status commands
-        if self.matches_any(text, &[
-            "status", "what's the status", "how's it going",
-            "show status", "what's happening", "report"
-        ]) {
-            return VoiceCommand::GetStatus;
+    /// Parse text, trying the user's voice macros before the built-in commands
+    pub async fn parse_with_macros(&self, text: &str, macros: &[VoiceMacro]) -> ParsedCommand {
+        match self.expand_macro(text, macros, chrono::Local::now().naive_local()) {
+            Some(parsed) => parsed,
+            None => self.parse_detailed(text).await,
         }
+    }
 
-        // Search commands
-        if let Some(query) = self.extract_after(text, &[
-            "search for", "find", "search", "look for", "investigate"
-        ]) {
-            return VoiceCommand::Search { query };
-        }
+    /// The macro the utterance names, its actions parsed into commands
+    fn expand_macro(&self, text: &str, macros: &[VoiceMacro], now: NaiveDateTime) -> Option<ParsedCommand> {
+        let (voice_macro, confidence) = macros
+            .iter()
+            .filter_map(|m| command_grammar::match_phrase(&m.phrase, text).map(|score| (m, score)))
+            .filter(|(_, score)| *score >= MIN_MACRO_CONFIDENCE)
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        let commands = voice_macro
+            .actions
+            .iter()
+            .map(|action| match self.grammar.parse(&action.to_lowercase(), now) {
+                Some(found) => found.command,
+                None => VoiceCommand::Unknown(action.clone()),
+            })
+            .collect();
+        Some(ParsedCommand {
+            command: VoiceCommand::RunMacro { phrase: voice_macro.phrase.clone(), commands },
+            confidence,
+            source: ParseSource::Macro,
+        })
+    }
 
-        // Create task commands
-        if let Some(description) = self.extract_after(text, &[
-            "create task", "new task", "add task",
-            "make a task", "note down", "remember"
-        ]) {
-            let priority = if text.contains("important") || text.contains("high priority") {
-                "high"
-            } else if text.contains("critical") || text.contains("urgent") {
-                "critical"
-            } else {
-                "normal"
-            };
-            return VoiceCommand::CreateTask {
-                description,
-                priority: priority.to_string(),
-            };
+    /// Check a macro before it is saved: its phrase must be free and every
+    /// action a complete command
+    pub fn validate_macro(&self, voice_macro: &VoiceMacro, others: &[VoiceMacro]) -> Result<(), String> {
+        let phrase = voice_macro.phrase.trim();
+        if phrase.is_empty() {
+            return Err("Makroen skal have en sætning".to_string());
         }
-
-        // Notifications
-        if self.matches_any(text, &[
-            "notifications", "read notifications", "messages",
-            "what's new", "new messages", "unread"
-        ]) {
-            return VoiceCommand::ReadNotifications;
+        if voice_macro.actions.is_empty() || voice_macro.actions.len() > MAX_MACRO_ACTIONS {
+            return Err(format!("En makro skal have mellem 1 og {} handlinger", MAX_MACRO_ACTIONS));
         }
-
-        // Help
-        if self.matches_any(text, &[
-            "help", "what can you do", "options", "commands",
-            "what can i say", "show help"
-        ]) {
-            return VoiceCommand::Help;
+        if others.iter().any(|other| {
+            other.id != voice_macro.id
+                && command_grammar::match_phrase(&other.phrase, phrase).is_some_and(|s| s >= MIN_MACRO_CONFIDENCE)
+        }) {
+            return Err(format!("\"{}\" bruges allerede af en anden makro", phrase));
         }
 
-        // Cancel
-        if self.matches_any(text, &[
-            "cancel", "abort", "nevermind", "no", "forget it", "undo"
-        ]) {
-            return VoiceCommand::Cancel;
+        let now = chrono::Local::now().naive_local();
+        let builtin = self.grammar.parse(&phrase.to_lowercase(), now);
+        if builtin.is_some_and(|found| found.confidence >= 0.9) {
+            return Err(format!("\"{}\" er allerede en indbygget kommando", phrase));
         }
 
-        // Repeat
-        if self.matches_any(text, &[
-            "repeat", "say that again", "what did you say",
-            "once more", "again", "pardon"
-        ]) {
-            return VoiceCommand::Repeat;
+        for action in &voice_macro.actions {
+            match self.grammar.parse(&action.to_lowercase(), now).map(|found| found.command) {
+                None => return Err(format!("Handlingen \"{}\" blev ikke forstået", action)),
+                Some(VoiceCommand::Confirm | VoiceCommand::Cancel | VoiceCommand::Repeat) => {
+                    return Err(format!("Handlingen \"{}\" kan ikke bruges i en makro", action));
+                }
+                Some(VoiceCommand::Search { query }) if query.is_empty() => {
+                    return Err(format!("Handlingen \"{}\" mangler hvad der skal søges efter", action));
+                }
+                Some(VoiceCommand::CreateTask { description, .. }) if description.is_empty() => {
+                    return Err(format!("Handlingen \"{}\" mangler hvad opgaven skal hedde", action));
+                }
+                Some(_) => {}
+            }
         }
-
-        VoiceCommand::Unknown(text.to_string())
+        Ok(())
     }
 
-    /// Check if text matches any of the patterns
-    fn matches_any(&self, text: &str, patterns: &[&str]) -> bool {
-        patterns.iter().any(|p| {
-            text == *p || text.starts_with(&format!("{} ", p)) || text.contains(p)
-        })
-    }
+    /// Parse with dates resolved relative to `now`
+    async fn parse_at(&self, text: &str, now: NaiveDateTime) -> ParsedCommand {
+        let lower = text.to_lowercase().trim().to_string();
 
-    /// Extract text after matching prefix
-    fn extract_after(&self, text: &str, prefixes: &[&str]) -> Option<String> {
-        for prefix in prefixes {
-            if let Some(rest) = text.strip_prefix(prefix) {
-                let trimmed = rest.trim();
-                if !trimmed.is_empty() {
-                    return Some(trimmed.to_string());
+        let grammar = match self.grammar.parse(&lower, now) {
+            Some(found) => ParsedCommand {
+                command: found.command,
+                confidence: found.confidence,
+                source: ParseSource::Grammar,
+            },
+            None => ParsedCommand {
+                command: VoiceCommand::Unknown(lower.clone()),
+                confidence: 0.0,
+                source: ParseSource::Unrecognized,
+            },
+        };
+
+        let Some(classifier) = &self.classifier else {
+            return grammar;
+        };
+        match classifier.classify(&lower).await {
+            Some(prediction)
+                if prediction.confidence >= MIN_INTENT_CONFIDENCE && prediction.confidence > grammar.confidence =>
+            {
+                let command = Self::from_intent(&prediction.intent, &lower, grammar.command.clone());
+                if command.intent() != prediction.intent {
+                    return grammar;
                 }
-            }
-            // Also check if prefix is contained
-            if let Some(pos) = text.find(prefix) {
-                let rest = &text[pos + prefix.len()..];
-                let trimmed = rest.trim();
-                if !trimmed.is_empty() {
-                    return Some(trimmed.to_string());
+                ParsedCommand {
+                    command,
+                    confidence: prediction.confidence,
+                    source: ParseSource::IntentModel,
                 }
             }
+            _ => grammar,
+        }
+    }
+
+    /// Command for a predicted intent; slots come from the grammar when they agree
+    fn from_intent(intent: &str, text: &str, keyword: VoiceCommand) -> VoiceCommand {
+        if keyword.intent() == intent {
+            return keyword;
+        }
+        match intent {
+            "start_commander" => VoiceCommand::StartCommander,
+            "stop_commander" => VoiceCommand::StopCommander,
+            "get_status" => VoiceCommand::GetStatus,
+            "search" => VoiceCommand::Search { query: text.to_string() },
+            "create_task" => VoiceCommand::CreateTask {
+                description: text.to_string(),
+                priority: "normal".to_string(),
+                due: None,
+            },
+            "read_notifications" => VoiceCommand::ReadNotifications,
+            "privacy_on" => VoiceCommand::SetPrivacyMode { enabled: true },
+            "privacy_off" => VoiceCommand::SetPrivacyMode { enabled: false },
+            "help" => VoiceCommand::Help,
+            "confirm" => VoiceCommand::Confirm,
+            "cancel" => VoiceCommand::Cancel,
+            "repeat" => VoiceCommand::Repeat,
+            // Settings need a setting and value only the grammar can extract
+            _ => keyword,
         }
-        None
     }
 }
 
@@ -273,10 +316,199 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_privacy_mode_intents() {
+        let parser = CommandParser::new("da-DK");
+        assert_eq!(parser.parse("privat tilstand").await, VoiceCommand::SetPrivacyMode { enabled: true });
+        assert_eq!(
+            parser.parse("afslut privat tilstand").await,
+            VoiceCommand::SetPrivacyMode { enabled: false }
+        );
+
+        let parser = CommandParser::new("en-US");
+        assert_eq!(parser.parse("exit privacy mode").await, VoiceCommand::SetPrivacyMode { enabled: false });
+    }
+
+    #[tokio::test]
+    async fn test_settings_intents() {
+        let parser = CommandParser::new("da-DK");
+        assert_eq!(
+            parser.parse("sæt CPU-grænse til 50 procent").await,
+            VoiceCommand::SetSetting { setting: SettingKey::CpuLimit, value: 50 }
+        );
+        assert_eq!(
+            parser.parse("slå synkronisering fra").await,
+            VoiceCommand::ToggleSetting { setting: SettingToggle::Sync, enabled: false }
+        );
+        assert_eq!(
+            parser.parse("sæt inaktivitet til 2 minutter").await,
+            VoiceCommand::SetSetting { setting: SettingKey::IdleThreshold, value: 120 }
+        );
+
+        let parser = CommandParser::new("en-US");
+        assert_eq!(
+            parser.parse("set ram limit to forty percent").await,
+            VoiceCommand::SetSetting { setting: SettingKey::RamLimit, value: 40 }
+        );
+        assert_eq!(
+            parser.parse("turn on text recognition").await,
+            VoiceCommand::ToggleSetting { setting: SettingToggle::Ocr, enabled: true }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_confirm_command() {
+        let parser = CommandParser::new("da-DK");
+        assert_eq!(parser.parse("ja tak").await, VoiceCommand::Confirm);
+        assert_eq!(parser.parse("prøv igen").await, VoiceCommand::Confirm);
+        assert_eq!(parser.parse("nej").await, VoiceCommand::Cancel);
+    }
+
     #[tokio::test]
     async fn test_english_help_command() {
         let parser = CommandParser::new("en-US");
         assert_eq!(parser.parse("help").await, VoiceCommand::Help);
         assert_eq!(parser.parse("what can you do").await, VoiceCommand::Help);
     }
+
+    struct FixedClassifier(Option<IntentPrediction>);
+
+    #[async_trait::async_trait]
+    impl IntentClassifier for FixedClassifier {
+        async fn classify(&self, _text: &str) -> Option<IntentPrediction> {
+            self.0.clone()
+        }
+    }
+
+    fn classifier(intent: &str, confidence: f32) -> Arc<dyn IntentClassifier> {
+        Arc::new(FixedClassifier(Some(IntentPrediction {
+            intent: intent.to_string(),
+            confidence,
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_intent_model_overrides_rules() {
+        // Phrasing the keyword rules do not know
+        let parser = CommandParser::new("da-DK").with_classifier(classifier("start_commander", 0.93));
+        assert_eq!(parser.parse("få gang i butikken").await, VoiceCommand::StartCommander);
+
+        // Slots from the rules are kept when both agree
+        let parser = CommandParser::new("da-DK").with_classifier(classifier("search", 0.9));
+        assert_eq!(
+            parser.parse("søg efter vejret i morgen").await,
+            VoiceCommand::Search { query: "vejret i morgen".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_training_labels_are_known_intents() {
+        let commands = [
+            VoiceCommand::Unknown(String::new()),
+            VoiceCommand::StartCommander,
+            VoiceCommand::StopCommander,
+            VoiceCommand::GetStatus,
+            VoiceCommand::Search { query: String::new() },
+            VoiceCommand::CreateTask { description: String::new(), priority: String::new(), due: None },
+            VoiceCommand::ReadNotifications,
+            VoiceCommand::SetSetting { setting: SettingKey::CpuLimit, value: 0 },
+            VoiceCommand::ToggleSetting { setting: SettingToggle::Sync, enabled: true },
+            VoiceCommand::SetPrivacyMode { enabled: true },
+            VoiceCommand::SetPrivacyMode { enabled: false },
+            VoiceCommand::Help,
+            VoiceCommand::Confirm,
+            VoiceCommand::Cancel,
+            VoiceCommand::Repeat,
+        ];
+        let intents: Vec<&str> = commands.iter().map(VoiceCommand::intent).collect();
+        let labels: Vec<&str> = include_str!("../../../scripts/intent-data/labels.txt").split_whitespace().collect();
+        assert_eq!(labels, intents);
+    }
+
+    #[tokio::test]
+    async fn test_intent_fallback_to_rules() {
+        // Low confidence
+        let parser = CommandParser::new("da-DK").with_classifier(classifier("cancel", 0.4));
+        assert_eq!(parser.parse("start arbejde").await, VoiceCommand::StartCommander);
+
+        // No model loaded
+        let parser = CommandParser::new("da-DK").with_classifier(Arc::new(FixedClassifier(None)));
+        assert_eq!(parser.parse("begynd").await, VoiceCommand::StartCommander);
+
+        // Settings need slots only the grammar provides
+        let parser = CommandParser::new("da-DK").with_classifier(classifier("set_setting", 0.95));
+        assert_eq!(parser.parse("hvad sker der").await, VoiceCommand::GetStatus);
+    }
+
+    #[tokio::test]
+    async fn test_confidence_ranking() {
+        let now = chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap().and_hms_opt(10, 0, 0).unwrap();
+
+        let parser = CommandParser::new("da-DK");
+        let parsed = parser.parse_at("opret opgave ring til mor i morgen kl 9", now).await;
+        assert_eq!(parsed.source, ParseSource::Grammar);
+        assert!(parsed.confidence > 0.9);
+        match parsed.command {
+            VoiceCommand::CreateTask { description, due, .. } => {
+                assert_eq!(description, "ring til mor");
+                assert_eq!(due, now.date().succ_opt().unwrap().and_hms_opt(9, 0, 0));
+            }
+            other => panic!("Expected CreateTask, got {:?}", other),
+        }
+
+        let parsed = parser.parse_at("blomkål og kartofler", now).await;
+        assert_eq!(parsed.source, ParseSource::Unrecognized);
+        assert_eq!(parsed.confidence, 0.0);
+
+        // A loose grammar match loses to a confident intent model
+        let parser = CommandParser::new("da-DK").with_classifier(classifier("read_notifications", 0.8));
+        let parsed = parser.parse_at("kan du læse hvad der er kommet af beskeder", now).await;
+        assert_eq!(parsed.command, VoiceCommand::ReadNotifications);
+        assert_eq!(parsed.source, ParseSource::IntentModel);
+    }
+
+    fn morning_routine() -> VoiceMacro {
+        VoiceMacro {
+            id: uuid::Uuid::new_v4(),
+            phrase: "Morgenrutine".to_string(),
+            actions: vec!["synkroniser nu".to_string(), "læs notifikationer".to_string(), "start arbejde".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_macro_expansion() {
+        let now = chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap().and_hms_opt(10, 0, 0).unwrap();
+        let parser = CommandParser::new("da-DK");
+        let macros = vec![morning_routine()];
+
+        let parsed = parser.expand_macro("kør morgenrutinen", &macros, now).unwrap();
+        assert_eq!(parsed.source, ParseSource::Macro);
+        assert_eq!(
+            parsed.command,
+            VoiceCommand::RunMacro {
+                phrase: "Morgenrutine".to_string(),
+                commands: vec![VoiceCommand::SyncNow, VoiceCommand::ReadNotifications, VoiceCommand::StartCommander],
+            }
+        );
+        assert!(parser.expand_macro("vis status", &macros, now).is_none());
+    }
+
+    #[test]
+    fn test_macro_validation() {
+        let parser = CommandParser::new("da-DK");
+        let routine = morning_routine();
+        assert!(parser.validate_macro(&routine, &[routine.clone()]).is_ok());
+
+        let taken = VoiceMacro { id: uuid::Uuid::new_v4(), ..routine.clone() };
+        assert!(parser.validate_macro(&taken, &[routine.clone()]).unwrap_err().contains("anden makro"));
+
+        let builtin = VoiceMacro { phrase: "vis status".to_string(), ..routine.clone() };
+        assert!(parser.validate_macro(&builtin, &[]).unwrap_err().contains("indbygget"));
+
+        let unclear = VoiceMacro { actions: vec!["blomkål og kartofler".to_string()], ..routine.clone() };
+        assert!(parser.validate_macro(&unclear, &[]).unwrap_err().contains("ikke forstået"));
+
+        let incomplete = VoiceMacro { actions: vec!["søg".to_string()], ..routine };
+        assert!(parser.validate_macro(&incomplete, &[]).is_err());
+    }
 }
@@ -0,0 +1,310 @@
+// Dialogue Manager - Short-term conversation state for voice control
+// Asks for what a command is missing over several turns ("opret opgave" ->
+// "Hvad skal opgaven hedde?"), asks before destructive actions and forgets
+// the conversation when the user goes quiet
+
+use chrono::NaiveDateTime;
+use std::time::{Duration, Instant};
+
+use crate::accessibility::command_grammar;
+use crate::accessibility::command_parser::VoiceCommand;
+
+/// A question left unanswered this long is dropped
+pub const DIALOGUE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Answers to "Hvilken prioritet?" that mean normal
+const NORMAL_PRIORITY: &[&str] = &[
+    "normal", "normal prioritet", "almindelig", "lav", "lav prioritet", "ingen", "ligegyldigt",
+    "normal priority", "low", "low priority", "none", "doesn't matter",
+];
+
+/// What the conversation is waiting for
+#[derive(Debug, Clone, PartialEq)]
+enum Frame {
+    /// A task being described; asks for the description, then the priority
+    Task {
+        description: Option<String>,
+        priority: Option<String>,
+        due: Option<NaiveDateTime>,
+    },
+    /// A search waiting for what to search for
+    Search,
+    /// A destructive command waiting for yes or no
+    Confirm(VoiceCommand),
+}
+
+/// What to do with an utterance
+#[derive(Debug, Clone, PartialEq)]
+pub enum Turn {
+    /// Run the command
+    Execute(VoiceCommand),
+    /// Ask the user and listen for the answer
+    Ask(String),
+    /// Say this; the conversation is over
+    Say(String),
+}
+
+/// Keeps what was said in the last turns so follow-ups can refer to it
+pub struct DialogueManager {
+    frame: Option<Frame>,
+    last_turn: Instant,
+    timeout: Duration,
+}
+
+impl DialogueManager {
+    pub fn new() -> Self {
+        Self {
+            frame: None,
+            last_turn: Instant::now(),
+            timeout: DIALOGUE_TIMEOUT,
+        }
+    }
+
+    /// Whether a question is waiting for an answer
+    pub fn is_active(&self) -> bool {
+        self.frame.is_some() && self.last_turn.elapsed() < self.timeout
+    }
+
+    /// Forget the conversation
+    pub fn reset(&mut self) {
+        self.frame = None;
+    }
+
+    /// Decide what to do with an utterance; `command` is how the parser read it
+    pub fn handle(&mut self, text: &str, command: VoiceCommand, now: NaiveDateTime, is_danish: bool) -> Turn {
+        if !self.is_active() {
+            self.frame = None;
+        }
+        self.last_turn = Instant::now();
+
+        let command = match self.frame.take() {
+            Some(frame) => match self.answer(frame, text, command, now, is_danish) {
+                Ok(turn) => return turn,
+                // Not an answer: the user moved on to something else
+                Err(command) => command,
+            },
+            None => command,
+        };
+        self.start(command, is_danish)
+    }
+
+    // Internal: Continue the conversation with an answer, or give the command
+    // back if it is not one
+    fn answer(
+        &mut self,
+        frame: Frame,
+        text: &str,
+        command: VoiceCommand,
+        now: NaiveDateTime,
+        is_danish: bool,
+    ) -> Result<Turn, VoiceCommand> {
+        if command == VoiceCommand::Cancel {
+            return Ok(Turn::Say(if is_danish {
+                "Okay, jeg annullerer.".to_string()
+            } else {
+                "Okay, cancelled.".to_string()
+            }));
+        }
+
+        match frame {
+            Frame::Confirm(pending) => match command {
+                VoiceCommand::Confirm => Ok(Turn::Execute(pending)),
+                other => Err(other),
+            },
+            Frame::Search => match command {
+                VoiceCommand::Unknown(query) => Ok(Turn::Execute(VoiceCommand::Search { query })),
+                VoiceCommand::Search { query } if !query.is_empty() => Ok(Turn::Execute(VoiceCommand::Search { query })),
+                other => Err(other),
+            },
+            Frame::Task { mut description, mut priority, mut due } => {
+                match command {
+                    VoiceCommand::Unknown(_) => {
+                        let answer = text.to_lowercase();
+                        let answer = answer.trim().trim_end_matches(['.', '!']);
+                        if description.is_some() && NORMAL_PRIORITY.contains(&answer) {
+                            priority = Some("normal".to_string());
+                        } else {
+                            let details = command_grammar::task_details(answer, now);
+                            if let Some(level) = details.priority {
+                                priority = Some(level.to_string());
+                            }
+                            due = details.due.or(due);
+                            if description.is_none() && !details.description.is_empty() {
+                                description = Some(details.description);
+                            }
+                        }
+                    }
+                    // "opret opgave ring til mor" while being asked fills in the same task
+                    VoiceCommand::CreateTask { description: spoken, priority: spoken_priority, due: spoken_due } => {
+                        if spoken_priority != "normal" {
+                            priority = Some(spoken_priority);
+                        }
+                        due = spoken_due.or(due);
+                        if !spoken.is_empty() {
+                            description = Some(spoken);
+                        }
+                    }
+                    other => return Err(other),
+                }
+                Ok(self.next_task_step(description, priority, due, is_danish))
+            }
+        }
+    }
+
+    // Internal: Begin a conversation for a new command, or run it right away
+    fn start(&mut self, command: VoiceCommand, is_danish: bool) -> Turn {
+        match command {
+            VoiceCommand::CreateTask { description, priority, due } => {
+                // "normal" is what the grammar gives when no priority was said
+                let priority = (priority != "normal").then_some(priority);
+                let description = (!description.is_empty()).then_some(description);
+                self.next_task_step(description, priority, due, is_danish)
+            }
+            VoiceCommand::Search { query } if query.is_empty() => {
+                self.frame = Some(Frame::Search);
+                Turn::Ask(if is_danish {
+                    "Hvad vil du søge efter?".to_string()
+                } else {
+                    "What do you want to search for?".to_string()
+                })
+            }
+            command => match confirmation_prompt(&command, is_danish) {
+                Some(prompt) => {
+                    self.frame = Some(Frame::Confirm(command));
+                    Turn::Ask(prompt)
+                }
+                None => Turn::Execute(command),
+            },
+        }
+    }
+
+    // Internal: Ask for the next missing part of a task, or create it
+    fn next_task_step(
+        &mut self,
+        description: Option<String>,
+        priority: Option<String>,
+        due: Option<NaiveDateTime>,
+        is_danish: bool,
+    ) -> Turn {
+        match (description, priority) {
+            (Some(description), Some(priority)) => Turn::Execute(VoiceCommand::CreateTask { description, priority, due }),
+            (description, priority) => {
+                let question = match (description.is_none(), is_danish) {
+                    (true, true) => "Hvad skal opgaven hedde?",
+                    (true, false) => "What should the task be called?",
+                    (false, true) => "Hvilken prioritet skal opgaven have? Normal, høj eller kritisk?",
+                    (false, false) => "Which priority should the task have? Normal, high or critical?",
+                };
+                self.frame = Some(Frame::Task { description, priority, due });
+                Turn::Ask(question.to_string())
+            }
+        }
+    }
+}
+
+impl Default for DialogueManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Question to ask before a command that stops work or turns something off
+fn confirmation_prompt(command: &VoiceCommand, is_danish: bool) -> Option<String> {
+    let action = match command {
+        VoiceCommand::StopCommander => {
+            if is_danish {
+                "stoppe Commander Unit og sætte igangværende opgaver på pause".to_string()
+            } else {
+                "stop the Commander Unit and pause running tasks".to_string()
+            }
+        }
+        VoiceCommand::ToggleSetting { setting, enabled: false } => {
+            let name = setting.describe(is_danish).to_lowercase();
+            if is_danish {
+                format!("slå {} fra", name)
+            } else {
+                format!("turn off {}", name)
+            }
+        }
+        _ => return None,
+    };
+
+    Some(if is_danish {
+        format!("Er du sikker på, at du vil {}? Sig ja eller nej.", action)
+    } else {
+        format!("Are you sure you want to {}? Say yes or no.", action)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accessibility::CommandParser;
+
+    fn now() -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2026, 10, 17).unwrap().and_hms_opt(10, 0, 0).unwrap()
+    }
+
+    async fn say(dialogue: &mut DialogueManager, text: &str) -> Turn {
+        let command = CommandParser::new("da-DK").parse(text).await;
+        dialogue.handle(text, command, now(), true)
+    }
+
+    #[tokio::test]
+    async fn test_task_filled_over_several_turns() {
+        let mut dialogue = DialogueManager::new();
+
+        assert_eq!(say(&mut dialogue, "opret opgave").await, Turn::Ask("Hvad skal opgaven hedde?".to_string()));
+        // Priority given before the description is kept
+        assert!(matches!(say(&mut dialogue, "med høj prioritet").await, Turn::Ask(_)));
+        assert_eq!(
+            say(&mut dialogue, "ring til mor i morgen").await,
+            Turn::Execute(VoiceCommand::CreateTask {
+                description: "ring til mor".to_string(),
+                priority: "high".to_string(),
+                due: now().date().succ_opt().unwrap().and_hms_opt(9, 0, 0),
+            })
+        );
+        assert!(!dialogue.is_active());
+
+        // Without a spoken priority it is asked for
+        assert!(matches!(say(&mut dialogue, "opret opgave køb mælk").await, Turn::Ask(q) if q.starts_with("Hvilken prioritet")));
+        assert_eq!(
+            say(&mut dialogue, "normal").await,
+            Turn::Execute(VoiceCommand::CreateTask {
+                description: "køb mælk".to_string(),
+                priority: "normal".to_string(),
+                due: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_destructive_actions_are_confirmed() {
+        let mut dialogue = DialogueManager::new();
+        assert!(matches!(say(&mut dialogue, "stop arbejde").await, Turn::Ask(q) if q.starts_with("Er du sikker")));
+        assert_eq!(say(&mut dialogue, "ja").await, Turn::Execute(VoiceCommand::StopCommander));
+
+        assert!(matches!(say(&mut dialogue, "slå synkronisering fra").await, Turn::Ask(_)));
+        assert_eq!(say(&mut dialogue, "nej").await, Turn::Say("Okay, jeg annullerer.".to_string()));
+
+        // Another command instead of an answer drops the question
+        assert!(matches!(say(&mut dialogue, "stop").await, Turn::Ask(_)));
+        assert_eq!(say(&mut dialogue, "hvad er status").await, Turn::Execute(VoiceCommand::GetStatus));
+        assert!(!dialogue.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_question_times_out() {
+        let mut dialogue = DialogueManager::new();
+        dialogue.timeout = Duration::ZERO;
+        assert!(matches!(say(&mut dialogue, "søg").await, Turn::Ask(_)));
+        assert!(!dialogue.is_active());
+
+        // The late answer is read as a new command
+        assert_eq!(
+            say(&mut dialogue, "ja").await,
+            Turn::Execute(VoiceCommand::Confirm)
+        );
+    }
+}
@@ -0,0 +1,97 @@
+// Error Narration - Speaks errors and offers their recovery action verbally
+// Uses ClaError::user_message and recovery_action so narration matches the UI
+
+use crate::error::{ClaError, RecoveryAction};
+use futures_util::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+
+/// Callback that performs the offered recovery when the user says yes
+pub type RecoveryHandler = Box<dyn FnOnce() -> BoxFuture<'static, Result<String, String>> + Send + Sync>;
+
+/// What to say about an error
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorNarration {
+    /// Spoken description of the error
+    pub message: String,
+    /// Yes/no question offering the recovery, if one can be offered
+    pub prompt: Option<String>,
+}
+
+impl ErrorNarration {
+    /// Full text to speak
+    pub fn spoken_text(&self) -> String {
+        match &self.prompt {
+            Some(prompt) => format!("{} {}", self.message, prompt),
+            None => self.message.clone(),
+        }
+    }
+
+    /// Whether the user is asked to confirm a recovery
+    pub fn offers_recovery(&self) -> bool {
+        self.prompt.is_some()
+    }
+}
+
+/// Build the narration for an error
+pub fn narrate(error: &ClaError, is_danish: bool) -> ErrorNarration {
+    let message = if is_danish {
+        error.user_message()
+    } else {
+        error.to_string()
+    };
+
+    let prompt = match error.recovery_action() {
+        RecoveryAction::Retry { .. } => Some(if is_danish {
+            "Skal jeg prøve igen?".to_string()
+        } else {
+            "Should I try again?".to_string()
+        }),
+        RecoveryAction::UseFallback { fallback_type } => Some(if is_danish {
+            format!("Skal jeg bruge {} i stedet?", fallback_type)
+        } else {
+            format!("Should I use {} instead?", fallback_type)
+        }),
+        RecoveryAction::RequireUserAction { .. }
+        | RecoveryAction::Skip
+        | RecoveryAction::Fatal => None,
+    };
+
+    // Actions the user has to take themselves are appended to the message
+    let message = match error.recovery_action() {
+        RecoveryAction::RequireUserAction { message: action } if !message.contains(&action) => {
+            format!("{} {}", message, action)
+        }
+        RecoveryAction::Fatal => {
+            if is_danish {
+                format!("{} Genstart venligst programmet.", message)
+            } else {
+                format!("{} Please restart the application.", message)
+            }
+        }
+        _ => message,
+    };
+
+    ErrorNarration { message, prompt }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{NetworkError, ResourceError};
+
+    #[test]
+    fn test_retry_offered_in_danish() {
+        let error = ClaError::Network(NetworkError::Timeout { url: "https://ckc".to_string(), timeout_ms: 30000 });
+        let narration = narrate(&error, true);
+        assert_eq!(narration.prompt.as_deref(), Some("Skal jeg prøve igen?"));
+        assert!(narration.spoken_text().starts_with("Serveren svarer langsomt"));
+    }
+
+    #[test]
+    fn test_skip_offers_no_recovery() {
+        let error = ClaError::Resource(ResourceError::BatteryTooLow { current: 5, minimum: 20 });
+        let narration = narrate(&error, true);
+        assert!(!narration.offers_recovery());
+        assert!(narration.message.contains("5%"));
+    }
+}
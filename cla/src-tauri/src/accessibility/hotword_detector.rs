@@ -1,9 +1,71 @@
 // Hotword Detector - Wake word detection for hands-free activation
 // Listens for "Hej Cirkelline" or custom hotword
 
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::process::Command;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::audio_input::SAMPLE_RATE;
+use super::keyword_spotter::{KeywordSpotter, MIN_TEMPLATES};
+use super::audio_input::MicrophoneCapture;
+use crate::inference::{Vad, VadConfig, VadEvent};
+
+/// Audio kept for matching; longer than the longest hotword sample
+const RECENT_SAMPLES: usize = 3 * SAMPLE_RATE as usize;
+
+/// Match the recent audio this often while someone is speaking (200ms)
+const SCORE_INTERVAL_SAMPLES: usize = SAMPLE_RATE as usize / 5;
+
+/// Ignore audio this long after a detection so one hotword fires once
+const COOLDOWN_SAMPLES: usize = 2 * SAMPLE_RATE as usize;
+
+/// Speech scoring within this factor of the threshold counts as a near miss
+const NEAR_MISS_FACTOR: f32 = 1.25;
+
+/// How long after a detection the command recording decides if it was real
+const CONFIRM_WINDOW: Duration = Duration::from_secs(15);
+
+/// How well hotword detection is doing, for tuning the sensitivity
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HotwordStats {
+    /// Whether the microphone is being listened to
+    pub listening: bool,
+    /// Recordings of the hotword the detector matches against
+    pub enrolled_samples: usize,
+    /// Samples needed before detection starts
+    pub required_samples: usize,
+    pub sensitivity: f32,
+    /// Distance below which speech counts as the hotword
+    pub threshold: Option<f32>,
+    /// Closest match in the last stretch of speech
+    pub last_score: Option<f32>,
+    /// Stretches of speech matched against the hotword
+    pub segments_scored: u64,
+    pub detections: u64,
+    /// Detections followed by a spoken command
+    pub confirmed: u64,
+    /// Detections followed by silence
+    pub false_positives: u64,
+    /// Speech that came close to the threshold without passing it
+    pub near_misses: u64,
+    /// False positives per judged detection
+    pub false_positive_rate: f32,
+}
+
+#[derive(Default)]
+struct Counters {
+    last_score: Option<f32>,
+    segments_scored: u64,
+    detections: u64,
+    confirmed: u64,
+    false_positives: u64,
+    near_misses: u64,
+    /// Detection handed to the voice loop, waiting for the command recording
+    awaiting_command: Option<Instant>,
+}
 
 /// Hotword Detector for voice activation
 pub struct HotwordDetector {
@@ -11,16 +73,29 @@ pub struct HotwordDetector {
     is_listening: Arc<AtomicBool>,
     detected: Arc<AtomicBool>,
     sensitivity: f32,
+    device: String,
+    noise_suppression: bool,
+    spotter: Arc<RwLock<KeywordSpotter>>,
+    counters: Arc<Mutex<Counters>>,
+    /// Bumped on every start so a listener from before a restart exits
+    session: Arc<AtomicU64>,
 }
 
 impl HotwordDetector {
     /// Create new hotword detector
     pub fn new(hotword: &str) -> Self {
+        let hotword = hotword.to_lowercase();
+        let sensitivity = 0.5; // 0.0 = less sensitive, 1.0 = very sensitive
         Self {
-            hotword: hotword.to_lowercase(),
+            spotter: Arc::new(RwLock::new(KeywordSpotter::load(&hotword, sensitivity))),
+            hotword,
             is_listening: Arc::new(AtomicBool::new(false)),
             detected: Arc::new(AtomicBool::new(false)),
-            sensitivity: 0.5, // 0.0 = less sensitive, 1.0 = very sensitive
+            sensitivity,
+            device: super::audio_input::DEFAULT_DEVICE.to_string(),
+            noise_suppression: true,
+            counters: Arc::new(Mutex::new(Counters::default())),
+            session: Arc::new(AtomicU64::new(0)),
         }
     }
 
@@ -36,50 +111,112 @@ impl HotwordDetector {
             return Ok(());
         }
 
+        let device = (self.device != super::audio_input::DEFAULT_DEVICE).then(|| self.device.clone());
+        let (capture, mut audio_rx) = MicrophoneCapture::start(device)?;
+
+        if !self.spotter.read().map(|spotter| spotter.is_ready()).unwrap_or(false) {
+            log::warn!(
+                "Fewer than {} samples of '{}' enrolled; the hotword cannot be detected yet",
+                MIN_TEMPLATES,
+                self.hotword
+            );
+        }
+
         self.is_listening.store(true, Ordering::SeqCst);
         self.detected.store(false, Ordering::SeqCst);
+        let session = self.session.fetch_add(1, Ordering::SeqCst) + 1;
 
         let hotword = self.hotword.clone();
+        let noise_suppression = self.noise_suppression;
+        let sensitivity = self.sensitivity;
         let is_listening = self.is_listening.clone();
         let detected = self.detected.clone();
+        let spotter = self.spotter.clone();
+        let counters = self.counters.clone();
+        let current_session = self.session.clone();
 
         // Start background listening task
         tokio::spawn(async move {
+            // The microphone is released when the task ends
+            let _capture = capture;
             log::info!("Hotword detection started, listening for: '{}'", hotword);
 
-            while is_listening.load(Ordering::SeqCst) {
-                // Record short audio snippet (1 second)
-                let temp_path = format!("/tmp/hotword_{}.wav", uuid::Uuid::new_v4());
-
-                let record_result = Command::new("arecord")
-                    .args([
-                        "-f", "S16_LE",
-                        "-r", "16000",
-                        "-c", "1",
-                        "-d", "1",  // 1 second
-                        "-q",       // Quiet mode
-                        &temp_path,
-                    ])
-                    .output();
-
-                if let Ok(output) = record_result {
-                    if output.status.success() {
-                        // Simple voice activity detection
-                        // In production, use a proper hotword engine like Porcupine or Snowboy
-                        if Self::detect_voice_activity(&temp_path).await {
-                            // For now, assume any voice activity is the hotword
-                            // A real implementation would use ML-based hotword detection
-                            detected.store(true, Ordering::SeqCst);
-                            log::info!("Hotword detected!");
-                        }
+            let models_dir = crate::inference::default_models_dir();
+            let mut vad = Vad::load(models_dir.as_deref(), VadConfig::with_sensitivity(sensitivity));
+            let mut recent: VecDeque<f32> = VecDeque::with_capacity(RECENT_SAMPLES);
+            let mut since_score = 0;
+            let mut cooldown: usize = 0;
+            let mut segment_best: Option<f32> = None;
+
+            while is_listening.load(Ordering::SeqCst) && current_session.load(Ordering::SeqCst) == session {
+                let chunk = match tokio::time::timeout(Duration::from_millis(200), audio_rx.recv()).await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => {
+                        log::warn!("Microphone stopped, hotword detection ends");
+                        is_listening.store(false, Ordering::SeqCst);
+                        break;
                     }
+                    Err(_) => continue,
+                };
+
+                // Speech detection gates the matching, so silence costs almost nothing
+                let events = vad.push(&chunk);
+                recent.extend(&chunk);
+                let overflow = recent.len().saturating_sub(RECENT_SAMPLES);
+                recent.drain(..overflow);
+
+                if cooldown > 0 {
+                    cooldown = cooldown.saturating_sub(chunk.len());
+                    continue;
+                }
+                let ended = events.iter().any(|event| matches!(event, VadEvent::SpeechEnd { .. }));
+                if !vad.is_speaking() && !ended {
+                    continue;
+                }
+                since_score += chunk.len();
+                if since_score < SCORE_INTERVAL_SAMPLES && !ended {
+                    continue;
                 }
+                since_score = 0;
 
-                // Clean up
-                let _ = std::fs::remove_file(&temp_path);
+                let window: Vec<f32> = if noise_suppression {
+                    let pcm: Vec<i16> = recent.iter().map(|&s| (s.clamp(-1.0, 1.0) * 32767.0) as i16).collect();
+                    super::noise_suppression::NoiseSuppressor::new()
+                        .gate(&pcm)
+                        .iter()
+                        .map(|&s| s as f32 / 32768.0)
+                        .collect()
+                } else {
+                    recent.iter().copied().collect()
+                };
+                let (score, threshold) = match spotter.read() {
+                    Ok(spotter) => (spotter.score(&window), spotter.threshold()),
+                    Err(_) => break,
+                };
+                let (Some(score), Some(threshold)) = (score, threshold) else {
+                    continue;
+                };
+                segment_best = Some(segment_best.map_or(score, |best: f32| best.min(score)));
 
-                // Small delay to prevent CPU overuse
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                let hit = score < threshold;
+                if hit || ended {
+                    let mut counters = counters.lock().unwrap_or_else(|e| e.into_inner());
+                    counters.segments_scored += 1;
+                    counters.last_score = segment_best;
+                    if hit {
+                        counters.detections += 1;
+                    } else if score < threshold * NEAR_MISS_FACTOR {
+                        counters.near_misses += 1;
+                    }
+                    segment_best = None;
+                }
+                if hit {
+                    detected.store(true, Ordering::SeqCst);
+                    log::info!("Hotword detected (distance {:.2}, threshold {:.2})", score, threshold);
+                    cooldown = COOLDOWN_SAMPLES;
+                    recent.clear();
+                    vad.reset();
+                }
             }
         });
 
@@ -94,68 +231,110 @@ impl HotwordDetector {
 
     /// Check if hotword was detected (and reset flag)
     pub async fn detected(&self) -> bool {
-        self.detected.swap(false, Ordering::SeqCst)
+        let detected = self.detected.swap(false, Ordering::SeqCst);
+        if detected {
+            self.lock_counters().awaiting_command = Some(Instant::now());
+        }
+        detected
     }
 
-    /// Check if currently listening
-    pub fn is_listening(&self) -> bool {
-        self.is_listening.load(Ordering::SeqCst)
+    /// Record whether the recording after a detection heard a command; silence
+    /// means the detection was a false positive
+    pub fn record_outcome(&self, heard_command: bool) {
+        let mut counters = self.lock_counters();
+        let Some(detected_at) = counters.awaiting_command.take() else {
+            return;
+        };
+        if detected_at.elapsed() > CONFIRM_WINDOW {
+            return;
+        }
+        if heard_command {
+            counters.confirmed += 1;
+        } else {
+            counters.false_positives += 1;
+        }
     }
 
-    /// Set hotword
-    pub fn set_hotword(&mut self, hotword: &str) {
-        self.hotword = hotword.to_lowercase();
+    /// Add a recording of the hotword (16kHz mono) and save it
+    pub fn enroll(&self, samples: &[f32]) -> Result<usize, String> {
+        let mut spotter = self.spotter.write().map_err(|_| "Hotword-prøverne er utilgængelige")?;
+        let count = spotter.enroll(samples)?;
+        spotter.save()?;
+        log::info!("Enrolled hotword sample {} for '{}'", count, self.hotword);
+        Ok(count)
     }
 
-    /// Set sensitivity (0.0 - 1.0)
-    pub fn set_sensitivity(&mut self, sensitivity: f32) {
-        self.sensitivity = sensitivity.clamp(0.0, 1.0);
+    /// Forget the recordings of the hotword
+    pub fn clear_samples(&self) {
+        if let Ok(mut spotter) = self.spotter.write() {
+            spotter.clear();
+        }
     }
 
-    // Internal: Simple voice activity detection
-    async fn detect_voice_activity(audio_path: &str) -> bool {
-        // Read WAV file and check RMS energy
-        use std::fs::File;
-        use std::io::Read;
-
-        let mut file = match File::open(audio_path) {
-            Ok(f) => f,
-            Err(_) => return false,
-        };
-
-        // Skip WAV header (44 bytes for standard WAV)
-        let mut header = [0u8; 44];
-        if file.read_exact(&mut header).is_err() {
-            return false;
-        }
+    /// Detection statistics
+    pub fn stats(&self) -> HotwordStats {
+        let (enrolled_samples, threshold) = self
+            .spotter
+            .read()
+            .map(|spotter| (spotter.template_count(), spotter.threshold()))
+            .unwrap_or((0, None));
+        let counters = self.lock_counters();
+        let judged = counters.confirmed + counters.false_positives;
 
-        // Read audio samples
-        let mut samples = Vec::new();
-        if file.read_to_end(&mut samples).is_err() {
-            return false;
+        HotwordStats {
+            listening: self.is_listening(),
+            enrolled_samples,
+            required_samples: MIN_TEMPLATES,
+            sensitivity: self.sensitivity,
+            threshold,
+            last_score: counters.last_score,
+            segments_scored: counters.segments_scored,
+            detections: counters.detections,
+            confirmed: counters.confirmed,
+            false_positives: counters.false_positives,
+            near_misses: counters.near_misses,
+            false_positive_rate: if judged > 0 {
+                counters.false_positives as f32 / judged as f32
+            } else {
+                0.0
+            },
         }
+    }
 
-        // Convert bytes to i16 samples
-        let samples: Vec<i16> = samples
-            .chunks_exact(2)
-            .map(|chunk| i16::from_le_bytes([chunk[0], chunk[1]]))
-            .collect();
+    /// Check if currently listening
+    pub fn is_listening(&self) -> bool {
+        self.is_listening.load(Ordering::SeqCst)
+    }
 
-        if samples.is_empty() {
-            return false;
+    /// Set hotword (loads the samples recorded for it)
+    pub fn set_hotword(&mut self, hotword: &str) {
+        let hotword = hotword.to_lowercase();
+        if hotword != self.hotword {
+            self.spotter = Arc::new(RwLock::new(KeywordSpotter::load(&hotword, self.sensitivity)));
+            self.hotword = hotword;
         }
+    }
 
-        // Calculate RMS energy
-        let sum_squares: f64 = samples.iter()
-            .map(|&s| (s as f64).powi(2))
-            .sum();
-        let rms = (sum_squares / samples.len() as f64).sqrt();
+    /// Set input device (takes effect on next start)
+    pub fn set_device(&mut self, device: &str) {
+        self.device = device.to_string();
+    }
+
+    /// Enable or disable noise suppression (takes effect on next start)
+    pub fn set_noise_suppression(&mut self, enabled: bool) {
+        self.noise_suppression = enabled;
+    }
 
-        // Threshold for voice detection (adjust based on sensitivity)
-        // Typical speech is around 2000-10000 for 16-bit audio
-        let threshold = 500.0 + (1.0 - 0.5) * 2000.0; // sensitivity adjustment
+    /// Set sensitivity (0.0 - 1.0)
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity.clamp(0.0, 1.0);
+        if let Ok(mut spotter) = self.spotter.write() {
+            spotter.set_sensitivity(self.sensitivity);
+        }
+    }
 
-        rms > threshold
+    fn lock_counters(&self) -> std::sync::MutexGuard<'_, Counters> {
+        self.counters.lock().unwrap_or_else(|e| e.into_inner())
     }
 }
 
@@ -168,4 +347,24 @@ mod tests {
         let detector = HotwordDetector::new("Hej Cirkelline");
         assert_eq!(detector.hotword, "hej cirkelline");
     }
+
+    #[tokio::test]
+    async fn test_false_positive_statistics() {
+        let detector = HotwordDetector::new("Hej Cirkelline");
+
+        // A command after the hotword confirms it, silence does not
+        for heard in [true, false, false, true] {
+            detector.detected.store(true, Ordering::SeqCst);
+            assert!(detector.detected().await);
+            detector.record_outcome(heard);
+        }
+        // Manual listening without a detection is not judged
+        detector.record_outcome(false);
+
+        let stats = detector.stats();
+        assert_eq!(stats.confirmed, 2);
+        assert_eq!(stats.false_positives, 2);
+        assert!((stats.false_positive_rate - 0.5).abs() < 1e-6);
+        assert_eq!(stats.required_samples, MIN_TEMPLATES);
+    }
 }
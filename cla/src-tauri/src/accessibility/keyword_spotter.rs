@@ -0,0 +1,410 @@
+// Keyword Spotter - Recognises the hotword by comparing speech to recordings of it
+// The user enrolls a few samples of the hotword; live speech is turned into MFCC
+// features and matched against them with subsequence DTW, so the hotword is
+// found even when a command follows it without a pause
+
+use crate::storage::JournaledFile;
+use realfft::RealFftPlanner;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const SAMPLE_RATE: usize = 16000;
+
+/// 25ms analysis frames every 10ms
+const FRAME_LEN: usize = 400;
+const HOP: usize = 160;
+const N_FFT: usize = 512;
+const N_MELS: usize = 26;
+
+/// Cepstral coefficients kept; c0 (loudness) is left out
+pub const N_MFCC: usize = 12;
+
+/// Shortest and longest enrollment sample after trimming silence
+const MIN_SAMPLE_MS: usize = 300;
+const MAX_SAMPLE_MS: usize = 2500;
+
+/// Samples needed before the hotword can be detected
+pub const MIN_TEMPLATES: usize = 3;
+
+/// Samples kept per hotword; the oldest is replaced after this
+const MAX_TEMPLATES: usize = 10;
+
+/// 10ms frames quieter than this fraction of the loudest one count as silence
+const SILENCE_RATIO: f32 = 0.08;
+
+/// MFCC features of one audio clip, one row per 10ms frame
+pub type Features = Vec<[f32; N_MFCC]>;
+
+/// Matches speech against enrolled samples of a hotword
+pub struct KeywordSpotter {
+    hotword: String,
+    templates: Vec<Features>,
+    sensitivity: f32,
+    /// Mean distance between the enrolled samples
+    spread: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredTemplates {
+    hotword: String,
+    templates: Vec<Vec<f32>>,
+}
+
+impl KeywordSpotter {
+    pub fn new(hotword: &str, sensitivity: f32) -> Self {
+        Self {
+            hotword: hotword.to_string(),
+            templates: Vec::new(),
+            sensitivity: sensitivity.clamp(0.0, 1.0),
+            spread: None,
+        }
+    }
+
+    /// Spotter with the samples saved for `hotword`, if any
+    pub fn load(hotword: &str, sensitivity: f32) -> Self {
+        let mut spotter = Self::new(hotword, sensitivity);
+        let Some(path) = templates_path(hotword) else {
+            return spotter;
+        };
+        // A damaged file is moved aside and reported; the hotword is enrolled again
+        let stored = templates_file(path).load::<StoredTemplates>().ok().flatten();
+        if let Some(stored) = stored.filter(|stored| stored.hotword == hotword) {
+            spotter.templates = stored
+                .templates
+                .iter()
+                .map(|flat| {
+                    flat.chunks_exact(N_MFCC)
+                        .map(|row| row.try_into().unwrap_or([0.0; N_MFCC]))
+                        .collect()
+                })
+                .collect();
+            spotter.update_spread();
+            log::info!("Loaded {} hotword samples for '{}'", spotter.templates.len(), hotword);
+        }
+        spotter
+    }
+
+    /// Save the enrolled samples so they survive restarts
+    pub fn save(&self) -> Result<(), String> {
+        let path = templates_path(&self.hotword).ok_or("Ingen datamappe fundet")?;
+        let stored = StoredTemplates {
+            hotword: self.hotword.clone(),
+            templates: self
+                .templates
+                .iter()
+                .map(|features| features.iter().flatten().copied().collect())
+                .collect(),
+        };
+        let json = serde_json::to_vec(&stored).map_err(|e| e.to_string())?;
+        templates_file(path)
+            .write(&json)
+            .map_err(|e| format!("Kunne ikke gemme hotword-prøver: {}", e))
+    }
+
+    /// Add a recording of the hotword (16kHz mono, silence is trimmed)
+    pub fn enroll(&mut self, samples: &[f32]) -> Result<usize, String> {
+        let speech = trim_silence(samples);
+        let duration_ms = speech.len() * 1000 / SAMPLE_RATE;
+        if duration_ms < MIN_SAMPLE_MS {
+            return Err("Hotword-prøven er for kort".to_string());
+        }
+        if duration_ms > MAX_SAMPLE_MS {
+            return Err("Hotword-prøven er for lang - sig kun hotwordet".to_string());
+        }
+
+        if self.templates.len() >= MAX_TEMPLATES {
+            self.templates.remove(0);
+        }
+        self.templates.push(mfcc(speech));
+        self.update_spread();
+        Ok(self.templates.len())
+    }
+
+    /// Forget all enrolled samples
+    pub fn clear(&mut self) {
+        self.templates.clear();
+        self.spread = None;
+        if let Some(path) = templates_path(&self.hotword) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    /// Number of enrolled samples
+    pub fn template_count(&self) -> usize {
+        self.templates.len()
+    }
+
+    /// Whether enough samples are enrolled to detect the hotword
+    pub fn is_ready(&self) -> bool {
+        self.templates.len() >= MIN_TEMPLATES && self.spread.is_some()
+    }
+
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity.clamp(0.0, 1.0);
+    }
+
+    /// Distance below which speech counts as the hotword. More sensitive allows
+    /// speech further from the samples than they are from each other.
+    pub fn threshold(&self) -> Option<f32> {
+        self.spread
+            .filter(|_| self.is_ready())
+            .map(|spread| spread * (1.1 + 0.9 * self.sensitivity))
+    }
+
+    /// Lowest distance between the audio and an enrolled sample
+    pub fn score(&self, samples: &[f32]) -> Option<f32> {
+        if self.templates.is_empty() {
+            return None;
+        }
+        let speech = trim_silence(samples);
+        if speech.len() * 1000 / SAMPLE_RATE < MIN_SAMPLE_MS {
+            return None;
+        }
+        let features = mfcc(speech);
+        self.templates
+            .iter()
+            .map(|template| dtw_distance(template, &features))
+            .min_by(f32::total_cmp)
+    }
+
+    // Internal: Mean pairwise distance between the enrolled samples
+    fn update_spread(&mut self) {
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for (i, a) in self.templates.iter().enumerate() {
+            for b in &self.templates[i + 1..] {
+                total += (dtw_distance(a, b) + dtw_distance(b, a)) / 2.0;
+                pairs += 1;
+            }
+        }
+        // Identical samples would make the threshold zero
+        self.spread = (pairs > 0).then(|| (total / pairs as f32).max(0.5));
+    }
+}
+
+/// Where the samples of a hotword are saved
+fn templates_file(path: PathBuf) -> JournaledFile {
+    JournaledFile::new("hotword_templates", path)
+}
+
+fn templates_path(hotword: &str) -> Option<PathBuf> {
+    let slug: String = hotword
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    Some(
+        dirs::data_dir()?
+            .join("cirkelline-cla")
+            .join("hotword")
+            .join(format!("{}.json", slug)),
+    )
+}
+
+/// Cut leading and trailing silence, relative to the loudest 10ms
+pub fn trim_silence(samples: &[f32]) -> &[f32] {
+    let energies: Vec<f32> = samples
+        .chunks(HOP)
+        .map(|chunk| (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt())
+        .collect();
+    let loudest = energies.iter().copied().fold(0.0f32, f32::max);
+    if loudest <= 0.0 {
+        return &[];
+    }
+    let floor = loudest * SILENCE_RATIO;
+    let first = energies.iter().position(|&e| e >= floor).unwrap_or(0);
+    let last = energies.iter().rposition(|&e| e >= floor).unwrap_or(0);
+    &samples[first * HOP..((last + 1) * HOP).min(samples.len())]
+}
+
+/// Mel-frequency cepstral coefficients of 16kHz audio
+pub fn mfcc(samples: &[f32]) -> Features {
+    if samples.len() < FRAME_LEN {
+        return Vec::new();
+    }
+
+    let fft = RealFftPlanner::<f32>::new().plan_fft_forward(N_FFT);
+    let mut input = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| 0.54 - 0.46 * (2.0 * std::f32::consts::PI * i as f32 / (FRAME_LEN - 1) as f32).cos())
+        .collect();
+    let filters = mel_filters();
+
+    let frame_count = (samples.len() - FRAME_LEN) / HOP + 1;
+    let mut features = Vec::with_capacity(frame_count);
+    let mut log_mel = [0.0f32; N_MELS];
+
+    for f in 0..frame_count {
+        let frame = &samples[f * HOP..f * HOP + FRAME_LEN];
+        // Pre-emphasis lifts the high frequencies consonants live in
+        input.fill(0.0);
+        input[0] = frame[0] * window[0];
+        for i in 1..FRAME_LEN {
+            input[i] = (frame[i] - 0.97 * frame[i - 1]) * window[i];
+        }
+        if fft.process(&mut input, &mut spectrum).is_err() {
+            break;
+        }
+
+        for (value, filter) in log_mel.iter_mut().zip(filters.iter()) {
+            let energy: f32 = filter.iter().zip(&spectrum).map(|(w, bin)| w * bin.norm_sqr()).sum();
+            *value = energy.max(1e-10).ln();
+        }
+
+        // DCT-II of the log mel energies, skipping c0
+        let mut row = [0.0f32; N_MFCC];
+        for (k, coefficient) in row.iter_mut().enumerate() {
+            let k = k + 1;
+            *coefficient = log_mel
+                .iter()
+                .enumerate()
+                .map(|(m, &e)| e * (std::f32::consts::PI * k as f32 * (m as f32 + 0.5) / N_MELS as f32).cos())
+                .sum::<f32>()
+                * (2.0 / N_MELS as f32).sqrt();
+        }
+        features.push(row);
+    }
+
+    features
+}
+
+/// Triangular filters on the HTK mel scale over the FFT bins
+fn mel_filters() -> Vec<Vec<f32>> {
+    let hz_to_mel = |hz: f32| 2595.0 * (1.0 + hz / 700.0).log10();
+    let mel_to_hz = |mel: f32| 700.0 * (10f32.powf(mel / 2595.0) - 1.0);
+    let bins = N_FFT / 2 + 1;
+    let low = hz_to_mel(64.0);
+    let high = hz_to_mel(SAMPLE_RATE as f32 / 2.0);
+    let edges: Vec<f32> = (0..N_MELS + 2)
+        .map(|i| mel_to_hz(low + (high - low) * i as f32 / (N_MELS + 1) as f32) * N_FFT as f32 / SAMPLE_RATE as f32)
+        .collect();
+
+    (0..N_MELS)
+        .map(|m| {
+            let (left, center, right) = (edges[m], edges[m + 1], edges[m + 2]);
+            (0..bins)
+                .map(|bin| {
+                    let bin = bin as f32;
+                    if bin <= left || bin >= right {
+                        0.0
+                    } else if bin <= center {
+                        (bin - left) / (center - left)
+                    } else {
+                        (right - bin) / (right - center)
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Distance of the best match of `template` anywhere in `features`: subsequence
+/// DTW with a free start and end in `features`, normalised by path length
+pub fn dtw_distance(template: &[[f32; N_MFCC]], features: &[[f32; N_MFCC]]) -> f32 {
+    let (n, m) = (template.len(), features.len());
+    if n == 0 || m == 0 {
+        return f32::INFINITY;
+    }
+
+    let distance = |a: &[f32; N_MFCC], b: &[f32; N_MFCC]| {
+        a.iter().zip(b).map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt()
+    };
+
+    // Accumulated cost and path length for the previous and current template frame
+    let mut prev: Vec<(f32, u32)> = features.iter().map(|f| (distance(&template[0], f), 1)).collect();
+    let mut current = vec![(0.0f32, 0u32); m];
+
+    for t in &template[1..] {
+        for j in 0..m {
+            let cost = distance(t, &features[j]);
+            let mut best = prev[j];
+            if j > 0 {
+                for candidate in [prev[j - 1], current[j - 1]] {
+                    if candidate.0 < best.0 {
+                        best = candidate;
+                    }
+                }
+            }
+            current[j] = (best.0 + cost, best.1 + 1);
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+
+    prev.iter()
+        .map(|&(cost, steps)| cost / steps as f32)
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake "word": tones at the given frequencies one after another
+    fn word(tones: &[f32], tone_ms: usize, seed: u32) -> Vec<f32> {
+        let mut state = seed;
+        let mut samples = Vec::new();
+        for &freq in tones {
+            for i in 0..tone_ms * SAMPLE_RATE / 1000 {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                let noise = ((state >> 16) as f32 / 32768.0 - 1.0) * 0.01;
+                let t = i as f32 / SAMPLE_RATE as f32;
+                samples.push(0.4 * (2.0 * std::f32::consts::PI * freq * t).sin() + noise);
+            }
+        }
+        samples
+    }
+
+    fn padded(samples: Vec<f32>) -> Vec<f32> {
+        let mut padded = vec![0.0; SAMPLE_RATE / 4];
+        padded.extend(samples);
+        padded.extend(vec![0.0; SAMPLE_RATE / 4]);
+        padded
+    }
+
+    #[test]
+    fn test_trim_silence() {
+        let speech = padded(word(&[440.0], 500, 1));
+        let trimmed = trim_silence(&speech);
+        let ms = trimmed.len() * 1000 / SAMPLE_RATE;
+        assert!((490..=520).contains(&ms), "trimmed to {}ms", ms);
+        assert!(trim_silence(&[0.0; 1600]).is_empty());
+    }
+
+    #[test]
+    fn test_dtw_finds_template_inside_longer_speech() {
+        let hotword = mfcc(&word(&[300.0, 900.0, 1800.0], 200, 1));
+        assert!(dtw_distance(&hotword, &hotword) < 1e-3);
+
+        // Hotword followed by a command
+        let mut sentence = word(&[300.0, 900.0, 1800.0], 210, 2);
+        sentence.extend(word(&[600.0, 2500.0, 1200.0], 200, 3));
+        let other = word(&[1800.0, 600.0, 2500.0], 200, 4);
+
+        let matched = dtw_distance(&hotword, &mfcc(&sentence));
+        let unmatched = dtw_distance(&hotword, &mfcc(&other));
+        assert!(matched * 2.0 < unmatched, "{} vs {}", matched, unmatched);
+    }
+
+    #[test]
+    fn test_spotter_needs_enrollment_and_rejects_other_words() {
+        let tones = [300.0, 900.0, 1800.0];
+        let mut spotter = KeywordSpotter::new("hej cirkelline", 0.5);
+        assert!(spotter.threshold().is_none());
+        assert!(spotter.enroll(&word(&tones, 50, 1)).is_err());
+
+        for (seed, tone_ms) in [(1, 190), (2, 200), (3, 215)] {
+            spotter.enroll(&padded(word(&tones, tone_ms, seed))).unwrap();
+        }
+        assert!(spotter.is_ready());
+        let threshold = spotter.threshold().unwrap();
+
+        let spoken = spotter.score(&padded(word(&tones, 205, 9))).unwrap();
+        let other = spotter.score(&padded(word(&[2500.0, 600.0, 1200.0], 200, 9))).unwrap();
+        assert!(spoken < threshold, "{} >= {}", spoken, threshold);
+        assert!(other > threshold, "{} <= {}", other, threshold);
+
+        // Less sensitive means a stricter threshold
+        spotter.set_sensitivity(0.0);
+        assert!(spotter.threshold().unwrap() < threshold);
+    }
+}
@@ -7,17 +7,29 @@
 // - Text-to-speech via espeak-ng
 // - Natural language command parsing (Danish/English)
 // - Continuous listening mode
-// - Sound feedback
+// - Multi-turn dialogue with follow-up questions
+// - Screen reader narration of pages, focus and notifications
+// - Sound feedback with earcon themes
 
 pub mod voice_controller;
 pub mod speech_synthesis;
 pub mod hotword_detector;
+pub mod keyword_spotter;
 pub mod command_parser;
+pub mod command_grammar;
+pub mod dialogue;
+pub mod narration;
+pub mod audio_input;
+pub mod noise_suppression;
+pub mod error_narration;
+pub mod voice_settings;
+pub mod streaming;
 
 pub use voice_controller::VoiceController;
-pub use speech_synthesis::SpeechSynthesizer;
-pub use hotword_detector::HotwordDetector;
-pub use command_parser::{CommandParser, VoiceCommand};
+pub use speech_synthesis::{SoundCue, SoundCueConfig, SoundTheme, SpeechSynthesizer};
+pub use hotword_detector::{HotwordDetector, HotwordStats};
+pub use command_parser::{CommandParser, IntentClassifier, IntentPrediction, ParsedCommand, VoiceCommand};
+pub use narration::{NarrationVerbosity, Narrator, UiEvent, UiNarration};
 
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +40,9 @@ pub struct AccessibilityConfig {
     pub voice_enabled: bool,
     /// Hotword phrase to activate listening (default: "Hej Cirkelline")
     pub hotword: String,
+    /// How readily speech counts as the hotword (0.0 = strict, 1.0 = lenient)
+    #[serde(default = "default_hotword_sensitivity")]
+    pub hotword_sensitivity: f32,
     /// Language code (e.g., "da-DK", "en-US")
     pub language: String,
     /// Speech rate (0.5 = slow, 1.0 = normal, 2.0 = fast)
@@ -36,14 +51,34 @@ pub struct AccessibilityConfig {
     pub auto_speak_responses: bool,
     /// Continuously listen for hotword
     pub continuous_listening: bool,
-    /// Screen reader friendly mode
+    /// Screen reader friendly mode: pages, focus moves and notifications are read aloud
     pub screen_reader_mode: bool,
+    /// How much is said about pages and focused elements
+    #[serde(default)]
+    pub narration_verbosity: NarrationVerbosity,
     /// High contrast UI
     pub high_contrast: bool,
     /// Large text mode
     pub large_text: bool,
     /// Play sound feedback for actions
     pub sound_feedback: bool,
+    /// Selected microphone (None = system default)
+    #[serde(default)]
+    pub input_device: Option<String>,
+    /// Apply spectral noise suppression to captured audio
+    #[serde(default = "default_noise_suppression")]
+    pub noise_suppression: bool,
+    /// Per-cue volume and enable flags
+    #[serde(default)]
+    pub sound_cues: SoundCueConfig,
+}
+
+fn default_noise_suppression() -> bool {
+    true
+}
+
+fn default_hotword_sensitivity() -> f32 {
+    0.5
 }
 
 impl Default for AccessibilityConfig {
@@ -51,14 +86,19 @@ impl Default for AccessibilityConfig {
         Self {
             voice_enabled: true,
             hotword: "Hej Cirkelline".to_string(),
+            hotword_sensitivity: default_hotword_sensitivity(),
             language: "da-DK".to_string(),
             speech_rate: 1.0,
             auto_speak_responses: true,
             continuous_listening: true,
             screen_reader_mode: false,
+            narration_verbosity: NarrationVerbosity::default(),
             high_contrast: false,
             large_text: false,
             sound_feedback: true,
+            input_device: None,
+            noise_suppression: true,
+            sound_cues: SoundCueConfig::default(),
         }
     }
 }
@@ -99,10 +139,20 @@ pub enum AccessibilityEvent {
     SpeakingFinished,
     /// Command processed
     CommandProcessed { input: String, response: String },
+    /// A follow-up question was asked; the next utterance answers it
+    AwaitingAnswer { question: String },
     /// State changed
     StateChanged { state: VoiceState },
     /// Error occurred
     Error { message: String },
+    /// Error was narrated, optionally offering a recovery
+    ErrorNarrated { message: String, recovery_offered: bool },
+    /// User accepted the offered recovery (frontend performs it if no handler was given)
+    RecoveryAccepted,
+    /// Microphone input level while listening
+    InputLevel { level: audio_input::InputLevel },
+    /// Input device changed (fallback = selected device disappeared)
+    InputDeviceChanged { device: String, fallback: bool },
 }
 
 #[cfg(test)]
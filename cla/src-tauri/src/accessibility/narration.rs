@@ -0,0 +1,430 @@
+// UI Narration - Screen reader feedback for the interface
+// The frontend reports page changes, focus moves and announcements; they are
+// turned into short descriptions ("Gem, knap"), sent back as structured events
+// for live regions and, in screen reader mode, spoken aloud
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+use crate::notifications::{Notification, NotificationCategory};
+
+/// The same narration reported again within this window is dropped
+/// (re-renders often report one focus move twice)
+const REPEAT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Pages of the app: route, Danish name, English name
+const PAGES: &[(&str, &str, &str)] = &[
+    ("/", "Status", "Status"),
+    ("/commander", "Commander", "Commander"),
+    ("/sync", "Synkronisering", "Sync"),
+    ("/models", "Modeller", "Models"),
+    ("/settings", "Indstillinger", "Settings"),
+];
+
+/// ARIA roles read aloud: role, Danish, English
+const ROLES: &[(&str, &str, &str)] = &[
+    ("button", "knap", "button"),
+    ("link", "link", "link"),
+    ("checkbox", "afkrydsningsfelt", "checkbox"),
+    ("switch", "kontakt", "switch"),
+    ("textbox", "tekstfelt", "text field"),
+    ("searchbox", "søgefelt", "search field"),
+    ("slider", "skyder", "slider"),
+    ("combobox", "rulleliste", "combo box"),
+    ("tab", "fane", "tab"),
+    ("menuitem", "menupunkt", "menu item"),
+    ("option", "valgmulighed", "option"),
+    ("heading", "overskrift", "heading"),
+    ("listitem", "listeelement", "list item"),
+    ("dialog", "dialog", "dialog"),
+];
+
+/// Element states read aloud: state, Danish, English
+const STATES: &[(&str, &str, &str)] = &[
+    ("checked", "markeret", "checked"),
+    ("unchecked", "ikke markeret", "not checked"),
+    ("expanded", "udvidet", "expanded"),
+    ("collapsed", "foldet sammen", "collapsed"),
+    ("selected", "valgt", "selected"),
+    ("pressed", "trykket ned", "pressed"),
+    ("disabled", "deaktiveret", "unavailable"),
+    ("required", "påkrævet", "required"),
+    ("invalid", "ugyldig", "invalid"),
+];
+
+/// How much is said about each element
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NarrationVerbosity {
+    /// Name, role, value and state
+    Brief,
+    /// Also position in lists, help text and page summaries
+    #[default]
+    Detailed,
+}
+
+/// Whether a narration may interrupt (maps to aria-live)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NarrationPriority {
+    Polite,
+    Assertive,
+}
+
+/// What a narration describes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NarrationKind {
+    Page,
+    Focus,
+    Notification,
+    Announcement,
+}
+
+/// An element that received focus, as reported by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiElement {
+    /// ARIA role, e.g. "button" or "checkbox"
+    pub role: String,
+    /// Accessible name
+    pub label: String,
+    /// Current value of text fields, sliders and lists
+    #[serde(default)]
+    pub value: Option<String>,
+    /// ARIA states, e.g. "checked", "expanded", "disabled"
+    #[serde(default)]
+    pub states: Vec<String>,
+    /// Position in its list or menu (1-based)
+    #[serde(default)]
+    pub position: Option<usize>,
+    /// Number of items in its list or menu
+    #[serde(default)]
+    pub set_size: Option<usize>,
+    /// Help text (aria-describedby)
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// Something that changed in the UI, reported by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UiEvent {
+    /// Navigated to another page
+    PageChanged {
+        page: String,
+        /// Heading shown on the page, for pages not known here
+        #[serde(default)]
+        title: Option<String>,
+        /// Short summary of what the page shows ("3 modeller, 1 indlæst")
+        #[serde(default)]
+        summary: Option<String>,
+    },
+    /// Focus moved to an element
+    FocusChanged { element: UiElement },
+    /// A status message the user should hear ("Indstillinger gemt")
+    Announcement {
+        text: String,
+        #[serde(default)]
+        assertive: bool,
+    },
+}
+
+/// Description of the UI for the user, emitted to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UiNarration {
+    pub kind: NarrationKind,
+    pub text: String,
+    pub priority: NarrationPriority,
+    /// Page the user is on
+    pub page: Option<String>,
+    /// The focused element, for focus narrations
+    pub element: Option<UiElement>,
+    /// Whether it is read aloud (screen reader mode)
+    pub spoken: bool,
+}
+
+/// Keeps track of where the user is and turns UI events into narrations
+pub struct Narrator {
+    event_tx: broadcast::Sender<UiNarration>,
+    page: Mutex<Option<String>>,
+    last: Mutex<Option<(String, Instant)>>,
+    latest: AtomicU64,
+}
+
+impl Narrator {
+    pub fn new() -> Self {
+        let (event_tx, _) = broadcast::channel(100);
+        Self {
+            event_tx,
+            page: Mutex::new(None),
+            last: Mutex::new(None),
+            latest: AtomicU64::new(0),
+        }
+    }
+
+    /// Subscribe to narrations
+    pub fn subscribe(&self) -> broadcast::Receiver<UiNarration> {
+        self.event_tx.subscribe()
+    }
+
+    /// Page the user is on
+    pub fn current_page(&self) -> Option<String> {
+        self.page.lock().unwrap().clone()
+    }
+
+    /// Describe a UI event; None if there is nothing new to say
+    pub fn describe(&self, event: UiEvent, verbosity: NarrationVerbosity, is_danish: bool) -> Option<UiNarration> {
+        let (kind, text, priority, element) = match event {
+            UiEvent::PageChanged { page, title, summary } => {
+                {
+                    let mut current = self.page.lock().unwrap();
+                    if current.as_deref() == Some(page.as_str()) {
+                        return None;
+                    }
+                    *current = Some(page.clone());
+                }
+                let text = describe_page(&page, title.as_deref(), summary.as_deref(), verbosity, is_danish);
+                (NarrationKind::Page, text, NarrationPriority::Assertive, None)
+            }
+            UiEvent::FocusChanged { element } => {
+                let text = describe_element(&element, verbosity, is_danish);
+                (NarrationKind::Focus, text, NarrationPriority::Polite, Some(element))
+            }
+            UiEvent::Announcement { text, assertive } => {
+                let priority = if assertive { NarrationPriority::Assertive } else { NarrationPriority::Polite };
+                (NarrationKind::Announcement, text.trim().to_string(), priority, None)
+            }
+        };
+
+        if text.is_empty() || self.is_repeat(&text) {
+            return None;
+        }
+        Some(UiNarration {
+            kind,
+            text,
+            priority,
+            page: self.current_page(),
+            element,
+            spoken: false,
+        })
+    }
+
+    /// Summarise a new notification
+    pub fn notification(
+        &self,
+        notification: &Notification,
+        unread: usize,
+        verbosity: NarrationVerbosity,
+        is_danish: bool,
+    ) -> UiNarration {
+        let mut text = if is_danish {
+            format!("Ny notifikation: {}.", notification.title.trim_end_matches('.'))
+        } else {
+            format!("New notification: {}.", notification.title.trim_end_matches('.'))
+        };
+        if verbosity == NarrationVerbosity::Detailed {
+            if !notification.body.is_empty() {
+                text.push_str(&format!(" {}.", notification.body.trim_end_matches('.')));
+            }
+            if unread > 1 {
+                text.push_str(&if is_danish {
+                    format!(" {} ulæste.", unread)
+                } else {
+                    format!(" {} unread.", unread)
+                });
+            }
+        }
+
+        UiNarration {
+            kind: NarrationKind::Notification,
+            text,
+            // Approvals wait for the user; the rest can wait for a pause
+            priority: if notification.category == NotificationCategory::Approval {
+                NarrationPriority::Assertive
+            } else {
+                NarrationPriority::Polite
+            },
+            page: self.current_page(),
+            element: None,
+            spoken: false,
+        }
+    }
+
+    /// Send a narration to the frontend; returns its ticket for speaking
+    pub fn emit(&self, narration: &UiNarration) -> u64 {
+        let _ = self.event_tx.send(narration.clone());
+        self.latest.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether nothing has been narrated since the ticket was given out
+    pub fn is_latest(&self, ticket: u64) -> bool {
+        self.latest.load(Ordering::SeqCst) == ticket
+    }
+
+    // Internal: Whether the same text was just narrated
+    fn is_repeat(&self, text: &str) -> bool {
+        let mut last = self.last.lock().unwrap();
+        let repeat = last
+            .as_ref()
+            .is_some_and(|(previous, at)| previous == text && at.elapsed() < REPEAT_WINDOW);
+        *last = Some((text.to_string(), Instant::now()));
+        repeat
+    }
+}
+
+impl Default for Narrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// "Side: Modeller. 3 modeller, 1 indlæst."
+pub fn describe_page(
+    page: &str,
+    title: Option<&str>,
+    summary: Option<&str>,
+    verbosity: NarrationVerbosity,
+    is_danish: bool,
+) -> String {
+    let name = PAGES
+        .iter()
+        .find(|(route, _, _)| *route == page)
+        .map(|(_, da, en)| if is_danish { *da } else { *en })
+        .or(title)
+        .unwrap_or(page);
+
+    let mut text = if is_danish { format!("Side: {}.", name) } else { format!("Page: {}.", name) };
+    if verbosity == NarrationVerbosity::Detailed {
+        if let Some(summary) = summary.map(str::trim).filter(|s| !s.is_empty()) {
+            text.push_str(&format!(" {}.", summary.trim_end_matches('.')));
+        }
+    }
+    text
+}
+
+/// "Støjreduktion, kontakt, markeret" or "Models, tab, 2 of 5"
+pub fn describe_element(element: &UiElement, verbosity: NarrationVerbosity, is_danish: bool) -> String {
+    let role = lookup(ROLES, &element.role, is_danish);
+    let mut parts = Vec::new();
+
+    let label = element.label.trim();
+    if label.is_empty() {
+        parts.push(if is_danish { format!("Unavngivet {}", role) } else { format!("Unlabelled {}", role) });
+    } else {
+        parts.push(label.to_string());
+        if !role.is_empty() {
+            parts.push(role);
+        }
+    }
+    if let Some(value) = element.value.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        parts.push(value.to_string());
+    }
+    parts.extend(element.states.iter().map(|state| lookup(STATES, state, is_danish)));
+
+    if verbosity == NarrationVerbosity::Detailed {
+        if let (Some(position), Some(size)) = (element.position, element.set_size) {
+            parts.push(if is_danish {
+                format!("{} af {}", position, size)
+            } else {
+                format!("{} of {}", position, size)
+            });
+        }
+    }
+
+    let mut text = parts.join(", ");
+    if verbosity == NarrationVerbosity::Detailed {
+        if let Some(description) = element.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            text.push_str(&format!(". {}", description));
+        }
+    }
+    text
+}
+
+// Internal: Translate a role or state; unknown ones are read as given
+fn lookup(table: &[(&str, &str, &str)], key: &str, is_danish: bool) -> String {
+    table
+        .iter()
+        .find(|(k, _, _)| k.eq_ignore_ascii_case(key))
+        .map(|(_, da, en)| if is_danish { *da } else { *en })
+        .unwrap_or(key)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn switch(label: &str) -> UiElement {
+        UiElement {
+            role: "switch".to_string(),
+            label: label.to_string(),
+            value: None,
+            states: vec!["checked".to_string()],
+            position: Some(2),
+            set_size: Some(5),
+            description: Some("Fjerner baggrundsstøj fra mikrofonen".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_describe_element() {
+        let element = switch("Støjreduktion");
+        assert_eq!(
+            describe_element(&element, NarrationVerbosity::Detailed, true),
+            "Støjreduktion, kontakt, markeret, 2 af 5. Fjerner baggrundsstøj fra mikrofonen"
+        );
+        assert_eq!(
+            describe_element(&element, NarrationVerbosity::Brief, false),
+            "Støjreduktion, switch, checked"
+        );
+
+        let unnamed = UiElement { label: String::new(), role: "button".to_string(), ..element };
+        assert!(describe_element(&unnamed, NarrationVerbosity::Brief, true).starts_with("Unavngivet knap"));
+    }
+
+    #[test]
+    fn test_page_and_repeats_are_narrated_once() {
+        let narrator = Narrator::new();
+        let page = |page: &str| UiEvent::PageChanged {
+            page: page.to_string(),
+            title: None,
+            summary: Some("3 modeller, 1 indlæst".to_string()),
+        };
+
+        let narration = narrator.describe(page("/models"), NarrationVerbosity::Detailed, true).unwrap();
+        assert_eq!(narration.text, "Side: Modeller. 3 modeller, 1 indlæst.");
+        assert_eq!(narration.page.as_deref(), Some("/models"));
+        assert!(narrator.describe(page("/models"), NarrationVerbosity::Detailed, true).is_none());
+
+        let focus = UiEvent::FocusChanged { element: switch("Støjreduktion") };
+        let narration = narrator.describe(focus.clone(), NarrationVerbosity::Brief, true).unwrap();
+        assert_eq!(narration.kind, NarrationKind::Focus);
+        assert_eq!(narration.page.as_deref(), Some("/models"));
+        assert!(narrator.describe(focus, NarrationVerbosity::Brief, true).is_none());
+    }
+
+    #[test]
+    fn test_notification_summary() {
+        let narrator = Narrator::new();
+        let notification = Notification {
+            id: "1".to_string(),
+            created_at: chrono::Utc::now(),
+            category: NotificationCategory::Approval,
+            title: "Godkendelse".to_string(),
+            body: "En beslutning venter.".to_string(),
+            read: false,
+            actions: Vec::new(),
+        };
+
+        let narration = narrator.notification(&notification, 3, NarrationVerbosity::Detailed, true);
+        assert_eq!(narration.text, "Ny notifikation: Godkendelse. En beslutning venter. 3 ulæste.");
+        assert_eq!(narration.priority, NarrationPriority::Assertive);
+        assert_eq!(
+            narrator.notification(&notification, 3, NarrationVerbosity::Brief, false).text,
+            "New notification: Godkendelse."
+        );
+    }
+}
@@ -1,10 +1,186 @@
 // Speech Synthesis - Text-to-Speech for accessibility
 // Uses espeak-ng on Linux, native APIs on other platforms
 
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+/// Short audio cues played as feedback
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SoundCue {
+    /// Hotword heard
+    Hotword,
+    /// Started listening for a command
+    Listening,
+    /// Command understood / action confirmed
+    Confirm,
+    /// Something went wrong
+    Error,
+    /// Task finished
+    Done,
+}
+
+impl SoundCue {
+    pub const ALL: [SoundCue; 5] =
+        [SoundCue::Hotword, SoundCue::Listening, SoundCue::Confirm, SoundCue::Error, SoundCue::Done];
+
+    /// File name stem of the cue in a sound pack
+    pub fn name(&self) -> &'static str {
+        match self {
+            SoundCue::Hotword => "hotword",
+            SoundCue::Listening => "listening",
+            SoundCue::Confirm => "confirm",
+            SoundCue::Error => "error",
+            SoundCue::Done => "done",
+        }
+    }
+
+    /// Map a sound name (including legacy names) to a cue
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hotword" => Some(SoundCue::Hotword),
+            "listening" => Some(SoundCue::Listening),
+            "confirm" | "notification" => Some(SoundCue::Confirm),
+            "error" => Some(SoundCue::Error),
+            "done" | "success" => Some(SoundCue::Done),
+            _ => None,
+        }
+    }
+
+    /// Cue from the pack bundled with the app
+    fn builtin(&self) -> &'static [u8] {
+        match self {
+            SoundCue::Hotword => include_bytes!("../../resources/sounds/hotword.wav"),
+            SoundCue::Listening => include_bytes!("../../resources/sounds/listening.wav"),
+            SoundCue::Confirm => include_bytes!("../../resources/sounds/confirm.wav"),
+            SoundCue::Error => include_bytes!("../../resources/sounds/error.wav"),
+            SoundCue::Done => include_bytes!("../../resources/sounds/done.wav"),
+        }
+    }
+}
+
+/// Playback settings of a single cue
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct CueSettings {
+    pub enabled: bool,
+    /// Volume (0.0 - 1.0)
+    pub volume: f32,
+}
+
+impl Default for CueSettings {
+    fn default() -> Self {
+        Self { enabled: true, volume: 0.8 }
+    }
+}
+
+/// Per-cue settings, sound theme and optional custom sound pack
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SoundCueConfig {
+    #[serde(default)]
+    pub hotword: CueSettings,
+    #[serde(default)]
+    pub listening: CueSettings,
+    #[serde(default)]
+    pub confirm: CueSettings,
+    #[serde(default)]
+    pub error: CueSettings,
+    #[serde(default)]
+    pub done: CueSettings,
+    /// Sound theme from the themes directory (None = bundled sounds)
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Directory with custom `<cue>.wav` files overriding the theme
+    #[serde(default)]
+    pub custom_pack_dir: Option<PathBuf>,
+}
+
+impl SoundCueConfig {
+    /// Settings of a cue
+    pub fn get(&self, cue: SoundCue) -> CueSettings {
+        match cue {
+            SoundCue::Hotword => self.hotword,
+            SoundCue::Listening => self.listening,
+            SoundCue::Confirm => self.confirm,
+            SoundCue::Error => self.error,
+            SoundCue::Done => self.done,
+        }
+    }
+}
+
+/// A set of cues installed in the themes directory
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SoundTheme {
+    /// Directory name, used as `SoundCueConfig::theme`
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Cues the theme has its own sound for; the rest use the bundled ones
+    pub cues: Vec<SoundCue>,
+}
+
+/// Optional `theme.json` in a theme directory
+#[derive(Deserialize)]
+struct ThemeManifest {
+    name: String,
+    #[serde(default)]
+    description: String,
+}
+
+/// A cue file's contents if it is a WAV file (the only format rodio decodes here)
+fn read_wav(path: &Path) -> Option<Vec<u8>> {
+    match std::fs::read(path) {
+        Ok(data) if data.starts_with(b"RIFF") => Some(data),
+        Ok(_) => {
+            log::debug!("Cue {:?} is not a WAV file", path);
+            None
+        }
+        Err(_) => None,
+    }
+}
+
+/// Where sound themes are installed, one directory of `<cue>.wav` files each
+pub fn sound_themes_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cirkelline-cla")
+        .join("sound-themes")
+}
+
+/// Installed sound themes, sorted by name
+pub fn list_sound_themes(dir: &Path) -> Vec<SoundTheme> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut themes: Vec<SoundTheme> = entries
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let id = entry.file_name().to_string_lossy().to_string();
+            let cues: Vec<SoundCue> = SoundCue::ALL
+                .into_iter()
+                .filter(|cue| read_wav(&path.join(format!("{}.wav", cue.name()))).is_some())
+                .collect();
+            if cues.is_empty() {
+                return None;
+            }
+            let manifest = std::fs::read_to_string(path.join("theme.json"))
+                .ok()
+                .and_then(|json| serde_json::from_str::<ThemeManifest>(&json).ok());
+            Some(match manifest {
+                Some(manifest) => SoundTheme { id, name: manifest.name, description: manifest.description, cues },
+                None => SoundTheme { name: id.clone(), id, description: String::new(), cues },
+            })
+        })
+        .collect();
+    themes.sort_by_key(|theme| theme.name.to_lowercase());
+    themes
+}
+
 /// Speech Synthesizer for text-to-speech output
 pub struct SpeechSynthesizer {
     language: String,
@@ -12,6 +188,8 @@ pub struct SpeechSynthesizer {
     volume: f32,
     is_speaking: Arc<AtomicBool>,
     last_text: Arc<tokio::sync::RwLock<String>>,
+    cues: SoundCueConfig,
+    themes_dir: PathBuf,
 }
 
 impl SpeechSynthesizer {
@@ -23,6 +201,8 @@ impl SpeechSynthesizer {
             volume: 1.0,
             is_speaking: Arc::new(AtomicBool::new(false)),
             last_text: Arc::new(tokio::sync::RwLock::new(String::new())),
+            cues: SoundCueConfig::default(),
+            themes_dir: sound_themes_dir(),
         }
     }
 
@@ -171,23 +351,57 @@ impl SpeechSynthesizer {
         }
     }
 
-    /// Play a notification sound
+    /// Play a notification sound by name
     pub async fn play_sound(&self, sound_type: &str) -> Result<(), String> {
-        let sound_file = match sound_type {
-            "listening" => "/usr/share/sounds/freedesktop/stereo/dialog-information.oga",
-            "success" => "/usr/share/sounds/freedesktop/stereo/complete.oga",
-            "error" => "/usr/share/sounds/freedesktop/stereo/dialog-error.oga",
-            "notification" => "/usr/share/sounds/freedesktop/stereo/message.oga",
-            _ => return Ok(()), // No sound for unknown types
-        };
+        match SoundCue::from_name(sound_type) {
+            Some(cue) => self.play_cue(cue).await,
+            None => Ok(()), // No sound for unknown types
+        }
+    }
 
-        if std::path::Path::new(sound_file).exists() {
-            let _ = Command::new("paplay")
-                .arg(sound_file)
-                .output();
+    /// Play an audio cue with its configured volume
+    pub async fn play_cue(&self, cue: SoundCue) -> Result<(), String> {
+        let settings = self.cues.get(cue);
+        if !settings.enabled || settings.volume <= 0.0 {
+            return Ok(());
         }
 
-        Ok(())
+        let data = self.load_cue(cue);
+        let volume = settings.volume.clamp(0.0, 1.0) * self.volume;
+
+        // rodio's output stream is not Send, so open it on the blocking thread
+        tokio::task::spawn_blocking(move || {
+            let (_stream, handle) = rodio::OutputStream::try_default()
+                .map_err(|e| format!("No audio output: {}", e))?;
+            let sink = rodio::Sink::try_new(&handle)
+                .map_err(|e| format!("Failed to open audio sink: {}", e))?;
+            let source = rodio::Decoder::new(std::io::Cursor::new(data))
+                .map_err(|e| format!("Invalid sound file: {}", e))?;
+
+            sink.set_volume(volume);
+            sink.append(source);
+            sink.sleep_until_end();
+            Ok(())
+        })
+        .await
+        .map_err(|e| format!("Sound playback failed: {}", e))?
+    }
+
+    /// Load a cue from the custom pack or the theme, falling back to the
+    /// bundled one
+    pub fn load_cue(&self, cue: SoundCue) -> Vec<u8> {
+        let theme_dir = self.cues.theme.as_ref().map(|theme| self.themes_dir.join(theme));
+        self.cues
+            .custom_pack_dir
+            .iter()
+            .chain(theme_dir.iter())
+            .find_map(|dir| read_wav(&dir.join(format!("{}.wav", cue.name()))))
+            .unwrap_or_else(|| cue.builtin().to_vec())
+    }
+
+    /// Set cue settings
+    pub fn set_cue_config(&mut self, cues: SoundCueConfig) {
+        self.cues = cues;
     }
 
     /// Repeat last spoken text
@@ -226,3 +440,55 @@ impl SpeechSynthesizer {
         self.volume = volume.clamp(0.0, 1.0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_cues_are_wav() {
+        let synth = SpeechSynthesizer::new("da-DK", 1.0);
+        for cue in SoundCue::ALL {
+            assert!(synth.load_cue(cue).starts_with(b"RIFF"), "{:?}", cue);
+        }
+    }
+
+    #[test]
+    fn test_legacy_sound_names() {
+        assert_eq!(SoundCue::from_name("success"), Some(SoundCue::Done));
+        assert_eq!(SoundCue::from_name("unknown"), None);
+    }
+
+    #[test]
+    fn test_theme_overrides_bundled_cues() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = dir.path().join("marimba");
+        std::fs::create_dir_all(&theme).unwrap();
+        std::fs::write(theme.join("hotword.wav"), b"RIFF-marimba").unwrap();
+        // Not a WAV file: the bundled cue is used
+        std::fs::write(theme.join("error.wav"), b"ID3").unwrap();
+        std::fs::write(theme.join("theme.json"), r#"{"name": "Marimba", "description": "Bløde toner"}"#).unwrap();
+
+        let themes = list_sound_themes(dir.path());
+        assert_eq!(themes.len(), 1);
+        assert_eq!(themes[0].name, "Marimba");
+        assert_eq!(themes[0].cues, vec![SoundCue::Hotword]);
+
+        let mut synth = SpeechSynthesizer::new("da-DK", 1.0);
+        synth.themes_dir = dir.path().to_path_buf();
+        synth.set_cue_config(SoundCueConfig { theme: Some("marimba".to_string()), ..SoundCueConfig::default() });
+        assert_eq!(synth.load_cue(SoundCue::Hotword), b"RIFF-marimba");
+        assert_eq!(synth.load_cue(SoundCue::Error), SoundCue::Error.builtin());
+        assert_eq!(synth.load_cue(SoundCue::Done), SoundCue::Done.builtin());
+    }
+
+    #[tokio::test]
+    async fn test_disabled_cue_is_silent() {
+        let mut synth = SpeechSynthesizer::new("da-DK", 1.0);
+        let mut cues = SoundCueConfig::default();
+        cues.error.enabled = false;
+        synth.set_cue_config(cues);
+
+        assert!(synth.play_cue(SoundCue::Error).await.is_ok());
+    }
+}
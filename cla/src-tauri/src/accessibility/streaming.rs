@@ -0,0 +1,376 @@
+// Streaming Transcription - Live microphone audio fed to Whisper in sliding windows
+// Capture runs through cpal (see audio_input); the utterance being spoken is
+// transcribed again as it grows (partial) and once more when it ends (final)
+
+use super::audio_input::{MicrophoneCapture, SAMPLE_RATE};
+use super::noise_suppression::NoiseSuppressor;
+use crate::inference::{InferenceEngine, InferenceLane, WhisperTask};
+use crate::security::privacy::PrivacyMode;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::{mpsc, oneshot, watch, RwLock};
+use uuid::Uuid;
+
+/// When partial and final transcripts are produced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    /// New audio needed before the utterance is transcribed again
+    pub step_ms: u32,
+    /// An utterance is cut here even without a pause (Whisper takes at most 30s)
+    pub max_segment_ms: u32,
+    /// Pause that ends an utterance
+    pub silence_ms: u32,
+    /// Input below this level counts as silence
+    pub silence_dbfs: f32,
+    /// Clean up background noise before each transcription
+    #[serde(default)]
+    pub noise_suppression: bool,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            step_ms: 1500,
+            max_segment_ms: 15_000,
+            silence_ms: 800,
+            silence_dbfs: -45.0,
+            noise_suppression: true,
+        }
+    }
+}
+
+/// Event sent to the frontend while streaming
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamingEvent {
+    /// Transcript of one utterance; replaced by later events with the same segment
+    Transcript {
+        session_id: Uuid,
+        segment: u32,
+        text: String,
+        is_final: bool,
+        start_ms: u64,
+        end_ms: u64,
+    },
+    /// Streaming ended, by request or because of `error`
+    Stopped { session_id: Uuid, error: Option<String> },
+}
+
+/// Audio of the utterance that is ready to transcribe
+#[derive(Debug, PartialEq)]
+pub enum WindowAction {
+    /// The utterance grew by a step; transcribe `SlidingWindow::current`
+    Partial,
+    /// The utterance ended; its samples and start offset in samples
+    Final { samples: Vec<f32>, start: usize },
+}
+
+/// Splits the incoming audio into utterances and decides when to transcribe
+pub struct SlidingWindow {
+    config: StreamingConfig,
+    segment: Vec<f32>,
+    /// Offset of the segment's first sample since streaming started
+    segment_start: usize,
+    since_step: usize,
+    /// Silent samples at the end of the segment
+    trailing_silence: usize,
+}
+
+impl SlidingWindow {
+    pub fn new(config: StreamingConfig) -> Self {
+        Self {
+            config,
+            segment: Vec::new(),
+            segment_start: 0,
+            since_step: 0,
+            trailing_silence: 0,
+        }
+    }
+
+    /// Add captured 16kHz samples
+    pub fn push(&mut self, samples: &[f32]) -> Option<WindowAction> {
+        self.segment.extend_from_slice(samples);
+        self.since_step += samples.len();
+        if level_dbfs(samples) < self.config.silence_dbfs {
+            self.trailing_silence += samples.len();
+        } else {
+            self.trailing_silence = 0;
+        }
+
+        let silence_limit = ms_to_samples(self.config.silence_ms);
+        if self.trailing_silence >= self.segment.len() && self.segment.len() >= silence_limit {
+            // Nothing said yet; drop the silence instead of transcribing it
+            self.segment_start += self.segment.len();
+            self.segment.clear();
+            self.since_step = 0;
+            self.trailing_silence = 0;
+            return None;
+        }
+        if self.trailing_silence >= silence_limit || self.segment.len() >= ms_to_samples(self.config.max_segment_ms) {
+            return self.finish().map(|(samples, start)| WindowAction::Final { samples, start });
+        }
+        if self.since_step >= ms_to_samples(self.config.step_ms) {
+            self.since_step = 0;
+            return Some(WindowAction::Partial);
+        }
+        None
+    }
+
+    /// The utterance so far and its start offset in samples
+    pub fn current(&self) -> (&[f32], usize) {
+        (&self.segment, self.segment_start)
+    }
+
+    /// End the current utterance, if anything was said in it
+    pub fn finish(&mut self) -> Option<(Vec<f32>, usize)> {
+        let start = self.segment_start;
+        let speech = self.segment.len() > self.trailing_silence;
+        self.segment_start += self.segment.len();
+        self.since_step = 0;
+        self.trailing_silence = 0;
+        let samples = std::mem::take(&mut self.segment);
+        speech.then_some((samples, start))
+    }
+}
+
+fn ms_to_samples(ms: u32) -> usize {
+    (SAMPLE_RATE as u64 * ms as u64 / 1000) as usize
+}
+
+fn samples_to_ms(samples: usize) -> u64 {
+    samples as u64 * 1000 / SAMPLE_RATE as u64
+}
+
+/// RMS level in dBFS (-96.0 for silence)
+fn level_dbfs(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return -96.0;
+    }
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt();
+    if rms > 0.0 { (20.0 * rms.log10()).max(-96.0) } else { -96.0 }
+}
+
+/// A running streaming transcription
+pub struct StreamingSession {
+    pub id: Uuid,
+    stop: Option<oneshot::Sender<()>>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl StreamingSession {
+    /// Open the microphone and start transcribing. `on_event` receives the
+    /// transcripts and a final `Stopped`.
+    pub fn start<F>(
+        device: Option<String>,
+        language: Option<String>,
+        config: StreamingConfig,
+        engine: Arc<RwLock<Option<InferenceEngine>>>,
+        privacy: Option<Arc<PrivacyMode>>,
+        on_event: F,
+    ) -> Result<Self, String>
+    where
+        F: Fn(StreamingEvent) + Send + Sync + 'static,
+    {
+        let (capture, audio_rx) = MicrophoneCapture::start(device)?;
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let id = Uuid::new_v4();
+        let task = tokio::spawn(run_session(
+            id,
+            capture,
+            audio_rx,
+            stop_rx,
+            SessionContext { language, config, engine, privacy, on_event },
+        ));
+        Ok(Self {
+            id,
+            stop: Some(stop_tx),
+            task,
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stop capturing and wait for the last utterance to be transcribed
+    pub async fn stop(mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        let _ = self.task.await;
+    }
+}
+
+struct SessionContext<F> {
+    language: Option<String>,
+    config: StreamingConfig,
+    engine: Arc<RwLock<Option<InferenceEngine>>>,
+    privacy: Option<Arc<PrivacyMode>>,
+    on_event: F,
+}
+
+impl<F: Fn(StreamingEvent)> SessionContext<F> {
+    async fn transcribe(&self, session_id: Uuid, segment: u32, samples: Vec<f32>, start: usize, is_final: bool) -> Result<(), String> {
+        let end = start + samples.len();
+        let samples = if self.config.noise_suppression {
+            NoiseSuppressor::new().gate_f32(&samples)
+        } else {
+            samples
+        };
+        let engine = self.engine.read().await;
+        let engine = engine
+            .as_ref()
+            .filter(|engine| engine.has_whisper_model())
+            .ok_or("Talegenkendelse kræver Whisper-modellen. Download den under Modeller.")?;
+        let result = engine
+            .transcribe_samples_in(InferenceLane::Accessibility, samples, self.language.as_deref(), WhisperTask::Transcribe)
+            .await?;
+        (self.on_event)(StreamingEvent::Transcript {
+            session_id,
+            segment,
+            text: result.text.trim().to_string(),
+            is_final,
+            start_ms: samples_to_ms(start),
+            end_ms: samples_to_ms(end),
+        });
+        Ok(())
+    }
+}
+
+/// Resolves once privacy mode is on; never without a privacy mode
+async fn privacy_activated(privacy_rx: &mut Option<watch::Receiver<bool>>) {
+    if let Some(rx) = privacy_rx {
+        if rx.wait_for(|active| *active).await.is_ok() {
+            return;
+        }
+    }
+    std::future::pending().await
+}
+
+async fn run_session<F: Fn(StreamingEvent)>(
+    session_id: Uuid,
+    capture: MicrophoneCapture,
+    mut audio_rx: mpsc::UnboundedReceiver<Vec<f32>>,
+    mut stop_rx: oneshot::Receiver<()>,
+    context: SessionContext<F>,
+) {
+    let mut window = SlidingWindow::new(context.config.clone());
+    let mut segment = 0u32;
+    let mut privacy_rx = context.privacy.as_ref().map(|privacy| privacy.subscribe());
+
+    let error = loop {
+        let first = tokio::select! {
+            chunk = audio_rx.recv() => match chunk {
+                Some(chunk) => chunk,
+                None => break Some("Mikrofonen blev afbrudt".to_string()),
+            },
+            _ = &mut stop_rx => break None,
+            _ = privacy_activated(&mut privacy_rx) => break Some("Mikrofonen er slået fra i privat tilstand".to_string()),
+        };
+
+        // Catch up on audio that arrived during the last transcription, so a
+        // slow model gives fewer partials instead of falling behind
+        let mut chunks = vec![first];
+        while let Ok(chunk) = audio_rx.try_recv() {
+            chunks.push(chunk);
+        }
+
+        let mut partial_due = false;
+        let mut failed = None;
+        for chunk in chunks {
+            match window.push(&chunk) {
+                Some(WindowAction::Final { samples, start }) => {
+                    partial_due = false;
+                    if let Err(e) = context.transcribe(session_id, segment, samples, start, true).await {
+                        failed = Some(e);
+                        break;
+                    }
+                    segment += 1;
+                }
+                Some(WindowAction::Partial) => partial_due = true,
+                None => {}
+            }
+        }
+        if failed.is_none() && partial_due {
+            let (samples, start) = window.current();
+            if let Err(e) = context.transcribe(session_id, segment, samples.to_vec(), start, false).await {
+                failed = Some(e);
+            }
+        }
+        if failed.is_some() {
+            break failed;
+        }
+    };
+
+    // Release the microphone before the last transcription
+    drop(capture);
+    let mut error = error;
+    if error.is_none() {
+        if let Some((samples, start)) = window.finish() {
+            if let Err(e) = context.transcribe(session_id, segment, samples, start, true).await {
+                error = Some(e);
+            }
+        }
+    }
+    if let Some(e) = &error {
+        log::warn!("Streaming transcription stopped: {}", e);
+    }
+    (context.on_event)(StreamingEvent::Stopped { session_id, error });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(ms: u32) -> Vec<f32> {
+        (0..ms_to_samples(ms)).map(|i| 0.3 * (i as f32 * 0.1).sin()).collect()
+    }
+
+    fn silence(ms: u32) -> Vec<f32> {
+        vec![0.0; ms_to_samples(ms)]
+    }
+
+    #[test]
+    fn test_partials_while_speaking_then_final_after_pause() {
+        let mut window = SlidingWindow::new(StreamingConfig::default());
+
+        assert_eq!(window.push(&tone(1000)), None);
+        assert_eq!(window.push(&tone(500)), Some(WindowAction::Partial));
+        assert_eq!(window.current().0.len(), ms_to_samples(1500));
+        assert_eq!(window.push(&silence(400)), None);
+
+        match window.push(&silence(400)) {
+            Some(WindowAction::Final { samples, start }) => {
+                assert_eq!(start, 0);
+                assert_eq!(samples.len(), ms_to_samples(2300));
+            }
+            other => panic!("expected final, got {:?}", other),
+        }
+        // The next utterance starts where the last one ended
+        window.push(&tone(200));
+        assert_eq!(window.current().1, ms_to_samples(2300));
+    }
+
+    #[test]
+    fn test_silence_is_not_transcribed() {
+        let mut window = SlidingWindow::new(StreamingConfig::default());
+        for _ in 0..10 {
+            assert_eq!(window.push(&silence(500)), None);
+        }
+        assert!(window.current().0.len() < ms_to_samples(1000));
+        assert!(window.finish().is_none());
+    }
+
+    #[test]
+    fn test_long_speech_is_cut_at_max_segment() {
+        let config = StreamingConfig {
+            max_segment_ms: 3000,
+            ..StreamingConfig::default()
+        };
+        let mut window = SlidingWindow::new(config);
+        let actions: Vec<_> = (0..4).filter_map(|_| window.push(&tone(1000))).collect();
+        assert_eq!(actions[0], WindowAction::Partial);
+        assert!(matches!(&actions[1], WindowAction::Final { samples, start: 0 } if samples.len() == ms_to_samples(3000)));
+        assert_eq!(window.current().1, ms_to_samples(3000));
+    }
+}
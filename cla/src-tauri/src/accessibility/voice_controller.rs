@@ -1,14 +1,51 @@
 // Voice Controller - Main orchestrator for voice interaction
 // Coordinates speech recognition, synthesis, and command execution
 
+use futures_util::future::BoxFuture;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
 
 use crate::accessibility::{
     AccessibilityConfig, AccessibilityEvent, VoiceState,
-    SpeechSynthesizer, HotwordDetector,
-    command_parser::{CommandParser, VoiceCommand},
+    SoundCue, SpeechSynthesizer, HotwordDetector, HotwordStats,
+    command_parser::{CommandParser, IntentClassifier, VoiceCommand},
+    dialogue::{DialogueManager, Turn},
+    narration::{NarrationKind, NarrationPriority, Narrator, UiEvent, UiNarration},
+    audio_input::{self, InputDevice, RecordingEnd},
+    error_narration::{self, RecoveryHandler},
+    noise_suppression::{self, NoiseSuppressor},
+    voice_settings,
 };
+use crate::error::{ClaError, NetworkError};
+use crate::inference::{InferenceEngine, InferenceLane, Vad, VadConfig, WhisperTask};
+use crate::models::{Settings, SyncResult};
+use crate::notifications::{Notification, NotificationCenter};
+use crate::security::privacy::PrivacyMode;
+use crate::telemetry::TelemetryService;
+use crate::utils::{Heartbeat, Watchdog};
+
+/// Longest spoken command; recording normally ends when the user pauses
+const MAX_COMMAND_SECONDS: u32 = 15;
+
+/// How long to wait for the user to start speaking
+const LISTEN_TIMEOUT_MS: u64 = 5000;
+
+/// Follow-up questions asked for one command before giving up
+const MAX_FOLLOW_UPS: usize = 4;
+
+/// Longest recording when enrolling a sample of the hotword
+const MAX_HOTWORD_SECONDS: u32 = 4;
+
+/// Spoken commands cannot be understood until the Whisper model is downloaded
+const WHISPER_MISSING: &str = "Talegenkendelse kræver Whisper-modellen. Download den under Modeller.";
+
+/// Runs a sync with CKC on request ("synkroniser nu")
+pub type SyncHandler = Arc<dyn Fn() -> BoxFuture<'static, SyncResult> + Send + Sync>;
+
+/// A recovery offered to the user, waiting for yes/no
+struct PendingRecovery {
+    handler: Option<RecoveryHandler>,
+}
 
 /// Main voice controller that orchestrates all voice interaction
 pub struct VoiceController {
@@ -19,13 +56,26 @@ pub struct VoiceController {
     command_parser: Arc<CommandParser>,
     event_tx: broadcast::Sender<AccessibilityEvent>,
     last_response: Arc<RwLock<String>>,
+    pending_recovery: Arc<RwLock<Option<PendingRecovery>>>,
+    dialogue: Arc<RwLock<DialogueManager>>,
+    narrator: Arc<Narrator>,
+    settings: Option<Arc<RwLock<Settings>>>,
+    active_device: Arc<RwLock<String>>,
+    telemetry: Option<Arc<TelemetryService>>,
+    watchdog: Option<Arc<Watchdog>>,
+    notifications: Option<Arc<NotificationCenter>>,
+    privacy: Option<Arc<PrivacyMode>>,
+    sync: Option<SyncHandler>,
+    engine: Option<Arc<RwLock<Option<InferenceEngine>>>>,
 }
 
 impl VoiceController {
     /// Create new voice controller with configuration
     pub fn new(config: AccessibilityConfig) -> Self {
-        let synthesizer = SpeechSynthesizer::new(&config.language, config.speech_rate);
-        let hotword_detector = HotwordDetector::new(&config.hotword);
+        let mut synthesizer = SpeechSynthesizer::new(&config.language, config.speech_rate);
+        synthesizer.set_cue_config(config.sound_cues.clone());
+        let mut hotword_detector = HotwordDetector::new(&config.hotword);
+        hotword_detector.set_sensitivity(config.hotword_sensitivity);
         let command_parser = CommandParser::new(&config.language);
         let (event_tx, _) = broadcast::channel(100);
 
@@ -37,9 +87,75 @@ impl VoiceController {
             command_parser: Arc::new(command_parser),
             event_tx,
             last_response: Arc::new(RwLock::new(String::new())),
+            pending_recovery: Arc::new(RwLock::new(None)),
+            dialogue: Arc::new(RwLock::new(DialogueManager::new())),
+            narrator: Arc::new(Narrator::new()),
+            settings: None,
+            active_device: Arc::new(RwLock::new(audio_input::DEFAULT_DEVICE.to_string())),
+            telemetry: None,
+            watchdog: None,
+            notifications: None,
+            privacy: None,
+            sync: None,
+            engine: None,
         }
     }
 
+    /// Report voice pipeline statistics (e.g. noise suppression SNR) to telemetry
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Run the voice loop under the watchdog so it is restarted if it dies
+    pub fn with_watchdog(mut self, watchdog: Arc<Watchdog>) -> Self {
+        self.watchdog = Some(watchdog);
+        self
+    }
+
+    /// Read notifications from the shared notification center
+    pub fn with_notifications(mut self, notifications: Arc<NotificationCenter>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Release the microphone while privacy mode is active
+    pub fn with_privacy(mut self, privacy: Arc<PrivacyMode>) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
+    /// Send UI narrations through a shared narrator
+    pub fn with_narrator(mut self, narrator: Arc<Narrator>) -> Self {
+        self.narrator = narrator;
+        self
+    }
+
+    /// Let "synkroniser nu" run a sync; it needs the app's state, so it is
+    /// set once the app is running
+    pub fn set_sync_handler(&mut self, handler: SyncHandler) {
+        self.sync = Some(handler);
+    }
+
+    /// Share the application settings so voice commands can adjust them
+    pub fn with_settings(mut self, settings: Arc<RwLock<Settings>>) -> Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Let an intent model recognise commands the keyword rules miss
+    pub fn with_intents(mut self, classifier: Arc<dyn IntentClassifier>) -> Self {
+        let parser = CommandParser::new(self.command_parser.language()).with_classifier(classifier);
+        self.command_parser = Arc::new(parser);
+        self
+    }
+
+    /// Transcribe spoken commands with the engine's Whisper model
+    pub fn with_transcription(mut self, engine: Arc<RwLock<Option<InferenceEngine>>>) -> Self {
+        self.engine = Some(engine);
+        self
+    }
+
     /// Initialize voice controller (load models, check dependencies)
     pub async fn initialize(&self) -> Result<(), String> {
         log::info!("Initializing voice controller...");
@@ -74,7 +190,9 @@ impl VoiceController {
 
         // Start hotword detection if continuous listening is enabled
         if config.continuous_listening {
-            let detector = self.hotword_detector.read().await;
+            let device = self.resolve_input_device(config.input_device.as_deref()).await;
+            let mut detector = self.hotword_detector.write().await;
+            detector.set_device(&device);
             detector.start().await?;
         }
 
@@ -84,37 +202,34 @@ impl VoiceController {
         // Play startup sound
         if config.sound_feedback {
             let synth = self.synthesizer.read().await;
-            let _ = synth.play_sound("listening").await;
+            let _ = synth.play_cue(SoundCue::Listening).await;
         }
 
         // Start the main voice loop
         let config_clone = self.config.clone();
         let state_clone = self.state.clone();
         let detector_clone = self.hotword_detector.clone();
+        let synthesizer_clone = self.synthesizer.clone();
         let event_tx_clone = self.event_tx.clone();
+        let privacy_clone = self.privacy.clone();
+        let voice_loop = move |heartbeat: Heartbeat| {
+            run_voice_loop(
+                config_clone.clone(),
+                state_clone.clone(),
+                detector_clone.clone(),
+                synthesizer_clone.clone(),
+                event_tx_clone.clone(),
+                privacy_clone.clone(),
+                heartbeat,
+            )
+        };
 
-        tokio::spawn(async move {
-            loop {
-                let config = config_clone.read().await;
-                if !config.voice_enabled {
-                    break;
-                }
-
-                if config.continuous_listening {
-                    // Check for hotword
-                    let detector = detector_clone.read().await;
-                    if detector.detected().await {
-                        // Hotword detected - transition to listening state
-                        let mut state = state_clone.write().await;
-                        *state = VoiceState::Listening;
-                        let _ = event_tx_clone.send(AccessibilityEvent::HotwordDetected);
-                    }
-                }
-
-                drop(config);
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        match &self.watchdog {
+            Some(watchdog) => watchdog.supervise("voice_loop", "voice_control", voice_loop),
+            None => {
+                tokio::spawn(voice_loop(Heartbeat::detached()));
             }
-        });
+        }
 
         log::info!("Voice control started");
         Ok(())
@@ -141,31 +256,88 @@ impl VoiceController {
         Ok(())
     }
 
-    /// Listen for a single command (manual trigger)
+    /// Listen for a single command (manual trigger). Follow-up questions
+    /// ("Hvilken prioritet?") are answered in the same call; silence ends the
+    /// conversation.
     pub async fn listen_now(&self) -> Result<String, String> {
-        self.set_state(VoiceState::Listening).await;
-        self.emit_event(AccessibilityEvent::ListeningStarted).await;
+        if self.privacy.as_ref().is_some_and(|privacy| privacy.is_active()) {
+            return Err("Mikrofonen er slået fra i privat tilstand".to_string());
+        }
 
-        let config = self.config.read().await;
-        if config.sound_feedback {
-            let synth = self.synthesizer.read().await;
-            let _ = synth.play_sound("listening").await;
+        let mut transcriptions = Vec::new();
+        for turn in 0..=MAX_FOLLOW_UPS {
+            self.set_state(VoiceState::Listening).await;
+            self.emit_event(AccessibilityEvent::ListeningStarted).await;
+
+            let config = self.config.read().await;
+            if config.sound_feedback {
+                let synth = self.synthesizer.read().await;
+                let _ = synth.play_cue(SoundCue::Listening).await;
+            }
+            drop(config);
+
+            // Record audio and transcribe
+            let transcription = match self.transcribe_audio().await {
+                Ok(transcription) => transcription,
+                Err(e) => {
+                    if turn > 0 {
+                        // No answer to the question: back to idle
+                        self.dialogue.write().await.reset();
+                        self.set_state(VoiceState::Idle).await;
+                    }
+                    return Err(e);
+                }
+            };
+
+            let (_, awaiting_answer) = self.respond(&transcription).await?;
+            transcriptions.push(transcription);
+            if !awaiting_answer {
+                break;
+            }
         }
-        drop(config);
 
-        // Record audio and transcribe
-        let transcription = self.transcribe_audio().await?;
+        self.dialogue.write().await.reset();
+        Ok(transcriptions.join(" "))
+    }
 
+    /// Handle one transcribed utterance as the next turn of the conversation;
+    /// returns the response and whether it is a question waiting for an answer
+    pub async fn respond(&self, transcription: &str) -> Result<(String, bool), String> {
         self.set_state(VoiceState::Processing).await;
         self.emit_event(AccessibilityEvent::Processing {
-            text: transcription.clone()
+            text: transcription.to_string()
         }).await;
 
-        // Parse command
-        let command = self.command_parser.parse(&transcription).await;
+        // Parse command; the user's macros come first
+        let macros = match &self.settings {
+            Some(settings) => settings.read().await.voice_macros.clone(),
+            None => Vec::new(),
+        };
+        let parsed = self.command_parser.parse_with_macros(transcription, &macros).await;
+        log::debug!("Parsed {:?} ({:?}, confidence {:.2})", parsed.command, parsed.source, parsed.confidence);
 
-        // Execute command and get response
-        let response = self.execute_command(command).await?;
+        let is_danish = self.config.read().await.language.starts_with("da");
+        let turn = self.dialogue.write().await.handle(
+            transcription,
+            parsed.command,
+            chrono::Local::now().naive_local(),
+            is_danish,
+        );
+
+        let (response, awaiting_answer) = match turn {
+            Turn::Execute(command) => {
+                // Execute command and get response
+                let cue = if matches!(command, VoiceCommand::Unknown(_)) {
+                    SoundCue::Error
+                } else {
+                    SoundCue::Confirm
+                };
+                self.play_cue(cue).await;
+                (self.execute_command(command).await?, false)
+            }
+            Turn::Ask(question) => (question, true),
+            Turn::Say(text) => (text, false),
+        };
 
         // Speak response if auto-speak is enabled
         let config = self.config.read().await;
@@ -175,12 +347,17 @@ impl VoiceController {
         }
 
         self.set_state(VoiceState::Idle).await;
+        if awaiting_answer {
+            self.emit_event(AccessibilityEvent::AwaitingAnswer {
+                question: response.clone(),
+            }).await;
+        }
         self.emit_event(AccessibilityEvent::CommandProcessed {
-            input: transcription.clone(),
+            input: transcription.to_string(),
             response: response.clone(),
         }).await;
 
-        Ok(transcription)
+        Ok((response, awaiting_answer))
     }
 
     /// Speak text aloud
@@ -205,6 +382,149 @@ impl VoiceController {
         Ok(())
     }
 
+    /// Speak an error and offer its recovery action ("Skal jeg prøve igen?")
+    ///
+    /// If the user answers yes, `handler` is run; without a handler a
+    /// `RecoveryAccepted` event is emitted so the frontend can retry.
+    pub async fn narrate_error(
+        &self,
+        error: &ClaError,
+        handler: Option<RecoveryHandler>,
+    ) -> Result<(), String> {
+        let is_danish = self.config.read().await.language.starts_with("da");
+        let narration = error_narration::narrate(error, is_danish);
+
+        {
+            let mut pending = self.pending_recovery.write().await;
+            *pending = if narration.offers_recovery() {
+                Some(PendingRecovery { handler })
+            } else {
+                None
+            };
+        }
+
+        self.play_cue(SoundCue::Error).await;
+        self.emit_event(AccessibilityEvent::ErrorNarrated {
+            message: narration.message.clone(),
+            recovery_offered: narration.offers_recovery(),
+        }).await;
+
+        self.speak(&narration.spoken_text()).await
+    }
+
+    /// Recovery the controller can carry out itself for an error: a failed or
+    /// slow request to CKC is retried by running a sync. None leaves the retry
+    /// to the frontend.
+    pub async fn recovery_for(&self, error: &ClaError) -> Option<RecoveryHandler> {
+        let url = match error {
+            ClaError::Network(NetworkError::ConnectionFailed { url, .. } | NetworkError::Timeout { url, .. }) => url,
+            _ => return None,
+        };
+        let endpoint = self.settings.as_ref()?.read().await.ckc_endpoint.clone()?;
+        if !url.starts_with(&endpoint) {
+            return None;
+        }
+
+        let sync = self.sync.clone()?;
+        let is_danish = self.config.read().await.language.starts_with("da");
+        Some(Box::new(move || {
+            Box::pin(async move {
+                match sync().await {
+                    SyncResult::Failed { error } => Err(error),
+                    result => Ok(sync_summary(result, is_danish)),
+                }
+            })
+        }))
+    }
+
+    /// Describe a page change, focus move or announcement reported by the
+    /// frontend; None if there is nothing new to say
+    pub async fn narrate_ui(&self, event: UiEvent) -> Option<UiNarration> {
+        let (verbosity, is_danish) = {
+            let config = self.config.read().await;
+            (config.narration_verbosity, config.language.starts_with("da"))
+        };
+        let narration = self.narrator.describe(event, verbosity, is_danish)?;
+        Some(self.deliver(narration).await)
+    }
+
+    /// Summarise a new notification for the user
+    pub async fn narrate_notification(&self, notification: &Notification, unread: usize) -> UiNarration {
+        let (verbosity, is_danish) = {
+            let config = self.config.read().await;
+            (config.narration_verbosity, config.language.starts_with("da"))
+        };
+        let narration = self.narrator.notification(notification, unread, verbosity, is_danish);
+        self.deliver(narration).await
+    }
+
+    /// Play a cue regardless of sound feedback (for previewing settings)
+    pub async fn preview_cue(&self, cue: SoundCue) -> Result<(), String> {
+        let synth = self.synthesizer.read().await;
+        synth.play_cue(cue).await
+    }
+
+    /// List available microphones
+    pub async fn list_input_devices(&self) -> Vec<InputDevice> {
+        audio_input::list_input_devices().await
+    }
+
+    /// Select a microphone (None = system default)
+    pub async fn select_input_device(&self, device: Option<String>) -> Result<(), String> {
+        if let Some(id) = &device {
+            let available = audio_input::list_input_devices().await;
+            if !available.iter().any(|d| &d.id == id) {
+                return Err(format!("Ukendt mikrofon: {}", id));
+            }
+        }
+
+        let mut config = self.config.read().await.clone();
+        config.input_device = device;
+        self.update_config(config).await;
+        Ok(())
+    }
+
+    /// Record the user saying the hotword and add it to the samples it is
+    /// detected by
+    pub async fn enroll_hotword_sample(&self) -> Result<HotwordStats, String> {
+        if self.privacy.as_ref().is_some_and(|privacy| privacy.is_active()) {
+            return Err("Mikrofonen er slået fra i privat tilstand".to_string());
+        }
+
+        let selected = self.config.read().await.input_device.clone();
+        let device = self.resolve_input_device(selected.as_deref()).await;
+        let models_dir = crate::inference::default_models_dir();
+        let mut vad = Vad::load(models_dir.as_deref(), VadConfig::default());
+        let event_tx = self.event_tx.clone();
+        let (samples, end) = audio_input::record_utterance(
+            &device,
+            MAX_HOTWORD_SECONDS,
+            LISTEN_TIMEOUT_MS,
+            &mut vad,
+            |level| {
+                let _ = event_tx.send(AccessibilityEvent::InputLevel { level });
+            },
+        ).await?;
+        if end == RecordingEnd::NoSpeech {
+            return Err("Ingen tale hørt".to_string());
+        }
+
+        let samples: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let detector = self.hotword_detector.read().await;
+        detector.enroll(&samples)?;
+        Ok(detector.stats())
+    }
+
+    /// Forget the recorded hotword samples
+    pub async fn clear_hotword_samples(&self) {
+        self.hotword_detector.read().await.clear_samples();
+    }
+
+    /// Hotword detection statistics
+    pub async fn hotword_stats(&self) -> HotwordStats {
+        self.hotword_detector.read().await.stats()
+    }
+
     /// Get current voice state
     pub async fn get_state(&self) -> VoiceState {
         self.state.read().await.clone()
@@ -221,12 +541,16 @@ impl VoiceController {
         {
             let mut synth = self.synthesizer.write().await;
             synth.set_rate(config.speech_rate);
+            synth.set_cue_config(config.sound_cues.clone());
         }
 
         // Update hotword
         {
             let mut detector = self.hotword_detector.write().await;
             detector.set_hotword(&config.hotword);
+            detector.set_sensitivity(config.hotword_sensitivity);
+            detector.set_device(config.input_device.as_deref().unwrap_or(audio_input::DEFAULT_DEVICE));
+            detector.set_noise_suppression(config.noise_suppression);
         }
 
         // Store new config
@@ -238,34 +562,101 @@ impl VoiceController {
         log::info!("Voice controller config updated");
     }
 
+    // Internal: Play an audio cue if sound feedback is enabled
+    async fn play_cue(&self, cue: SoundCue) {
+        if !self.config.read().await.sound_feedback {
+            return;
+        }
+        let synth = self.synthesizer.read().await;
+        if let Err(e) = synth.play_cue(cue).await {
+            log::debug!("Could not play {:?} cue: {}", cue, e);
+        }
+    }
+
+    // Internal: Pick the device to record from, emitting an event when it changes
+    async fn resolve_input_device(&self, selected: Option<&str>) -> String {
+        let available = audio_input::list_input_devices().await;
+        let (device, fallback) = audio_input::resolve_device(selected, &available);
+
+        let mut active = self.active_device.write().await;
+        if *active != device {
+            *active = device.clone();
+            self.emit_event(AccessibilityEvent::InputDeviceChanged {
+                device: device.clone(),
+                fallback,
+            }).await;
+        }
+
+        device
+    }
+
     // Internal: Record audio and transcribe
     async fn transcribe_audio(&self) -> Result<String, String> {
-        use std::process::Command;
-
-        // Record audio (3 seconds)
-        let temp_path = format!("/tmp/voice_input_{}.wav", uuid::Uuid::new_v4());
+        let (selected, noise_suppression, language) = {
+            let config = self.config.read().await;
+            (config.input_device.clone(), config.noise_suppression, config.language.clone())
+        };
+        let device = self.resolve_input_device(selected.as_deref()).await;
 
-        let record_result = Command::new("arecord")
-            .args([
-                "-f", "S16_LE",
-                "-r", "16000",
-                "-c", "1",
-                "-d", "3",  // 3 seconds
-                "-q",       // Quiet mode
-                &temp_path,
-            ])
-            .output()
-            .map_err(|e| format!("Failed to record audio: {}", e))?;
+        // Record until the user stops speaking, streaming the input level to the frontend
+        let models_dir = crate::inference::default_models_dir();
+        let mut vad = Vad::load(models_dir.as_deref(), VadConfig::default());
+        let event_tx = self.event_tx.clone();
+        let (samples, end) = audio_input::record_utterance(
+            &device,
+            MAX_COMMAND_SECONDS,
+            LISTEN_TIMEOUT_MS,
+            &mut vad,
+            |level| {
+                let _ = event_tx.send(AccessibilityEvent::InputLevel { level });
+            },
+        ).await?;
 
-        if !record_result.status.success() {
-            return Err("Audio recording failed".to_string());
+        log::debug!("Captured {} samples from '{}' ({:?})", samples.len(), device, end);
+        // Silence right after a hotword detection means it was a false positive
+        self.hotword_detector.read().await.record_outcome(end != RecordingEnd::NoSpeech);
+        if end == RecordingEnd::NoSpeech {
+            return Err("Ingen tale hørt".to_string());
         }
 
-        // Clean up temp file (transcription would use Whisper in production)
-        let _ = std::fs::remove_file(&temp_path);
-        
-        // Placeholder - in production, use Whisper model for transcription
-        Err("Speech recognition requires Whisper model. Install with: download-models command.".to_string())
+        // Clean up background noise before transcription
+        let samples = if noise_suppression {
+            let (cleaned, stats) = NoiseSuppressor::new().process(&samples);
+            log::debug!(
+                "Noise suppression: SNR {:.1}dB -> {:.1}dB",
+                stats.snr_before_db,
+                stats.snr_after_db
+            );
+            if let Some(telemetry) = &self.telemetry {
+                telemetry.record_noise_suppression(stats.snr_before_db, stats.snr_after_db).await;
+            }
+            cleaned
+        } else {
+            samples
+        };
+
+        let engine = match &self.engine {
+            Some(engine) => engine.read().await,
+            None => return Err(WHISPER_MISSING.to_string()),
+        };
+        let engine = engine
+            .as_ref()
+            .filter(|engine| engine.has_whisper_model())
+            .ok_or(WHISPER_MISSING)?;
+        let samples = samples.into_iter().map(noise_suppression::to_f32).collect();
+        let result = engine
+            .transcribe_samples_in(InferenceLane::Accessibility, samples, Some(&language), WhisperTask::Transcribe)
+            .await?;
+        Ok(result.text.trim().to_string())
+    }
+
+    // Internal: Spoken response when no settings are attached
+    fn settings_unavailable(is_danish: bool) -> String {
+        if is_danish {
+            "Indstillinger kan ikke ændres lige nu.".to_string()
+        } else {
+            "Settings cannot be changed right now.".to_string()
+        }
     }
 
     // Internal: Execute a voice command
@@ -303,33 +694,140 @@ impl VoiceController {
                     format!("Searching for: {}. Please wait.", query)
                 })
             }
-            VoiceCommand::CreateTask { description, priority } => {
+            VoiceCommand::CreateTask { description, priority, due } => {
                 Ok(if is_danish {
-                    format!("Opretter opgave: {} med {} prioritet.", description,
+                    let due = due.map(|due| format!(" til {}", due.format("%d.%m. kl. %H:%M"))).unwrap_or_default();
+                    format!("Opretter opgave: {} med {} prioritet{}.", description,
                         match priority.as_str() {
                             "critical" => "kritisk",
                             "high" => "høj",
                             _ => "normal"
-                        })
+                        },
+                        due)
                 } else {
-                    format!("Creating task: {} with {} priority.", description, priority)
+                    let due = due.map(|due| format!(" due {}", due.format("%B %-d at %H:%M"))).unwrap_or_default();
+                    format!("Creating task: {} with {} priority{}.", description, priority, due)
                 })
             }
             VoiceCommand::ReadNotifications => {
+                let Some(notifications) = &self.notifications else {
+                    return Ok(if is_danish {
+                        "Notifikationer er ikke tilgængelige lige nu.".to_string()
+                    } else {
+                        "Notifications are not available right now.".to_string()
+                    });
+                };
+                Ok(notifications.read_aloud(is_danish).await)
+            }
+            VoiceCommand::Help => {
                 Ok(if is_danish {
-                    "Du har ingen ulæste notifikationer.".to_string()
+                    "Du kan sige: start, stop, status, synkroniser, søg efter noget, opret opgave, notifikationer, hjælp, annuller, eller gentag.".to_string()
                 } else {
-                    "You have no unread notifications.".to_string()
+                    "You can say: start, stop, status, sync, search for something, create task, notifications, help, cancel, or repeat.".to_string()
                 })
             }
-            VoiceCommand::Help => {
+            VoiceCommand::SyncNow => {
+                let Some(sync) = &self.sync else {
+                    return Ok(if is_danish {
+                        "Synkronisering er ikke tilgængelig lige nu.".to_string()
+                    } else {
+                        "Sync is not available right now.".to_string()
+                    });
+                };
+                let result = sync().await;
+                let cue = if matches!(result, SyncResult::Failed { .. }) { SoundCue::Error } else { SoundCue::Done };
+                self.play_cue(cue).await;
+                Ok(sync_summary(result, is_danish))
+            }
+            VoiceCommand::RunMacro { phrase, commands } => {
+                let mut responses = vec![if is_danish {
+                    format!("Kører {}.", phrase)
+                } else {
+                    format!("Running {}.", phrase)
+                }];
+                for command in commands {
+                    responses.push(Box::pin(self.execute_command(command)).await?);
+                }
+                self.play_cue(SoundCue::Done).await;
+                Ok(responses.join(" "))
+            }
+            VoiceCommand::SetSetting { setting, value } => {
+                let Some(settings) = &self.settings else {
+                    return Ok(Self::settings_unavailable(is_danish));
+                };
+                let mut settings = settings.write().await;
+                Ok(voice_settings::set_value(&mut settings, setting, value, is_danish)
+                    .await
+                    .unwrap_or_else(|e| e))
+            }
+            VoiceCommand::ToggleSetting { setting, enabled } => {
+                let Some(settings) = &self.settings else {
+                    return Ok(Self::settings_unavailable(is_danish));
+                };
+                let mut settings = settings.write().await;
+                Ok(voice_settings::set_toggle(&mut settings, setting, enabled, is_danish)
+                    .await
+                    .unwrap_or_else(|e| e))
+            }
+            VoiceCommand::SetPrivacyMode { enabled } => {
+                let Some(privacy) = &self.privacy else {
+                    return Ok(if is_danish {
+                        "Privat tilstand er ikke tilgængelig lige nu.".to_string()
+                    } else {
+                        "Privacy mode is not available right now.".to_string()
+                    });
+                };
+                if !enabled {
+                    privacy.disable().await;
+                    return Ok(if is_danish {
+                        "Privat tilstand er slået fra. Jeg lytter igen.".to_string()
+                    } else {
+                        "Privacy mode is off. I am listening again.".to_string()
+                    });
+                }
+
+                let minutes = match &self.settings {
+                    Some(settings) => settings.read().await.privacy_mode_minutes,
+                    None => Settings::default().privacy_mode_minutes,
+                };
+                privacy.enable(std::time::Duration::from_secs(minutes as u64 * 60)).await;
                 Ok(if is_danish {
-                    "Du kan sige: start, stop, status, søg efter noget, opret opgave, notifikationer, hjælp, annuller, eller gentag.".to_string()
+                    format!("Privat tilstand i {} minutter. Mikrofon og research er sat på pause.", minutes)
                 } else {
-                    "You can say: start, stop, status, search for something, create task, notifications, help, cancel, or repeat.".to_string()
+                    format!("Privacy mode for {} minutes. Microphone and research are paused.", minutes)
                 })
             }
+            VoiceCommand::Confirm => {
+                let pending = self.pending_recovery.write().await.take();
+                match pending {
+                    Some(PendingRecovery { handler: Some(handler) }) => match handler().await {
+                        Ok(result) => {
+                            self.play_cue(SoundCue::Done).await;
+                            Ok(result)
+                        }
+                        Err(e) => Ok(if is_danish {
+                            format!("Det lykkedes desværre ikke: {}", e)
+                        } else {
+                            format!("That did not work: {}", e)
+                        }),
+                    },
+                    Some(PendingRecovery { handler: None }) => {
+                        self.emit_event(AccessibilityEvent::RecoveryAccepted).await;
+                        Ok(if is_danish {
+                            "Okay, jeg prøver igen.".to_string()
+                        } else {
+                            "Okay, trying again.".to_string()
+                        })
+                    }
+                    None => Ok(if is_danish {
+                        "Der er intet at bekræfte.".to_string()
+                    } else {
+                        "There is nothing to confirm.".to_string()
+                    }),
+                }
+            }
             VoiceCommand::Cancel => {
+                self.pending_recovery.write().await.take();
                 Ok(if is_danish {
                     "Handling annulleret.".to_string()
                 } else {
@@ -358,6 +856,38 @@ impl VoiceController {
         }
     }
 
+    // Internal: Emit a narration and, in screen reader mode, read it aloud.
+    // Polite narrations wait while the user is speaking to us; focus moves
+    // overtaken by a newer narration before they are reached are skipped.
+    async fn deliver(&self, mut narration: UiNarration) -> UiNarration {
+        let screen_reader = self.config.read().await.screen_reader_mode;
+        let busy = matches!(*self.state.read().await, VoiceState::Listening | VoiceState::Processing);
+        narration.spoken = screen_reader && (!busy || narration.priority == NarrationPriority::Assertive);
+
+        let ticket = self.narrator.emit(&narration);
+        if narration.spoken {
+            let synthesizer = self.synthesizer.clone();
+            let narrator = self.narrator.clone();
+            let narration = narration.clone();
+            tokio::spawn(async move {
+                let synth = synthesizer.read().await;
+                if narration.priority == NarrationPriority::Assertive {
+                    let _ = synth.stop().await;
+                }
+                while synth.is_speaking() {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+                }
+                if narration.kind == NarrationKind::Focus && !narrator.is_latest(ticket) {
+                    return;
+                }
+                if let Err(e) = synth.speak(&narration.text).await {
+                    log::debug!("Could not speak narration: {}", e);
+                }
+            });
+        }
+        narration
+    }
+
     // Internal: Set state and emit event
     async fn set_state(&self, state: VoiceState) {
         let mut current = self.state.write().await;
@@ -377,6 +907,72 @@ impl Default for VoiceController {
     }
 }
 
+/// Spoken outcome of a sync
+fn sync_summary(result: SyncResult, is_danish: bool) -> String {
+    match (result, is_danish) {
+        (SyncResult::Success, true) => "Synkroniseringen er færdig.".to_string(),
+        (SyncResult::Success, false) => "Sync complete.".to_string(),
+        (SyncResult::PartialSuccess { errors }, true) => {
+            format!("Synkroniseringen er færdig med {} fejl.", errors.len())
+        }
+        (SyncResult::PartialSuccess { errors }, false) => {
+            format!("Sync finished with {} errors.", errors.len())
+        }
+        (SyncResult::Failed { error }, true) => format!("Synkroniseringen mislykkedes: {}", error),
+        (SyncResult::Failed { error }, false) => format!("Sync failed: {}", error),
+    }
+}
+
+/// Main voice loop: watches for the hotword until voice control is disabled
+async fn run_voice_loop(
+    config: Arc<RwLock<AccessibilityConfig>>,
+    state: Arc<RwLock<VoiceState>>,
+    detector: Arc<RwLock<HotwordDetector>>,
+    synthesizer: Arc<RwLock<SpeechSynthesizer>>,
+    event_tx: broadcast::Sender<AccessibilityEvent>,
+    privacy: Option<Arc<PrivacyMode>>,
+    heartbeat: Heartbeat,
+) {
+    // Set while the detector is stopped for privacy mode
+    let mut suspended = false;
+
+    loop {
+        heartbeat.beat(tokio::time::Duration::from_secs(10));
+        let config = config.read().await;
+        if !config.voice_enabled {
+            break;
+        }
+
+        // Release the microphone while privacy mode is active, take it back after
+        let private = privacy.as_ref().is_some_and(|privacy| privacy.is_active());
+        if private != suspended && config.continuous_listening {
+            let detector = detector.read().await;
+            let result = if private { detector.stop().await } else { detector.start().await };
+            match result {
+                Ok(()) => suspended = private,
+                Err(e) => log::warn!("Could not switch microphone for privacy mode: {}", e),
+            }
+        }
+
+        if config.continuous_listening && !suspended {
+            // Check for hotword
+            let detector = detector.read().await;
+            if detector.detected().await {
+                // Hotword detected - transition to listening state
+                let mut state = state.write().await;
+                *state = VoiceState::Listening;
+                let _ = event_tx.send(AccessibilityEvent::HotwordDetected);
+                if config.sound_feedback {
+                    let _ = synthesizer.read().await.play_cue(SoundCue::Hotword).await;
+                }
+            }
+        }
+
+        drop(config);
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -389,6 +985,100 @@ mod tests {
         assert!(matches!(state, VoiceState::Idle));
     }
 
+    #[tokio::test]
+    async fn test_narration_is_emitted_and_spoken_only_in_screen_reader_mode() {
+        let narrator = Arc::new(Narrator::new());
+        let controller = VoiceController::new(AccessibilityConfig::default()).with_narrator(narrator.clone());
+        let mut narrations = narrator.subscribe();
+
+        let event = UiEvent::Announcement { text: "Indstillinger gemt".to_string(), assertive: false };
+        let narration = controller.narrate_ui(event).await.unwrap();
+        assert!(!narration.spoken);
+        assert_eq!(narrations.recv().await.unwrap(), narration);
+    }
+
+    #[tokio::test]
+    async fn test_follow_up_question_is_answered_next_turn() {
+        let config = AccessibilityConfig {
+            auto_speak_responses: false,
+            sound_feedback: false,
+            ..AccessibilityConfig::default()
+        };
+        let controller = VoiceController::new(config);
+
+        let (question, awaiting) = controller.respond("opret opgave ring til mor").await.unwrap();
+        assert!(awaiting);
+        assert!(question.starts_with("Hvilken prioritet"));
+
+        let (response, awaiting) = controller.respond("kritisk").await.unwrap();
+        assert!(!awaiting);
+        assert_eq!(response, "Opretter opgave: ring til mor med kritisk prioritet.");
+    }
+
+    #[tokio::test]
+    async fn test_out_of_range_setting_is_spoken() {
+        let settings = Arc::new(RwLock::new(Settings::default()));
+        let controller = VoiceController::new(AccessibilityConfig::default())
+            .with_settings(settings.clone());
+
+        let before = settings.read().await.max_cpu_percent;
+        let response = controller
+            .execute_command(VoiceCommand::SetSetting {
+                setting: voice_settings::SettingKey::CpuLimit,
+                value: 95,
+            })
+            .await
+            .unwrap();
+
+        assert!(response.contains("mellem 10 og 80"));
+        assert_eq!(settings.read().await.max_cpu_percent, before);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_runs_recovery_handler() {
+        let controller = VoiceController::new(AccessibilityConfig::default());
+        *controller.pending_recovery.write().await = Some(PendingRecovery {
+            handler: Some(Box::new(|| Box::pin(async { Ok("Synkronisering gennemført.".to_string()) }))),
+        });
+
+        let response = controller.execute_command(VoiceCommand::Confirm).await.unwrap();
+        assert_eq!(response, "Synkronisering gennemført.");
+
+        let response = controller.execute_command(VoiceCommand::Confirm).await.unwrap();
+        assert_eq!(response, "Der er intet at bekræfte.");
+    }
+
+    #[tokio::test]
+    async fn test_ckc_errors_are_retried_with_a_sync() {
+        let settings = Arc::new(RwLock::new(Settings::default()));
+        let endpoint = settings.read().await.ckc_endpoint.clone().unwrap();
+        let mut controller = VoiceController::new(AccessibilityConfig::default()).with_settings(settings);
+        controller.set_sync_handler(Arc::new(|| Box::pin(async { SyncResult::Success })));
+
+        let ckc = ClaError::Network(NetworkError::Timeout { url: format!("{}/api/cla/sync", endpoint), timeout_ms: 30000 });
+        *controller.pending_recovery.write().await = Some(PendingRecovery {
+            handler: controller.recovery_for(&ckc).await,
+        });
+        let response = controller.execute_command(VoiceCommand::Confirm).await.unwrap();
+        assert_eq!(response, "Synkroniseringen er færdig.");
+
+        let other = ClaError::Network(NetworkError::Timeout { url: "https://api.github.com".to_string(), timeout_ms: 30000 });
+        assert!(controller.recovery_for(&other).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_privacy_mode_blocks_listening() {
+        let privacy = Arc::new(PrivacyMode::new());
+        let controller = VoiceController::new(AccessibilityConfig::default()).with_privacy(privacy.clone());
+
+        controller.execute_command(VoiceCommand::SetPrivacyMode { enabled: true }).await.unwrap();
+        assert!(privacy.is_active());
+        assert!(controller.listen_now().await.unwrap_err().contains("privat tilstand"));
+
+        controller.execute_command(VoiceCommand::SetPrivacyMode { enabled: false }).await.unwrap();
+        assert!(!privacy.is_active());
+    }
+
     #[tokio::test]
     async fn test_execute_help_command() {
         let controller = VoiceController::new(AccessibilityConfig::default());
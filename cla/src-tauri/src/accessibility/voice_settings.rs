@@ -0,0 +1,179 @@
+// Voice Settings - Applies settings changes spoken by the user
+// Validates against allowed ranges and persists through the settings service
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::settings::{apply_settings_update, persist_settings, SettingsUpdate};
+use crate::models::Settings;
+
+/// Numeric setting adjustable by voice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SettingKey {
+    CpuLimit,
+    RamLimit,
+    GpuLimit,
+    MinBattery,
+    /// Seconds of inactivity before work starts
+    IdleThreshold,
+    /// Minutes between syncs
+    SyncInterval,
+}
+
+/// On/off setting toggled by voice
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SettingToggle {
+    /// Synchronisation with CKC (inverse of offline mode)
+    Sync,
+    Transcription,
+    Ocr,
+    Embeddings,
+    RunOnBattery,
+}
+
+impl SettingKey {
+    /// Allowed range for the setting
+    pub fn range(&self) -> (u32, u32) {
+        match self {
+            SettingKey::CpuLimit => (10, 80),
+            SettingKey::RamLimit => (10, 50),
+            SettingKey::GpuLimit => (0, 80),
+            SettingKey::MinBattery => (5, 100),
+            SettingKey::IdleThreshold => (30, 3600),
+            SettingKey::SyncInterval => (5, 1440),
+        }
+    }
+
+    /// Spoken name and unit
+    fn describe(&self, is_danish: bool) -> (&'static str, &'static str) {
+        match (self, is_danish) {
+            (SettingKey::CpuLimit, true) => ("CPU-grænsen", "procent"),
+            (SettingKey::CpuLimit, false) => ("The CPU limit", "percent"),
+            (SettingKey::RamLimit, true) => ("RAM-grænsen", "procent"),
+            (SettingKey::RamLimit, false) => ("The RAM limit", "percent"),
+            (SettingKey::GpuLimit, true) => ("GPU-grænsen", "procent"),
+            (SettingKey::GpuLimit, false) => ("The GPU limit", "percent"),
+            (SettingKey::MinBattery, true) => ("Batterigrænsen", "procent"),
+            (SettingKey::MinBattery, false) => ("The battery limit", "percent"),
+            (SettingKey::IdleThreshold, true) => ("Inaktivitetstærsklen", "sekunder"),
+            (SettingKey::IdleThreshold, false) => ("The idle threshold", "seconds"),
+            (SettingKey::SyncInterval, true) => ("Synkroniseringsintervallet", "minutter"),
+            (SettingKey::SyncInterval, false) => ("The sync interval", "minutes"),
+        }
+    }
+}
+
+impl SettingToggle {
+    pub(crate) fn describe(&self, is_danish: bool) -> &'static str {
+        match (self, is_danish) {
+            (SettingToggle::Sync, true) => "Synkronisering",
+            (SettingToggle::Sync, false) => "Sync",
+            (SettingToggle::Transcription, true) => "Transskription",
+            (SettingToggle::Transcription, false) => "Transcription",
+            (SettingToggle::Ocr, true) => "Tekstgenkendelse",
+            (SettingToggle::Ocr, false) => "Text recognition",
+            (SettingToggle::Embeddings, true) => "Embeddings",
+            (SettingToggle::Embeddings, false) => "Embeddings",
+            (SettingToggle::RunOnBattery, true) => "Kørsel på batteri",
+            (SettingToggle::RunOnBattery, false) => "Running on battery",
+        }
+    }
+}
+
+/// Validate a spoken value against the allowed range
+pub fn validate(setting: SettingKey, value: u32, is_danish: bool) -> Result<(), String> {
+    let (min, max) = setting.range();
+    if (min..=max).contains(&value) {
+        return Ok(());
+    }
+
+    let (name, unit) = setting.describe(is_danish);
+    Err(if is_danish {
+        format!("{} skal være mellem {} og {} {}.", name, min, max, unit)
+    } else {
+        format!("{} must be between {} and {} {}.", name, min, max, unit)
+    })
+}
+
+/// Settings update for a numeric setting
+fn value_update(setting: SettingKey, value: u32) -> SettingsUpdate {
+    let mut update = SettingsUpdate::default();
+    match setting {
+        SettingKey::CpuLimit => update.max_cpu_percent = Some(value as u8),
+        SettingKey::RamLimit => update.max_ram_percent = Some(value as u8),
+        SettingKey::GpuLimit => update.max_gpu_percent = Some(value as u8),
+        SettingKey::MinBattery => update.min_battery_percent = Some(value as u8),
+        SettingKey::IdleThreshold => update.idle_threshold_seconds = Some(value),
+        SettingKey::SyncInterval => update.sync_interval_minutes = Some(value),
+    }
+    update
+}
+
+/// Settings update for a toggle
+fn toggle_update(setting: SettingToggle, enabled: bool) -> SettingsUpdate {
+    let mut update = SettingsUpdate::default();
+    match setting {
+        SettingToggle::Sync => update.offline_mode = Some(!enabled),
+        SettingToggle::Transcription => update.enable_transcription = Some(enabled),
+        SettingToggle::Ocr => update.enable_ocr = Some(enabled),
+        SettingToggle::Embeddings => update.enable_embeddings = Some(enabled),
+        SettingToggle::RunOnBattery => update.run_on_battery = Some(enabled),
+    }
+    update
+}
+
+/// Validate, apply and persist a numeric setting; returns the spoken confirmation
+pub async fn set_value(
+    settings: &mut Settings,
+    setting: SettingKey,
+    value: u32,
+    is_danish: bool,
+) -> Result<String, String> {
+    validate(setting, value, is_danish)?;
+    apply_settings_update(settings, value_update(setting, value))?;
+    persist_settings(settings).await?;
+
+    let (name, unit) = setting.describe(is_danish);
+    Ok(if is_danish {
+        format!("{} er sat til {} {}.", name, value, unit)
+    } else {
+        format!("{} is set to {} {}.", name, value, unit)
+    })
+}
+
+/// Apply and persist a toggle; returns the spoken confirmation
+pub async fn set_toggle(
+    settings: &mut Settings,
+    setting: SettingToggle,
+    enabled: bool,
+    is_danish: bool,
+) -> Result<String, String> {
+    apply_settings_update(settings, toggle_update(setting, enabled))?;
+    persist_settings(settings).await?;
+
+    let name = setting.describe(is_danish);
+    Ok(match (is_danish, enabled) {
+        (true, true) => format!("{} er slået til.", name),
+        (true, false) => format!("{} er slået fra.", name),
+        (false, true) => format!("{} is turned on.", name),
+        (false, false) => format!("{} is turned off.", name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_range() {
+        assert!(validate(SettingKey::CpuLimit, 50, true).is_ok());
+        let err = validate(SettingKey::CpuLimit, 95, true).unwrap_err();
+        assert_eq!(err, "CPU-grænsen skal være mellem 10 og 80 procent.");
+        assert!(validate(SettingKey::SyncInterval, 2, false).is_err());
+    }
+
+    #[test]
+    fn test_sync_toggle_maps_to_offline_mode() {
+        let update = toggle_update(SettingToggle::Sync, false);
+        assert_eq!(update.offline_mode, Some(true));
+    }
+}
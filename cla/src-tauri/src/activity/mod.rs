@@ -0,0 +1,173 @@
+// Activity Log - User-facing timeline of what the agent did and why
+// Local only; never part of telemetry reports
+
+use chrono::{DateTime, Duration, Utc};
+use crate::storage::JournaledFile;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+/// Entries kept; the oldest are dropped first
+const MAX_ENTRIES: usize = 2_000;
+
+/// Entries older than this are dropped
+const MAX_AGE_DAYS: i64 = 30;
+
+/// Area of the agent an entry belongs to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityCategory {
+    Sync,
+    Inference,
+    Research,
+    Commander,
+    System,
+}
+
+/// One thing the agent did
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub id: String,
+    pub at: DateTime<Utc>,
+    pub category: ActivityCategory,
+    /// What happened, e.g. "Synkroniserede 12 elementer"
+    pub summary: String,
+    /// Why it happened, when not obvious from the summary
+    pub reason: Option<String>,
+}
+
+/// Time window of a query; open ends are unbounded
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ActivityRange {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl ActivityRange {
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| at >= from) && self.to.is_none_or(|to| at < to)
+    }
+}
+
+/// Persisted activity timeline
+pub struct ActivityLog {
+    file: JournaledFile,
+    entries: RwLock<VecDeque<ActivityEntry>>,
+}
+
+impl ActivityLog {
+    /// Open a log, loading existing entries from disk
+    pub fn new(path: PathBuf) -> Self {
+        let file = JournaledFile::new("activity", path);
+        // A damaged log is moved aside and reported; start with an empty timeline
+        let entries = file.load().ok().flatten().unwrap_or_default();
+
+        Self {
+            file,
+            entries: RwLock::new(entries),
+        }
+    }
+
+    /// Record an activity
+    pub async fn record(&self, category: ActivityCategory, summary: impl Into<String>, reason: Option<&str>) {
+        self.record_at(category, summary.into(), reason, Utc::now()).await;
+    }
+
+    async fn record_at(&self, category: ActivityCategory, summary: String, reason: Option<&str>, at: DateTime<Utc>) {
+        let mut entries = self.entries.write().await;
+        entries.push_back(ActivityEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            at,
+            category,
+            summary,
+            reason: reason.map(|r| r.to_string()),
+        });
+
+        let cutoff = at - Duration::days(MAX_AGE_DAYS);
+        while entries.front().is_some_and(|e| e.at < cutoff) || entries.len() > MAX_ENTRIES {
+            entries.pop_front();
+        }
+
+        if let Err(e) = self.persist(&entries) {
+            log::warn!("Failed to persist activity log: {}", e);
+        }
+    }
+
+    /// Entries within `range`, optionally of one category, newest first
+    pub async fn query(&self, range: &ActivityRange, category: Option<ActivityCategory>) -> Vec<ActivityEntry> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|e| range.contains(e.at) && category.is_none_or(|c| e.category == c))
+            .cloned()
+            .collect()
+    }
+
+    fn persist(&self, entries: &VecDeque<ActivityEntry>) -> Result<(), String> {
+        let json = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+        self.file.write(json.as_bytes()).map_err(|e| e.to_string())
+    }
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self::new(
+            dirs::data_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("activity.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_query_filters_range_and_category() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("activity.json");
+        let log = ActivityLog::new(path.clone());
+        let now = Utc::now();
+        log.record_at(ActivityCategory::Sync, "Synkroniserede 12 elementer".into(), None, now - Duration::hours(3))
+            .await;
+        log.record_at(ActivityCategory::Inference, "Transskriberede audio.wav".into(), None, now - Duration::hours(2))
+            .await;
+        log.record_at(ActivityCategory::Sync, "Synkroniserede 3 elementer".into(), None, now - Duration::hours(1))
+            .await;
+
+        let syncs = log.query(&ActivityRange::default(), Some(ActivityCategory::Sync)).await;
+        assert_eq!(syncs.len(), 2);
+        assert_eq!(syncs[0].summary, "Synkroniserede 3 elementer");
+
+        let range = ActivityRange {
+            from: Some(now - Duration::minutes(150)),
+            to: Some(now - Duration::minutes(30)),
+        };
+        let window = log.query(&range, None).await;
+        assert_eq!(window.len(), 2);
+        assert_eq!(window[1].category, ActivityCategory::Inference);
+
+        // Entries survive a restart
+        let reopened = ActivityLog::new(path);
+        assert_eq!(reopened.query(&ActivityRange::default(), None).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retention_drops_old_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(dir.path().join("activity.json"));
+        let now = Utc::now();
+        log.record_at(ActivityCategory::System, "Gammel".into(), None, now - Duration::days(MAX_AGE_DAYS + 1))
+            .await;
+        log.record_at(ActivityCategory::System, "Ny".into(), Some("Test"), now).await;
+
+        let entries = log.query(&ActivityRange::default(), None).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].reason.as_deref(), Some("Test"));
+    }
+}
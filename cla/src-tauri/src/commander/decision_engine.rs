@@ -1,7 +1,9 @@
 // Decision Engine - Autonomous decision-making for Commander Unit
 
+use super::rules::{DecisionRules, RulesFile};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
 
 /// Signal types that the Commander can receive
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -89,12 +91,9 @@ pub struct Decision {
     pub rationale: String,
     pub timestamp: DateTime<Utc>,
     pub requires_approval: bool,
-}
-
-/// Decision rule for matching signals
-struct Rule {
-    condition: Box<dyn Fn(&SignalContext) -> bool + Send + Sync>,
-    action: Action,
+    /// The signal the decision was made from, kept for the audit log
+    #[serde(default)]
+    pub signal: Option<Signal>,
 }
 
 /// Context for evaluating rules
@@ -106,12 +105,38 @@ pub struct SignalContext {
 
 /// The Decision Engine - OODA loop implementation
 pub struct DecisionEngine {
-    // In real implementation, would have ML model and rules DB
+    rules: RwLock<DecisionRules>,
+    rules_file: Option<RulesFile>,
 }
 
 impl DecisionEngine {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            rules: RwLock::new(DecisionRules::default()),
+            rules_file: None,
+        }
+    }
+
+    /// Decide with the rules in a user-editable file, reloaded when it changes
+    pub fn with_rules_file(mut self, file: RulesFile) -> Self {
+        self.rules_file = Some(file);
+        self
+    }
+
+    /// The rules in use
+    pub async fn rules(&self) -> DecisionRules {
+        self.reload_rules().await;
+        self.rules.read().await.clone()
+    }
+
+    /// Validate, save and start using new rules
+    pub async fn update_rules(&self, rules: DecisionRules) -> Result<(), String> {
+        rules.validate()?;
+        if let Some(file) = &self.rules_file {
+            file.save(&rules)?;
+        }
+        *self.rules.write().await = rules;
+        Ok(())
     }
 
     /// Process a signal and return a decision (OODA: Observe-Orient-Decide-Act)
@@ -123,7 +148,7 @@ impl DecisionEngine {
         let signal_type = self.get_signal_type(&signal);
 
         // DECIDE: Apply decision rules
-        let (action, confidence) = self.apply_rules(&signal, &context);
+        let (action, confidence) = self.apply_rules(&signal_type, &signal, &context).await;
 
         // Create decision
         let decision = Decision {
@@ -134,6 +159,7 @@ impl DecisionEngine {
             rationale: self.generate_rationale(&signal, &action),
             timestamp: Utc::now(),
             requires_approval: self.requires_approval(&action, confidence),
+            signal: Some(signal),
         };
 
         // Log decision
@@ -190,47 +216,20 @@ impl DecisionEngine {
     }
 
     /// Apply decision rules
-    fn apply_rules(&self, signal: &Signal, context: &SignalContext) -> (Action, f32) {
-        match signal {
-            Signal::NewTechnologyDetected { relevance_score, .. } => {
-                if *relevance_score > 0.8 {
-                    (Action::DeepAnalyze, 0.9)
-                } else if *relevance_score > 0.5 {
-                    (Action::QueueForReview, 0.7)
-                } else {
-                    (Action::Archive, 0.8)
-                }
-            }
-            Signal::SecurityVulnerability { severity, .. } => {
-                match severity {
-                    Severity::Critical => (Action::ImmediateAlert, 0.95),
-                    Severity::High => (Action::DeepAnalyze, 0.85),
-                    _ => (Action::StandardProcess, 0.7),
-                }
-            }
-            Signal::MarketSignal { confidence, .. } => {
-                if *confidence > 0.9 {
-                    (Action::RecommendAction, 0.85)
-                } else if *confidence > 0.7 {
-                    (Action::RequestValidation, 0.75)
-                } else {
-                    (Action::Monitor, 0.6)
-                }
-            }
-            Signal::ResearchPublished { relevance_score, .. } => {
-                if *relevance_score > 0.7 {
-                    (Action::DeepAnalyze, 0.8)
-                } else {
-                    (Action::Archive, 0.7)
-                }
-            }
-            Signal::SocialTrend { momentum, .. } => {
-                if *momentum > 0.8 {
-                    (Action::DeepAnalyze, 0.7)
-                } else {
-                    (Action::Monitor, 0.6)
-                }
-            }
+    async fn apply_rules(&self, signal_type: &str, signal: &Signal, context: &SignalContext) -> (Action, f32) {
+        self.reload_rules().await;
+        let rules = self.rules.read().await;
+        match rules.matching(signal_type, signal_score(signal), context.severity.as_ref()) {
+            Some(rule) => (rule.action.clone(), rule.confidence),
+            // No rule covers the signal, so let the user look at it
+            None => (Action::QueueForReview, 0.5),
+        }
+    }
+
+    /// Pick up edits to the rules file
+    async fn reload_rules(&self) {
+        if let Some(rules) = self.rules_file.as_ref().and_then(RulesFile::changed) {
+            *self.rules.write().await = rules;
         }
     }
 
@@ -265,6 +264,17 @@ impl DecisionEngine {
     }
 }
 
+/// Score decision rules compare against; security signals have a severity instead
+fn signal_score(signal: &Signal) -> Option<f32> {
+    match signal {
+        Signal::NewTechnologyDetected { relevance_score, .. } => Some(*relevance_score),
+        Signal::SecurityVulnerability { .. } => None,
+        Signal::MarketSignal { confidence, .. } => Some(*confidence),
+        Signal::ResearchPublished { relevance_score, .. } => Some(*relevance_score),
+        Signal::SocialTrend { momentum, .. } => Some(*momentum),
+    }
+}
+
 impl Default for DecisionEngine {
     fn default() -> Self {
         Self::new()
@@ -5,11 +5,15 @@ pub mod unit;
 pub mod decision_engine;
 pub mod task_scheduler;
 pub mod sync;
+pub mod policy;
+pub mod rules;
 
 pub use unit::CommanderUnit;
 pub use decision_engine::{DecisionEngine, Decision, Action, Signal};
 pub use task_scheduler::{TaskScheduler, ResearchTask, TaskPriority};
 pub use sync::CkcSync;
+pub use policy::AutonomyPolicy;
+pub use rules::DecisionRules;
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
@@ -18,6 +22,8 @@ use chrono::{DateTime, Utc};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommanderStatus {
     pub is_running: bool,
+    #[serde(default)]
+    pub is_paused: bool,
     pub uptime_seconds: u64,
     pub tasks_completed: u64,
     pub tasks_pending: u64,
@@ -30,6 +36,7 @@ impl Default for CommanderStatus {
     fn default() -> Self {
         Self {
             is_running: false,
+            is_paused: false,
             uptime_seconds: 0,
             tasks_completed: 0,
             tasks_pending: 0,
@@ -74,16 +81,23 @@ pub struct ResearchFinding {
     pub tags: Vec<String>,
     pub url: Option<String>,
     pub metadata: serde_json::Value,
+    /// How the relevance score was composed (set by RelevanceScorer)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<crate::research::processors::ScoreBreakdown>,
+    /// Scorer version that produced `relevance_score`; None for unscored findings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scorer_version: Option<u32>,
 }
 
 /// Research source type
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum ResearchSource {
     GitHub,
     ArXiv,
     Twitter,
     Farcaster,
     LensProtocol,
+    Reddit,
     CustomFeed(String),
 }
 
@@ -99,6 +113,60 @@ pub struct CommanderConfig {
     pub alert_on_critical: bool,
     pub sync_to_cosmic_library: bool,
     pub offline_mode_enabled: bool,
+    /// Per-source overrides of the scan interval, active hours and result limit
+    #[serde(default = "default_source_schedules")]
+    pub source_schedules: Vec<SourceSchedule>,
+    /// Window and thresholds for keyword trends across scans
+    #[serde(default)]
+    pub trend: crate::research::processors::TrendConfig,
+    /// Keep local copies of high-relevance findings' pages for offline reading;
+    /// off unless the user opts in, since it downloads and stores every page
+    #[serde(default)]
+    pub archive_findings: bool,
+}
+
+/// Scheduling policy for a single research source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceSchedule {
+    pub source: ResearchSource,
+    /// Minutes between scans (falls back to scan_interval_minutes)
+    #[serde(default)]
+    pub scan_interval_minutes: Option<u32>,
+    /// Local hours [start, end) in which scans may run; wraps past midnight
+    #[serde(default)]
+    pub active_hours: Option<(u8, u8)>,
+    /// Maximum results per scan
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Seconds the source's adapter may take per search
+    #[serde(default)]
+    pub timeout_seconds: Option<u32>,
+}
+
+impl SourceSchedule {
+    pub fn new(source: ResearchSource) -> Self {
+        Self {
+            source,
+            scan_interval_minutes: None,
+            active_hours: None,
+            max_results: None,
+            timeout_seconds: None,
+        }
+    }
+}
+
+/// arXiv publishes daily, GitHub trending changes hourly
+fn default_source_schedules() -> Vec<SourceSchedule> {
+    vec![
+        SourceSchedule {
+            scan_interval_minutes: Some(24 * 60),
+            ..SourceSchedule::new(ResearchSource::ArXiv)
+        },
+        SourceSchedule {
+            scan_interval_minutes: Some(60),
+            ..SourceSchedule::new(ResearchSource::GitHub)
+        },
+    ]
 }
 
 impl Default for CommanderConfig {
@@ -113,6 +181,9 @@ impl Default for CommanderConfig {
             alert_on_critical: true,
             sync_to_cosmic_library: true,
             offline_mode_enabled: true,
+            source_schedules: default_source_schedules(),
+            trend: Default::default(),
+            archive_findings: false,
         }
     }
 }
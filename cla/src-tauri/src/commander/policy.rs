@@ -0,0 +1,444 @@
+// Autonomy Policy - Enforces AutonomyLevel before decisions are executed
+// Denied decisions are logged and routed to the approval queue; with a decision
+// log every verdict and approval transition is also kept in the local database
+
+use super::{Action, AutonomyLevel, Decision, ResearchFinding};
+use crate::storage::LocalDatabase;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::{broadcast, RwLock};
+
+/// Risk class of an action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RiskClass {
+    /// No side effects (archive, monitor)
+    Safe = 0,
+    /// Local bookkeeping or user notification
+    Low = 1,
+    /// Consumes significant resources or network
+    Medium = 2,
+    /// Acts on the user's behalf
+    High = 3,
+}
+
+impl RiskClass {
+    /// Risk class of an action
+    pub fn of(action: &Action) -> Self {
+        match action {
+            Action::Archive | Action::Monitor => RiskClass::Safe,
+            Action::StandardProcess
+            | Action::QueueForReview
+            | Action::RequestValidation
+            | Action::ImmediateAlert => RiskClass::Low,
+            Action::DeepAnalyze => RiskClass::Medium,
+            Action::RecommendAction => RiskClass::High,
+        }
+    }
+
+    /// Highest risk class an autonomy level may execute without approval
+    pub fn max_for(level: &AutonomyLevel) -> Self {
+        match level {
+            AutonomyLevel::Supervised => RiskClass::Safe,
+            AutonomyLevel::Assisted => RiskClass::Low,
+            AutonomyLevel::Autonomous => RiskClass::Medium,
+            AutonomyLevel::FullAutonomy => RiskClass::High,
+        }
+    }
+}
+
+/// Outcome of a policy check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum PolicyVerdict {
+    Allowed,
+    Denied { reason: String },
+}
+
+/// What a decision acts on, parked with it until the user approves
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DecisionWork {
+    /// Research topic of the task that produced the signal
+    pub topic: String,
+    /// Finding to deep-analyze; held back from the frontend, which has it already
+    #[serde(skip)]
+    pub finding: Option<ResearchFinding>,
+}
+
+/// A denied decision waiting for user approval
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub decision: Decision,
+    pub risk_class: RiskClass,
+    pub reason: String,
+    pub queued_at: DateTime<Utc>,
+    #[serde(default)]
+    pub work: DecisionWork,
+}
+
+/// Change to the approval queue, sent to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ApprovalEvent {
+    Queued { approval: Box<PendingApproval> },
+    /// Approved, rejected or expired; the decision left the queue
+    Resolved {
+        decision_id: String,
+        state: ApprovalState,
+        reason: Option<String>,
+    },
+}
+
+/// Where a logged decision stands
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalState {
+    /// Allowed by the policy and carried out without asking
+    AutoExecuted,
+    /// Denied by the policy, waiting in the approval queue
+    Pending,
+    Approved,
+    Rejected,
+    /// Pushed out of a full approval queue, or left behind by a restart, before the user decided
+    Expired,
+}
+
+/// One change of a decision's approval state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalTransition {
+    pub state: ApprovalState,
+    pub reason: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// A decision from the audit log with its approval history, oldest transition first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub decision: Decision,
+    pub state: ApprovalState,
+    pub transitions: Vec<ApprovalTransition>,
+}
+
+/// Which logged decisions to return; empty fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DecisionFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub action: Option<Action>,
+    pub state: Option<ApprovalState>,
+    pub signal_type: Option<String>,
+    /// Defaults to 100
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+/// Approval queue summary for the policy status command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolicyStatus {
+    /// Decisions waiting for approval
+    pub pending_approvals: usize,
+    /// Decisions denied since the Commander started
+    pub denials: u64,
+}
+
+/// Policy middleware between the DecisionEngine and action execution
+pub struct AutonomyPolicy {
+    approval_queue: RwLock<VecDeque<PendingApproval>>,
+    max_pending: usize,
+    denials: AtomicU64,
+    decision_log: Option<Arc<LocalDatabase>>,
+    event_tx: broadcast::Sender<ApprovalEvent>,
+}
+
+impl AutonomyPolicy {
+    pub fn new() -> Self {
+        Self {
+            approval_queue: RwLock::new(VecDeque::new()),
+            max_pending: 100,
+            denials: AtomicU64::new(0),
+            decision_log: None,
+            event_tx: broadcast::channel(32).0,
+        }
+    }
+
+    /// Keep every decision and approval transition in the database
+    pub fn with_decision_log(mut self, database: Arc<LocalDatabase>) -> Self {
+        self.decision_log = Some(database);
+        self.expire_orphaned_approvals();
+        self
+    }
+
+    /// The approval queue only lives in memory, so decisions still pending in the
+    /// log were left behind by an earlier run; their work is gone and they can
+    /// never be approved, so the log records them as expired
+    fn expire_orphaned_approvals(&self) {
+        let Some(database) = &self.decision_log else {
+            return;
+        };
+        let filter = DecisionFilter {
+            state: Some(ApprovalState::Pending),
+            ..Default::default()
+        };
+        loop {
+            let orphaned = match database.list_decisions(&filter) {
+                Ok(records) => records,
+                Err(e) => {
+                    log::warn!("Could not list pending decisions: {}", e);
+                    return;
+                }
+            };
+            if orphaned.is_empty() {
+                return;
+            }
+            for record in orphaned {
+                if let Err(e) = database.record_approval_transition(
+                    &record.decision.id,
+                    ApprovalState::Expired,
+                    Some("Agent restarted before approval"),
+                ) {
+                    // Stop rather than list the same decisions again
+                    log::warn!("Could not expire decision {}: {}", record.decision.id, e);
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Check a decision against an autonomy level (pure, no side effects)
+    pub fn evaluate(decision: &Decision, level: &AutonomyLevel) -> PolicyVerdict {
+        let risk = RiskClass::of(&decision.action);
+        let max = RiskClass::max_for(level);
+
+        if risk > max {
+            return PolicyVerdict::Denied {
+                reason: format!(
+                    "{:?} is {:?} risk, {:?} allows up to {:?}",
+                    decision.action, risk, level, max
+                ),
+            };
+        }
+
+        // Below full autonomy, the engine's own approval flag still applies
+        if decision.requires_approval && *level != AutonomyLevel::FullAutonomy && risk > RiskClass::Safe {
+            return PolicyVerdict::Denied {
+                reason: format!("{:?} requires approval at {:?}", decision.action, level),
+            };
+        }
+
+        PolicyVerdict::Allowed
+    }
+
+    /// Receive approval queue changes
+    pub fn subscribe(&self) -> broadcast::Receiver<ApprovalEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Check a decision, parking it with its work for approval if denied
+    pub async fn enforce(&self, decision: &Decision, level: &AutonomyLevel, work: &DecisionWork) -> PolicyVerdict {
+        let verdict = Self::evaluate(decision, level);
+
+        match &verdict {
+            PolicyVerdict::Allowed => self.log_decision(decision, ApprovalState::AutoExecuted, None),
+            PolicyVerdict::Denied { reason } => {
+                log::warn!("Policy denied decision {}: {}", decision.id, reason);
+                self.denials.fetch_add(1, Ordering::Relaxed);
+                self.log_decision(decision, ApprovalState::Pending, Some(reason));
+
+                let mut queue = self.approval_queue.write().await;
+                if queue.len() >= self.max_pending {
+                    if let Some(expired) = queue.pop_front() {
+                        self.transition(&expired.decision.id, ApprovalState::Expired, Some("Approval queue was full"));
+                    }
+                }
+                let approval = PendingApproval {
+                    decision: decision.clone(),
+                    risk_class: RiskClass::of(&decision.action),
+                    reason: reason.clone(),
+                    queued_at: Utc::now(),
+                    work: work.clone(),
+                };
+                queue.push_back(approval.clone());
+                let _ = self.event_tx.send(ApprovalEvent::Queued {
+                    approval: Box::new(approval),
+                });
+            }
+        }
+
+        verdict
+    }
+
+    /// Take a decision out of the approval queue with the user's verdict and reason;
+    /// None if it is not waiting for approval
+    pub async fn resolve(&self, decision_id: &str, approved: bool, reason: Option<&str>) -> Option<PendingApproval> {
+        let mut queue = self.approval_queue.write().await;
+        let index = queue.iter().position(|p| p.decision.id == decision_id)?;
+        let pending = queue.remove(index)?;
+        let state = if approved { ApprovalState::Approved } else { ApprovalState::Rejected };
+        self.transition(decision_id, state, reason);
+        Some(pending)
+    }
+
+    /// Decisions waiting for approval, oldest first
+    pub async fn pending_approvals(&self) -> Vec<PendingApproval> {
+        self.approval_queue.read().await.iter().cloned().collect()
+    }
+
+    /// Total number of denied decisions
+    pub fn denial_count(&self) -> u64 {
+        self.denials.load(Ordering::Relaxed)
+    }
+
+    /// Approval queue size and denials so far
+    pub async fn status(&self) -> PolicyStatus {
+        PolicyStatus {
+            pending_approvals: self.approval_queue.read().await.len(),
+            denials: self.denial_count(),
+        }
+    }
+
+    /// A lost audit entry must not stop the decision itself, so failures are only logged
+    fn log_decision(&self, decision: &Decision, state: ApprovalState, reason: Option<&str>) {
+        if let Some(database) = &self.decision_log {
+            if let Err(e) = database.record_decision(decision, state, reason) {
+                log::warn!("Could not log decision {}: {}", decision.id, e);
+            }
+        }
+    }
+
+    /// Log a decision leaving the queue and tell subscribers
+    fn transition(&self, decision_id: &str, state: ApprovalState, reason: Option<&str>) {
+        let _ = self.event_tx.send(ApprovalEvent::Resolved {
+            decision_id: decision_id.to_string(),
+            state,
+            reason: reason.map(str::to_string),
+        });
+        if let Some(database) = &self.decision_log {
+            if let Err(e) = database.record_approval_transition(decision_id, state, reason) {
+                log::warn!("Could not log {:?} for decision {}: {}", state, decision_id, e);
+            }
+        }
+    }
+}
+
+impl Default for AutonomyPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(action: Action, requires_approval: bool) -> Decision {
+        Decision {
+            id: "d-1".to_string(),
+            signal_type: "test".to_string(),
+            action,
+            confidence: 0.9,
+            rationale: String::new(),
+            timestamp: Utc::now(),
+            requires_approval,
+            signal: None,
+        }
+    }
+
+    #[test]
+    fn test_supervised_only_allows_safe_actions() {
+        let level = AutonomyLevel::Supervised;
+        assert_eq!(
+            AutonomyPolicy::evaluate(&decision(Action::Archive, false), &level),
+            PolicyVerdict::Allowed
+        );
+        assert!(matches!(
+            AutonomyPolicy::evaluate(&decision(Action::DeepAnalyze, false), &level),
+            PolicyVerdict::Denied { .. }
+        ));
+    }
+
+    #[test]
+    fn test_full_autonomy_ignores_approval_flag() {
+        let d = decision(Action::RecommendAction, true);
+        assert_eq!(
+            AutonomyPolicy::evaluate(&d, &AutonomyLevel::FullAutonomy),
+            PolicyVerdict::Allowed
+        );
+        assert!(matches!(
+            AutonomyPolicy::evaluate(&d, &AutonomyLevel::Autonomous),
+            PolicyVerdict::Denied { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_denials_are_queued() {
+        let policy = AutonomyPolicy::new();
+        policy
+            .enforce(&decision(Action::DeepAnalyze, false), &AutonomyLevel::Assisted, &DecisionWork::default())
+            .await;
+
+        assert_eq!(policy.denial_count(), 1);
+        assert_eq!(policy.pending_approvals().await.len(), 1);
+        assert_eq!(
+            policy.status().await,
+            PolicyStatus {
+                pending_approvals: 1,
+                denials: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_verdicts_and_resolutions_are_logged() {
+        let database = Arc::new(LocalDatabase::in_memory(100));
+        let policy = AutonomyPolicy::new().with_decision_log(database.clone());
+        let mut allowed = decision(Action::Archive, false);
+        allowed.id = "d-allowed".to_string();
+        let mut denied = decision(Action::RecommendAction, true);
+        denied.id = "d-denied".to_string();
+        let mut events = policy.subscribe();
+        let work = DecisionWork {
+            topic: "agents".to_string(),
+            finding: None,
+        };
+        policy.enforce(&allowed, &AutonomyLevel::Assisted, &work).await;
+        policy.enforce(&denied, &AutonomyLevel::Assisted, &work).await;
+        assert!(matches!(events.try_recv(), Ok(ApprovalEvent::Queued { approval }) if approval.work.topic == "agents"));
+
+        assert_eq!(database.get_decision("d-allowed").unwrap().unwrap().state, ApprovalState::AutoExecuted);
+        assert!(policy.resolve("d-allowed", true, None).await.is_none());
+        assert!(policy.resolve("d-denied", false, Some("Ikke relevant")).await.is_some());
+        assert!(policy.pending_approvals().await.is_empty());
+        assert!(matches!(
+            events.try_recv(),
+            Ok(ApprovalEvent::Resolved { state: ApprovalState::Rejected, .. })
+        ));
+
+        let record = database.get_decision("d-denied").unwrap().unwrap();
+        assert_eq!(record.state, ApprovalState::Rejected);
+        let states: Vec<_> = record.transitions.iter().map(|t| t.state).collect();
+        assert_eq!(states, vec![ApprovalState::Pending, ApprovalState::Rejected]);
+        assert!(record.transitions[0].reason.is_some());
+        assert_eq!(record.transitions[1].reason.as_deref(), Some("Ikke relevant"));
+    }
+
+    #[tokio::test]
+    async fn test_pending_decisions_expire_on_restart() {
+        let database = Arc::new(LocalDatabase::in_memory(100));
+        let policy = AutonomyPolicy::new().with_decision_log(database.clone());
+        policy
+            .enforce(&decision(Action::DeepAnalyze, false), &AutonomyLevel::Assisted, &DecisionWork::default())
+            .await;
+        assert_eq!(database.get_decision("d-1").unwrap().unwrap().state, ApprovalState::Pending);
+
+        let restarted = AutonomyPolicy::new().with_decision_log(database.clone());
+        assert!(restarted.pending_approvals().await.is_empty());
+        let record = database.get_decision("d-1").unwrap().unwrap();
+        assert_eq!(record.state, ApprovalState::Expired);
+        assert_eq!(
+            record.transitions.last().unwrap().reason.as_deref(),
+            Some("Agent restarted before approval")
+        );
+    }
+}
@@ -0,0 +1,280 @@
+// Decision Rules - User-editable rules that map signals to Commander actions
+// The rules live in decision_rules.json in the config directory and are picked up
+// again whenever the file changes; the first rule that matches a signal decides
+
+use super::decision_engine::{Action, Severity};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Signal types rules can match, as named in `Decision::signal_type`
+pub const SIGNAL_TYPES: &[&str] = &[
+    "new_technology_detected",
+    "security_vulnerability",
+    "market_signal",
+    "research_published",
+    "social_trend",
+];
+
+/// Signal type without a score; its rules match on severity instead
+const SEVERITY_SIGNAL: &str = "security_vulnerability";
+
+const MAX_RULES: usize = 100;
+
+/// One rule: a signal type with optional score range and severities, and the action it leads to
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecisionRule {
+    pub signal_type: String,
+    /// Matches scores strictly above this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_above: Option<f32>,
+    /// Matches scores up to and including this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_at_most: Option<f32>,
+    /// Matches any of these severities; empty matches all
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub severities: Vec<Severity>,
+    pub action: Action,
+    /// Confidence given to decisions made by this rule
+    pub confidence: f32,
+}
+
+impl DecisionRule {
+    fn new(signal_type: &str, action: Action, confidence: f32) -> Self {
+        Self {
+            signal_type: signal_type.to_string(),
+            score_above: None,
+            score_at_most: None,
+            severities: Vec::new(),
+            action,
+            confidence,
+        }
+    }
+
+    fn above(mut self, score: f32) -> Self {
+        self.score_above = Some(score);
+        self
+    }
+
+    fn severities(mut self, severities: Vec<Severity>) -> Self {
+        self.severities = severities;
+        self
+    }
+
+    fn matches(&self, signal_type: &str, score: Option<f32>, severity: Option<&Severity>) -> bool {
+        if self.signal_type != signal_type {
+            return false;
+        }
+        if !self.severities.is_empty() && !severity.is_some_and(|s| self.severities.contains(s)) {
+            return false;
+        }
+        match score {
+            Some(score) => {
+                self.score_above.is_none_or(|above| score > above)
+                    && self.score_at_most.is_none_or(|at_most| score <= at_most)
+            }
+            None => self.score_above.is_none() && self.score_at_most.is_none(),
+        }
+    }
+}
+
+/// The ordered rule list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DecisionRules {
+    pub rules: Vec<DecisionRule>,
+}
+
+impl Default for DecisionRules {
+    fn default() -> Self {
+        Self {
+            rules: vec![
+                DecisionRule::new("new_technology_detected", Action::DeepAnalyze, 0.9).above(0.8),
+                DecisionRule::new("new_technology_detected", Action::QueueForReview, 0.7).above(0.5),
+                DecisionRule::new("new_technology_detected", Action::Archive, 0.8),
+                DecisionRule::new(SEVERITY_SIGNAL, Action::ImmediateAlert, 0.95).severities(vec![Severity::Critical]),
+                DecisionRule::new(SEVERITY_SIGNAL, Action::DeepAnalyze, 0.85).severities(vec![Severity::High]),
+                DecisionRule::new(SEVERITY_SIGNAL, Action::StandardProcess, 0.7),
+                DecisionRule::new("market_signal", Action::RecommendAction, 0.85).above(0.9),
+                DecisionRule::new("market_signal", Action::RequestValidation, 0.75).above(0.7),
+                DecisionRule::new("market_signal", Action::Monitor, 0.6),
+                DecisionRule::new("research_published", Action::DeepAnalyze, 0.8).above(0.7),
+                DecisionRule::new("research_published", Action::Archive, 0.7),
+                DecisionRule::new("social_trend", Action::DeepAnalyze, 0.7).above(0.8),
+                DecisionRule::new("social_trend", Action::Monitor, 0.6),
+            ],
+        }
+    }
+}
+
+impl DecisionRules {
+    /// First rule matching a signal; security signals have no score
+    pub fn matching(&self, signal_type: &str, score: Option<f32>, severity: Option<&Severity>) -> Option<&DecisionRule> {
+        self.rules.iter().find(|rule| rule.matches(signal_type, score, severity))
+    }
+
+    /// Check rules before they are used or saved
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rules.len() > MAX_RULES {
+            return Err(format!("Der må højst være {} regler", MAX_RULES));
+        }
+        for (index, rule) in self.rules.iter().enumerate() {
+            let number = index + 1;
+            if !SIGNAL_TYPES.contains(&rule.signal_type.as_str()) {
+                return Err(format!("Regel {}: ukendt signaltype \"{}\"", number, rule.signal_type));
+            }
+            if !(0.0..=1.0).contains(&rule.confidence) {
+                return Err(format!("Regel {}: tilliden skal være mellem 0 og 1", number));
+            }
+            let bounds = [rule.score_above, rule.score_at_most];
+            if bounds.iter().flatten().any(|bound| !(0.0..=1.0).contains(bound)) {
+                return Err(format!("Regel {}: scoregrænser skal være mellem 0 og 1", number));
+            }
+            if let (Some(above), Some(at_most)) = (rule.score_above, rule.score_at_most) {
+                if above >= at_most {
+                    return Err(format!("Regel {}: den nedre scoregrænse skal være under den øvre", number));
+                }
+            }
+            let severity_signal = rule.signal_type == SEVERITY_SIGNAL;
+            if severity_signal && bounds.iter().any(Option::is_some) {
+                return Err(format!("Regel {}: sikkerhedssignaler har ingen score, brug alvorlighed", number));
+            }
+            if !severity_signal && !rule.severities.is_empty() {
+                return Err(format!("Regel {}: kun sikkerhedssignaler har alvorlighed", number));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The rules file, read again when its modification time or size changes
+pub struct RulesFile {
+    path: PathBuf,
+    seen: Mutex<Option<(SystemTime, u64)>>,
+}
+
+impl RulesFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            seen: Mutex::new(None),
+        }
+    }
+
+    /// Default location, next to settings.json
+    pub fn default_path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cirkelline-cla")
+            .join("decision_rules.json")
+    }
+
+    /// Rules to use if the file changed since the last call. A removed file brings
+    /// back the defaults; an invalid one is ignored so the current rules stay.
+    pub fn changed(&self) -> Option<DecisionRules> {
+        let current = fs::metadata(&self.path)
+            .ok()
+            .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+        {
+            let mut seen = self.seen.lock().unwrap();
+            if *seen == current {
+                return None;
+            }
+            *seen = current;
+        }
+        if current.is_none() {
+            return Some(DecisionRules::default());
+        }
+
+        let rules = fs::read_to_string(&self.path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| serde_json::from_str::<DecisionRules>(&json).map_err(|e| e.to_string()))
+            .and_then(|rules| rules.validate().map(|_| rules));
+        match rules {
+            Ok(rules) => {
+                log::info!("Loaded {} decision rules from {:?}", rules.rules.len(), self.path);
+                Some(rules)
+            }
+            Err(e) => {
+                log::warn!("Ignoring decision rules in {:?}: {}", self.path, e);
+                None
+            }
+        }
+    }
+
+    /// Write the rules; the write is not reported back by `changed`
+    pub fn save(&self, rules: &DecisionRules) -> Result<(), String> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Kunne ikke oprette config-mappe: {}", e))?;
+        }
+        let json = serde_json::to_string_pretty(rules)
+            .map_err(|e| format!("Kunne ikke serialisere beslutningsregler: {}", e))?;
+        fs::write(&self.path, json).map_err(|e| format!("Kunne ikke gemme beslutningsregler: {}", e))?;
+        *self.seen.lock().unwrap() = fs::metadata(&self.path)
+            .ok()
+            .and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_rules_keep_builtin_thresholds() {
+        let rules = DecisionRules::default();
+        rules.validate().unwrap();
+        let action = |signal_type: &str, score: Option<f32>, severity: Option<Severity>| {
+            rules.matching(signal_type, score, severity.as_ref()).map(|rule| rule.action.clone())
+        };
+        assert_eq!(action("new_technology_detected", Some(0.8), None), Some(Action::QueueForReview));
+        assert_eq!(action("new_technology_detected", Some(0.81), None), Some(Action::DeepAnalyze));
+        assert_eq!(action("market_signal", Some(0.2), None), Some(Action::Monitor));
+        assert_eq!(action(SEVERITY_SIGNAL, None, Some(Severity::Critical)), Some(Action::ImmediateAlert));
+        assert_eq!(action(SEVERITY_SIGNAL, None, Some(Severity::Low)), Some(Action::StandardProcess));
+    }
+
+    #[test]
+    fn test_invalid_rules_are_rejected() {
+        let invalid = |rule: DecisionRule| DecisionRules { rules: vec![rule] }.validate().unwrap_err();
+        assert!(invalid(DecisionRule::new("weather", Action::Monitor, 0.5)).contains("ukendt signaltype"));
+        assert!(invalid(DecisionRule::new("market_signal", Action::Monitor, 1.5)).contains("tilliden"));
+        assert!(invalid(DecisionRule::new("market_signal", Action::Monitor, 0.5).above(f32::NAN)).contains("scoregrænser"));
+        assert!(invalid(DecisionRule::new(SEVERITY_SIGNAL, Action::Monitor, 0.5).above(0.2)).contains("ingen score"));
+        assert!(invalid(
+            DecisionRule::new("social_trend", Action::Monitor, 0.5).severities(vec![Severity::High])
+        )
+        .contains("alvorlighed"));
+
+        let mut reversed = DecisionRule::new("social_trend", Action::Monitor, 0.5).above(0.6);
+        reversed.score_at_most = Some(0.4);
+        assert!(invalid(reversed).contains("nedre scoregrænse"));
+    }
+
+    #[test]
+    fn test_file_changes_are_picked_up() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = RulesFile::new(dir.path().join("decision_rules.json"));
+        assert!(file.changed().is_none());
+
+        let custom = DecisionRules {
+            rules: vec![DecisionRule::new("social_trend", Action::Archive, 0.9)],
+        };
+        file.save(&custom).unwrap();
+        assert!(file.changed().is_none());
+
+        fs::write(dir.path().join("decision_rules.json"), r#"{"rules": [{"signal_type": "x"}]}"#).unwrap();
+        assert!(file.changed().is_none());
+        fs::write(
+            dir.path().join("decision_rules.json"),
+            r#"{"rules": [{"signal_type": "market_signal", "score_above": 0.3, "action": "Monitor", "confidence": 0.5}]}"#,
+        )
+        .unwrap();
+        assert_eq!(file.changed().unwrap().rules[0].score_above, Some(0.3));
+
+        fs::remove_file(dir.path().join("decision_rules.json")).unwrap();
+        assert_eq!(file.changed(), Some(DecisionRules::default()));
+    }
+}
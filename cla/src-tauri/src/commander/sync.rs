@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use tokio::sync::RwLock;
 use std::collections::VecDeque;
+use crate::research::http_cache::{self, HttpCacheStats};
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
 
 /// Sync configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -123,7 +125,7 @@ impl CkcSync {
             }
         };
 
-        match client.get(url).send().await {
+        match client.get(url).send_metered(NetworkSubsystem::Sync).await {
             Ok(response) => {
                 let success = response.status().is_success();
                 if success {
@@ -237,6 +239,7 @@ impl CkcSync {
             status: self.get_status().await,
             queue_size: self.get_queue_size().await,
             last_sync: self.get_last_sync().await,
+            http_cache: http_cache::cache().stats(),
         }
     }
 }
@@ -280,4 +283,6 @@ pub struct SyncStats {
     pub status: SyncStatus,
     pub queue_size: usize,
     pub last_sync: Option<DateTime<Utc>>,
+    /// Research adapters' conditional-request cache
+    pub http_cache: HttpCacheStats,
 }
@@ -1,10 +1,25 @@
 // Task Scheduler - Research task queue management
 
-use super::{ResearchFinding, ResearchSource, Signal};
+use super::{CommanderConfig, ResearchFinding, ResearchSource, Signal, SourceSchedule};
+use crate::research::adapters::CustomFeed;
+use crate::research::dedup::finding_hash;
+use crate::research::history::FindingsDiff;
+use crate::research::store::{FindingFilter, FindingRetention};
+use crate::research::traits::ResearchResult;
+use crate::inference::{embed_texts, InferenceEngine};
+use crate::research::{archive::ArchivedContent, feedback::LearnedScoring, processors::{embedding_text, RelevanceScorer, ScoreBreakdown, SemanticDedup, SignalProcessor, TrendConfig}, FindingArchive, FindingsDedup, FindingsHistory, ScoreFeedback};
+use crate::storage::LocalDatabase;
+use crate::telemetry::TelemetryService;
+use crate::utils::timebox::{report_overrun, run_timeboxed, Overrun, OverrunAction, TimeboxedWork};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
-use std::collections::VecDeque;
-use tokio::sync::RwLock;
+use chrono::{DateTime, Local, Timelike, Utc};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Notify, RwLock};
+
+/// Stored findings rescored per database query
+const RESCORE_PAGE: u32 = 200;
 
 /// Task priority levels
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
@@ -16,11 +31,25 @@ pub enum TaskPriority {
     Background = 0,
 }
 
+impl TaskPriority {
+    /// One level lower (Background stays Background)
+    pub fn demoted(self) -> Self {
+        match self {
+            Self::Critical => Self::High,
+            Self::High => Self::Normal,
+            Self::Normal => Self::Low,
+            Self::Low | Self::Background => Self::Background,
+        }
+    }
+}
+
 /// Task status
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
     Pending,
     Running,
+    /// Preempted by a foreground request, resumes when it finishes
+    Paused,
     Completed,
     Failed(String),
     Cancelled,
@@ -39,6 +68,9 @@ pub struct ResearchTask {
     pub completed_at: Option<DateTime<Utc>>,
     pub retry_count: u32,
     pub max_retries: u32,
+    /// Foreground tasks are user-initiated and never preempted
+    #[serde(default)]
+    pub foreground: bool,
 }
 
 impl ResearchTask {
@@ -54,6 +86,7 @@ impl ResearchTask {
             completed_at: None,
             retry_count: 0,
             max_retries: 3,
+            foreground: false,
         }
     }
 
@@ -61,12 +94,186 @@ impl ResearchTask {
         self.source = Some(source);
         self
     }
+
+    pub fn with_foreground(mut self) -> Self {
+        self.foreground = true;
+        self
+    }
+
+    /// Whether this task may be preempted by foreground work
+    pub fn is_preemptible(&self) -> bool {
+        !self.foreground && self.priority < TaskPriority::Critical
+    }
+}
+
+/// Cooperative cancellation token handed to running tasks
+#[derive(Debug, Clone, Default)]
+pub struct PreemptionToken {
+    preempted: Arc<AtomicBool>,
+    notify: Arc<Notify>,
+}
+
+impl PreemptionToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Signal the task to yield at its next checkpoint
+    pub fn preempt(&self) {
+        self.preempted.store(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_preempted(&self) -> bool {
+        self.preempted.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token has been preempted
+    pub async fn preempted(&self) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_preempted() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// Per-adapter scan position, kept so scans can resume after a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdapterCursor {
+    pub last_query: String,
+    pub last_scan_at: DateTime<Utc>,
+    pub results_seen: u64,
+}
+
+/// Per-source scan intervals, active hours and result limits
+#[derive(Debug, Clone, Default)]
+pub struct SchedulingPolicy {
+    /// Interval for sources without their own (None = no limit)
+    pub default_interval_minutes: Option<u32>,
+    pub schedules: Vec<SourceSchedule>,
+}
+
+impl SchedulingPolicy {
+    pub fn from_config(config: &CommanderConfig) -> Self {
+        Self {
+            default_interval_minutes: Some(config.scan_interval_minutes),
+            schedules: config.source_schedules.clone(),
+        }
+    }
+
+    /// Scan followed feeds at their own poll intervals; schedules in the config win
+    pub fn with_feeds(mut self, feeds: &[CustomFeed]) -> Self {
+        for feed in feeds {
+            let source = feed.source();
+            if self.schedule(&source).is_none() {
+                self.schedules.push(SourceSchedule {
+                    scan_interval_minutes: feed.poll_interval_minutes,
+                    ..SourceSchedule::new(source)
+                });
+            }
+        }
+        self
+    }
+
+    fn schedule(&self, source: &ResearchSource) -> Option<&SourceSchedule> {
+        self.schedules.iter().find(|s| &s.source == source)
+    }
+
+    /// Whether a source may be scanned now
+    pub fn is_due(
+        &self,
+        source: &ResearchSource,
+        last_scan: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        local_hour: u8,
+    ) -> bool {
+        let schedule = self.schedule(source);
+
+        if let Some((start, end)) = schedule.and_then(|s| s.active_hours) {
+            let active = if start <= end {
+                (start..end).contains(&local_hour)
+            } else {
+                local_hour >= start || local_hour < end
+            };
+            if !active {
+                return false;
+            }
+        }
+
+        let interval = schedule
+            .and_then(|s| s.scan_interval_minutes)
+            .or(self.default_interval_minutes);
+        match (interval, last_scan) {
+            (Some(minutes), Some(last)) => now - last >= chrono::Duration::minutes(minutes as i64),
+            _ => true,
+        }
+    }
+
+    /// Maximum results per scan of a source
+    pub fn max_results(&self, source: &ResearchSource) -> Option<usize> {
+        self.schedule(source).and_then(|s| s.max_results)
+    }
+
+    /// Sources with their own adapter timeout
+    pub fn timeouts(&self) -> impl Iterator<Item = (ResearchSource, std::time::Duration)> + '_ {
+        self.schedules.iter().filter_map(|s| {
+            let seconds = s.timeout_seconds?;
+            Some((s.source.clone(), std::time::Duration::from_secs(seconds as u64)))
+        })
+    }
+}
+
+/// Serializable scheduler state used for pause/resume
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerSnapshot {
+    pub queue: Vec<ResearchTask>,
+    pub cursors: HashMap<String, AdapterCursor>,
+    pub recent_findings: Vec<ResearchFinding>,
+}
+
+/// A task currently being executed
+struct RunningTask {
+    task: ResearchTask,
+    token: PreemptionToken,
 }
 
 /// The Task Scheduler
 pub struct TaskScheduler {
     queue: RwLock<VecDeque<ResearchTask>>,
+    running: RwLock<HashMap<String, RunningTask>>,
+    /// Woken whenever a task leaves `running`
+    running_changed: Notify,
+    /// Tasks restored from a pause snapshot, which continue from their adapters' cursors
+    resumed: RwLock<HashSet<String>>,
     recent_findings: RwLock<Vec<ResearchFinding>>,
+    /// Finding behind the signal each task produced
+    signal_findings: RwLock<HashMap<String, String>>,
+    /// Turns findings into signals and tracks keyword trends across scans
+    signal_processor: RwLock<SignalProcessor>,
+    /// Trend signals of finished scans, not yet taken by the Commander loop
+    trend_signals: RwLock<Vec<Signal>>,
+    policy: RwLock<SchedulingPolicy>,
+    /// When each source was last scanned
+    last_scans: RwLock<HashMap<ResearchSource, DateTime<Utc>>>,
+    cursors: RwLock<HashMap<String, AdapterCursor>>,
+    /// Content hashes of findings already seen
+    dedup: RwLock<FindingsDedup>,
+    /// Every finding observed over time, for day-over-day diffs
+    history: FindingsHistory,
+    /// Users' ratings of findings, which new scans are scored with
+    feedback: ScoreFeedback,
+    foreground_active: AtomicUsize,
+    archive: FindingArchive,
+    /// Merges findings that say the same thing in different words
+    semantic_dedup: SemanticDedup,
+    /// Embeds topics and findings for semantic scoring and dedup
+    inference: Option<Arc<RwLock<Option<InferenceEngine>>>>,
+    /// Findings of every scan, deduplicated across scans and searchable
+    finding_store: Option<Arc<LocalDatabase>>,
+    telemetry: Option<Arc<TelemetryService>>,
     max_queue_size: usize,
     max_findings_cache: usize,
 }
@@ -75,16 +282,64 @@ impl TaskScheduler {
     pub fn new() -> Self {
         Self {
             queue: RwLock::new(VecDeque::new()),
+            running: RwLock::new(HashMap::new()),
+            running_changed: Notify::new(),
+            resumed: RwLock::new(HashSet::new()),
             recent_findings: RwLock::new(Vec::new()),
+            signal_findings: RwLock::new(HashMap::new()),
+            signal_processor: RwLock::new(SignalProcessor::default()),
+            trend_signals: RwLock::new(Vec::new()),
+            policy: RwLock::new(SchedulingPolicy::default()),
+            last_scans: RwLock::new(HashMap::new()),
+            cursors: RwLock::new(HashMap::new()),
+            dedup: RwLock::new(FindingsDedup::default()),
+            history: FindingsHistory::default(),
+            feedback: ScoreFeedback::default(),
+            foreground_active: AtomicUsize::new(0),
+            archive: FindingArchive::default(),
+            semantic_dedup: SemanticDedup::new(),
+            inference: None,
+            finding_store: None,
+            telemetry: None,
             max_queue_size: 100,
             max_findings_cache: 50,
         }
     }
 
+    /// Report scan overruns to telemetry
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Score findings by similarity to the topic and merge near-duplicates, embedding
+    /// them with the shared inference engine
+    pub fn with_inference(mut self, inference: Arc<RwLock<Option<InferenceEngine>>>) -> Self {
+        self.inference = Some(inference);
+        self
+    }
+
+    /// Keep findings in the database, where repeats from later scans are merged,
+    /// and keyword trends continue from the history recorded there
+    pub fn with_finding_store(mut self, database: Arc<LocalDatabase>) -> Self {
+        match database.load_keyword_history() {
+            Ok(history) => {
+                let processor = self.signal_processor.get_mut();
+                *processor = processor.clone().with_history(history);
+            }
+            Err(e) => log::warn!("Failed to load keyword history: {}", e),
+        }
+        self.finding_store = Some(database);
+        self
+    }
+
     /// Add a task to the queue
     pub async fn add_task(&self, task: ResearchTask) {
         let mut queue = self.queue.write().await;
+        self.insert_task(&mut queue, task);
+    }
 
+    fn insert_task(&self, queue: &mut VecDeque<ResearchTask>, task: ResearchTask) {
         // Maintain max queue size
         if queue.len() >= self.max_queue_size {
             // Remove lowest priority task
@@ -105,28 +360,221 @@ impl TaskScheduler {
         log::debug!("Task added to queue. Queue size: {}", queue.len());
     }
 
+    /// Queue a background scan for each feed that has none queued or running;
+    /// the scan waits in the queue until the feed's poll interval has passed
+    pub async fn queue_feed_scans(&self, feeds: &[CustomFeed]) {
+        for feed in feeds {
+            let source = Some(feed.source());
+            let queued = self.queue.read().await.iter().any(|t| t.source == source)
+                || self.running.read().await.values().any(|r| r.task.source == source);
+            if !queued {
+                self.add_task(
+                    ResearchTask::new(feed.topic().to_string(), TaskPriority::Background)
+                        .with_source(feed.source()),
+                )
+                .await;
+            }
+        }
+    }
+
+    /// Replace the per-source scheduling policy
+    pub async fn set_policy(&self, policy: SchedulingPolicy) {
+        *self.policy.write().await = policy;
+    }
+
+    /// Change when keywords count as trending; the mention history is kept
+    pub async fn set_trend_config(&self, trend: TrendConfig) {
+        self.signal_processor.write().await.trend = trend;
+    }
+
+    /// Turn archiving of high-relevance findings on or off
+    pub fn set_archiving(&self, enabled: bool) {
+        self.archive.set_enabled(enabled);
+    }
+
+    /// Trend signals found since the last call
+    pub async fn take_trend_signals(&self) -> Vec<Signal> {
+        std::mem::take(&mut *self.trend_signals.write().await)
+    }
+
     /// Get the next task to process
     pub async fn get_next_task(&self) -> Option<ResearchTask> {
         let mut queue = self.queue.write().await;
+        let foreground_active = self.is_foreground_active();
+        let policy = self.policy.read().await;
+        let last_scans = self.last_scans.read().await;
+        let now = Utc::now();
+        let local_hour = Local::now().hour() as u8;
+
+        // Find first pending task (only non-preemptible ones while foreground work runs).
+        // Background tasks also wait for their source's schedule; user requests don't.
+        let idx = queue.iter().position(|t| {
+            let source = task_source(t);
+            t.status == TaskStatus::Pending
+                && (!foreground_active || !t.is_preemptible())
+                && (t.foreground || policy.is_due(&source, last_scans.get(&source).copied(), now, local_hour))
+        })?;
+        drop(last_scans);
+        drop(policy);
+
+        let mut task = queue.remove(idx)?;
+        task.status = TaskStatus::Running;
+        task.started_at = Some(Utc::now());
+
+        self.running.write().await.insert(
+            task.id.clone(),
+            RunningTask {
+                task: task.clone(),
+                token: PreemptionToken::new(),
+            },
+        );
+
+        Some(task)
+    }
+
+    /// Get the preemption token of a running task
+    pub async fn preemption_token(&self, task_id: &str) -> Option<PreemptionToken> {
+        self.running
+            .read()
+            .await
+            .get(task_id)
+            .map(|r| r.token.clone())
+    }
+
+    /// Mark a running task as finished
+    pub async fn finish_task(&self, task_id: &str) {
+        self.running.write().await.remove(task_id);
+        self.resumed.write().await.remove(task_id);
+        self.running_changed.notify_waiters();
+    }
+
+    /// Move a running task back into the queue. The queue lock is held across the
+    /// move, as in `get_next_task`, so `snapshot` never sees the task in neither place.
+    async fn requeue(&self, task: ResearchTask) {
+        let mut queue = self.queue.write().await;
+        self.running.write().await.remove(&task.id);
+        self.insert_task(&mut queue, task);
+        self.running_changed.notify_waiters();
+    }
 
-        // Find first pending task
-        if let Some(idx) = queue.iter().position(|t| t.status == TaskStatus::Pending) {
-            let mut task = queue.remove(idx)?;
-            task.status = TaskStatus::Running;
-            task.started_at = Some(Utc::now());
-            Some(task)
+    /// Put a preempted task back in the queue as paused
+    pub async fn requeue_preempted(&self, task: &ResearchTask) {
+        let mut task = task.clone();
+        task.status = if self.is_foreground_active() {
+            TaskStatus::Paused
         } else {
-            None
+            TaskStatus::Pending
+        };
+        task.started_at = None;
+
+        log::info!("Task {} preempted by foreground work", task.id);
+        self.requeue(task).await;
+    }
+
+    /// Begin foreground work: preempts lower-priority running tasks
+    pub async fn begin_foreground(&self) {
+        self.foreground_active.fetch_add(1, Ordering::SeqCst);
+
+        let running = self.running.read().await;
+        for entry in running.values().filter(|r| r.task.is_preemptible()) {
+            log::debug!("Preempting background task: {}", entry.task.topic);
+            entry.token.preempt();
         }
     }
 
-    /// Execute a task using research adapters
-    pub async fn execute_task(&self, task: &ResearchTask) -> Option<Signal> {
-        use crate::research::{ResearchAdapterRegistry, traits::{SearchOptions, SortOrder}};
-        use crate::research::processors::SignalProcessor;
+    /// End foreground work: resumes paused tasks once no foreground work remains
+    pub async fn end_foreground(&self) {
+        let previous = self
+            .foreground_active
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| Some(n.saturating_sub(1)))
+            .unwrap_or(0);
+
+        if previous <= 1 {
+            let mut queue = self.queue.write().await;
+            let mut resumed = 0;
+            for task in queue.iter_mut().filter(|t| t.status == TaskStatus::Paused) {
+                task.status = TaskStatus::Pending;
+                resumed += 1;
+            }
+            if resumed > 0 {
+                log::info!("Resumed {} preempted tasks", resumed);
+            }
+        }
+    }
 
+    /// Ask every running task to yield at its next checkpoint
+    pub async fn preempt_running(&self) {
+        for entry in self.running.read().await.values() {
+            entry.token.preempt();
+        }
+    }
+
+    /// Resolves once no task is running, e.g. after `preempt_running`
+    pub async fn wait_until_idle(&self) {
+        loop {
+            let changed = self.running_changed.notified();
+            if self.running.read().await.is_empty() {
+                return;
+            }
+            changed.await;
+        }
+    }
+
+    /// Whether any foreground work is currently running
+    pub fn is_foreground_active(&self) -> bool {
+        self.foreground_active.load(Ordering::SeqCst) > 0
+    }
+
+    /// Put a task that overran its scan budget back at lower priority, or drop it
+    /// once it has used up its retries
+    pub async fn requeue_overrun(&self, task: &ResearchTask, overrun: &Overrun) {
+        let mut task = task.clone();
+        task.retry_count += 1;
+        let action = if task.retry_count > task.max_retries {
+            log::warn!("Task {} dropped after {} overruns", task.id, task.retry_count);
+            self.finish_task(&task.id).await;
+            OverrunAction::Aborted
+        } else {
+            task.priority = task.priority.demoted();
+            task.status = TaskStatus::Pending;
+            task.started_at = None;
+            self.requeue(task).await;
+            OverrunAction::Rescheduled
+        };
+        report_overrun(self.telemetry.as_deref(), overrun, action).await;
+    }
+
+    /// Execute a task, yielding early if foreground work preempts it
+    /// or the scan exceeds its time budget
+    pub async fn execute_task(&self, task: &ResearchTask) -> Option<Signal> {
         log::info!("Executing research task: {} - {}", task.id, task.topic);
 
+        let token = self.preemption_token(&task.id).await.unwrap_or_default();
+        let scan = run_timeboxed(TimeboxedWork::Scan, &task.topic, TimeboxedWork::Scan.budget(), self.run_task(task));
+        let signal = tokio::select! {
+            result = scan => match result {
+                Ok(signal) => signal,
+                Err(overrun) => {
+                    self.requeue_overrun(task, &overrun).await;
+                    self.last_scans.write().await.insert(task_source(task), Utc::now());
+                    return None;
+                }
+            },
+            _ = token.preempted() => {
+                self.requeue_preempted(task).await;
+                return None;
+            }
+        };
+
+        self.finish_task(&task.id).await;
+        self.last_scans.write().await.insert(task_source(task), Utc::now());
+        signal
+    }
+
+    /// Run the research for a task
+    async fn run_task(&self, task: &ResearchTask) -> Option<Signal> {
+        use crate::research::{ResearchAdapterRegistry, SearchOrchestrator, traits::{SearchOptions, SortOrder}};
+
         // Create adapter registry with defaults
         let registry = match ResearchAdapterRegistry::with_defaults().await {
             Ok(r) => r,
@@ -136,59 +584,154 @@ impl TaskScheduler {
             }
         };
 
-        // Determine which adapter to use
-        let adapter = if let Some(source) = &task.source {
-            registry.get_by_source(source).await
-        } else {
-            // Default to GitHub if no source specified
-            registry.get("GitHub").await
-        };
-
-        let adapter = match adapter {
-            Some(a) => a,
-            None => {
-                log::warn!("No adapter available for task: {}", task.topic);
-                return None;
-            }
+        // A task for one source searches its adapter; a task without one searches all at once
+        let adapters = match &task.source {
+            Some(source) => registry.get_by_source(source).await.into_iter().collect(),
+            None => registry.all().await,
         };
+        if adapters.is_empty() {
+            log::warn!("No adapter available for task: {}", task.topic);
+            return None;
+        }
 
         // Configure search options
+        let policy = self.policy.read().await;
+        let max_results = task.source.as_ref().and_then(|source| policy.max_results(source));
         let options = SearchOptions {
-            limit: Some(10),
+            limit: Some(max_results.unwrap_or(10)),
             min_relevance: Some(0.5),
             sort_by: Some(SortOrder::Relevance),
             ..Default::default()
         };
+        // Only a scan interrupted by a pause continues from the cursors; a new
+        // scan of the same topic looks at everything again
+        let resume_from = if self.resumed.read().await.contains(&task.id) {
+            self.resume_points(&task.topic, adapters.iter().map(|a| a.name())).await
+        } else {
+            Vec::new()
+        };
+        let orchestrator = policy
+            .timeouts()
+            .fold(SearchOrchestrator::new(adapters), |o, (source, timeout)| o.with_timeout(source, timeout));
+        let orchestrator = resume_from
+            .into_iter()
+            .fold(orchestrator, |o, (adapter, since)| o.with_resume_from(adapter, since));
+        drop(policy);
 
         // Execute search
-        let findings = match adapter.search(&task.topic, &options).await {
-            Ok(f) => f,
-            Err(e) => {
-                log::error!("Research search failed for '{}': {}", task.topic, e);
-                return None;
-            }
-        };
+        let search = orchestrator.search(&task.topic, &options).await;
+        if search.outcomes.iter().all(|o| o.error.is_some()) {
+            log::error!("Research search failed for '{}' on every source", task.topic);
+            return None;
+        }
+        let mut findings = search.findings;
 
         log::info!(
             "Research task '{}' found {} results from {}",
             task.topic,
             findings.len(),
-            adapter.name()
+            search.outcomes.iter().map(|o| o.adapter.as_str()).collect::<Vec<_>>().join(", ")
         );
 
+        {
+            let mut cursors = self.cursors.write().await;
+            for outcome in search.outcomes.iter().filter(|o| o.error.is_none()) {
+                let cursor = cursors
+                    .entry(outcome.adapter.clone())
+                    .or_insert_with(|| AdapterCursor {
+                        last_query: String::new(),
+                        last_scan_at: Utc::now(),
+                        results_seen: 0,
+                    });
+                cursor.last_query = task.topic.clone();
+                cursor.last_scan_at = Utc::now();
+                cursor.results_seen += outcome.findings as u64;
+            }
+        }
+
+        // Rescore with the shared scorer so every finding carries a score breakdown;
+        // weights and source authority follow the user's ratings
+        let embeddings = self.embed_scan(&task.topic, &findings).await;
+        let mut scorer = RelevanceScorer::with_keywords(
+            task.topic.split_whitespace().map(|s| s.to_string()).collect(),
+        )
+        .with_learned(&self.feedback.learned().await);
+        if let Some((query, vectors)) = &embeddings {
+            let by_id = findings.iter().map(|f| f.id.clone()).zip(vectors.iter().cloned()).collect();
+            scorer = scorer.with_query_embedding(query.clone()).with_finding_embeddings(by_id);
+        }
+        scorer.score_all(&mut findings);
+
+        // "GPT-5 released" and "OpenAI launches GPT-5" become one finding
+        let mut findings = match &embeddings {
+            Some((_, vectors)) => self.semantic_dedup.merge(findings, vectors),
+            None => findings,
+        };
+
+        // Record every result (including repeats) so score movements show up in diffs
+        if let Err(e) = self.history.observe(&findings).await {
+            log::warn!("Failed to record findings history: {}", e);
+        }
+
+        // Merge findings stored by earlier scans, also before a restart, and keep the new ones
+        if let Some(store) = &self.finding_store {
+            match store.store_findings(&findings, Utc::now()) {
+                Ok(duplicates) => {
+                    let mut duplicates = duplicates.into_iter();
+                    findings.retain(|_| duplicates.next().flatten().is_none());
+                }
+                Err(e) => log::warn!("Failed to store findings: {}", e),
+            }
+            if let Err(e) = store.apply_finding_retention(&FindingRetention::default(), Utc::now()) {
+                log::warn!("Failed to apply finding retention: {}", e);
+            }
+        }
+
+        // Drop findings seen before; the hash matches what the Python services compute
+        {
+            let mut dedup = self.dedup.write().await;
+            findings.retain(|f| dedup.insert(finding_hash(f)));
+        }
+
+        // Keywords of the new findings feed the trend history
+        let processor = self.signal_processor.read().await.clone();
+        let trends = processor.detect_trends(&findings, Utc::now());
+        self.trend_signals.write().await.extend(trends);
+        if let Some(store) = &self.finding_store {
+            if let Err(e) = store.save_keyword_history(&processor.history()) {
+                log::warn!("Failed to store keyword history: {}", e);
+            }
+        }
+
         if findings.is_empty() {
             return None;
         }
 
+        findings.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
         // Store all findings
         for finding in &findings {
             self.add_finding(finding.clone()).await;
         }
 
+        // Archive linked documents of high-relevance findings for offline reading
+        for finding in findings.iter().filter(|f| self.archive.should_archive(f)) {
+            if let Err(e) = self.archive.archive(finding).await {
+                log::warn!("Failed to archive finding {}: {}", finding.id, e);
+            }
+        }
+
         // Process the best finding into a signal
-        let processor = SignalProcessor::default();
         let best_finding = findings.into_iter().next()?;
         let signal = processor.process(&best_finding);
+        self.signal_findings
+            .write()
+            .await
+            .insert(task.id.clone(), best_finding.id.clone());
 
         if signal.is_none() {
             // Generate fallback signal for low-scoring findings
@@ -203,8 +746,47 @@ impl TaskScheduler {
         }
     }
 
+    /// Embeddings of a scan's topic and of its findings, in order; None without an
+    /// embedding model
+    async fn embed_scan(&self, topic: &str, findings: &[ResearchFinding]) -> Option<(Vec<f32>, Vec<Vec<f32>>)> {
+        if findings.is_empty() {
+            return None;
+        }
+        let texts = std::iter::once(topic.to_string()).chain(findings.iter().map(embedding_text));
+        match embed_texts(self.inference.as_ref()?, texts).await? {
+            Ok(mut vectors) => {
+                let query = vectors.remove(0);
+                Some((query, vectors))
+            }
+            Err(e) => {
+                log::warn!("Scan of '{}' scored without embeddings, embedding failed: {}", topic, e);
+                None
+            }
+        }
+    }
+
+    /// Where each adapter's scan of `topic` resumes: adapters whose cursor is for
+    /// the same query only look at results newer than their last scan
+    async fn resume_points<'a>(
+        &self,
+        topic: &str,
+        adapters: impl Iterator<Item = &'a str>,
+    ) -> Vec<(String, i64)> {
+        let cursors = self.cursors.read().await;
+        adapters
+            .filter_map(|name| {
+                let cursor = cursors.get(name).filter(|c| c.last_query == topic)?;
+                Some((name.to_string(), cursor.last_scan_at.timestamp()))
+            })
+            .collect()
+    }
+
     /// Add a finding to the cache
-    pub async fn add_finding(&self, finding: ResearchFinding) {
+    pub async fn add_finding(&self, mut finding: ResearchFinding) {
+        let hash = finding_hash(&finding);
+        if let Some(metadata) = finding.metadata.as_object_mut() {
+            metadata.insert("content_hash".to_string(), hash.into());
+        }
         let mut findings = self.recent_findings.write().await;
 
         findings.insert(0, finding);
@@ -215,23 +797,204 @@ impl TaskScheduler {
         }
     }
 
+    /// Changes in findings since a point in time
+    pub async fn get_findings_diff(&self, since: DateTime<Utc>) -> FindingsDiff {
+        self.history.diff(since).await
+    }
+
     /// Get recent findings
     pub async fn get_recent_findings(&self, limit: usize) -> Vec<ResearchFinding> {
         let findings = self.recent_findings.read().await;
         findings.iter().take(limit).cloned().collect()
     }
 
+    /// Take the finding behind the signal a task produced
+    pub async fn take_signal_finding(&self, task_id: &str) -> Option<ResearchFinding> {
+        let finding_id = self.signal_findings.write().await.remove(task_id)?;
+        let findings = self.recent_findings.read().await;
+        findings.iter().find(|f| f.id == finding_id).cloned()
+    }
+
+    /// Attach deep-analysis highlights to a recent or stored finding
+    pub async fn attach_highlights(&self, finding_id: &str, highlights: &[String]) -> bool {
+        let Some(mut finding) = self.find_finding(finding_id).await else {
+            return false;
+        };
+        set_metadata(&mut finding, "highlights", serde_json::json!(highlights));
+        self.save_finding(&finding).await;
+        true
+    }
+
+    /// A recent finding, or one kept in the finding store by an earlier scan
+    async fn find_finding(&self, finding_id: &str) -> Option<ResearchFinding> {
+        let recent = self
+            .recent_findings
+            .read()
+            .await
+            .iter()
+            .find(|f| f.id == finding_id)
+            .cloned();
+        if recent.is_some() {
+            return recent;
+        }
+        match self.finding_store.as_ref()?.get_finding(finding_id) {
+            Ok(stored) => stored.map(|stored| stored.finding),
+            Err(e) => {
+                log::warn!("Failed to load finding {}: {}", finding_id, e);
+                None
+            }
+        }
+    }
+
+    /// Get the relevance score breakdown of a recent or stored finding
+    pub async fn get_score_breakdown(&self, finding_id: &str) -> Option<ScoreBreakdown> {
+        self.find_finding(finding_id).await?.score_breakdown
+    }
+
+    /// Write a changed finding to the recent findings and the finding store
+    async fn save_finding(&self, finding: &ResearchFinding) {
+        if let Some(recent) = self.recent_findings.write().await.iter_mut().find(|f| f.id == finding.id) {
+            *recent = finding.clone();
+        }
+        if let Some(store) = &self.finding_store {
+            if let Err(e) = store.update_finding(finding) {
+                log::warn!("Failed to store finding {}: {}", finding.id, e);
+            }
+        }
+    }
+
+    /// Record whether a recent or stored finding was useful, on the finding and in
+    /// the feedback new scans are scored with. None if the finding is unknown.
+    pub async fn rate_finding(&self, finding_id: &str, useful: bool) -> Option<ResearchResult<LearnedScoring>> {
+        let mut finding = self.find_finding(finding_id).await?;
+        set_metadata(&mut finding, "useful", serde_json::json!(useful));
+        self.save_finding(&finding).await;
+        Some(self.feedback.rate(&finding, useful).await)
+    }
+
+    /// Recompute the scores of stored and recent findings with scorer `version`,
+    /// reusing the keywords and reference time each finding was first scored with.
+    /// Returns None when the version does not exist.
+    pub async fn rescore_findings(&self, version: u32) -> Option<RescoreReport> {
+        RelevanceScorer::for_version(version, Vec::new())?;
+        let mut report = RescoreReport {
+            version,
+            rescored: 0,
+            changed: 0,
+            max_delta: 0.0,
+        };
+
+        // The recent findings are also stored, so they only count without a store
+        let mut recent = self.recent_findings.write().await;
+        for finding in recent.iter_mut() {
+            let delta = rescore(finding, version)?;
+            if self.finding_store.is_none() {
+                report.count(delta);
+            }
+        }
+        drop(recent);
+
+        if let Some(store) = &self.finding_store {
+            let mut filter = FindingFilter {
+                include_archived: true,
+                limit: Some(RESCORE_PAGE),
+                ..Default::default()
+            };
+            loop {
+                let page = match store.search_findings("", &filter) {
+                    Ok(page) => page,
+                    Err(e) => {
+                        log::warn!("Failed to load stored findings for rescoring: {}", e);
+                        break;
+                    }
+                };
+                for stored in &page {
+                    let mut finding = stored.finding.clone();
+                    let delta = rescore(&mut finding, version)?;
+                    match store.update_finding(&finding) {
+                        Ok(_) => report.count(delta),
+                        Err(e) => log::warn!("Failed to store rescored finding {}: {}", finding.id, e),
+                    }
+                }
+                if page.len() < RESCORE_PAGE as usize {
+                    break;
+                }
+                filter.offset += RESCORE_PAGE;
+            }
+        }
+
+        log::info!(
+            "Rescored {} findings with scorer v{} ({} changed)",
+            report.rescored,
+            version,
+            report.changed
+        );
+        Some(report)
+    }
+
+    /// Get archived content for a finding
+    pub async fn get_finding_content(&self, finding_id: &str) -> Option<ArchivedContent> {
+        self.archive.get(finding_id).await
+    }
+
+    /// Capture queue, cursors and partial results; running tasks are saved as pending
+    pub async fn snapshot(&self) -> SchedulerSnapshot {
+        // Both under one lock order (queue, then running) so a task moving between
+        // them is captured exactly once
+        let queued = self.queue.read().await;
+        let mut queue: Vec<ResearchTask> = queued.iter().cloned().collect();
+        for running in self.running.read().await.values() {
+            let mut task = running.task.clone();
+            task.status = TaskStatus::Pending;
+            task.started_at = None;
+            queue.push(task);
+        }
+        drop(queued);
+
+        for task in queue.iter_mut().filter(|t| t.status == TaskStatus::Paused) {
+            task.status = TaskStatus::Pending;
+        }
+
+        SchedulerSnapshot {
+            queue,
+            cursors: self.cursors.read().await.clone(),
+            recent_findings: self.recent_findings.read().await.clone(),
+        }
+    }
+
+    /// Restore state captured by `snapshot`, replacing the current queue
+    pub async fn restore(&self, snapshot: SchedulerSnapshot) {
+        self.queue.write().await.clear();
+        let mut resumed = HashSet::new();
+        for task in snapshot.queue {
+            if !matches!(task.status, TaskStatus::Completed | TaskStatus::Cancelled) {
+                resumed.insert(task.id.clone());
+                self.add_task(task).await;
+            }
+        }
+        *self.resumed.write().await = resumed;
+
+        *self.cursors.write().await = snapshot.cursors;
+
+        let mut findings = self.recent_findings.write().await;
+        *findings = snapshot.recent_findings;
+        findings.truncate(self.max_findings_cache);
+    }
+
     /// Get queue status
     pub async fn get_queue_status(&self) -> QueueStatus {
         let queue = self.queue.read().await;
 
         let pending = queue.iter().filter(|t| t.status == TaskStatus::Pending).count();
-        let running = queue.iter().filter(|t| matches!(t.status, TaskStatus::Running)).count();
+        let paused = queue.iter().filter(|t| t.status == TaskStatus::Paused).count();
+        let running = self.running.read().await.len();
 
         QueueStatus {
-            total: queue.len(),
+            total: queue.len() + running,
             pending,
             running,
+            paused,
+            foreground_active: self.is_foreground_active(),
             by_priority: PriorityBreakdown {
                 critical: queue.iter().filter(|t| t.priority == TaskPriority::Critical).count(),
                 high: queue.iter().filter(|t| t.priority == TaskPriority::High).count(),
@@ -260,18 +1023,67 @@ impl TaskScheduler {
     }
 }
 
+/// Source a task scans (GitHub when unspecified)
+fn task_source(task: &ResearchTask) -> ResearchSource {
+    task.source.clone().unwrap_or(ResearchSource::GitHub)
+}
+
 impl Default for TaskScheduler {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Outcome of a rescoring run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RescoreReport {
+    pub version: u32,
+    pub rescored: usize,
+    /// Findings whose score moved
+    pub changed: usize,
+    pub max_delta: f32,
+}
+
+impl RescoreReport {
+    fn count(&mut self, delta: f32) {
+        self.rescored += 1;
+        if delta > f32::EPSILON {
+            self.changed += 1;
+        }
+        self.max_delta = self.max_delta.max(delta);
+    }
+}
+
+/// Set a metadata field, replacing metadata that is not an object
+fn set_metadata(finding: &mut ResearchFinding, key: &str, value: serde_json::Value) {
+    if !finding.metadata.is_object() {
+        finding.metadata = serde_json::json!({});
+    }
+    finding.metadata[key] = value;
+}
+
+/// Rescore a finding with scorer `version`; returns how far its score moved
+fn rescore(finding: &mut ResearchFinding, version: u32) -> Option<f32> {
+    // Findings scored before versioning have no reference time; score them as of now
+    let (keywords, scored_at) = finding
+        .score_breakdown
+        .as_ref()
+        .map(|b| (b.keywords.clone(), b.scored_at))
+        .unwrap_or_default();
+    let scorer = RelevanceScorer::for_version(version, keywords)?;
+    let previous = finding.relevance_score;
+    scorer.apply(finding, scored_at.unwrap_or_else(Utc::now));
+    Some((finding.relevance_score - previous).abs())
+}
+
 /// Queue status summary
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueueStatus {
     pub total: usize,
     pub pending: usize,
     pub running: usize,
+    pub paused: usize,
+    pub foreground_active: bool,
     pub by_priority: PriorityBreakdown,
 }
 
@@ -284,3 +1096,318 @@ pub struct PriorityBreakdown {
     pub low: usize,
     pub background: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_foreground_preempts_background_task() {
+        let scheduler = TaskScheduler::new();
+        scheduler
+            .add_task(ResearchTask::new("rust async".to_string(), TaskPriority::Background))
+            .await;
+
+        let task = scheduler.get_next_task().await.unwrap();
+        let token = scheduler.preemption_token(&task.id).await.unwrap();
+        assert!(!token.is_preempted());
+
+        scheduler.begin_foreground().await;
+        assert!(token.is_preempted());
+
+        scheduler.requeue_preempted(&task).await;
+        let status = scheduler.get_queue_status().await;
+        assert_eq!(status.paused, 1);
+        assert_eq!(status.running, 0);
+        assert!(scheduler.get_next_task().await.is_none());
+
+        scheduler.end_foreground().await;
+        let resumed = scheduler.get_next_task().await.unwrap();
+        assert_eq!(resumed.id, task.id);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_restores_running_tasks() {
+        let scheduler = TaskScheduler::new();
+        scheduler
+            .add_task(ResearchTask::new("queued".to_string(), TaskPriority::Low))
+            .await;
+        scheduler
+            .add_task(ResearchTask::new("running".to_string(), TaskPriority::High))
+            .await;
+        let running = scheduler.get_next_task().await.unwrap();
+
+        let json = serde_json::to_string(&scheduler.snapshot().await).unwrap();
+        let snapshot: SchedulerSnapshot = serde_json::from_str(&json).unwrap();
+
+        let restored = TaskScheduler::new();
+        restored.restore(snapshot).await;
+
+        let next = restored.get_next_task().await.unwrap();
+        assert_eq!(next.id, running.id);
+        assert_eq!(restored.get_queue_status().await.pending, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rescoring_updates_stored_findings() {
+        let store = Arc::new(LocalDatabase::in_memory(100));
+        let scheduler = TaskScheduler::new().with_finding_store(store.clone());
+        let finding = ResearchFinding {
+            id: "old".to_string(),
+            source: ResearchSource::GitHub,
+            title: "Rust agents".to_string(),
+            summary: String::new(),
+            relevance_score: 0.1,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        };
+        // Stored by an earlier run; no longer among the recent findings
+        store.store_findings(&[finding], Utc::now()).unwrap();
+
+        let report = scheduler.rescore_findings(1).await.unwrap();
+        assert_eq!(report.rescored, 1);
+        let stored = store.get_finding("old").unwrap().unwrap().finding;
+        assert_eq!(stored.scorer_version, Some(1));
+        assert_eq!(scheduler.get_score_breakdown("old").await, stored.score_breakdown);
+        assert!(stored.score_breakdown.is_some());
+        assert!(scheduler.rescore_findings(99).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_rating_a_stored_finding() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(LocalDatabase::in_memory(100));
+        let mut scheduler = TaskScheduler::new().with_finding_store(store.clone());
+        scheduler.feedback = ScoreFeedback::new(dir.path().join("findings_feedback.json"));
+        let finding = ResearchFinding {
+            id: "stored".to_string(),
+            source: ResearchSource::ArXiv,
+            title: "Planning with language agents".to_string(),
+            summary: String::new(),
+            relevance_score: 0.5,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        };
+        store.store_findings(&[finding], Utc::now()).unwrap();
+
+        let learned = scheduler.rate_finding("stored", true).await.unwrap().unwrap();
+        assert_eq!(learned.ratings, 1);
+        assert_eq!(store.get_finding("stored").unwrap().unwrap().finding.metadata["useful"], true);
+        assert!(scheduler.attach_highlights("stored", &["Agents plan tool calls".to_string()]).await);
+        let stored = store.get_finding("stored").unwrap().unwrap().finding;
+        assert_eq!(stored.metadata["highlights"], serde_json::json!(["Agents plan tool calls"]));
+        assert_eq!(stored.metadata["useful"], true);
+        assert!(scheduler.rate_finding("missing", true).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_scans_resume_from_cursor_of_same_query() {
+        let scheduler = TaskScheduler::new();
+        let last_scan_at = Utc::now() - chrono::Duration::hours(2);
+        scheduler.cursors.write().await.insert(
+            "GitHub".to_string(),
+            AdapterCursor {
+                last_query: "rust agents".to_string(),
+                last_scan_at,
+                results_seen: 12,
+            },
+        );
+
+        let resume = scheduler.resume_points("rust agents", ["GitHub", "ArXiv"].into_iter()).await;
+        assert_eq!(resume, vec![("GitHub".to_string(), last_scan_at.timestamp())]);
+        // A different query starts from scratch
+        assert!(scheduler.resume_points("wasm", ["GitHub"].into_iter()).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_only_restored_tasks_resume_from_cursors() {
+        let scheduler = TaskScheduler::new();
+        let paused = ResearchTask::new("rust agents".to_string(), TaskPriority::Normal);
+        let snapshot = SchedulerSnapshot {
+            queue: vec![paused.clone()],
+            ..Default::default()
+        };
+        scheduler.restore(snapshot).await;
+        let fresh = ResearchTask::new("wasm".to_string(), TaskPriority::Normal);
+        scheduler.add_task(fresh.clone()).await;
+
+        assert!(scheduler.resumed.read().await.contains(&paused.id));
+        assert!(!scheduler.resumed.read().await.contains(&fresh.id));
+        scheduler.finish_task(&paused.id).await;
+        assert!(scheduler.resumed.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_until_idle_after_preemption() {
+        let scheduler = Arc::new(TaskScheduler::new());
+        scheduler
+            .add_task(ResearchTask::new("rust async".to_string(), TaskPriority::Background))
+            .await;
+        let task = scheduler.get_next_task().await.unwrap();
+
+        let waiter = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move { scheduler.wait_until_idle().await }
+        });
+        scheduler.preempt_running().await;
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        scheduler.requeue_preempted(&task).await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), waiter).await.unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_policy_intervals_and_hours() {
+        let policy = SchedulingPolicy {
+            default_interval_minutes: Some(30),
+            schedules: vec![SourceSchedule {
+                scan_interval_minutes: Some(24 * 60),
+                active_hours: Some((22, 6)),
+                ..SourceSchedule::new(ResearchSource::ArXiv)
+            }],
+        };
+        let now = Utc::now();
+        let hour_ago = Some(now - chrono::Duration::hours(1));
+
+        assert!(policy.is_due(&ResearchSource::GitHub, hour_ago, now, 12));
+        assert!(!policy.is_due(&ResearchSource::ArXiv, None, now, 12));
+        assert!(policy.is_due(&ResearchSource::ArXiv, None, now, 23));
+        assert!(!policy.is_due(&ResearchSource::ArXiv, hour_ago, now, 2));
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_holds_tasks_until_source_due() {
+        let scheduler = TaskScheduler::new();
+        scheduler
+            .set_policy(SchedulingPolicy {
+                default_interval_minutes: Some(60),
+                schedules: Vec::new(),
+            })
+            .await;
+        scheduler
+            .last_scans
+            .write()
+            .await
+            .insert(ResearchSource::GitHub, Utc::now());
+
+        scheduler
+            .add_task(ResearchTask::new("background".to_string(), TaskPriority::High))
+            .await;
+        scheduler
+            .add_task(ResearchTask::new("papers".to_string(), TaskPriority::Low).with_source(ResearchSource::ArXiv))
+            .await;
+        scheduler
+            .add_task(ResearchTask::new("asked".to_string(), TaskPriority::Low).with_foreground())
+            .await;
+
+        assert_eq!(scheduler.get_next_task().await.unwrap().topic, "papers");
+        assert_eq!(scheduler.get_next_task().await.unwrap().topic, "asked");
+        assert!(scheduler.get_next_task().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_feed_scans_follow_poll_interval() {
+        let feed = CustomFeed {
+            name: "Rust Blog".to_string(),
+            url: "https://blog.rust-lang.org/feed.xml".to_string(),
+            topic: Some("rust release".to_string()),
+            poll_interval_minutes: Some(120),
+            added_at: Utc::now(),
+        };
+        let scheduler = TaskScheduler::new();
+        scheduler
+            .set_policy(
+                SchedulingPolicy {
+                    default_interval_minutes: Some(30),
+                    schedules: Vec::new(),
+                }
+                .with_feeds(std::slice::from_ref(&feed)),
+            )
+            .await;
+
+        scheduler.queue_feed_scans(std::slice::from_ref(&feed)).await;
+        scheduler.queue_feed_scans(std::slice::from_ref(&feed)).await;
+        assert_eq!(scheduler.get_queue_status().await.pending, 1);
+
+        let task = scheduler.get_next_task().await.unwrap();
+        assert_eq!(task.topic, "rust release");
+        assert_eq!(task.source, Some(feed.source()));
+        scheduler.queue_feed_scans(std::slice::from_ref(&feed)).await;
+        assert_eq!(scheduler.get_queue_status().await.pending, 0);
+
+        // Scanned an hour ago: not due yet under the feed's two-hour interval
+        scheduler.finish_task(&task.id).await;
+        scheduler
+            .last_scans
+            .write()
+            .await
+            .insert(feed.source(), Utc::now() - chrono::Duration::hours(1));
+        scheduler.queue_feed_scans(std::slice::from_ref(&feed)).await;
+        assert!(scheduler.get_next_task().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_overrun_demotes_then_drops_task() {
+        use crate::telemetry::{TelemetryConfig, TelemetryEvent};
+
+        let telemetry = Arc::new(TelemetryService::new(TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        }));
+        let scheduler = TaskScheduler::new().with_telemetry(telemetry.clone());
+        let mut task = ResearchTask::new("slow".to_string(), TaskPriority::High);
+        task.max_retries = 1;
+        scheduler.add_task(task).await;
+        let overrun = Overrun {
+            work: TimeboxedWork::Scan,
+            label: "slow".to_string(),
+            granted: TimeboxedWork::Scan.budget(),
+        };
+
+        let task = scheduler.get_next_task().await.unwrap();
+        scheduler.requeue_overrun(&task, &overrun).await;
+        let retried = scheduler.get_next_task().await.unwrap();
+        assert_eq!(retried.id, task.id);
+        assert_eq!(retried.priority, TaskPriority::Normal);
+        assert_eq!(retried.retry_count, 1);
+
+        scheduler.requeue_overrun(&retried, &overrun).await;
+        assert_eq!(scheduler.get_queue_status().await.total, 0);
+
+        let actions: Vec<String> = telemetry
+            .get_buffered_events()
+            .await
+            .into_iter()
+            .filter_map(|e| match e {
+                TelemetryEvent::TaskOverrun { action, .. } => Some(action),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(actions, ["rescheduled", "aborted"]);
+    }
+
+    #[tokio::test]
+    async fn test_foreground_tasks_not_preempted() {
+        let scheduler = TaskScheduler::new();
+        scheduler
+            .add_task(ResearchTask::new("urgent".to_string(), TaskPriority::Normal).with_foreground())
+            .await;
+
+        let task = scheduler.get_next_task().await.unwrap();
+        scheduler.begin_foreground().await;
+
+        let token = scheduler.preemption_token(&task.id).await.unwrap();
+        assert!(!token.is_preempted());
+        scheduler.end_foreground().await;
+        assert!(!scheduler.is_foreground_active());
+    }
+}
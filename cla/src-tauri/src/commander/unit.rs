@@ -2,21 +2,55 @@
 
 use super::{
     CommanderConfig, CommanderStatus, ResearchFinding, SyncStatus,
-    DecisionEngine, TaskScheduler, CkcSync, Signal, Action,
+    DecisionEngine, TaskScheduler, CkcSync, Signal, Action, AutonomyPolicy, Decision,
 };
+use super::rules::{DecisionRules, RulesFile};
+use super::policy::{ApprovalEvent, DecisionWork, PendingApproval, PolicyStatus, PolicyVerdict};
+use super::task_scheduler::{SchedulerSnapshot, SchedulingPolicy};
+use crate::activity::{ActivityCategory, ActivityLog};
+use crate::inference::InferenceEngine;
+use crate::notifications::{NotificationAction, NotificationCategory, NotificationCenter};
+use crate::research::adapters::CustomFeed;
+use crate::research::traits::ResearchResult;
+use crate::research::{DeepAnalyzer, FeedRegistry, KnowledgeStore};
+use crate::security::privacy::PrivacyMode;
+use crate::storage::{JournaledFile, LocalDatabase};
+use crate::telemetry::TelemetryService;
+use crate::utils::timebox::{report_overrun, run_timeboxed, OverrunAction, TimeboxedWork};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+
+/// How long `pause` waits for running tasks to yield
+const PAUSE_GRACE: Duration = Duration::from_secs(10);
+
+/// On-disk state written when the Commander is paused
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommanderSnapshot {
+    pub paused_at: DateTime<Utc>,
+    pub scheduler: SchedulerSnapshot,
+}
 
 /// The Commander Unit - autonomous research and decision-making
 pub struct CommanderUnit {
     config: Arc<RwLock<CommanderConfig>>,
     status: Arc<RwLock<CommanderStatus>>,
     decision_engine: Arc<DecisionEngine>,
+    policy: Arc<AutonomyPolicy>,
     task_scheduler: Arc<TaskScheduler>,
     ckc_sync: Arc<CkcSync>,
+    knowledge: Arc<KnowledgeStore>,
+    deep_analyzer: Arc<DeepAnalyzer>,
+    feeds: Arc<FeedRegistry>,
+    telemetry: Option<Arc<TelemetryService>>,
+    activity: Option<Arc<ActivityLog>>,
+    notifications: Option<Arc<NotificationCenter>>,
+    privacy: Option<Arc<PrivacyMode>>,
     findings_tx: mpsc::Sender<ResearchFinding>,
     shutdown_tx: Option<mpsc::Sender<()>>,
+    snapshot_file: JournaledFile,
 }
 
 impl CommanderUnit {
@@ -25,17 +59,87 @@ impl CommanderUnit {
         config: CommanderConfig,
         findings_tx: mpsc::Sender<ResearchFinding>,
     ) -> Self {
+        let knowledge = Arc::new(KnowledgeStore::default());
+
         Self {
             config: Arc::new(RwLock::new(config)),
             status: Arc::new(RwLock::new(CommanderStatus::default())),
-            decision_engine: Arc::new(DecisionEngine::new()),
+            decision_engine: Arc::new(DecisionEngine::new().with_rules_file(RulesFile::new(RulesFile::default_path()))),
+            policy: Arc::new(AutonomyPolicy::new()),
             task_scheduler: Arc::new(TaskScheduler::new()),
             ckc_sync: Arc::new(CkcSync::new()),
+            deep_analyzer: Arc::new(DeepAnalyzer::new(knowledge.clone())),
+            knowledge,
+            feeds: Arc::new(FeedRegistry::default()),
+            telemetry: None,
+            activity: None,
+            notifications: None,
+            privacy: None,
             findings_tx,
             shutdown_tx: None,
+            snapshot_file: JournaledFile::new(
+                "commander_snapshot",
+                dirs::data_dir()
+                    .unwrap_or_else(std::env::temp_dir)
+                    .join("cirkelline-cla")
+                    .join("commander_snapshot.json"),
+            ),
         }
     }
 
+    /// Embed deep-analyzed papers and scan findings with the shared inference engine
+    pub fn with_inference(mut self, inference: Arc<RwLock<Option<InferenceEngine>>>) -> Self {
+        self.deep_analyzer = Arc::new(
+            DeepAnalyzer::new(self.knowledge.clone()).with_inference(inference.clone()),
+        );
+        self.configure_scheduler(|scheduler| scheduler.with_inference(inference))
+    }
+
+    /// Report scan and analysis overruns to telemetry
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry.clone());
+        self.configure_scheduler(|scheduler| scheduler.with_telemetry(telemetry))
+    }
+
+    /// Record decisions in the user-facing activity log
+    pub fn with_activity(mut self, activity: Arc<ActivityLog>) -> Self {
+        self.activity = Some(activity);
+        self
+    }
+
+    /// Post alerts and pending approvals to the notification center
+    pub fn with_notifications(mut self, notifications: Arc<NotificationCenter>) -> Self {
+        self.notifications = Some(notifications);
+        self
+    }
+
+    /// Keep every decision and approval transition in the database for auditing
+    pub fn with_decision_log(mut self, database: Arc<LocalDatabase>) -> Self {
+        self.policy = Arc::new(AutonomyPolicy::new().with_decision_log(database));
+        self
+    }
+
+    /// Keep findings in the database across scans and restarts
+    pub fn with_finding_store(self, database: Arc<LocalDatabase>) -> Self {
+        self.configure_scheduler(|scheduler| scheduler.with_finding_store(database))
+    }
+
+    /// Apply a builder option to the task scheduler. Only used while the unit is
+    /// built, before anything else holds the scheduler.
+    fn configure_scheduler(mut self, configure: impl FnOnce(TaskScheduler) -> TaskScheduler) -> Self {
+        self.task_scheduler = match Arc::try_unwrap(self.task_scheduler) {
+            Ok(scheduler) => Arc::new(configure(scheduler)),
+            Err(_) => unreachable!("task scheduler is shared while the Commander Unit is built"),
+        };
+        self
+    }
+
+    /// Pause research scans while privacy mode is active
+    pub fn with_privacy(mut self, privacy: Arc<PrivacyMode>) -> Self {
+        self.privacy = Some(privacy);
+        self
+    }
+
     /// Start the Commander Unit's autonomous operation
     pub async fn start(&mut self) -> Result<(), CommanderError> {
         log::info!("Starting Commander Unit...");
@@ -45,12 +149,20 @@ impl CommanderUnit {
             log::warn!("Commander Unit is disabled in config");
             return Ok(());
         }
+        config.trend.validate().map_err(CommanderError::ConfigError)?;
+        self.task_scheduler.set_policy(self.scheduling_policy(&config)).await;
+        self.task_scheduler.set_trend_config(config.trend.clone()).await;
+        self.task_scheduler.set_archiving(config.archive_findings);
         drop(config);
 
+        // Continue where we left off if a pause snapshot exists
+        self.restore_snapshot().await?;
+
         // Update status
         {
             let mut status = self.status.write().await;
             status.is_running = true;
+            status.is_paused = false;
         }
 
         // Create shutdown channel
@@ -61,13 +173,32 @@ impl CommanderUnit {
         let status = self.status.clone();
         let config = self.config.clone();
         let decision_engine = self.decision_engine.clone();
+        let policy = self.policy.clone();
         let task_scheduler = self.task_scheduler.clone();
         let ckc_sync = self.ckc_sync.clone();
+        let actions = self.executor();
+        let activity = self.activity.clone();
+        let notifications = self.notifications.clone();
+        let privacy = self.privacy.clone();
         let findings_tx = self.findings_tx.clone();
+        let feeds = self.feeds.clone();
 
         tokio::spawn(async move {
             let start_time = Utc::now();
 
+            // Stop running scans the moment privacy mode turns on
+            let privacy_watch = privacy.as_ref().map(|privacy| {
+                let mut active_rx = privacy.subscribe();
+                let task_scheduler = task_scheduler.clone();
+                tokio::spawn(async move {
+                    while active_rx.changed().await.is_ok() {
+                        if *active_rx.borrow_and_update() {
+                            task_scheduler.preempt_running().await;
+                        }
+                    }
+                })
+            });
+
             loop {
                 tokio::select! {
                     _ = shutdown_rx.recv() => {
@@ -78,6 +209,7 @@ impl CommanderUnit {
                         // Main operation loop
                         let cfg = config.read().await;
                         let scan_interval = cfg.scan_interval_minutes as u64 * 60;
+                        let autonomy_level = cfg.autonomy_level.clone();
                         drop(cfg);
 
                         // Update uptime
@@ -86,39 +218,76 @@ impl CommanderUnit {
                             s.uptime_seconds = (Utc::now() - start_time).num_seconds() as u64;
                         }
 
-                        // Process pending tasks
-                        if let Some(task) = task_scheduler.get_next_task().await {
+                        // Process pending tasks (none while privacy mode is active)
+                        let private = privacy.as_ref().is_some_and(|privacy| privacy.is_active());
+                        if !private {
+                            task_scheduler.queue_feed_scans(&feeds.list()).await;
+                        }
+                        if let Some(task) = if private { None } else { task_scheduler.get_next_task().await } {
                             log::debug!("Processing task: {:?}", task);
 
                             // Execute task and get signal
                             let signal = task_scheduler.execute_task(&task).await;
+                            if let Some(activity) = &activity {
+                                let reason = if task.foreground { "Brugeranmodning" } else { "Planlagt baggrundsscanning" };
+                                activity
+                                    .record(ActivityCategory::Research, format!("Undersøgte \"{}\"", task.topic), Some(reason))
+                                    .await;
+                            }
 
-                            // Make decision based on signal
-                            if let Some(sig) = signal {
+                            // Make decisions on the task's signal, then on trends its scan revealed
+                            let trends = task_scheduler.take_trend_signals().await;
+                            let signals = signal.map(|s| (s, true)).into_iter().chain(trends.into_iter().map(|s| (s, false)));
+                            for (sig, from_task) in signals {
                                 let decision = decision_engine.process_signal(sig).await;
 
                                 // Update status
                                 {
                                     let mut s = status.write().await;
                                     s.last_decision_at = Some(Utc::now());
-                                    s.tasks_completed += 1;
+                                    if from_task {
+                                        s.tasks_completed += 1;
+                                    }
                                 }
 
-                                // Handle decision action
-                                match decision.action {
-                                    Action::DeepAnalyze => {
-                                        log::info!("Deep analysis triggered");
-                                    }
-                                    Action::QueueForReview => {
-                                        log::info!("Queued for human review");
-                                    }
-                                    Action::ImmediateAlert => {
-                                        log::warn!("Immediate alert: {}", decision.rationale);
-                                    }
-                                    Action::Archive => {
-                                        log::debug!("Archived finding");
+                                // Enforce autonomy level; denied decisions are parked with their work until approved
+                                let work = DecisionWork {
+                                    topic: task.topic.clone(),
+                                    finding: if from_task && decision.action == Action::DeepAnalyze {
+                                        task_scheduler.take_signal_finding(&task.id).await
+                                    } else {
+                                        None
+                                    },
+                                };
+                                let verdict = policy.enforce(&decision, &autonomy_level, &work).await;
+                                if let Some(activity) = &activity {
+                                    let summary = if verdict == PolicyVerdict::Allowed {
+                                        format!("{} ({})", action_summary(&decision.action), task.topic)
+                                    } else {
+                                        format!("Afventer godkendelse: {} ({})", action_summary(&decision.action), task.topic)
+                                    };
+                                    activity
+                                        .record(ActivityCategory::Commander, summary, Some(&decision.rationale))
+                                        .await;
+                                }
+
+                                if verdict == PolicyVerdict::Allowed {
+                                    actions.execute(&decision, work).await;
+                                } else {
+                                    log::info!("Decision {} awaiting approval", decision.id);
+                                    if let Some(notifications) = &notifications {
+                                        notifications
+                                            .notify(
+                                                NotificationCategory::Approval,
+                                                format!("Afventer godkendelse: {}", task.topic),
+                                                decision.rationale.clone(),
+                                                vec![NotificationAction {
+                                                    id: "open_approvals".to_string(),
+                                                    label: "Se godkendelser".to_string(),
+                                                }],
+                                            )
+                                            .await;
                                     }
-                                    _ => {}
                                 }
                             }
                         }
@@ -132,6 +301,10 @@ impl CommanderUnit {
                     }
                 }
             }
+
+            if let Some(privacy_watch) = privacy_watch {
+                privacy_watch.abort();
+            }
         });
 
         log::info!("Commander Unit started successfully");
@@ -154,6 +327,72 @@ impl CommanderUnit {
         Ok(())
     }
 
+    /// Pause the Commander, snapshotting queue, cursors and partial results to disk
+    pub async fn pause(&mut self) -> Result<(), CommanderError> {
+        log::info!("Pausing Commander Unit...");
+
+        // Running tasks yield and are put back in the queue before snapshotting;
+        // one stuck past its checkpoint is still saved as pending by the snapshot
+        self.task_scheduler.preempt_running().await;
+        if tokio::time::timeout(PAUSE_GRACE, self.task_scheduler.wait_until_idle())
+            .await
+            .is_err()
+        {
+            log::warn!("Running tasks did not yield within {:?}, snapshotting anyway", PAUSE_GRACE);
+        }
+
+        let snapshot = CommanderSnapshot {
+            paused_at: Utc::now(),
+            scheduler: self.task_scheduler.snapshot().await,
+        };
+
+        let json = serde_json::to_string_pretty(&snapshot)
+            .map_err(|e| CommanderError::SnapshotError(e.to_string()))?;
+        self.snapshot_file
+            .write(json.as_bytes())
+            .map_err(|e| CommanderError::SnapshotError(e.to_string()))?;
+
+        self.stop().await?;
+        self.status.write().await.is_paused = true;
+
+        log::info!(
+            "Commander paused with {} queued tasks",
+            snapshot.scheduler.queue.len()
+        );
+        Ok(())
+    }
+
+    /// Resume a paused Commander from its snapshot
+    pub async fn resume(&mut self) -> Result<(), CommanderError> {
+        log::info!("Resuming Commander Unit...");
+        self.start().await
+    }
+
+    /// Load and remove the pause snapshot, if any
+    async fn restore_snapshot(&self) -> Result<(), CommanderError> {
+        // A damaged snapshot is moved aside and reported, so the next start is clean
+        let Some(snapshot) = self
+            .snapshot_file
+            .load::<CommanderSnapshot>()
+            .map_err(|e| CommanderError::SnapshotError(e.to_string()))?
+        else {
+            return Ok(());
+        };
+
+        log::info!(
+            "Restoring Commander snapshot from {} ({} tasks)",
+            snapshot.paused_at,
+            snapshot.scheduler.queue.len()
+        );
+
+        let pending = snapshot.scheduler.queue.len() as u64;
+        self.task_scheduler.restore(snapshot.scheduler).await;
+        self.status.write().await.tasks_pending = pending;
+
+        self.snapshot_file.remove();
+        Ok(())
+    }
+
     /// Get current status
     pub async fn get_status(&self) -> CommanderStatus {
         self.status.read().await.clone()
@@ -161,24 +400,184 @@ impl CommanderUnit {
 
     /// Update configuration
     pub async fn update_config(&self, new_config: CommanderConfig) {
+        self.status.write().await.autonomy_level = new_config.autonomy_level.clone();
+        self.task_scheduler.set_policy(self.scheduling_policy(&new_config)).await;
+        self.task_scheduler.set_trend_config(new_config.trend.clone()).await;
+        self.task_scheduler.set_archiving(new_config.archive_findings);
         let mut config = self.config.write().await;
         *config = new_config;
     }
 
     /// Add a research task manually
-    pub async fn add_research_task(&self, topic: String, priority: super::TaskPriority) {
-        let task = super::ResearchTask::new(topic, priority);
+    pub async fn add_research_task(
+        &self,
+        topic: String,
+        priority: super::TaskPriority,
+        foreground: bool,
+    ) {
+        let mut task = super::ResearchTask::new(topic, priority);
+        if foreground {
+            task = task.with_foreground();
+        }
         self.task_scheduler.add_task(task).await;
 
         let mut status = self.status.write().await;
         status.tasks_pending += 1;
     }
 
+    /// RSS/Atom feeds scanned as research sources
+    pub fn list_feeds(&self) -> Vec<CustomFeed> {
+        self.feeds.list()
+    }
+
+    /// Follow a feed; its first scan is queued on the next tick
+    pub async fn add_feed(&self, feed: CustomFeed) -> ResearchResult<()> {
+        self.feeds.add(feed)?;
+        let config = self.config.read().await;
+        self.task_scheduler.set_policy(self.scheduling_policy(&config)).await;
+        Ok(())
+    }
+
+    /// Stop following a feed
+    pub async fn remove_feed(&self, name: &str) -> ResearchResult<bool> {
+        let removed = self.feeds.remove(name)?;
+        let config = self.config.read().await;
+        self.task_scheduler.set_policy(self.scheduling_policy(&config)).await;
+        Ok(removed)
+    }
+
+    /// Scheduling policy from the config plus the followed feeds' poll intervals
+    fn scheduling_policy(&self, config: &CommanderConfig) -> SchedulingPolicy {
+        SchedulingPolicy::from_config(config).with_feeds(&self.feeds.list())
+    }
+
+    /// Local knowledge store shared with deep analysis
+    pub fn knowledge(&self) -> Arc<KnowledgeStore> {
+        self.knowledge.clone()
+    }
+
+    /// Mark the start of user-initiated work; background research yields until it ends
+    pub async fn begin_foreground_work(&self) {
+        self.task_scheduler.begin_foreground().await;
+    }
+
+    /// Mark the end of user-initiated work and resume preempted tasks
+    pub async fn end_foreground_work(&self) {
+        self.task_scheduler.end_foreground().await;
+    }
+
+    /// Decisions denied by the autonomy policy, waiting for approval
+    pub async fn get_pending_approvals(&self) -> Vec<PendingApproval> {
+        self.policy.pending_approvals().await
+    }
+
+    /// How many decisions the autonomy policy has denied and still holds
+    pub async fn get_policy_status(&self) -> PolicyStatus {
+        self.policy.status().await
+    }
+
+    /// Rules the decision engine maps signals to actions with
+    pub async fn decision_rules(&self) -> DecisionRules {
+        self.decision_engine.rules().await
+    }
+
+    /// Validate and save new decision rules; they apply to the next signal
+    pub async fn update_decision_rules(&self, rules: DecisionRules) -> Result<(), String> {
+        self.decision_engine.update_rules(rules).await
+    }
+
+    /// Receive approval queue changes
+    pub fn subscribe_approvals(&self) -> tokio::sync::broadcast::Receiver<ApprovalEvent> {
+        self.policy.subscribe()
+    }
+
+    /// Approve a parked decision and carry out its action; None if it is not waiting
+    pub async fn approve_decision(&self, decision_id: &str) -> Option<PendingApproval> {
+        let pending = self.policy.resolve(decision_id, true, None).await?;
+        self.record_verdict("Godkendt", &pending, &pending.decision.rationale).await;
+        self.executor().execute(&pending.decision, pending.work.clone()).await;
+        Some(pending)
+    }
+
+    /// Reject a parked decision; its action is dropped. None if it is not waiting
+    pub async fn reject_decision(&self, decision_id: &str, reason: Option<&str>) -> Option<PendingApproval> {
+        let pending = self.policy.resolve(decision_id, false, reason).await?;
+        self.record_verdict("Afvist", &pending, reason.unwrap_or(&pending.decision.rationale)).await;
+        Some(pending)
+    }
+
+    async fn record_verdict(&self, verdict: &str, pending: &PendingApproval, reason: &str) {
+        if let Some(activity) = &self.activity {
+            let summary = format!(
+                "{}: {} ({})",
+                verdict,
+                action_summary(&pending.decision.action),
+                pending.work.topic
+            );
+            activity.record(ActivityCategory::Commander, summary, Some(reason)).await;
+        }
+    }
+
+    fn executor(&self) -> ActionExecutor {
+        ActionExecutor {
+            task_scheduler: self.task_scheduler.clone(),
+            deep_analyzer: self.deep_analyzer.clone(),
+            telemetry: self.telemetry.clone(),
+            notifications: self.notifications.clone(),
+        }
+    }
+
     /// Get recent findings
     pub async fn get_recent_findings(&self, limit: usize) -> Vec<ResearchFinding> {
         self.task_scheduler.get_recent_findings(limit).await
     }
 
+    /// Get new, updated and rescored findings since a point in time
+    pub async fn get_findings_diff(
+        &self,
+        since: DateTime<Utc>,
+    ) -> crate::research::history::FindingsDiff {
+        self.task_scheduler.get_findings_diff(since).await
+    }
+
+    /// Record whether a finding was useful so future relevance scores follow the user
+    pub async fn rate_finding(
+        &self,
+        finding_id: &str,
+        useful: bool,
+    ) -> Option<crate::research::traits::ResearchResult<crate::research::feedback::LearnedScoring>> {
+        self.task_scheduler.rate_finding(finding_id, useful).await
+    }
+
+    /// Get the relevance score breakdown of a finding
+    pub async fn get_finding_score_breakdown(
+        &self,
+        finding_id: &str,
+    ) -> Option<crate::research::processors::ScoreBreakdown> {
+        self.task_scheduler.get_score_breakdown(finding_id).await
+    }
+
+    /// Recompute finding scores with a scorer version
+    pub async fn rescore_findings(
+        &self,
+        version: u32,
+    ) -> Option<crate::commander::task_scheduler::RescoreReport> {
+        self.task_scheduler.rescore_findings(version).await
+    }
+
+    /// Get locally archived content of a finding
+    pub async fn get_finding_content(
+        &self,
+        finding_id: &str,
+    ) -> Option<crate::research::archive::ArchivedContent> {
+        self.task_scheduler.get_finding_content(finding_id).await
+    }
+
+    /// Get knowledge chunks stored by deep analysis of a finding
+    pub async fn get_knowledge_chunks(&self, finding_id: &str) -> Vec<crate::models::LocalKnowledgeChunk> {
+        self.knowledge.chunks_for_source(finding_id).await
+    }
+
     /// Force sync with CKC
     pub async fn force_sync(&self) -> Result<(), CommanderError> {
         self.ckc_sync.sync_now().await
@@ -202,6 +601,93 @@ impl CommanderUnit {
     }
 }
 
+/// Carries out allowed and approved decisions
+struct ActionExecutor {
+    task_scheduler: Arc<TaskScheduler>,
+    deep_analyzer: Arc<DeepAnalyzer>,
+    telemetry: Option<Arc<TelemetryService>>,
+    notifications: Option<Arc<NotificationCenter>>,
+}
+
+impl ActionExecutor {
+    async fn execute(&self, decision: &Decision, work: DecisionWork) {
+        match decision.action {
+            Action::DeepAnalyze => {
+                match work.finding {
+                    Some(finding) if DeepAnalyzer::supports(&finding) => {
+                        log::info!("Deep analysis triggered for {}", finding.id);
+                        let analyzer = self.deep_analyzer.clone();
+                        let scheduler = self.task_scheduler.clone();
+                        let telemetry = self.telemetry.clone();
+                        tokio::spawn(async move {
+                            let analysis = run_timeboxed(
+                                TimeboxedWork::Ingestion,
+                                &finding.id,
+                                TimeboxedWork::Ingestion.budget(),
+                                analyzer.analyze(&finding),
+                            )
+                            .await;
+                            match analysis {
+                                Err(overrun) => {
+                                    report_overrun(telemetry.as_deref(), &overrun, OverrunAction::Aborted).await;
+                                }
+                                Ok(Ok(analysis)) => {
+                                    scheduler
+                                        .attach_highlights(&finding.id, &analysis.highlights)
+                                        .await;
+                                }
+                                Ok(Err(e)) => {
+                                    log::warn!("Deep analysis of {} failed: {}", finding.id, e);
+                                }
+                            }
+                        });
+                    }
+                    Some(finding) => {
+                        log::info!("Deep analysis not supported for {:?} finding {}", finding.source, finding.id);
+                    }
+                    None => {
+                        log::debug!("Deep analysis triggered without a finding");
+                    }
+                }
+            }
+            Action::QueueForReview => {
+                log::info!("Queued for human review");
+            }
+            Action::ImmediateAlert => {
+                log::warn!("Immediate alert: {}", decision.rationale);
+                if let Some(notifications) = &self.notifications {
+                    notifications
+                        .notify(
+                            NotificationCategory::Research,
+                            format!("Vigtigt fund: {}", work.topic),
+                            decision.rationale.clone(),
+                            Vec::new(),
+                        )
+                        .await;
+                }
+            }
+            Action::Archive => {
+                log::debug!("Archived finding");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Activity log wording of a decision's action
+fn action_summary(action: &Action) -> &'static str {
+    match action {
+        Action::DeepAnalyze => "Startede dybdeanalyse",
+        Action::QueueForReview => "Satte fund i kø til gennemsyn",
+        Action::Archive => "Arkiverede fund",
+        Action::ImmediateAlert => "Sendte straks-advarsel",
+        Action::Monitor => "Overvåger emnet",
+        Action::RecommendAction => "Anbefalede en handling",
+        Action::RequestValidation => "Bad om bekræftelse",
+        Action::StandardProcess => "Behandlede fund",
+    }
+}
+
 /// Commander Unit errors
 #[derive(Debug, thiserror::Error)]
 pub enum CommanderError {
@@ -216,4 +702,7 @@ pub enum CommanderError {
 
     #[error("Task error: {0}")]
     TaskError(String),
+
+    #[error("Snapshot error: {0}")]
+    SnapshotError(String),
 }
@@ -3,24 +3,76 @@
 
 use tauri::{State, Emitter};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex, RwLock};
 
 use crate::accessibility::{
     AccessibilityConfig, AccessibilityEvent, VoiceState,
-    VoiceController, VoiceCommand,
+    VoiceController, VoiceCommand, ParsedCommand, SoundCue, SoundTheme, HotwordStats,
+    Narrator, UiEvent, UiNarration,
+    audio_input::InputDevice,
+    streaming::{StreamingConfig, StreamingSession},
 };
+use crate::error::ClaError;
+use crate::inference::InferenceEngine;
+use crate::commands::settings::persist_settings;
+use crate::models::{Settings, VoiceMacro};
+use crate::notifications::NotificationCenter;
+use crate::security::consent::Capability;
+use crate::security::privacy::PrivacyMode;
+use crate::telemetry::TelemetryService;
+use crate::utils::Watchdog;
+use crate::AppState;
 
 /// Accessibility state (managed by Tauri)
 pub struct AccessibilityState {
     pub controller: Arc<RwLock<VoiceController>>,
     pub config: Arc<RwLock<AccessibilityConfig>>,
+    /// Live microphone transcription, while one runs
+    pub streaming: Arc<Mutex<Option<StreamingSession>>>,
+    /// UI narrations, forwarded to the frontend as "ui-narration"
+    pub narrator: Arc<Narrator>,
 }
 
 impl AccessibilityState {
     pub fn new(config: AccessibilityConfig) -> Self {
+        let narrator = Arc::new(Narrator::new());
         Self {
-            controller: Arc::new(RwLock::new(VoiceController::new(config.clone()))),
+            controller: Arc::new(RwLock::new(VoiceController::new(config.clone()).with_narrator(narrator.clone()))),
             config: Arc::new(RwLock::new(config)),
+            streaming: Arc::new(Mutex::new(None)),
+            narrator,
+        }
+    }
+}
+
+impl AccessibilityState {
+    /// Create state whose voice controller uses the shared settings, telemetry,
+    /// watchdog, notification center and intent model
+    pub fn with_services(
+        settings: Arc<RwLock<Settings>>,
+        telemetry: Arc<TelemetryService>,
+        watchdog: Arc<Watchdog>,
+        notifications: Arc<NotificationCenter>,
+        privacy: Arc<PrivacyMode>,
+        inference_engine: Arc<RwLock<Option<InferenceEngine>>>,
+    ) -> Self {
+        let config = AccessibilityConfig::default();
+        let narrator = Arc::new(Narrator::new());
+        Self {
+            controller: Arc::new(RwLock::new(
+                VoiceController::new(config.clone())
+                    .with_narrator(narrator.clone())
+                    .with_settings(settings)
+                    .with_telemetry(telemetry)
+                    .with_watchdog(watchdog)
+                    .with_notifications(notifications)
+                    .with_privacy(privacy)
+                    .with_intents(inference_engine.clone())
+                    .with_transcription(inference_engine),
+            )),
+            config: Arc::new(RwLock::new(config)),
+            streaming: Arc::new(Mutex::new(None)),
+            narrator,
         }
     }
 }
@@ -73,8 +125,10 @@ pub async fn get_voice_state(
 #[tauri::command]
 pub async fn start_voice_control(
     state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
     window: tauri::Window,
 ) -> Result<(), String> {
+    app.consent.require(Capability::Microphone)?;
     let mut controller = state.controller.write().await;
 
     // Subscribe to events and forward to frontend
@@ -120,21 +174,189 @@ pub async fn speak_text(
 #[tauri::command]
 pub async fn listen_for_command(
     state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
 ) -> Result<String, String> {
+    app.consent.require(Capability::Microphone)?;
     let controller = state.controller.read().await;
     controller.listen_now().await
 }
 
+/// Record one sample of the hotword; detection starts once enough are enrolled
+#[tauri::command]
+pub async fn enroll_hotword_sample(
+    state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
+) -> Result<HotwordStats, String> {
+    app.consent.require(Capability::Microphone)?;
+    let controller = state.controller.read().await;
+    controller.enroll_hotword_sample().await
+}
+
+/// Forget the recorded hotword samples
+#[tauri::command]
+pub async fn clear_hotword_samples(
+    state: State<'_, AccessibilityState>,
+) -> Result<(), String> {
+    let controller = state.controller.read().await;
+    controller.clear_hotword_samples().await;
+    Ok(())
+}
+
+/// Hotword detection statistics (detections, false positives, near misses)
+#[tauri::command]
+pub async fn get_hotword_stats(
+    state: State<'_, AccessibilityState>,
+) -> Result<HotwordStats, String> {
+    let controller = state.controller.read().await;
+    Ok(controller.hotword_stats().await)
+}
+
+/// Transcribe the microphone live. Partial and final transcripts are emitted as
+/// `streaming-transcription` events until `stop_streaming_transcription`.
+#[tauri::command]
+pub async fn start_streaming_transcription(
+    state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
+    window: tauri::Window,
+    language: Option<String>,
+) -> Result<uuid::Uuid, String> {
+    app.consent.require(Capability::Microphone)?;
+    if app.privacy.is_active() {
+        return Err("Mikrofonen er slået fra i privat tilstand".to_string());
+    }
+    if !app.inference_engine.read().await.as_ref().is_some_and(|engine| engine.has_whisper_model()) {
+        return Err("Talegenkendelse kræver Whisper-modellen. Download den under Modeller.".to_string());
+    }
+
+    let mut streaming = state.streaming.lock().await;
+    if streaming.as_ref().is_some_and(|session| session.is_running()) {
+        return Err("Live-transskription kører allerede".to_string());
+    }
+
+    let (device, language, noise_suppression) = {
+        let config = state.config.read().await;
+        (
+            config.input_device.clone(),
+            language.or_else(|| Some(config.language.clone())),
+            config.noise_suppression,
+        )
+    };
+    let session = StreamingSession::start(
+        device,
+        language,
+        StreamingConfig {
+            noise_suppression,
+            ..StreamingConfig::default()
+        },
+        app.inference_engine.clone(),
+        Some(app.privacy.clone()),
+        move |event| {
+            let _ = window.emit("streaming-transcription", &event);
+        },
+    )?;
+
+    log::info!("Streaming transcription {} started", session.id);
+    let id = session.id;
+    *streaming = Some(session);
+    Ok(id)
+}
+
+/// Stop live transcription; the last utterance is still transcribed
+#[tauri::command]
+pub async fn stop_streaming_transcription(
+    state: State<'_, AccessibilityState>,
+) -> Result<(), String> {
+    let session = state.streaming.lock().await.take();
+    match session {
+        Some(session) => {
+            let id = session.id;
+            session.stop().await;
+            log::info!("Streaming transcription {} stopped", id);
+            Ok(())
+        }
+        None => Err("Ingen live-transskription kører".to_string()),
+    }
+}
+
+/// Preview an audio cue with its current volume. `cue` is a cue name such as
+/// "confirm"; the legacy names "notification" and "success" are accepted too.
+#[tauri::command]
+pub async fn preview_sound_cue(
+    state: State<'_, AccessibilityState>,
+    cue: String,
+) -> Result<(), String> {
+    let cue = SoundCue::from_name(&cue).ok_or_else(|| format!("Ukendt lyd: {}", cue))?;
+    let controller = state.controller.read().await;
+    controller.preview_cue(cue).await
+}
+
+/// Installed sound themes; select one with `sound_cues.theme` in the config
+#[tauri::command]
+pub async fn list_sound_themes() -> Result<Vec<SoundTheme>, String> {
+    use crate::accessibility::speech_synthesis;
+
+    Ok(speech_synthesis::list_sound_themes(&speech_synthesis::sound_themes_dir()))
+}
+
+/// List available microphones
+#[tauri::command]
+pub async fn list_input_devices(
+    state: State<'_, AccessibilityState>,
+) -> Result<Vec<InputDevice>, String> {
+    let controller = state.controller.read().await;
+    Ok(controller.list_input_devices().await)
+}
+
+/// Select the microphone used for voice control (None = system default)
+#[tauri::command]
+pub async fn select_input_device(
+    state: State<'_, AccessibilityState>,
+    device: Option<String>,
+) -> Result<(), String> {
+    {
+        let controller = state.controller.read().await;
+        controller.select_input_device(device.clone()).await?;
+    }
+
+    let mut config = state.config.write().await;
+    config.input_device = device;
+    Ok(())
+}
+
+/// Narrate an error aloud and offer its recovery action
+#[tauri::command]
+pub async fn narrate_error(
+    state: State<'_, AccessibilityState>,
+    error: ClaError,
+) -> Result<(), String> {
+    let controller = state.controller.read().await;
+    let handler = controller.recovery_for(&error).await;
+    controller.narrate_error(&error, handler).await
+}
+
+/// Describe a page change, focus move or announcement for screen reader
+/// users; read aloud in screen reader mode. None if nothing new was said.
+#[tauri::command]
+pub async fn narrate_ui(
+    state: State<'_, AccessibilityState>,
+    event: UiEvent,
+) -> Result<Option<UiNarration>, String> {
+    let controller = state.controller.read().await;
+    Ok(controller.narrate_ui(event).await)
+}
+
 /// Execute a voice command programmatically
 #[tauri::command]
 pub async fn execute_voice_command(
+    app: State<'_, AppState>,
     command: String,
 ) -> Result<String, String> {
     // Parse command string into VoiceCommand
     use crate::accessibility::CommandParser;
 
     let parser = CommandParser::new("da-DK");
-    let voice_command = parser.parse(&command).await;
+    let macros = app.settings.read().await.voice_macros.clone();
+    let voice_command = parser.parse_with_macros(&command, &macros).await.command;
 
     // Return description of what would be done
     match voice_command {
@@ -142,17 +364,102 @@ pub async fn execute_voice_command(
         VoiceCommand::StopCommander => Ok("Commander Unit stoppes".to_string()),
         VoiceCommand::GetStatus => Ok("Henter status...".to_string()),
         VoiceCommand::Search { query } => Ok(format!("Søger efter: {}", query)),
-        VoiceCommand::CreateTask { description, priority } => {
-            Ok(format!("Opretter opgave: {} (prioritet: {})", description, priority))
-        }
+        VoiceCommand::CreateTask { description, priority, due } => match due {
+            Some(due) => Ok(format!(
+                "Opretter opgave: {} (prioritet: {}, frist: {})",
+                description,
+                priority,
+                due.format("%d.%m.%Y %H:%M")
+            )),
+            None => Ok(format!("Opretter opgave: {} (prioritet: {})", description, priority)),
+        },
         VoiceCommand::ReadNotifications => Ok("Læser notifikationer...".to_string()),
+        VoiceCommand::SetSetting { setting, value } => {
+            Ok(format!("Sætter {:?} til {}", setting, value))
+        }
+        VoiceCommand::ToggleSetting { setting, enabled } => {
+            Ok(format!("Slår {:?} {}", setting, if enabled { "til" } else { "fra" }))
+        }
+        VoiceCommand::SetPrivacyMode { enabled } => {
+            Ok(format!("Privat tilstand {}", if enabled { "slås til" } else { "slås fra" }))
+        }
+        VoiceCommand::SyncNow => Ok("Synkroniserer...".to_string()),
+        VoiceCommand::RunMacro { phrase, commands } => {
+            Ok(format!("Kører makro: {} ({} handlinger)", phrase, commands.len()))
+        }
         VoiceCommand::Help => Ok("Viser hjælp...".to_string()),
+        VoiceCommand::Confirm => Ok("Bekræfter...".to_string()),
         VoiceCommand::Cancel => Ok("Handling annulleret".to_string()),
         VoiceCommand::Repeat => Ok("Gentager sidste besked...".to_string()),
         VoiceCommand::Unknown(text) => Ok(format!("Ukendt kommando: {}", text)),
     }
 }
 
+/// Parse a command without running it, with the parser's confidence
+#[tauri::command]
+pub async fn parse_voice_command(
+    state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
+    command: String,
+) -> Result<ParsedCommand, String> {
+    use crate::accessibility::CommandParser;
+
+    let language = state.config.read().await.language.clone();
+    let macros = app.settings.read().await.voice_macros.clone();
+    Ok(CommandParser::new(&language).parse_with_macros(&command, &macros).await)
+}
+
+/// The user's voice macros
+#[tauri::command]
+pub async fn list_voice_macros(app: State<'_, AppState>) -> Result<Vec<VoiceMacro>, String> {
+    Ok(app.settings.read().await.voice_macros.clone())
+}
+
+/// Create a voice macro, or replace the one with the same id
+#[tauri::command]
+pub async fn save_voice_macro(
+    state: State<'_, AccessibilityState>,
+    app: State<'_, AppState>,
+    voice_macro: VoiceMacro,
+) -> Result<VoiceMacro, String> {
+    use crate::accessibility::CommandParser;
+
+    let mut voice_macro = VoiceMacro {
+        phrase: voice_macro.phrase.trim().to_string(),
+        actions: voice_macro.actions.iter().map(|a| a.trim().to_string()).filter(|a| !a.is_empty()).collect(),
+        ..voice_macro
+    };
+    if voice_macro.id.is_nil() {
+        voice_macro.id = uuid::Uuid::new_v4();
+    }
+
+    let language = state.config.read().await.language.clone();
+    let mut settings = app.settings.write().await;
+    CommandParser::new(&language).validate_macro(&voice_macro, &settings.voice_macros)?;
+
+    match settings.voice_macros.iter_mut().find(|m| m.id == voice_macro.id) {
+        Some(existing) => *existing = voice_macro.clone(),
+        None => settings.voice_macros.push(voice_macro.clone()),
+    }
+    persist_settings(&settings).await?;
+
+    log::info!("Voice macro saved: {}", voice_macro.phrase);
+    Ok(voice_macro)
+}
+
+/// Delete a voice macro; false if it did not exist
+#[tauri::command]
+pub async fn delete_voice_macro(app: State<'_, AppState>, id: uuid::Uuid) -> Result<bool, String> {
+    let mut settings = app.settings.write().await;
+    let before = settings.voice_macros.len();
+    settings.voice_macros.retain(|m| m.id != id);
+    if settings.voice_macros.len() == before {
+        return Ok(false);
+    }
+    persist_settings(&settings).await?;
+    Ok(true)
+}
+
 /// Get available voice commands
 #[tauri::command]
 pub async fn get_available_commands() -> Result<Vec<CommandInfo>, String> {
@@ -199,6 +506,18 @@ pub async fn get_available_commands() -> Result<Vec<CommandInfo>, String> {
             description: "Get system status".to_string(),
             category: "Information".to_string(),
         },
+        CommandInfo {
+            danish: vec![
+                "synkroniser".to_string(),
+                "synkroniser nu".to_string(),
+            ],
+            english: vec![
+                "sync".to_string(),
+                "sync now".to_string(),
+            ],
+            description: "Sync with CKC now".to_string(),
+            category: "Control".to_string(),
+        },
         CommandInfo {
             danish: vec![
                 "søg efter [emne]".to_string(),
@@ -215,10 +534,12 @@ pub async fn get_available_commands() -> Result<Vec<CommandInfo>, String> {
             danish: vec![
                 "opret opgave [beskrivelse]".to_string(),
                 "ny opgave [beskrivelse]".to_string(),
+                "opret opgave [beskrivelse] i morgen kl 9".to_string(),
             ],
             english: vec![
                 "create task [description]".to_string(),
                 "new task [description]".to_string(),
+                "remind me to [description] on friday at 2 pm".to_string(),
             ],
             description: "Create a new task".to_string(),
             category: "Tasks".to_string(),
@@ -247,6 +568,32 @@ pub async fn get_available_commands() -> Result<Vec<CommandInfo>, String> {
             description: "Get help".to_string(),
             category: "Help".to_string(),
         },
+        CommandInfo {
+            danish: vec![
+                "sæt CPU-grænse til 50 procent".to_string(),
+                "slå synkronisering fra".to_string(),
+                "slå tekstgenkendelse til".to_string(),
+            ],
+            english: vec![
+                "set CPU limit to 50 percent".to_string(),
+                "turn off sync".to_string(),
+                "turn on text recognition".to_string(),
+            ],
+            description: "Adjust settings".to_string(),
+            category: "Settings".to_string(),
+        },
+        CommandInfo {
+            danish: vec![
+                "ja".to_string(),
+                "prøv igen".to_string(),
+            ],
+            english: vec![
+                "yes".to_string(),
+                "try again".to_string(),
+            ],
+            description: "Confirm the offered action".to_string(),
+            category: "Control".to_string(),
+        },
         CommandInfo {
             danish: vec![
                 "annuller".to_string(),
@@ -288,6 +635,7 @@ pub async fn toggle_accessibility_mode(
     config.auto_speak_responses = enabled;
     config.continuous_listening = enabled;
     config.sound_feedback = enabled;
+    config.screen_reader_mode = enabled;
 
     // If enabling, also enable helpful UI features
     if enabled {
@@ -0,0 +1,15 @@
+// Activity commands for Cirkelline Local Agent
+
+use tauri::State;
+use crate::AppState;
+use crate::activity::{ActivityCategory, ActivityEntry, ActivityRange};
+
+/// Get the activity timeline, newest first
+#[tauri::command]
+pub async fn get_activity(
+    state: State<'_, AppState>,
+    range: Option<ActivityRange>,
+    category: Option<ActivityCategory>,
+) -> Result<Vec<ActivityEntry>, String> {
+    Ok(state.activity.query(&range.unwrap_or_default(), category).await)
+}
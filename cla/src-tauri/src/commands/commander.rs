@@ -2,10 +2,27 @@
 // Connected to real CommanderUnit implementation
 
 use crate::commander::{
-    CommanderConfig, CommanderStatus, CommanderUnit, ResearchFinding, TaskPriority,
-    task_scheduler::QueueStatus,
+    policy::{DecisionFilter, DecisionRecord, PendingApproval, PolicyStatus},
+    CommanderConfig, CommanderStatus, CommanderUnit, DecisionRules, ResearchFinding, TaskPriority,
+    task_scheduler::{QueueStatus, RescoreReport},
     sync::SyncStats,
 };
+use crate::inference::InferenceEngine;
+use crate::models::LocalKnowledgeChunk;
+use crate::research::adapters::{CredentialsRegistry, CustomFeed, RssAdapter};
+use crate::research::traits::ResearchAdapter;
+use crate::research::digest::{DigestBuilder, DigestPeriod, ResearchDigest, MAX_DIGEST_FINDINGS};
+use crate::research::store::{FindingFilter, StoredFinding};
+use crate::research::{archive::ArchivedContent, feedback::LearnedScoring, history::FindingsDiff, processors::{ScoreBreakdown, SCORER_VERSION}};
+use crate::commands::accessibility::AccessibilityState;
+use crate::activity::ActivityLog;
+use crate::notifications::NotificationCenter;
+use crate::security::consent::Capability;
+use crate::security::privacy::PrivacyMode;
+use crate::storage::LocalDatabase;
+use crate::AppState;
+use crate::telemetry::TelemetryService;
+use chrono::{DateTime, Duration, Utc};
 use tauri::State;
 use std::sync::Arc;
 use tokio::sync::{RwLock, mpsc};
@@ -30,6 +47,33 @@ impl CommanderState {
             findings_rx: Arc::new(RwLock::new(findings_rx)),
         }
     }
+
+    /// Create a CommanderState whose deep analysis embeds with the shared engine
+    /// and whose overruns, decisions and alerts go to telemetry, the activity log
+    /// and the notification center
+    pub fn with_services(
+        inference: Arc<RwLock<Option<InferenceEngine>>>,
+        telemetry: Arc<TelemetryService>,
+        activity: Arc<ActivityLog>,
+        notifications: Arc<NotificationCenter>,
+        privacy: Arc<PrivacyMode>,
+        database: Arc<LocalDatabase>,
+    ) -> Self {
+        let (findings_tx, findings_rx) = mpsc::channel::<ResearchFinding>(100);
+        let unit = CommanderUnit::new(CommanderConfig::default(), findings_tx)
+            .with_inference(inference)
+            .with_telemetry(telemetry)
+            .with_activity(activity)
+            .with_notifications(notifications)
+            .with_privacy(privacy)
+            .with_decision_log(database.clone())
+            .with_finding_store(database);
+
+        Self {
+            unit: Arc::new(RwLock::new(unit)),
+            findings_rx: Arc::new(RwLock::new(findings_rx)),
+        }
+    }
 }
 
 impl Default for CommanderState {
@@ -62,6 +106,18 @@ pub async fn update_commander_config(
     state: State<'_, CommanderState>,
     new_config: CommanderConfig,
 ) -> Result<(), String> {
+    new_config.trend.validate()?;
+    for schedule in &new_config.source_schedules {
+        if let Some((start, end)) = schedule.active_hours {
+            if start > 23 || end > 24 {
+                return Err(format!("Ugyldige aktive timer for {:?}: {}-{}", schedule.source, start, end));
+            }
+        }
+        if schedule.scan_interval_minutes == Some(0) || schedule.max_results == Some(0) {
+            return Err(format!("Interval og resultatgrænse for {:?} skal være større end 0", schedule.source));
+        }
+    }
+
     let unit = state.unit.read().await;
     unit.update_config(new_config).await;
     log::info!("Commander config updated via API");
@@ -72,7 +128,9 @@ pub async fn update_commander_config(
 #[tauri::command]
 pub async fn start_commander(
     state: State<'_, CommanderState>,
+    app: State<'_, AppState>,
 ) -> Result<(), String> {
+    app.consent.require(Capability::AutonomousResearch)?;
     let mut unit = state.unit.write().await;
 
     // Check if already running
@@ -108,12 +166,53 @@ pub async fn stop_commander(
     Ok(())
 }
 
+/// Pause Commander Unit, saving its progress to disk
+#[tauri::command]
+pub async fn pause_commander(
+    state: State<'_, CommanderState>,
+) -> Result<(), String> {
+    let mut unit = state.unit.write().await;
+
+    let status = unit.get_status().await;
+    if !status.is_running {
+        return Err("Commander is not running".to_string());
+    }
+
+    unit.pause().await
+        .map_err(|e| format!("Failed to pause Commander: {}", e))?;
+
+    log::info!("Commander Unit paused via API");
+    Ok(())
+}
+
+/// Resume Commander Unit from its saved progress
+#[tauri::command]
+pub async fn resume_commander(
+    state: State<'_, CommanderState>,
+    app: State<'_, AppState>,
+) -> Result<(), String> {
+    app.consent.require(Capability::AutonomousResearch)?;
+    let mut unit = state.unit.write().await;
+
+    let status = unit.get_status().await;
+    if status.is_running {
+        return Err("Commander is already running".to_string());
+    }
+
+    unit.resume().await
+        .map_err(|e| format!("Failed to resume Commander: {}", e))?;
+
+    log::info!("Commander Unit resumed via API");
+    Ok(())
+}
+
 /// Add a research task
 #[tauri::command]
 pub async fn add_research_task(
     state: State<'_, CommanderState>,
     topic: String,
     priority: String,
+    foreground: Option<bool>,
 ) -> Result<String, String> {
     let priority = match priority.to_lowercase().as_str() {
         "critical" => TaskPriority::Critical,
@@ -125,7 +224,7 @@ pub async fn add_research_task(
     };
 
     let unit = state.unit.read().await;
-    unit.add_research_task(topic.clone(), priority).await;
+    unit.add_research_task(topic.clone(), priority, foreground.unwrap_or(false)).await;
 
     // Generate task ID for tracking (the actual task has its own internal ID)
     let task_id = uuid::Uuid::new_v4().to_string();
@@ -156,6 +255,202 @@ pub async fn get_recent_findings(
     Ok(findings)
 }
 
+/// Get the relevance score breakdown of a finding
+#[tauri::command]
+pub async fn get_finding_score_breakdown(
+    state: State<'_, CommanderState>,
+    id: String,
+) -> Result<ScoreBreakdown, String> {
+    let unit = state.unit.read().await;
+    unit.get_finding_score_breakdown(&id)
+        .await
+        .ok_or_else(|| format!("Ingen scoreforklaring for fund: {}", id))
+}
+
+/// Mark a finding as useful or not; later scans score findings with what the ratings teach
+#[tauri::command]
+pub async fn rate_finding(
+    state: State<'_, CommanderState>,
+    id: String,
+    useful: bool,
+) -> Result<LearnedScoring, String> {
+    let unit = state.unit.read().await;
+    unit.rate_finding(&id, useful)
+        .await
+        .ok_or_else(|| format!("Fund ikke fundet: {}", id))?
+        .map_err(|e| e.to_string())
+}
+
+/// Recompute recent finding scores with a scorer version (default: the current one)
+/// so scores from different periods can be compared
+#[tauri::command]
+pub async fn rescore_findings(
+    state: State<'_, CommanderState>,
+    version: Option<u32>,
+) -> Result<RescoreReport, String> {
+    let version = version.unwrap_or(SCORER_VERSION);
+    let unit = state.unit.read().await;
+    unit.rescore_findings(version)
+        .await
+        .ok_or_else(|| format!("Ukendt scoreversion: {}", version))
+}
+
+/// Get what changed in the findings since `since` (default: the last 24 hours),
+/// optionally speaking a summary
+#[tauri::command]
+pub async fn get_findings_diff(
+    state: State<'_, CommanderState>,
+    accessibility: State<'_, AccessibilityState>,
+    since: Option<DateTime<Utc>>,
+    speak: Option<bool>,
+) -> Result<FindingsDiff, String> {
+    let since = since.unwrap_or_else(|| Utc::now() - Duration::hours(24));
+    let diff = state.unit.read().await.get_findings_diff(since).await;
+
+    if speak.unwrap_or(false) {
+        let is_danish = accessibility.config.read().await.language.starts_with("da");
+        let controller = accessibility.controller.read().await;
+        controller
+            .speak(&diff.spoken_summary(is_danish))
+            .await
+            .map_err(|e| format!("Kunne ikke læse ændringer op: {}", e))?;
+    }
+    Ok(diff)
+}
+
+/// Get the findings first seen in the last day, week or month (default: day)
+/// grouped by topic, optionally speaking a summary
+#[tauri::command]
+pub async fn get_research_digest(
+    app: State<'_, AppState>,
+    accessibility: State<'_, AccessibilityState>,
+    period: Option<DigestPeriod>,
+    speak: Option<bool>,
+) -> Result<ResearchDigest, String> {
+    let period = period.unwrap_or_default();
+    let since = Utc::now() - period.duration();
+    let filter = FindingFilter {
+        since: Some(since),
+        limit: Some(MAX_DIGEST_FINDINGS as u32),
+        ..Default::default()
+    };
+    let findings = app
+        .database
+        .search_findings("", &filter)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|stored| stored.finding)
+        .collect();
+    let digest = DigestBuilder::new()
+        .with_inference(app.inference_engine.clone())
+        .build(period, since, findings)
+        .await;
+
+    if speak.unwrap_or(false) {
+        let is_danish = accessibility.config.read().await.language.starts_with("da");
+        let controller = accessibility.controller.read().await;
+        controller
+            .speak(&digest.spoken_summary(is_danish))
+            .await
+            .map_err(|e| format!("Kunne ikke læse oversigten op: {}", e))?;
+    }
+    Ok(digest)
+}
+
+/// Get archived content of a finding for offline reading
+#[tauri::command]
+pub async fn get_finding_content(
+    state: State<'_, CommanderState>,
+    id: String,
+) -> Result<ArchivedContent, String> {
+    let unit = state.unit.read().await;
+    unit.get_finding_content(&id)
+        .await
+        .ok_or_else(|| format!("Intet arkiveret indhold for fund: {}", id))
+}
+
+/// Get knowledge chunks stored by deep analysis of a finding
+#[tauri::command]
+pub async fn get_finding_knowledge(
+    state: State<'_, CommanderState>,
+    id: String,
+) -> Result<Vec<LocalKnowledgeChunk>, String> {
+    let unit = state.unit.read().await;
+    Ok(unit.get_knowledge_chunks(&id).await)
+}
+
+/// List research adapters with a stored or environment credential
+#[tauri::command]
+pub async fn list_adapter_credentials() -> Result<Vec<String>, String> {
+    Ok(CredentialsRegistry::default().configured())
+}
+
+/// Store an API credential for a research adapter
+#[tauri::command]
+pub async fn set_adapter_credential(adapter: String, credential: String) -> Result<(), String> {
+    CredentialsRegistry::default()
+        .set(&adapter, &credential)
+        .map_err(|e| format!("Kunne ikke gemme nøgle for {}: {}", adapter, e))
+}
+
+/// Remove the stored API credential of a research adapter
+#[tauri::command]
+pub async fn remove_adapter_credential(adapter: String) -> Result<bool, String> {
+    CredentialsRegistry::default()
+        .remove(&adapter)
+        .map_err(|e| format!("Kunne ikke fjerne nøgle for {}: {}", adapter, e))
+}
+
+/// List the RSS/Atom feeds followed as research sources
+#[tauri::command]
+pub async fn list_research_feeds(
+    state: State<'_, CommanderState>,
+) -> Result<Vec<CustomFeed>, String> {
+    let unit = state.unit.read().await;
+    Ok(unit.list_feeds())
+}
+
+/// Follow an RSS/Atom feed; it is fetched once first so a wrong URL is caught here
+#[tauri::command]
+pub async fn add_research_feed(
+    state: State<'_, CommanderState>,
+    name: String,
+    url: String,
+    topic: Option<String>,
+    poll_interval_minutes: Option<u32>,
+) -> Result<CustomFeed, String> {
+    let feed = CustomFeed {
+        name: name.trim().to_string(),
+        url: url.trim().to_string(),
+        topic: topic.map(|t| t.trim().to_string()).filter(|t| !t.is_empty()),
+        poll_interval_minutes,
+        added_at: Utc::now(),
+    };
+    RssAdapter::new(feed.clone())
+        .validate()
+        .await
+        .map_err(|e| format!("Kunne ikke læse feedet: {}", e))?;
+
+    let unit = state.unit.read().await;
+    unit.add_feed(feed.clone())
+        .await
+        .map_err(|e| format!("Kunne ikke tilføje feedet: {}", e))?;
+    log::info!("Following research feed {} ({})", feed.name, feed.url);
+    Ok(feed)
+}
+
+/// Stop following an RSS/Atom feed
+#[tauri::command]
+pub async fn remove_research_feed(
+    state: State<'_, CommanderState>,
+    name: String,
+) -> Result<bool, String> {
+    let unit = state.unit.read().await;
+    unit.remove_feed(&name)
+        .await
+        .map_err(|e| format!("Kunne ikke fjerne feedet: {}", e))
+}
+
 /// Force sync with CKC
 #[tauri::command]
 pub async fn force_commander_sync(
@@ -178,6 +473,117 @@ pub async fn get_sync_stats(
     Ok(unit.get_sync_stats().await)
 }
 
+/// Get decisions denied by the autonomy policy that wait for approval
+#[tauri::command]
+pub async fn get_pending_approvals(
+    state: State<'_, CommanderState>,
+) -> Result<Vec<PendingApproval>, String> {
+    let unit = state.unit.read().await;
+    Ok(unit.get_pending_approvals().await)
+}
+
+/// Get how many decisions the autonomy policy has denied and how many still wait
+#[tauri::command]
+pub async fn get_policy_status(
+    state: State<'_, CommanderState>,
+) -> Result<PolicyStatus, String> {
+    let unit = state.unit.read().await;
+    Ok(unit.get_policy_status().await)
+}
+
+/// Approve a parked decision and carry out its action
+#[tauri::command]
+pub async fn approve_decision(
+    state: State<'_, CommanderState>,
+    id: String,
+) -> Result<PendingApproval, String> {
+    let unit = state.unit.read().await;
+    unit.approve_decision(&id)
+        .await
+        .ok_or_else(|| "Beslutningen venter ikke på godkendelse".to_string())
+}
+
+/// Reject a parked decision so its action is never carried out
+#[tauri::command]
+pub async fn reject_decision(
+    state: State<'_, CommanderState>,
+    id: String,
+    reason: Option<String>,
+) -> Result<PendingApproval, String> {
+    let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+    let unit = state.unit.read().await;
+    unit.reject_decision(&id, reason.as_deref())
+        .await
+        .ok_or_else(|| "Beslutningen venter ikke på godkendelse".to_string())
+}
+
+/// Get the rules that map research signals to Commander actions
+#[tauri::command]
+pub async fn get_decision_rules(
+    state: State<'_, CommanderState>,
+) -> Result<DecisionRules, String> {
+    let unit = state.unit.read().await;
+    Ok(unit.decision_rules().await)
+}
+
+/// Validate and save new decision rules
+#[tauri::command]
+pub async fn update_decision_rules(
+    state: State<'_, CommanderState>,
+    rules: DecisionRules,
+) -> Result<DecisionRules, String> {
+    let unit = state.unit.read().await;
+    unit.update_decision_rules(rules.clone()).await?;
+    Ok(rules)
+}
+
+/// Get logged Commander decisions with their approval history, newest first
+#[tauri::command]
+pub async fn get_decision_history(
+    app: State<'_, AppState>,
+    filter: Option<DecisionFilter>,
+) -> Result<Vec<DecisionRecord>, String> {
+    app.database
+        .list_decisions(&filter.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Get one logged Commander decision
+#[tauri::command]
+pub async fn get_decision(
+    app: State<'_, AppState>,
+    id: String,
+) -> Result<DecisionRecord, String> {
+    app.database
+        .get_decision(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Beslutning ikke fundet: {}", id))
+}
+
+/// Search stored findings of every scan by title, summary and tags
+#[tauri::command]
+pub async fn search_findings(
+    app: State<'_, AppState>,
+    query: String,
+    filters: Option<FindingFilter>,
+) -> Result<Vec<StoredFinding>, String> {
+    app.database
+        .search_findings(&query, &filters.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Get one stored finding
+#[tauri::command]
+pub async fn get_finding(
+    app: State<'_, AppState>,
+    id: String,
+) -> Result<StoredFinding, String> {
+    app.database
+        .get_finding(&id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Fund ikke fundet: {}", id))
+}
+
 /// Set Commander autonomy level
 #[tauri::command]
 pub async fn set_autonomy_level(
@@ -0,0 +1,37 @@
+// Capability consent commands for Cirkelline Local Agent
+
+use tauri::State;
+use crate::AppState;
+use crate::security::consent::{Capability, CapabilityGrant, CapabilityRequest};
+
+fn parse_capability(name: &str) -> Result<Capability, String> {
+    Capability::from_name(name).ok_or_else(|| format!("Ukendt tilladelse: {}", name))
+}
+
+/// Check a capability before first use; returns a prompt when the user has not decided yet
+#[tauri::command]
+pub async fn request_capability(state: State<'_, AppState>, name: String) -> Result<CapabilityRequest, String> {
+    Ok(state.consent.request(parse_capability(&name)?))
+}
+
+/// Remember the user's answer to a capability prompt
+#[tauri::command]
+pub async fn respond_capability(
+    state: State<'_, AppState>,
+    name: String,
+    granted: bool,
+) -> Result<CapabilityGrant, String> {
+    state.consent.respond(parse_capability(&name)?, granted)
+}
+
+/// Remembered capability decisions
+#[tauri::command]
+pub async fn list_capability_grants(state: State<'_, AppState>) -> Result<Vec<CapabilityGrant>, String> {
+    Ok(state.consent.grants())
+}
+
+/// Forget a decision so the user is asked again
+#[tauri::command]
+pub async fn revoke_capability(state: State<'_, AppState>, name: String) -> Result<bool, String> {
+    state.consent.revoke(parse_capability(&name)?)
+}
@@ -0,0 +1,91 @@
+// Device management commands for Cirkelline Local Agent
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::AppState;
+use crate::security::device::{is_device_id, DeviceInfo};
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+
+/// A device registered with CKC under the current account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisteredDevice {
+    pub device_id: String,
+    pub name: String,
+    pub platform: String,
+    pub last_seen: Option<DateTime<Utc>>,
+    /// Whether this is the device the app is running on
+    #[serde(default)]
+    pub current: bool,
+}
+
+/// This device's identity
+#[tauri::command]
+pub async fn get_device_info(state: State<'_, AppState>) -> Result<DeviceInfo, String> {
+    Ok(state.device.info().clone())
+}
+
+/// List the account's devices known to CKC
+#[tauri::command]
+pub async fn list_devices(state: State<'_, AppState>) -> Result<Vec<RegisteredDevice>, String> {
+    let path = "/api/cla/devices";
+    let response = ckc_request(&state, reqwest::Method::GET, path).await?;
+
+    let mut devices: Vec<RegisteredDevice> = response
+        .json()
+        .await
+        .map_err(|e| format!("Ugyldigt svar fra CKC: {}", e))?;
+    for device in &mut devices {
+        device.current = device.device_id == state.device.device_id();
+    }
+    Ok(devices)
+}
+
+/// Revoke another device so it can no longer sync
+#[tauri::command]
+pub async fn revoke_device(state: State<'_, AppState>, device_id: String) -> Result<(), String> {
+    if !is_device_id(&device_id) {
+        return Err("Ugyldigt enheds-id".to_string());
+    }
+    if device_id == state.device.device_id() {
+        return Err("Denne enhed kan ikke tilbagekaldes herfra".to_string());
+    }
+
+    let path = format!("/api/cla/devices/{}", device_id);
+    ckc_request(&state, reqwest::Method::DELETE, &path).await?;
+    log::info!("Revoked device {}", device_id);
+    Ok(())
+}
+
+/// Authenticated, device-signed request to CKC
+async fn ckc_request(
+    state: &AppState,
+    method: reqwest::Method,
+    path: &str,
+) -> Result<reqwest::Response, String> {
+    let settings = state.settings.read().await;
+    if settings.offline_mode {
+        return Err("Offline-tilstand er aktiveret".to_string());
+    }
+    let endpoint = settings.ckc_endpoint.clone()
+        .unwrap_or_else(|| "https://ckc.cirkelline.com".to_string());
+    let api_key = settings.api_key.clone().filter(|k| !k.is_empty())
+        .ok_or("Log ind på CKC for at administrere enheder")?;
+    drop(settings);
+
+    let request = reqwest::Client::new()
+        .request(method.clone(), format!("{}{}", endpoint.trim_end_matches('/'), path))
+        .bearer_auth(api_key)
+        .timeout(std::time::Duration::from_secs(30));
+    let response = state
+        .device
+        .sign_request(request, method.as_str(), path, &[])
+        .send_metered(NetworkSubsystem::Sync)
+        .await
+        .map_err(|e| format!("Kunne ikke forbinde til CKC: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("CKC svarede med status: {}", response.status()));
+    }
+    Ok(response)
+}
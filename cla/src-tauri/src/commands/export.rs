@@ -0,0 +1,33 @@
+// Session export commands for Cirkelline Local Agent
+
+use tauri::State;
+use crate::AppState;
+use crate::error::ClaError;
+use crate::export::{self, ExportFormat};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Export a local session as Markdown or PDF to a user-chosen path
+#[tauri::command]
+pub async fn export_session(
+    state: State<'_, AppState>,
+    id: Uuid,
+    format: ExportFormat,
+    path: String,
+) -> Result<u64, String> {
+    let path = PathBuf::from(path);
+    if path.as_os_str().is_empty() {
+        return Err("Ingen sti valgt til eksporten".to_string());
+    }
+
+    let session = state
+        .database
+        .get_session(id)
+        .map_err(|e| ClaError::Storage(e).user_message())?
+        .ok_or_else(|| format!("Session ikke fundet: {}", id))?;
+    tokio::task::spawn_blocking(move || {
+        export::export_session(&session, format, &path)
+    })
+    .await
+    .map_err(|e| format!("Eksport mislykkedes: {}", e))?
+}
@@ -2,43 +2,331 @@
 
 use tauri::{State, Emitter};
 use crate::AppState;
+use crate::activity::ActivityCategory;
+use crate::commands::commander::CommanderState;
+use crate::commands::resource::check_execution;
 use crate::models::{
-    EmbeddingResult, TranscriptionResult, TextExtractionResult, ModelInfo,
+    BatchEmbeddingResult, CanExecuteResult, EmbeddingResult, Settings, TextGenerationResult, TranscriptionResult, TranscriptSummary, TextExtractionResult, ModelInfo,
 };
+use crate::inference::evaluation::{compare, evaluate, ComparisonReport, EvalDataset};
+use crate::inference::manifest::{self, ManifestEntry, ModelManifest};
+use crate::inference::{
+    backend_order, run_benchmark as run_hardware_benchmark, EmbeddingModel, HardwareProfile,
+    summarize_transcript, ExtractiveSummarizer, InferenceBackend, InferenceEngine, InferenceLane, LaneStats, QueueSnapshot,
+    GenerationParams, ModelLoadState, ModelStatus, RemoteInferenceClient, WarmupState, WarmupStatus, WhisperTask,
+    LLM_MODEL_DIR, MIN_SUMMARY_CHARS,
+};
+use crate::error::ClaError;
+use crate::security::consent::Capability;
+use crate::memory;
+use crate::storage::LocalDatabase;
+use crate::utils::timebox::{report_overrun, run_timeboxed, OverrunAction, TimeboxedWork};
+use crate::utils::ResourceMonitor;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Instant;
+use tokio::sync::RwLock;
+use uuid::Uuid;
 
-/// Generate embeddings for text using local model
+/// Generate embeddings for text, locally or through CKC per settings
 #[tauri::command]
 pub async fn generate_embedding(
     state: State<'_, AppState>,
     text: String,
+    lane: Option<InferenceLane>,
 ) -> Result<EmbeddingResult, String> {
     let start = Instant::now();
 
-    // Check if inference engine is available
+    let settings = state.settings.read().await.clone();
+    let engine_guard = state.inference_engine.read().await;
+    let local = engine_guard.as_ref().filter(|e| e.has_embedding_model());
+    let remote = RemoteInferenceClient::from_settings(&settings);
+
+    let backends = backend_order(settings.embedding_inference, local.is_some(), remote.is_some());
+    let mut last_error = "Ingen embedding-model tilgængelig lokalt eller via CKC".to_string();
+
+    for backend in backends {
+        let result = match (backend, local, &remote) {
+            (InferenceBackend::Local, Some(engine), _) => {
+                run_local(&state, "Embedding", "embedding", engine.generate_embedding_in(lane.unwrap_or_default(), &text))
+                    .await
+                    .map(|embedding| (embedding, "all-MiniLM-L6-v2".to_string()))
+            }
+            (InferenceBackend::Cloud, _, Some(client)) => client.generate_embedding(&text).await,
+            _ => continue,
+        };
+
+        match result {
+            Ok((embedding, model_used)) => {
+                return Ok(EmbeddingResult {
+                    embedding,
+                    model_used,
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+            Err(e) => {
+                log::warn!("{:?} embedding failed: {}", backend, e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Generate embeddings for many texts in one call, locally or through CKC per settings.
+/// Locally the texts run through the model in batches.
+#[tauri::command]
+pub async fn generate_embeddings_batch(
+    state: State<'_, AppState>,
+    texts: Vec<String>,
+    lane: Option<InferenceLane>,
+) -> Result<BatchEmbeddingResult, String> {
+    let start = Instant::now();
+
+    let settings = state.settings.read().await.clone();
+    let engine_guard = state.inference_engine.read().await;
+    let local = engine_guard.as_ref().filter(|e| e.has_embedding_model());
+    let remote = RemoteInferenceClient::from_settings(&settings);
+
+    let backends = backend_order(settings.embedding_inference, local.is_some(), remote.is_some());
+    let mut last_error = "Ingen embedding-model tilgængelig lokalt eller via CKC".to_string();
+
+    for backend in backends {
+        let result = match (backend, local, &remote) {
+            (InferenceBackend::Local, Some(engine), _) => {
+                run_local(&state, "Embedding", "embedding", engine.generate_embeddings_in(lane.unwrap_or_default(), texts.clone()))
+                    .await
+                    .map(|embeddings| (embeddings, "all-MiniLM-L6-v2".to_string()))
+            }
+            (InferenceBackend::Cloud, _, Some(client)) => embed_remote(client, &texts).await,
+            _ => continue,
+        };
+
+        match result {
+            Ok((embeddings, model_used)) => {
+                log::info!("Embedded {} texts in {:?}", texts.len(), start.elapsed());
+                return Ok(BatchEmbeddingResult {
+                    embeddings: embeddings
+                        .into_iter()
+                        .map(|(embedding, elapsed)| EmbeddingResult {
+                            embedding,
+                            model_used: model_used.clone(),
+                            processing_time_ms: elapsed.as_millis() as u64,
+                        })
+                        .collect(),
+                    model_used,
+                    processing_time_ms: start.elapsed().as_millis() as u64,
+                });
+            }
+            Err(e) => {
+                log::warn!("{:?} batch embedding failed: {}", backend, e);
+                last_error = e;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Embed texts one by one through CKC, timing each
+async fn embed_remote(
+    client: &RemoteInferenceClient,
+    texts: &[String],
+) -> Result<(Vec<(Vec<f32>, std::time::Duration)>, String), String> {
+    let mut embeddings = Vec::with_capacity(texts.len());
+    let mut model_used = String::new();
+    for text in texts {
+        let start = Instant::now();
+        let (embedding, model) = client.generate_embedding(text).await?;
+        embeddings.push((embedding, start.elapsed()));
+        model_used = model;
+    }
+    Ok((embeddings, model_used))
+}
+
+/// RAM the LLM needs on top of CLA's current use when it is not loaded yet
+const LLM_MEMORY_MB: u64 = 2600;
+
+/// Longest time generation waits for the resource limits before it gives up
+const LLM_MAX_PAUSE: std::time::Duration = std::time::Duration::from_secs(120);
+
+#[derive(serde::Serialize, Clone)]
+struct TokenEvent {
+    request_id: Uuid,
+    token: String,
+}
+
+/// Generate text with the local LLM, streaming each piece as an `llm-token` event.
+/// Runs only within the configured CPU and RAM limits: generation pauses while
+/// they are exceeded and stops if they stay exceeded.
+#[tauri::command]
+pub async fn generate_text(
+    state: State<'_, AppState>,
+    window: tauri::Window,
+    prompt: String,
+    max_tokens: Option<u32>,
+    temperature: Option<f32>,
+) -> Result<TextGenerationResult, String> {
+    let start = Instant::now();
+    let request_id = Uuid::new_v4();
+    let defaults = GenerationParams::default();
+    let params = GenerationParams {
+        max_tokens: max_tokens.unwrap_or(defaults.max_tokens).clamp(1, 4096),
+        temperature: temperature.unwrap_or(defaults.temperature).clamp(0.0, 2.0),
+        ..defaults
+    };
+
     let engine_guard = state.inference_engine.read().await;
     let engine = engine_guard
         .as_ref()
-        .ok_or("Inference-motor ikke initialiseret")?;
-
-    // Generate embedding
-    let embedding = engine.generate_embedding(&text).await?;
+        .filter(|e| e.has_llm_model())
+        .ok_or("Ingen sprogmodel tilgængelig. Download phi-3-mini-4k først.")?;
+
+    // The user asked for this, so it does not wait for idle, but it does respect the limits
+    let loaded = engine
+        .model_statuses()
+        .iter()
+        .any(|m| m.model_id == LLM_MODEL_DIR && m.state == ModelLoadState::Loaded);
+    let check = llm_resource_check(&state.settings, &state.resource_monitor, if loaded { 0 } else { LLM_MEMORY_MB }).await;
+    if !check.can_execute {
+        return Err(check.reason.unwrap_or_else(|| "Ressourcegrænse nået".to_string()));
+    }
 
-    Ok(EmbeddingResult {
-        embedding,
-        model_used: "all-MiniLM-L6-v2".to_string(),
+    let control = Arc::new(GenerationControl::default());
+    let _guard = GenerationGuard {
+        control: control.clone(),
+        watcher: tokio::spawn(watch_resources(
+            state.settings.clone(),
+            state.resource_monitor.clone(),
+            control.clone(),
+        )),
+    };
+
+    let on_token = {
+        let control = control.clone();
+        let window = window.clone();
+        move |token: &str| {
+            let _ = window.emit("llm-token", TokenEvent { request_id, token: token.to_string() });
+            control.wait_while_paused()
+        }
+    };
+    let result = engine
+        .generate_text_in(InferenceLane::Interactive, &prompt, params, on_token)
+        .await;
+
+    state
+        .telemetry
+        .record_inference("local", "generation", start.elapsed().as_millis() as u64, result.is_ok())
+        .await;
+    let generation = result?;
+
+    Ok(TextGenerationResult {
+        request_id,
+        text: generation.text,
+        prompt_tokens: generation.prompt_tokens,
+        completion_tokens: generation.completion_tokens,
+        finish_reason: generation.finish_reason,
+        stop_reason: control.stop_reason(),
         processing_time_ms: start.elapsed().as_millis() as u64,
     })
 }
 
-/// Transcribe audio file using local Whisper model
+/// Pause and stop flags shared between the resource watcher and the generating thread
+#[derive(Default)]
+struct GenerationControl {
+    paused: AtomicBool,
+    /// Set when the command returns or is dropped, so the model thread stops too
+    finished: AtomicBool,
+    stop_reason: std::sync::Mutex<Option<String>>,
+}
+
+impl GenerationControl {
+    fn stop_reason(&self) -> Option<String> {
+        self.stop_reason.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Block the generating thread while paused; false once it should stop
+    fn wait_while_paused(&self) -> bool {
+        while self.paused.load(Ordering::SeqCst) && self.should_continue() {
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        }
+        self.should_continue()
+    }
+
+    fn should_continue(&self) -> bool {
+        !self.finished.load(Ordering::SeqCst) && self.stop_reason().is_none()
+    }
+}
+
+struct GenerationGuard {
+    control: Arc<GenerationControl>,
+    watcher: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for GenerationGuard {
+    fn drop(&mut self) {
+        self.control.finished.store(true, Ordering::SeqCst);
+        self.watcher.abort();
+    }
+}
+
+/// Check the CPU and RAM limits for generation the user asked for, so without waiting for idle
+async fn llm_resource_check(
+    settings: &RwLock<Settings>,
+    monitor: &RwLock<ResourceMonitor>,
+    estimated_ram_mb: u64,
+) -> CanExecuteResult {
+    let settings = Settings { idle_only: false, ..settings.read().await.clone() };
+    let metrics = monitor.read().await.get_current_metrics();
+    check_execution(&settings, &metrics, 0, estimated_ram_mb, false)
+}
+
+/// Pause generation while CLA is over its limits; stop it if that lasts too long
+async fn watch_resources(
+    settings: Arc<RwLock<Settings>>,
+    monitor: Arc<RwLock<ResourceMonitor>>,
+    control: Arc<GenerationControl>,
+) {
+    let mut over_since: Option<Instant> = None;
+    loop {
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+        let check = llm_resource_check(&settings, &monitor, 0).await;
+        if check.can_execute {
+            over_since = None;
+            control.paused.store(false, Ordering::SeqCst);
+            continue;
+        }
+
+        let since = *over_since.get_or_insert_with(Instant::now);
+        control.paused.store(true, Ordering::SeqCst);
+        if since.elapsed() >= LLM_MAX_PAUSE {
+            let reason = check.reason.unwrap_or_else(|| "Ressourcegrænse nået".to_string());
+            log::warn!("Stopping text generation: {}", reason);
+            *control.stop_reason.lock().unwrap_or_else(|e| e.into_inner()) = Some(reason);
+            return;
+        }
+    }
+}
+
+/// Transcribe audio file using local Whisper or CKC per settings.
+/// Without `language` the spoken language is identified and reported in `detected_language`.
+/// With `translate` the text is translated to English and the original kept.
+/// Long transcripts get a summary stored as a memory, unless `summarize` is false.
 #[tauri::command]
 pub async fn transcribe_audio(
     state: State<'_, AppState>,
+    commander: State<'_, CommanderState>,
     audio_path: String,
     language: Option<String>,
+    lane: Option<InferenceLane>,
+    translate: Option<bool>,
+    summarize: Option<bool>,
 ) -> Result<TranscriptionResult, String> {
     let start = Instant::now();
+    let task = if translate.unwrap_or(false) { WhisperTask::Translate } else { WhisperTask::Transcribe };
+    state.consent.require(Capability::FolderAccess)?;
 
     // Validate file exists
     if !std::path::Path::new(&audio_path).exists() {
@@ -46,22 +334,56 @@ pub async fn transcribe_audio(
     }
 
     // Check settings
-    let settings = state.settings.read().await;
+    let settings = state.settings.read().await.clone();
     if !settings.enable_transcription {
         return Err("Transskription er deaktiveret i indstillinger".to_string());
     }
 
-    // Check inference engine
     let engine_guard = state.inference_engine.read().await;
-    let engine = engine_guard
-        .as_ref()
-        .ok_or("Inference-motor ikke initialiseret")?;
-
-    // Perform transcription
-    let result = engine.transcribe(&audio_path, language.as_deref()).await?;
+    let local = engine_guard.as_ref().filter(|e| e.has_whisper_model());
+    let remote = RemoteInferenceClient::from_settings(&settings);
+    let backends = backend_order(settings.transcription_inference, local.is_some(), remote.is_some());
+
+    // User-requested work: background research yields until we are done
+    commander.unit.read().await.begin_foreground_work().await;
+    let mut result = Err("Ingen transskriptionsmodel tilgængelig lokalt eller via CKC".to_string());
+    for backend in backends {
+        result = match (backend, local, &remote) {
+            (InferenceBackend::Local, Some(engine), _) => {
+                let transcription = engine.transcribe_in(lane.unwrap_or_default(), &audio_path, language.as_deref(), task);
+                run_local(&state, "Transskription", "transcription", transcription).await
+            }
+            (InferenceBackend::Cloud, _, Some(client)) => client.transcribe(&audio_path, language.as_deref(), task).await,
+            _ => continue,
+        };
+        match &result {
+            Ok(_) => break,
+            Err(e) => log::warn!("{:?} transcription failed: {}", backend, e),
+        }
+    }
+    commander.unit.read().await.end_foreground_work().await;
+    let result = result?;
+    state
+        .activity
+        .record(ActivityCategory::Inference, format!("Transskriberede {}", file_name(&audio_path)), None)
+        .await;
+
+    let summary = if summarize.unwrap_or(result.text.chars().count() >= MIN_SUMMARY_CHARS) {
+        match summarize_and_remember(&result.text, &file_name(&audio_path), local, &state.database).await {
+            Ok(summary) => Some(summary),
+            Err(e) => {
+                log::warn!("Transcript summary failed: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
 
     Ok(TranscriptionResult {
         text: result.text,
+        original_text: result.original_text,
+        detected_language: language.is_none().then(|| result.detected_language.clone()).flatten(),
         language: result.detected_language,
         confidence: result.confidence,
         segments: result
@@ -75,9 +397,39 @@ pub async fn transcribe_audio(
             })
             .collect(),
         processing_time_ms: start.elapsed().as_millis() as u64,
+        summary,
     })
 }
 
+/// Summarize a transcript and keep the summary as a local memory
+async fn summarize_and_remember(
+    text: &str,
+    source: &str,
+    engine: Option<&InferenceEngine>,
+    database: &LocalDatabase,
+) -> Result<TranscriptSummary, String> {
+    // Extractive until a local LLM can be loaded as a `ChunkSummarizer`
+    let transcript = text.to_string();
+    let mut summary = tokio::task::spawn_blocking(move || summarize_transcript(&transcript, &ExtractiveSummarizer::new()))
+        .await
+        .map_err(|e| format!("Opsummering afbrudt: {}", e))??;
+
+    let mut content = summary.abstract_text.clone();
+    if !summary.action_items.is_empty() {
+        content.push_str("\n\nHandlingspunkter:\n");
+        for item in &summary.action_items {
+            content.push_str(&format!("- {}\n", item));
+        }
+    }
+    let mut entry = memory::new_memory(content, "transcript_summary", vec!["transskription".to_string(), source.to_string()], 0.6);
+    if let Some(engine) = engine.filter(|e| e.has_embedding_model()) {
+        entry.embedding_local = engine.generate_embedding_in(InferenceLane::Background, &summary.abstract_text).await.ok();
+    }
+    database.save_memory(&entry).map_err(|e| ClaError::Storage(e).user_message())?;
+    summary.memory_id = Some(entry.id);
+    Ok(summary)
+}
+
 /// Extract text from image using OCR
 #[tauri::command]
 pub async fn extract_text(
@@ -85,6 +437,7 @@ pub async fn extract_text(
     image_path: String,
 ) -> Result<TextExtractionResult, String> {
     let start = Instant::now();
+    state.consent.require(Capability::FolderAccess)?;
 
     // Validate file exists
     if !std::path::Path::new(&image_path).exists() {
@@ -104,7 +457,11 @@ pub async fn extract_text(
         .ok_or("Inference-motor ikke initialiseret")?;
 
     // Perform OCR
-    let result = engine.extract_text(&image_path).await?;
+    let result = run_local(&state, "OCR", "ocr", engine.extract_text(&image_path)).await?;
+    state
+        .activity
+        .record(ActivityCategory::Inference, format!("Udtrak tekst fra {}", file_name(&image_path)), None)
+        .await;
 
     Ok(TextExtractionResult {
         text: result.text,
@@ -127,6 +484,66 @@ pub async fn extract_text(
     })
 }
 
+/// File name shown in the activity log (full paths stay private)
+fn file_name(path: &str) -> String {
+    std::path::Path::new(path)
+        .file_name()
+        .map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// Run a local model call within its time budget; overruns are aborted and reported
+async fn run_local<T>(
+    state: &AppState,
+    label: &str,
+    task_type: &str,
+    call: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    let start = Instant::now();
+    let result = match run_timeboxed(TimeboxedWork::Inference, label, TimeboxedWork::Inference.budget(), call).await {
+        Ok(result) => result,
+        Err(overrun) => {
+            report_overrun(Some(&state.telemetry), &overrun, OverrunAction::Aborted).await;
+            Err(overrun.to_string())
+        }
+    };
+    state
+        .telemetry
+        .record_inference("local", task_type, start.elapsed().as_millis() as u64, result.is_ok())
+        .await;
+    result
+}
+
+/// Get occupancy of the inference priority lanes
+#[tauri::command]
+pub async fn get_inference_lanes(state: State<'_, AppState>) -> Result<LaneStats, String> {
+    let engine_guard = state.inference_engine.read().await;
+    let engine = engine_guard
+        .as_ref()
+        .ok_or("Inference-motor ikke initialiseret")?;
+    Ok(engine.lane_stats())
+}
+
+/// Whether local embedding inference is loaded and warmed up, so the frontend can
+/// show when it is ready
+#[tauri::command]
+pub async fn get_inference_warmup_status(state: State<'_, AppState>) -> Result<WarmupStatus, String> {
+    Ok(match state.inference_engine.read().await.as_ref() {
+        Some(engine) => engine.embedding_warmup_status(),
+        None => WarmupStatus {
+            model_id: "all-minilm-l6-v2".to_string(),
+            state: WarmupState::NotStarted,
+            warmup_ms: None,
+            error: None,
+        },
+    })
+}
+
+/// Get waiting inference requests with their queue position and ETA
+#[tauri::command]
+pub async fn get_inference_queue(state: State<'_, AppState>) -> Result<QueueSnapshot, String> {
+    Ok(state.inference_scheduler.queue())
+}
+
 /// Get status of installed models
 #[tauri::command]
 pub async fn get_model_status() -> Result<Vec<ModelInfo>, String> {
@@ -153,14 +570,44 @@ pub async fn get_model_status() -> Result<Vec<ModelInfo>, String> {
             version: "1.0.0".to_string(),
         },
         ModelInfo {
-            id: "tesseract-wasm".to_string(),
-            name: "Tesseract OCR".to_string(),
-            size_mb: 15,
+            id: "silero-vad".to_string(),
+            name: "Silero VAD (Taledetektion)".to_string(),
+            size_mb: 2,
+            tier: 1,
+            capabilities: vec!["vad".to_string()],
+            downloaded: check_model_exists("silero-vad"),
+            download_progress: None,
+            version: "5.0.0".to_string(),
+        },
+        ModelInfo {
+            id: "ocr-det".to_string(),
+            name: "PaddleOCR Tekstdetektion".to_string(),
+            size_mb: 5,
+            tier: 1,
+            capabilities: vec!["ocr".to_string()],
+            downloaded: check_model_exists("ocr-det"),
+            download_progress: None,
+            version: "4.0.0".to_string(),
+        },
+        ModelInfo {
+            id: "ocr-rec-eng".to_string(),
+            name: "PaddleOCR Genkendelse (Engelsk)".to_string(),
+            size_mb: 10,
             tier: 1,
             capabilities: vec!["ocr".to_string()],
-            downloaded: check_model_exists("tesseract-wasm"),
+            downloaded: check_model_exists("ocr-rec-eng"),
+            download_progress: None,
+            version: "4.0.0".to_string(),
+        },
+        ModelInfo {
+            id: "ocr-rec-latin".to_string(),
+            name: "PaddleOCR Genkendelse (Dansk og latinske sprog)".to_string(),
+            size_mb: 10,
+            tier: 1,
+            capabilities: vec!["ocr".to_string(), "multilingual".to_string()],
+            downloaded: check_model_exists("ocr-rec-latin"),
             download_progress: None,
-            version: "5.3.0".to_string(),
+            version: "4.0.0".to_string(),
         },
         ModelInfo {
             id: "whisper-small".to_string(),
@@ -197,50 +644,21 @@ pub async fn get_model_status() -> Result<Vec<ModelInfo>, String> {
     Ok(models)
 }
 
-/// Download a model
+/// Download a model listed in the signed model manifest. An interrupted download
+/// continues where it stopped; the model is installed only if its checksum matches.
 #[tauri::command]
 pub async fn download_model(
+    state: State<'_, AppState>,
     model_id: String,
     window: tauri::Window,
 ) -> Result<(), String> {
     log::info!("Starting download of model: {}", model_id);
 
-    // Get model URL based on ID
-    let model_url = get_model_download_url(&model_id)
-        .ok_or(format!("Ukendt model: {}", model_id))?;
-
-    // Create download directory
     let models_dir = get_models_directory()?;
-    std::fs::create_dir_all(&models_dir)
-        .map_err(|e| format!("Kunne ikke oprette model-mappe: {}", e))?;
-
-    // Download with progress reporting
-    let client = reqwest::Client::new();
-    let response = client
-        .get(&model_url)
-        .send()
-        .await
-        .map_err(|e| format!("Download fejlede: {}", e))?;
-
-    let total_size = response.content_length().unwrap_or(0);
-    let mut downloaded = 0u64;
+    let entry = manifest_entry(&state, &models_dir, &model_id).await?;
 
-    let model_path = models_dir.join(format!("{}.onnx", model_id));
-    let mut file = std::fs::File::create(&model_path)
-        .map_err(|e| format!("Kunne ikke oprette fil: {}", e))?;
-
-    let mut stream = response.bytes_stream();
-    use futures_util::StreamExt;
-    use std::io::Write;
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| format!("Download fejl: {}", e))?;
-        file.write_all(&chunk)
-            .map_err(|e| format!("Skrivefejl: {}", e))?;
-
-        downloaded += chunk.len() as u64;
-
-        // Report progress
+    let total_size = entry.size_bytes;
+    manifest::download(&entry, &models_dir, |downloaded| {
         if total_size > 0 {
             let progress = (downloaded as f64 / total_size as f64) * 100.0;
             let _ = window.emit("model-download-progress", DownloadProgress {
@@ -250,12 +668,181 @@ pub async fn download_model(
                 total_mb: (total_size / 1024 / 1024) as u32,
             });
         }
-    }
+    })
+    .await?;
 
-    log::info!("Model {} downloaded successfully", model_id);
+    log::info!("Model {} downloaded and verified", model_id);
     Ok(())
 }
 
+/// Unload a model from memory; it loads again the next time it is needed
+#[tauri::command]
+pub async fn unload_model(state: State<'_, AppState>, model_id: String) -> Result<bool, String> {
+    let engine_guard = state.inference_engine.read().await;
+    let engine = engine_guard
+        .as_ref()
+        .ok_or("Inference-motor ikke initialiseret")?;
+    engine.unload_model(&model_id)
+}
+
+/// Reload all models from disk, starting the inference engine if it is not running.
+/// Picks up newly downloaded models without restarting the app.
+#[tauri::command]
+pub async fn reload_models(state: State<'_, AppState>) -> Result<Vec<ModelStatus>, String> {
+    // Swapping models needs no write lock, so inference keeps running meanwhile
+    if let Some(engine) = state.inference_engine.read().await.as_ref() {
+        return Ok(engine.reload_models().await);
+    }
+
+    let settings = state.settings.read().await.clone();
+    let mut engine_guard = state.inference_engine.write().await;
+    if let Some(engine) = engine_guard.as_ref() {
+        return Ok(engine.model_statuses());
+    }
+    let engine = InferenceEngine::new(
+        get_models_directory()?,
+        state.inference_scheduler.clone(),
+        state.model_events.clone(),
+        &settings,
+    )
+    .await?;
+    let statuses = engine.model_statuses();
+    *engine_guard = Some(engine);
+    Ok(statuses)
+}
+
+/// Manifest entry of a model, fetching a fresh manifest when the cached one lacks it
+async fn manifest_entry(
+    state: &AppState,
+    models_dir: &std::path::Path,
+    model_id: &str,
+) -> Result<ManifestEntry, String> {
+    if let Some(entry) = ModelManifest::load_cached(models_dir).and_then(|m| m.entry(model_id).cloned()) {
+        return Ok(entry);
+    }
+
+    let settings = state.settings.read().await.clone();
+    if settings.offline_mode {
+        return Err("Modelmanifestet kan ikke hentes i offline-tilstand".to_string());
+    }
+    let endpoint = settings
+        .ckc_endpoint
+        .ok_or("Ingen CKC-adresse konfigureret")?;
+    ModelManifest::fetch(&endpoint, models_dir)
+        .await?
+        .entry(model_id)
+        .cloned()
+        .ok_or(format!("Ukendt model: {}", model_id))
+}
+
+/// Compare two downloaded embedding models on a labeled query/passage set.
+/// The report is returned and saved under models/evaluation.
+#[tauri::command]
+pub async fn compare_embedding_models(
+    state: State<'_, AppState>,
+    model_a: String,
+    model_b: String,
+    dataset_path: Option<String>,
+    k: Option<usize>,
+) -> Result<ComparisonReport, String> {
+    if dataset_path.is_some() {
+        state.consent.require(Capability::FolderAccess)?;
+    }
+    let models_dir = get_models_directory()?;
+    let eval_dir = models_dir.join("evaluation");
+    let dataset_path = dataset_path
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| eval_dir.join("embeddings.json"));
+    let k = k.unwrap_or(5).max(1);
+
+    for model_id in [&model_a, &model_b] {
+        if !check_model_exists(model_id) {
+            return Err(format!("Model ikke downloadet: {}", model_id));
+        }
+    }
+
+    let report = tokio::task::spawn_blocking(move || -> Result<ComparisonReport, String> {
+        let dataset = EvalDataset::load(&dataset_path)?;
+
+        let mut metrics = Vec::with_capacity(2);
+        for model_id in [&model_a, &model_b] {
+            manifest::verify_installed(&models_dir, model_id)?;
+            let mut model = EmbeddingModel::load(&manifest::model_path(&models_dir, model_id))?;
+            log::info!("Evaluating embedding model {} (k={})", model_id, k);
+            metrics.push(evaluate(&dataset, model_id, k, |text| model.encode(text))?);
+        }
+
+        let model_b_metrics = metrics.pop().ok_or("Evaluering fejlede")?;
+        let model_a_metrics = metrics.pop().ok_or("Evaluering fejlede")?;
+        Ok(compare(&dataset, k, model_a_metrics, model_b_metrics))
+    })
+    .await
+    .map_err(|e| format!("Evaluering afbrudt: {}", e))??;
+
+    std::fs::create_dir_all(&eval_dir)
+        .map_err(|e| format!("Kunne ikke oprette evalueringsmappe: {}", e))?;
+    let report_path = eval_dir.join(format!("report-{}.json", report.generated_at.format("%Y%m%d-%H%M%S")));
+    let json = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    std::fs::write(&report_path, json)
+        .map_err(|e| format!("Kunne ikke gemme rapport: {}", e))?;
+
+    log::info!(
+        "Embedding comparison: {} MRR {:.3} vs {} MRR {:.3}",
+        report.model_a.model_id,
+        report.model_a.mrr,
+        report.model_b.model_id,
+        report.model_b.mrr
+    );
+    Ok(report)
+}
+
+/// Benchmark this machine and store the hardware profile used for
+/// task-duration estimates. `share` sends anonymized results via telemetry.
+#[tauri::command]
+pub async fn run_benchmark(
+    state: State<'_, AppState>,
+    share: Option<bool>,
+) -> Result<HardwareProfile, String> {
+    let models_dir = get_models_directory()?;
+    let engine_guard = state.inference_engine.read().await;
+
+    log::info!("Running hardware benchmark");
+    let profile = run_hardware_benchmark(engine_guard.as_ref(), &models_dir.join("benchmark"))
+        .await
+        .map_err(|e| format!("Benchmark fejlede: {}", e))?;
+
+    profile
+        .save(&HardwareProfile::path(&models_dir))
+        .map_err(|e| format!("Kunne ikke gemme hardwareprofil: {}", e))?;
+    if let Some(engine) = engine_guard.as_ref() {
+        engine.set_hardware_profile(profile.clone());
+    }
+
+    // Recorded only when telemetry consent is given
+    if share.unwrap_or(false) {
+        state.telemetry.record_hardware_benchmark(&profile).await;
+    }
+
+    log::info!(
+        "Benchmark: {:?} embeddings/s, Whisper RTF {:?}, OCR {:?} pages/min, disk {:.0}/{:.0} MB/s",
+        profile.embeddings_per_sec,
+        profile.whisper_rtf,
+        profile.ocr_pages_per_min,
+        profile.disk_read_mb_s,
+        profile.disk_write_mb_s
+    );
+    Ok(profile)
+}
+
+/// Get the stored hardware profile, if the machine was benchmarked
+#[tauri::command]
+pub async fn get_hardware_profile(state: State<'_, AppState>) -> Result<Option<HardwareProfile>, String> {
+    if let Some(engine) = state.inference_engine.read().await.as_ref() {
+        return Ok(engine.hardware_profile());
+    }
+    Ok(HardwareProfile::load(&HardwareProfile::path(&get_models_directory()?)))
+}
+
 fn check_model_exists(model_id: &str) -> bool {
     if let Ok(models_dir) = get_models_directory() {
         models_dir.join(format!("{}.onnx", model_id)).exists()
@@ -265,23 +852,8 @@ fn check_model_exists(model_id: &str) -> bool {
 }
 
 fn get_models_directory() -> Result<std::path::PathBuf, String> {
-    let data_dir = dirs::data_dir()
-        .ok_or("Kunne ikke finde data-mappe")?;
-    Ok(data_dir.join("cirkelline-cla").join("models"))
-}
-
-fn get_model_download_url(model_id: &str) -> Option<String> {
-    // In production, these would be actual URLs to model files
-    match model_id {
-        "all-minilm-l6-v2" => Some(
-            "https://huggingface.co/sentence-transformers/all-MiniLM-L6-v2/resolve/main/onnx/model.onnx".to_string()
-        ),
-        "whisper-tiny-en" => Some(
-            "https://huggingface.co/openai/whisper-tiny.en/resolve/main/onnx/encoder.onnx".to_string()
-        ),
-        // Add more model URLs as needed
-        _ => None,
-    }
+    crate::inference::default_models_dir()
+        .ok_or_else(|| "Kunne ikke finde data-mappe".to_string())
 }
 
 #[derive(serde::Serialize, Clone)]
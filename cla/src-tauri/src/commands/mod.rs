@@ -8,3 +8,13 @@ pub mod settings;
 pub mod telemetry;
 pub mod commander;
 pub mod accessibility;
+pub mod activity;
+pub mod notifications;
+pub mod export;
+pub mod privacy;
+pub mod devices;
+pub mod pipeline;
+pub mod consent;
+pub mod onboarding;
+pub mod storage;
+pub mod tasks;
@@ -0,0 +1,38 @@
+// Notification center commands for Cirkelline Local Agent
+
+use serde::{Deserialize, Serialize};
+use tauri::State;
+use crate::AppState;
+use crate::notifications::{Notification, NotificationCategory, UnreadCounts};
+
+/// Notifications with their unread counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationList {
+    pub notifications: Vec<Notification>,
+    pub unread: UnreadCounts,
+}
+
+/// List notifications, newest first
+#[tauri::command]
+pub async fn list_notifications(
+    state: State<'_, AppState>,
+    unread_only: Option<bool>,
+    category: Option<NotificationCategory>,
+) -> Result<NotificationList, String> {
+    Ok(NotificationList {
+        notifications: state.notifications.list(unread_only.unwrap_or(false), category).await,
+        unread: state.notifications.unread_counts().await,
+    })
+}
+
+/// Mark notifications read (all unread when no ids are given)
+#[tauri::command]
+pub async fn mark_read(state: State<'_, AppState>, ids: Option<Vec<String>>) -> Result<usize, String> {
+    Ok(state.notifications.mark_read(ids.as_deref()).await)
+}
+
+/// Remove notifications (all read ones when no ids are given)
+#[tauri::command]
+pub async fn clear_notifications(state: State<'_, AppState>, ids: Option<Vec<String>>) -> Result<usize, String> {
+    Ok(state.notifications.clear(ids.as_deref()).await)
+}
@@ -0,0 +1,57 @@
+// First-run onboarding commands for Cirkelline Local Agent
+
+use tauri::State;
+use crate::AppState;
+use crate::onboarding::{OnboardingState, OnboardingStep};
+use crate::security::consent::{Capability, CapabilityRequest};
+
+/// Steps done so far and the next one to show
+#[tauri::command]
+pub async fn get_onboarding_state(state: State<'_, AppState>) -> Result<OnboardingState, String> {
+    Ok(state.onboarding.state())
+}
+
+/// Finish the current onboarding step; optional steps may be skipped
+#[tauri::command]
+pub async fn complete_step(
+    state: State<'_, AppState>,
+    step: String,
+    skip: Option<bool>,
+) -> Result<OnboardingState, String> {
+    let step = OnboardingStep::from_name(&step).ok_or_else(|| format!("Ukendt trin: {}", step))?;
+    let skip = skip.unwrap_or(false);
+    if !skip {
+        check_step(&state, step).await?;
+    }
+    state.onboarding.complete_step(step, skip)
+}
+
+/// Start onboarding over
+#[tauri::command]
+pub async fn reset_onboarding(state: State<'_, AppState>) -> Result<OnboardingState, String> {
+    state.onboarding.reset()
+}
+
+/// A step is only complete once its settings are in place
+async fn check_step(state: &AppState, step: OnboardingStep) -> Result<(), String> {
+    match step {
+        OnboardingStep::Consent => {
+            let undecided = Capability::ALL
+                .iter()
+                .any(|c| matches!(state.consent.request(*c), CapabilityRequest::Prompt(_)));
+            if undecided {
+                return Err("Tag stilling til alle tilladelser først".to_string());
+            }
+        }
+        OnboardingStep::Connection => {
+            let settings = state.settings.read().await;
+            let configured = settings.ckc_endpoint.as_deref().is_some_and(|e| !e.is_empty())
+                && settings.api_key.is_some();
+            if !configured && !settings.offline_mode {
+                return Err("Angiv forbindelse og API-nøgle, eller vælg offline-tilstand".to_string());
+            }
+        }
+        OnboardingStep::Models | OnboardingStep::Accessibility => {}
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+// Task pipeline commands for Cirkelline Local Agent
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use tauri::State;
+use crate::AppState;
+use crate::activity::ActivityCategory;
+use crate::commands::commander::CommanderState;
+use crate::security::consent::Capability;
+use crate::pipeline::{EngineBackend, PipelineDefinition, PipelineReport, PipelineRunner, PipelineValue, ValueKind};
+
+/// Built-in pipelines
+#[tauri::command]
+pub fn list_pipelines() -> Vec<PipelineDefinition> {
+    PipelineDefinition::builtin()
+}
+
+/// Run a pipeline; `input` is a file path or text, depending on the first step
+#[tauri::command]
+pub async fn run_pipeline(
+    state: State<'_, AppState>,
+    commander: State<'_, CommanderState>,
+    pipeline: PipelineDefinition,
+    input: String,
+) -> Result<PipelineReport, String> {
+    let value = match pipeline.input_kind() {
+        Some(ValueKind::File) => {
+            state.consent.require(Capability::FolderAccess)?;
+            let path = PathBuf::from(&input);
+            if !path.exists() {
+                return Err(format!("Fil ikke fundet: {}", input));
+            }
+            PipelineValue::File(path)
+        }
+        Some(ValueKind::Text) => PipelineValue::Text(input),
+        Some(kind) => return Err(format!("Pipelinen kan ikke starte med {:?}", kind)),
+        None => return Err("Pipelinen har ingen trin".to_string()),
+    };
+
+    let knowledge = commander.unit.read().await.knowledge();
+    let backend = EngineBackend::new(state.inference_engine.clone(), knowledge);
+    let runner = PipelineRunner::new(Arc::new(backend)).with_telemetry(state.telemetry.clone());
+
+    let report = runner
+        .run(&pipeline, value)
+        .await
+        .map_err(|e| format!("Pipelinen {} fejlede: {}", pipeline.name, e))?;
+    state
+        .activity
+        .record(ActivityCategory::Inference, format!("Kørte pipelinen {}", pipeline.name), None)
+        .await;
+    Ok(report)
+}
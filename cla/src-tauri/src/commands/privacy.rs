@@ -0,0 +1,33 @@
+// Privacy mode commands for Cirkelline Local Agent
+
+use std::time::Duration;
+use tauri::State;
+use crate::AppState;
+use crate::security::privacy::PrivacyStatus;
+
+/// Turn privacy mode on or off; `minutes` overrides the configured duration
+#[tauri::command]
+pub async fn set_privacy_mode(
+    state: State<'_, AppState>,
+    enabled: bool,
+    minutes: Option<u32>,
+) -> Result<PrivacyStatus, String> {
+    if !enabled {
+        return Ok(state.privacy.disable().await);
+    }
+
+    let minutes = match minutes {
+        Some(minutes) if !(1..=720).contains(&minutes) => {
+            return Err("Privat tilstand skal vare mellem 1 minut og 12 timer".to_string());
+        }
+        Some(minutes) => minutes,
+        None => state.settings.read().await.privacy_mode_minutes,
+    };
+    Ok(state.privacy.enable(Duration::from_secs(minutes as u64 * 60)).await)
+}
+
+/// Current privacy mode
+#[tauri::command]
+pub async fn get_privacy_status(state: State<'_, AppState>) -> Result<PrivacyStatus, String> {
+    Ok(state.privacy.status())
+}
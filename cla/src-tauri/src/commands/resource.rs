@@ -2,7 +2,7 @@
 
 use tauri::State;
 use crate::AppState;
-use crate::models::{SystemMetrics, CanExecuteResult};
+use crate::models::{SystemMetrics, CanExecuteResult, Settings};
 use sysinfo::System;
 
 /// Get current system metrics
@@ -23,91 +23,142 @@ pub async fn can_execute_task(
     let settings = state.settings.read().await;
     let monitor = state.resource_monitor.read().await;
     let metrics = monitor.get_current_metrics();
+    Ok(check_execution(&settings, &metrics, estimated_cpu_percent, estimated_ram_mb, requires_gpu))
+}
 
+/// Decide whether a task fits within the configured limits right now
+pub fn check_execution(
+    settings: &Settings,
+    metrics: &SystemMetrics,
+    estimated_cpu_percent: u8,
+    estimated_ram_mb: u64,
+    requires_gpu: bool,
+) -> CanExecuteResult {
     // Check if paused
     if settings.paused {
-        return Ok(CanExecuteResult {
+        return CanExecuteResult {
             can_execute: false,
             reason: Some("CLA er sat på pause".to_string()),
             estimated_wait_seconds: None,
-        });
+        };
     }
 
     // Check idle requirement
     if settings.idle_only && !metrics.is_idle {
-        return Ok(CanExecuteResult {
+        return CanExecuteResult {
             can_execute: false,
             reason: Some(format!(
                 "Venter på idle-tilstand ({}s / {}s)",
                 metrics.idle_seconds, settings.idle_threshold_seconds
             )),
             estimated_wait_seconds: Some(settings.idle_threshold_seconds - metrics.idle_seconds),
-        });
+        };
     }
 
     // Check battery
     if metrics.on_battery {
         if !settings.run_on_battery {
-            return Ok(CanExecuteResult {
+            return CanExecuteResult {
                 can_execute: false,
                 reason: Some("Kører ikke på batteri".to_string()),
                 estimated_wait_seconds: None,
-            });
+            };
         }
         if let Some(battery) = metrics.battery_percent {
             if battery < settings.min_battery_percent {
-                return Ok(CanExecuteResult {
+                return CanExecuteResult {
                     can_execute: false,
                     reason: Some(format!(
                         "Batteri for lavt ({}% / min {}%)",
                         battery, settings.min_battery_percent
                     )),
                     estimated_wait_seconds: None,
-                });
+                };
             }
         }
     }
 
-    // Check CPU headroom
-    let cpu_headroom = settings.max_cpu_percent as f32 - metrics.cpu_usage_percent;
-    if estimated_cpu_percent as f32 > cpu_headroom {
-        return Ok(CanExecuteResult {
+    // Check CLA's own CPU budget
+    if metrics.process_cpu_percent + estimated_cpu_percent as f32 > settings.max_cpu_percent as f32 {
+        return CanExecuteResult {
             can_execute: false,
             reason: Some(format!(
-                "CPU-grænse nået ({:.0}% brugt, maks {}%)",
-                metrics.cpu_usage_percent, settings.max_cpu_percent
+                "CPU-grænse nået (CLA bruger {:.0}%, maks {}%)",
+                metrics.process_cpu_percent, settings.max_cpu_percent
             )),
             estimated_wait_seconds: Some(30), // Estimate
-        });
+        };
+    }
+
+    // Check machine-wide CPU headroom
+    if metrics.cpu_usage_percent + estimated_cpu_percent as f32 > settings.max_system_cpu_percent as f32 {
+        return CanExecuteResult {
+            can_execute: false,
+            reason: Some(format!(
+                "Computeren er travl (CPU {:.0}% brugt, maks {}%)",
+                metrics.cpu_usage_percent, settings.max_system_cpu_percent
+            )),
+            estimated_wait_seconds: Some(30),
+        };
     }
 
-    // Check RAM headroom
-    let ram_usage_percent = metrics.ram_usage_percent;
-    if ram_usage_percent > settings.max_ram_percent as f32 {
-        return Ok(CanExecuteResult {
+    // Check CLA's own RAM budget
+    let process_ram_percent = if metrics.ram_total_mb > 0 {
+        (metrics.process_ram_mb + estimated_ram_mb) as f32 / metrics.ram_total_mb as f32 * 100.0
+    } else {
+        0.0
+    };
+    if process_ram_percent > settings.max_ram_percent as f32 {
+        return CanExecuteResult {
             can_execute: false,
             reason: Some(format!(
-                "RAM-grænse nået ({:.0}% brugt, maks {}%)",
-                ram_usage_percent, settings.max_ram_percent
+                "RAM-grænse nået (CLA bruger {} MB, maks {}%)",
+                metrics.process_ram_mb, settings.max_ram_percent
             )),
             estimated_wait_seconds: Some(60),
-        });
+        };
+    }
+
+    // Check machine-wide RAM headroom
+    if metrics.ram_usage_percent > settings.max_system_ram_percent as f32 {
+        return CanExecuteResult {
+            can_execute: false,
+            reason: Some(format!(
+                "Computeren mangler hukommelse (RAM {:.0}% brugt, maks {}%)",
+                metrics.ram_usage_percent, settings.max_system_ram_percent
+            )),
+            estimated_wait_seconds: Some(60),
+        };
     }
 
     // Check GPU if required
     if requires_gpu && !metrics.gpu_available {
-        return Ok(CanExecuteResult {
+        return CanExecuteResult {
             can_execute: false,
             reason: Some("GPU ikke tilgængelig".to_string()),
             estimated_wait_seconds: None,
-        });
+        };
     }
 
-    Ok(CanExecuteResult {
+    // Check GPU headroom
+    if let (true, Some(gpu_usage)) = (requires_gpu, metrics.gpu_usage_percent) {
+        if gpu_usage > settings.max_gpu_percent as f32 {
+            return CanExecuteResult {
+                can_execute: false,
+                reason: Some(format!(
+                    "GPU-grænse nået ({:.0}% brugt, maks {}%)",
+                    gpu_usage, settings.max_gpu_percent
+                )),
+                estimated_wait_seconds: Some(30),
+            };
+        }
+    }
+
+    CanExecuteResult {
         can_execute: true,
         reason: None,
         estimated_wait_seconds: None,
-    })
+    }
 }
 
 /// Get current resource limits
@@ -121,6 +172,8 @@ pub async fn get_resource_limits(
         max_ram_percent: settings.max_ram_percent,
         max_gpu_percent: settings.max_gpu_percent,
         max_disk_mb: settings.max_disk_mb,
+        max_system_cpu_percent: settings.max_system_cpu_percent,
+        max_system_ram_percent: settings.max_system_ram_percent,
         idle_only: settings.idle_only,
         idle_threshold_seconds: settings.idle_threshold_seconds,
     })
@@ -144,23 +197,36 @@ pub async fn set_resource_limits(
     if limits.max_gpu_percent > 80 {
         return Err("GPU-grænse kan ikke overstige 80%".to_string());
     }
+    if !(10..=100).contains(&limits.max_system_cpu_percent) {
+        return Err("System-CPU-grænse skal være mellem 10% og 100%".to_string());
+    }
+    if !(10..=100).contains(&limits.max_system_ram_percent) {
+        return Err("System-RAM-grænse skal være mellem 10% og 100%".to_string());
+    }
 
     settings.max_cpu_percent = limits.max_cpu_percent;
     settings.max_ram_percent = limits.max_ram_percent;
     settings.max_gpu_percent = limits.max_gpu_percent;
     settings.max_disk_mb = limits.max_disk_mb;
+    settings.max_system_cpu_percent = limits.max_system_cpu_percent;
+    settings.max_system_ram_percent = limits.max_system_ram_percent;
     settings.idle_only = limits.idle_only;
     settings.idle_threshold_seconds = limits.idle_threshold_seconds;
+    state.database.set_quota_mb(settings.max_disk_mb);
 
     Ok(())
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct ResourceLimits {
+    /// CLA's own use, in % of the machine
     pub max_cpu_percent: u8,
     pub max_ram_percent: u8,
     pub max_gpu_percent: u8,
     pub max_disk_mb: u32,
+    /// Machine-wide use above which CLA waits
+    pub max_system_cpu_percent: u8,
+    pub max_system_ram_percent: u8,
     pub idle_only: bool,
     pub idle_threshold_seconds: u32,
 }
@@ -2,8 +2,12 @@
 
 use tauri::State;
 use crate::AppState;
-use crate::models::{Settings, ConnectionStatus};
+use crate::models::{Settings, CommandBudget, ConnectionStatus, InferencePreference};
+use std::collections::HashMap;
 use chrono::Utc;
+use crate::inference::OcrEngine;
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+use crate::security::command_limits::SETTINGS_COMMANDS;
 
 /// Get all settings
 #[tauri::command]
@@ -18,9 +22,44 @@ pub async fn update_settings(
     state: State<'_, AppState>,
     new_settings: SettingsUpdate,
 ) -> Result<Settings, String> {
-    let mut settings = state.settings.write().await;
+    let settings = {
+        let mut settings = state.settings.write().await;
 
-    // Apply updates with validation
+        let budgets = settings.command_budgets.clone();
+        apply_settings_update(&mut settings, new_settings)?;
+        if settings.command_budgets != budgets {
+            state.command_limiter.configure(&settings.command_budgets);
+        }
+        state.database.set_quota_mb(settings.max_disk_mb);
+
+        // Persist settings
+        persist_settings(&settings).await?;
+        settings.clone()
+    };
+
+    // Settings are released first; inference commands take the engine lock before them
+    apply_ocr_language(&state, &settings.ocr_language).await;
+    apply_inference_preferences(&state, &settings).await;
+
+    Ok(settings)
+}
+
+/// Tell the inference engine where embeddings and transcriptions run now
+async fn apply_inference_preferences(state: &AppState, settings: &Settings) {
+    if let Some(engine) = state.inference_engine.read().await.as_ref() {
+        engine.set_inference_preferences(settings.embedding_inference, settings.transcription_inference);
+    }
+}
+
+/// Switch the OCR engine to a new language; it loads the language pack on next use
+async fn apply_ocr_language(state: &AppState, language: &str) {
+    if let Some(engine) = state.inference_engine.read().await.as_ref() {
+        engine.set_ocr_language(language).await;
+    }
+}
+
+/// Apply a settings update with validation
+pub fn apply_settings_update(settings: &mut Settings, new_settings: SettingsUpdate) -> Result<(), String> {
     if let Some(cpu) = new_settings.max_cpu_percent {
         if cpu > 80 {
             return Err("CPU-grænse kan ikke overstige 80%".to_string());
@@ -46,6 +85,20 @@ pub async fn update_settings(
         settings.max_disk_mb = disk;
     }
 
+    if let Some(cpu) = new_settings.max_system_cpu_percent {
+        if !(10..=100).contains(&cpu) {
+            return Err("System-CPU-grænse skal være mellem 10% og 100%".to_string());
+        }
+        settings.max_system_cpu_percent = cpu;
+    }
+
+    if let Some(ram) = new_settings.max_system_ram_percent {
+        if !(10..=100).contains(&ram) {
+            return Err("System-RAM-grænse skal være mellem 10% og 100%".to_string());
+        }
+        settings.max_system_ram_percent = ram;
+    }
+
     if let Some(idle_only) = new_settings.idle_only {
         settings.idle_only = idle_only;
     }
@@ -93,6 +146,13 @@ pub async fn update_settings(
         settings.enable_ocr = ocr;
     }
 
+    if let Some(language) = new_settings.ocr_language {
+        if !OcrEngine::is_language_supported(&language) {
+            return Err(format!("OCR-sprog understøttes ikke: {}", language));
+        }
+        settings.ocr_language = language;
+    }
+
     if let Some(embeddings) = new_settings.enable_embeddings {
         settings.enable_embeddings = embeddings;
     }
@@ -105,6 +165,14 @@ pub async fn update_settings(
         settings.download_tier3_models = tier3;
     }
 
+    if let Some(preference) = new_settings.embedding_inference {
+        settings.embedding_inference = preference;
+    }
+
+    if let Some(preference) = new_settings.transcription_inference {
+        settings.transcription_inference = preference;
+    }
+
     if let Some(endpoint) = new_settings.ckc_endpoint {
         // Validate URL
         if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
@@ -117,21 +185,42 @@ pub async fn update_settings(
         settings.api_key = if api_key.is_empty() { None } else { Some(api_key) };
     }
 
-    // Persist settings
-    persist_settings(&settings).await?;
+    if let Some(minutes) = new_settings.privacy_mode_minutes {
+        if !(5..=720).contains(&minutes) {
+            return Err("Privat tilstand skal vare mellem 5 minutter og 12 timer".to_string());
+        }
+        settings.privacy_mode_minutes = minutes;
+    }
+
+    if let Some(budgets) = new_settings.command_budgets {
+        if budgets.values().any(|b| b.max_calls == 0 || !(1..=3600).contains(&b.window_secs)) {
+            return Err("Kaldgrænser skal tillade mindst ét kald i et vindue på 1 sekund til 1 time".to_string());
+        }
+        if SETTINGS_COMMANDS.iter().any(|command| budgets.contains_key(*command)) {
+            return Err("Kaldgrænsen for indstillinger kan ikke ændres".to_string());
+        }
+        settings.command_budgets = budgets;
+    }
 
-    Ok(settings.clone())
+    Ok(())
 }
 
 /// Reset settings to defaults
 #[tauri::command]
 pub async fn reset_settings(state: State<'_, AppState>) -> Result<Settings, String> {
-    let mut settings = state.settings.write().await;
-    *settings = Settings::default();
-
-    persist_settings(&settings).await?;
-
-    Ok(settings.clone())
+    let settings = {
+        let mut settings = state.settings.write().await;
+        *settings = Settings::default();
+        state.command_limiter.configure(&settings.command_budgets);
+        state.database.set_quota_mb(settings.max_disk_mb);
+
+        persist_settings(&settings).await?;
+        settings.clone()
+    };
+    apply_ocr_language(&state, &settings.ocr_language).await;
+    apply_inference_preferences(&state, &settings).await;
+
+    Ok(settings)
 }
 
 /// Get connection status to CKC
@@ -152,7 +241,7 @@ pub async fn get_connection_status(
         .unwrap_or("https://ckc.cirkelline.com");
     let health_url = format!("{}/health", endpoint_str);
 
-    match client.get(&health_url).send().await {
+    match client.get(&health_url).send_metered(NetworkSubsystem::Sync).await {
         Ok(response) if response.status().is_success() => {
             Ok(ConnectionStatus {
                 connected: true,
@@ -194,7 +283,7 @@ pub async fn test_connection(endpoint: String) -> Result<ConnectionStatus, Strin
     let start = std::time::Instant::now();
     let health_url = format!("{}/health", endpoint);
 
-    match client.get(&health_url).send().await {
+    match client.get(&health_url).send_metered(NetworkSubsystem::Sync).await {
         Ok(response) if response.status().is_success() => {
             Ok(ConnectionStatus {
                 connected: true,
@@ -226,7 +315,7 @@ pub async fn test_connection(endpoint: String) -> Result<ConnectionStatus, Strin
 }
 
 /// Persist settings to disk
-async fn persist_settings(settings: &Settings) -> Result<(), String> {
+pub async fn persist_settings(settings: &Settings) -> Result<(), String> {
     let config_dir = dirs::config_dir()
         .ok_or("Kunne ikke finde config-mappe")?
         .join("cirkelline-cla");
@@ -262,12 +351,14 @@ pub async fn load_settings() -> Settings {
     Settings::default()
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Default)]
 pub struct SettingsUpdate {
     pub max_cpu_percent: Option<u8>,
     pub max_ram_percent: Option<u8>,
     pub max_gpu_percent: Option<u8>,
     pub max_disk_mb: Option<u32>,
+    pub max_system_cpu_percent: Option<u8>,
+    pub max_system_ram_percent: Option<u8>,
     pub idle_only: Option<bool>,
     pub idle_threshold_seconds: Option<u32>,
     pub auto_start: Option<bool>,
@@ -278,9 +369,14 @@ pub struct SettingsUpdate {
     pub offline_mode: Option<bool>,
     pub enable_transcription: Option<bool>,
     pub enable_ocr: Option<bool>,
+    pub ocr_language: Option<String>,
     pub enable_embeddings: Option<bool>,
     pub download_tier2_models: Option<bool>,
     pub download_tier3_models: Option<bool>,
+    pub embedding_inference: Option<InferencePreference>,
+    pub transcription_inference: Option<InferencePreference>,
     pub ckc_endpoint: Option<String>,
     pub api_key: Option<String>,
+    pub privacy_mode_minutes: Option<u32>,
+    pub command_budgets: Option<HashMap<String, CommandBudget>>,
 }
@@ -0,0 +1,135 @@
+// Local storage commands for Cirkelline Local Agent
+// Integrity status of the local stores, recovery of damaged ones and local memories
+
+use tauri::State;
+use crate::AppState;
+use crate::error::ClaError;
+use crate::memory;
+use crate::models::LocalMemory;
+use crate::storage::{self, StoreHealth, StoreRecovery};
+use chrono::Utc;
+use serde::Deserialize;
+use uuid::Uuid;
+
+/// Page size when the frontend does not ask for one
+const DEFAULT_MEMORY_LIMIT: u32 = 50;
+
+/// Result of each store's integrity check since startup
+#[tauri::command]
+pub fn get_storage_health() -> Vec<StoreHealth> {
+    storage::health()
+}
+
+/// Restore a corrupted store from a file, or keep it empty.
+/// A restored store is loaded by restarting the app.
+#[tauri::command]
+pub async fn recover_store(
+    app: tauri::AppHandle,
+    name: String,
+    action: StoreRecovery,
+) -> Result<StoreHealth, String> {
+    let restart = matches!(action, StoreRecovery::RestoreFile { .. });
+    let health = storage::recover(&name, action).map_err(|e| ClaError::Storage(e).user_message())?;
+    log::info!("Store {} recovered: {:?}", name, health.detail);
+
+    if restart {
+        // Give the frontend time to show the result first
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+            app.restart();
+        });
+    }
+    Ok(health)
+}
+
+/// A memory as written in the app; without `id` a new memory is created
+#[derive(Debug, Deserialize)]
+pub struct MemoryInput {
+    pub id: Option<Uuid>,
+    pub content: String,
+    pub memory_type: String,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    #[serde(default = "default_importance")]
+    pub importance: f32,
+}
+
+fn default_importance() -> f32 {
+    0.5
+}
+
+/// Create or update a local memory; it is synced to CKC on the next sync
+#[tauri::command]
+pub async fn save_memory(state: State<'_, AppState>, memory: MemoryInput) -> Result<LocalMemory, String> {
+    let content = memory.content.trim().to_string();
+    if content.is_empty() {
+        return Err("Hukommelsen er tom".to_string());
+    }
+
+    let entry = match memory.id {
+        Some(id) => {
+            let mut existing = state
+                .database
+                .get_memory(id)
+                .map_err(|e| ClaError::Storage(e).user_message())?
+                .ok_or("Hukommelse ikke fundet")?;
+            if existing.content != content {
+                // The embedding belongs to the old text
+                existing.embedding_local = None;
+            }
+            existing.content = content;
+            existing.memory_type = memory.memory_type;
+            existing.topics = memory.topics;
+            existing.importance = memory.importance.clamp(0.0, 1.0);
+            existing.updated_at = Utc::now();
+            existing.pending_sync = true;
+            existing
+        }
+        None => memory::new_memory(content, &memory.memory_type, memory.topics, memory.importance),
+    };
+
+    state
+        .database
+        .save_memory(&entry)
+        .map_err(|e| ClaError::Storage(e).user_message())?;
+    Ok(entry)
+}
+
+/// Local memories, most recently changed first
+#[tauri::command]
+pub async fn list_memories(
+    state: State<'_, AppState>,
+    limit: Option<u32>,
+    offset: Option<u32>,
+) -> Result<Vec<LocalMemory>, String> {
+    state
+        .database
+        .list_memories(Some(limit.unwrap_or(DEFAULT_MEMORY_LIMIT)), offset.unwrap_or(0))
+        .map_err(|e| ClaError::Storage(e).user_message())
+}
+
+/// Local memories containing every word of `query`
+#[tauri::command]
+pub async fn search_memories(
+    state: State<'_, AppState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<LocalMemory>, String> {
+    state
+        .database
+        .search_memories(&query, limit.unwrap_or(DEFAULT_MEMORY_LIMIT))
+        .map_err(|e| ClaError::Storage(e).user_message())
+}
+
+/// Delete a local memory
+#[tauri::command]
+pub async fn delete_memory(state: State<'_, AppState>, id: Uuid) -> Result<(), String> {
+    let deleted = state
+        .database
+        .delete_memory(id)
+        .map_err(|e| ClaError::Storage(e).user_message())?;
+    if !deleted {
+        return Err("Hukommelse ikke fundet".to_string());
+    }
+    Ok(())
+}
@@ -2,8 +2,13 @@
 
 use tauri::State;
 use crate::AppState;
-use crate::models::{SyncStatus, SyncConflict, ConflictResolution, SyncResult};
+use crate::activity::ActivityCategory;
+use crate::commands::commander::CommanderState;
+use crate::models::{SyncStatus, ConflictResolution, SyncResult};
+use crate::research::KnowledgeStore;
+use crate::utils::sync::SyncEngine;
 use chrono::Utc;
+use std::sync::Arc;
 use uuid::Uuid;
 
 /// Get current sync status
@@ -15,36 +20,38 @@ pub async fn get_sync_status(state: State<'_, AppState>) -> Result<SyncStatus, S
 
 /// Trigger immediate sync
 #[tauri::command]
-pub async fn sync_now(state: State<'_, AppState>) -> Result<SyncResult, String> {
-    let settings = state.settings.read().await;
+pub async fn sync_now(
+    state: State<'_, AppState>,
+    commander: State<'_, CommanderState>,
+) -> Result<SyncResult, String> {
+    Ok(sync_on_request(&state, &commander).await)
+}
 
+/// Sync now because the user asked for it (button or voice), unless offline
+/// or setup is unfinished
+pub async fn sync_on_request(state: &AppState, commander: &CommanderState) -> SyncResult {
     // Check if offline mode
-    if settings.offline_mode {
-        return Ok(SyncResult::Failed {
+    if state.settings.read().await.offline_mode {
+        return SyncResult::Failed {
             error: "Offline-tilstand er aktiveret".to_string(),
-        });
+        };
     }
 
-    // Update status to syncing
-    {
-        let mut status = state.sync_status.write().await;
-        status.is_syncing = true;
+    if !state.onboarding.is_ready() {
+        return SyncResult::Failed {
+            error: "Gør opsætningen færdig før synkronisering".to_string(),
+        };
     }
 
-    // Perform sync (placeholder for actual implementation)
-    let endpoint = settings.ckc_endpoint.as_deref()
-        .unwrap_or("https://ckc.cirkelline.com");
-    let result = perform_sync(endpoint, settings.api_key.as_deref()).await;
-
-    // Update status
-    {
-        let mut status = state.sync_status.write().await;
-        status.is_syncing = false;
-        status.last_sync = Some(Utc::now());
-        status.last_sync_result = Some(result.clone());
-    }
+    let knowledge = commander.unit.read().await.knowledge();
+    let result = perform_sync(state, Some(knowledge)).await;
+
+    state
+        .activity
+        .record(ActivityCategory::Sync, sync_summary(&result), Some("Brugeranmodning"))
+        .await;
 
-    Ok(result)
+    result
 }
 
 /// Get pending changes not yet synced
@@ -68,19 +75,18 @@ pub async fn resolve_conflict(
     conflict_id: Uuid,
     resolution: ConflictResolution,
 ) -> Result<(), String> {
-    let mut status = state.sync_status.write().await;
-
-    // Find and remove the conflict
-    let conflict_idx = status
+    let conflict = state
+        .sync_status
+        .read()
+        .await
         .conflicts
         .iter()
-        .position(|c| c.id == conflict_id)
+        .find(|c| c.id == conflict_id)
+        .cloned()
         .ok_or("Konflikt ikke fundet")?;
 
-    let conflict = status.conflicts.remove(conflict_idx);
-
-    // Apply resolution (placeholder)
-    apply_conflict_resolution(&conflict, &resolution).await?;
+    sync_engine(&state).await.resolve(&conflict, &resolution).await?;
+    state.sync_status.write().await.conflicts.retain(|c| c.id != conflict_id);
 
     log::info!(
         "Resolved conflict {} with {:?}",
@@ -91,60 +97,52 @@ pub async fn resolve_conflict(
     Ok(())
 }
 
-/// Perform the actual sync operation
-async fn perform_sync(endpoint: &str, api_key: Option<&str>) -> SyncResult {
-    // Check connectivity
-    let client = reqwest::Client::new();
-    let health_url = format!("{}/health", endpoint);
-
-    match client.get(&health_url).send().await {
-        Ok(response) if response.status().is_success() => {
-            // Connection OK, proceed with sync
-
-            // 1. Upload local changes
-            // TODO: Implement actual upload logic
-
-            // 2. Download remote changes
-            // TODO: Implement actual download logic
-
-            // 3. Handle conflicts
-            // TODO: Implement conflict detection
-
-            SyncResult::Success
-        }
-        Ok(response) => SyncResult::Failed {
-            error: format!("Server svarede med status: {}", response.status()),
-        },
-        Err(e) => SyncResult::Failed {
-            error: format!("Kunne ikke forbinde til server: {}", e),
-        },
+/// Activity log wording of a sync result
+pub fn sync_summary(result: &SyncResult) -> String {
+    match result {
+        SyncResult::Success => "Synkroniserede med CKC".to_string(),
+        SyncResult::PartialSuccess { errors } => format!("Synkroniserede delvist med CKC ({} fejl)", errors.len()),
+        SyncResult::Failed { error } => format!("Synkronisering mislykkedes: {}", error),
     }
 }
 
-/// Apply a conflict resolution
-async fn apply_conflict_resolution(
-    conflict: &SyncConflict,
-    resolution: &ConflictResolution,
-) -> Result<(), String> {
-    match resolution {
-        ConflictResolution::KeepLocal => {
-            // Mark local version as authoritative, queue for upload
-            log::info!("Keeping local version for {:?}", conflict.data_type);
-        }
-        ConflictResolution::KeepRemote => {
-            // Download and overwrite local version
-            log::info!("Keeping remote version for {:?}", conflict.data_type);
-        }
-        ConflictResolution::Merge => {
-            // Attempt automatic merge
-            log::info!("Merging versions for {:?}", conflict.data_type);
-        }
-        ConflictResolution::Manual => {
-            // User will handle manually
-            log::info!("Manual resolution requested for {:?}", conflict.data_type);
-        }
+/// Sync engine for the configured CKC endpoint
+async fn sync_engine(state: &AppState) -> SyncEngine {
+    let settings = state.settings.read().await;
+    let endpoint = settings.ckc_endpoint.as_deref()
+        .unwrap_or("https://ckc.cirkelline.com");
+    SyncEngine::new(endpoint, settings.api_key.as_deref(), state.device.clone(), state.database.clone())
+}
+
+/// Run a sync with CKC, keeping the sync status and telemetry up to date
+pub async fn perform_sync(state: &AppState, knowledge: Option<Arc<KnowledgeStore>>) -> SyncResult {
+    let mut engine = sync_engine(state).await;
+    if let Some(store) = knowledge {
+        engine = engine.with_knowledge(store);
     }
-    Ok(())
+
+    state.sync_status.write().await.is_syncing = true;
+
+    let started = std::time::Instant::now();
+    let (result, items, bytes) = match engine.run(&state.sync_status).await {
+        Ok(report) => (
+            report.result(),
+            report.uploaded + report.downloaded,
+            report.bytes_uploaded + report.bytes_downloaded,
+        ),
+        Err(error) => (SyncResult::Failed { error }, 0, 0),
+    };
+    let success = !matches!(result, SyncResult::Failed { .. });
+    state
+        .telemetry
+        .record_sync("both", items, bytes, started.elapsed().as_millis() as u64, success)
+        .await;
+
+    let mut status = state.sync_status.write().await;
+    status.is_syncing = false;
+    status.last_sync = Some(Utc::now());
+    status.last_sync_result = Some(result.clone());
+    result
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -0,0 +1,68 @@
+// Task queue commands for Cirkelline Local Agent
+// Inspect and manage the background tasks that run when resources allow
+
+use tauri::State;
+use crate::AppState;
+use crate::error::ClaError;
+use crate::models::{PendingTask, TaskType};
+use crate::security::consent::Capability;
+use uuid::Uuid;
+
+const DEFAULT_PRIORITY: u8 = 5;
+const DEFAULT_MAX_RETRIES: u8 = 3;
+
+/// All tasks in the queue, newest first
+#[tauri::command]
+pub async fn list_tasks(state: State<'_, AppState>) -> Result<Vec<PendingTask>, String> {
+    state
+        .task_queue
+        .list()
+        .map_err(|e| ClaError::Storage(e).user_message())
+}
+
+/// Add a task; it runs when it is due and the resource limits allow
+#[tauri::command]
+pub async fn enqueue_task(
+    state: State<'_, AppState>,
+    task_type: TaskType,
+    payload: Option<serde_json::Value>,
+    priority: Option<u8>,
+    max_retries: Option<u8>,
+) -> Result<PendingTask, String> {
+    // These tasks read a file the payload points at
+    if matches!(task_type, TaskType::TranscribeAudio | TaskType::ExtractText) {
+        state.consent.require(Capability::FolderAccess)?;
+    }
+    let task = state
+        .task_queue
+        .enqueue(
+            task_type,
+            payload.unwrap_or_else(|| serde_json::json!({})),
+            priority.unwrap_or(DEFAULT_PRIORITY),
+            max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+        )
+        .map_err(|e| ClaError::Storage(e).user_message())?;
+    log::info!("Task {} queued ({:?})", task.id, task.task_type);
+    Ok(task)
+}
+
+/// Cancel a task that has not started yet
+#[tauri::command]
+pub async fn cancel_task(state: State<'_, AppState>, id: Uuid) -> Result<PendingTask, String> {
+    state.task_queue.cancel(id)
+}
+
+/// Run a failed or cancelled task again
+#[tauri::command]
+pub async fn retry_task(state: State<'_, AppState>, id: Uuid) -> Result<PendingTask, String> {
+    state.task_queue.retry(id)
+}
+
+/// Remove completed, failed and cancelled tasks; returns how many were removed
+#[tauri::command]
+pub async fn clear_finished_tasks(state: State<'_, AppState>) -> Result<usize, String> {
+    state
+        .task_queue
+        .clear_finished()
+        .map_err(|e| ClaError::Storage(e).user_message())
+}
@@ -4,6 +4,11 @@ use tauri::State;
 use crate::AppState;
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use crate::activity::ActivityRange;
+use crate::commands::accessibility::AccessibilityState;
+use crate::telemetry::history::{parse_week, WeeklyReport};
+use crate::telemetry::network::{self, NetworkUsage};
+use crate::telemetry::TelemetryConfig;
 
 /// Telemetry consent status
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -51,6 +56,10 @@ pub async fn set_telemetry_consent(
     if enabled {
         settings.telemetry_consent_date = Some(Utc::now());
     }
+    state
+        .telemetry_reporter
+        .update_config(TelemetryConfig::from_settings(&settings))
+        .await;
 
     // Save settings
     // TODO: Persist to disk
@@ -64,6 +73,27 @@ pub async fn set_telemetry_consent(
     })
 }
 
+/// Weekly summary from the local metrics history. `week` is an ISO week such as
+/// "2026-W42" (default: the current week); `narrate` reads the summary aloud.
+#[tauri::command]
+pub async fn get_weekly_report(
+    state: State<'_, AppState>,
+    accessibility: State<'_, AccessibilityState>,
+    week: Option<String>,
+    narrate: Option<bool>,
+) -> Result<WeeklyReport, String> {
+    let day = match week {
+        Some(week) => parse_week(&week).ok_or_else(|| format!("Ugyldig uge: {} (brug fx 2026-W42)", week))?,
+        None => chrono::Local::now().date_naive(),
+    };
+    let report = state.telemetry.weekly_report(day);
+
+    if narrate.unwrap_or(false) {
+        accessibility.controller.read().await.speak(&report.summary).await?;
+    }
+    Ok(report)
+}
+
 /// Get telemetry statistics
 #[tauri::command]
 pub async fn get_telemetry_stats(
@@ -87,55 +117,19 @@ pub async fn get_telemetry_stats(
 pub async fn send_telemetry_report(
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
-    let settings = state.settings.read().await;
-
-    if !settings.telemetry_enabled {
+    if !state.settings.read().await.telemetry_enabled {
         return Err("Telemetry is disabled".to_string());
     }
 
-    let endpoint = settings.ckc_endpoint.clone()
-        .unwrap_or_else(|| "https://ckc.cirkelline.com".to_string());
-
-    drop(settings);
-
-    let telemetry = state.telemetry_stats.read().await;
-
-    // Build report
-    let report = serde_json::json!({
-        "session_id": telemetry.session_id,
-        "version": env!("CARGO_PKG_VERSION"),
-        "platform": std::env::consts::OS,
-        "metrics": {
-            "app_starts": telemetry.app_starts,
-            "inference_count": telemetry.inference_count,
-            "sync_count": telemetry.sync_count,
-            "error_count": telemetry.error_count,
-            "uptime_hours": telemetry.uptime_hours
-        },
-        "timestamp": Utc::now().to_rfc3339()
-    });
-
-    drop(telemetry);
-
-    // Send report
-    let client = reqwest::Client::new();
-    let response = client
-        .post(&format!("{}/api/cla/telemetry", endpoint))
-        .json(&report)
-        .timeout(std::time::Duration::from_secs(30))
-        .send()
+    // The reporter anonymizes the report and converts it to the endpoint's schema
+    state
+        .telemetry_reporter
+        .force_report()
         .await
         .map_err(|e| format!("Failed to send telemetry: {}", e))?;
 
-    if response.status().is_success() {
-        let mut telemetry = state.telemetry_stats.write().await;
-        telemetry.last_report = Some(Utc::now());
-
-        log::info!("Telemetry report sent successfully");
-        Ok(true)
-    } else {
-        Err(format!("Server returned status: {}", response.status()))
-    }
+    state.telemetry_stats.write().await.last_report = Some(Utc::now());
+    Ok(true)
 }
 
 /// Record an event locally
@@ -189,3 +183,23 @@ Data er:
 Du kan deaktivere telemetri når som helst i Indstillinger.
 "#.to_string()
 }
+
+/// Get the JSON Schema telemetry reports are validated against
+#[tauri::command]
+pub fn get_telemetry_schema() -> serde_json::Value {
+    crate::telemetry::schema::report_json_schema()
+}
+
+/// Get the state of background loops supervised by the watchdog
+#[tauri::command]
+pub async fn get_watchdog_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::utils::watchdog::LoopStatus>, String> {
+    Ok(state.watchdog.status())
+}
+
+/// Bytes sent and received per subsystem within a time range
+#[tauri::command]
+pub fn get_network_usage(range: Option<ActivityRange>) -> NetworkUsage {
+    network::meter().usage(&range.unwrap_or_default())
+}
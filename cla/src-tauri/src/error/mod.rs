@@ -4,6 +4,10 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+// Only the backoff is used so far, by the task queue
+#[allow(dead_code)]
+pub mod retry;
+
 /// Main error type for CLA
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ClaError {
@@ -316,8 +320,8 @@ impl ClaError {
             Self::Storage(StorageError::QuotaExceeded { .. }) => RecoveryAction::RequireUserAction {
                 message: "Ryd noget lokal data for at frigøre plads".to_string(),
             },
-            Self::Storage(StorageError::CorruptedData { .. }) => RecoveryAction::UseFallback {
-                fallback_type: "cloud_sync".to_string(),
+            Self::Storage(StorageError::CorruptedData { .. }) => RecoveryAction::RequireUserAction {
+                message: "Gendan eller nulstil de beskadigede data under Indstillinger".to_string(),
             },
 
             // Resource errors - wait and retry
@@ -393,6 +397,9 @@ impl ClaError {
                     used_mb, limit_mb
                 )
             }
+            Self::Storage(StorageError::CorruptedData { .. }) => {
+                "Lokale data er beskadigede. Gendan fra en sikkerhedskopi eller start forfra under Indstillinger.".to_string()
+            }
             _ => self.to_string(),
         }
     }
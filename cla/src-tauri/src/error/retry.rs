@@ -5,7 +5,7 @@ use std::future::Future;
 use std::time::Duration;
 use tokio::time::sleep;
 
-use super::{ClaError, ClaResult, RecoveryAction};
+use super::{ClaError, ClaResult};
 
 /// Retry configuration
 #[derive(Clone)]
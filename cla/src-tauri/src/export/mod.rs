@@ -0,0 +1,171 @@
+// Session Export - Renders local sessions as Markdown or PDF for archiving
+// Sessions are read from the local database; nothing leaves the machine
+
+mod pdf;
+
+use pdf::{PdfDocument, PdfStyle};
+
+use crate::models::{LocalMessage, LocalSession};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Output format of an export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Markdown,
+    Pdf,
+}
+
+/// Directory where older versions kept one `<id>.json` file per session;
+/// imported into the database on first start
+pub fn sessions_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cirkelline-cla")
+        .join("sessions")
+}
+
+/// Render a session and write it to `path`; returns the bytes written
+pub fn export_session(session: &LocalSession, format: ExportFormat, path: &Path) -> Result<u64, String> {
+    let bytes = match format {
+        ExportFormat::Markdown => render_markdown(session).into_bytes(),
+        ExportFormat::Pdf => render_pdf(session),
+    };
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(|e| format!("Kunne ikke oprette mappe: {}", e))?;
+    }
+    std::fs::write(path, &bytes).map_err(|e| format!("Kunne ikke gemme eksport: {}", e))?;
+    Ok(bytes.len() as u64)
+}
+
+/// Session as Markdown
+pub fn render_markdown(session: &LocalSession) -> String {
+    let mut md = format!("# {}\n\n", title(session));
+    for (label, value) in metadata(session) {
+        md.push_str(&format!("- **{}:** {}\n", label, value));
+    }
+
+    if let Some(context) = context_json(session) {
+        md.push_str(&format!("\n## Kontekst\n\n```json\n{}\n```\n", context));
+    }
+
+    md.push_str("\n## Beskeder\n");
+    if session.messages.is_empty() {
+        md.push_str("\n_Ingen beskeder._\n");
+    }
+    for message in &session.messages {
+        md.push_str(&format!("\n### {} · {}\n\n{}\n", role_label(message), timestamp(message.timestamp), message.content.trim()));
+    }
+    md
+}
+
+/// Session as PDF
+pub fn render_pdf(session: &LocalSession) -> Vec<u8> {
+    let mut doc = PdfDocument::new();
+    doc.paragraph(PdfStyle::Title, &title(session));
+    doc.space(6.0);
+    for (label, value) in metadata(session) {
+        doc.paragraph(PdfStyle::Meta, &format!("{}: {}", label, value));
+    }
+
+    if let Some(context) = context_json(session) {
+        doc.space(12.0);
+        doc.paragraph(PdfStyle::Heading, "Kontekst");
+        doc.paragraph(PdfStyle::Meta, &context);
+    }
+
+    doc.space(12.0);
+    doc.paragraph(PdfStyle::Heading, "Beskeder");
+    if session.messages.is_empty() {
+        doc.paragraph(PdfStyle::Body, "Ingen beskeder.");
+    }
+    for message in &session.messages {
+        doc.space(8.0);
+        doc.paragraph(PdfStyle::Meta, &format!("{} · {}", role_label(message), timestamp(message.timestamp)));
+        doc.paragraph(PdfStyle::Body, message.content.trim());
+    }
+    doc.finish()
+}
+
+fn title(session: &LocalSession) -> String {
+    format!("Session: {}", session.session_type)
+}
+
+fn metadata(session: &LocalSession) -> Vec<(&'static str, String)> {
+    vec![
+        ("ID", session.id.to_string()),
+        ("Oprettet", timestamp(session.created_at)),
+        ("Opdateret", timestamp(session.updated_at)),
+        (
+            "Synkroniseret",
+            session.synced_at.map_or_else(|| "Ikke synkroniseret".to_string(), timestamp),
+        ),
+        ("Beskeder", session.messages.len().to_string()),
+    ]
+}
+
+/// Pretty-printed context, if it holds anything
+fn context_json(session: &LocalSession) -> Option<String> {
+    let empty = match &session.context {
+        serde_json::Value::Null => true,
+        serde_json::Value::Object(map) => map.is_empty(),
+        _ => false,
+    };
+    (!empty).then(|| serde_json::to_string_pretty(&session.context).unwrap_or_default())
+}
+
+fn role_label(message: &LocalMessage) -> String {
+    match message.role.as_str() {
+        "user" => "Bruger".to_string(),
+        "assistant" => "Assistent".to_string(),
+        "system" => "System".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use uuid::Uuid;
+
+    fn session() -> LocalSession {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 14, 2, 0).unwrap();
+        LocalSession {
+            id: Uuid::nil(),
+            session_type: "chat".to_string(),
+            context: serde_json::json!({"project": "cla"}),
+            messages: vec![
+                LocalMessage { role: "user".to_string(), content: "Hvad er nyt?".to_string(), timestamp: at },
+                LocalMessage { role: "assistant".to_string(), content: "Tre nye fund.\n".to_string(), timestamp: at },
+            ],
+            created_at: at,
+            updated_at: at,
+            synced_at: None,
+            cloud_id: None,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let md = render_markdown(&session());
+        assert!(md.starts_with("# Session: chat\n\n- **ID:** 00000000-0000-0000-0000-000000000000\n"));
+        assert!(md.contains("- **Synkroniseret:** Ikke synkroniseret\n"));
+        assert!(md.contains("```json\n{\n  \"project\": \"cla\"\n}\n```"));
+        assert!(md.ends_with("### Assistent · 2026-03-02 14:02:00 UTC\n\nTre nye fund.\n"));
+    }
+
+    #[test]
+    fn test_export_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export").join("session.pdf");
+        let written = export_session(&session(), ExportFormat::Pdf, &path).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), written);
+    }
+}
@@ -0,0 +1,238 @@
+// Minimal PDF writer - Text documents with the standard Helvetica fonts
+// No embedded fonts, so only Latin-1 text renders; other characters become '?'
+
+use std::io::Write;
+
+/// A4 in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 56.0;
+
+/// Average Helvetica glyph width as a fraction of the font size (used for wrapping)
+const AVG_GLYPH_WIDTH: f32 = 0.5;
+
+/// Text style of a paragraph
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PdfStyle {
+    Title,
+    Heading,
+    Body,
+    /// Small print (timestamps, ids)
+    Meta,
+}
+
+impl PdfStyle {
+    /// Font resource name and size
+    fn font(self) -> (&'static str, f32) {
+        match self {
+            Self::Title => ("F2", 18.0),
+            Self::Heading => ("F2", 12.0),
+            Self::Body => ("F1", 10.5),
+            Self::Meta => ("F1", 8.5),
+        }
+    }
+}
+
+/// Text-only PDF document laid out top to bottom across A4 pages
+pub struct PdfDocument {
+    pages: Vec<Vec<u8>>,
+    current: Vec<u8>,
+    /// Baseline of the next line
+    y: f32,
+}
+
+impl PdfDocument {
+    pub fn new() -> Self {
+        Self {
+            pages: Vec::new(),
+            current: Vec::new(),
+            y: PAGE_HEIGHT - MARGIN,
+        }
+    }
+
+    /// Add a paragraph, wrapping lines and starting new pages as needed
+    pub fn paragraph(&mut self, style: PdfStyle, text: &str) {
+        let (font, size) = style.font();
+        let line_height = size * 1.4;
+        let max_chars = ((PAGE_WIDTH - 2.0 * MARGIN) / (size * AVG_GLYPH_WIDTH)) as usize;
+
+        for line in text.lines().flat_map(|line| wrap(line, max_chars)) {
+            if self.y - line_height < MARGIN {
+                self.new_page();
+            }
+            self.y -= line_height;
+            let _ = writeln!(
+                self.current,
+                "BT /{} {} Tf {} {:.1} Td ({}) Tj ET",
+                font,
+                size,
+                MARGIN,
+                self.y,
+                escape(&line)
+            );
+        }
+    }
+
+    /// Vertical gap in points
+    pub fn space(&mut self, points: f32) {
+        self.y -= points;
+    }
+
+    fn new_page(&mut self) {
+        self.pages.push(std::mem::take(&mut self.current));
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    /// Serialize the document
+    pub fn finish(mut self) -> Vec<u8> {
+        self.new_page();
+        let page_count = self.pages.len();
+
+        // Objects: 1 catalog, 2 page tree, 3-4 fonts, then a page and its content per page
+        let mut objects: Vec<Vec<u8>> = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                (0..page_count).map(|i| format!("{} 0 R", 5 + 2 * i)).collect::<Vec<_>>().join(" "),
+                page_count
+            )
+            .into_bytes(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>".to_vec(),
+            b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+        ];
+        for (i, content) in self.pages.iter().enumerate() {
+            objects.push(
+                format!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                     /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                    PAGE_WIDTH,
+                    PAGE_HEIGHT,
+                    6 + 2 * i
+                )
+                .into_bytes(),
+            );
+            let compressed = deflate(content);
+            let mut stream = format!("<< /Length {} /Filter /FlateDecode >>\nstream\n", compressed.len()).into_bytes();
+            stream.extend_from_slice(&compressed);
+            stream.extend_from_slice(b"\nendstream");
+            objects.push(stream);
+        }
+
+        let mut pdf = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".to_vec();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            pdf.extend_from_slice(object);
+            pdf.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = pdf.len();
+        let _ = write!(pdf, "xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1);
+        for offset in offsets {
+            let _ = writeln!(pdf, "{:010} 00000 n ", offset);
+        }
+        let _ = write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        );
+        pdf
+    }
+}
+
+impl Default for PdfDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Break a line into chunks of at most `max_chars`, at spaces where possible
+fn wrap(line: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in line.split(' ').filter(|w| !w.is_empty()) {
+        let mut word = word.to_string();
+        while word.chars().count() > max_chars {
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            let split = word.char_indices().nth(max_chars).map_or(word.len(), |(i, _)| i);
+            lines.push(word[..split].to_string());
+            word = word[split..].to_string();
+        }
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&word);
+    }
+    lines.push(current);
+    lines
+}
+
+/// PDF string literal body in WinAnsi (Latin-1) encoding
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            '\t' => escaped.push(' '),
+            // Latin-1 letters as octal escapes keep the content stream ASCII
+            '\u{a0}'..='\u{ff}' => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    let _ = encoder.write_all(data);
+    encoder.finish().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::archive::extract_pdf_text;
+
+    #[test]
+    fn test_document_round_trips_through_text_extraction() {
+        let mut doc = PdfDocument::new();
+        doc.paragraph(PdfStyle::Title, "Session export");
+        doc.paragraph(PdfStyle::Body, "Hello (world)");
+        let pdf = doc.finish();
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        assert_eq!(extract_pdf_text(&pdf), "Session export Hello (world)");
+    }
+
+    #[test]
+    fn test_long_text_wraps_and_paginates() {
+        let mut doc = PdfDocument::new();
+        for _ in 0..120 {
+            doc.paragraph(PdfStyle::Body, &"ord ".repeat(60));
+        }
+        let pdf = String::from_utf8_lossy(&doc.finish()).into_owned();
+
+        // 360 wrapped lines at 49 lines per page
+        let count = pdf.split("/Count ").nth(1).unwrap();
+        assert_eq!(count.split_whitespace().next(), Some("8"));
+    }
+
+    #[test]
+    fn test_wrap_and_escape() {
+        assert_eq!(wrap("aa bb cc", 5), ["aa bb", "cc"]);
+        assert_eq!(wrap("abcdefgh", 3), ["abc", "def", "gh"]);
+        assert_eq!(escape("blå (x) ✓"), "bl\\345 \\(x\\) ?");
+    }
+}
@@ -0,0 +1,290 @@
+// Hardware benchmark - Measures inference throughput on the user's machine
+// The resulting profile drives task-duration estimates in the scheduler
+
+use super::{InferenceEngine, InferenceLane, WhisperTask};
+use crate::storage::JournaledFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// File name of the stored profile inside the models directory
+const PROFILE_FILE: &str = "hardware_profile.json";
+
+/// Texts embedded per benchmark run
+const EMBEDDING_SAMPLES: usize = 32;
+
+/// Length of the synthetic audio clip transcribed
+const AUDIO_SECONDS: u32 = 10;
+
+/// Pages run through OCR
+const OCR_PAGES: usize = 5;
+
+/// Size of the file written and read back for disk I/O
+const DISK_TEST_MB: usize = 64;
+
+/// Kind of inference work with a measured duration
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkTask {
+    /// Units are texts
+    Embedding,
+    /// Units are seconds of audio
+    Transcription,
+    /// Units are pages
+    Ocr,
+}
+
+/// Measured performance of this machine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareProfile {
+    pub cpu_cores: u32,
+    pub total_memory_mb: u64,
+    /// None when the embedding model is not installed
+    pub embeddings_per_sec: Option<f32>,
+    /// Processing time per second of audio (below 1.0 is faster than real time)
+    pub whisper_rtf: Option<f32>,
+    pub ocr_pages_per_min: Option<f32>,
+    pub disk_write_mb_s: f32,
+    pub disk_read_mb_s: f32,
+    pub measured_at: DateTime<Utc>,
+}
+
+impl HardwareProfile {
+    /// Location of the stored profile
+    pub fn path(models_dir: &Path) -> PathBuf {
+        models_dir.join(PROFILE_FILE)
+    }
+
+    /// Load a stored profile, if one exists
+    pub fn load(path: &Path) -> Option<Self> {
+        // A damaged profile is moved aside and reported; benchmark again
+        Self::file(path).load().ok().flatten()
+    }
+
+    /// Store the profile
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize hardware profile: {}", e))?;
+        Self::file(path)
+            .write(json.as_bytes())
+            .map_err(|e| format!("Failed to save hardware profile: {}", e))
+    }
+
+    fn file(path: &Path) -> JournaledFile {
+        JournaledFile::new("hardware_profile", path.to_path_buf())
+    }
+
+    /// Expected duration of `units` of work, if the task was measured
+    pub fn estimate(&self, task: BenchmarkTask, units: usize) -> Option<Duration> {
+        let secs = match task {
+            BenchmarkTask::Embedding => units as f32 / self.embeddings_per_sec.filter(|r| *r > 0.0)?,
+            BenchmarkTask::Transcription => units as f32 * self.whisper_rtf?,
+            BenchmarkTask::Ocr => units as f32 * 60.0 / self.ocr_pages_per_min.filter(|r| *r > 0.0)?,
+        };
+        Some(Duration::from_secs_f32(secs.max(0.0)))
+    }
+}
+
+/// Run all benchmarks; model benchmarks are skipped when no engine is loaded
+pub async fn run_benchmark(engine: Option<&InferenceEngine>, work_dir: &Path) -> Result<HardwareProfile, String> {
+    std::fs::create_dir_all(work_dir)
+        .map_err(|e| format!("Failed to create benchmark directory: {}", e))?;
+
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    system.refresh_cpu();
+
+    let (disk_write_mb_s, disk_read_mb_s) = {
+        let dir = work_dir.to_path_buf();
+        tokio::task::spawn_blocking(move || benchmark_disk(&dir, DISK_TEST_MB))
+            .await
+            .map_err(|e| format!("Disk benchmark failed: {}", e))??
+    };
+
+    let mut profile = HardwareProfile {
+        cpu_cores: system.cpus().len() as u32,
+        total_memory_mb: system.total_memory() / 1024 / 1024,
+        embeddings_per_sec: None,
+        whisper_rtf: None,
+        ocr_pages_per_min: None,
+        disk_write_mb_s,
+        disk_read_mb_s,
+        measured_at: Utc::now(),
+    };
+
+    if let Some(engine) = engine {
+        if engine.has_embedding_model() {
+            profile.embeddings_per_sec = Some(benchmark_embedding(engine).await?);
+        }
+        if engine.has_whisper_model() {
+            profile.whisper_rtf = Some(benchmark_whisper(engine, work_dir).await?);
+        }
+        if engine.has_ocr_engine() {
+            profile.ocr_pages_per_min = Some(benchmark_ocr(engine, work_dir).await?);
+        }
+    }
+
+    Ok(profile)
+}
+
+async fn benchmark_embedding(engine: &InferenceEngine) -> Result<f32, String> {
+    let started = Instant::now();
+    for i in 0..EMBEDDING_SAMPLES {
+        let text = format!(
+            "Benchmark passage {} about local inference, retrieval and accessible voice control.",
+            i
+        );
+        engine.generate_embedding_in(InferenceLane::Background, &text).await?;
+    }
+    Ok(EMBEDDING_SAMPLES as f32 / started.elapsed().as_secs_f32().max(f32::EPSILON))
+}
+
+async fn benchmark_whisper(engine: &InferenceEngine, work_dir: &Path) -> Result<f32, String> {
+    let path = work_dir.join("benchmark.wav");
+    write_test_audio(&path, AUDIO_SECONDS)?;
+
+    let started = Instant::now();
+    let result = engine
+        .transcribe_in(InferenceLane::Background, &path.to_string_lossy(), Some("en"), WhisperTask::Transcribe)
+        .await;
+    let elapsed = started.elapsed().as_secs_f32();
+    let _ = std::fs::remove_file(&path);
+
+    result?;
+    Ok(elapsed / AUDIO_SECONDS as f32)
+}
+
+async fn benchmark_ocr(engine: &InferenceEngine, work_dir: &Path) -> Result<f32, String> {
+    let path = work_dir.join("benchmark.bmp");
+    std::fs::write(&path, test_page_bmp(1240, 1754))
+        .map_err(|e| format!("Failed to write benchmark image: {}", e))?;
+
+    let started = Instant::now();
+    let mut result = Ok(());
+    for _ in 0..OCR_PAGES {
+        if let Err(e) = engine.extract_text(&path.to_string_lossy()).await {
+            result = Err(e);
+            break;
+        }
+    }
+    let elapsed = started.elapsed().as_secs_f32();
+    let _ = std::fs::remove_file(&path);
+
+    result?;
+    Ok(OCR_PAGES as f32 * 60.0 / elapsed.max(f32::EPSILON))
+}
+
+/// Sequential write and read throughput in MB/s
+fn benchmark_disk(dir: &Path, size_mb: usize) -> Result<(f32, f32), String> {
+    let path = dir.join("benchmark.bin");
+    let block = vec![0xA5u8; 1024 * 1024];
+
+    let started = Instant::now();
+    {
+        let mut file = std::fs::File::create(&path)
+            .map_err(|e| format!("Failed to create benchmark file: {}", e))?;
+        for _ in 0..size_mb {
+            file.write_all(&block).map_err(|e| format!("Disk write failed: {}", e))?;
+        }
+        file.sync_all().map_err(|e| format!("Disk sync failed: {}", e))?;
+    }
+    let write_secs = started.elapsed().as_secs_f32();
+
+    let started = Instant::now();
+    let mut buffer = vec![0u8; block.len()];
+    let read_result = std::fs::File::open(&path).and_then(|mut file| {
+        while file.read(&mut buffer)? > 0 {}
+        Ok(())
+    });
+    let read_secs = started.elapsed().as_secs_f32();
+    let _ = std::fs::remove_file(&path);
+    read_result.map_err(|e| format!("Disk read failed: {}", e))?;
+
+    Ok((
+        size_mb as f32 / write_secs.max(f32::EPSILON),
+        size_mb as f32 / read_secs.max(f32::EPSILON),
+    ))
+}
+
+/// 16kHz mono clip with a quiet tone, so the decoder has work to do
+fn write_test_audio(path: &Path, seconds: u32) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: 16000,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to write benchmark audio: {}", e))?;
+    for n in 0..spec.sample_rate * seconds {
+        let t = n as f32 / spec.sample_rate as f32;
+        let sample = (t * 440.0 * std::f32::consts::TAU).sin() * 3000.0;
+        writer
+            .write_sample(sample as i16)
+            .map_err(|e| format!("Failed to write benchmark audio: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to write benchmark audio: {}", e))
+}
+
+/// Uncompressed 24-bit BMP of a blank A4 page at 150 dpi
+fn test_page_bmp(width: u32, height: u32) -> Vec<u8> {
+    let row = (width * 3 + 3) & !3;
+    let pixels = row * height;
+
+    let mut bmp = Vec::with_capacity(54 + pixels as usize);
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(54 + pixels).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&54u32.to_le_bytes());
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    bmp.extend_from_slice(&width.to_le_bytes());
+    bmp.extend_from_slice(&height.to_le_bytes());
+    bmp.extend_from_slice(&1u16.to_le_bytes());
+    bmp.extend_from_slice(&24u16.to_le_bytes());
+    bmp.extend_from_slice(&[0; 24]);
+    bmp.resize(54 + pixels as usize, 0xFF);
+    bmp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> HardwareProfile {
+        HardwareProfile {
+            cpu_cores: 4,
+            total_memory_mb: 8192,
+            embeddings_per_sec: Some(20.0),
+            whisper_rtf: Some(0.5),
+            ocr_pages_per_min: None,
+            disk_write_mb_s: 500.0,
+            disk_read_mb_s: 1000.0,
+            measured_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_estimates_from_profile() {
+        let profile = profile();
+        assert_eq!(profile.estimate(BenchmarkTask::Embedding, 40), Some(Duration::from_secs(2)));
+        assert_eq!(profile.estimate(BenchmarkTask::Transcription, 60), Some(Duration::from_secs(30)));
+        assert_eq!(profile.estimate(BenchmarkTask::Ocr, 3), None);
+    }
+
+    #[test]
+    fn test_disk_benchmark_and_profile_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let (write, read) = benchmark_disk(dir.path(), 2).unwrap();
+        assert!(write > 0.0 && read > 0.0);
+        assert!(!dir.path().join("benchmark.bin").exists());
+
+        let path = HardwareProfile::path(dir.path());
+        profile().save(&path).unwrap();
+        assert_eq!(HardwareProfile::load(&path).unwrap().whisper_rtf, Some(0.5));
+    }
+}
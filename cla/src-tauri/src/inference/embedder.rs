@@ -0,0 +1,135 @@
+// Embedder - Shared access to the embedding model for background work
+// Callers take a handle to the loaded model and release the engine lock before
+// embedding, so model reloads and settings changes are not held up by long batches
+
+use super::{BenchmarkTask, EmbeddingModel, InferenceEngine, InferenceLane, InferenceScheduler, JobWork};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{Mutex, RwLock};
+
+/// Characters of text embedded per item; the model truncates longer input anyway
+pub const MAX_EMBED_CHARS: usize = 512;
+
+/// Texts per forward pass when embedding in bulk
+const EMBEDDING_BATCH_SIZE: usize = 32;
+
+/// Handle to the loaded embedding model, usable without the engine lock
+#[derive(Clone)]
+pub struct Embedder {
+    model: Arc<Mutex<EmbeddingModel>>,
+    scheduler: Arc<InferenceScheduler>,
+}
+
+impl Embedder {
+    pub(super) fn new(model: Arc<Mutex<EmbeddingModel>>, scheduler: Arc<InferenceScheduler>) -> Self {
+        Self { model, scheduler }
+    }
+
+    /// Embed one text in a priority lane
+    pub async fn embed_in(&self, lane: InferenceLane, text: &str) -> Result<Vec<f32>, String> {
+        let work = JobWork {
+            task: BenchmarkTask::Embedding,
+            units: 1,
+        };
+        let _permit = self.scheduler.acquire(lane, Some(work)).await;
+        let text = text.to_string();
+        super::run_blocking(&self.model, move |model| model.encode(&text)).await
+    }
+
+    /// Embed many texts in a priority lane. Texts of similar length are batched
+    /// together; each result carries its share of its batch's inference time,
+    /// split by text length.
+    pub async fn embed_many_in(
+        &self,
+        lane: InferenceLane,
+        texts: Vec<String>,
+    ) -> Result<Vec<(Vec<f32>, Duration)>, String> {
+        // Sort by length so little of each batch is padding
+        let mut order: Vec<usize> = (0..texts.len()).collect();
+        order.sort_by_key(|&i| texts[i].len());
+
+        let mut results: Vec<Option<(Vec<f32>, Duration)>> = vec![None; texts.len()];
+        for indices in order.chunks(EMBEDDING_BATCH_SIZE) {
+            let batch: Vec<String> = indices.iter().map(|&i| texts[i].clone()).collect();
+            let lengths: Vec<usize> = batch.iter().map(|text| text.len().max(1)).collect();
+
+            // One permit per batch lets interactive work in between batches
+            let work = JobWork {
+                task: BenchmarkTask::Embedding,
+                units: batch.len(),
+            };
+            let _permit = self.scheduler.acquire(lane, Some(work)).await;
+            let start = Instant::now();
+            let embeddings = super::run_blocking(&self.model, move |model| model.encode_batch(&batch)).await?;
+            let elapsed = start.elapsed();
+
+            let total: usize = lengths.iter().sum();
+            for ((&i, embedding), length) in indices.iter().zip(embeddings).zip(lengths) {
+                results[i] = Some((embedding, elapsed.mul_f64(length as f64 / total as f64)));
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|result| result.ok_or_else(|| "Embedding batch returned too few vectors".to_string()))
+            .collect()
+    }
+}
+
+/// Embedder of the shared engine, or None when no embedding model is available.
+/// The engine lock is only held while the handle is taken.
+pub async fn shared_embedder(inference: &RwLock<Option<InferenceEngine>>) -> Option<Embedder> {
+    let engine = inference.read().await;
+    let engine = engine.as_ref().filter(|e| e.has_embedding_model())?;
+    match engine.embedder().await {
+        Ok(embedder) => Some(embedder),
+        Err(e) => {
+            log::warn!("Embedding model unavailable: {}", e);
+            None
+        }
+    }
+}
+
+/// Embed texts, each cut to `MAX_EMBED_CHARS`, in the background lane of the shared
+/// engine. None when no embedding model is available.
+pub async fn embed_texts(
+    inference: &RwLock<Option<InferenceEngine>>,
+    texts: impl IntoIterator<Item = String>,
+) -> Option<Result<Vec<Vec<f32>>, String>> {
+    let embedder = shared_embedder(inference).await?;
+    let texts = texts
+        .into_iter()
+        .map(|text| text.chars().take(MAX_EMBED_CHARS).collect())
+        .collect();
+    let embeddings = embedder.embed_many_in(InferenceLane::Background, texts).await;
+    Some(embeddings.map(|embeddings| embeddings.into_iter().map(|(embedding, _)| embedding).collect()))
+}
+
+/// Cosine similarity of two vectors; 0 for vectors of different length or zero norm
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[2.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[0.0, 0.0]), 0.0);
+        // Vectors of different models are never similar
+        assert_eq!(cosine_similarity(&[1.0, 0.0], &[1.0, 0.0, 0.0]), 0.0);
+    }
+}
@@ -1,10 +1,15 @@
 // Embedding model implementation using ONNX Runtime v2
 // Model: all-MiniLM-L6-v2 (384 dimensions)
 
+use std::collections::HashMap;
 use std::path::Path;
+use std::time::{Duration, Instant};
 use ort::session::{Session, builder::GraphOptimizationLevel};
 use ort::value::Tensor;
 
+/// Words whose word pieces the tokenizer remembers
+const TOKEN_CACHE_WORDS: usize = 50_000;
+
 /// Embedding model for semantic search
 pub struct EmbeddingModel {
     session: Session,
@@ -45,24 +50,55 @@ impl EmbeddingModel {
         })
     }
 
+    /// Run a throwaway inference so the first real call does not pay for
+    /// ONNX Runtime's lazy initialization. Returns how long it took.
+    pub fn warm_up(&mut self) -> Result<Duration, String> {
+        let start = Instant::now();
+        self.encode_batch(&["Cirkelline er klar.", "Cirkelline is ready."])?;
+        Ok(start.elapsed())
+    }
+
     /// Generate embedding for text (synchronous)
     pub fn encode(&mut self, text: &str) -> Result<Vec<f32>, String> {
-        // Tokenize input
-        let encoding = self.tokenizer.encode(text, 512)?;
+        self.encode_batch(&[text])?
+            .pop()
+            .ok_or_else(|| "Empty embedding batch".to_string())
+    }
 
-        // Prepare inputs
-        let input_ids: Vec<i64> = encoding.input_ids.iter().map(|&x| x as i64).collect();
-        let attention_mask: Vec<i64> = encoding.attention_mask.iter().map(|&x| x as i64).collect();
-        let token_type_ids: Vec<i64> = vec![0i64; input_ids.len()];
+    /// Generate embeddings for several texts in one forward pass (synchronous).
+    /// Shorter texts are padded to the longest; the attention mask keeps the
+    /// padding out of the pooled vectors.
+    pub fn encode_batch<S: AsRef<str>>(&mut self, texts: &[S]) -> Result<Vec<Vec<f32>>, String> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let seq_len = input_ids.len();
+        // Tokenize inputs
+        let encodings: Vec<Encoding> = texts
+            .iter()
+            .map(|text| self.tokenizer.encode(text.as_ref(), 512))
+            .collect();
+        let batch_size = encodings.len();
+        let seq_len = encodings.iter().map(|e| e.input_ids.len()).max().unwrap_or(0);
+
+        // Prepare inputs, padded to the longest sequence
+        let mut input_ids: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        let mut attention_mask: Vec<i64> = Vec::with_capacity(batch_size * seq_len);
+        for encoding in &encodings {
+            let padding = seq_len - encoding.input_ids.len();
+            input_ids.extend(encoding.input_ids.iter().map(|&x| x as i64));
+            input_ids.extend(std::iter::repeat_n(self.tokenizer.pad_id as i64, padding));
+            attention_mask.extend(encoding.attention_mask.iter().map(|&x| x as i64));
+            attention_mask.extend(std::iter::repeat_n(0i64, padding));
+        }
+        let token_type_ids: Vec<i64> = vec![0i64; input_ids.len()];
 
         // Create input tensors using ort v2 API
-        let input_ids_tensor = Tensor::from_array(([1usize, seq_len], input_ids))
+        let input_ids_tensor = Tensor::from_array(([batch_size, seq_len], input_ids))
             .map_err(|e| format!("Failed to create input_ids tensor: {}", e))?;
-        let attention_mask_tensor = Tensor::from_array(([1usize, seq_len], attention_mask.clone()))
+        let attention_mask_tensor = Tensor::from_array(([batch_size, seq_len], attention_mask))
             .map_err(|e| format!("Failed to create attention_mask tensor: {}", e))?;
-        let token_type_ids_tensor = Tensor::from_array(([1usize, seq_len], token_type_ids))
+        let token_type_ids_tensor = Tensor::from_array(([batch_size, seq_len], token_type_ids))
             .map_err(|e| format!("Failed to create token_type_ids tensor: {}", e))?;
 
         // Build inputs vec - ort v2 inputs! returns Vec directly
@@ -76,7 +112,7 @@ impl EmbeddingModel {
         let outputs = self.session.run(inputs)
             .map_err(|e| format!("Inference failed: {}", e))?;
 
-        // Extract output - last_hidden_state shape: (1, seq_len, 384)
+        // Extract output - last_hidden_state shape: (batch, seq_len, 384)
         let output = outputs.get("last_hidden_state")
             .ok_or("Missing output: last_hidden_state")?;
 
@@ -92,13 +128,18 @@ impl EmbeddingModel {
 
         let hidden_size = shape_dims[2] as usize;
 
-        // Mean pooling over sequence dimension
-        let embedding = mean_pooling_flat(data, &encoding.attention_mask, seq_len, hidden_size)?;
-
-        // L2 normalize
-        let normalized = l2_normalize(&embedding);
-
-        Ok(normalized)
+        // Mean pooling over each sequence, then L2 normalize
+        encodings
+            .iter()
+            .enumerate()
+            .map(|(i, encoding)| {
+                let rows = data
+                    .get(i * seq_len * hidden_size..(i + 1) * seq_len * hidden_size)
+                    .ok_or("Embedding output smaller than batch")?;
+                let embedding = mean_pooling_flat(rows, &encoding.attention_mask, seq_len, hidden_size)?;
+                Ok(l2_normalize(&embedding))
+            })
+            .collect()
     }
 
     /// Get model ID
@@ -122,13 +163,13 @@ fn mean_pooling_flat(
     let mut embedding = vec![0.0f32; hidden_size];
     let mut total_weight = 0.0f32;
 
-    // hidden_states shape: (1, seq_len, hidden_size) - stored in row-major order
+    // hidden_states shape: (seq_len, hidden_size) of one sequence - stored in row-major order
     for i in 0..seq_len {
         let weight = attention_mask.get(i).copied().unwrap_or(0) as f32;
         total_weight += weight;
 
         for j in 0..hidden_size {
-            // Access flattened tensor: batch=0, seq=i, hidden=j
+            // Access flattened tensor: seq=i, hidden=j
             let idx = i * hidden_size + j;
             if let Some(&val) = hidden_states.get(idx) {
                 embedding[j] += val * weight;
@@ -157,12 +198,13 @@ fn l2_normalize(vec: &[f32]) -> Vec<f32> {
 
 /// Simple WordPiece tokenizer for BERT-based models
 pub struct Tokenizer {
-    vocab: std::collections::HashMap<String, u32>,
+    vocab: HashMap<String, u32>,
     unk_id: u32,
     cls_id: u32,
     sep_id: u32,
-    #[allow(dead_code)]
     pad_id: u32,
+    /// Word pieces by lowercased word, so repeated words skip the vocabulary search
+    cache: HashMap<String, Vec<u32>>,
 }
 
 pub struct Encoding {
@@ -174,8 +216,12 @@ impl Tokenizer {
     pub fn new(vocab_path: &Path) -> Result<Self, String> {
         let vocab_text = std::fs::read_to_string(vocab_path)
             .map_err(|e| format!("Failed to read vocab: {}", e))?;
+        Ok(Self::from_vocab(&vocab_text))
+    }
 
-        let mut vocab = std::collections::HashMap::new();
+    /// Tokenizer for a vocabulary with one token per line
+    fn from_vocab(vocab_text: &str) -> Self {
+        let mut vocab = HashMap::new();
         for (idx, line) in vocab_text.lines().enumerate() {
             vocab.insert(line.to_string(), idx as u32);
         }
@@ -185,75 +231,84 @@ impl Tokenizer {
         let sep_id = *vocab.get("[SEP]").unwrap_or(&102);
         let pad_id = *vocab.get("[PAD]").unwrap_or(&0);
 
-        Ok(Self {
+        Self {
             vocab,
             unk_id,
             cls_id,
             sep_id,
             pad_id,
-        })
+            cache: HashMap::new(),
+        }
     }
 
-    pub fn encode(&self, text: &str, max_len: usize) -> Result<Encoding, String> {
-        let tokens = self.tokenize(text);
-
-        // Truncate if needed (leaving room for [CLS] and [SEP])
+    pub fn encode(&mut self, text: &str, max_len: usize) -> Encoding {
+        // Leave room for [CLS] and [SEP]
         let max_tokens = max_len.saturating_sub(2);
-        let tokens: Vec<_> = tokens.into_iter().take(max_tokens).collect();
 
         // Build input_ids: [CLS] + tokens + [SEP]
-        let mut input_ids = Vec::with_capacity(tokens.len() + 2);
-        input_ids.push(self.cls_id);
-        for token in &tokens {
-            let id = self.vocab.get(token).copied().unwrap_or(self.unk_id);
-            input_ids.push(id);
+        let mut input_ids = vec![self.cls_id];
+        for word in text.to_lowercase().split_whitespace() {
+            if input_ids.len() > max_tokens {
+                break;
+            }
+            if let Some(pieces) = self.cache.get(word) {
+                input_ids.extend_from_slice(pieces);
+                continue;
+            }
+
+            let pieces = self.word_pieces(word);
+            input_ids.extend_from_slice(&pieces);
+            if self.cache.len() >= TOKEN_CACHE_WORDS {
+                self.cache.clear();
+            }
+            self.cache.insert(word.to_string(), pieces);
         }
+        // Truncate if needed
+        input_ids.truncate(max_tokens + 1);
         input_ids.push(self.sep_id);
 
         // Attention mask
         let attention_mask = vec![1u32; input_ids.len()];
 
-        Ok(Encoding {
+        Encoding {
             input_ids,
             attention_mask,
-        })
+        }
     }
 
-    fn tokenize(&self, text: &str) -> Vec<String> {
-        let mut tokens = Vec::new();
-        let text = text.to_lowercase();
-
-        for word in text.split_whitespace() {
-            // Simple WordPiece tokenization
-            let mut start = 0;
-            while start < word.len() {
-                let mut end = word.len();
-                let mut found = false;
-
-                while start < end {
-                    let substr = if start == 0 {
-                        word[start..end].to_string()
-                    } else {
-                        format!("##{}", &word[start..end])
-                    };
-
-                    if self.vocab.contains_key(&substr) {
-                        tokens.push(substr);
-                        found = true;
-                        break;
-                    }
+    /// Greedy longest-match WordPiece ids of one word; [UNK] if it cannot be split
+    fn word_pieces(&self, word: &str) -> Vec<u32> {
+        let mut pieces = Vec::new();
+        let mut start = 0;
+        while start < word.len() {
+            let mut end = word.len();
+            let mut found = None;
+
+            while start < end {
+                let substr = if start == 0 {
+                    word[start..end].to_string()
+                } else {
+                    format!("##{}", &word[start..end])
+                };
+
+                if let Some(&id) = self.vocab.get(&substr) {
+                    found = Some(id);
+                    break;
+                }
+                // Step back a whole character; Danish letters are multi-byte
+                end -= 1;
+                while !word.is_char_boundary(end) {
                     end -= 1;
                 }
+            }
 
-                if !found {
-                    tokens.push("[UNK]".to_string());
-                    break;
-                }
-                start = end;
+            match found {
+                Some(id) => pieces.push(id),
+                None => return vec![self.unk_id],
             }
+            start = end;
         }
-
-        tokens
+        pieces
     }
 }
 
@@ -268,4 +323,25 @@ mod tests {
         assert!((normalized[0] - 0.6).abs() < 0.001);
         assert!((normalized[1] - 0.8).abs() < 0.001);
     }
+
+    #[test]
+    fn test_tokenizer_caches_word_pieces() {
+        let mut tokenizer = Tokenizer::from_vocab("[PAD]\n[UNK]\n[CLS]\n[SEP]\nhej\n##sa\nverden");
+
+        let encoding = tokenizer.encode("Hejsa verden xyz", 512);
+        assert_eq!(encoding.input_ids, vec![2, 4, 5, 6, 1, 3]);
+        assert_eq!(tokenizer.cache.len(), 3);
+
+        // Cached words tokenize the same, and truncation keeps [CLS] and [SEP]
+        assert_eq!(tokenizer.encode("hejsa verden", 512).input_ids, vec![2, 4, 5, 6, 3]);
+        assert_eq!(tokenizer.encode("hejsa verden", 4).input_ids, vec![2, 4, 5, 3]);
+    }
+
+    #[test]
+    fn test_mean_pooling_skips_padding() {
+        // Two real tokens and one padding row
+        let hidden = vec![1.0, 2.0, 3.0, 4.0, 100.0, 100.0];
+        let pooled = mean_pooling_flat(&hidden, &[1, 1], 3, 2).unwrap();
+        assert_eq!(pooled, vec![2.0, 3.0]);
+    }
 }
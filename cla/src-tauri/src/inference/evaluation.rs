@@ -0,0 +1,231 @@
+// Embedding evaluation - A/B comparison of embedding models
+// Runs a labeled query/passage set through two models and scores retrieval quality
+
+use super::cosine_similarity;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::Instant;
+
+/// Labeled evaluation set with relevance judgments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalDataset {
+    pub queries: Vec<EvalItem>,
+    pub passages: Vec<EvalItem>,
+    pub judgments: Vec<RelevanceJudgment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvalItem {
+    pub id: String,
+    pub text: String,
+}
+
+/// A passage judged relevant to a query (relevance 0 = not relevant)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelevanceJudgment {
+    pub query_id: String,
+    pub passage_id: String,
+    pub relevance: u8,
+}
+
+impl EvalDataset {
+    /// Load a dataset from JSON
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read evaluation set: {}", e))?;
+        let dataset: Self = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid evaluation set: {}", e))?;
+
+        if dataset.queries.is_empty() || dataset.passages.is_empty() {
+            return Err("Evaluation set has no queries or passages".to_string());
+        }
+        Ok(dataset)
+    }
+
+    /// Relevant passage ids per query
+    fn relevant(&self) -> HashMap<&str, HashSet<&str>> {
+        let mut relevant: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for judgment in self.judgments.iter().filter(|j| j.relevance > 0) {
+            relevant
+                .entry(judgment.query_id.as_str())
+                .or_default()
+                .insert(judgment.passage_id.as_str());
+        }
+        relevant
+    }
+}
+
+/// Retrieval quality of one model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelMetrics {
+    pub model_id: String,
+    pub recall_at_k: f32,
+    pub mrr: f32,
+    pub queries_evaluated: usize,
+    pub mean_embed_ms: f32,
+    /// Reciprocal rank per query id
+    pub reciprocal_ranks: HashMap<String, f32>,
+}
+
+/// Side-by-side comparison of two models on the same set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonReport {
+    pub k: usize,
+    pub queries: usize,
+    pub passages: usize,
+    pub model_a: ModelMetrics,
+    pub model_b: ModelMetrics,
+    /// Queries where model B ranked the first relevant passage higher
+    pub b_better_queries: usize,
+    /// Queries where model A ranked the first relevant passage higher
+    pub a_better_queries: usize,
+    /// Model with the higher MRR (None on a tie)
+    pub winner: Option<String>,
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Evaluate a model given as an embedding function
+pub fn evaluate<F>(dataset: &EvalDataset, model_id: &str, k: usize, mut embed: F) -> Result<ModelMetrics, String>
+where
+    F: FnMut(&str) -> Result<Vec<f32>, String>,
+{
+    let relevant = dataset.relevant();
+    let started = Instant::now();
+    let mut embedded = 0usize;
+
+    let passages: Vec<(&str, Vec<f32>)> = dataset
+        .passages
+        .iter()
+        .map(|p| {
+            embedded += 1;
+            embed(&p.text).map(|v| (p.id.as_str(), v))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let mut recall_sum = 0.0;
+    let mut rr_sum = 0.0;
+    let mut reciprocal_ranks = HashMap::new();
+
+    for query in &dataset.queries {
+        let Some(relevant) = relevant.get(query.id.as_str()) else {
+            continue;
+        };
+        embedded += 1;
+        let query_vec = embed(&query.text)?;
+
+        let mut ranked: Vec<(&str, f32)> = passages
+            .iter()
+            .map(|(id, vec)| (*id, cosine_similarity(&query_vec, vec)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        let hits = ranked.iter().take(k).filter(|(id, _)| relevant.contains(id)).count();
+        recall_sum += hits as f32 / relevant.len() as f32;
+
+        let rr = ranked
+            .iter()
+            .position(|(id, _)| relevant.contains(id))
+            .map_or(0.0, |rank| 1.0 / (rank + 1) as f32);
+        rr_sum += rr;
+        reciprocal_ranks.insert(query.id.clone(), rr);
+    }
+
+    let evaluated = reciprocal_ranks.len();
+    if evaluated == 0 {
+        return Err("No query has a relevance judgment".to_string());
+    }
+
+    Ok(ModelMetrics {
+        model_id: model_id.to_string(),
+        recall_at_k: recall_sum / evaluated as f32,
+        mrr: rr_sum / evaluated as f32,
+        queries_evaluated: evaluated,
+        mean_embed_ms: started.elapsed().as_secs_f32() * 1000.0 / embedded.max(1) as f32,
+        reciprocal_ranks,
+    })
+}
+
+/// Compare two evaluated models
+pub fn compare(dataset: &EvalDataset, k: usize, model_a: ModelMetrics, model_b: ModelMetrics) -> ComparisonReport {
+    let mut a_better = 0;
+    let mut b_better = 0;
+    for (query, rr_a) in &model_a.reciprocal_ranks {
+        let rr_b = model_b.reciprocal_ranks.get(query).copied().unwrap_or(0.0);
+        if rr_a > &rr_b {
+            a_better += 1;
+        } else if rr_b > *rr_a {
+            b_better += 1;
+        }
+    }
+
+    let winner = if (model_a.mrr - model_b.mrr).abs() < f32::EPSILON {
+        None
+    } else if model_a.mrr > model_b.mrr {
+        Some(model_a.model_id.clone())
+    } else {
+        Some(model_b.model_id.clone())
+    };
+
+    ComparisonReport {
+        k,
+        queries: dataset.queries.len(),
+        passages: dataset.passages.len(),
+        model_a,
+        model_b,
+        b_better_queries: b_better,
+        a_better_queries: a_better,
+        winner,
+        generated_at: Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dataset() -> EvalDataset {
+        let item = |id: &str, text: &str| EvalItem { id: id.to_string(), text: text.to_string() };
+        EvalDataset {
+            queries: vec![item("q1", "rust async"), item("q2", "python typing")],
+            passages: vec![
+                item("p1", "rust async runtime"),
+                item("p2", "python typing hints"),
+                item("p3", "gardening tips"),
+            ],
+            judgments: vec![
+                RelevanceJudgment { query_id: "q1".into(), passage_id: "p1".into(), relevance: 2 },
+                RelevanceJudgment { query_id: "q2".into(), passage_id: "p2".into(), relevance: 1 },
+            ],
+        }
+    }
+
+    /// Bag-of-words over a tiny vocabulary
+    fn words(text: &str) -> Result<Vec<f32>, String> {
+        Ok(["rust", "async", "python", "typing", "gardening"]
+            .iter()
+            .map(|w| if text.contains(w) { 1.0 } else { 0.0 })
+            .collect())
+    }
+
+    #[test]
+    fn test_perfect_model_scores_one() {
+        let metrics = evaluate(&dataset(), "words", 1, words).unwrap();
+        assert_eq!(metrics.recall_at_k, 1.0);
+        assert_eq!(metrics.mrr, 1.0);
+        assert_eq!(metrics.queries_evaluated, 2);
+    }
+
+    #[test]
+    fn test_compare_picks_better_model() {
+        let data = dataset();
+        let good = evaluate(&data, "words", 1, words).unwrap();
+        // Constant vectors rank passages arbitrarily
+        let bad = evaluate(&data, "constant", 1, |_| Ok(vec![1.0, 1.0])).unwrap();
+
+        let report = compare(&data, 1, bad, good);
+        assert_eq!(report.winner.as_deref(), Some("words"));
+        assert!(report.b_better_queries >= 1);
+    }
+}
@@ -0,0 +1,122 @@
+// Intent classification model - Distilled multilingual BERT fine-tuned on the voice command intents
+// Files: intent-da/model.onnx, vocab.txt and labels.txt (one intent per line, in logit order)
+
+use super::embedding::Tokenizer;
+use super::{InferenceEngine, InferenceLane};
+use crate::accessibility::{IntentClassifier, IntentPrediction};
+use async_trait::async_trait;
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
+use std::path::Path;
+use tokio::sync::RwLock;
+
+/// Directory of the model inside the models directory
+pub const INTENT_MODEL_DIR: &str = "intent-da";
+
+/// Voice commands are short; longer input is truncated
+const MAX_TOKENS: usize = 64;
+
+/// Voice command intent classifier
+pub struct IntentModel {
+    session: Session,
+    tokenizer: Tokenizer,
+    labels: Vec<String>,
+}
+
+impl IntentModel {
+    /// Load model, vocabulary and labels from `dir`
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let labels: Vec<String> = std::fs::read_to_string(dir.join("labels.txt"))
+            .map_err(|e| format!("Failed to read intent labels: {}", e))?
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect();
+        if labels.is_empty() {
+            return Err("Intent model has no labels".to_string());
+        }
+
+        let session = Session::builder()
+            .map_err(|e| format!("Failed to create session builder: {}", e))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| format!("Failed to set optimization level: {}", e))?
+            .with_intra_threads(1)
+            .map_err(|e| format!("Failed to set thread count: {}", e))?
+            .commit_from_file(dir.join("model.onnx"))
+            .map_err(|e| format!("Failed to load intent model: {}", e))?;
+
+        Ok(Self {
+            session,
+            tokenizer: Tokenizer::new(&dir.join("vocab.txt"))?,
+            labels,
+        })
+    }
+
+    /// Most likely intent with its softmax probability
+    pub fn classify(&mut self, text: &str) -> Result<IntentPrediction, String> {
+        let encoding = self.tokenizer.encode(text, MAX_TOKENS);
+        let seq_len = encoding.input_ids.len();
+        let input_ids: Vec<i64> = encoding.input_ids.iter().map(|&x| x as i64).collect();
+        let attention_mask: Vec<i64> = encoding.attention_mask.iter().map(|&x| x as i64).collect();
+
+        let input_ids = Tensor::from_array(([1usize, seq_len], input_ids))
+            .map_err(|e| format!("Failed to create input_ids tensor: {}", e))?;
+        let attention_mask = Tensor::from_array(([1usize, seq_len], attention_mask))
+            .map_err(|e| format!("Failed to create attention_mask tensor: {}", e))?;
+
+        let outputs = self
+            .session
+            .run(ort::inputs!["input_ids" => input_ids, "attention_mask" => attention_mask])
+            .map_err(|e| format!("Intent inference failed: {}", e))?;
+        let (_, logits) = outputs
+            .get("logits")
+            .ok_or("Missing output: logits")?
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract logits: {}", e))?;
+        if logits.len() != self.labels.len() {
+            return Err(format!("Intent model has {} outputs but {} labels", logits.len(), self.labels.len()));
+        }
+
+        let (index, confidence) = best_class(logits);
+        Ok(IntentPrediction {
+            intent: self.labels[index].clone(),
+            confidence,
+        })
+    }
+}
+
+/// Index and softmax probability of the largest logit
+fn best_class(logits: &[f32]) -> (usize, f32) {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let exp: Vec<f32> = logits.iter().map(|l| (l - max).exp()).collect();
+    let total: f32 = exp.iter().sum();
+    let index = logits.iter().position(|&l| l == max).unwrap_or(0);
+    (index, exp[index] / total)
+}
+
+/// The shared engine classifies when it is loaded and has the intent model
+#[async_trait]
+impl IntentClassifier for RwLock<Option<InferenceEngine>> {
+    async fn classify(&self, text: &str) -> Option<IntentPrediction> {
+        let engine = self.read().await;
+        let engine = engine.as_ref().filter(|e| e.has_intent_model())?;
+        engine
+            .classify_intent_in(InferenceLane::Accessibility, text)
+            .await
+            .map_err(|e| log::warn!("Intent classification failed: {}", e))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_class() {
+        let (index, confidence) = best_class(&[0.0, 3.0, 1.0]);
+        assert_eq!(index, 1);
+        assert!((confidence - 0.8438).abs() < 1e-3);
+    }
+}
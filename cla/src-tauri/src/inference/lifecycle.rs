@@ -0,0 +1,423 @@
+// Model Lifecycle - Loading, unloading and hot-swapping local models within a memory budget
+// A model unloaded to free RAM loads again on its next use; in-flight calls keep the old copy
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex};
+
+/// A model must sit unused this long before the budget may unload it
+pub const MIN_IDLE: Duration = Duration::from_secs(60);
+
+/// Whether a model is in memory
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelLoadState {
+    Loaded,
+    /// On disk, loads on next use
+    Unloaded,
+    /// Not downloaded
+    Missing,
+    /// On disk but failed to load; retried after `reload_models`
+    Failed,
+}
+
+/// Sent as `model-state-changed` whenever a model loads or unloads
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStateChange {
+    pub model_id: String,
+    pub state: ModelLoadState,
+    pub reason: String,
+}
+
+/// A model's current state and footprint
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelStatus {
+    pub model_id: String,
+    pub state: ModelLoadState,
+    /// Estimated from the model files
+    pub memory_mb: u64,
+    pub idle_seconds: u64,
+    pub in_use: bool,
+}
+
+/// Whether a model is ready to answer without first-call latency
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarmupState {
+    /// The inference engine has not been started
+    NotStarted,
+    /// Not downloaded
+    Missing,
+    /// On disk but not loaded; warms up on load
+    Cold,
+    WarmingUp,
+    Ready,
+    Failed,
+}
+
+/// Warm-up progress of a model, for showing when inference is ready
+#[derive(Debug, Clone, Serialize)]
+pub struct WarmupStatus {
+    pub model_id: String,
+    pub state: WarmupState,
+    /// Duration of the last warm-up inference
+    pub warmup_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Outcome of the last load and warm-up, written by the slot's loader
+#[derive(Debug, Default)]
+pub struct WarmupRecord {
+    pub warming_up: bool,
+    pub warmup_ms: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl WarmupRecord {
+    /// Status of a model given its slot state
+    pub fn status(&self, model: &ModelStatus) -> WarmupStatus {
+        let state = match model.state {
+            ModelLoadState::Loaded => WarmupState::Ready,
+            ModelLoadState::Missing => WarmupState::Missing,
+            ModelLoadState::Failed => WarmupState::Failed,
+            ModelLoadState::Unloaded if self.warming_up => WarmupState::WarmingUp,
+            ModelLoadState::Unloaded => WarmupState::Cold,
+        };
+        WarmupStatus {
+            model_id: model.model_id.clone(),
+            state,
+            warmup_ms: self.warmup_ms,
+            error: self.error.clone().filter(|_| state == WarmupState::Failed),
+        }
+    }
+}
+
+type Loader<M> = Arc<dyn Fn() -> Result<M, String> + Send + Sync>;
+
+struct SlotState<M> {
+    model: Option<Arc<Mutex<M>>>,
+    /// Size of the files the loaded model came from
+    memory_bytes: u64,
+    last_used: Instant,
+    failed: bool,
+}
+
+/// One model the engine can load on demand
+pub struct ModelSlot<M> {
+    id: String,
+    /// File or directory the model is loaded from; None when nothing is downloaded
+    source: Option<PathBuf>,
+    missing_error: &'static str,
+    loader: Loader<M>,
+    state: std::sync::Mutex<SlotState<M>>,
+    /// Held while loading so concurrent first uses load once
+    loading: Mutex<()>,
+    available: AtomicBool,
+    events: broadcast::Sender<ModelStateChange>,
+}
+
+impl<M: Send + 'static> ModelSlot<M> {
+    pub fn new(
+        id: &str,
+        source: Option<PathBuf>,
+        missing_error: &'static str,
+        events: broadcast::Sender<ModelStateChange>,
+        loader: impl Fn() -> Result<M, String> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            available: AtomicBool::new(source.as_deref().is_none_or(Path::exists)),
+            source,
+            missing_error,
+            loader: Arc::new(loader),
+            state: std::sync::Mutex::new(SlotState {
+                model: None,
+                memory_bytes: 0,
+                last_used: Instant::now(),
+                failed: false,
+            }),
+            loading: Mutex::new(()),
+            events,
+        }
+    }
+
+    /// Whether the model is loaded or can be loaded
+    pub fn is_available(&self) -> bool {
+        self.available.load(Ordering::SeqCst)
+    }
+
+    /// The model, loading it first if it was unloaded
+    pub async fn get(&self) -> Result<Arc<Mutex<M>>, String> {
+        if let Some(model) = self.touch() {
+            return Ok(model);
+        }
+        if !self.is_available() {
+            return Err(self.missing_error.to_string());
+        }
+
+        let _loading = self.loading.lock().await;
+        // Someone else may have loaded it while we waited
+        if let Some(model) = self.touch() {
+            return Ok(model);
+        }
+        self.load("first use").await?;
+        self.touch().ok_or_else(|| self.missing_error.to_string())
+    }
+
+    /// Load a fresh copy from disk and swap it in; unloads it if the files are gone
+    pub async fn reload(&self, reason: &str) {
+        let _loading = self.loading.lock().await;
+        self.lock().failed = false;
+        if let Some(source) = self.source.as_deref().filter(|source| !source.exists()) {
+            log::info!("Model {} not found at {:?}", self.id, source);
+            self.available.store(false, Ordering::SeqCst);
+            if self.lock().model.take().is_some() {
+                self.emit(ModelLoadState::Missing, "files removed");
+            }
+            return;
+        }
+        self.available.store(true, Ordering::SeqCst);
+        if let Err(e) = self.load(reason).await {
+            log::warn!("Failed to load model {}: {}", self.id, e);
+        }
+    }
+
+    /// Like `reload`, but a model that is not loaded only has its files checked
+    /// and loads on first use; for models too large to keep in memory unasked
+    pub async fn rescan(&self, reason: &str) {
+        if self.lock().model.is_some() {
+            return self.reload(reason).await;
+        }
+        let _loading = self.loading.lock().await;
+        let mut state = self.lock();
+        state.failed = false;
+        self.available
+            .store(self.source.as_deref().is_none_or(Path::exists), Ordering::SeqCst);
+    }
+
+    async fn load(&self, reason: &str) -> Result<(), String> {
+        let loader = self.loader.clone();
+        let loaded = tokio::task::spawn_blocking(move || loader())
+            .await
+            .map_err(|e| format!("Loading {} failed: {}", self.id, e))
+            .and_then(|result| result);
+
+        match loaded {
+            Ok(model) => {
+                let mut state = self.lock();
+                state.model = Some(Arc::new(Mutex::new(model)));
+                state.memory_bytes = self.source.as_deref().map(disk_size).unwrap_or(0);
+                state.last_used = Instant::now();
+                drop(state);
+                log::info!("Loaded model {} ({})", self.id, reason);
+                self.emit(ModelLoadState::Loaded, reason);
+                Ok(())
+            }
+            Err(e) => {
+                self.lock().failed = true;
+                self.available.store(false, Ordering::SeqCst);
+                self.emit(ModelLoadState::Failed, &e);
+                Err(e)
+            }
+        }
+    }
+
+    fn touch(&self) -> Option<Arc<Mutex<M>>> {
+        let mut state = self.lock();
+        state.last_used = Instant::now();
+        state.model.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SlotState<M>> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn emit(&self, state: ModelLoadState, reason: &str) {
+        let _ = self.events.send(ModelStateChange {
+            model_id: self.id.clone(),
+            state,
+            reason: reason.to_string(),
+        });
+    }
+}
+
+/// What the engine needs from a slot regardless of the model type
+pub trait ManagedModel: Send + Sync {
+    fn id(&self) -> &str;
+    fn status(&self) -> ModelStatus;
+    /// Drop the engine's copy; returns false if it was not loaded
+    fn unload(&self, reason: &str) -> bool;
+}
+
+impl<M: Send + 'static> ManagedModel for ModelSlot<M> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn status(&self) -> ModelStatus {
+        let state = self.lock();
+        let load_state = match (&state.model, state.failed) {
+            (Some(_), _) => ModelLoadState::Loaded,
+            (None, true) => ModelLoadState::Failed,
+            (None, false) if self.is_available() => ModelLoadState::Unloaded,
+            (None, false) => ModelLoadState::Missing,
+        };
+        ModelStatus {
+            model_id: self.id.clone(),
+            state: load_state,
+            memory_mb: state.memory_bytes / 1024 / 1024,
+            idle_seconds: state.last_used.elapsed().as_secs(),
+            // Calls in flight hold a clone of the model
+            in_use: state.model.as_ref().is_some_and(|model| Arc::strong_count(model) > 1),
+        }
+    }
+
+    fn unload(&self, reason: &str) -> bool {
+        if self.lock().model.take().is_none() {
+            return false;
+        }
+        log::info!("Unloaded model {} ({})", self.id, reason);
+        self.emit(ModelLoadState::Unloaded, reason);
+        true
+    }
+}
+
+/// Models to unload, least recently used first, to free `need_mb`.
+/// Only loaded models that are not in use and idle for `MIN_IDLE` qualify.
+pub fn plan_unloads(models: &[ModelStatus], need_mb: u64) -> Vec<String> {
+    let mut candidates: Vec<&ModelStatus> = models
+        .iter()
+        .filter(|m| m.state == ModelLoadState::Loaded && !m.in_use && m.idle_seconds >= MIN_IDLE.as_secs())
+        .collect();
+    candidates.sort_by_key(|m| std::cmp::Reverse(m.idle_seconds));
+
+    let mut freed = 0;
+    candidates
+        .into_iter()
+        .take_while(|m| {
+            let needed = freed < need_mb;
+            freed += m.memory_mb.max(1);
+            needed
+        })
+        .map(|m| m.model_id.clone())
+        .collect()
+}
+
+/// Megabytes to free: the larger of what the loaded models exceed CLA's RAM share
+/// by, and what the machine exceeds its RAM ceiling by
+pub fn memory_overrun_mb(
+    loaded_mb: u64,
+    ram_used_mb: u64,
+    ram_total_mb: u64,
+    max_ram_percent: u8,
+    max_system_ram_percent: u8,
+) -> u64 {
+    let budget_mb = ram_total_mb * max_ram_percent as u64 / 100;
+    let system_limit_mb = ram_total_mb * max_system_ram_percent as u64 / 100;
+    loaded_mb
+        .saturating_sub(budget_mb)
+        .max(ram_used_mb.saturating_sub(system_limit_mb))
+}
+
+/// Bytes of a model file, or of all files in a model directory
+fn disk_size(path: &Path) -> u64 {
+    match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| disk_size(&entry.path())).sum())
+            .unwrap_or(0),
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(id: &str, memory_mb: u64, idle_seconds: u64, in_use: bool) -> ModelStatus {
+        ModelStatus {
+            model_id: id.to_string(),
+            state: ModelLoadState::Loaded,
+            memory_mb,
+            idle_seconds,
+            in_use,
+        }
+    }
+
+    #[test]
+    fn test_plan_unloads_least_recently_used_first() {
+        let models = vec![
+            status("embedding", 90, 120, false),
+            status("whisper", 150, 600, false),
+            status("intent", 60, 10, false),
+            status("busy", 500, 900, true),
+        ];
+
+        assert!(plan_unloads(&models, 0).is_empty());
+        assert_eq!(plan_unloads(&models, 100), vec!["whisper"]);
+        assert_eq!(plan_unloads(&models, 200), vec!["whisper", "embedding"]);
+        // Recently used and busy models stay even when more is needed
+        assert_eq!(plan_unloads(&models, 10_000), vec!["whisper", "embedding"]);
+    }
+
+    #[test]
+    fn test_memory_overrun() {
+        // 16GB machine, 20% for CLA, 90% machine-wide
+        assert_eq!(memory_overrun_mb(1000, 8000, 16000, 20, 90), 0);
+        assert_eq!(memory_overrun_mb(4000, 8000, 16000, 20, 90), 800);
+        assert_eq!(memory_overrun_mb(1000, 15000, 16000, 20, 90), 600);
+    }
+
+    #[test]
+    fn test_warmup_status_follows_slot() {
+        let mut record = WarmupRecord {
+            warming_up: true,
+            ..Default::default()
+        };
+        let mut model = status("embedding", 90, 0, false);
+        model.state = ModelLoadState::Unloaded;
+        assert_eq!(record.status(&model).state, WarmupState::WarmingUp);
+
+        record.warming_up = false;
+        record.warmup_ms = Some(140);
+        model.state = ModelLoadState::Loaded;
+        let ready = record.status(&model);
+        assert_eq!((ready.state, ready.warmup_ms, ready.error), (WarmupState::Ready, Some(140), None));
+
+        model.state = ModelLoadState::Unloaded;
+        assert_eq!(record.status(&model).state, WarmupState::Cold);
+    }
+
+    #[tokio::test]
+    async fn test_slot_loads_again_after_unload() {
+        let (events, mut rx) = broadcast::channel(16);
+        let slot = ModelSlot::new("counter", None, "not loaded", events, || Ok(42u32));
+
+        assert_eq!(slot.status().state, ModelLoadState::Unloaded);
+        assert_eq!(*slot.get().await.unwrap().lock().await, 42);
+        assert_eq!(rx.try_recv().unwrap().state, ModelLoadState::Loaded);
+
+        assert!(slot.unload("test"));
+        assert!(!slot.unload("test"));
+        assert_eq!(rx.try_recv().unwrap().state, ModelLoadState::Unloaded);
+
+        let model = slot.get().await.unwrap();
+        assert!(slot.status().in_use);
+        drop(model);
+        assert!(!slot.status().in_use);
+    }
+
+    #[tokio::test]
+    async fn test_missing_model() {
+        let (events, _) = broadcast::channel(16);
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("gone.onnx");
+        let slot = ModelSlot::new("gone", Some(source), "not loaded", events, || Ok(()));
+        assert!(!slot.is_available());
+        assert_eq!(slot.status().state, ModelLoadState::Missing);
+        assert_eq!(slot.get().await.unwrap_err(), "not loaded");
+    }
+}
@@ -0,0 +1,333 @@
+// Local LLM - Text generation with Phi-3 Mini exported for ONNX Runtime GenAI
+// Files: phi-3-mini-4k/ with the decoder .onnx (+ .onnx.data), genai_config.json and tokenizer.json
+
+use ort::session::{builder::GraphOptimizationLevel, Session, SessionInputValue};
+use ort::value::{DynValue, Tensor};
+use crate::models::FinishReason;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::path::Path;
+
+/// Directory of the model inside the models directory
+pub const LLM_MODEL_DIR: &str = "phi-3-mini-4k";
+
+/// Sampling settings for one generation
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct GenerationParams {
+    pub max_tokens: u32,
+    /// 0.0 always picks the most likely token
+    pub temperature: f32,
+    /// Sample only from the most likely tokens covering this much probability
+    pub top_p: f32,
+}
+
+impl Default for GenerationParams {
+    fn default() -> Self {
+        Self {
+            max_tokens: 256,
+            temperature: 0.7,
+            top_p: 0.9,
+        }
+    }
+}
+
+/// Generated text and token counts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Generation {
+    pub text: String,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub finish_reason: FinishReason,
+}
+
+/// The parts of genai_config.json needed to drive the decoder
+#[derive(Debug, Deserialize)]
+struct GenaiConfig {
+    model: ModelConfig,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelConfig {
+    context_length: usize,
+    eos_token_id: TokenIds,
+    decoder: DecoderConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TokenIds {
+    One(u32),
+    Many(Vec<u32>),
+}
+
+#[derive(Debug, Deserialize)]
+struct DecoderConfig {
+    filename: String,
+    head_size: usize,
+    num_hidden_layers: usize,
+    num_key_value_heads: usize,
+}
+
+/// Phi-3 decoder with its key/value cache carried between steps
+pub struct LlmModel {
+    session: Session,
+    tokenizer: tokenizers::Tokenizer,
+    context_length: usize,
+    eos_tokens: Vec<u32>,
+    num_layers: usize,
+    kv_heads: usize,
+    head_size: usize,
+    /// Some exports also take explicit positions
+    needs_position_ids: bool,
+}
+
+impl LlmModel {
+    /// Load decoder, config and tokenizer from `dir`
+    pub fn load(dir: &Path, threads: usize) -> Result<Self, String> {
+        let config: GenaiConfig = std::fs::read_to_string(dir.join("genai_config.json"))
+            .map_err(|e| format!("Failed to read LLM config: {}", e))
+            .and_then(|text| serde_json::from_str(&text).map_err(|e| format!("Invalid LLM config: {}", e)))?;
+        let tokenizer = tokenizers::Tokenizer::from_file(dir.join("tokenizer.json"))
+            .map_err(|e| format!("Failed to load LLM tokenizer: {}", e))?;
+
+        let session = Session::builder()
+            .map_err(|e| format!("Failed to create session builder: {}", e))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| format!("Failed to set optimization level: {}", e))?
+            .with_intra_threads(threads.max(1))
+            .map_err(|e| format!("Failed to set thread count: {}", e))?
+            .commit_from_file(dir.join(&config.model.decoder.filename))
+            .map_err(|e| format!("Failed to load LLM: {}", e))?;
+        let needs_position_ids = session.inputs.iter().any(|input| input.name == "position_ids");
+
+        let decoder = config.model.decoder;
+        Ok(Self {
+            session,
+            tokenizer,
+            context_length: config.model.context_length,
+            eos_tokens: match config.model.eos_token_id {
+                TokenIds::One(id) => vec![id],
+                TokenIds::Many(ids) => ids,
+            },
+            num_layers: decoder.num_hidden_layers,
+            kv_heads: decoder.num_key_value_heads,
+            head_size: decoder.head_size,
+            needs_position_ids,
+        })
+    }
+
+    /// Answer `prompt` as the assistant. `on_token` gets each new piece of text
+    /// and returns false to stop early.
+    pub fn generate(
+        &mut self,
+        prompt: &str,
+        params: &GenerationParams,
+        mut on_token: impl FnMut(&str) -> bool,
+    ) -> Result<Generation, String> {
+        let prompt_ids: Vec<u32> = self
+            .tokenizer
+            .encode(chat_prompt(prompt), true)
+            .map_err(|e| format!("Failed to tokenize prompt: {}", e))?
+            .get_ids()
+            .to_vec();
+        if prompt_ids.len() >= self.context_length {
+            return Err(format!(
+                "Prompten er for lang ({} tokens, maks {})",
+                prompt_ids.len(),
+                self.context_length
+            ));
+        }
+
+        let mut rng = rand::thread_rng();
+        let mut past = self.empty_cache()?;
+        let mut step_ids = prompt_ids.clone();
+        let mut generated: Vec<u32> = Vec::new();
+        let mut text = String::new();
+        let mut finish_reason = FinishReason::Length;
+
+        while generated.len() < params.max_tokens as usize
+            && prompt_ids.len() + generated.len() < self.context_length
+        {
+            let total_len = prompt_ids.len() + generated.len();
+            let logits = self.step(&step_ids, total_len, &mut past)?;
+            let token = sample(&logits, params.temperature, params.top_p, rand::Rng::gen(&mut rng)) as u32;
+            if self.eos_tokens.contains(&token) {
+                finish_reason = FinishReason::Stop;
+                break;
+            }
+            generated.push(token);
+            step_ids = vec![token];
+
+            // Decode everything so far; pieces of a multi-byte character wait for the rest
+            let decoded = self
+                .tokenizer
+                .decode(&generated, true)
+                .map_err(|e| format!("Failed to decode tokens: {}", e))?;
+            if decoded.ends_with('\u{FFFD}') || !decoded.starts_with(&text) {
+                continue;
+            }
+            let piece = decoded[text.len()..].to_string();
+            text = decoded;
+            if !piece.is_empty() && !on_token(&piece) {
+                finish_reason = FinishReason::Cancelled;
+                break;
+            }
+        }
+
+        Ok(Generation {
+            text: text.trim().to_string(),
+            prompt_tokens: prompt_ids.len(),
+            completion_tokens: generated.len(),
+            finish_reason,
+        })
+    }
+
+    /// Run the decoder on the new tokens; returns the logits of the last one
+    /// and replaces `past` with the grown cache
+    fn step(&mut self, ids: &[u32], total_len: usize, past: &mut Vec<(String, DynValue)>) -> Result<Vec<f32>, String> {
+        let seq_len = ids.len();
+        let input_ids = Tensor::from_array(([1usize, seq_len], ids.iter().map(|&id| id as i64).collect::<Vec<_>>()))
+            .map_err(|e| format!("Failed to create input_ids tensor: {}", e))?;
+        let attention_mask = Tensor::from_array(([1usize, total_len], vec![1i64; total_len]))
+            .map_err(|e| format!("Failed to create attention_mask tensor: {}", e))?;
+
+        let mut inputs: Vec<(Cow<str>, SessionInputValue)> = vec![
+            ("input_ids".into(), input_ids.into()),
+            ("attention_mask".into(), attention_mask.into()),
+        ];
+        if self.needs_position_ids {
+            let positions: Vec<i64> = (total_len - seq_len..total_len).map(|p| p as i64).collect();
+            let position_ids = Tensor::from_array(([1usize, seq_len], positions))
+                .map_err(|e| format!("Failed to create position_ids tensor: {}", e))?;
+            inputs.push(("position_ids".into(), position_ids.into()));
+        }
+        for (name, value) in past.drain(..) {
+            inputs.push((name.into(), value.into()));
+        }
+
+        let mut outputs = self
+            .session
+            .run(inputs)
+            .map_err(|e| format!("LLM inference failed: {}", e))?;
+
+        let (shape, logits) = outputs
+            .get("logits")
+            .ok_or("Missing output: logits")?
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract logits: {}", e))?;
+        let vocab_size = *shape.last().ok_or("Logits have no vocabulary dimension")? as usize;
+        let last = logits
+            .get(logits.len() - vocab_size..)
+            .ok_or("Logits shorter than the vocabulary")?
+            .to_vec();
+
+        for layer in 0..self.num_layers {
+            for kind in ["key", "value"] {
+                let value = outputs
+                    .remove(format!("present.{}.{}", layer, kind))
+                    .ok_or_else(|| format!("Missing output: present.{}.{}", layer, kind))?;
+                past.push((format!("past_key_values.{}.{}", layer, kind), value));
+            }
+        }
+        Ok(last)
+    }
+
+    /// Key/value cache holding no tokens yet
+    fn empty_cache(&self) -> Result<Vec<(String, DynValue)>, String> {
+        let mut past = Vec::with_capacity(self.num_layers * 2);
+        for layer in 0..self.num_layers {
+            for kind in ["key", "value"] {
+                let empty = Tensor::from_array(([1usize, self.kv_heads, 0, self.head_size], Vec::<f32>::new()))
+                    .map_err(|e| format!("Failed to create cache tensor: {}", e))?;
+                past.push((format!("past_key_values.{}.{}", layer, kind), empty.into_dyn()));
+            }
+        }
+        Ok(past)
+    }
+}
+
+/// Phi-3 chat template for a single user turn
+fn chat_prompt(prompt: &str) -> String {
+    format!("<|user|>\n{}<|end|>\n<|assistant|>\n", prompt.trim())
+}
+
+/// Pick the next token from `logits`. `r` is uniform in [0, 1) and chooses
+/// among the `top_p` most likely tokens after temperature scaling.
+fn sample(logits: &[f32], temperature: f32, top_p: f32, r: f32) -> usize {
+    let argmax = logits
+        .iter()
+        .enumerate()
+        .fold(0, |best, (i, &v)| if v > logits[best] { i } else { best });
+    if temperature <= 0.0 || logits.is_empty() {
+        return argmax;
+    }
+
+    // Softmax with temperature, most likely first
+    let max = logits[argmax];
+    let mut probs: Vec<(usize, f32)> = logits
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| (i, ((v - max) / temperature).exp()))
+        .collect();
+    let sum: f32 = probs.iter().map(|(_, p)| p).sum();
+    probs.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    // Smallest set of tokens covering top_p
+    let mut cumulative = 0.0;
+    let mut nucleus = 0;
+    for (_, p) in &probs {
+        cumulative += p / sum;
+        nucleus += 1;
+        if cumulative >= top_p {
+            break;
+        }
+    }
+
+    let nucleus = &probs[..nucleus];
+    let nucleus_sum: f32 = nucleus.iter().map(|(_, p)| p).sum();
+    let mut target = r * nucleus_sum;
+    for &(i, p) in nucleus {
+        if target < p {
+            return i;
+        }
+        target -= p;
+    }
+    nucleus.last().map(|&(i, _)| i).unwrap_or(argmax)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_greedy_sampling() {
+        let logits = [0.1, 2.0, 1.5, -1.0];
+        assert_eq!(sample(&logits, 0.0, 0.9, 0.99), 1);
+        // A tiny nucleus only holds the best token
+        assert_eq!(sample(&logits, 1.0, 0.01, 0.99), 1);
+    }
+
+    #[test]
+    fn test_top_p_sampling() {
+        // Probabilities roughly 0.5, 0.3, 0.2 at temperature 1
+        let logits = [0.5f32.ln(), 0.3f32.ln(), 0.2f32.ln()];
+        assert_eq!(sample(&logits, 1.0, 1.0, 0.1), 0);
+        assert_eq!(sample(&logits, 1.0, 1.0, 0.6), 1);
+        assert_eq!(sample(&logits, 1.0, 1.0, 0.9), 2);
+        // With top_p 0.7 the least likely token is never drawn
+        assert_eq!(sample(&logits, 1.0, 0.7, 0.99), 1);
+    }
+
+    #[test]
+    fn test_genai_config() {
+        let config: GenaiConfig = serde_json::from_str(
+            r#"{"model": {"context_length": 4096, "eos_token_id": [32007, 32000],
+                "decoder": {"filename": "phi3.onnx", "head_size": 96, "num_hidden_layers": 32,
+                "num_key_value_heads": 32, "hidden_size": 3072}}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.model.decoder.num_hidden_layers, 32);
+        assert!(matches!(config.model.eos_token_id, TokenIds::Many(ref ids) if ids == &[32007, 32000]));
+        assert_eq!(chat_prompt(" Hej "), "<|user|>\nHej<|end|>\n<|assistant|>\n");
+    }
+}
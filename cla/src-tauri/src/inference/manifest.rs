@@ -0,0 +1,463 @@
+// Model Manifest - Which models exist, where to get them and what they must hash to
+// CKC publishes the manifest signed with the Cirkelline release key; downloads resume
+// from a .part file and a model is only installed once its size and SHA-256 match
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::telemetry::network::{self, NetworkSubsystem};
+
+/// Signed manifest as last fetched, inside the models directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Base64 Ed25519 key the manifest is signed with, set by the release build
+const MANIFEST_PUBLIC_KEY: Option<&str> = option_env!("CLA_MANIFEST_PUBLIC_KEY");
+
+// A release without the key could never verify a manifest, so it must not build
+#[cfg(not(debug_assertions))]
+const _: () = assert!(
+    matches!(MANIFEST_PUBLIC_KEY, Some(key) if !key.is_empty()),
+    "CLA_MANIFEST_PUBLIC_KEY must be set for release builds"
+);
+
+/// Set to 1 in a debug build to load models the manifest cannot vouch for
+const ALLOW_UNVERIFIED_ENV: &str = "CLA_ALLOW_UNVERIFIED_MODELS";
+
+/// One downloadable model
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManifestEntry {
+    pub id: String,
+    pub version: String,
+    pub download_url: String,
+    /// Hex SHA-256 of the complete file
+    pub checksum_sha256: String,
+    pub size_bytes: u64,
+    pub tier: u8,
+}
+
+/// All models CKC offers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelManifest {
+    pub models: Vec<ManifestEntry>,
+}
+
+impl ModelManifest {
+    pub fn entry(&self, model_id: &str) -> Option<&ManifestEntry> {
+        self.models.iter().find(|entry| entry.id == model_id)
+    }
+
+    /// The manifest saved by the last fetch, if it still verifies
+    pub fn load_cached(models_dir: &Path) -> Option<Self> {
+        let text = std::fs::read_to_string(models_dir.join(MANIFEST_FILE)).ok()?;
+        let key = publisher_key().ok()?;
+        match serde_json::from_str::<SignedManifest>(&text)
+            .map_err(|e| e.to_string())
+            .and_then(|signed| signed.verify(&key))
+        {
+            Ok(manifest) => Some(manifest),
+            Err(e) => {
+                log::warn!("Ignoring cached model manifest: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Fetch the manifest from CKC, verify it and cache it
+    pub async fn fetch(endpoint: &str, models_dir: &Path) -> Result<Self, String> {
+        let url = format!("{}/api/cla/models/available", endpoint.trim_end_matches('/'));
+        let response = reqwest::get(&url)
+            .await
+            .map_err(|e| format!("Kunne ikke hente modelmanifest: {}", e))?;
+        network::meter().record(NetworkSubsystem::ModelDownload, url.len() as u64, 0);
+        if !response.status().is_success() {
+            return Err(format!("Kunne ikke hente modelmanifest (status {})", response.status()));
+        }
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Kunne ikke hente modelmanifest: {}", e))?;
+        network::meter().record_received(NetworkSubsystem::ModelDownload, text.len() as u64);
+
+        let signed: SignedManifest = serde_json::from_str(&text)
+            .map_err(|e| format!("Ugyldigt modelmanifest: {}", e))?;
+        let manifest = signed.verify(&publisher_key()?)?;
+
+        std::fs::create_dir_all(models_dir)
+            .map_err(|e| format!("Kunne ikke oprette model-mappe: {}", e))?;
+        std::fs::write(models_dir.join(MANIFEST_FILE), &text)
+            .map_err(|e| format!("Kunne ikke gemme modelmanifest: {}", e))?;
+        Ok(manifest)
+    }
+}
+
+/// The manifest as served: the exact JSON that was signed, and its signature
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub manifest: String,
+    /// Base64 Ed25519 signature over the bytes of `manifest`
+    pub signature: String,
+}
+
+impl SignedManifest {
+    pub fn verify(&self, key: &VerifyingKey) -> Result<ModelManifest, String> {
+        let signature = BASE64
+            .decode(&self.signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or("Modelmanifestet har en ugyldig signatur")?;
+        key.verify_strict(self.manifest.as_bytes(), &signature)
+            .map_err(|_| "Modelmanifestets signatur kunne ikke verificeres".to_string())?;
+        serde_json::from_str(&self.manifest).map_err(|e| format!("Ugyldigt modelmanifest: {}", e))
+    }
+}
+
+fn publisher_key() -> Result<VerifyingKey, String> {
+    MANIFEST_PUBLIC_KEY
+        .and_then(|key| BASE64.decode(key).ok())
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        .ok_or_else(|| "Denne version kan ikke verificere modelmanifester".to_string())
+}
+
+/// Path of an installed model file
+pub fn model_path(models_dir: &Path, model_id: &str) -> PathBuf {
+    models_dir.join(format!("{}.onnx", model_id))
+}
+
+/// What an installed model is loaded from: its .onnx file, or the directory of
+/// the same name for models made of several files (Whisper, intent, LLM)
+fn model_source(models_dir: &Path, model_id: &str) -> PathBuf {
+    let file = model_path(models_dir, model_id);
+    if file.exists() {
+        file
+    } else {
+        models_dir.join(model_id)
+    }
+}
+
+/// Whether the developer override for unverified models is on
+fn unverified_allowed() -> bool {
+    cfg!(debug_assertions) && std::env::var(ALLOW_UNVERIFIED_ENV).is_ok_and(|value| value == "1")
+}
+
+/// Written next to a model once its hash matched, so it is not rehashed at every start
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct VerifiedStamp {
+    sha256: String,
+    size_bytes: u64,
+    modified_secs: u64,
+}
+
+impl VerifiedStamp {
+    fn path(models_dir: &Path, model_id: &str) -> PathBuf {
+        models_dir.join(format!("{}.verified.json", model_id))
+    }
+
+    /// Stamp for the file or directory as it is on disk now
+    fn for_file(path: &Path, sha256: &str) -> std::io::Result<Self> {
+        let (size_bytes, modified_secs) = disk_state(path)?;
+        Ok(Self {
+            sha256: sha256.to_string(),
+            size_bytes,
+            modified_secs,
+        })
+    }
+}
+
+/// Total size and latest modification of a file, or of every file in a directory
+fn disk_state(path: &Path) -> std::io::Result<(u64, u64)> {
+    let metadata = std::fs::metadata(path)?;
+    if !metadata.is_dir() {
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        return Ok((metadata.len(), modified));
+    }
+    let mut state = (0, 0);
+    for entry in std::fs::read_dir(path)? {
+        let (size, modified) = disk_state(&entry?.path())?;
+        state = (state.0 + size, state.1.max(modified));
+    }
+    Ok(state)
+}
+
+/// Check an installed model before it is loaded. It must be listed in a manifest
+/// signed with the release key and match its hash; without such a manifest the
+/// model is not loaded, unless CLA_ALLOW_UNVERIFIED_MODELS=1 in a debug build.
+pub fn verify_installed(models_dir: &Path, model_id: &str) -> Result<(), String> {
+    match ModelManifest::load_cached(models_dir) {
+        Some(manifest) => verify_against(models_dir, model_id, &manifest),
+        None if unverified_allowed() => {
+            log::warn!("No verified model manifest, loading {} unchecked ({})", model_id, ALLOW_UNVERIFIED_ENV);
+            Ok(())
+        }
+        None => Err(format!(
+            "Model {} kan ikke verificeres uden et signeret modelmanifest og indlæses ikke",
+            model_id
+        )),
+    }
+}
+
+fn verify_against(models_dir: &Path, model_id: &str, manifest: &ModelManifest) -> Result<(), String> {
+    let Some(entry) = manifest.entry(model_id) else {
+        if unverified_allowed() {
+            log::warn!("Model {} is not in the manifest, loading it unchecked ({})", model_id, ALLOW_UNVERIFIED_ENV);
+            return Ok(());
+        }
+        return Err(format!("Model {} findes ikke i modelmanifestet og indlæses ikke", model_id));
+    };
+
+    let path = model_source(models_dir, model_id);
+    let stamp_path = VerifiedStamp::path(models_dir, model_id);
+    let stamp: Option<VerifiedStamp> = std::fs::read_to_string(&stamp_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok());
+    let current = VerifiedStamp::for_file(&path, &entry.checksum_sha256)
+        .map_err(|e| format!("Kunne ikke læse model {}: {}", model_id, e))?;
+    if stamp.as_ref() == Some(&current) {
+        return Ok(());
+    }
+
+    // Changed since it was verified, or never verified
+    let sha256 = sha256_model(&path).map_err(|e| format!("Kunne ikke læse model {}: {}", model_id, e))?;
+    if !sha256.eq_ignore_ascii_case(&entry.checksum_sha256) {
+        let _ = std::fs::remove_file(&stamp_path);
+        return Err(format!("Model {} matcher ikke sin kontrolsum og indlæses ikke", model_id));
+    }
+    write_stamp(models_dir, model_id, &path, &entry.checksum_sha256);
+    Ok(())
+}
+
+fn write_stamp(models_dir: &Path, model_id: &str, path: &Path, sha256: &str) {
+    let written = VerifiedStamp::for_file(path, sha256)
+        .map_err(|e| e.to_string())
+        .and_then(|stamp| serde_json::to_string(&stamp).map_err(|e| e.to_string()))
+        .and_then(|json| {
+            std::fs::write(VerifiedStamp::path(models_dir, model_id), json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = written {
+        log::warn!("Could not record verification of {}: {}", model_id, e);
+    }
+}
+
+/// Hex SHA-256 of a model file. A model directory hashes to the SHA-256 of its
+/// files' `<relative path> <sha256>` lines, sorted by path.
+fn sha256_model(path: &Path) -> std::io::Result<String> {
+    if !path.is_dir() {
+        return sha256_file(path);
+    }
+    let mut files = Vec::new();
+    collect_files(path, path, &mut files)?;
+    files.sort();
+    let mut hasher = Sha256::new();
+    for relative in files {
+        let sha256 = sha256_file(&path.join(&relative))?;
+        hasher.update(format!("{} {}\n", relative, sha256).as_bytes());
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Paths of the files below `dir`, relative to `root` and with `/` separators
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<String> = relative.components().map(|c| c.as_os_str().to_string_lossy().into_owned()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+/// Hex SHA-256 of a file
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Download a model into `models_dir`, continuing an earlier partial download.
+/// `on_progress` gets the bytes on disk so far. The model is only moved into place
+/// once size and checksum match; a corrupt download is deleted.
+pub async fn download(
+    entry: &ManifestEntry,
+    models_dir: &Path,
+    mut on_progress: impl FnMut(u64),
+) -> Result<PathBuf, String> {
+    use futures_util::StreamExt;
+
+    std::fs::create_dir_all(models_dir)
+        .map_err(|e| format!("Kunne ikke oprette model-mappe: {}", e))?;
+    let part_path = models_dir.join(format!("{}.onnx.part", entry.id));
+
+    let mut offset = std::fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+    if offset > entry.size_bytes {
+        offset = 0;
+    }
+
+    if offset < entry.size_bytes {
+        let client = reqwest::Client::new();
+        let mut request = client.get(&entry.download_url);
+        if offset > 0 {
+            log::info!("Resuming download of {} at {} bytes", entry.id, offset);
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("Download fejlede: {}", e))?;
+        network::meter().record(NetworkSubsystem::ModelDownload, entry.download_url.len() as u64, 0);
+
+        // A server that ignores the range sends the whole file again
+        match response.status().as_u16() {
+            206 => {}
+            200 => offset = 0,
+            status => return Err(format!("Download fejlede med status {}", status)),
+        }
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(offset > 0)
+            .truncate(offset == 0)
+            .open(&part_path)
+            .map_err(|e| format!("Kunne ikke oprette fil: {}", e))?;
+
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("Download fejl: {}", e))?;
+            file.write_all(&chunk)
+                .map_err(|e| format!("Skrivefejl: {}", e))?;
+
+            offset += chunk.len() as u64;
+            network::meter().record_received(NetworkSubsystem::ModelDownload, chunk.len() as u64);
+            on_progress(offset);
+        }
+        file.flush().map_err(|e| format!("Skrivefejl: {}", e))?;
+    }
+
+    if offset != entry.size_bytes {
+        if offset > entry.size_bytes {
+            let _ = std::fs::remove_file(&part_path);
+        }
+        return Err(format!(
+            "Download af {} ufuldstændig ({} af {} bytes)",
+            entry.id, offset, entry.size_bytes
+        ));
+    }
+
+    let hash_path = part_path.clone();
+    let sha256 = tokio::task::spawn_blocking(move || sha256_file(&hash_path))
+        .await
+        .map_err(|e| format!("Kontrol af download fejlede: {}", e))?
+        .map_err(|e| format!("Kunne ikke læse download: {}", e))?;
+    if !sha256.eq_ignore_ascii_case(&entry.checksum_sha256) {
+        let _ = std::fs::remove_file(&part_path);
+        return Err(format!("Kontrolsum for {} passer ikke; download slettet", entry.id));
+    }
+
+    let path = model_path(models_dir, &entry.id);
+    std::fs::rename(&part_path, &path)
+        .map_err(|e| format!("Kunne ikke installere model: {}", e))?;
+    write_stamp(models_dir, &entry.id, &path, &entry.checksum_sha256);
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signed(manifest: &ModelManifest, key: &SigningKey) -> SignedManifest {
+        let manifest = serde_json::to_string(manifest).unwrap();
+        SignedManifest {
+            signature: BASE64.encode(key.sign(manifest.as_bytes()).to_bytes()),
+            manifest,
+        }
+    }
+
+    fn manifest(sha256: &str, size_bytes: u64) -> ModelManifest {
+        ModelManifest {
+            models: vec![ManifestEntry {
+                id: "tiny".to_string(),
+                version: "1.0.0".to_string(),
+                download_url: "https://example.com/tiny.onnx".to_string(),
+                checksum_sha256: sha256.to_string(),
+                size_bytes,
+                tier: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_signature_verification() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let mut signed = signed(&manifest("00", 1), &key);
+        assert_eq!(signed.verify(&key.verifying_key()).unwrap(), manifest("00", 1));
+
+        let other = SigningKey::from_bytes(&[8; 32]);
+        assert!(signed.verify(&other.verifying_key()).is_err());
+
+        signed.manifest = signed.manifest.replace("example.com", "evil.example");
+        assert!(signed.verify(&key.verifying_key()).is_err());
+    }
+
+    #[test]
+    fn test_installed_model_must_match_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(model_path(dir.path(), "tiny"), b"model bytes").unwrap();
+        let sha256 = hex::encode(Sha256::digest(b"model bytes"));
+
+        assert!(verify_against(dir.path(), "tiny", &manifest(&sha256, 11)).is_ok());
+        assert!(VerifiedStamp::path(dir.path(), "tiny").exists());
+
+        // Tampering changes the size, so the stamp no longer vouches for the file
+        std::fs::write(model_path(dir.path(), "tiny"), b"other model bytes").unwrap();
+        assert!(verify_against(dir.path(), "tiny", &manifest(&sha256, 11)).is_err());
+        assert!(!VerifiedStamp::path(dir.path(), "tiny").exists());
+
+        // Models the manifest does not list are not loaded
+        assert!(verify_against(dir.path(), "unlisted", &manifest(&sha256, 11)).is_err());
+    }
+
+    #[test]
+    fn test_model_directory_is_verified_file_by_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("tiny").join("tokenizer")).unwrap();
+        std::fs::write(dir.path().join("tiny").join("model.onnx"), b"weights").unwrap();
+        std::fs::write(dir.path().join("tiny").join("tokenizer").join("vocab.txt"), b"vocab").unwrap();
+        let expected = hex::encode(Sha256::digest(
+            format!(
+                "model.onnx {}\ntokenizer/vocab.txt {}\n",
+                hex::encode(Sha256::digest(b"weights")),
+                hex::encode(Sha256::digest(b"vocab"))
+            )
+            .as_bytes(),
+        ));
+
+        assert!(verify_against(dir.path(), "tiny", &manifest(&expected, 12)).is_ok());
+        std::fs::write(dir.path().join("tiny").join("tokenizer").join("vocab.txt"), b"tampered").unwrap();
+        assert!(verify_against(dir.path(), "tiny", &manifest(&expected, 12)).is_err());
+    }
+
+    #[test]
+    fn test_no_manifest_means_no_load() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(model_path(dir.path(), "tiny"), b"model bytes").unwrap();
+
+        assert!(verify_installed(dir.path(), "tiny").is_err());
+    }
+}
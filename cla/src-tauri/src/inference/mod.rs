@@ -1,140 +1,531 @@
 // AI Inference Engine for Cirkelline Local Agent
 // Uses ONNX Runtime for cross-platform inference
 
+mod benchmark;
+mod embedder;
 mod embedding;
+pub mod evaluation;
+mod intent;
+mod lifecycle;
+mod llm;
+pub mod manifest;
 mod whisper;
 mod ocr;
+mod quantize;
+mod remote;
+mod scheduler;
+mod summarize;
+mod vad;
 
+pub use benchmark::{run_benchmark, BenchmarkTask, HardwareProfile};
+pub use embedder::{cosine_similarity, embed_texts, shared_embedder, Embedder};
 pub use embedding::EmbeddingModel;
-pub use whisper::{WhisperModel, TranscriptionResult as TranscriptionOutput, TranscriptionSegment};
-pub use ocr::{OcrEngine, OcrResult as OcrOutput, TextRegion as OcrRegion};
+pub use intent::{IntentModel, INTENT_MODEL_DIR};
+pub use lifecycle::{ModelLoadState, ModelStateChange, ModelStatus, WarmupState, WarmupStatus};
+pub use llm::{Generation, GenerationParams, LlmModel, LLM_MODEL_DIR};
+pub use whisper::{resample as resample_audio, WhisperModel, WhisperTask, TranscriptionResult as TranscriptionOutput, TranscriptionSegment};
+pub use scheduler::{InferenceLane, InferenceScheduler, JobWork, LaneStats, QueueSnapshot};
+pub use remote::{backend_order, InferenceBackend, RemoteInferenceClient};
+pub use ocr::{OcrEngine, OcrResult as OcrOutput, TextRegion as OcrRegion, OCR_DETECTION_MODEL_ID};
+pub use quantize::{dequantize_int8, quantize_int8};
+pub use summarize::{summarize_transcript, ExtractiveSummarizer, MIN_SUMMARY_CHARS};
+pub use vad::{Vad, VadConfig, VadEvent};
 
+use crate::accessibility::IntentPrediction;
+use crate::models::{InferencePreference, Settings, SystemMetrics};
+use lifecycle::{memory_overrun_mb, plan_unloads, ManagedModel, ModelSlot, WarmupRecord};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{broadcast, Mutex};
+
+/// Where downloaded models are kept
+pub fn default_models_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("cirkelline-cla").join("models"))
+}
 
 /// Main inference engine managing all AI models
 pub struct InferenceEngine {
     models_dir: PathBuf,
-    embedding_model: Option<Arc<Mutex<EmbeddingModel>>>,
-    whisper_model: Option<Arc<Mutex<WhisperModel>>>,
-    ocr_engine: Option<Arc<Mutex<OcrEngine>>>,
+    embedding_model: ModelSlot<EmbeddingModel>,
+    /// Warm-up of the embedding model, run on every load
+    embedding_warmup: Arc<std::sync::Mutex<WarmupRecord>>,
+    whisper_model: ModelSlot<WhisperModel>,
+    /// Tier 2 model for languages other than English and for language identification
+    whisper_multilingual: ModelSlot<WhisperModel>,
+    ocr_engine: ModelSlot<OcrEngine>,
+    /// Language the OCR slot loads its recognizer for
+    ocr_language: Arc<std::sync::RwLock<String>>,
+    intent_model: ModelSlot<IntentModel>,
+    llm_model: ModelSlot<LlmModel>,
+    /// Where embeddings run; CloudOnly keeps the embedding model from loading
+    embedding_inference: std::sync::RwLock<InferencePreference>,
+    /// Where transcriptions run; CloudOnly keeps the Whisper models from loading
+    transcription_inference: std::sync::RwLock<InferencePreference>,
+    scheduler: Arc<InferenceScheduler>,
 }
 
 impl InferenceEngine {
-    /// Create a new inference engine
-    pub async fn new(models_dir: PathBuf) -> Result<Self, String> {
+    /// Create a new inference engine queueing requests on `scheduler` and
+    /// reporting loads and unloads on `events`. Models whose task is set to
+    /// CloudOnly in `settings` are not loaded.
+    pub async fn new(
+        models_dir: PathBuf,
+        scheduler: Arc<InferenceScheduler>,
+        events: broadcast::Sender<ModelStateChange>,
+        settings: &Settings,
+    ) -> Result<Self, String> {
         std::fs::create_dir_all(&models_dir)
             .map_err(|e| format!("Failed to create models directory: {}", e))?;
 
-        let mut engine = Self {
+        scheduler.set_profile(HardwareProfile::load(&HardwareProfile::path(&models_dir)));
+
+        let embedding_model_path = models_dir.join("all-minilm-l6-v2.onnx");
+        let whisper_dir = models_dir.join("whisper-tiny-en");
+        let whisper_multilingual_dir = models_dir.join("whisper-small");
+        let intent_dir = models_dir.join(INTENT_MODEL_DIR);
+        let llm_dir = models_dir.join(LLM_MODEL_DIR);
+        // Leave half the cores to the rest of the machine
+        let llm_threads = std::thread::available_parallelism().map(|n| n.get() / 2).unwrap_or(2);
+        let ocr_language = Arc::new(std::sync::RwLock::new(settings.ocr_language.clone()));
+        let embedding_warmup = Arc::new(std::sync::Mutex::new(WarmupRecord::default()));
+
+        let engine = Self {
+            embedding_model: ModelSlot::new(
+                "all-minilm-l6-v2",
+                Some(embedding_model_path.clone()),
+                "Embedding model not loaded. Download the model first.",
+                events.clone(),
+                {
+                    let models_dir = models_dir.clone();
+                    let warmup = embedding_warmup.clone();
+                    move || {
+                        warmup.lock().unwrap_or_else(|e| e.into_inner()).warming_up = true;
+                        let loaded = manifest::verify_installed(&models_dir, "all-minilm-l6-v2")
+                            .and_then(|_| EmbeddingModel::load(&embedding_model_path))
+                            .and_then(|mut model| Ok((model.warm_up()?, model)));
+
+                        let mut record = warmup.lock().unwrap_or_else(|e| e.into_inner());
+                        record.warming_up = false;
+                        match loaded {
+                            Ok((took, model)) => {
+                                log::info!("Embedding model warmed up in {} ms", took.as_millis());
+                                record.warmup_ms = Some(took.as_millis() as u64);
+                                record.error = None;
+                                Ok(model)
+                            }
+                            Err(e) => {
+                                record.error = Some(e.clone());
+                                Err(e)
+                            }
+                        }
+                    }
+                },
+            ),
+            embedding_warmup,
+            whisper_model: ModelSlot::new(
+                "whisper-tiny-en",
+                Some(whisper_dir.clone()),
+                "Whisper model not loaded. Download the model first.",
+                events.clone(),
+                {
+                    let models_dir = models_dir.clone();
+                    move || {
+                        manifest::verify_installed(&models_dir, "whisper-tiny-en")?;
+                        WhisperModel::load(&whisper_dir, "tiny-en")
+                    }
+                },
+            ),
+            whisper_multilingual: ModelSlot::new(
+                "whisper-small",
+                Some(whisper_multilingual_dir.clone()),
+                "Multilingual Whisper model not loaded. Download the model first.",
+                events.clone(),
+                {
+                    let models_dir = models_dir.clone();
+                    move || {
+                        manifest::verify_installed(&models_dir, "whisper-small")?;
+                        WhisperModel::load(&whisper_multilingual_dir, "small")
+                    }
+                },
+            ),
+            // Command parsing falls back to keyword rules without the intent classifier
+            intent_model: ModelSlot::new(
+                INTENT_MODEL_DIR,
+                Some(intent_dir.clone()),
+                "Intent model not loaded. Download the model first.",
+                events.clone(),
+                {
+                    let models_dir = models_dir.clone();
+                    move || {
+                        manifest::verify_installed(&models_dir, INTENT_MODEL_DIR)?;
+                        IntentModel::load(&intent_dir)
+                    }
+                },
+            ),
+            llm_model: ModelSlot::new(
+                LLM_MODEL_DIR,
+                Some(llm_dir.clone()),
+                "LLM not loaded. Download the model first.",
+                events.clone(),
+                {
+                    let models_dir = models_dir.clone();
+                    move || {
+                        manifest::verify_installed(&models_dir, LLM_MODEL_DIR)?;
+                        LlmModel::load(&llm_dir, llm_threads)
+                    }
+                },
+            ),
+            ocr_engine: ModelSlot::new(
+                OCR_DETECTION_MODEL_ID,
+                Some(models_dir.join(format!("{}.onnx", OCR_DETECTION_MODEL_ID))),
+                "OCR model not loaded. Download the model first.",
+                events,
+                {
+                    let models_dir = models_dir.clone();
+                    let ocr_language = ocr_language.clone();
+                    move || {
+                        let language = ocr_language.read().unwrap_or_else(|e| e.into_inner()).clone();
+                        let rec_id = OcrEngine::recognition_model_id(&language);
+                        manifest::verify_installed(&models_dir, OCR_DETECTION_MODEL_ID)?;
+                        manifest::verify_installed(&models_dir, rec_id)?;
+                        OcrEngine::load(&models_dir, &language)
+                    }
+                },
+            ),
+            ocr_language,
+            embedding_inference: std::sync::RwLock::new(settings.embedding_inference),
+            transcription_inference: std::sync::RwLock::new(settings.transcription_inference),
             models_dir,
-            embedding_model: None,
-            whisper_model: None,
-            ocr_engine: None,
+            scheduler,
         };
 
         // Try to load available models
-        engine.load_available_models().await?;
+        engine.load_available_models("startup").await;
 
         Ok(engine)
     }
 
-    /// Load all available models from disk
-    async fn load_available_models(&mut self) -> Result<(), String> {
-        // Load embedding model if available
-        let embedding_model_path = self.models_dir.join("all-minilm-l6-v2.onnx");
-
-        if embedding_model_path.exists() {
-            match EmbeddingModel::load(&embedding_model_path) {
-                Ok(model) => {
-                    log::info!("Loaded embedding model from {:?}", embedding_model_path);
-                    self.embedding_model = Some(Arc::new(Mutex::new(model)));
-                }
-                Err(e) => {
-                    log::warn!("Failed to load embedding model: {}", e);
-                }
-            }
+    /// Load all available models from disk, replacing loaded copies.
+    /// Models for CloudOnly tasks are only checked for on disk.
+    async fn load_available_models(&self, reason: &str) {
+        if self.cloud_only(&self.embedding_inference) {
+            self.embedding_model.unload("cloud only");
+            self.embedding_model.rescan(reason).await;
         } else {
-            log::info!("Embedding model not found at {:?}", embedding_model_path);
+            self.embedding_model.reload(reason).await;
         }
-
-        // Load Whisper model if available
-        let whisper_dir = self.models_dir.join("whisper-tiny-en");
-
-        if whisper_dir.exists() {
-            match WhisperModel::load(&whisper_dir, "tiny-en") {
-                Ok(model) => {
-                    log::info!("Loaded Whisper model from {:?}", whisper_dir);
-                    self.whisper_model = Some(Arc::new(Mutex::new(model)));
-                }
-                Err(e) => {
-                    log::warn!("Failed to load Whisper model: {}", e);
-                }
-            }
+        if self.cloud_only(&self.transcription_inference) {
+            self.whisper_model.unload("cloud only");
+            self.whisper_multilingual.unload("cloud only");
+            self.whisper_model.rescan(reason).await;
         } else {
-            log::info!("Whisper model not found at {:?}", whisper_dir);
+            self.whisper_model.reload(reason).await;
         }
+        self.intent_model.reload(reason).await;
+        self.ocr_engine.reload(reason).await;
+        // Large models only load when first needed
+        self.whisper_multilingual.rescan(reason).await;
+        self.llm_model.rescan(reason).await;
+    }
 
-        // Initialize OCR engine
-        match OcrEngine::new("eng") {
-            Ok(engine) => {
-                log::info!("Initialized OCR engine");
-                self.ocr_engine = Some(Arc::new(Mutex::new(engine)));
-            }
-            Err(e) => {
-                log::warn!("Failed to initialize OCR: {}", e);
+    /// Reload every model from disk, picking up new downloads and dropping deleted ones.
+    /// Calls in flight finish on the copy they started with.
+    pub async fn reload_models(&self) -> Vec<ModelStatus> {
+        self.load_available_models("reloaded").await;
+        self.model_statuses()
+    }
+
+    /// Switch where embeddings and transcriptions run. Models of tasks that
+    /// become CloudOnly are unloaded.
+    pub fn set_inference_preferences(&self, embedding: InferencePreference, transcription: InferencePreference) {
+        *self.embedding_inference.write().unwrap_or_else(|e| e.into_inner()) = embedding;
+        *self.transcription_inference.write().unwrap_or_else(|e| e.into_inner()) = transcription;
+        if embedding == InferencePreference::CloudOnly {
+            self.embedding_model.unload("cloud only");
+        }
+        if transcription == InferencePreference::CloudOnly {
+            self.whisper_model.unload("cloud only");
+            self.whisper_multilingual.unload("cloud only");
+        }
+    }
+
+    fn cloud_only(&self, preference: &std::sync::RwLock<InferencePreference>) -> bool {
+        *preference.read().unwrap_or_else(|e| e.into_inner()) == InferencePreference::CloudOnly
+    }
+
+    /// Unload a model; it loads again on next use
+    pub fn unload_model(&self, model_id: &str) -> Result<bool, String> {
+        self.managed_models()
+            .into_iter()
+            .find(|model| model.id() == model_id)
+            .map(|model| model.unload("unloaded by user"))
+            .ok_or_else(|| format!("Ukendt model: {}", model_id))
+    }
+
+    /// State and footprint of every model
+    pub fn model_statuses(&self) -> Vec<ModelStatus> {
+        self.managed_models().iter().map(|model| model.status()).collect()
+    }
+
+    /// Unload idle models, least recently used first, while the loaded models exceed
+    /// CLA's RAM share or the machine is over its RAM ceiling. Returns the unloaded ids.
+    pub fn enforce_memory_budget(&self, metrics: &SystemMetrics, settings: &Settings) -> Vec<String> {
+        let statuses = self.model_statuses();
+        let loaded_mb = statuses
+            .iter()
+            .filter(|status| status.state == ModelLoadState::Loaded)
+            .map(|status| status.memory_mb)
+            .sum();
+        let need_mb = memory_overrun_mb(
+            loaded_mb,
+            metrics.ram_used_mb,
+            metrics.ram_total_mb,
+            settings.max_ram_percent,
+            settings.max_system_ram_percent,
+        );
+        if need_mb == 0 {
+            return Vec::new();
+        }
+
+        let unloaded = plan_unloads(&statuses, need_mb);
+        for model in self.managed_models() {
+            if unloaded.iter().any(|id| id == model.id()) {
+                model.unload("memory pressure");
             }
         }
+        unloaded
+    }
 
-        Ok(())
+    fn managed_models(&self) -> [&dyn ManagedModel; 6] {
+        [
+            &self.embedding_model,
+            &self.whisper_model,
+            &self.whisper_multilingual,
+            &self.intent_model,
+            &self.ocr_engine,
+            &self.llm_model,
+        ]
     }
 
     /// Check if embedding model is available
     pub fn has_embedding_model(&self) -> bool {
-        self.embedding_model.is_some()
+        self.embedding_model.is_available()
+    }
+
+    /// Whether the embedding model is loaded and warmed up
+    pub fn embedding_warmup_status(&self) -> WarmupStatus {
+        self.embedding_warmup
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .status(&self.embedding_model.status())
     }
 
     /// Check if whisper model is available
     pub fn has_whisper_model(&self) -> bool {
-        self.whisper_model.is_some()
+        self.whisper_model.is_available() || self.whisper_multilingual.is_available()
+    }
+
+    /// Check if OCR engine is available
+    pub fn has_ocr_engine(&self) -> bool {
+        self.ocr_engine.is_available()
+    }
+
+    /// Recognize text in `language` from now on; the language pack loads on next use
+    pub async fn set_ocr_language(&self, language: &str) {
+        {
+            let mut current = self.ocr_language.write().unwrap_or_else(|e| e.into_inner());
+            if *current == language {
+                return;
+            }
+            *current = language.to_string();
+        }
+        self.ocr_engine.unload("OCR language changed");
+        self.ocr_engine.rescan("OCR language changed").await;
+    }
+
+    /// Check if intent model is available
+    pub fn has_intent_model(&self) -> bool {
+        self.intent_model.is_available()
+    }
+
+    /// Check if the local LLM is available
+    pub fn has_llm_model(&self) -> bool {
+        self.llm_model.is_available()
+    }
+
+    /// Hardware profile from the last benchmark
+    pub fn hardware_profile(&self) -> Option<HardwareProfile> {
+        self.scheduler.profile()
+    }
+
+    /// Use a new benchmark result for duration estimates
+    pub fn set_hardware_profile(&self, profile: HardwareProfile) {
+        self.scheduler.set_profile(Some(profile));
+    }
+
+    /// Expected duration of a task on this machine
+    pub fn estimate_duration(&self, task: BenchmarkTask, units: usize) -> Option<std::time::Duration> {
+        self.scheduler.estimate(task, units)
+    }
+
+    /// Lane occupancy of the inference scheduler
+    pub fn lane_stats(&self) -> LaneStats {
+        self.scheduler.stats()
     }
 
     /// Generate embedding for text
     pub async fn generate_embedding(&self, text: &str) -> Result<Vec<f32>, String> {
-        let model = self.embedding_model
-            .as_ref()
-            .ok_or("Embedding model not loaded. Download the model first.")?;
+        self.generate_embedding_in(InferenceLane::Interactive, text).await
+    }
+
+    /// Handle to the embedding model, loading it on first use
+    pub async fn embedder(&self) -> Result<Embedder, String> {
+        Ok(Embedder::new(self.embedding_model.get().await?, self.scheduler.clone()))
+    }
 
-        let mut model = model.lock().await;
-        // encode() is synchronous, no await needed
-        model.encode(text)
+    /// Generate embedding for text in a priority lane
+    pub async fn generate_embedding_in(&self, lane: InferenceLane, text: &str) -> Result<Vec<f32>, String> {
+        self.embedder().await?.embed_in(lane, text).await
     }
 
-    /// Transcribe audio file
+    /// Generate embeddings for many texts in a priority lane; see `Embedder::embed_many_in`
+    pub async fn generate_embeddings_in(
+        &self,
+        lane: InferenceLane,
+        texts: Vec<String>,
+    ) -> Result<Vec<(Vec<f32>, std::time::Duration)>, String> {
+        self.embedder().await?.embed_many_in(lane, texts).await
+    }
+
+    /// Transcribe or translate audio file
     pub async fn transcribe(
         &self,
         audio_path: &str,
         language: Option<&str>,
+        task: WhisperTask,
+    ) -> Result<TranscriptionOutput, String> {
+        self.transcribe_in(InferenceLane::Interactive, audio_path, language, task).await
+    }
+
+    /// Transcribe audio file in a priority lane
+    pub async fn transcribe_in(
+        &self,
+        lane: InferenceLane,
+        audio_path: &str,
+        language: Option<&str>,
+        task: WhisperTask,
     ) -> Result<TranscriptionOutput, String> {
-        let model = self.whisper_model
-            .as_ref()
-            .ok_or("Whisper model not loaded. Download the model first.")?;
+        let work = whisper::audio_seconds(audio_path).map(|units| JobWork {
+            task: BenchmarkTask::Transcription,
+            units,
+        });
+        let _permit = self.scheduler.acquire(lane, work).await;
+        let audio_path = audio_path.to_string();
+        let (model, language) = {
+            let audio_path = audio_path.clone();
+            self.whisper_for(language, task, move |model| model.identify_language(&audio_path)).await?
+        };
+
+        run_blocking(&model, move |model| model.transcribe(&audio_path, language.as_deref(), task)).await
+    }
+
+    /// Transcribe 16kHz mono samples, such as live microphone audio, in a priority lane
+    pub async fn transcribe_samples_in(
+        &self,
+        lane: InferenceLane,
+        samples: Vec<f32>,
+        language: Option<&str>,
+        task: WhisperTask,
+    ) -> Result<TranscriptionOutput, String> {
+        let work = JobWork {
+            task: BenchmarkTask::Transcription,
+            units: (samples.len() / 16000).max(1),
+        };
+        let _permit = self.scheduler.acquire(lane, Some(work)).await;
+        let (model, language) = {
+            let opening = samples[..samples.len().min(30 * 16000)].to_vec();
+            self.whisper_for(language, task, move |model| model.identify_language_samples(&opening)).await?
+        };
 
-        let mut model = model.lock().await;
-        // transcribe() is synchronous, no await needed
-        model.transcribe(audio_path, language)
+        run_blocking(&model, move |model| model.transcribe_samples(&samples, language.as_deref(), task)).await
+    }
+
+    /// Whisper model for a language, and the language to decode in. English is
+    /// transcribed by the English-only model, anything else by the multilingual one,
+    /// each falling back to the other. Without a language the multilingual model
+    /// identifies it with `identify` first.
+    async fn whisper_for(
+        &self,
+        language: Option<&str>,
+        task: WhisperTask,
+        identify: impl FnOnce(&mut WhisperModel) -> Result<String, String> + Send + 'static,
+    ) -> Result<(Arc<Mutex<WhisperModel>>, Option<String>), String> {
+        let language = match language {
+            Some(language) => Some(language.to_string()),
+            None if self.whisper_multilingual.is_available() => {
+                let model = self.whisper_multilingual.get().await?;
+                let detected = run_blocking(&model, identify).await?;
+                log::debug!("Detected spoken language: {}", detected);
+                Some(detected)
+            }
+            // The English-only model assumes English
+            None => None,
+        };
+
+        let english = language.as_deref().is_none_or(|l| l.starts_with("en")) && task == WhisperTask::Transcribe;
+        let (preferred, fallback) = if english {
+            (&self.whisper_model, &self.whisper_multilingual)
+        } else {
+            (&self.whisper_multilingual, &self.whisper_model)
+        };
+        let slot = if preferred.is_available() || !fallback.is_available() { preferred } else { fallback };
+        if !english && std::ptr::eq(slot, &self.whisper_model) {
+            log::warn!("No multilingual Whisper model; using the English-only model for {:?}", language);
+        }
+        Ok((slot.get().await?, language))
     }
 
     /// Extract text from image
     pub async fn extract_text(&self, image_path: &str) -> Result<OcrOutput, String> {
-        let engine = self.ocr_engine
-            .as_ref()
-            .ok_or("OCR engine not initialized")?;
+        let work = JobWork {
+            task: BenchmarkTask::Ocr,
+            units: 1,
+        };
+        let _permit = self.scheduler.acquire(InferenceLane::Interactive, Some(work)).await;
+        let engine = self.ocr_engine.get().await?;
 
-        let engine = engine.lock().await;
-        // extract() is synchronous, no await needed
-        engine.extract(image_path)
+        let image_path = image_path.to_string();
+        run_blocking(&engine, move |engine| engine.extract(&image_path)).await
+    }
+
+    /// Classify a voice command into one of the built-in intents in a priority lane
+    pub async fn classify_intent_in(
+        &self,
+        lane: InferenceLane,
+        text: &str,
+    ) -> Result<IntentPrediction, String> {
+        let _permit = self.scheduler.acquire(lane, None).await;
+        let model = self.intent_model.get().await?;
+
+        let text = text.to_string();
+        run_blocking(&model, move |model| model.classify(&text)).await
+    }
+
+    /// Generate an answer to `prompt` with the local LLM in a priority lane.
+    /// `on_token` runs on the inference thread for each new piece of text and
+    /// returns false to stop; it may block to pause generation.
+    pub async fn generate_text_in(
+        &self,
+        lane: InferenceLane,
+        prompt: &str,
+        params: GenerationParams,
+        on_token: impl FnMut(&str) -> bool + Send + 'static,
+    ) -> Result<Generation, String> {
+        let _permit = self.scheduler.acquire(lane, None).await;
+        let model = self.llm_model.get().await?;
+
+        let prompt = prompt.to_string();
+        run_blocking(&model, move |model| model.generate(&prompt, &params, on_token)).await
     }
 
     /// Get models directory path
@@ -142,3 +533,19 @@ impl InferenceEngine {
         &self.models_dir
     }
 }
+
+/// Run a synchronous model call on the blocking pool so callers can time it out.
+/// A call that is abandoned keeps the model locked until it finishes.
+async fn run_blocking<M, T>(
+    model: &Arc<Mutex<M>>,
+    call: impl FnOnce(&mut M) -> Result<T, String> + Send + 'static,
+) -> Result<T, String>
+where
+    M: Send + 'static,
+    T: Send + 'static,
+{
+    let mut model = model.clone().lock_owned().await;
+    tokio::task::spawn_blocking(move || call(&mut model))
+        .await
+        .map_err(|e| format!("Inference task failed: {}", e))?
+}
@@ -1,13 +1,37 @@
-// OCR implementation using Tesseract WASM or native bindings
-// Extracts text from images with bounding box information
+// OCR using ONNX text detection (DBNet) and recognition (CRNN) models in PaddleOCR's export format
+// Pages are binarized and deskewed before detection; regions come back in the original image's pixels
 
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
 use std::path::Path;
 
+/// Id of the text detection model in the model manifest (ocr-det.onnx), shared by all languages
+pub const OCR_DETECTION_MODEL_ID: &str = "ocr-det";
+
+/// Longest side detection runs at; larger pages are scaled down
+const DET_MAX_SIDE: usize = 960;
+/// Probability above which a detection pixel counts as text
+const DET_PIXEL_THRESHOLD: f32 = 0.3;
+/// Mean probability a detected region needs to be kept
+const DET_BOX_THRESHOLD: f32 = 0.6;
+/// DBNet shrinks text regions while training; boxes grow back by this ratio
+const DET_UNCLIP_RATIO: f32 = 1.5;
+/// Height text lines are scaled to for recognition
+const REC_HEIGHT: usize = 48;
+const REC_MAX_WIDTH: usize = 2048;
+/// Skew angles tried when deskewing, in degrees either way
+const MAX_SKEW_DEGREES: f32 = 10.0;
+const SKEW_STEP_DEGREES: f32 = 0.5;
+
+const IMAGENET_MEAN: [f32; 3] = [0.485, 0.456, 0.406];
+const IMAGENET_STD: [f32; 3] = [0.229, 0.224, 0.225];
+
 /// OCR engine for text extraction
 pub struct OcrEngine {
-    // In production: tesseract-rs or tesseract-wasm instance
-    initialized: bool,
-    language: String,
+    detector: Session,
+    recognizer: Session,
+    /// Characters by recognizer class; class 0 is the CTC blank
+    charset: Vec<String>,
 }
 
 /// OCR extraction result
@@ -27,63 +51,154 @@ pub struct TextRegion {
 }
 
 impl OcrEngine {
-    /// Initialize OCR engine
-    pub fn new(language: &str) -> Result<Self, String> {
-        // In production:
-        // - Download tessdata for the language if not present
-        // - Initialize Tesseract with the language model
+    /// Load the detection model and the recognition model for `language` from `models_dir`
+    pub fn load(models_dir: &Path, language: &str) -> Result<Self, String> {
+        if !Self::is_language_supported(language) {
+            return Err(format!("Unsupported OCR language: {}", language));
+        }
+        let rec_id = Self::recognition_model_id(language);
+        let rec_path = models_dir.join(format!("{}.onnx", rec_id));
+        if !rec_path.exists() {
+            return Err(format!("OCR language pack {} not downloaded", rec_id));
+        }
+
+        let detector = load_session(&models_dir.join(format!("{}.onnx", OCR_DETECTION_MODEL_ID)))?;
+        let recognizer = load_session(&rec_path)?;
+
+        // PaddleOCR exports carry their dictionary in the model metadata; older ones ship it alongside
+        let dictionary = recognizer
+            .metadata()
+            .ok()
+            .and_then(|metadata| metadata.custom("character").ok().flatten())
+            .map_or_else(|| std::fs::read_to_string(rec_path.with_extension("txt")), Ok)
+            .map_err(|e| format!("Failed to read OCR dictionary for {}: {}", rec_id, e))?;
 
         Ok(Self {
-            initialized: true,
-            language: language.to_string(),
+            detector,
+            recognizer,
+            charset: charset(&dictionary),
         })
     }
 
     /// Extract text from image file
-    pub fn extract(&self, image_path: &str) -> Result<OcrResult, String> {
-        if !self.initialized {
-            return Err("OCR engine not initialized".to_string());
-        }
-
+    pub fn extract(&mut self, image_path: &str) -> Result<OcrResult, String> {
         let path = Path::new(image_path);
         if !path.exists() {
             return Err(format!("Image not found: {}", image_path));
         }
 
-        // Load image
-        let image_data = load_image(path)?;
-
-        // Perform OCR
-        let ocr_result = self.perform_ocr(&image_data)?;
-
-        Ok(ocr_result)
-    }
-
-    fn perform_ocr(&self, image: &ImageData) -> Result<OcrResult, String> {
-        // Placeholder implementation
-        // In production, this would:
-        // 1. Preprocess image (deskew, denoise, binarize)
-        // 2. Run Tesseract OCR
-        // 3. Parse HOCR or TSV output for word bounding boxes
-
-        // Simulated result
-        Ok(OcrResult {
-            text: format!(
-                "[OCR result from {}x{} image using language: {}]",
-                image.width, image.height, self.language
-            ),
-            confidence: 0.85,
-            regions: vec![
-                TextRegion {
-                    text: "[Detected text region]".to_string(),
-                    x: 10.0,
-                    y: 10.0,
-                    width: image.width as f32 - 20.0,
-                    height: 50.0,
-                    confidence: 0.85,
-                },
-            ],
-        })
+        let image = load_image(path)?;
+        let page = preprocess(&image);
+
+        let mut regions = Vec::new();
+        for bounds in self.detect(&page.image)? {
+            let crop = page.image.crop(&bounds);
+            let (text, confidence) = self.recognize(&crop)?;
+            if text.trim().is_empty() {
+                continue;
+            }
+
+            // Boxes are axis-aligned on the deskewed page; map their centre back to the original
+            let (cx, cy) = page.to_original(
+                (bounds.left + bounds.right) as f32 / 2.0,
+                (bounds.top + bounds.bottom) as f32 / 2.0,
+            );
+            let (width, height) = ((bounds.right - bounds.left) as f32, (bounds.bottom - bounds.top) as f32);
+            regions.push(TextRegion {
+                text: text.trim().to_string(),
+                x: (cx - width / 2.0).max(0.0),
+                y: (cy - height / 2.0).max(0.0),
+                width,
+                height,
+                confidence,
+            });
+        }
+
+        Ok(assemble(regions))
+    }
+
+    /// Text regions on a preprocessed page, in page pixels
+    fn detect(&mut self, page: &GrayImage) -> Result<Vec<Bounds>, String> {
+        if page.width == 0 || page.height == 0 {
+            return Ok(Vec::new());
+        }
+
+        // DBNet needs sides divisible by 32
+        let scale = (DET_MAX_SIDE as f32 / page.width.max(page.height) as f32).min(1.0);
+        let det_width = ((page.width as f32 * scale / 32.0).round() as usize).max(1) * 32;
+        let det_height = ((page.height as f32 * scale / 32.0).round() as usize).max(1) * 32;
+        let resized = page.resize(det_width, det_height);
+
+        let plane = det_width * det_height;
+        let mut input = vec![0.0f32; 3 * plane];
+        for (i, &pixel) in resized.pixels.iter().enumerate() {
+            let value = pixel as f32 / 255.0;
+            for c in 0..3 {
+                input[c * plane + i] = (value - IMAGENET_MEAN[c]) / IMAGENET_STD[c];
+            }
+        }
+
+        let input = Tensor::from_array(([1usize, 3, det_height, det_width], input))
+            .map_err(|e| format!("Failed to create detection input tensor: {}", e))?;
+        let outputs = self
+            .detector
+            .run(ort::inputs![input])
+            .map_err(|e| format!("Text detection failed: {}", e))?;
+        let (_, probabilities) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract detection output: {}", e))?;
+        if probabilities.len() < plane {
+            return Err("Detection output smaller than its input".to_string());
+        }
+
+        let scale_x = page.width as f32 / det_width as f32;
+        let scale_y = page.height as f32 / det_height as f32;
+        Ok(find_text_boxes(&probabilities[..plane], det_width, det_height)
+            .into_iter()
+            .map(|b| Bounds {
+                left: ((b.left as f32 * scale_x) as usize).min(page.width),
+                top: ((b.top as f32 * scale_y) as usize).min(page.height),
+                right: ((b.right as f32 * scale_x).ceil() as usize).min(page.width),
+                bottom: ((b.bottom as f32 * scale_y).ceil() as usize).min(page.height),
+            })
+            .filter(|b| b.right > b.left && b.bottom > b.top)
+            .collect())
+    }
+
+    /// Text and confidence of one cropped line
+    fn recognize(&mut self, line: &GrayImage) -> Result<(String, f32), String> {
+        let width = (line.width * REC_HEIGHT / line.height.max(1)).clamp(8, REC_MAX_WIDTH);
+        let resized = line.resize(width, REC_HEIGHT);
+
+        let plane = width * REC_HEIGHT;
+        let mut input = vec![0.0f32; 3 * plane];
+        for (i, &pixel) in resized.pixels.iter().enumerate() {
+            let value = (pixel as f32 / 255.0 - 0.5) / 0.5;
+            for c in 0..3 {
+                input[c * plane + i] = value;
+            }
+        }
+
+        let input = Tensor::from_array(([1usize, 3, REC_HEIGHT, width], input))
+            .map_err(|e| format!("Failed to create recognition input tensor: {}", e))?;
+        let outputs = self
+            .recognizer
+            .run(ort::inputs![input])
+            .map_err(|e| format!("Text recognition failed: {}", e))?;
+        let (shape, probabilities) = outputs[0]
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract recognition output: {}", e))?;
+        let classes = *shape.last().ok_or("Recognition output has no shape")? as usize;
+
+        Ok(ctc_decode(probabilities, classes, &self.charset))
+    }
+
+    /// Recognition model covering a language; Latin-script languages share one
+    pub fn recognition_model_id(language: &str) -> &'static str {
+        match language {
+            "eng" => "ocr-rec-eng",
+            _ => "ocr-rec-latin",
+        }
     }
 
     /// Get supported languages
@@ -108,17 +223,342 @@ impl OcrEngine {
     }
 }
 
-/// Image data for OCR processing
-struct ImageData {
-    width: u32,
-    height: u32,
-    pixels: Vec<u8>, // Grayscale or RGB
-    channels: u8,
+fn load_session(path: &Path) -> Result<Session, String> {
+    Session::builder()
+        .map_err(|e| format!("Failed to create session builder: {}", e))?
+        .with_optimization_level(GraphOptimizationLevel::Level3)
+        .map_err(|e| format!("Failed to set optimization level: {}", e))?
+        .with_intra_threads(2)
+        .map_err(|e| format!("Failed to set thread count: {}", e))?
+        .commit_from_file(path)
+        .map_err(|e| format!("Failed to load OCR model {:?}: {}", path.file_name().unwrap_or_default(), e))
+}
+
+/// Recognizer classes from a dictionary with one character per line; class 0 is the
+/// CTC blank and the last class is a space
+fn charset(dictionary: &str) -> Vec<String> {
+    let mut charset = vec![String::new()];
+    charset.extend(dictionary.lines().map(|line| line.trim_end_matches('\r').to_string()));
+    charset.push(" ".to_string());
+    charset
+}
+
+/// Greedy CTC decoding: the best class per time step, dropping blanks and repeats.
+/// Confidence is the mean probability of the characters kept.
+fn ctc_decode(probabilities: &[f32], classes: usize, charset: &[String]) -> (String, f32) {
+    if classes == 0 {
+        return (String::new(), 0.0);
+    }
+
+    let mut text = String::new();
+    let mut scores = Vec::new();
+    let mut previous = 0;
+    for step in probabilities.chunks_exact(classes) {
+        let (class, &score) = step
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .unwrap_or((0, &0.0));
+        if class != 0 && class != previous {
+            if let Some(character) = charset.get(class) {
+                text.push_str(character);
+                scores.push(score);
+            }
+        }
+        previous = class;
+    }
+
+    let confidence = if scores.is_empty() { 0.0 } else { scores.iter().sum::<f32>() / scores.len() as f32 };
+    (text, confidence)
+}
+
+/// Pixel rectangle, right and bottom exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Bounds {
+    left: usize,
+    top: usize,
+    right: usize,
+    bottom: usize,
+}
+
+/// Boxes around connected text pixels of a detection probability map, grown back
+/// to the full text size and kept when confident enough
+fn find_text_boxes(probabilities: &[f32], width: usize, height: usize) -> Vec<Bounds> {
+    let mut visited = vec![false; width * height];
+    let mut boxes = Vec::new();
+    let mut stack = Vec::new();
+
+    for start in 0..width * height {
+        if visited[start] || probabilities[start] <= DET_PIXEL_THRESHOLD {
+            continue;
+        }
+
+        visited[start] = true;
+        stack.push(start);
+        let (mut left, mut top, mut right, mut bottom) = (width, height, 0, 0);
+        let (mut sum, mut count) = (0.0f32, 0usize);
+        while let Some(i) = stack.pop() {
+            let (x, y) = (i % width, i / width);
+            left = left.min(x);
+            right = right.max(x + 1);
+            top = top.min(y);
+            bottom = bottom.max(y + 1);
+            sum += probabilities[i];
+            count += 1;
+
+            let neighbours = [
+                (x > 0).then(|| i - 1),
+                (x + 1 < width).then(|| i + 1),
+                (y > 0).then(|| i - width),
+                (y + 1 < height).then(|| i + width),
+            ];
+            for n in neighbours.into_iter().flatten() {
+                if !visited[n] && probabilities[n] > DET_PIXEL_THRESHOLD {
+                    visited[n] = true;
+                    stack.push(n);
+                }
+            }
+        }
+
+        let (box_width, box_height) = (right - left, bottom - top);
+        if box_width.min(box_height) < 3 || sum / (count as f32) < DET_BOX_THRESHOLD {
+            continue;
+        }
+
+        let area = (box_width * box_height) as f32;
+        let grow = (area * DET_UNCLIP_RATIO / (2 * (box_width + box_height)) as f32).round() as usize;
+        boxes.push(Bounds {
+            left: left.saturating_sub(grow),
+            top: top.saturating_sub(grow),
+            right: (right + grow).min(width),
+            bottom: (bottom + grow).min(height),
+        });
+    }
+
+    boxes
+}
+
+/// Order regions into lines, top to bottom and left to right, and join their text
+fn assemble(mut regions: Vec<TextRegion>) -> OcrResult {
+    regions.sort_by(|a, b| (a.y + a.height / 2.0).total_cmp(&(b.y + b.height / 2.0)));
+
+    // A region joins the line above when its centre lies within that line's height
+    let mut lines: Vec<Vec<TextRegion>> = Vec::new();
+    for region in regions {
+        let centre = region.y + region.height / 2.0;
+        match lines.last_mut() {
+            Some(line) if line.iter().any(|r| centre >= r.y && centre <= r.y + r.height) => line.push(region),
+            _ => lines.push(vec![region]),
+        }
+    }
+    for line in &mut lines {
+        line.sort_by(|a, b| a.x.total_cmp(&b.x));
+    }
+
+    let text = lines
+        .iter()
+        .map(|line| line.iter().map(|r| r.text.as_str()).collect::<Vec<_>>().join(" "))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let regions: Vec<TextRegion> = lines.into_iter().flatten().collect();
+
+    // Longer regions weigh more in the page's confidence
+    let characters: usize = regions.iter().map(|r| r.text.chars().count()).sum();
+    let confidence = if characters == 0 {
+        0.0
+    } else {
+        regions.iter().map(|r| r.confidence * r.text.chars().count() as f32).sum::<f32>() / characters as f32
+    };
+
+    OcrResult { text, confidence, regions }
+}
+
+/// 8-bit grayscale image
+#[derive(Clone)]
+struct GrayImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl GrayImage {
+    fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Bilinear sample; outside the image is white
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        if x < 0.0 || y < 0.0 || x > (self.width - 1) as f32 || y > (self.height - 1) as f32 {
+            return 255.0;
+        }
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+        let top = self.get(x0, y0) as f32 * (1.0 - fx) + self.get(x1, y0) as f32 * fx;
+        let bottom = self.get(x0, y1) as f32 * (1.0 - fx) + self.get(x1, y1) as f32 * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    fn resize(&self, width: usize, height: usize) -> GrayImage {
+        let scale_x = self.width as f32 / width as f32;
+        let scale_y = self.height as f32 / height as f32;
+        let mut pixels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                let sx = ((x as f32 + 0.5) * scale_x - 0.5).clamp(0.0, (self.width - 1) as f32);
+                let sy = ((y as f32 + 0.5) * scale_y - 0.5).clamp(0.0, (self.height - 1) as f32);
+                pixels.push(self.sample(sx, sy).round() as u8);
+            }
+        }
+        GrayImage { width, height, pixels }
+    }
+
+    fn crop(&self, bounds: &Bounds) -> GrayImage {
+        let width = bounds.right - bounds.left;
+        let mut pixels = Vec::with_capacity(width * (bounds.bottom - bounds.top));
+        for y in bounds.top..bounds.bottom {
+            let row = y * self.width;
+            pixels.extend_from_slice(&self.pixels[row + bounds.left..row + bounds.right]);
+        }
+        GrayImage {
+            width,
+            height: bounds.bottom - bounds.top,
+            pixels,
+        }
+    }
+
+    /// Image whose content is this one turned by `-degrees` around the centre,
+    /// so text sloping by `degrees` comes out level
+    fn rotate(&self, degrees: f32) -> GrayImage {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let (cx, cy) = (self.width as f32 / 2.0, self.height as f32 / 2.0);
+        let mut pixels = Vec::with_capacity(self.pixels.len());
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (dx, dy) = (x as f32 - cx, y as f32 - cy);
+                pixels.push(self.sample(cx + dx * cos - dy * sin, cy + dx * sin + dy * cos).round() as u8);
+            }
+        }
+        GrayImage {
+            width: self.width,
+            height: self.height,
+            pixels,
+        }
+    }
+}
+
+/// A binarized, deskewed page and how to map its pixels back to the original
+struct Page {
+    image: GrayImage,
+    skew_degrees: f32,
+}
+
+impl Page {
+    fn to_original(&self, x: f32, y: f32) -> (f32, f32) {
+        let (sin, cos) = self.skew_degrees.to_radians().sin_cos();
+        let (cx, cy) = (self.image.width as f32 / 2.0, self.image.height as f32 / 2.0);
+        let (dx, dy) = (x - cx, y - cy);
+        (cx + dx * cos - dy * sin, cy + dx * sin + dy * cos)
+    }
 }
 
-/// Load image from file
-fn load_image(path: &Path) -> Result<ImageData, String> {
-    // In production, use image crate
+/// Level the text lines and reduce the page to black text on white
+fn preprocess(image: &GrayImage) -> Page {
+    let threshold = otsu_threshold(&image.pixels);
+    let skew_degrees = Some(estimate_skew(image, threshold)).filter(|d| d.abs() >= SKEW_STEP_DEGREES);
+    let levelled = skew_degrees.map_or_else(|| image.clone(), |degrees| image.rotate(degrees));
+
+    // Light text on a dark background is turned around, since the models expect dark ink
+    let ink_pixels = levelled.pixels.iter().filter(|&&p| p <= threshold).count();
+    let inverted = ink_pixels * 2 > levelled.pixels.len();
+    let pixels = levelled
+        .pixels
+        .iter()
+        .map(|&p| if (p <= threshold) != inverted { 0 } else { 255 })
+        .collect();
+
+    Page {
+        image: GrayImage {
+            width: levelled.width,
+            height: levelled.height,
+            pixels,
+        },
+        skew_degrees: skew_degrees.unwrap_or(0.0),
+    }
+}
+
+/// Threshold separating ink from background that maximizes the between-class variance
+fn otsu_threshold(pixels: &[u8]) -> u8 {
+    let mut histogram = [0u64; 256];
+    for &p in pixels {
+        histogram[p as usize] += 1;
+    }
+
+    let total = pixels.len() as f64;
+    let sum_all: f64 = histogram.iter().enumerate().map(|(v, &n)| v as f64 * n as f64).sum();
+    let (mut weight_below, mut sum_below) = (0.0, 0.0);
+    let (mut best, mut best_variance) = (127u8, 0.0);
+    for (value, &count) in histogram.iter().enumerate() {
+        weight_below += count as f64;
+        sum_below += value as f64 * count as f64;
+        let weight_above = total - weight_below;
+        if weight_below == 0.0 || weight_above == 0.0 {
+            continue;
+        }
+        let mean_below = sum_below / weight_below;
+        let mean_above = (sum_all - sum_below) / weight_above;
+        let variance = weight_below * weight_above * (mean_below - mean_above).powi(2);
+        if variance > best_variance {
+            best_variance = variance;
+            best = value as u8;
+        }
+    }
+    best
+}
+
+/// Slope of the text lines in degrees, positive when they fall to the right.
+/// Ink projected along the right slope piles up into sharp rows.
+fn estimate_skew(image: &GrayImage, threshold: u8) -> f32 {
+    // Sampling keeps large scans fast; lines span many samples either way
+    let step = (image.width.max(image.height) / 1000).max(1);
+    let mut ink = Vec::new();
+    for y in (0..image.height).step_by(step) {
+        for x in (0..image.width).step_by(step) {
+            if image.get(x, y) <= threshold {
+                ink.push((x as f32, y as f32));
+            }
+        }
+    }
+    // Mostly ink means light text on dark; the background is not worth projecting
+    if ink.is_empty() || ink.len() * 2 > (image.width / step) * (image.height / step) {
+        return 0.0;
+    }
+
+    let steps = (MAX_SKEW_DEGREES / SKEW_STEP_DEGREES) as i32;
+    let mut best = (0.0f32, 0u64);
+    let mut rows = Vec::new();
+    for i in -steps..=steps {
+        let degrees = i as f32 * SKEW_STEP_DEGREES;
+        let tan = degrees.to_radians().tan();
+        let offset = image.width as f32 * tan.abs();
+        rows.clear();
+        rows.resize(((image.height as f32 + 2.0 * offset) / step as f32) as usize + 2, 0);
+        for &(x, y) in &ink {
+            let row = ((y - x * tan + offset) / step as f32) as usize;
+            if let Some(count) = rows.get_mut(row) {
+                *count += 1;
+            }
+        }
+        let score = rows.iter().map(|&n| n * n).sum();
+        if score > best.1 || (score == best.1 && degrees.abs() < best.0.abs()) {
+            best = (degrees, score);
+        }
+    }
+    best.0
+}
+
+/// Load image from file as grayscale
+fn load_image(path: &Path) -> Result<GrayImage, String> {
     let data = std::fs::read(path)
         .map_err(|e| format!("Failed to read image: {}", e))?;
 
@@ -127,8 +567,8 @@ fn load_image(path: &Path) -> Result<ImageData, String> {
 
     match format {
         ImageFormat::Png => decode_png(&data),
-        ImageFormat::Jpeg => decode_jpeg(&data),
         ImageFormat::Bmp => decode_bmp(&data),
+        ImageFormat::Jpeg => Err("JPEG images are not supported yet; save the image as PNG".to_string()),
         _ => Err(format!("Unsupported image format: {:?}", format)),
     }
 }
@@ -164,77 +604,101 @@ fn detect_image_format(data: &[u8]) -> Result<ImageFormat, String> {
     Ok(ImageFormat::Unknown)
 }
 
-fn decode_png(data: &[u8]) -> Result<ImageData, String> {
-    // Simplified PNG parsing - in production use png crate
-    // Just extract dimensions from header
-
-    if data.len() < 24 {
-        return Err("Invalid PNG data".to_string());
-    }
-
-    // IHDR chunk starts at byte 8
-    let width = u32::from_be_bytes([data[16], data[17], data[18], data[19]]);
-    let height = u32::from_be_bytes([data[20], data[21], data[22], data[23]]);
-
-    Ok(ImageData {
-        width,
-        height,
-        pixels: vec![128u8; (width * height * 3) as usize], // Placeholder gray
-        channels: 3,
-    })
+/// ITU-R BT.601 luma
+fn luma(r: u8, g: u8, b: u8) -> u8 {
+    ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8
 }
 
-fn decode_jpeg(data: &[u8]) -> Result<ImageData, String> {
-    // Simplified JPEG parsing - in production use jpeg-decoder crate
-    // Extract dimensions from SOF0 marker
-
-    let mut i = 2;
-    while i < data.len() - 9 {
-        if data[i] == 0xFF {
-            let marker = data[i + 1];
-            // SOF0, SOF1, SOF2 (baseline, extended, progressive)
-            if marker == 0xC0 || marker == 0xC1 || marker == 0xC2 {
-                let height = u16::from_be_bytes([data[i + 5], data[i + 6]]) as u32;
-                let width = u16::from_be_bytes([data[i + 7], data[i + 8]]) as u32;
-
-                return Ok(ImageData {
-                    width,
-                    height,
-                    pixels: vec![128u8; (width * height * 3) as usize],
-                    channels: 3,
-                });
-            }
-
-            // Skip marker segment
-            if marker != 0x00 && marker != 0xFF && marker != 0xD0 && marker < 0xD8 {
-                let len = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
-                i += len + 2;
-                continue;
-            }
-        }
-        i += 1;
-    }
-
-    Err("Could not find image dimensions in JPEG".to_string())
+fn decode_png(data: &[u8]) -> Result<GrayImage, String> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder
+        .read_info()
+        .map_err(|e| format!("Invalid PNG data: {}", e))?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buffer)
+        .map_err(|e| format!("Failed to decode PNG: {}", e))?;
+    let bytes = &buffer[..info.buffer_size()];
+
+    // Transparent pixels are shown on white
+    let over_white = |value: u8, alpha: u8| ((value as u32 * alpha as u32 + 255 * (255 - alpha as u32)) / 255) as u8;
+    let pixels = match info.color_type {
+        png::ColorType::Grayscale => bytes.to_vec(),
+        png::ColorType::GrayscaleAlpha => bytes.chunks_exact(2).map(|p| over_white(p[0], p[1])).collect(),
+        png::ColorType::Rgb => bytes.chunks_exact(3).map(|p| luma(p[0], p[1], p[2])).collect(),
+        png::ColorType::Rgba => bytes.chunks_exact(4).map(|p| over_white(luma(p[0], p[1], p[2]), p[3])).collect(),
+        png::ColorType::Indexed => return Err("Failed to expand PNG palette".to_string()),
+    };
+
+    Ok(GrayImage {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    })
 }
 
-fn decode_bmp(data: &[u8]) -> Result<ImageData, String> {
+fn decode_bmp(data: &[u8]) -> Result<GrayImage, String> {
     // BMP header parsing
-    if data.len() < 26 {
+    if data.len() < 54 {
         return Err("Invalid BMP data".to_string());
     }
 
-    let width = u32::from_le_bytes([data[18], data[19], data[20], data[21]]);
+    let u16_at = |i: usize| u16::from_le_bytes([data[i], data[i + 1]]);
+    let u32_at = |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+
+    let pixel_offset = u32_at(10) as usize;
+    let header_size = u32_at(14) as usize;
+    let width = i32::from_le_bytes([data[18], data[19], data[20], data[21]]).unsigned_abs() as usize;
     // BMP height can be negative (top-down vs bottom-up), read as i32 and take abs
     let height_raw = i32::from_le_bytes([data[22], data[23], data[24], data[25]]);
-    let height = height_raw.unsigned_abs();
+    let height = height_raw.unsigned_abs() as usize;
+    let bits = u16_at(28);
+    let compression = u32_at(30);
 
-    Ok(ImageData {
-        width,
-        height,
-        pixels: vec![128u8; (width * height * 3) as usize],
-        channels: 3,
-    })
+    // BI_RGB, or BI_BITFIELDS with the usual masks for 32-bit images
+    if compression != 0 && !(compression == 3 && bits == 32) {
+        return Err("Compressed BMP images are not supported".to_string());
+    }
+
+    let palette: Vec<u8> = if bits <= 8 {
+        let colours = match u32_at(46) {
+            0 => 1usize << bits,
+            n => n as usize,
+        };
+        let start = 14 + header_size;
+        (0..colours)
+            .map(|i| start + i * 4)
+            .take_while(|&at| at + 3 <= data.len())
+            .map(|at| luma(data[at + 2], data[at + 1], data[at]))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let row_size = (width * bits as usize).div_ceil(32) * 4;
+    if data.len() < pixel_offset + row_size * height {
+        return Err("BMP pixel data truncated".to_string());
+    }
+
+    let mut pixels = Vec::with_capacity(width * height);
+    for y in 0..height {
+        // Bottom-up unless the height is negative
+        let source_row = if height_raw > 0 { height - 1 - y } else { y };
+        let row = &data[pixel_offset + source_row * row_size..][..row_size];
+        for x in 0..width {
+            let value = match bits {
+                32 => luma(row[x * 4 + 2], row[x * 4 + 1], row[x * 4]),
+                24 => luma(row[x * 3 + 2], row[x * 3 + 1], row[x * 3]),
+                8 => palette.get(row[x] as usize).copied().unwrap_or(0),
+                1 => palette.get(((row[x / 8] >> (7 - x % 8)) & 1) as usize).copied().unwrap_or(0),
+                _ => return Err(format!("Unsupported BMP bit depth: {}", bits)),
+            };
+            pixels.push(value);
+        }
+    }
+
+    Ok(GrayImage { width, height, pixels })
 }
 
 
@@ -242,6 +706,24 @@ fn decode_bmp(data: &[u8]) -> Result<ImageData, String> {
 mod tests {
     use super::*;
 
+    /// White page with dark horizontal bars standing in for text lines, sloping by `degrees`
+    fn page_with_lines(width: usize, height: usize, degrees: f32) -> GrayImage {
+        let tan = degrees.to_radians().tan();
+        let mut pixels = vec![255u8; width * height];
+        for line_top in (40..height - 40).step_by(60) {
+            for x in 30..width - 30 {
+                let shift = ((x as f32 - width as f32 / 2.0) * tan) as isize;
+                for dy in 0..12 {
+                    let y = line_top as isize + dy + shift;
+                    if (0..height as isize).contains(&y) {
+                        pixels[y as usize * width + x] = 20;
+                    }
+                }
+            }
+        }
+        GrayImage { width, height, pixels }
+    }
+
     #[test]
     fn test_detect_png() {
         let png_header = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
@@ -254,5 +736,97 @@ mod tests {
         assert!(OcrEngine::is_language_supported("eng"));
         assert!(OcrEngine::is_language_supported("dan"));
         assert!(!OcrEngine::is_language_supported("xyz"));
+        assert_eq!(OcrEngine::recognition_model_id("dan"), "ocr-rec-latin");
+    }
+
+    #[test]
+    fn test_decode_png_to_grayscale() {
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut encoded, 2, 1);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header().unwrap();
+            writer.write_image_data(&[255, 255, 255, 0, 0, 0]).unwrap();
+        }
+
+        let image = decode_png(&encoded).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.pixels, vec![255, 0]);
+    }
+
+    #[test]
+    fn test_otsu_separates_ink() {
+        let page = page_with_lines(200, 200, 0.0);
+        let threshold = otsu_threshold(&page.pixels);
+        assert!((20..255).contains(&threshold));
+    }
+
+    #[test]
+    fn test_estimate_and_correct_skew() {
+        let page = page_with_lines(400, 300, 3.0);
+        let threshold = otsu_threshold(&page.pixels);
+        let skew = estimate_skew(&page, threshold);
+        assert!((skew - 3.0).abs() <= SKEW_STEP_DEGREES, "skew {}", skew);
+
+        assert_eq!(estimate_skew(&page_with_lines(400, 300, 0.0), threshold), 0.0);
+    }
+
+    #[test]
+    fn test_find_text_boxes() {
+        let (width, height) = (64, 32);
+        let mut probabilities = vec![0.0f32; width * height];
+        for y in 10..16 {
+            for x in 8..40 {
+                probabilities[y * width + x] = 0.9;
+            }
+        }
+        // Too faint to count as text
+        for y in 24..28 {
+            for x in 8..20 {
+                probabilities[y * width + x] = 0.4;
+            }
+        }
+
+        let boxes = find_text_boxes(&probabilities, width, height);
+        assert_eq!(boxes.len(), 1);
+        // 32x6 grows by round(192 * 1.5 / 76) = 4 on every side
+        assert_eq!(boxes[0], Bounds { left: 4, top: 6, right: 44, bottom: 20 });
+    }
+
+    #[test]
+    fn test_ctc_decode_drops_blanks_and_repeats() {
+        let charset = charset("h\ne\nl\no");
+        // Classes: blank, h, e, l, o, space
+        let steps = [1, 1, 2, 0, 3, 3, 0, 3, 4, 5];
+        let mut probabilities = Vec::new();
+        for &class in &steps {
+            let mut step = vec![0.0f32; 6];
+            step[class] = 0.8;
+            probabilities.extend(step);
+        }
+
+        let (text, confidence) = ctc_decode(&probabilities, 6, &charset);
+        assert_eq!(text, "hello ");
+        assert!((confidence - 0.8).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_assemble_reading_order() {
+        let region = |text: &str, x: f32, y: f32| TextRegion {
+            text: text.to_string(),
+            x,
+            y,
+            width: 40.0,
+            height: 20.0,
+            confidence: 0.9,
+        };
+        let result = assemble(vec![
+            region("verden", 60.0, 12.0),
+            region("næste", 10.0, 50.0),
+            region("Hej", 10.0, 10.0),
+        ]);
+        assert_eq!(result.text, "Hej verden\nnæste");
+        assert!((result.confidence - 0.9).abs() < 1e-6);
     }
 }
@@ -0,0 +1,101 @@
+// Embedding Quantization - Compact int8 storage of embedding vectors
+// The blob layout must stay identical to cirkelline_native's quantize_embeddings
+
+/// Scheme byte of int8 blobs (0 is raw float32, only produced by the native module)
+const SCHEME_INT8: u8 = 1;
+
+/// Scheme byte + dimension (u32 LE) + vector count (u32 LE)
+const HEADER_LEN: usize = 9;
+
+/// Encode vectors of equal dimension as int8 with a per-vector scale.
+///
+/// Each vector is stored as its f32 LE scale (max |x| / 127) followed by one
+/// i8 per value, `round(x / scale)` clamped to [-127, 127].
+pub fn quantize_int8(vectors: &[Vec<f32>]) -> Result<Vec<u8>, String> {
+    let dim = vectors.first().map_or(0, Vec::len);
+    if vectors.iter().any(|v| v.len() != dim) {
+        return Err("All vectors must have the same dimension".to_string());
+    }
+
+    let mut blob = Vec::with_capacity(HEADER_LEN + vectors.len() * (4 + dim));
+    blob.push(SCHEME_INT8);
+    blob.extend_from_slice(&(dim as u32).to_le_bytes());
+    blob.extend_from_slice(&(vectors.len() as u32).to_le_bytes());
+
+    for vector in vectors {
+        let max_abs = vector.iter().fold(0.0f32, |max, x| max.max(x.abs()));
+        let scale = max_abs / 127.0;
+        blob.extend_from_slice(&scale.to_le_bytes());
+        for x in vector {
+            let q = if scale > 0.0 { (x / scale).round().clamp(-127.0, 127.0) } else { 0.0 };
+            blob.push(q as i8 as u8);
+        }
+    }
+    Ok(blob)
+}
+
+/// Decode a blob produced by `quantize_int8`
+pub fn dequantize_int8(blob: &[u8]) -> Result<Vec<Vec<f32>>, String> {
+    if blob.len() < HEADER_LEN {
+        return Err("Quantized blob is truncated".to_string());
+    }
+    if blob[0] != SCHEME_INT8 {
+        return Err(format!("Unsupported quantization scheme {}", blob[0]));
+    }
+    let dim = u32::from_le_bytes([blob[1], blob[2], blob[3], blob[4]]) as usize;
+    let count = u32::from_le_bytes([blob[5], blob[6], blob[7], blob[8]]) as usize;
+    let body = &blob[HEADER_LEN..];
+    if body.len() != count * (4 + dim) {
+        return Err(format!(
+            "Quantized blob has {} bytes, expected {} for {} vectors of {}",
+            body.len(),
+            count * (4 + dim),
+            count,
+            dim
+        ));
+    }
+
+    Ok(body
+        .chunks_exact(4 + dim)
+        .map(|record| {
+            let scale = f32::from_le_bytes([record[0], record[1], record[2], record[3]]);
+            record[4..].iter().map(|q| *q as i8 as f32 * scale).collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vectors shared with the Python native module tests
+    const VECTORS: &str = include_str!("../../../../cirkelline/native/test_vectors/embedding_quantization.json");
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_shared_vectors() {
+        let file: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+        let cases = file["int8"].as_array().unwrap();
+        assert!(!cases.is_empty());
+
+        for case in cases {
+            let vectors: Vec<Vec<f32>> = serde_json::from_value(case["vectors"].clone()).unwrap();
+            let blob = quantize_int8(&vectors).unwrap();
+            assert_eq!(hex(&blob), case["blob"].as_str().unwrap());
+
+            let decoded: Vec<Vec<f32>> = serde_json::from_value(case["decoded"].clone()).unwrap();
+            assert_eq!(dequantize_int8(&blob).unwrap(), decoded);
+        }
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(quantize_int8(&[vec![1.0], vec![1.0, 2.0]]).is_err());
+        let blob = quantize_int8(&[vec![0.5, -0.5]]).unwrap();
+        assert!(dequantize_int8(&blob[..blob.len() - 1]).is_err());
+        assert!(dequantize_int8(&[0; HEADER_LEN]).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Remote inference through CKC
+// Serves embeddings and transcriptions on machines too weak for local models
+
+use super::{TranscriptionOutput, TranscriptionSegment, WhisperTask};
+use crate::models::{InferencePreference, Settings};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde::Deserialize;
+use std::time::Duration;
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+
+/// Where an inference request runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferenceBackend {
+    Local,
+    Cloud,
+}
+
+/// Backends to try, in order, for a preference and what is available
+pub fn backend_order(
+    preference: InferencePreference,
+    local_available: bool,
+    cloud_available: bool,
+) -> Vec<InferenceBackend> {
+    let order: &[InferenceBackend] = match preference {
+        InferencePreference::PreferLocal => &[InferenceBackend::Local, InferenceBackend::Cloud],
+        InferencePreference::PreferCloud => &[InferenceBackend::Cloud, InferenceBackend::Local],
+        InferencePreference::LocalOnly => &[InferenceBackend::Local],
+        InferencePreference::CloudOnly => &[InferenceBackend::Cloud],
+    };
+
+    order
+        .iter()
+        .copied()
+        .filter(|backend| match backend {
+            InferenceBackend::Local => local_available,
+            InferenceBackend::Cloud => cloud_available,
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    embedding: Vec<f32>,
+    model: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TranscriptionResponse {
+    text: String,
+    #[serde(default)]
+    original_text: Option<String>,
+    language: Option<String>,
+    confidence: f32,
+    #[serde(default)]
+    segments: Vec<SegmentResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SegmentResponse {
+    start_ms: u64,
+    end_ms: u64,
+    text: String,
+    confidence: f32,
+}
+
+/// Client for CKC inference endpoints
+pub struct RemoteInferenceClient {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+}
+
+impl RemoteInferenceClient {
+    pub fn new(endpoint: &str, api_key: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Client for the configured CKC; None when offline or not authenticated
+    pub fn from_settings(settings: &Settings) -> Option<Self> {
+        if settings.offline_mode {
+            return None;
+        }
+        let endpoint = settings.ckc_endpoint.as_deref()?;
+        let api_key = settings.api_key.as_deref().filter(|k| !k.is_empty())?;
+        Some(Self::new(endpoint, api_key))
+    }
+
+    /// Generate an embedding; returns the vector and the model that produced it
+    pub async fn generate_embedding(&self, text: &str) -> Result<(Vec<f32>, String), String> {
+        let response: EmbeddingResponse = self
+            .post("embeddings", serde_json::json!({ "text": text }))
+            .await?;
+        Ok((response.embedding, response.model))
+    }
+
+    /// Transcribe an audio file, or translate it to English
+    pub async fn transcribe(
+        &self,
+        audio_path: &str,
+        language: Option<&str>,
+        task: WhisperTask,
+    ) -> Result<TranscriptionOutput, String> {
+        let audio = tokio::fs::read(audio_path)
+            .await
+            .map_err(|e| format!("Failed to read audio file: {}", e))?;
+
+        let format = std::path::Path::new(audio_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("wav")
+            .to_lowercase();
+
+        let response: TranscriptionResponse = self
+            .post(
+                "transcriptions",
+                serde_json::json!({
+                    "audio_base64": BASE64.encode(&audio),
+                    "format": format,
+                    "language": language,
+                    "task": task,
+                }),
+            )
+            .await?;
+
+        Ok(TranscriptionOutput {
+            text: response.text,
+            original_text: response.original_text,
+            detected_language: response.language,
+            confidence: response.confidence,
+            segments: response
+                .segments
+                .into_iter()
+                .map(|s| TranscriptionSegment {
+                    start_ms: s.start_ms,
+                    end_ms: s.end_ms,
+                    text: s.text,
+                    confidence: s.confidence,
+                })
+                .collect(),
+        })
+    }
+
+    async fn post<T: for<'de> Deserialize<'de>>(
+        &self,
+        task: &str,
+        body: serde_json::Value,
+    ) -> Result<T, String> {
+        let url = format!("{}/api/v1/inference/{}", self.endpoint, task);
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send_metered(NetworkSubsystem::Inference)
+            .await
+            .map_err(|e| format!("CKC inference request failed: {}", e))?;
+
+        match response.status().as_u16() {
+            401 | 403 => Err("CKC rejected the API key".to_string()),
+            status if !(200..300).contains(&status) => {
+                Err(format!("CKC inference failed with status {}", status))
+            }
+            _ => response
+                .json()
+                .await
+                .map_err(|e| format!("Invalid CKC inference response: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_order() {
+        use InferenceBackend::*;
+
+        assert_eq!(backend_order(InferencePreference::PreferLocal, true, true), vec![Local, Cloud]);
+        assert_eq!(backend_order(InferencePreference::PreferCloud, true, true), vec![Cloud, Local]);
+        assert_eq!(backend_order(InferencePreference::PreferLocal, false, true), vec![Cloud]);
+        assert_eq!(backend_order(InferencePreference::CloudOnly, true, false), vec![]);
+        assert_eq!(backend_order(InferencePreference::LocalOnly, true, true), vec![Local]);
+    }
+
+    #[test]
+    fn test_from_settings_requires_online_and_key() {
+        let mut settings = Settings::default();
+        assert!(RemoteInferenceClient::from_settings(&settings).is_none());
+
+        settings.api_key = Some("key".to_string());
+        assert!(RemoteInferenceClient::from_settings(&settings).is_some());
+
+        settings.offline_mode = true;
+        assert!(RemoteInferenceClient::from_settings(&settings).is_none());
+    }
+}
@@ -0,0 +1,444 @@
+// Inference Scheduler - Priority lanes for model execution
+// Accessibility requests go first and always have warm capacity reserved
+
+use super::benchmark::{BenchmarkTask, HardwareProfile};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Notify};
+
+/// Priority lane of an inference request (highest first)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InferenceLane {
+    /// Batch work (deep analysis, re-embedding)
+    Background,
+    /// User requests from the UI
+    #[default]
+    Interactive,
+    /// Voice interaction; never queues behind other work
+    Accessibility,
+}
+
+/// Current lane occupancy
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LaneStats {
+    pub capacity: usize,
+    pub reserved_for_accessibility: usize,
+    /// Running requests per lane: [background, interactive, accessibility]
+    pub running: [usize; 3],
+    /// Waiting requests per lane: [background, interactive, accessibility]
+    pub waiting: [usize; 3],
+}
+
+/// Work a request will do once admitted, used for ETAs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JobWork {
+    pub task: BenchmarkTask,
+    pub units: usize,
+}
+
+/// A request waiting for a slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub id: u64,
+    pub lane: InferenceLane,
+    pub task: Option<BenchmarkTask>,
+    /// Requests admitted before this one (0 = next)
+    pub position: usize,
+    /// Seconds until the request starts; None until the machine is benchmarked
+    /// or while anything ahead of it has no estimate
+    pub eta_seconds: Option<f32>,
+}
+
+/// Queue state, published whenever a request is queued, starts or finishes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueueSnapshot {
+    /// Waiting requests
+    pub depth: usize,
+    pub running: usize,
+    /// Waiting requests in admission order
+    pub jobs: Vec<QueuedJob>,
+}
+
+#[derive(Debug)]
+struct Job {
+    id: u64,
+    lane: InferenceLane,
+    work: Option<JobWork>,
+    /// Set once the job holds a slot
+    started: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+struct Lanes {
+    running: [usize; 3],
+    waiting: [usize; 3],
+    /// Queued and running jobs in submission order
+    jobs: Vec<Job>,
+    next_id: u64,
+}
+
+/// Admission control for inference slots
+#[derive(Debug)]
+pub struct InferenceScheduler {
+    capacity: usize,
+    reserved: usize,
+    lanes: Mutex<Lanes>,
+    notify: Notify,
+    /// Measured hardware performance used for duration estimates
+    profile: Mutex<Option<HardwareProfile>>,
+    queue_tx: broadcast::Sender<QueueSnapshot>,
+}
+
+impl InferenceScheduler {
+    /// `reserved` slots can only be used by the accessibility lane
+    pub fn new(capacity: usize, reserved: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (queue_tx, _) = broadcast::channel(32);
+        Self {
+            capacity,
+            reserved: reserved.min(capacity - 1),
+            lanes: Mutex::new(Lanes::default()),
+            notify: Notify::new(),
+            profile: Mutex::new(None),
+            queue_tx,
+        }
+    }
+
+    /// Wait for a slot in the given lane. `work` feeds the ETAs of this and later requests.
+    pub async fn acquire(self: &Arc<Self>, lane: InferenceLane, work: Option<JobWork>) -> LanePermit {
+        let index = lane as usize;
+        let id = {
+            let mut lanes = self.lock();
+            let id = lanes.next_id;
+            lanes.next_id += 1;
+            lanes.waiting[index] += 1;
+            lanes.jobs.push(Job {
+                id,
+                lane,
+                work,
+                started: None,
+            });
+            id
+        };
+        self.publish();
+
+        // Dropped if the caller gives up while queued
+        let mut guard = QueuedGuard {
+            scheduler: self,
+            id,
+            lane,
+            admitted: false,
+        };
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut lanes = self.lock();
+                if self.can_run(&lanes, lane) {
+                    lanes.waiting[index] -= 1;
+                    lanes.running[index] += 1;
+                    if let Some(job) = lanes.jobs.iter_mut().find(|j| j.id == id) {
+                        job.started = Some(Instant::now());
+                    }
+                    drop(lanes);
+                    guard.admitted = true;
+                    self.publish();
+                    return LanePermit {
+                        scheduler: self.clone(),
+                        lane,
+                        id,
+                    };
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Waiting requests with their position and ETA
+    pub fn queue(&self) -> QueueSnapshot {
+        let lanes = self.lock();
+        let profile = self.profile.lock().unwrap_or_else(|e| e.into_inner()).clone();
+        let estimate = |job: &Job| -> Option<f32> {
+            let work = job.work?;
+            Some(profile.as_ref()?.estimate(work.task, work.units)?.as_secs_f32())
+        };
+
+        // Remaining time of running jobs
+        let running: Vec<Option<f32>> = lanes
+            .jobs
+            .iter()
+            .filter_map(|job| {
+                let started = job.started?;
+                Some(estimate(job).map(|secs| (secs - started.elapsed().as_secs_f32()).max(0.0)))
+            })
+            .collect();
+
+        // Higher lanes first, first come first served within a lane
+        let mut waiting: Vec<&Job> = lanes.jobs.iter().filter(|job| job.started.is_none()).collect();
+        waiting.sort_by_key(|job| std::cmp::Reverse(job.lane));
+
+        let jobs = waiting
+            .iter()
+            .enumerate()
+            .map(|(position, job)| {
+                let ahead: Vec<Option<f32>> = waiting[..position].iter().map(|j| estimate(j)).collect();
+                QueuedJob {
+                    id: job.id,
+                    lane: job.lane,
+                    task: job.work.map(|w| w.task),
+                    position,
+                    eta_seconds: start_eta(&running, &ahead, self.slots(job.lane)),
+                }
+            })
+            .collect();
+
+        QueueSnapshot {
+            depth: waiting.len(),
+            running: running.len(),
+            jobs,
+        }
+    }
+
+    /// Queue snapshots as requests are queued, start and finish
+    pub fn subscribe(&self) -> broadcast::Receiver<QueueSnapshot> {
+        self.queue_tx.subscribe()
+    }
+
+    pub fn stats(&self) -> LaneStats {
+        let lanes = self.lock();
+        LaneStats {
+            capacity: self.capacity,
+            reserved_for_accessibility: self.reserved,
+            running: lanes.running,
+            waiting: lanes.waiting,
+        }
+    }
+
+    pub fn set_profile(&self, profile: Option<HardwareProfile>) {
+        *self.profile.lock().unwrap_or_else(|e| e.into_inner()) = profile;
+    }
+
+    pub fn profile(&self) -> Option<HardwareProfile> {
+        self.profile.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Expected run time of a task on this machine (None until benchmarked)
+    pub fn estimate(&self, task: BenchmarkTask, units: usize) -> Option<Duration> {
+        self.profile
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .as_ref()
+            .and_then(|p| p.estimate(task, units))
+    }
+
+    /// Slots a lane may use
+    fn slots(&self, lane: InferenceLane) -> usize {
+        match lane {
+            InferenceLane::Accessibility => self.capacity,
+            _ => self.capacity - self.reserved,
+        }
+    }
+
+    fn publish(&self) {
+        if self.queue_tx.receiver_count() > 0 {
+            let _ = self.queue_tx.send(self.queue());
+        }
+    }
+
+    /// Forget a job that left the queue or finished
+    fn remove_job(&self, id: u64) {
+        self.lock().jobs.retain(|job| job.id != id);
+        self.notify.notify_waiters();
+        self.publish();
+    }
+
+    fn can_run(&self, lanes: &Lanes, lane: InferenceLane) -> bool {
+        let running: usize = lanes.running.iter().sum();
+        // Higher lanes waiting go first
+        if lanes.waiting[lane as usize + 1..].iter().any(|&w| w > 0) {
+            return false;
+        }
+        running < self.slots(lane)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Lanes> {
+        self.lanes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for InferenceScheduler {
+    fn default() -> Self {
+        // One shared slot plus one kept warm for voice interaction
+        Self::new(2, 1)
+    }
+}
+
+/// Seconds until a request can start, simulating `slots` workers that first finish
+/// the `running` jobs and then the jobs `ahead` of it; None if any of those has no estimate
+fn start_eta(running: &[Option<f32>], ahead: &[Option<f32>], slots: usize) -> Option<f32> {
+    let mut remaining = running.iter().copied().collect::<Option<Vec<f32>>>()?;
+    remaining.sort_by(f32::total_cmp);
+    // A slot frees when enough running jobs finish to bring the count below `slots`
+    let mut free_at: Vec<f32> = vec![0.0; slots.saturating_sub(remaining.len())];
+    free_at.extend(remaining.iter().skip(remaining.len().saturating_sub(slots)));
+
+    for duration in ahead {
+        let duration = (*duration)?;
+        let next = free_at.iter_mut().min_by(|a, b| a.total_cmp(b))?;
+        *next += duration;
+    }
+    free_at.into_iter().min_by(f32::total_cmp)
+}
+
+/// Removes a job that is abandoned while still queued
+struct QueuedGuard<'a> {
+    scheduler: &'a InferenceScheduler,
+    id: u64,
+    lane: InferenceLane,
+    admitted: bool,
+}
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        if !self.admitted {
+            self.scheduler.lock().waiting[self.lane as usize] -= 1;
+            self.scheduler.remove_job(self.id);
+        }
+    }
+}
+
+/// Slot held while a request runs; released on drop
+#[derive(Debug)]
+pub struct LanePermit {
+    scheduler: Arc<InferenceScheduler>,
+    lane: InferenceLane,
+    id: u64,
+}
+
+impl Drop for LanePermit {
+    fn drop(&mut self) {
+        self.scheduler.lock().running[self.lane as usize] -= 1;
+        self.scheduler.remove_job(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_reserved_slot_only_for_accessibility() {
+        let scheduler = Arc::new(InferenceScheduler::new(2, 1));
+        let _background = scheduler.acquire(InferenceLane::Background, None).await;
+
+        // The shared slot is taken; a second background request must wait
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            scheduler.acquire(InferenceLane::Background, None),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        // Voice gets the warm slot immediately
+        let voice = tokio::time::timeout(
+            Duration::from_millis(50),
+            scheduler.acquire(InferenceLane::Accessibility, None),
+        )
+        .await;
+        assert!(voice.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_accessibility_jumps_the_queue() {
+        let scheduler = Arc::new(InferenceScheduler::new(1, 0));
+        let running = scheduler.acquire(InferenceLane::Background, None).await;
+
+        let background = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move {
+                let _permit = scheduler.acquire(InferenceLane::Background, None).await;
+                "background"
+            }
+        });
+        let voice = tokio::spawn({
+            let scheduler = scheduler.clone();
+            async move {
+                let permit = scheduler.acquire(InferenceLane::Accessibility, None).await;
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                drop(permit);
+                "voice"
+            }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert_eq!(scheduler.stats().waiting, [1, 0, 1]);
+        drop(running);
+
+        // Voice runs first; background only gets in after it finishes
+        assert_eq!(voice.await.unwrap(), "voice");
+        assert_eq!(background.await.unwrap(), "background");
+        assert_eq!(scheduler.stats().running, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_start_eta() {
+        // Two slots: one free now, one after the running job
+        assert_eq!(start_eta(&[Some(10.0)], &[], 2), Some(0.0));
+        assert_eq!(start_eta(&[Some(10.0)], &[Some(4.0)], 2), Some(4.0));
+        assert_eq!(start_eta(&[Some(10.0)], &[Some(4.0), Some(4.0)], 2), Some(8.0));
+        // More running than the lane may use: wait for both to finish
+        assert_eq!(start_eta(&[Some(3.0), Some(5.0)], &[], 1), Some(5.0));
+        assert_eq!(start_eta(&[None], &[], 1), None);
+    }
+
+    #[tokio::test]
+    async fn test_queue_positions_and_events() {
+        let scheduler = Arc::new(InferenceScheduler::new(1, 0));
+        scheduler.set_profile(Some(HardwareProfile {
+            cpu_cores: 4,
+            total_memory_mb: 8192,
+            embeddings_per_sec: None,
+            whisper_rtf: Some(0.5),
+            ocr_pages_per_min: None,
+            disk_write_mb_s: 100.0,
+            disk_read_mb_s: 100.0,
+            measured_at: chrono::Utc::now(),
+        }));
+        let mut events = scheduler.subscribe();
+        let transcription = |secs| Some(JobWork {
+            task: BenchmarkTask::Transcription,
+            units: secs,
+        });
+
+        let running = scheduler.acquire(InferenceLane::Background, transcription(60)).await;
+        let queued = |lane, work| {
+            let scheduler = scheduler.clone();
+            tokio::spawn(async move {
+                let _permit = scheduler.acquire(lane, work).await;
+            })
+        };
+        let background = queued(InferenceLane::Background, None);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let interactive = queued(InferenceLane::Interactive, transcription(20));
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        let queue = scheduler.queue();
+        assert_eq!((queue.depth, queue.running), (2, 1));
+        // The interactive request jumps ahead of the earlier background one
+        assert_eq!(queue.jobs[0].lane, InferenceLane::Interactive);
+        assert!((queue.jobs[0].eta_seconds.unwrap() - 30.0).abs() < 1.0);
+        assert_eq!(queue.jobs[1].position, 1);
+        assert!((queue.jobs[1].eta_seconds.unwrap() - 40.0).abs() < 1.0);
+
+        drop(running);
+        interactive.await.unwrap();
+        background.await.unwrap();
+        let mut last = QueueSnapshot::default();
+        while let Ok(snapshot) = events.try_recv() {
+            last = snapshot;
+        }
+        assert_eq!((last.depth, last.running), (0, 0));
+    }
+}
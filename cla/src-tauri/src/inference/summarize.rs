@@ -0,0 +1,203 @@
+// Transcript summarization - Map-reduce over long transcripts
+// Each chunk is summarized, the partial summaries are merged until they fit one chunk
+
+use crate::models::TranscriptSummary;
+use crate::research::deep_analysis::chunk_text;
+use std::collections::{HashMap, HashSet};
+
+/// Characters per map chunk; chunks end on sentence boundaries and do not overlap
+const CHUNK_CHARS: usize = 4000;
+
+/// Sentences kept per chunk by the extractive summarizer
+const SENTENCES_PER_CHUNK: usize = 3;
+
+/// Action items kept for a whole transcript
+const MAX_ACTION_ITEMS: usize = 10;
+
+/// Transcripts shorter than this are not worth summarizing
+pub const MIN_SUMMARY_CHARS: usize = 2000;
+
+/// Phrases that mark a sentence as something to do
+const ACTION_CUES: &[&str] = &[
+    "skal vi", "vi skal", "jeg skal", "du skal", "husk at", "husk ", "aftalt", "deadline",
+    "opfølgning", "følg op", "senest", "todo", "to do", "action item", "we need to", "need to",
+    "i will", "i'll", "we will", "we'll", "follow up", "remember to", "make sure", "by friday",
+];
+
+/// Summary of one chunk, or of the whole transcript
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PartialSummary {
+    pub summary: String,
+    pub action_items: Vec<String>,
+}
+
+/// Model that condenses one chunk of text
+pub trait ChunkSummarizer: Send + Sync {
+    fn model_id(&self) -> &str;
+    fn summarize_chunk(&self, text: &str) -> Result<PartialSummary, String>;
+}
+
+/// Picks the most representative sentences; used when no local LLM is available
+pub struct ExtractiveSummarizer {
+    sentences: usize,
+}
+
+impl ExtractiveSummarizer {
+    pub fn new() -> Self {
+        Self {
+            sentences: SENTENCES_PER_CHUNK,
+        }
+    }
+}
+
+impl Default for ExtractiveSummarizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChunkSummarizer for ExtractiveSummarizer {
+    fn model_id(&self) -> &str {
+        "extractive"
+    }
+
+    fn summarize_chunk(&self, text: &str) -> Result<PartialSummary, String> {
+        let sentences = split_sentences(text);
+        let frequencies = word_frequencies(&sentences);
+
+        // Score sentences by their average word frequency, keep the best in original order
+        let mut ranked: Vec<(usize, f32)> = sentences
+            .iter()
+            .enumerate()
+            .map(|(i, sentence)| {
+                let words = words(sentence);
+                let score = words.iter().map(|w| frequencies.get(w).copied().unwrap_or(0) as f32).sum::<f32>()
+                    / words.len().max(1) as f32;
+                (i, score)
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        let mut keep: Vec<usize> = ranked.into_iter().take(self.sentences).map(|(i, _)| i).collect();
+        keep.sort_unstable();
+
+        Ok(PartialSummary {
+            summary: keep.iter().map(|&i| sentences[i].as_str()).collect::<Vec<_>>().join(" "),
+            action_items: sentences.iter().filter(|s| is_action_item(s)).cloned().collect(),
+        })
+    }
+}
+
+/// Map: summarize each chunk. Reduce: merge the summaries, repeating until they fit one chunk.
+pub fn summarize_transcript(text: &str, summarizer: &dyn ChunkSummarizer) -> Result<TranscriptSummary, String> {
+    let chunks = chunk_text(text, CHUNK_CHARS, 0);
+    let mut partials = chunks
+        .iter()
+        .map(|chunk| summarizer.summarize_chunk(chunk))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut seen = HashSet::new();
+    let action_items: Vec<String> = partials
+        .iter()
+        .flat_map(|p| p.action_items.iter())
+        .filter(|item| seen.insert(item.to_lowercase()))
+        .take(MAX_ACTION_ITEMS)
+        .cloned()
+        .collect();
+
+    while partials.len() > 1 {
+        let merged = partials.iter().map(|p| p.summary.as_str()).collect::<Vec<_>>().join(" ");
+        let next = chunk_text(&merged, CHUNK_CHARS, 0)
+            .iter()
+            .map(|chunk| summarizer.summarize_chunk(chunk))
+            .collect::<Result<Vec<_>, _>>()?;
+        // A summarizer that cannot shrink its input would loop forever
+        if next.len() >= partials.len() {
+            partials = vec![summarizer.summarize_chunk(&merged)?];
+            break;
+        }
+        partials = next;
+    }
+
+    Ok(TranscriptSummary {
+        abstract_text: partials.pop().map(|p| p.summary).unwrap_or_default(),
+        action_items,
+        model: summarizer.model_id().to_string(),
+        chunks: chunks.len(),
+        memory_id: None,
+    })
+}
+
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        current.push(c);
+        if matches!(c, '.' | '!' | '?' | '\n') {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+fn words(sentence: &str) -> Vec<String> {
+    sentence
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 3)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn word_frequencies(sentences: &[String]) -> HashMap<String, usize> {
+    let mut frequencies = HashMap::new();
+    for word in sentences.iter().flat_map(|s| words(s)) {
+        *frequencies.entry(word).or_insert(0) += 1;
+    }
+    frequencies
+}
+
+fn is_action_item(sentence: &str) -> bool {
+    let lower = sentence.to_lowercase();
+    ACTION_CUES.iter().any(|cue| lower.contains(cue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extractive_summary_and_action_items() {
+        let summary = ExtractiveSummarizer::new()
+            .summarize_chunk(
+                "Budgettet for projektet blev gennemgået. Budgettet for projektet er stramt. \
+                 Vejret var fint. Husk at sende budgettet til Anna senest fredag.",
+            )
+            .unwrap();
+        assert!(summary.summary.starts_with("Budgettet for projektet blev gennemgået."));
+        assert!(!summary.summary.contains("Vejret"));
+        assert_eq!(summary.action_items, vec!["Husk at sende budgettet til Anna senest fredag."]);
+    }
+
+    #[test]
+    fn test_long_transcript_is_reduced() {
+        let paragraph = "The migration plan covers the database and the sync service. \
+                         Most of the meeting discussed the database migration plan. \
+                         We need to test the rollback before release. Lunch was late. ";
+        let transcript = paragraph.repeat(200);
+        let summary = summarize_transcript(&transcript, &ExtractiveSummarizer::new()).unwrap();
+
+        assert!(summary.chunks > 1);
+        assert!(summary.abstract_text.chars().count() <= CHUNK_CHARS);
+        assert!(summary.abstract_text.contains("migration plan"));
+        // Repeated action items are listed once
+        assert_eq!(summary.action_items, vec!["We need to test the rollback before release."]);
+        assert_eq!(summary.model, "extractive");
+    }
+}
@@ -0,0 +1,359 @@
+// Voice Activity Detection - Where speech starts and stops in microphone audio
+// Silero VAD (silero-vad.onnx) when it is downloaded, otherwise an adaptive energy
+// detector; both score 32ms frames that a hangover state machine turns into speech
+
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
+use std::path::Path;
+
+/// Id of the Silero model in the model manifest (silero-vad.onnx)
+const VAD_MODEL_ID: &str = "silero-vad";
+
+const SAMPLE_RATE: usize = 16000;
+
+/// Silero v5 scores 512-sample frames at 16kHz
+pub const FRAME_SAMPLES: usize = 512;
+
+/// Samples of the previous frame Silero v5 sees in front of each frame
+const CONTEXT_SAMPLES: usize = 64;
+
+/// Size of Silero's recurrent state (2 x 1 x 128)
+const STATE_SIZE: usize = 2 * 128;
+
+/// When frames count as speech and how long a pause ends it
+#[derive(Debug, Clone)]
+pub struct VadConfig {
+    /// Speech probability that starts speech; it ends 0.15 below this
+    pub threshold: f32,
+    /// Speech this long before it counts, so clicks and coughs are ignored
+    pub min_speech_ms: u32,
+    /// Pause that ends speech
+    pub min_silence_ms: u32,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.5,
+            min_speech_ms: 96,
+            min_silence_ms: 640,
+        }
+    }
+}
+
+impl VadConfig {
+    /// Config for a detector sensitivity from 0.0 (only clear speech) to 1.0
+    pub fn with_sensitivity(sensitivity: f32) -> Self {
+        Self {
+            threshold: 0.8 - 0.6 * sensitivity.clamp(0.0, 1.0),
+            ..Self::default()
+        }
+    }
+}
+
+/// Start or end of speech, in milliseconds since the detector started
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    SpeechStart { at_ms: u64 },
+    SpeechEnd { at_ms: u64 },
+}
+
+/// Silero VAD v5 ONNX model
+pub struct SileroVad {
+    session: Session,
+    state: Vec<f32>,
+    context: Vec<f32>,
+}
+
+impl SileroVad {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let session = Session::builder()
+            .map_err(|e| format!("Failed to create session builder: {}", e))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|e| format!("Failed to set optimization level: {}", e))?
+            .with_intra_threads(1)
+            .map_err(|e| format!("Failed to set thread count: {}", e))?
+            .commit_from_file(path)
+            .map_err(|e| format!("Failed to load VAD model: {}", e))?;
+
+        Ok(Self {
+            session,
+            state: vec![0.0; STATE_SIZE],
+            context: vec![0.0; CONTEXT_SAMPLES],
+        })
+    }
+
+    /// Speech probability of one `FRAME_SAMPLES` frame
+    fn speech_probability(&mut self, frame: &[f32]) -> Result<f32, String> {
+        let mut input = Vec::with_capacity(CONTEXT_SAMPLES + frame.len());
+        input.extend_from_slice(&self.context);
+        input.extend_from_slice(frame);
+        let input_len = input.len();
+
+        let input = Tensor::from_array(([1usize, input_len], input))
+            .map_err(|e| format!("Failed to create VAD input tensor: {}", e))?;
+        let state = Tensor::from_array(([2usize, 1, 128], self.state.clone()))
+            .map_err(|e| format!("Failed to create VAD state tensor: {}", e))?;
+        let sample_rate = Tensor::from_array(((), vec![SAMPLE_RATE as i64]))
+            .map_err(|e| format!("Failed to create VAD sample rate tensor: {}", e))?;
+
+        let outputs = self
+            .session
+            .run(ort::inputs!["input" => input, "state" => state, "sr" => sample_rate])
+            .map_err(|e| format!("VAD inference failed: {}", e))?;
+        let (_, probability) = outputs
+            .get("output")
+            .ok_or("Missing output: output")?
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract VAD output: {}", e))?;
+        let (_, state) = outputs
+            .get("stateN")
+            .ok_or("Missing output: stateN")?
+            .try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract VAD state: {}", e))?;
+
+        self.state.copy_from_slice(&state[..STATE_SIZE]);
+        self.context.copy_from_slice(&frame[frame.len() - CONTEXT_SAMPLES..]);
+        probability.first().copied().ok_or_else(|| "Empty VAD output".to_string())
+    }
+
+    fn reset(&mut self) {
+        self.state.fill(0.0);
+        self.context.fill(0.0);
+    }
+}
+
+/// Speech score from frame energy above an adaptive noise floor
+struct EnergyVad {
+    noise_floor_db: f32,
+}
+
+impl EnergyVad {
+    const INITIAL_FLOOR_DB: f32 = -60.0;
+    /// Anything quieter is silence whatever the noise floor
+    const ABSOLUTE_FLOOR_DB: f32 = -55.0;
+
+    fn new() -> Self {
+        Self {
+            noise_floor_db: Self::INITIAL_FLOOR_DB,
+        }
+    }
+
+    fn speech_probability(&mut self, frame: &[f32]) -> f32 {
+        let rms = (frame.iter().map(|s| s * s).sum::<f32>() / frame.len().max(1) as f32).sqrt();
+        let db = if rms > 0.0 { 20.0 * rms.log10() } else { -96.0 };
+
+        // Follow the floor down at once and up slowly, so speech does not raise it
+        if db < self.noise_floor_db {
+            self.noise_floor_db = db.max(-96.0);
+        } else {
+            self.noise_floor_db += (db - self.noise_floor_db) * 0.01;
+        }
+        if db < Self::ABSOLUTE_FLOOR_DB {
+            return 0.0;
+        }
+        // 6 dB above the floor is doubtful, 18 dB above is certain
+        ((db - self.noise_floor_db - 6.0) / 12.0).clamp(0.0, 1.0)
+    }
+
+    fn reset(&mut self) {
+        self.noise_floor_db = Self::INITIAL_FLOOR_DB;
+    }
+}
+
+enum Backend {
+    Silero(Box<SileroVad>),
+    Energy(EnergyVad),
+}
+
+/// Voice activity detector for a stream of 16kHz mono audio
+pub struct Vad {
+    backend: Backend,
+    config: VadConfig,
+    /// Samples waiting for a full frame
+    pending: Vec<f32>,
+    frames: u64,
+    speaking: bool,
+    /// Consecutive frames on the other side of the threshold
+    run: u32,
+}
+
+impl Vad {
+    /// Silero from `models_dir` if it is there, the energy detector otherwise
+    pub fn load(models_dir: Option<&Path>, config: VadConfig) -> Self {
+        let path = models_dir.map(|dir| (dir, super::manifest::model_path(dir, VAD_MODEL_ID)));
+        let backend = match path.filter(|(_, path)| path.exists()).map(|(dir, path)| {
+            super::manifest::verify_installed(dir, VAD_MODEL_ID).and_then(|_| SileroVad::load(&path))
+        }) {
+            Some(Ok(model)) => Backend::Silero(Box::new(model)),
+            Some(Err(e)) => {
+                log::warn!("Falling back to energy VAD: {}", e);
+                Backend::Energy(EnergyVad::new())
+            }
+            None => Backend::Energy(EnergyVad::new()),
+        };
+        Self::with_backend(backend, config)
+    }
+
+    fn with_backend(backend: Backend, config: VadConfig) -> Self {
+        Self {
+            backend,
+            config,
+            pending: Vec::with_capacity(FRAME_SAMPLES),
+            frames: 0,
+            speaking: false,
+            run: 0,
+        }
+    }
+
+    /// Feed samples; returns speech starts and ends completed by them
+    pub fn push(&mut self, samples: &[f32]) -> Vec<VadEvent> {
+        let mut events = Vec::new();
+        self.pending.extend_from_slice(samples);
+        let mut offset = 0;
+        while self.pending.len() - offset >= FRAME_SAMPLES {
+            let frame: Vec<f32> = self.pending[offset..offset + FRAME_SAMPLES].to_vec();
+            offset += FRAME_SAMPLES;
+            let probability = self.score(&frame);
+            if let Some(event) = self.update(probability) {
+                events.push(event);
+            }
+        }
+        self.pending.drain(..offset);
+        events
+    }
+
+    /// Feed 16-bit samples
+    pub fn push_i16(&mut self, samples: &[i16]) -> Vec<VadEvent> {
+        let samples: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        self.push(&samples)
+    }
+
+    /// Whether speech is going on right now
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Forget all audio seen so far
+    pub fn reset(&mut self) {
+        match &mut self.backend {
+            Backend::Silero(model) => model.reset(),
+            Backend::Energy(energy) => energy.reset(),
+        }
+        self.pending.clear();
+        self.frames = 0;
+        self.speaking = false;
+        self.run = 0;
+    }
+
+    fn score(&mut self, frame: &[f32]) -> f32 {
+        match &mut self.backend {
+            Backend::Silero(model) => match model.speech_probability(frame) {
+                Ok(probability) => probability,
+                Err(e) => {
+                    log::warn!("Silero VAD failed, using energy VAD: {}", e);
+                    let mut energy = EnergyVad::new();
+                    let probability = energy.speech_probability(frame);
+                    self.backend = Backend::Energy(energy);
+                    probability
+                }
+            },
+            Backend::Energy(energy) => energy.speech_probability(frame),
+        }
+    }
+
+    /// Advance the speech state by one frame
+    fn update(&mut self, probability: f32) -> Option<VadEvent> {
+        self.frames += 1;
+        let crossed = if self.speaking {
+            probability < self.config.threshold - 0.15
+        } else {
+            probability >= self.config.threshold
+        };
+        if !crossed {
+            self.run = 0;
+            return None;
+        }
+
+        self.run += 1;
+        let needed_ms = if self.speaking { self.config.min_silence_ms } else { self.config.min_speech_ms };
+        if frames_to_ms(self.run as u64) < needed_ms as u64 {
+            return None;
+        }
+
+        // The change happened where the run began
+        let at_ms = frames_to_ms(self.frames - self.run as u64);
+        self.speaking = !self.speaking;
+        self.run = 0;
+        Some(if self.speaking {
+            VadEvent::SpeechStart { at_ms }
+        } else {
+            VadEvent::SpeechEnd { at_ms }
+        })
+    }
+}
+
+fn frames_to_ms(frames: u64) -> u64 {
+    frames * FRAME_SAMPLES as u64 * 1000 / SAMPLE_RATE as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(ms: usize, amplitude: f32) -> Vec<f32> {
+        (0..ms * SAMPLE_RATE / 1000)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 220.0 * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect()
+    }
+
+    /// Quiet deterministic noise, like a room
+    fn noise(ms: usize) -> Vec<f32> {
+        let mut seed = 12345u32;
+        (0..ms * SAMPLE_RATE / 1000)
+            .map(|_| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                ((seed >> 16) as f32 / 32768.0 - 1.0) * 0.003
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_speech_between_pauses() {
+        let mut vad = Vad::load(None, VadConfig::default());
+        assert!(vad.push(&noise(1000)).is_empty());
+
+        let events = vad.push(&tone(1000, 0.3));
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], VadEvent::SpeechStart { at_ms } if (990..=1030).contains(&at_ms)));
+        assert!(vad.is_speaking());
+
+        // A short breath does not end the speech
+        assert!(vad.push(&noise(300)).is_empty());
+        assert!(vad.push(&tone(500, 0.3)).is_empty());
+
+        let events = vad.push(&noise(1000));
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], VadEvent::SpeechEnd { at_ms } if (2790..=2830).contains(&at_ms)));
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_clicks_are_not_speech() {
+        let mut vad = Vad::load(None, VadConfig::default());
+        let mut audio = noise(500);
+        audio.extend(tone(40, 0.5));
+        audio.extend(noise(500));
+        assert!(vad.push_i16(&audio.iter().map(|s| (s * 32767.0) as i16).collect::<Vec<_>>()).is_empty());
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_silence_is_not_speech() {
+        let mut vad = Vad::load(None, VadConfig::with_sensitivity(1.0));
+        assert!(vad.push_i16(&[0; 16000]).is_empty());
+        let quiet: Vec<i16> = noise(1000).iter().map(|s| (s * 32767.0) as i16).collect();
+        assert!(vad.push_i16(&quiet).is_empty());
+        assert!(!vad.is_speaking());
+    }
+}
@@ -2,8 +2,10 @@
 // Model: whisper-tiny.en (39MB) or whisper-small (466MB)
 
 use std::path::Path;
+use std::sync::OnceLock;
 use ort::session::{Session, builder::GraphOptimizationLevel};
 use ort::value::Tensor;
+use realfft::RealFftPlanner;
 
 /// Audio transcription using Whisper
 pub struct WhisperModel {
@@ -11,11 +13,54 @@ pub struct WhisperModel {
     decoder: Session,
     model_id: String,
     sample_rate: u32,
+    /// English-only models (`.en`) cannot identify or transcribe other languages
+    multilingual: bool,
+    /// Width of the encoder output: 384 for tiny, 768 for small
+    encoder_hidden_dim: usize,
+}
+
+/// Special tokens of the multilingual vocabulary
+const TOKEN_END_OF_TEXT: u32 = 50257;
+const TOKEN_START_OF_TRANSCRIPT: u32 = 50258;
+const TOKEN_FIRST_LANGUAGE: u32 = 50259;
+
+/// Language codes in vocabulary order; `<|en|>` is 50259, `<|zh|>` 50260, ...
+const LANGUAGES: [&str; 99] = [
+    "en", "zh", "de", "es", "ru", "ko", "fr", "ja", "pt", "tr", "pl", "ca", "nl", "ar", "sv", "it",
+    "id", "hi", "fi", "vi", "he", "uk", "el", "ms", "cs", "ro", "da", "hu", "ta", "no", "th", "ur",
+    "hr", "bg", "lt", "la", "mi", "ml", "cy", "sk", "te", "fa", "lv", "bn", "sr", "az", "sl", "kn",
+    "et", "mk", "br", "eu", "is", "hy", "ne", "mn", "bs", "kk", "sq", "sw", "gl", "mr", "pa", "si",
+    "km", "sn", "yo", "so", "af", "oc", "ka", "be", "tg", "sd", "gu", "am", "yi", "lo", "uz", "fo",
+    "ht", "ps", "tk", "nn", "mt", "sa", "lb", "my", "bo", "tl", "mg", "as", "tt", "haw", "ln", "ha",
+    "ba", "jw", "su",
+];
+
+/// What the decoder produces
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhisperTask {
+    /// Text in the spoken language
+    #[default]
+    Transcribe,
+    /// English translation of the speech
+    Translate,
+}
+
+impl WhisperTask {
+    fn token(self) -> u32 {
+        match self {
+            Self::Translate => 50358,   // <|translate|>
+            Self::Transcribe => 50359,  // <|transcribe|>
+        }
+    }
 }
 
 /// Transcription result
 pub struct TranscriptionResult {
+    /// Transcript, or the English translation for `WhisperTask::Translate`
     pub text: String,
+    /// Text in the spoken language when `text` is a translation
+    pub original_text: Option<String>,
     pub detected_language: Option<String>,
     pub confidence: f32,
     pub segments: Vec<TranscriptionSegment>,
@@ -62,34 +107,59 @@ impl WhisperModel {
             encoder,
             decoder,
             model_id: format!("whisper-{}", model_variant),
-            sample_rate: 16000,
+            sample_rate: SAMPLE_RATE as u32,
+            multilingual: !model_variant.ends_with("en"),
+            encoder_hidden_dim: 384,
         })
     }
 
-    /// Transcribe audio file (synchronous)
+    /// Transcribe or translate audio file (synchronous)
     pub fn transcribe(
         &mut self,
         audio_path: &str,
         language: Option<&str>,
+        task: WhisperTask,
     ) -> Result<TranscriptionResult, String> {
         // Load and preprocess audio
         let audio_data = load_audio(audio_path, self.sample_rate)?;
+        self.transcribe_samples(&audio_data, language, task)
+    }
 
+    /// Transcribe or translate up to 30 seconds of 16kHz mono audio (synchronous)
+    pub fn transcribe_samples(
+        &mut self,
+        audio_data: &[f32],
+        language: Option<&str>,
+        task: WhisperTask,
+    ) -> Result<TranscriptionResult, String> {
         // Extract mel spectrogram features
-        let mel_features = compute_mel_spectrogram(&audio_data, self.sample_rate)?;
+        let mel_features = compute_mel_spectrogram(audio_data)?;
 
         // Run encoder
         let encoder_output = self.run_encoder(&mel_features)?;
 
-        // Run decoder with greedy search
-        let (tokens, confidence) = self.run_decoder(&encoder_output, language)?;
+        // Use the requested language, or let the model pick one
+        let language = match language.and_then(language_token) {
+            Some(token) => token,
+            None if self.multilingual => self.detect_language(&encoder_output)?,
+            None => TOKEN_FIRST_LANGUAGE,
+        };
 
-        // Decode tokens to text
-        let text = decode_tokens(&tokens);
+        // Run decoder with greedy search; a translation also keeps the original
+        // text unless the speech already is English
+        let (tokens, mut confidence) = self.run_decoder(&encoder_output, language, WhisperTask::Transcribe)?;
+        let mut text = decode_tokens(&tokens);
+        let mut original_text = None;
+        if task == WhisperTask::Translate && language != TOKEN_FIRST_LANGUAGE {
+            let (tokens, translation_confidence) = self.run_decoder(&encoder_output, language, task)?;
+            original_text = Some(std::mem::replace(&mut text, decode_tokens(&tokens)));
+            confidence = confidence.min(translation_confidence);
+        }
 
         Ok(TranscriptionResult {
             text: text.clone(),
-            detected_language: language.map(|s| s.to_string()),
+            original_text,
+            detected_language: language_code(language).map(str::to_string),
             confidence,
             segments: vec![TranscriptionSegment {
                 start_ms: 0,
@@ -100,9 +170,29 @@ impl WhisperModel {
         })
     }
 
+    /// Spoken language of an audio file, from its first 30 seconds, as a code such as "da"
+    pub fn identify_language(&mut self, audio_path: &str) -> Result<String, String> {
+        let audio_data = load_audio(audio_path, self.sample_rate)?;
+        self.identify_language_samples(&audio_data)
+    }
+
+    /// Spoken language of 16kHz mono audio, from its first 30 seconds
+    pub fn identify_language_samples(&mut self, audio_data: &[f32]) -> Result<String, String> {
+        if !self.multilingual {
+            return Err(format!("{} only understands English", self.model_id));
+        }
+        // The spectrogram only covers the first 30 seconds
+        let mel_features = compute_mel_spectrogram(audio_data)?;
+        let encoder_output = self.run_encoder(&mel_features)?;
+        let language = self.detect_language(&encoder_output)?;
+        language_code(language)
+            .map(str::to_string)
+            .ok_or_else(|| "Decoder returned an unknown language".to_string())
+    }
+
     fn run_encoder(&mut self, mel_features: &[f32]) -> Result<Vec<f32>, String> {
         // Create mel tensor (1, 80, 3000)
-        let mel_tensor = Tensor::from_array(([1usize, 80, 3000], mel_features.to_vec()))
+        let mel_tensor = Tensor::from_array(([1usize, N_MELS, N_FRAMES], mel_features.to_vec()))
             .map_err(|e| format!("Failed to create mel tensor: {}", e))?;
 
         // Build inputs vec - ort v2 inputs! returns Vec directly
@@ -118,84 +208,47 @@ impl WhisperModel {
             .ok_or("Missing encoder output")?;
 
         // ort v2: try_extract_tensor returns (&Shape, &[T]) tuple
-        let (_shape, data) = output.try_extract_tensor::<f32>()
+        let (shape, data) = output.try_extract_tensor::<f32>()
             .map_err(|e| format!("Failed to extract encoder output: {}", e))?;
 
+        // (1, seq_len, hidden_dim)
+        if let Some(&hidden_dim) = shape.last().filter(|&&dim| dim > 0) {
+            self.encoder_hidden_dim = hidden_dim as usize;
+        }
         Ok(data.to_vec())
     }
 
+    /// Most likely spoken language, as its token
+    fn detect_language(&mut self, encoder_output: &[f32]) -> Result<u32, String> {
+        let logits = self.next_token_logits(encoder_output, &[TOKEN_START_OF_TRANSCRIPT])?;
+        let languages = TOKEN_FIRST_LANGUAGE as usize..TOKEN_FIRST_LANGUAGE as usize + LANGUAGES.len();
+        let (token, _) = argmax(&logits[languages.start.min(logits.len())..languages.end.min(logits.len())])
+            .ok_or("Decoder returned no language logits")?;
+        Ok(TOKEN_FIRST_LANGUAGE + token as u32)
+    }
+
     fn run_decoder(
         &mut self,
         encoder_output: &[f32],
-        _language: Option<&str>,
+        language: u32,
+        task: WhisperTask,
     ) -> Result<(Vec<u32>, f32), String> {
         // Simplified greedy decoding
-        let mut tokens = vec![50258u32]; // <|startoftranscript|>
+        let mut tokens = decoder_prompt(language, task);
         let max_length = 448;
         let mut total_log_prob = 0.0f32;
         let mut num_tokens = 0;
 
-        // Add language token if specified
-        tokens.push(50259); // <|en|> for English
-
-        // Add task token
-        tokens.push(50359); // <|transcribe|>
-
-        // Estimate encoder output dimensions (assume 1, seq_len, hidden_dim)
-        // For whisper-tiny: hidden_dim=384, for small: hidden_dim=768
-        let encoder_hidden_dim = 384;
-        let encoder_seq_len = encoder_output.len() / encoder_hidden_dim;
-
         for _ in 0..max_length {
-            let input_ids: Vec<i64> = tokens.iter().map(|&x| x as i64).collect();
-            let seq_len = input_ids.len();
-
-            let input_tensor = Tensor::from_array(([1usize, seq_len], input_ids))
-                .map_err(|e| format!("Failed to create decoder input: {}", e))?;
-
-            let encoder_tensor = Tensor::from_array(([1usize, encoder_seq_len, encoder_hidden_dim], encoder_output.to_vec()))
-                .map_err(|e| format!("Failed to create encoder hidden states tensor: {}", e))?;
-
-            // Build inputs vec - ort v2 inputs! returns Vec directly
-            let inputs = ort::inputs![
-                "input_ids" => input_tensor,
-                "encoder_hidden_states" => encoder_tensor
-            ];
-
-            let outputs = self.decoder.run(inputs)
-                .map_err(|e| format!("Decoder inference failed: {}", e))?;
-
-            let logits = outputs.get("logits")
-                .ok_or("Missing logits output")?;
-
-            // ort v2: try_extract_tensor returns (&Shape, &[T]) tuple
-            let (_shape, logits_slice) = logits.try_extract_tensor::<f32>()
-                .map_err(|e| format!("Failed to extract logits: {}", e))?;
-
-            // Get last token logits
-            // Logits shape: (1, seq_len, vocab_size) = 51865 for whisper
-            let vocab_size = 51865;
-            let last_idx = tokens.len() - 1;
-            let start_offset = last_idx * vocab_size;
-
-            let mut max_prob = f32::NEG_INFINITY;
-            let mut max_token = 0u32;
-
-            for i in 0..vocab_size {
-                if let Some(&prob) = logits_slice.get(start_offset + i) {
-                    if prob > max_prob {
-                        max_prob = prob;
-                        max_token = i as u32;
-                    }
-                }
-            }
+            let logits = self.next_token_logits(encoder_output, &tokens)?;
+            let (max_token, max_prob) = argmax(&logits).ok_or("Missing logits output")?;
 
             // Check for end token
-            if max_token == 50257 { // <|endoftext|>
+            if max_token as u32 == TOKEN_END_OF_TEXT {
                 break;
             }
 
-            tokens.push(max_token);
+            tokens.push(max_token as u32);
             total_log_prob += max_prob;
             num_tokens += 1;
         }
@@ -209,11 +262,86 @@ impl WhisperModel {
         Ok((tokens, confidence))
     }
 
+    /// Logits for the token following `tokens`
+    fn next_token_logits(&mut self, encoder_output: &[f32], tokens: &[u32]) -> Result<Vec<f32>, String> {
+        let encoder_hidden_dim = self.encoder_hidden_dim;
+        let encoder_seq_len = encoder_output.len() / encoder_hidden_dim;
+
+        let input_ids: Vec<i64> = tokens.iter().map(|&x| x as i64).collect();
+        let seq_len = input_ids.len();
+
+        let input_tensor = Tensor::from_array(([1usize, seq_len], input_ids))
+            .map_err(|e| format!("Failed to create decoder input: {}", e))?;
+
+        let encoder_tensor = Tensor::from_array(([1usize, encoder_seq_len, encoder_hidden_dim], encoder_output.to_vec()))
+            .map_err(|e| format!("Failed to create encoder hidden states tensor: {}", e))?;
+
+        // Build inputs vec - ort v2 inputs! returns Vec directly
+        let inputs = ort::inputs![
+            "input_ids" => input_tensor,
+            "encoder_hidden_states" => encoder_tensor
+        ];
+
+        let outputs = self.decoder.run(inputs)
+            .map_err(|e| format!("Decoder inference failed: {}", e))?;
+
+        let logits = outputs.get("logits")
+            .ok_or("Missing logits output")?;
+
+        // ort v2: try_extract_tensor returns (&Shape, &[T]) tuple
+        let (shape, logits_slice) = logits.try_extract_tensor::<f32>()
+            .map_err(|e| format!("Failed to extract logits: {}", e))?;
+
+        // Get last token logits
+        // Logits shape: (1, seq_len, vocab_size): 51865 multilingual, 51864 English-only
+        let vocab_size = shape.last().map_or(51865, |&dim| dim as usize);
+        let start_offset = (seq_len - 1) * vocab_size;
+        let end_offset = (start_offset + vocab_size).min(logits_slice.len());
+        Ok(logits_slice.get(start_offset..end_offset).unwrap_or_default().to_vec())
+    }
+
     pub fn model_id(&self) -> &str {
         &self.model_id
     }
 }
 
+/// `<|startoftranscript|> <|lang|> <|task|>`
+fn decoder_prompt(language: u32, task: WhisperTask) -> Vec<u32> {
+    vec![TOKEN_START_OF_TRANSCRIPT, language, task.token()]
+}
+
+/// Token of a language code such as "da" or "da-DK"
+fn language_token(language: &str) -> Option<u32> {
+    let code = language.split(['-', '_']).next()?.to_lowercase();
+    LANGUAGES
+        .iter()
+        .position(|&l| l == code)
+        .map(|i| TOKEN_FIRST_LANGUAGE + i as u32)
+}
+
+fn language_code(token: u32) -> Option<&'static str> {
+    LANGUAGES.get(token.checked_sub(TOKEN_FIRST_LANGUAGE)? as usize).copied()
+}
+
+/// Index and value of the largest logit
+fn argmax(logits: &[f32]) -> Option<(usize, f32)> {
+    logits
+        .iter()
+        .copied()
+        .enumerate()
+        .fold(None, |best, (i, v)| match best {
+            Some((_, max)) if max >= v => best,
+            _ => Some((i, v)),
+        })
+}
+
+/// Length of a WAV file in whole seconds, read from its header
+pub fn audio_seconds(path: &str) -> Option<usize> {
+    let reader = hound::WavReader::open(path).ok()?;
+    let spec = reader.spec();
+    Some((reader.duration() / spec.sample_rate.max(1)) as usize)
+}
+
 /// Load audio file and convert to 16kHz mono f32
 fn load_audio(path: &str, target_sample_rate: u32) -> Result<Vec<f32>, String> {
     let path = Path::new(path);
@@ -273,7 +401,7 @@ fn load_wav(path: &Path, target_sample_rate: u32) -> Result<Vec<f32>, String> {
 }
 
 /// Simple linear resampling
-fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     let ratio = from_rate as f64 / to_rate as f64;
     let new_len = (samples.len() as f64 / ratio) as usize;
 
@@ -291,55 +419,130 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
         .collect()
 }
 
-/// Compute mel spectrogram features for Whisper
-fn compute_mel_spectrogram(audio: &[f32], _sample_rate: u32) -> Result<Vec<f32>, String> {
-    // Whisper expects 30 seconds of audio (480000 samples at 16kHz)
-    // Output: (1, 80, 3000) mel spectrogram
-
-    const N_MELS: usize = 80;
-    const N_FRAMES: usize = 3000;
-    const HOP_LENGTH: usize = 160;
-    const N_FFT: usize = 400;
-
+/// Audio front end constants from whisper/audio.py
+const SAMPLE_RATE: usize = 16000;
+const N_FFT: usize = 400;
+const HOP_LENGTH: usize = 160;
+const N_MELS: usize = 80;
+/// 30 seconds of audio per encoder window
+const N_SAMPLES: usize = 30 * SAMPLE_RATE;
+const N_FRAMES: usize = N_SAMPLES / HOP_LENGTH;
+
+/// Compute the (1, 80, 3000) log-mel spectrogram Whisper's encoder takes,
+/// the same way as `whisper.log_mel_spectrogram` on 30 seconds of audio
+fn compute_mel_spectrogram(audio: &[f32]) -> Result<Vec<f32>, String> {
     // Pad or truncate to 30 seconds
-    let target_len = 30 * 16000;
-    let mut padded = vec![0.0f32; target_len];
-    let copy_len = audio.len().min(target_len);
-    padded[..copy_len].copy_from_slice(&audio[..copy_len]);
-
-    // Compute STFT magnitude (simplified - in production use rustfft)
+    let mut samples = vec![0.0f32; N_SAMPLES];
+    let copy_len = audio.len().min(N_SAMPLES);
+    samples[..copy_len].copy_from_slice(&audio[..copy_len]);
+    // Centered frames, like torch.stft(center=True)
+    let padded = reflect_pad(&samples, N_FFT / 2);
+
+    let window = hann_window(N_FFT);
+    let filters = mel_filters();
+    let fft = RealFftPlanner::<f32>::new().plan_fft_forward(N_FFT);
+    let mut frame = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let mut power = vec![0.0f32; spectrum.len()];
     let mut mel_spec = vec![0.0f32; N_MELS * N_FRAMES];
 
-    for frame in 0..N_FRAMES {
-        let start = frame * HOP_LENGTH;
-        if start + N_FFT > padded.len() {
-            break;
+    // torch.stft gives one frame more; Whisper drops the last
+    for index in 0..N_FRAMES {
+        let start = index * HOP_LENGTH;
+        for (i, value) in frame.iter_mut().enumerate() {
+            *value = padded[start + i] * window[i];
         }
-
-        // Apply Hanning window and compute power spectrum
-        let mut power = vec![0.0f32; N_FFT / 2 + 1];
-        for i in 0..N_FFT {
-            let window = 0.5 * (1.0 - (2.0 * std::f32::consts::PI * i as f32 / (N_FFT - 1) as f32).cos());
-            let sample = padded[start + i] * window;
-            // Simplified power estimation (proper implementation needs FFT)
-            power[i % (N_FFT / 2 + 1)] += sample * sample;
+        fft.process(&mut frame, &mut spectrum)
+            .map_err(|e| format!("FFT failed: {}", e))?;
+        for (p, bin) in power.iter_mut().zip(&spectrum) {
+            *p = bin.norm_sqr();
         }
 
-        // Apply mel filterbank (simplified)
-        for mel in 0..N_MELS {
-            let mut sum = 0.0f32;
-            let mel_start = mel * (N_FFT / 2) / N_MELS;
-            let mel_end = (mel + 1) * (N_FFT / 2) / N_MELS;
-            for i in mel_start..mel_end.min(power.len()) {
-                sum += power[i];
-            }
-            mel_spec[mel * N_FRAMES + frame] = (sum + 1e-10).log10();
+        for (mel, filter) in filters.iter().enumerate() {
+            let energy: f32 = filter.iter().zip(&power).map(|(w, p)| w * p).sum();
+            mel_spec[mel * N_FRAMES + index] = energy.max(1e-10).log10();
         }
     }
 
+    // Keep 80 dB of dynamic range and scale to roughly [-1, 1]
+    let max = mel_spec.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    for value in &mut mel_spec {
+        *value = (value.max(max - 8.0) + 4.0) / 4.0;
+    }
+
     Ok(mel_spec)
 }
 
+/// Mirror `padding` samples at each end, leaving out the edge sample itself
+fn reflect_pad(samples: &[f32], padding: usize) -> Vec<f32> {
+    let last = samples.len() - 1;
+    let mut padded = Vec::with_capacity(samples.len() + 2 * padding);
+    padded.extend((1..=padding).rev().map(|i| samples[i]));
+    padded.extend_from_slice(samples);
+    padded.extend((1..=padding).map(|i| samples[last - i]));
+    padded
+}
+
+/// Periodic Hann window, as torch.hann_window
+fn hann_window(size: usize) -> Vec<f32> {
+    (0..size)
+        .map(|i| 0.5 * (1.0 - (2.0 * std::f64::consts::PI * i as f64 / size as f64).cos()) as f32)
+        .collect()
+}
+
+/// Mel filterbank Whisper was trained with: librosa.filters.mel(sr=16000,
+/// n_fft=400, n_mels=80), Slaney mel scale with area-normalized triangles
+fn mel_filters() -> &'static [Vec<f32>] {
+    static FILTERS: OnceLock<Vec<Vec<f32>>> = OnceLock::new();
+    FILTERS.get_or_init(|| {
+        let max_mel = hz_to_mel(SAMPLE_RATE as f64 / 2.0);
+        let edges: Vec<f64> = (0..N_MELS + 2)
+            .map(|i| mel_to_hz(max_mel * i as f64 / (N_MELS + 1) as f64))
+            .collect();
+
+        edges
+            .windows(3)
+            .map(|edge| {
+                let (lower, center, upper) = (edge[0], edge[1], edge[2]);
+                let norm = 2.0 / (upper - lower);
+                (0..=N_FFT / 2)
+                    .map(|bin| {
+                        let hz = bin as f64 * SAMPLE_RATE as f64 / N_FFT as f64;
+                        let rising = (hz - lower) / (center - lower);
+                        let falling = (upper - hz) / (upper - center);
+                        (rising.min(falling).max(0.0) * norm) as f32
+                    })
+                    .collect()
+            })
+            .collect()
+    })
+}
+
+/// Slaney mel scale: linear below 1 kHz, logarithmic above
+const MEL_HZ_PER_MEL: f64 = 200.0 / 3.0;
+const MEL_LOG_START_HZ: f64 = 1000.0;
+const MEL_LOG_START: f64 = MEL_LOG_START_HZ / MEL_HZ_PER_MEL;
+
+fn mel_log_step() -> f64 {
+    6.4f64.ln() / 27.0
+}
+
+fn hz_to_mel(hz: f64) -> f64 {
+    if hz >= MEL_LOG_START_HZ {
+        MEL_LOG_START + (hz / MEL_LOG_START_HZ).ln() / mel_log_step()
+    } else {
+        hz / MEL_HZ_PER_MEL
+    }
+}
+
+fn mel_to_hz(mel: f64) -> f64 {
+    if mel >= MEL_LOG_START {
+        MEL_LOG_START_HZ * (mel_log_step() * (mel - MEL_LOG_START)).exp()
+    } else {
+        mel * MEL_HZ_PER_MEL
+    }
+}
+
 /// Decode token IDs to text
 fn decode_tokens(tokens: &[u32]) -> String {
     // Simplified token decoding - in production load tokenizer
@@ -369,4 +572,90 @@ mod tests {
         let resampled = resample(&samples, 100, 50);
         assert_eq!(resampled.len(), 50);
     }
+
+    /// 0.25 s of a 440 Hz tone at half amplitude, then silence
+    fn tone_spectrogram() -> Vec<f32> {
+        let tone: Vec<f32> = (0..4000)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / SAMPLE_RATE as f32).sin())
+            .collect();
+        compute_mel_spectrogram(&tone).unwrap()
+    }
+
+    fn assert_frame(mel_spec: &[f32], frame: usize, expected: &[f32]) {
+        for (mel, expected) in expected.iter().enumerate() {
+            let actual = mel_spec[mel * N_FRAMES + frame];
+            assert!(
+                (actual - expected).abs() < 1e-3,
+                "frame {} mel {}: {} != {}",
+                frame,
+                mel,
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_mel_filters_match_whisper() {
+        assert!((hz_to_mel(1000.0) - 15.0).abs() < 1e-9);
+        assert!((mel_to_hz(hz_to_mel(4321.0)) - 4321.0).abs() < 1e-6);
+
+        let filters = mel_filters();
+        assert_eq!(filters.len(), N_MELS);
+        assert!(filters.iter().all(|filter| filter.len() == N_FFT / 2 + 1));
+        // First row of whisper's mel_filters.npz: a single weight on bin 1
+        let nonzero: Vec<_> = filters[0].iter().enumerate().filter(|(_, w)| **w > 0.0).collect();
+        assert_eq!(nonzero.len(), 1);
+        assert_eq!(nonzero[0].0, 1);
+        assert!((nonzero[0].1 - 0.024_862_59).abs() < 1e-7);
+    }
+
+    #[test]
+    fn test_mel_spectrogram_of_silence() {
+        let mel_spec = compute_mel_spectrogram(&[]).unwrap();
+        assert_eq!(mel_spec.len(), N_MELS * N_FRAMES);
+        // log10 of the 1e-10 floor, shifted and scaled
+        assert!(mel_spec.iter().all(|v| (v + 1.5).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_mel_spectrogram_reference_frames() {
+        // Reference values from Whisper's algorithm in double precision
+        let mel_spec = tone_spectrogram();
+        let floor = -0.561_796_2;
+
+        // Inside the tone: only the bands around 440 Hz rise above the 80 dB floor
+        let mut inside = [floor; 16];
+        inside[9..13].copy_from_slice(&[1.15939, 1.34874, 1.4382, 1.29352]);
+        assert_frame(&mel_spec, 10, &inside);
+
+        // First frame, reflected around the start of the tone
+        assert_frame(
+            &mel_spec,
+            0,
+            &[0.98328, 0.98662, 0.99715, 1.00781, 1.02748, 1.04823, 1.08384, 1.12315, 1.21196, 1.30782, 1.33624, 1.1116, 1.3383, 1.25274, 1.08849, 1.03271],
+        );
+
+        // The tone breaks off mid-frame and spreads over all bands
+        assert_frame(
+            &mel_spec,
+            26,
+            &[0.32076, 0.32512, 0.33125, 0.33824, 0.3452, 0.35141, 0.35633, 0.35955, 0.36081, 0.3599, 0.35668, 0.35105, 0.34292, 0.336, 0.32101, 0.29971],
+        );
+
+        // Silence after the tone sits on the floor
+        assert_frame(&mel_spec, 100, &[floor; N_MELS]);
+    }
+
+    #[test]
+    fn test_decoder_prompt() {
+        let danish = language_token("da-DK").unwrap();
+        assert_eq!(danish, 50285);
+        assert_eq!(language_code(danish), Some("da"));
+        assert_eq!(decoder_prompt(danish, WhisperTask::Translate), vec![50258, 50285, 50358]);
+        assert_eq!(decoder_prompt(language_token("en").unwrap(), WhisperTask::Transcribe), vec![50258, 50259, 50359]);
+        // The last language token sits right before <|translate|>
+        assert_eq!(language_token("su"), Some(WhisperTask::Translate.token() - 1));
+        assert_eq!(language_token("xx"), None);
+    }
 }
@@ -13,9 +13,17 @@ mod telemetry;
 mod commander;
 mod research;
 mod accessibility;
+mod activity;
+mod notifications;
+mod export;
+mod pipeline;
+mod memory;
+mod onboarding;
+mod storage;
+mod task_queue;
 
-use commands::{resource, sync, inference as inference_cmd, settings, telemetry as telemetry_cmd, commander as commander_cmd, accessibility as accessibility_cmd};
-use tauri::Manager;
+use commands::{resource, sync, inference as inference_cmd, settings, telemetry as telemetry_cmd, commander as commander_cmd, accessibility as accessibility_cmd, activity as activity_cmd, notifications as notifications_cmd, export as export_cmd, privacy as privacy_cmd, devices as devices_cmd, pipeline as pipeline_cmd, consent as consent_cmd, onboarding as onboarding_cmd, storage as storage_cmd, tasks as tasks_cmd};
+use tauri::{Emitter, Manager};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -25,17 +33,65 @@ pub struct AppState {
     pub sync_status: Arc<RwLock<models::SyncStatus>>,
     pub resource_monitor: Arc<RwLock<utils::ResourceMonitor>>,
     pub inference_engine: Arc<RwLock<Option<inference::InferenceEngine>>>,
+    pub inference_scheduler: Arc<inference::InferenceScheduler>,
+    /// Model loads and unloads, forwarded to the frontend
+    pub model_events: tokio::sync::broadcast::Sender<inference::ModelStateChange>,
     pub telemetry_stats: Arc<RwLock<models::TelemetryStats>>,
+    pub telemetry: Arc<telemetry::TelemetryService>,
+    /// Sends anonymized reports to CKC while the user has consented
+    pub telemetry_reporter: Arc<telemetry::TelemetryReporter>,
+    pub watchdog: Arc<utils::Watchdog>,
+    pub activity: Arc<activity::ActivityLog>,
+    pub notifications: Arc<notifications::NotificationCenter>,
+    pub privacy: Arc<security::privacy::PrivacyMode>,
+    pub device: Arc<security::device::DeviceIdentity>,
+    pub command_limiter: Arc<security::command_limits::CommandLimiter>,
+    pub consent: Arc<security::consent::ConsentStore>,
+    pub onboarding: Arc<onboarding::Onboarding>,
+    pub database: Arc<storage::LocalDatabase>,
+    pub task_queue: Arc<task_queue::TaskQueue>,
 }
 
 impl Default for AppState {
     fn default() -> Self {
+        let settings = models::Settings::default();
+        let telemetry_config = telemetry::TelemetryConfig::from_settings(&settings);
+        let telemetry = Arc::new(
+            telemetry::TelemetryService::new(telemetry_config.clone()).with_history(
+                telemetry::history::MetricsHistory::load(telemetry::history::MetricsHistory::default_path()),
+            ),
+        );
+        let database = storage::LocalDatabase::open(&storage::LocalDatabase::default_path(), settings.max_disk_mb)
+            .unwrap_or_else(|e| {
+                log::error!("Could not open the local database, keeping data in memory: {}", e);
+                storage::LocalDatabase::in_memory(settings.max_disk_mb)
+            });
+        database.import_legacy_dirs(&memory::memories_dir(), &export::sessions_dir());
+        let database = Arc::new(database);
         Self {
-            settings: Arc::new(RwLock::new(models::Settings::default())),
+            command_limiter: Arc::new(security::command_limits::CommandLimiter::new(&settings.command_budgets)),
+            settings: Arc::new(RwLock::new(settings)),
             sync_status: Arc::new(RwLock::new(models::SyncStatus::default())),
             resource_monitor: Arc::new(RwLock::new(utils::ResourceMonitor::new())),
             inference_engine: Arc::new(RwLock::new(None)),
+            inference_scheduler: Arc::new(inference::InferenceScheduler::default()),
+            model_events: tokio::sync::broadcast::channel(32).0,
             telemetry_stats: Arc::new(RwLock::new(models::TelemetryStats::default())),
+            watchdog: Arc::new(utils::Watchdog::default().with_telemetry(telemetry.clone())),
+            privacy: Arc::new(security::privacy::PrivacyMode::new().with_telemetry(telemetry.clone())),
+            telemetry_reporter: Arc::new(telemetry::TelemetryReporter::new(
+                telemetry_config,
+                telemetry.clone(),
+                env!("CARGO_PKG_VERSION"),
+            )),
+            telemetry,
+            activity: Arc::new(activity::ActivityLog::default()),
+            notifications: Arc::new(notifications::NotificationCenter::default()),
+            device: Arc::new(security::device::DeviceIdentity::load_or_create(&security::device::data_dir())),
+            consent: Arc::new(security::consent::ConsentStore::load(&security::device::data_dir())),
+            onboarding: Arc::new(onboarding::Onboarding::load(&security::device::data_dir())),
+            task_queue: Arc::new(task_queue::TaskQueue::new(database.clone())),
+            database,
         }
     }
 }
@@ -50,6 +106,24 @@ async fn main() {
 
     // Create application state
     let app_state = AppState::default();
+    let accessibility_state = accessibility_cmd::AccessibilityState::with_services(
+        app_state.settings.clone(),
+        app_state.telemetry.clone(),
+        app_state.watchdog.clone(),
+        app_state.notifications.clone(),
+        app_state.privacy.clone(),
+        app_state.inference_engine.clone(),
+    );
+    let commander_state = commander_cmd::CommanderState::with_services(
+        app_state.inference_engine.clone(),
+        app_state.telemetry.clone(),
+        app_state.activity.clone(),
+        app_state.notifications.clone(),
+        app_state.privacy.clone(),
+        app_state.database.clone(),
+    );
+    let command_limiter = app_state.command_limiter.clone();
+    app_state.telemetry_reporter.clone().start();
 
     tauri::Builder::default()
         // Plugins
@@ -62,11 +136,11 @@ async fn main() {
 
         // State management
         .manage(app_state)
-        .manage(commander_cmd::CommanderState::default())
-        .manage(accessibility_cmd::AccessibilityState::default())
+        .manage(commander_state)
+        .manage(accessibility_state)
 
-        // Commands
-        .invoke_handler(tauri::generate_handler![
+        // Commands, with call budgets for the expensive ones
+        .invoke_handler(security::command_limits::rate_limited(command_limiter, tauri::generate_handler![
             // Resource monitoring
             resource::get_system_metrics,
             resource::can_execute_task,
@@ -81,10 +155,22 @@ async fn main() {
 
             // AI inference
             inference_cmd::generate_embedding,
+            inference_cmd::generate_embeddings_batch,
+            inference_cmd::generate_text,
             inference_cmd::transcribe_audio,
             inference_cmd::extract_text,
             inference_cmd::get_model_status,
+            pipeline_cmd::list_pipelines,
+            pipeline_cmd::run_pipeline,
             inference_cmd::download_model,
+            inference_cmd::unload_model,
+            inference_cmd::reload_models,
+            inference_cmd::compare_embedding_models,
+            inference_cmd::get_inference_lanes,
+            inference_cmd::get_inference_queue,
+            inference_cmd::get_inference_warmup_status,
+            inference_cmd::run_benchmark,
+            inference_cmd::get_hardware_profile,
 
             // Settings
             settings::get_settings,
@@ -97,9 +183,59 @@ async fn main() {
             telemetry_cmd::get_telemetry_consent,
             telemetry_cmd::set_telemetry_consent,
             telemetry_cmd::get_telemetry_stats,
+            telemetry_cmd::get_weekly_report,
             telemetry_cmd::send_telemetry_report,
             telemetry_cmd::record_telemetry_event,
             telemetry_cmd::get_privacy_info,
+            telemetry_cmd::get_telemetry_schema,
+            telemetry_cmd::get_watchdog_status,
+            telemetry_cmd::get_network_usage,
+
+            // Activity timeline
+            activity_cmd::get_activity,
+
+            // Notification center
+            notifications_cmd::list_notifications,
+            notifications_cmd::mark_read,
+            notifications_cmd::clear_notifications,
+
+            // Session export
+            export_cmd::export_session,
+
+            // Privacy mode
+            privacy_cmd::set_privacy_mode,
+            privacy_cmd::get_privacy_status,
+
+            // Capability consent
+            consent_cmd::request_capability,
+            consent_cmd::respond_capability,
+            consent_cmd::list_capability_grants,
+            consent_cmd::revoke_capability,
+
+            // First-run onboarding
+            onboarding_cmd::get_onboarding_state,
+            onboarding_cmd::complete_step,
+            onboarding_cmd::reset_onboarding,
+
+            // Local storage
+            storage_cmd::get_storage_health,
+            storage_cmd::recover_store,
+            storage_cmd::save_memory,
+            storage_cmd::list_memories,
+            storage_cmd::search_memories,
+            storage_cmd::delete_memory,
+
+            // Task queue
+            tasks_cmd::list_tasks,
+            tasks_cmd::enqueue_task,
+            tasks_cmd::cancel_task,
+            tasks_cmd::retry_task,
+            tasks_cmd::clear_finished_tasks,
+
+            // Device management
+            devices_cmd::get_device_info,
+            devices_cmd::list_devices,
+            devices_cmd::revoke_device,
 
             // Commander Unit (FASE 6)
             commander_cmd::get_commander_status,
@@ -107,12 +243,37 @@ async fn main() {
             commander_cmd::update_commander_config,
             commander_cmd::start_commander,
             commander_cmd::stop_commander,
+            commander_cmd::pause_commander,
+            commander_cmd::resume_commander,
             commander_cmd::add_research_task,
             commander_cmd::get_task_queue_status,
             commander_cmd::get_recent_findings,
+            commander_cmd::get_findings_diff,
+            commander_cmd::get_finding_content,
+            commander_cmd::get_finding_knowledge,
+            commander_cmd::list_adapter_credentials,
+            commander_cmd::set_adapter_credential,
+            commander_cmd::remove_adapter_credential,
+            commander_cmd::get_finding_score_breakdown,
+            commander_cmd::rescore_findings,
+            commander_cmd::rate_finding,
+            commander_cmd::list_research_feeds,
+            commander_cmd::add_research_feed,
+            commander_cmd::remove_research_feed,
             commander_cmd::force_commander_sync,
             commander_cmd::get_sync_stats,
             commander_cmd::set_autonomy_level,
+            commander_cmd::get_pending_approvals,
+            commander_cmd::get_policy_status,
+            commander_cmd::approve_decision,
+            commander_cmd::reject_decision,
+            commander_cmd::get_decision_history,
+            commander_cmd::get_decision_rules,
+            commander_cmd::update_decision_rules,
+            commander_cmd::get_decision,
+            commander_cmd::search_findings,
+            commander_cmd::get_finding,
+            commander_cmd::get_research_digest,
 
             // Accessibility / Voice Control (Hands-free for handicapped users)
             accessibility_cmd::get_accessibility_config,
@@ -122,10 +283,25 @@ async fn main() {
             accessibility_cmd::stop_voice_control,
             accessibility_cmd::speak_text,
             accessibility_cmd::listen_for_command,
+            accessibility_cmd::enroll_hotword_sample,
+            accessibility_cmd::clear_hotword_samples,
+            accessibility_cmd::get_hotword_stats,
+            accessibility_cmd::start_streaming_transcription,
+            accessibility_cmd::stop_streaming_transcription,
             accessibility_cmd::execute_voice_command,
+            accessibility_cmd::parse_voice_command,
+            accessibility_cmd::list_voice_macros,
+            accessibility_cmd::save_voice_macro,
+            accessibility_cmd::delete_voice_macro,
             accessibility_cmd::get_available_commands,
             accessibility_cmd::toggle_accessibility_mode,
-        ])
+            accessibility_cmd::narrate_error,
+            accessibility_cmd::narrate_ui,
+            accessibility_cmd::list_input_devices,
+            accessibility_cmd::select_input_device,
+            accessibility_cmd::preview_sound_cue,
+            accessibility_cmd::list_sound_themes,
+        ]))
 
         // Window events - Tauri v2 API
         .on_window_event(|window, event| {
@@ -146,17 +322,141 @@ async fn main() {
                 let _ = window.show();
             }
 
-            // Start background tasks
+            // Start background tasks under the watchdog, which restarts them if they die
+            let watchdog = app.state::<AppState>().watchdog.clone();
+            let app_handle = app.handle().clone();
+            watchdog.supervise("resource_monitor", "resource_monitor", move |heartbeat| {
+                utils::start_resource_monitor(app_handle.clone(), heartbeat)
+            });
+
+            let app_handle = app.handle().clone();
+            watchdog.supervise("sync_loop", "sync_service", move |heartbeat| {
+                utils::start_sync_loop(app_handle.clone(), heartbeat)
+            });
+
+            let app_handle = app.handle().clone();
+            watchdog.supervise("task_worker", "task_queue", move |heartbeat| {
+                task_queue::start_task_worker(app_handle.clone(), heartbeat)
+            });
+
+            // Forward notification center changes to the frontend
+            let mut notification_rx = app.state::<AppState>().notifications.subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                while let Ok(event) = notification_rx.recv().await {
+                    let _ = app_handle.emit("notification", &event);
+                }
+            });
+
+            // Forward UI narrations to the frontend's live regions
+            let mut narration_rx = app.state::<accessibility_cmd::AccessibilityState>().narrator.subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match narration_rx.recv().await {
+                        Ok(narration) => {
+                            let _ = app_handle.emit("ui-narration", &narration);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Forward parked and resolved Commander decisions to the frontend
+            let unit = app.state::<commander_cmd::CommanderState>().unit.clone();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let mut approval_rx = unit.read().await.subscribe_approvals();
+                loop {
+                    match approval_rx.recv().await {
+                        Ok(event) => {
+                            let _ = app_handle.emit("approval-event", &event);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Let "synkroniser nu" run the same sync as the sync button
+            let controller = app.state::<accessibility_cmd::AccessibilityState>().controller.clone();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                controller.write().await.set_sync_handler(Arc::new(move || {
+                    let app_handle = app_handle.clone();
+                    Box::pin(async move {
+                        let state = app_handle.state::<AppState>();
+                        let commander = app_handle.state::<commander_cmd::CommanderState>();
+                        sync::sync_on_request(&state, &commander).await
+                    })
+                }));
+            });
+
+            // Summarise new notifications for screen reader users
+            let mut notification_rx = app.state::<AppState>().notifications.subscribe();
+            let controller = app.state::<accessibility_cmd::AccessibilityState>().controller.clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match notification_rx.recv().await {
+                        Ok(notifications::NotificationEvent::Added { notification, unread }) => {
+                            controller.read().await.narrate_notification(&notification, unread).await;
+                        }
+                        Ok(_) | Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Forward inference queue position and ETA changes to the frontend
+            let mut queue_rx = app.state::<AppState>().inference_scheduler.subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match queue_rx.recv().await {
+                        Ok(snapshot) => {
+                            let _ = app_handle.emit("inference-queue", &snapshot);
+                        }
+                        // Only the latest snapshot matters
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Forward model loads and unloads to the frontend
+            let mut model_rx = app.state::<AppState>().model_events.subscribe();
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    match model_rx.recv().await {
+                        Ok(change) => {
+                            let _ = app_handle.emit("model-state-changed", &change);
+                        }
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            // Forward privacy mode changes to the frontend
+            let privacy = app.state::<AppState>().privacy.clone();
+            let mut privacy_rx = privacy.subscribe();
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                // Start resource monitoring loop
-                utils::start_resource_monitor(app_handle.clone()).await;
+                while privacy_rx.changed().await.is_ok() {
+                    let _ = app_handle.emit("privacy-mode", privacy.status());
+                }
             });
 
+            // Tell the frontend when background work is unlocked or locked again
+            let onboarding = app.state::<AppState>().onboarding.clone();
+            let mut onboarding_rx = onboarding.subscribe();
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                // Start sync loop
-                utils::start_sync_loop(app_handle).await;
+                while onboarding_rx.changed().await.is_ok() {
+                    let _ = app_handle.emit("onboarding", onboarding.state());
+                }
             });
 
             Ok(())
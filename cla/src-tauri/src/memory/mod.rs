@@ -0,0 +1,47 @@
+// Local Memories - Memory entries kept on this device until they are synced
+// Stored in the local database (see storage::LocalDatabase)
+
+use crate::models::LocalMemory;
+use chrono::Utc;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+/// Directory where older versions kept one `<id>.json` file per memory;
+/// imported into the database on first start
+pub fn memories_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cirkelline-cla")
+        .join("memories")
+}
+
+/// New memory waiting to be synced
+pub fn new_memory(content: String, memory_type: &str, topics: Vec<String>, importance: f32) -> LocalMemory {
+    let now = Utc::now();
+    LocalMemory {
+        id: Uuid::new_v4(),
+        content,
+        memory_type: memory_type.to_string(),
+        topics,
+        embedding_local: None,
+        importance: importance.clamp(0.0, 1.0),
+        created_at: now,
+        updated_at: now,
+        synced_at: None,
+        cloud_id: None,
+        pending_sync: true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_memory() {
+        let memory = new_memory("Møde om budget".to_string(), "transcript_summary", vec!["møde".to_string()], 1.5);
+        assert_eq!(memory.importance, 1.0);
+        assert!(memory.pending_sync);
+        assert!(memory.synced_at.is_none());
+    }
+}
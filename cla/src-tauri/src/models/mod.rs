@@ -2,16 +2,23 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 /// User settings for CLA
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
-    // Resource limits
+    // Resource limits (CLA's own use, in % of the machine)
     pub max_cpu_percent: u8,
     pub max_ram_percent: u8,
     pub max_gpu_percent: u8,
     pub max_disk_mb: u32,
+    /// Machine-wide CPU use above which CLA waits, leaving headroom for other apps
+    #[serde(default = "default_max_system_percent")]
+    pub max_system_cpu_percent: u8,
+    /// Machine-wide RAM use above which CLA waits
+    #[serde(default = "default_max_system_percent")]
+    pub max_system_ram_percent: u8,
 
     // Behavior
     pub idle_only: bool,
@@ -29,9 +36,18 @@ pub struct Settings {
     // Model settings
     pub enable_transcription: bool,
     pub enable_ocr: bool,
+    /// Language of the text OCR recognizes (Tesseract-style code, e.g. "dan")
+    #[serde(default = "default_ocr_language")]
+    pub ocr_language: String,
     pub enable_embeddings: bool,
     pub download_tier2_models: bool,
     pub download_tier3_models: bool,
+    /// Where embeddings are computed
+    #[serde(default)]
+    pub embedding_inference: InferencePreference,
+    /// Where transcriptions are computed
+    #[serde(default)]
+    pub transcription_inference: InferencePreference,
 
     // Connection
     pub ckc_endpoint: Option<String>,
@@ -40,6 +56,68 @@ pub struct Settings {
     // Telemetry
     pub telemetry_enabled: bool,
     pub telemetry_consent_date: Option<DateTime<Utc>>,
+
+    // Privacy
+    /// How long privacy mode lasts before capture resumes
+    #[serde(default = "default_privacy_mode_minutes")]
+    pub privacy_mode_minutes: u32,
+
+    // Security
+    /// Call budgets for expensive commands, keyed by command name
+    #[serde(default = "default_command_budgets")]
+    pub command_budgets: HashMap<String, CommandBudget>,
+
+    // Voice control
+    /// The user's own phrases for running several voice commands at once
+    #[serde(default)]
+    pub voice_macros: Vec<VoiceMacro>,
+}
+
+fn default_privacy_mode_minutes() -> u32 {
+    60
+}
+
+fn default_max_system_percent() -> u8 {
+    90
+}
+
+fn default_ocr_language() -> String {
+    "eng".to_string()
+}
+
+/// Calls a webview may make to one command within a window
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommandBudget {
+    pub max_calls: usize,
+    pub window_secs: u64,
+}
+
+/// A spoken phrase that runs several voice commands in order
+/// ("morgenrutine" -> sync now, read notifications, start commander)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VoiceMacro {
+    pub id: Uuid,
+    /// What the user says, e.g. "morgenrutine"
+    pub phrase: String,
+    /// Commands in the order they run, as they would be spoken ("synkroniser nu")
+    pub actions: Vec<String>,
+}
+
+fn default_command_budgets() -> HashMap<String, CommandBudget> {
+    [
+        ("transcribe_audio", 10, 60),
+        ("extract_text", 20, 60),
+        ("generate_embedding", 120, 60),
+        ("run_pipeline", 10, 60),
+        ("download_model", 5, 300),
+        ("run_benchmark", 2, 300),
+        ("compare_embedding_models", 2, 300),
+        ("sync_now", 6, 60),
+        ("export_session", 10, 60),
+    ]
+    .into_iter()
+    .map(|(command, max_calls, window_secs)| (command.to_string(), CommandBudget { max_calls, window_secs }))
+    .collect()
 }
 
 impl Default for Settings {
@@ -50,6 +128,8 @@ impl Default for Settings {
             max_ram_percent: 20,
             max_gpu_percent: 30,
             max_disk_mb: 2000, // 2GB
+            max_system_cpu_percent: default_max_system_percent(),
+            max_system_ram_percent: default_max_system_percent(),
 
             idle_only: true,
             idle_threshold_seconds: 120, // 2 minutes
@@ -64,19 +144,43 @@ impl Default for Settings {
 
             enable_transcription: true,
             enable_ocr: true,
+            ocr_language: default_ocr_language(),
             enable_embeddings: true,
             download_tier2_models: false,
             download_tier3_models: false,
+            embedding_inference: InferencePreference::default(),
+            transcription_inference: InferencePreference::default(),
 
             ckc_endpoint: Some("https://ckc.cirkelline.com".to_string()),
             api_key: None,
 
             telemetry_enabled: false, // Opt-in by default
             telemetry_consent_date: None,
+
+            privacy_mode_minutes: default_privacy_mode_minutes(),
+
+            command_budgets: default_command_budgets(),
+
+            voice_macros: Vec::new(),
         }
     }
 }
 
+/// Local vs. CKC (cloud) inference for a task type
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InferencePreference {
+    /// Local model, CKC when it is missing or fails
+    PreferLocal,
+    /// CKC, local model when offline or CKC fails
+    PreferCloud,
+    /// Nothing leaves the device unless the user opts in
+    #[default]
+    LocalOnly,
+    /// Never load or use local models (low-end devices)
+    CloudOnly,
+}
+
 /// Current sync status
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SyncStatus {
@@ -116,7 +220,7 @@ pub enum ConflictResolution {
     Manual,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum DataType {
     Memory,
     Session,
@@ -136,6 +240,11 @@ pub struct SystemMetrics {
     pub ram_total_mb: u64,
     pub ram_usage_percent: f32,
 
+    // CLA's own process and its child processes
+    pub process_cpu_percent: f32,
+    pub process_ram_mb: u64,
+    pub process_count: u32,
+
     // GPU (optional)
     pub gpu_available: bool,
     pub gpu_usage_percent: Option<f32>,
@@ -225,9 +334,15 @@ pub struct PendingTask {
     pub retry_count: u8,
     pub max_retries: u8,
     pub status: TaskStatus,
+    /// Earliest time the next attempt may start, after a failed attempt
+    #[serde(default)]
+    pub not_before: Option<DateTime<Utc>>,
+    /// Error of the last failed attempt
+    #[serde(default)]
+    pub last_error: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TaskType {
     GenerateEmbedding,
     TranscribeAudio,
@@ -236,7 +351,7 @@ pub enum TaskType {
     PreloadKnowledge,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum TaskStatus {
     Queued,
     Running,
@@ -266,14 +381,73 @@ pub struct EmbeddingResult {
     pub processing_time_ms: u64,
 }
 
+/// Embeddings of many texts generated in one call
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchEmbeddingResult {
+    /// One result per text, in the order the texts were given
+    pub embeddings: Vec<EmbeddingResult>,
+    pub model_used: String,
+    pub processing_time_ms: u64,
+}
+
+/// Why text generation stopped
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The model ended its answer
+    Stop,
+    /// `max_tokens` or the context window was reached
+    Length,
+    /// Stopped early, by the caller or the resource limits
+    Cancelled,
+}
+
+/// Text generated by the local LLM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextGenerationResult {
+    /// Matches the `llm-token` events streamed while generating
+    pub request_id: Uuid,
+    pub text: String,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub finish_reason: FinishReason,
+    /// Why generation was cut short, if it was
+    pub stop_reason: Option<String>,
+    pub processing_time_ms: u64,
+}
+
 /// Transcription result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptionResult {
     pub text: String,
+    /// Text in the spoken language when `text` is an English translation
+    #[serde(default)]
+    pub original_text: Option<String>,
     pub language: Option<String>,
+    /// Language identified from the audio when the caller gave none
+    #[serde(default)]
+    pub detected_language: Option<String>,
     pub confidence: f32,
     pub segments: Vec<TranscriptionSegment>,
     pub processing_time_ms: u64,
+    /// Abstract and action items of a long transcript
+    #[serde(default)]
+    pub summary: Option<TranscriptSummary>,
+}
+
+/// Abstract and action items of a transcript
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TranscriptSummary {
+    #[serde(rename = "abstract")]
+    pub abstract_text: String,
+    pub action_items: Vec<String>,
+    /// Model that wrote the summary
+    pub model: String,
+    /// Chunks summarized in the map stage
+    pub chunks: usize,
+    /// Local memory the summary was stored as
+    #[serde(default)]
+    pub memory_id: Option<Uuid>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,299 @@
+// Notification Center - Persisted notifications with read/unread state
+// OS toasts disappear; everything shown to the user is also kept here
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::{broadcast, RwLock};
+
+/// Notifications kept; the oldest are dropped first
+const MAX_NOTIFICATIONS: usize = 500;
+
+/// Unread notifications read aloud at most
+const MAX_SPOKEN: usize = 5;
+
+/// What a notification is about
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    Research,
+    Approval,
+    Sync,
+    System,
+}
+
+/// Follow-up the user can take from a notification
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotificationAction {
+    /// Identifier handled by the frontend, e.g. "open_approvals"
+    pub id: String,
+    pub label: String,
+}
+
+/// A notification shown to the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Notification {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub category: NotificationCategory,
+    pub title: String,
+    pub body: String,
+    pub read: bool,
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+}
+
+/// Change in the notification center, forwarded to the frontend
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    Added { notification: Notification, unread: usize },
+    /// Notifications were marked read or cleared
+    Changed { unread: usize },
+}
+
+/// Unread count per category
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UnreadCounts {
+    pub total: usize,
+    pub by_category: HashMap<NotificationCategory, usize>,
+}
+
+/// Persisted notification store
+pub struct NotificationCenter {
+    path: PathBuf,
+    notifications: RwLock<Vec<Notification>>,
+    event_tx: broadcast::Sender<NotificationEvent>,
+}
+
+impl NotificationCenter {
+    /// Open a notification center, loading existing notifications from disk
+    pub fn new(path: PathBuf) -> Self {
+        let notifications = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        let (event_tx, _) = broadcast::channel(100);
+
+        Self {
+            path,
+            notifications: RwLock::new(notifications),
+            event_tx,
+        }
+    }
+
+    /// Subscribe to notification changes
+    pub fn subscribe(&self) -> broadcast::Receiver<NotificationEvent> {
+        self.event_tx.subscribe()
+    }
+
+    /// Add a notification
+    pub async fn notify(
+        &self,
+        category: NotificationCategory,
+        title: impl Into<String>,
+        body: impl Into<String>,
+        actions: Vec<NotificationAction>,
+    ) -> Notification {
+        let notification = Notification {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: Utc::now(),
+            category,
+            title: title.into(),
+            body: body.into(),
+            read: false,
+            actions,
+        };
+
+        let mut notifications = self.notifications.write().await;
+        notifications.push(notification.clone());
+        if notifications.len() > MAX_NOTIFICATIONS {
+            let excess = notifications.len() - MAX_NOTIFICATIONS;
+            notifications.drain(..excess);
+        }
+        self.persist(&notifications);
+        let unread = count_unread(&notifications);
+        drop(notifications);
+
+        let _ = self.event_tx.send(NotificationEvent::Added {
+            notification: notification.clone(),
+            unread,
+        });
+        notification
+    }
+
+    /// Notifications newest first, optionally only unread ones of one category
+    pub async fn list(&self, unread_only: bool, category: Option<NotificationCategory>) -> Vec<Notification> {
+        self.notifications
+            .read()
+            .await
+            .iter()
+            .rev()
+            .filter(|n| (!unread_only || !n.read) && category.is_none_or(|c| n.category == c))
+            .cloned()
+            .collect()
+    }
+
+    /// Unread counts, total and per category
+    pub async fn unread_counts(&self) -> UnreadCounts {
+        let notifications = self.notifications.read().await;
+        let mut counts = UnreadCounts::default();
+        for notification in notifications.iter().filter(|n| !n.read) {
+            counts.total += 1;
+            *counts.by_category.entry(notification.category).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Mark notifications read (all when `ids` is None); returns how many changed
+    pub async fn mark_read(&self, ids: Option<&[String]>) -> usize {
+        let mut notifications = self.notifications.write().await;
+        let mut changed = 0;
+        for notification in notifications
+            .iter_mut()
+            .filter(|n| !n.read && ids.is_none_or(|ids| ids.contains(&n.id)))
+        {
+            notification.read = true;
+            changed += 1;
+        }
+        if changed > 0 {
+            self.persist(&notifications);
+            let _ = self.event_tx.send(NotificationEvent::Changed {
+                unread: count_unread(&notifications),
+            });
+        }
+        changed
+    }
+
+    /// Remove notifications (all read ones when `ids` is None); returns how many were removed
+    pub async fn clear(&self, ids: Option<&[String]>) -> usize {
+        let mut notifications = self.notifications.write().await;
+        let before = notifications.len();
+        notifications.retain(|n| match ids {
+            Some(ids) => !ids.contains(&n.id),
+            None => !n.read,
+        });
+        let removed = before - notifications.len();
+        if removed > 0 {
+            self.persist(&notifications);
+            let _ = self.event_tx.send(NotificationEvent::Changed {
+                unread: count_unread(&notifications),
+            });
+        }
+        removed
+    }
+
+    /// Read unread notifications aloud (oldest first) and mark them read
+    pub async fn read_aloud(&self, is_danish: bool) -> String {
+        let mut unread = self.list(true, None).await;
+        if unread.is_empty() {
+            return if is_danish {
+                "Du har ingen ulæste notifikationer.".to_string()
+            } else {
+                "You have no unread notifications.".to_string()
+            };
+        }
+        unread.reverse();
+
+        let mut spoken = match (is_danish, unread.len()) {
+            (true, 1) => "Du har 1 ulæst notifikation.".to_string(),
+            (true, n) => format!("Du har {} ulæste notifikationer.", n),
+            (false, 1) => "You have 1 unread notification.".to_string(),
+            (false, n) => format!("You have {} unread notifications.", n),
+        };
+        for notification in unread.iter().take(MAX_SPOKEN) {
+            spoken.push_str(&format!(" {}: {}.", notification.title, notification.body.trim_end_matches('.')));
+        }
+        if unread.len() > MAX_SPOKEN {
+            spoken.push_str(if is_danish {
+                " Resten kan ses i notifikationscenteret."
+            } else {
+                " The rest are in the notification center."
+            });
+        }
+
+        let ids: Vec<String> = unread.iter().take(MAX_SPOKEN).map(|n| n.id.clone()).collect();
+        self.mark_read(Some(&ids)).await;
+        spoken
+    }
+
+    fn persist(&self, notifications: &[Notification]) {
+        let result = self
+            .path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&self.path, serde_json::to_string(notifications).unwrap_or_default()));
+        if let Err(e) = result {
+            log::warn!("Failed to persist notifications: {}", e);
+        }
+    }
+}
+
+impl Default for NotificationCenter {
+    fn default() -> Self {
+        Self::new(
+            dirs::data_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("notifications.json"),
+        )
+    }
+}
+
+fn count_unread(notifications: &[Notification]) -> usize {
+    notifications.iter().filter(|n| !n.read).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_center() -> (tempfile::TempDir, NotificationCenter) {
+        let dir = tempfile::tempdir().unwrap();
+        let center = NotificationCenter::new(dir.path().join("notifications.json"));
+        (dir, center)
+    }
+
+    #[tokio::test]
+    async fn test_unread_counts_and_mark_read() {
+        let (_dir, center) = temp_center();
+        let mut events = center.subscribe();
+        let first = center
+            .notify(NotificationCategory::Research, "Nyt fund", "Rust 2.0 annonceret", Vec::new())
+            .await;
+        center
+            .notify(NotificationCategory::Approval, "Godkendelse", "En beslutning venter", Vec::new())
+            .await;
+
+        let counts = center.unread_counts().await;
+        assert_eq!(counts.total, 2);
+        assert_eq!(counts.by_category[&NotificationCategory::Approval], 1);
+        assert!(matches!(events.recv().await.unwrap(), NotificationEvent::Added { unread: 1, .. }));
+
+        assert_eq!(center.mark_read(Some(std::slice::from_ref(&first.id))).await, 1);
+        assert_eq!(center.list(true, None).await.len(), 1);
+        assert_eq!(center.clear(None).await, 1);
+
+        // State survives a restart
+        let reopened = NotificationCenter::new(center.path.clone());
+        let remaining = reopened.list(false, None).await;
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].category, NotificationCategory::Approval);
+    }
+
+    #[tokio::test]
+    async fn test_read_aloud_marks_read() {
+        let (_dir, center) = temp_center();
+        assert_eq!(center.read_aloud(true).await, "Du har ingen ulæste notifikationer.");
+
+        center
+            .notify(NotificationCategory::Sync, "Synkronisering", "Synkronisering mislykkedes.", Vec::new())
+            .await;
+        assert_eq!(
+            center.read_aloud(false).await,
+            "You have 1 unread notification. Synkronisering: Synkronisering mislykkedes."
+        );
+        assert_eq!(center.unread_counts().await.total, 0);
+    }
+}
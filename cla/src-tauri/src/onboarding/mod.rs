@@ -0,0 +1,238 @@
+// Onboarding - First-run setup steps and the gate for background work
+// Steps are done in order; background subsystems wait until the required ones are finished
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tokio::sync::watch;
+
+const ONBOARDING_FILE: &str = "onboarding.json";
+
+/// Setup steps, in the order they are shown
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    /// Capability and telemetry decisions
+    Consent,
+    /// CKC endpoint and API key, or offline mode
+    Connection,
+    /// Which models to download
+    Models,
+    /// Voice and speech preferences
+    Accessibility,
+}
+
+impl OnboardingStep {
+    pub const ALL: [OnboardingStep; 4] = [Self::Consent, Self::Connection, Self::Models, Self::Accessibility];
+
+    /// Parse a step name as sent by the frontend
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Consent => "consent",
+            Self::Connection => "connection",
+            Self::Models => "models",
+            Self::Accessibility => "accessibility",
+        }
+    }
+
+    /// Required steps cannot be skipped and gate background work
+    pub fn required(&self) -> bool {
+        matches!(self, Self::Consent | Self::Connection)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Pending,
+    Completed,
+    Skipped,
+}
+
+/// A finished step as persisted
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct StepRecord {
+    step: OnboardingStep,
+    status: StepStatus,
+    finished_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepState {
+    pub step: OnboardingStep,
+    pub status: StepStatus,
+    pub required: bool,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Progress through onboarding, as shown by the frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OnboardingState {
+    pub steps: Vec<StepState>,
+    /// Next step to show; None when every step is finished
+    pub current: Option<OnboardingStep>,
+    /// Required steps are finished and background work may run
+    pub ready: bool,
+}
+
+/// Persisted onboarding progress
+pub struct Onboarding {
+    path: PathBuf,
+    steps: Mutex<HashMap<OnboardingStep, StepRecord>>,
+    ready_tx: watch::Sender<bool>,
+}
+
+impl Onboarding {
+    /// Load progress stored in `data_dir`
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(ONBOARDING_FILE);
+        let steps: HashMap<OnboardingStep, StepRecord> = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| {
+                serde_json::from_str::<Vec<StepRecord>>(&json)
+                    .map_err(|e| log::warn!("Ignoring invalid onboarding state: {}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|record| (record.step, record))
+            .collect();
+        let (ready_tx, _) = watch::channel(required_done(&steps));
+        Self {
+            path,
+            steps: Mutex::new(steps),
+            ready_tx,
+        }
+    }
+
+    pub fn state(&self) -> OnboardingState {
+        build_state(&self.steps.lock().unwrap())
+    }
+
+    /// Whether background subsystems may run
+    pub fn is_ready(&self) -> bool {
+        *self.ready_tx.borrow()
+    }
+
+    /// Receiver that changes when the required steps are finished or onboarding is reset
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.ready_tx.subscribe()
+    }
+
+    /// Finish the current step. Only the current step can be finished, and
+    /// required steps cannot be skipped.
+    pub fn complete_step(&self, step: OnboardingStep, skip: bool) -> Result<OnboardingState, String> {
+        let mut steps = self.steps.lock().unwrap();
+        if steps.contains_key(&step) {
+            return Err(format!("Trinnet {} er allerede gennemført", step.name()));
+        }
+        if let Some(current) = current_step(&steps) {
+            if current != step {
+                return Err(format!("Gennemfør trinnet {} først", current.name()));
+            }
+        }
+        if skip && step.required() {
+            return Err(format!("Trinnet {} kan ikke springes over", step.name()));
+        }
+
+        steps.insert(
+            step,
+            StepRecord {
+                step,
+                status: if skip { StepStatus::Skipped } else { StepStatus::Completed },
+                finished_at: Utc::now(),
+            },
+        );
+        self.persist(&steps)?;
+        self.ready_tx.send_replace(required_done(&steps));
+        log::info!("Onboarding step {} {}", step.name(), if skip { "skipped" } else { "completed" });
+        Ok(build_state(&steps))
+    }
+
+    /// Start onboarding over; background work waits again until it is finished
+    pub fn reset(&self) -> Result<OnboardingState, String> {
+        let mut steps = self.steps.lock().unwrap();
+        steps.clear();
+        self.persist(&steps)?;
+        self.ready_tx.send_replace(false);
+        Ok(build_state(&steps))
+    }
+
+    fn persist(&self, steps: &HashMap<OnboardingStep, StepRecord>) -> Result<(), String> {
+        let list: Vec<&StepRecord> = OnboardingStep::ALL.iter().filter_map(|s| steps.get(s)).collect();
+        let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Kunne ikke oprette mappe: {}", e))?;
+        }
+        std::fs::write(&self.path, json).map_err(|e| format!("Kunne ikke gemme opsætningen: {}", e))
+    }
+}
+
+fn current_step(steps: &HashMap<OnboardingStep, StepRecord>) -> Option<OnboardingStep> {
+    OnboardingStep::ALL.into_iter().find(|s| !steps.contains_key(s))
+}
+
+fn required_done(steps: &HashMap<OnboardingStep, StepRecord>) -> bool {
+    OnboardingStep::ALL
+        .iter()
+        .filter(|s| s.required())
+        .all(|s| steps.contains_key(s))
+}
+
+fn build_state(steps: &HashMap<OnboardingStep, StepRecord>) -> OnboardingState {
+    OnboardingState {
+        steps: OnboardingStep::ALL
+            .iter()
+            .map(|step| StepState {
+                step: *step,
+                status: steps.get(step).map_or(StepStatus::Pending, |r| r.status),
+                required: step.required(),
+                finished_at: steps.get(step).map(|r| r.finished_at),
+            })
+            .collect(),
+        current: current_step(steps),
+        ready: required_done(steps),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_steps_in_order_and_gate() {
+        let dir = tempfile::tempdir().unwrap();
+        let onboarding = Onboarding::load(dir.path());
+        let ready = onboarding.subscribe();
+        assert_eq!(onboarding.state().current, Some(OnboardingStep::Consent));
+        assert!(!onboarding.is_ready());
+
+        // Out of order, and skipping a required step
+        assert!(onboarding.complete_step(OnboardingStep::Models, false).is_err());
+        assert!(onboarding.complete_step(OnboardingStep::Consent, true).is_err());
+
+        onboarding.complete_step(OnboardingStep::Consent, false).unwrap();
+        let state = onboarding.complete_step(OnboardingStep::Connection, false).unwrap();
+        assert!(state.ready);
+        assert!(*ready.borrow());
+        assert_eq!(state.current, Some(OnboardingStep::Models));
+
+        onboarding.complete_step(OnboardingStep::Models, true).unwrap();
+
+        // Progress survives a restart
+        let onboarding = Onboarding::load(dir.path());
+        let state = onboarding.state();
+        assert!(onboarding.is_ready());
+        assert_eq!(state.current, Some(OnboardingStep::Accessibility));
+        assert_eq!(state.steps[2].status, StepStatus::Skipped);
+
+        onboarding.reset().unwrap();
+        assert!(!onboarding.is_ready());
+    }
+}
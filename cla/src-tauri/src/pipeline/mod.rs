@@ -0,0 +1,550 @@
+// Task Pipelines - Fixed inference flows declared as typed steps
+// Every step names the kind of value it takes and returns, so a pipeline is
+// checked before it runs; steps retry on their own and run in an inference lane
+
+use crate::inference::{InferenceEngine, InferenceLane, WhisperTask};
+use crate::models::LocalKnowledgeChunk;
+use crate::research::deep_analysis::chunk_text;
+use crate::research::KnowledgeStore;
+use crate::telemetry::TelemetryService;
+use crate::utils::timebox::{report_overrun, run_timeboxed, OverrunAction, TimeboxedWork};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Kind of value passed between steps
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueKind {
+    File,
+    Text,
+    Chunks,
+    Embedded,
+    Stored,
+}
+
+/// Value passed between steps
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum PipelineValue {
+    File(PathBuf),
+    Text(String),
+    Chunks(Vec<String>),
+    /// Chunks with their embeddings
+    Embedded(Vec<(String, Vec<f32>)>),
+    Stored { source_id: String, chunks: usize },
+}
+
+impl PipelineValue {
+    pub fn kind(&self) -> ValueKind {
+        match self {
+            Self::File(_) => ValueKind::File,
+            Self::Text(_) => ValueKind::Text,
+            Self::Chunks(_) => ValueKind::Chunks,
+            Self::Embedded(_) => ValueKind::Embedded,
+            Self::Stored { .. } => ValueKind::Stored,
+        }
+    }
+}
+
+/// One operation of a pipeline
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PipelineStep {
+    /// Audio file to text; `task: "translate"` yields English text
+    Transcribe {
+        #[serde(default)]
+        language: Option<String>,
+        #[serde(default)]
+        task: WhisperTask,
+    },
+    /// Image file to text (OCR)
+    ExtractText,
+    /// Text to overlapping chunks
+    Chunk { max_chars: usize, overlap: usize },
+    /// Chunks to embedded chunks
+    Embed,
+    /// Embedded chunks into the local knowledge store
+    Store {
+        #[serde(default)]
+        source_id: Option<String>,
+    },
+}
+
+impl PipelineStep {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Transcribe { .. } => "transcribe",
+            Self::ExtractText => "extract_text",
+            Self::Chunk { .. } => "chunk",
+            Self::Embed => "embed",
+            Self::Store { .. } => "store",
+        }
+    }
+
+    pub fn input(&self) -> ValueKind {
+        match self {
+            Self::Transcribe { .. } | Self::ExtractText => ValueKind::File,
+            Self::Chunk { .. } => ValueKind::Text,
+            Self::Embed => ValueKind::Chunks,
+            Self::Store { .. } => ValueKind::Embedded,
+        }
+    }
+
+    pub fn output(&self) -> ValueKind {
+        match self {
+            Self::Transcribe { .. } | Self::ExtractText => ValueKind::Text,
+            Self::Chunk { .. } => ValueKind::Chunks,
+            Self::Embed => ValueKind::Embedded,
+            Self::Store { .. } => ValueKind::Stored,
+        }
+    }
+
+    /// Whether a failure is worth another attempt (chunking is deterministic)
+    fn retryable(&self) -> bool {
+        !matches!(self, Self::Chunk { .. })
+    }
+}
+
+/// How often a step is attempted; the wait doubles after each failure
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub backoff_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            backoff_ms: 500,
+        }
+    }
+}
+
+/// A step with its retry policy
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StepSpec {
+    #[serde(flatten)]
+    pub step: PipelineStep,
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+impl From<PipelineStep> for StepSpec {
+    fn from(step: PipelineStep) -> Self {
+        Self {
+            step,
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// A named sequence of steps
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PipelineDefinition {
+    pub name: String,
+    /// Inference lane every step runs in
+    #[serde(default = "default_lane")]
+    pub lane: InferenceLane,
+    pub steps: Vec<StepSpec>,
+}
+
+fn default_lane() -> InferenceLane {
+    InferenceLane::Background
+}
+
+impl PipelineDefinition {
+    pub fn new(name: &str, steps: Vec<PipelineStep>) -> Self {
+        Self {
+            name: name.to_string(),
+            lane: default_lane(),
+            steps: steps.into_iter().map(StepSpec::from).collect(),
+        }
+    }
+
+    /// Recording → transcript → searchable knowledge
+    pub fn voice_memo() -> Self {
+        Self::new(
+            "voice_memo",
+            vec![
+                PipelineStep::Transcribe { language: None, task: WhisperTask::Transcribe },
+                PipelineStep::Chunk { max_chars: 1200, overlap: 200 },
+                PipelineStep::Embed,
+                PipelineStep::Store { source_id: None },
+            ],
+        )
+    }
+
+    /// Scanned page → text → searchable knowledge
+    pub fn document_scan() -> Self {
+        Self::new(
+            "document_scan",
+            vec![
+                PipelineStep::ExtractText,
+                PipelineStep::Chunk { max_chars: 1200, overlap: 200 },
+                PipelineStep::Embed,
+                PipelineStep::Store { source_id: None },
+            ],
+        )
+    }
+
+    pub fn builtin() -> Vec<Self> {
+        vec![Self::voice_memo(), Self::document_scan()]
+    }
+
+    /// Kind of value the first step takes
+    pub fn input_kind(&self) -> Option<ValueKind> {
+        self.steps.first().map(|spec| spec.step.input())
+    }
+
+    /// Check that every step accepts what the previous one produces
+    pub fn validate(&self, input: ValueKind) -> Result<(), PipelineError> {
+        if self.steps.is_empty() {
+            return Err(PipelineError::Empty);
+        }
+        let mut current = input;
+        for spec in &self.steps {
+            if spec.step.input() != current {
+                return Err(PipelineError::TypeMismatch {
+                    step: spec.step.name(),
+                    expected: spec.step.input(),
+                    found: current,
+                });
+            }
+            if let PipelineStep::Chunk { max_chars, overlap } = spec.step {
+                if max_chars == 0 || overlap >= max_chars {
+                    return Err(PipelineError::InvalidStep {
+                        step: spec.step.name(),
+                        reason: "overlap must be smaller than max_chars".to_string(),
+                    });
+                }
+            }
+            current = spec.step.output();
+        }
+        Ok(())
+    }
+}
+
+/// Models and storage the steps run against
+#[async_trait]
+pub trait PipelineBackend: Send + Sync {
+    async fn transcribe(&self, lane: InferenceLane, path: &Path, language: Option<&str>, task: WhisperTask) -> Result<String, String>;
+    async fn extract_text(&self, lane: InferenceLane, path: &Path) -> Result<String, String>;
+    async fn embed(&self, lane: InferenceLane, text: &str) -> Result<Vec<f32>, String>;
+    async fn store(&self, pipeline: &str, source_id: &str, chunks: &[(String, Vec<f32>)]) -> Result<(), String>;
+}
+
+/// Backend using the shared inference engine and knowledge store
+pub struct EngineBackend {
+    inference: Arc<RwLock<Option<InferenceEngine>>>,
+    knowledge: Arc<KnowledgeStore>,
+}
+
+impl EngineBackend {
+    pub fn new(inference: Arc<RwLock<Option<InferenceEngine>>>, knowledge: Arc<KnowledgeStore>) -> Self {
+        Self { inference, knowledge }
+    }
+}
+
+#[async_trait]
+impl PipelineBackend for EngineBackend {
+    async fn transcribe(&self, lane: InferenceLane, path: &Path, language: Option<&str>, task: WhisperTask) -> Result<String, String> {
+        let engine = self.inference.read().await;
+        let engine = engine.as_ref().ok_or("Inference engine not initialized")?;
+        Ok(engine.transcribe_in(lane, &path.to_string_lossy(), language, task).await?.text)
+    }
+
+    async fn extract_text(&self, _lane: InferenceLane, path: &Path) -> Result<String, String> {
+        let engine = self.inference.read().await;
+        let engine = engine.as_ref().ok_or("Inference engine not initialized")?;
+        Ok(engine.extract_text(&path.to_string_lossy()).await?.text)
+    }
+
+    async fn embed(&self, lane: InferenceLane, text: &str) -> Result<Vec<f32>, String> {
+        let engine = self.inference.read().await;
+        let engine = engine.as_ref().ok_or("Inference engine not initialized")?;
+        engine.generate_embedding_in(lane, text).await
+    }
+
+    async fn store(&self, pipeline: &str, source_id: &str, chunks: &[(String, Vec<f32>)]) -> Result<(), String> {
+        let chunks = chunks
+            .iter()
+            .enumerate()
+            .map(|(index, (content, embedding))| LocalKnowledgeChunk {
+                id: uuid::Uuid::new_v4(),
+                source_id: source_id.to_string(),
+                content: content.clone(),
+                embedding_local: embedding.clone(),
+                metadata: serde_json::json!({ "pipeline": pipeline, "chunk_index": index }),
+                priority: 5,
+                expires_at: None,
+            })
+            .collect();
+        self.knowledge
+            .replace_source(source_id, chunks)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Outcome of one step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StepReport {
+    pub step: String,
+    pub attempts: u32,
+    pub duration_ms: u64,
+}
+
+/// Outcome of a pipeline run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineReport {
+    pub pipeline: String,
+    pub steps: Vec<StepReport>,
+    pub output: PipelineValue,
+}
+
+/// Runs pipeline definitions against a backend
+pub struct PipelineRunner {
+    backend: Arc<dyn PipelineBackend>,
+    telemetry: Option<Arc<TelemetryService>>,
+}
+
+impl PipelineRunner {
+    pub fn new(backend: Arc<dyn PipelineBackend>) -> Self {
+        Self {
+            backend,
+            telemetry: None,
+        }
+    }
+
+    /// Report steps that overrun their time budget
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    pub async fn run(&self, definition: &PipelineDefinition, input: PipelineValue) -> Result<PipelineReport, PipelineError> {
+        definition.validate(input.kind())?;
+
+        let mut value = input;
+        let mut steps = Vec::with_capacity(definition.steps.len());
+        for spec in &definition.steps {
+            let started = Instant::now();
+            let mut attempts = 0;
+            let mut backoff = Duration::from_millis(spec.retry.backoff_ms);
+            value = loop {
+                attempts += 1;
+                match self.run_step(definition, &spec.step, value.clone()).await {
+                    Ok(output) => break output,
+                    Err(reason) if attempts >= spec.retry.max_attempts.max(1) || !spec.step.retryable() => {
+                        return Err(PipelineError::StepFailed {
+                            step: spec.step.name(),
+                            attempts,
+                            reason,
+                        });
+                    }
+                    Err(reason) => {
+                        log::warn!(
+                            "Pipeline {} step {} failed (attempt {}): {}",
+                            definition.name,
+                            spec.step.name(),
+                            attempts,
+                            reason
+                        );
+                        tokio::time::sleep(backoff).await;
+                        backoff *= 2;
+                    }
+                }
+            };
+            steps.push(StepReport {
+                step: spec.step.name().to_string(),
+                attempts,
+                duration_ms: started.elapsed().as_millis() as u64,
+            });
+        }
+
+        log::info!("Pipeline {} finished {} steps", definition.name, steps.len());
+        Ok(PipelineReport {
+            pipeline: definition.name.clone(),
+            steps,
+            output: value,
+        })
+    }
+
+    /// Run one step within the inference time budget
+    async fn run_step(&self, definition: &PipelineDefinition, step: &PipelineStep, input: PipelineValue) -> Result<PipelineValue, String> {
+        let call = self.execute(definition, step, input);
+        match run_timeboxed(TimeboxedWork::Inference, step.name(), TimeboxedWork::Inference.budget(), call).await {
+            Ok(result) => result,
+            Err(overrun) => {
+                report_overrun(self.telemetry.as_deref(), &overrun, OverrunAction::Aborted).await;
+                Err(overrun.to_string())
+            }
+        }
+    }
+
+    async fn execute(&self, definition: &PipelineDefinition, step: &PipelineStep, input: PipelineValue) -> Result<PipelineValue, String> {
+        let lane = definition.lane;
+        match (step, input) {
+            (PipelineStep::Transcribe { language, task }, PipelineValue::File(path)) => {
+                self.backend.transcribe(lane, &path, language.as_deref(), *task).await.map(PipelineValue::Text)
+            }
+            (PipelineStep::ExtractText, PipelineValue::File(path)) => {
+                self.backend.extract_text(lane, &path).await.map(PipelineValue::Text)
+            }
+            (PipelineStep::Chunk { max_chars, overlap }, PipelineValue::Text(text)) => {
+                Ok(PipelineValue::Chunks(chunk_text(&text, *max_chars, *overlap)))
+            }
+            (PipelineStep::Embed, PipelineValue::Chunks(chunks)) => {
+                let mut embedded = Vec::with_capacity(chunks.len());
+                for chunk in chunks {
+                    let embedding = self.backend.embed(lane, &chunk).await?;
+                    embedded.push((chunk, embedding));
+                }
+                Ok(PipelineValue::Embedded(embedded))
+            }
+            (PipelineStep::Store { source_id }, PipelineValue::Embedded(chunks)) => {
+                let source_id = source_id
+                    .clone()
+                    .unwrap_or_else(|| format!("{}:{}", definition.name, uuid::Uuid::new_v4()));
+                self.backend.store(&definition.name, &source_id, &chunks).await?;
+                Ok(PipelineValue::Stored {
+                    source_id,
+                    chunks: chunks.len(),
+                })
+            }
+            (step, input) => Err(format!("{} cannot take {:?}", step.name(), input.kind())),
+        }
+    }
+}
+
+/// Pipeline errors
+#[derive(Debug, thiserror::Error)]
+pub enum PipelineError {
+    #[error("Pipeline has no steps")]
+    Empty,
+    #[error("Step {step} expects {expected:?} but receives {found:?}")]
+    TypeMismatch {
+        step: &'static str,
+        expected: ValueKind,
+        found: ValueKind,
+    },
+    #[error("Step {step} is invalid: {reason}")]
+    InvalidStep { step: &'static str, reason: String },
+    #[error("Step {step} failed after {attempts} attempts: {reason}")]
+    StepFailed {
+        step: &'static str,
+        attempts: u32,
+        reason: String,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+
+    /// Backend whose transcription fails a given number of times first
+    #[derive(Default)]
+    struct FakeBackend {
+        transcribe_failures: AtomicU32,
+        stored: Mutex<Vec<(String, usize)>>,
+    }
+
+    #[async_trait]
+    impl PipelineBackend for FakeBackend {
+        async fn transcribe(&self, _lane: InferenceLane, _path: &Path, _language: Option<&str>, _task: WhisperTask) -> Result<String, String> {
+            if self.transcribe_failures.load(Ordering::SeqCst) > 0 {
+                self.transcribe_failures.fetch_sub(1, Ordering::SeqCst);
+                return Err("model busy".to_string());
+            }
+            Ok("Første punkt. Andet punkt. Tredje punkt.".to_string())
+        }
+
+        async fn extract_text(&self, _lane: InferenceLane, _path: &Path) -> Result<String, String> {
+            Ok("scanned".to_string())
+        }
+
+        async fn embed(&self, _lane: InferenceLane, text: &str) -> Result<Vec<f32>, String> {
+            Ok(vec![text.len() as f32])
+        }
+
+        async fn store(&self, _pipeline: &str, source_id: &str, chunks: &[(String, Vec<f32>)]) -> Result<(), String> {
+            self.stored.lock().unwrap().push((source_id.to_string(), chunks.len()));
+            Ok(())
+        }
+    }
+
+    fn fast_retry(mut definition: PipelineDefinition) -> PipelineDefinition {
+        for spec in &mut definition.steps {
+            spec.retry.backoff_ms = 1;
+        }
+        definition
+    }
+
+    #[tokio::test]
+    async fn test_voice_memo_retries_and_stores() {
+        let backend = Arc::new(FakeBackend::default());
+        backend.transcribe_failures.store(2, Ordering::SeqCst);
+        let runner = PipelineRunner::new(backend.clone());
+
+        let mut definition = fast_retry(PipelineDefinition::voice_memo());
+        definition.steps[3].step = PipelineStep::Store { source_id: Some("memo-1".to_string()) };
+        let report = runner
+            .run(&definition, PipelineValue::File(PathBuf::from("memo.wav")))
+            .await
+            .unwrap();
+
+        assert_eq!(report.steps[0].attempts, 3);
+        assert_eq!(report.output, PipelineValue::Stored { source_id: "memo-1".to_string(), chunks: 1 });
+        assert_eq!(backend.stored.lock().unwrap().as_slice(), [("memo-1".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn test_step_gives_up_after_max_attempts() {
+        let backend = Arc::new(FakeBackend::default());
+        backend.transcribe_failures.store(5, Ordering::SeqCst);
+        let runner = PipelineRunner::new(backend);
+
+        let err = runner
+            .run(&fast_retry(PipelineDefinition::voice_memo()), PipelineValue::File(PathBuf::from("memo.wav")))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PipelineError::StepFailed { step: "transcribe", attempts: 3, .. }));
+    }
+
+    #[test]
+    fn test_validation_catches_type_mismatch() {
+        let definition = PipelineDefinition::new("bad", vec![PipelineStep::ExtractText, PipelineStep::Embed]);
+        assert!(matches!(
+            definition.validate(ValueKind::File),
+            Err(PipelineError::TypeMismatch { step: "embed", expected: ValueKind::Chunks, found: ValueKind::Text })
+        ));
+        assert!(PipelineDefinition::document_scan().validate(ValueKind::File).is_ok());
+        assert!(PipelineDefinition::document_scan().validate(ValueKind::Text).is_err());
+    }
+
+    #[test]
+    fn test_definition_from_json() {
+        let json = r#"{
+            "name": "memo",
+            "steps": [
+                {"type": "transcribe", "language": "da", "task": "translate", "retry": {"max_attempts": 5, "backoff_ms": 100}},
+                {"type": "chunk", "max_chars": 800, "overlap": 100},
+                {"type": "embed"},
+                {"type": "store"}
+            ]
+        }"#;
+        let definition: PipelineDefinition = serde_json::from_str(json).unwrap();
+        assert_eq!(definition.lane, InferenceLane::Background);
+        assert_eq!(definition.steps[0].retry.max_attempts, 5);
+        assert!(matches!(definition.steps[0].step, PipelineStep::Transcribe { task: WhisperTask::Translate, .. }));
+        assert_eq!(definition.steps[2].retry, RetryPolicy::default());
+        assert!(definition.validate(ValueKind::File).is_ok());
+    }
+}
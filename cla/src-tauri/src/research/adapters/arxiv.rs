@@ -1,15 +1,18 @@
 // ArXiv Research Adapter
 // Searches academic papers from arXiv.org
 
+use super::common::QueryBuilder;
 use crate::commander::{ResearchFinding, ResearchSource};
 use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
 use async_trait::async_trait;
-use chrono::{DateTime, Utc, NaiveDateTime};
-use serde::Deserialize;
-
-/// ArXiv API uses Atom XML, but we'll parse key fields
-/// ArXiv API response entry
-#[derive(Debug)]
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use crate::research::http_cache::CachedSend;
+use crate::telemetry::network::NetworkSubsystem;
+
+/// ArXiv API response entry (Atom `<entry>` with the arxiv namespace extensions)
+#[derive(Debug, Default)]
 struct ArXivEntry {
     id: String,
     title: String,
@@ -18,7 +21,38 @@ struct ArXivEntry {
     published: String,
     updated: String,
     categories: Vec<String>,
+    primary_category: Option<String>,
+    /// Abstract page
     link: String,
+    pdf_url: Option<String>,
+    doi: Option<String>,
+    journal_ref: Option<String>,
+    /// Author comment, e.g. page count or venue
+    comment: Option<String>,
+}
+
+/// A `<link>` element of an entry
+#[derive(Debug, Default)]
+struct AtomLink {
+    href: String,
+    rel: Option<String>,
+    title: Option<String>,
+}
+
+impl AtomLink {
+    fn from_element(element: &BytesStart) -> Self {
+        let mut link = Self::default();
+        for attribute in element.attributes().flatten() {
+            let Ok(value) = attribute.unescape_value() else { continue };
+            match attribute.key.local_name().as_ref() {
+                b"href" => link.href = value.into_owned(),
+                b"rel" => link.rel = Some(value.into_owned()),
+                b"title" => link.title = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        link
+    }
 }
 
 /// ArXiv Research Adapter
@@ -43,98 +77,117 @@ impl ArXivAdapter {
         }
     }
 
-    /// Parse arXiv Atom XML response (simplified parsing)
+    /// Parse an arXiv Atom response. Tags are matched by local name, so the
+    /// `arxiv:` and `atom:` prefixes make no difference.
     fn parse_atom_response(xml: &str) -> Result<Vec<ArXivEntry>, String> {
+        let mut reader = Reader::from_str(xml);
         let mut entries = Vec::new();
-
-        // Split by <entry> tags
-        let entry_parts: Vec<&str> = xml.split("<entry>").skip(1).collect();
-
-        for entry_xml in entry_parts {
-            let entry_end = entry_xml.find("</entry>").unwrap_or(entry_xml.len());
-            let entry_content = &entry_xml[..entry_end];
-
-            // Parse ID
-            let id = Self::extract_tag(entry_content, "id")
-                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
-
-            // Parse title (remove newlines and extra whitespace)
-            let title = Self::extract_tag(entry_content, "title")
-                .map(|t| t.split_whitespace().collect::<Vec<_>>().join(" "))
-                .unwrap_or_else(|| "Untitled".to_string());
-
-            // Parse summary/abstract
-            let summary = Self::extract_tag(entry_content, "summary")
-                .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
-                .unwrap_or_default();
-
-            // Parse authors (simplified - just gets names)
-            let mut authors = Vec::new();
-            for author_section in entry_content.split("<author>").skip(1) {
-                if let Some(name) = Self::extract_tag(author_section, "name") {
-                    authors.push(name);
+        let mut entry: Option<ArXivEntry> = None;
+        let mut in_author = false;
+        let mut text = String::new();
+
+        loop {
+            match reader.read_event().map_err(|e| format!("at byte {}: {}", reader.error_position(), e))? {
+                Event::Start(element) => {
+                    text.clear();
+                    match element.local_name().as_ref() {
+                        b"entry" => entry = Some(ArXivEntry::default()),
+                        b"author" => in_author = true,
+                        _ => {}
+                    }
+                    if let Some(entry) = entry.as_mut() {
+                        Self::read_attributes(entry, &element);
+                    }
                 }
-            }
-
-            // Parse dates
-            let published = Self::extract_tag(entry_content, "published")
-                .unwrap_or_else(|| Utc::now().to_rfc3339());
-            let updated = Self::extract_tag(entry_content, "updated")
-                .unwrap_or_else(|| published.clone());
-
-            // Parse categories
-            let mut categories = Vec::new();
-            for cat_match in entry_content.match_indices("category term=\"") {
-                let start = cat_match.0 + "category term=\"".len();
-                if let Some(end) = entry_content[start..].find('"') {
-                    categories.push(entry_content[start..start + end].to_string());
+                Event::Empty(element) => {
+                    if let Some(entry) = entry.as_mut() {
+                        Self::read_attributes(entry, &element);
+                    }
                 }
+                Event::Text(content) => {
+                    text.push_str(&content.unescape().map_err(|e| e.to_string())?);
+                }
+                Event::CData(content) => {
+                    text.push_str(&content.decode().map_err(|e| e.to_string())?);
+                }
+                Event::End(element) => {
+                    let value = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    text.clear();
+                    let name = element.local_name();
+                    let Some(current) = entry.as_mut() else { continue };
+                    match name.as_ref() {
+                        b"entry" => {
+                            let mut done = entry.take().unwrap_or_default();
+                            // The API reports query errors as a single entry
+                            if done.id.contains("/api/errors") {
+                                return Err(format!("ArXiv API error: {}", done.summary));
+                            }
+                            if done.id.is_empty() {
+                                done.id = uuid::Uuid::new_v4().to_string();
+                            }
+                            if done.title.is_empty() {
+                                done.title = "Untitled".to_string();
+                            }
+                            if done.link.is_empty() {
+                                done.link = format!("https://arxiv.org/abs/{}", Self::short_id(&done.id));
+                            }
+                            if done.updated.is_empty() {
+                                done.updated = done.published.clone();
+                            }
+                            entries.push(done);
+                        }
+                        b"author" => in_author = false,
+                        b"name" if in_author => current.authors.push(value),
+                        b"id" => current.id = value,
+                        b"title" => current.title = value,
+                        b"summary" => current.summary = value,
+                        b"published" => current.published = value,
+                        b"updated" => current.updated = value,
+                        b"doi" => current.doi = Some(value),
+                        b"journal_ref" => current.journal_ref = Some(value),
+                        b"comment" => current.comment = Some(value),
+                        _ => {}
+                    }
+                }
+                Event::Eof => break,
+                _ => {}
             }
-
-            // Get PDF link
-            let link = Self::extract_link(entry_content)
-                .unwrap_or_else(|| format!("https://arxiv.org/abs/{}", id.split('/').last().unwrap_or(&id)));
-
-            entries.push(ArXivEntry {
-                id,
-                title,
-                summary,
-                authors,
-                published,
-                updated,
-                categories,
-                link,
-            });
         }
 
         Ok(entries)
     }
 
-    /// Extract content from XML tag
-    fn extract_tag(xml: &str, tag: &str) -> Option<String> {
-        let start_tag = format!("<{}>", tag);
-        let end_tag = format!("</{}>", tag);
-
-        let start = xml.find(&start_tag)?;
-        let content_start = start + start_tag.len();
-        let end = xml[content_start..].find(&end_tag)?;
-
-        Some(xml[content_start..content_start + end].trim().to_string())
-    }
-
-    /// Extract link from entry
-    fn extract_link(xml: &str) -> Option<String> {
-        // Look for the abstract page link
-        for link_match in xml.match_indices("href=\"") {
-            let start = link_match.0 + "href=\"".len();
-            if let Some(end) = xml[start..].find('"') {
-                let href = &xml[start..start + end];
-                if href.contains("arxiv.org/abs/") {
-                    return Some(href.to_string());
+    /// Categories and links carried in attributes
+    fn read_attributes(entry: &mut ArXivEntry, element: &BytesStart) {
+        let term = || {
+            element
+                .try_get_attribute("term")
+                .ok()
+                .flatten()
+                .and_then(|a| a.unescape_value().ok())
+                .map(|v| v.into_owned())
+        };
+        match element.local_name().as_ref() {
+            b"category" => entry.categories.extend(term()),
+            b"primary_category" => entry.primary_category = term(),
+            b"link" => {
+                let link = AtomLink::from_element(element);
+                match (link.title.as_deref(), link.rel.as_deref()) {
+                    (Some("pdf"), _) => entry.pdf_url = Some(link.href),
+                    (Some("doi"), _) if entry.doi.is_none() => {
+                        entry.doi = link.href.split("doi.org/").nth(1).map(str::to_string);
+                    }
+                    (None, Some("alternate")) => entry.link = link.href,
+                    _ => {}
                 }
             }
+            _ => {}
         }
-        None
+    }
+
+    /// `2401.00001v1` of `http://arxiv.org/abs/2401.00001v1`
+    fn short_id(id: &str) -> &str {
+        id.split("/abs/").last().unwrap_or(id)
     }
 
     /// Calculate relevance score for arXiv paper
@@ -217,8 +270,8 @@ impl ArXivAdapter {
         let summary = format!(
             "Authors: {}\n\n{}",
             authors_str,
-            if entry.summary.len() > 500 {
-                format!("{}...", &entry.summary[..500])
+            if entry.summary.chars().count() > 500 {
+                format!("{}...", entry.summary.chars().take(500).collect::<String>())
             } else {
                 entry.summary.clone()
             }
@@ -236,10 +289,34 @@ impl ArXivAdapter {
             metadata: serde_json::json!({
                 "authors": entry.authors,
                 "categories": entry.categories,
+                "primary_category": entry.primary_category,
                 "published": entry.published,
                 "updated": entry.updated,
+                "pdf_url": entry.pdf_url,
+                "doi": entry.doi,
+                "journal_ref": entry.journal_ref,
+                "comment": entry.comment,
             }),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    /// arXiv search syntax (`all:term`, `ti:title`, `cat:cs.AI`, AND/OR/ANDNOT):
+    /// plain terms must all match; queries already in that syntax pass through
+    fn build_search_query(query: &str) -> String {
+        const FIELDS: &[&str] = &["all:", "ti:", "au:", "abs:", "co:", "jr:", "cat:", "rn:", "id:"];
+        let advanced = query.split_whitespace().any(|term| {
+            matches!(term, "AND" | "OR" | "ANDNOT") || FIELDS.iter().any(|field| term.starts_with(field))
+        });
+        if advanced {
+            return query.trim().to_string();
         }
+        query
+            .split_whitespace()
+            .map(|term| format!("all:{}", term))
+            .collect::<Vec<_>>()
+            .join(" AND ")
     }
 
     /// Build arXiv sort parameter
@@ -271,9 +348,12 @@ impl ResearchAdapter for ArXivAdapter {
 
     async fn validate(&self) -> ResearchResult<()> {
         // Test with a simple query
-        let url = format!("{}?search_query=all:test&max_results=1", self.base_url);
+        let url = QueryBuilder::new(self.base_url.as_str())
+            .param("search_query", "all:test")
+            .param("max_results", "1")
+            .build();
 
-        match self.client.get(&url).send().await {
+        match self.client.get(&url).send_cached(NetworkSubsystem::Research).await {
             Ok(response) => {
                 if response.status().is_success() {
                     Ok(())
@@ -304,21 +384,16 @@ impl ResearchAdapter for ArXivAdapter {
             _ => "descending",
         };
 
-        // URL encode the query
-        let encoded_query = query
-            .replace(' ', "+AND+")
-            .replace(':', "%3A");
-
-        // Build arXiv API URL
-        // search_query format: all:query or ti:title or abs:abstract
-        let url = format!(
-            "{}?search_query=all:{}&max_results={}&sortBy={}&sortOrder={}",
-            self.base_url, encoded_query, limit, sort_by, sort_order
-        );
+        let url = QueryBuilder::new(self.base_url.as_str())
+            .param("search_query", Self::build_search_query(query))
+            .param("max_results", limit.to_string())
+            .param("sortBy", sort_by)
+            .param("sortOrder", sort_order)
+            .build();
 
         log::debug!("ArXiv API URL: {}", url);
 
-        let response = self.client.get(&url).send().await.map_err(|e| {
+        let response = self.client.get(&url).send_cached(NetworkSubsystem::Research).await.map_err(|e| {
             ResearchError::NetworkError(format!("ArXiv API request failed: {}", e))
         })?;
 
@@ -380,3 +455,89 @@ impl ResearchAdapter for ArXivAdapter {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:arxiv="http://arxiv.org/schemas/atom">
+  <title type="html">ArXiv Query: search_query=all:agents</title>
+  <id>http://arxiv.org/api/cHxbiOdZaP56ODnBPIenZhzg5f8</id>
+  <entry>
+    <id>http://arxiv.org/abs/2401.00001v2</id>
+    <updated>2024-01-05T10:00:00Z</updated>
+    <published>2024-01-01T09:30:00Z</published>
+    <title>Tool Use &amp; Planning in
+      LLM Agents</title>
+    <summary><![CDATA[We study <agents> that plan.]]>   More text.</summary>
+    <author><name>Ada Lovelace</name><arxiv:affiliation>Analytical Engines</arxiv:affiliation></author>
+    <author><name>Alan Turing</name></author>
+    <arxiv:doi>10.1000/xyz123</arxiv:doi>
+    <link title="doi" href="http://dx.doi.org/10.1000/xyz123" rel="related"/>
+    <arxiv:comment>12 pages, 3 figures</arxiv:comment>
+    <arxiv:journal_ref>J. Agents 1 (2024) 1-12</arxiv:journal_ref>
+    <link href="http://arxiv.org/abs/2401.00001v2" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/2401.00001v2" rel="related" type="application/pdf"/>
+    <arxiv:primary_category term="cs.AI" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.AI" scheme="http://arxiv.org/schemas/atom"/>
+    <category term="cs.LG" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/2401.00002v1</id>
+    <published>2024-01-02T09:30:00Z</published>
+    <title>Second</title>
+    <summary>Plain</summary>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_parse_entries_with_arxiv_fields() {
+        let entries = ArXivAdapter::parse_atom_response(RESPONSE).unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let entry = &entries[0];
+        assert_eq!(entry.title, "Tool Use & Planning in LLM Agents");
+        assert_eq!(entry.summary, "We study <agents> that plan. More text.");
+        assert_eq!(entry.authors, vec!["Ada Lovelace".to_string(), "Alan Turing".to_string()]);
+        assert_eq!(entry.categories, vec!["cs.AI".to_string(), "cs.LG".to_string()]);
+        assert_eq!(entry.primary_category.as_deref(), Some("cs.AI"));
+        assert_eq!(entry.doi.as_deref(), Some("10.1000/xyz123"));
+        assert_eq!(entry.journal_ref.as_deref(), Some("J. Agents 1 (2024) 1-12"));
+        assert_eq!(entry.comment.as_deref(), Some("12 pages, 3 figures"));
+        assert_eq!(entry.link, "http://arxiv.org/abs/2401.00001v2");
+        assert_eq!(entry.pdf_url.as_deref(), Some("http://arxiv.org/pdf/2401.00001v2"));
+
+        // Missing fields fall back
+        assert_eq!(entries[1].link, "https://arxiv.org/abs/2401.00002v1");
+        assert_eq!(entries[1].updated, entries[1].published);
+        assert!(entries[1].doi.is_none());
+
+        let finding = ArXivAdapter::entry_to_finding(entries.into_iter().next().unwrap(), "agents");
+        assert_eq!(finding.id, "arxiv-2401.00001v2");
+        assert_eq!(finding.metadata["doi"], "10.1000/xyz123");
+        assert_eq!(crate::research::deep_analysis::pdf_url(&finding).as_deref(), Some("http://arxiv.org/pdf/2401.00001v2"));
+    }
+
+    #[test]
+    fn test_search_query_encoding() {
+        assert_eq!(ArXivAdapter::build_search_query("c++ graph  neural"), "all:c++ AND all:graph AND all:neural");
+        assert_eq!(ArXivAdapter::build_search_query("cat:cs.AI OR cat:cs.LG"), "cat:cs.AI OR cat:cs.LG");
+
+        let url = QueryBuilder::new("http://export.arxiv.org/api/query")
+            .param("search_query", ArXivAdapter::build_search_query("R&D c++"))
+            .build();
+        assert_eq!(url, "http://export.arxiv.org/api/query?search_query=all%3AR%26D%20AND%20all%3Ac%2B%2B");
+    }
+
+    #[test]
+    fn test_api_errors_and_bad_xml_are_reported() {
+        let error = r#"<feed xmlns="http://www.w3.org/2005/Atom"><entry>
+            <id>http://arxiv.org/api/errors#incorrect_id_format_for_1234</id>
+            <title>Error</title>
+            <summary>incorrect id format for 1234</summary>
+        </entry></feed>"#;
+        assert!(ArXivAdapter::parse_atom_response(error).unwrap_err().contains("incorrect id format"));
+        assert!(ArXivAdapter::parse_atom_response("<feed><entry><title>x</entry></feed>").is_err());
+    }
+}
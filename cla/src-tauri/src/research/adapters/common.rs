@@ -5,6 +5,8 @@ use crate::research::traits::{ResearchResult, ResearchError};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
+use crate::research::http_cache::CachedSend;
+use crate::telemetry::network::NetworkSubsystem;
 
 /// Common configuration for adapters
 #[derive(Debug, Clone)]
@@ -135,7 +137,7 @@ impl HttpHelper {
         }
 
         request
-            .send()
+            .send_cached(NetworkSubsystem::Research)
             .await
             .map_err(|e| ResearchError::NetworkError(e.to_string()))
     }
@@ -159,7 +161,7 @@ impl HttpHelper {
         }
 
         request
-            .send()
+            .send_cached(NetworkSubsystem::Research)
             .await
             .map_err(|e| ResearchError::NetworkError(e.to_string()))
     }
@@ -184,6 +186,60 @@ pub fn parse_relevance(score: Option<f64>, max_score: f64) -> f32 {
     }
 }
 
+/// Score social engagement (0.0-1.0) on a log scale; shares and replies
+/// weigh more than likes since they signal discussion
+#[cfg(feature = "social")]
+pub fn engagement_score(likes: u32, shares: u32, replies: u32) -> f32 {
+    let weighted = likes as f64 + shares as f64 * 2.0 + replies as f64 * 3.0;
+    ((1.0 + weighted).ln() / 1001f64.ln()).min(1.0) as f32
+}
+
+/// Percent-encode a URL query component (RFC 3986): unreserved characters are
+/// kept, everything else, including spaces, `&`, `+` and non-ASCII, becomes `%XX`
+pub fn encode_component(input: &str) -> String {
+    let mut encoded = String::with_capacity(input.len());
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+/// Builds `base?key=value&...` with every key and value percent-encoded
+#[derive(Debug, Clone)]
+pub struct QueryBuilder {
+    url: String,
+    has_query: bool,
+}
+
+impl QueryBuilder {
+    /// Start from a URL, which may already carry a query
+    pub fn new(base: impl Into<String>) -> Self {
+        let url = base.into();
+        let has_query = url.contains('?');
+        Self { url, has_query }
+    }
+
+    /// Append a parameter
+    pub fn param(mut self, key: &str, value: impl AsRef<str>) -> Self {
+        self.url.push(if self.has_query { '&' } else { '?' });
+        self.has_query = true;
+        self.url.push_str(&encode_component(key));
+        self.url.push('=');
+        self.url.push_str(&encode_component(value.as_ref()));
+        self
+    }
+
+    /// The finished URL
+    pub fn build(self) -> String {
+        self.url
+    }
+}
+
 /// Sanitize search query for API
 pub fn sanitize_query(query: &str) -> String {
     query
@@ -205,6 +261,27 @@ mod tests {
         assert_eq!(sanitize_query("test<script>"), "testscript");
     }
 
+    #[test]
+    fn test_encode_component() {
+        assert_eq!(encode_component("rust-lang_1.0~"), "rust-lang_1.0~");
+        assert_eq!(encode_component("c++ & go"), "c%2B%2B%20%26%20go");
+        assert_eq!(encode_component("a=b?c/d#e"), "a%3Db%3Fc%2Fd%23e");
+        assert_eq!(encode_component("søgning 日本"), "s%C3%B8gning%20%E6%97%A5%E6%9C%AC");
+        assert_eq!(encode_component("100%"), "100%25");
+    }
+
+    #[test]
+    fn test_query_builder() {
+        let url = QueryBuilder::new("https://api.example.com/search")
+            .param("q", "stars:>10 language:c++")
+            .param("per_page", 5.to_string())
+            .build();
+        assert_eq!(url, "https://api.example.com/search?q=stars%3A%3E10%20language%3Ac%2B%2B&per_page=5");
+
+        let url = QueryBuilder::new("https://example.com/feed?format=json").param("tag", "a&b").build();
+        assert_eq!(url, "https://example.com/feed?format=json&tag=a%26b");
+    }
+
     #[test]
     fn test_parse_relevance() {
         assert_eq!(parse_relevance(Some(5.0), 10.0), 0.5);
@@ -212,6 +289,14 @@ mod tests {
         assert_eq!(parse_relevance(None, 10.0), 0.5);
     }
 
+    #[cfg(feature = "social")]
+    #[test]
+    fn test_engagement_score() {
+        assert_eq!(engagement_score(0, 0, 0), 0.0);
+        assert!(engagement_score(10, 5, 0) < engagement_score(10, 5, 5));
+        assert_eq!(engagement_score(10_000, 0, 0), 1.0);
+    }
+
     #[tokio::test]
     async fn test_rate_limiter() {
         let limiter = RateLimiter::new(2, 1);
@@ -0,0 +1,143 @@
+// Adapter Credentials - API keys for research adapters
+// Keys come from environment variables or the local credentials file
+
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Environment variables checked for each adapter
+const ENV_KEYS: &[(&str, &str)] = &[
+    ("GitHub", "GITHUB_TOKEN"),
+    ("Farcaster", "NEYNAR_API_KEY"),
+    ("Lens", "LENS_ACCESS_TOKEN"),
+    ("Twitter", "TWITTER_BEARER_TOKEN"),
+    // Self-hosted nitter endpoint used instead of, or as fallback for, the API
+    ("Nitter", "NITTER_URL"),
+];
+
+/// Registry of API credentials keyed by adapter name
+#[derive(Debug)]
+pub struct CredentialsRegistry {
+    file: JournaledFile,
+    credentials: RwLock<HashMap<String, String>>,
+}
+
+impl CredentialsRegistry {
+    /// Open the registry, loading stored credentials from disk
+    pub fn new(path: PathBuf) -> Self {
+        // API keys are readable by the current user only
+        let file = JournaledFile::new("credentials", path).private();
+        // A damaged file is moved aside and reported; keys must be entered again
+        let credentials = file.load().ok().flatten().unwrap_or_default();
+
+        Self {
+            file,
+            credentials: RwLock::new(credentials),
+        }
+    }
+
+    /// Credential for an adapter; environment variables take precedence
+    pub fn get(&self, adapter: &str) -> Option<String> {
+        let from_env = ENV_KEYS
+            .iter()
+            .find(|(name, _)| *name == adapter)
+            .and_then(|(_, var)| std::env::var(var).ok())
+            .filter(|value| !value.is_empty());
+
+        from_env.or_else(|| {
+            self.credentials
+                .read()
+                .ok()
+                .and_then(|c| c.get(adapter).cloned())
+        })
+    }
+
+    /// Store a credential for an adapter
+    pub fn set(&self, adapter: &str, credential: &str) -> ResearchResult<()> {
+        if credential.trim().is_empty() {
+            return Err(ResearchError::ConfigError("Credential is empty".to_string()));
+        }
+        let mut credentials = self.write()?;
+        credentials.insert(adapter.to_string(), credential.trim().to_string());
+        self.persist(&credentials)
+    }
+
+    /// Remove the stored credential of an adapter
+    pub fn remove(&self, adapter: &str) -> ResearchResult<bool> {
+        let mut credentials = self.write()?;
+        let removed = credentials.remove(adapter).is_some();
+        self.persist(&credentials)?;
+        Ok(removed)
+    }
+
+    /// Names of adapters with a credential available
+    pub fn configured(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .credentials
+            .read()
+            .map(|c| c.keys().cloned().collect())
+            .unwrap_or_default();
+        for (name, _) in ENV_KEYS {
+            if !names.iter().any(|n| n == name) && self.get(name).is_some() {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        names
+    }
+
+    fn write(&self) -> ResearchResult<std::sync::RwLockWriteGuard<'_, HashMap<String, String>>> {
+        self.credentials
+            .write()
+            .map_err(|_| ResearchError::ConfigError("Credentials lock poisoned".to_string()))
+    }
+
+    fn persist(&self, credentials: &HashMap<String, String>) -> ResearchResult<()> {
+        let json = serde_json::to_string_pretty(credentials)
+            .map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        self.file
+            .write(json.as_bytes())
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))
+    }
+}
+
+impl Default for CredentialsRegistry {
+    fn default() -> Self {
+        Self::new(
+            dirs::config_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("credentials.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_and_remove_credential() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        let registry = CredentialsRegistry::new(path.clone());
+
+        assert!(registry.set("Example", "  ").is_err());
+        registry.set("Example", "secret").unwrap();
+        assert_eq!(registry.get("Example").as_deref(), Some("secret"));
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        // Reloaded from disk
+        let reopened = CredentialsRegistry::new(path.clone());
+        assert!(reopened.configured().contains(&"Example".to_string()));
+
+        assert!(reopened.remove("Example").unwrap());
+        assert_eq!(reopened.get("Example"), None);
+    }
+}
@@ -0,0 +1,307 @@
+// Farcaster Research Adapter
+// Searches casts through the Neynar API (requires an API key)
+
+use super::common::{engagement_score, QueryBuilder};
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::research::http_cache::CachedSend;
+use crate::telemetry::network::NetworkSubsystem;
+
+/// Maximum casts per page allowed by Neynar search
+const PAGE_SIZE: usize = 100;
+
+/// Maximum pages fetched per search
+const MAX_PAGES: usize = 3;
+
+/// Neynar API response structures
+#[derive(Debug, Deserialize)]
+struct CastSearchResponse {
+    result: CastSearchResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct CastSearchResult {
+    casts: Vec<Cast>,
+    next: Option<NextCursor>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NextCursor {
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cast {
+    hash: String,
+    text: String,
+    timestamp: String,
+    author: CastAuthor,
+    #[serde(default)]
+    reactions: CastReactions,
+    #[serde(default)]
+    replies: CastReplies,
+    channel: Option<CastChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CastAuthor {
+    fid: u64,
+    username: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CastReactions {
+    #[serde(default)]
+    likes_count: u32,
+    #[serde(default)]
+    recasts_count: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CastReplies {
+    #[serde(default)]
+    count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct CastChannel {
+    id: String,
+}
+
+/// Farcaster Research Adapter
+#[derive(Debug)]
+pub struct FarcasterAdapter {
+    client: reqwest::Client,
+    api_key: Option<String>,
+    base_url: String,
+}
+
+impl FarcasterAdapter {
+    /// Create a new Farcaster adapter
+    pub fn new(api_key: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            api_key,
+            base_url: "https://api.neynar.com/v2/farcaster".to_string(),
+        }
+    }
+
+    fn api_key(&self) -> ResearchResult<&str> {
+        self.api_key
+            .as_deref()
+            .ok_or_else(|| ResearchError::ConfigError("Neynar API key not configured".to_string()))
+    }
+
+    /// Fetch one page of search results
+    async fn fetch_page(
+        &self,
+        query: &str,
+        limit: usize,
+        cursor: Option<&str>,
+    ) -> ResearchResult<CastSearchResult> {
+        let mut url = QueryBuilder::new(format!("{}/cast/search", self.base_url))
+            .param("q", query)
+            .param("limit", limit.to_string());
+        if let Some(cursor) = cursor {
+            url = url.param("cursor", cursor);
+        }
+
+        let response = self
+            .client
+            .get(url.build())
+            .header("x-api-key", self.api_key()?)
+            .header("accept", "application/json")
+            .send_cached(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(format!("Neynar API request failed: {}", e)))?;
+
+        match response.status().as_u16() {
+            401 | 403 => return Err(ResearchError::ConfigError("Invalid Neynar API key".to_string())),
+            429 => {
+                let retry_after = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok());
+                return Err(ResearchError::RateLimited { retry_after_secs: retry_after });
+            }
+            status if !(200..300).contains(&status) => {
+                let text = response.text().await.unwrap_or_default();
+                return Err(ResearchError::ApiError { status, message: text });
+            }
+            _ => {}
+        }
+
+        let body: CastSearchResponse = response.json().await.map_err(|e| {
+            ResearchError::ParseError(format!("Failed to parse Neynar response: {}", e))
+        })?;
+
+        Ok(body.result)
+    }
+
+    /// Convert a cast to a ResearchFinding
+    fn cast_to_finding(cast: Cast, query: &str) -> ResearchFinding {
+        let engagement = engagement_score(
+            cast.reactions.likes_count,
+            cast.reactions.recasts_count,
+            cast.replies.count,
+        );
+
+        // Engagement dominates; a literal query match adds a bonus
+        let mut relevance_score = engagement * 0.8;
+        if cast.text.to_lowercase().contains(&query.to_lowercase()) {
+            relevance_score += 0.2;
+        }
+
+        let discovered_at = DateTime::parse_from_rfc3339(&cast.timestamp)
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+
+        let mut tags = vec!["farcaster".to_string()];
+        if let Some(channel) = &cast.channel {
+            tags.push(format!("channel:{}", channel.id));
+        }
+
+        let title: String = cast.text.lines().next().unwrap_or_default().chars().take(100).collect();
+        let short_hash: String = cast.hash.chars().take(10).collect();
+
+        ResearchFinding {
+            id: format!("farcaster-{}", cast.hash),
+            source: ResearchSource::Farcaster,
+            title: format!("@{}: {}", cast.author.username, title),
+            summary: cast.text,
+            relevance_score: relevance_score.min(1.0),
+            discovered_at,
+            tags,
+            url: Some(format!("https://warpcast.com/{}/{}", cast.author.username, short_hash)),
+            metadata: serde_json::json!({
+                "author_fid": cast.author.fid,
+                "likes": cast.reactions.likes_count,
+                "recasts": cast.reactions.recasts_count,
+                "replies": cast.replies.count,
+                "engagement_score": engagement,
+            }),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+}
+
+#[async_trait]
+impl ResearchAdapter for FarcasterAdapter {
+    fn name(&self) -> &str {
+        "Farcaster"
+    }
+
+    fn source(&self) -> ResearchSource {
+        ResearchSource::Farcaster
+    }
+
+    async fn validate(&self) -> ResearchResult<()> {
+        self.fetch_page("farcaster", 1, None).await.map(|_| ())
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> ResearchResult<Vec<ResearchFinding>> {
+        if query.trim().is_empty() {
+            return Err(ResearchError::InvalidQuery("Query cannot be empty".to_string()));
+        }
+
+        let limit = options.limit.unwrap_or(10);
+        let mut casts = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        // Follow the cursor until enough casts are collected
+        for _ in 0..MAX_PAGES {
+            let page = self
+                .fetch_page(query, (limit - casts.len()).min(PAGE_SIZE), cursor.as_deref())
+                .await?;
+            casts.extend(page.casts);
+
+            cursor = page.next.and_then(|n| n.cursor);
+            if casts.len() >= limit || cursor.is_none() {
+                break;
+            }
+        }
+
+        log::info!("Farcaster search returned {} casts", casts.len());
+
+        let mut findings: Vec<ResearchFinding> = casts
+            .into_iter()
+            .filter(|cast| {
+                options.since_timestamp.map_or(true, |since| {
+                    DateTime::parse_from_rfc3339(&cast.timestamp)
+                        .map_or(true, |dt| dt.timestamp() >= since)
+                })
+            })
+            .map(|cast| Self::cast_to_finding(cast, query))
+            .collect();
+
+        if let Some(min_rel) = options.min_relevance {
+            findings.retain(|f| f.relevance_score >= min_rel);
+        }
+
+        match options.sort_by {
+            Some(SortOrder::DateDesc) => findings.sort_by(|a, b| b.discovered_at.cmp(&a.discovered_at)),
+            Some(SortOrder::DateAsc) => findings.sort_by(|a, b| a.discovered_at.cmp(&b.discovered_at)),
+            _ => findings.sort_by(|a, b| {
+                b.relevance_score
+                    .partial_cmp(&a.relevance_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+        }
+
+        findings.truncate(limit);
+        Ok(findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cast_search_response() {
+        let json = r#"{
+            "result": {
+                "casts": [{
+                    "hash": "0xabcdef1234567890",
+                    "text": "Local-first agents are the future",
+                    "timestamp": "2024-05-01T12:00:00.000Z",
+                    "author": {"fid": 3, "username": "dwr"},
+                    "reactions": {"likes_count": 120, "recasts_count": 30},
+                    "replies": {"count": 12},
+                    "channel": {"id": "ai"}
+                }],
+                "next": {"cursor": "abc"}
+            }
+        }"#;
+        let response: CastSearchResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.result.next.unwrap().cursor.as_deref(), Some("abc"));
+
+        let cast = response.result.casts.into_iter().next().unwrap();
+        let finding = FarcasterAdapter::cast_to_finding(cast, "local-first");
+
+        assert_eq!(finding.url.as_deref(), Some("https://warpcast.com/dwr/0xabcdef12"));
+        assert!(finding.tags.contains(&"channel:ai".to_string()));
+        assert!(finding.relevance_score > 0.5);
+    }
+
+    #[tokio::test]
+    async fn test_validate_requires_api_key() {
+        let adapter = FarcasterAdapter::new(None);
+        assert!(matches!(adapter.validate().await, Err(ResearchError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,162 @@
+// Custom Feeds - RSS/Atom feeds the user follows, scanned as ResearchSource::CustomFeed
+// Feeds are kept in the local feeds file; each gets its own RssAdapter and schedule
+
+use crate::commander::ResearchSource;
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+/// Adapter names a feed may not take
+const RESERVED_NAMES: &[&str] = &["GitHub", "ArXiv", "Farcaster", "Lens", "Reddit", "Twitter", "Nitter"];
+
+/// A followed RSS or Atom feed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CustomFeed {
+    /// Unique name, shown as the finding source
+    pub name: String,
+    pub url: String,
+    /// Keywords entries are scored against; defaults to the name
+    #[serde(default)]
+    pub topic: Option<String>,
+    /// Minutes between scans (falls back to scan_interval_minutes)
+    #[serde(default)]
+    pub poll_interval_minutes: Option<u32>,
+    pub added_at: DateTime<Utc>,
+}
+
+impl CustomFeed {
+    pub fn source(&self) -> ResearchSource {
+        ResearchSource::CustomFeed(self.name.clone())
+    }
+
+    /// Topic of the feed's scan tasks
+    pub fn topic(&self) -> &str {
+        self.topic.as_deref().filter(|t| !t.trim().is_empty()).unwrap_or(&self.name)
+    }
+}
+
+/// Registry of followed feeds
+#[derive(Debug)]
+pub struct FeedRegistry {
+    file: JournaledFile,
+    feeds: RwLock<Vec<CustomFeed>>,
+}
+
+impl FeedRegistry {
+    /// Open the registry, loading stored feeds from disk
+    pub fn new(path: PathBuf) -> Self {
+        let file = JournaledFile::new("feeds", path);
+        // A damaged feed list is moved aside and reported; start without feeds
+        let feeds = file.load().ok().flatten().unwrap_or_default();
+
+        Self {
+            file,
+            feeds: RwLock::new(feeds),
+        }
+    }
+
+    /// All feeds, in the order they were added
+    pub fn list(&self) -> Vec<CustomFeed> {
+        self.feeds.read().map(|f| f.clone()).unwrap_or_default()
+    }
+
+    /// Follow a feed; names are unique
+    pub fn add(&self, feed: CustomFeed) -> ResearchResult<()> {
+        let name = feed.name.trim();
+        if name.is_empty() {
+            return Err(ResearchError::ConfigError("Feed name is empty".to_string()));
+        }
+        if RESERVED_NAMES.iter().any(|r| r.eq_ignore_ascii_case(name)) {
+            return Err(ResearchError::ConfigError(format!("\"{}\" is the name of a built-in source", name)));
+        }
+        if !(feed.url.starts_with("https://") || feed.url.starts_with("http://")) {
+            return Err(ResearchError::ConfigError("Feed URL must start with http:// or https://".to_string()));
+        }
+        if feed.poll_interval_minutes == Some(0) {
+            return Err(ResearchError::ConfigError("Poll interval must be at least one minute".to_string()));
+        }
+
+        let mut feeds = self.write()?;
+        if feeds.iter().any(|f| f.name.eq_ignore_ascii_case(name)) {
+            return Err(ResearchError::ConfigError(format!("A feed named \"{}\" already exists", name)));
+        }
+        feeds.push(CustomFeed {
+            name: name.to_string(),
+            url: feed.url.trim().to_string(),
+            ..feed
+        });
+        self.persist(&feeds)
+    }
+
+    /// Stop following a feed
+    pub fn remove(&self, name: &str) -> ResearchResult<bool> {
+        let mut feeds = self.write()?;
+        let before = feeds.len();
+        feeds.retain(|f| f.name != name);
+        let removed = feeds.len() < before;
+        self.persist(&feeds)?;
+        Ok(removed)
+    }
+
+    fn write(&self) -> ResearchResult<std::sync::RwLockWriteGuard<'_, Vec<CustomFeed>>> {
+        self.feeds
+            .write()
+            .map_err(|_| ResearchError::ConfigError("Feeds lock poisoned".to_string()))
+    }
+
+    fn persist(&self, feeds: &[CustomFeed]) -> ResearchResult<()> {
+        let json = serde_json::to_string_pretty(feeds).map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        self.file
+            .write(json.as_bytes())
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))
+    }
+}
+
+impl Default for FeedRegistry {
+    fn default() -> Self {
+        Self::new(
+            dirs::config_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("feeds.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(name: &str, url: &str) -> CustomFeed {
+        CustomFeed {
+            name: name.to_string(),
+            url: url.to_string(),
+            topic: None,
+            poll_interval_minutes: Some(15),
+            added_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_remove_feed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("feeds.json");
+        let registry = FeedRegistry::new(path.clone());
+
+        registry.add(feed(" Rust Blog ", "https://blog.rust-lang.org/feed.xml")).unwrap();
+        assert!(registry.add(feed("rust blog", "https://example.com/rss")).is_err());
+        assert!(registry.add(feed("GitHub", "https://example.com/rss")).is_err());
+        assert!(registry.add(feed("Local", "file:///etc/passwd")).is_err());
+
+        let reopened = FeedRegistry::new(path.clone());
+        assert_eq!(reopened.list().len(), 1);
+        assert_eq!(reopened.list()[0].source(), ResearchSource::CustomFeed("Rust Blog".to_string()));
+        assert_eq!(reopened.list()[0].topic(), "Rust Blog");
+
+        assert!(reopened.remove("Rust Blog").unwrap());
+        assert!(!reopened.remove("Rust Blog").unwrap());
+    }
+}
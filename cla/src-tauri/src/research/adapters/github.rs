@@ -1,11 +1,25 @@
 // GitHub Research Adapter
 // Searches GitHub repositories, trending repos, and discussions
 
+use super::common::{QueryBuilder, RateLimiter};
 use crate::commander::{ResearchFinding, ResearchSource};
 use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
 use serde::Deserialize;
+use std::sync::Mutex;
+use crate::research::http_cache::CachedSend;
+use crate::telemetry::network::{self, NetworkSubsystem};
+
+/// Maximum README size downloaded for enrichment
+const README_MAX_BYTES: usize = 64 * 1024;
+
+/// Maximum length of the README snippet added to the summary
+const README_SNIPPET_CHARS: usize = 600;
+
+/// Stop enriching when the core API quota drops to this many requests
+const README_QUOTA_RESERVE: u32 = 10;
 
 /// GitHub API response structures
 #[derive(Debug, Deserialize)]
@@ -31,12 +45,45 @@ struct GitHubRepo {
     pushed_at: Option<String>,
 }
 
+/// README enrichment settings
+#[derive(Debug)]
+struct ReadmeEnrichment {
+    /// Number of top findings per search to enrich
+    max_findings: usize,
+    rate_limiter: RateLimiter,
+    /// Core API quota reported by the last README response
+    quota: Mutex<Option<CoreQuota>>,
+}
+
+/// Requests left in the core API quota and when it resets
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CoreQuota {
+    remaining: u32,
+    reset_at: i64,
+}
+
+impl CoreQuota {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Option<Self> {
+        let number = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+        Some(Self {
+            remaining: u32::try_from(number("x-ratelimit-remaining")?).ok()?,
+            reset_at: number("x-ratelimit-reset")?,
+        })
+    }
+
+    /// Seconds until the quota resets, if it is down to the reserve until then
+    fn exhausted_for(&self, now: i64) -> Option<u64> {
+        (self.remaining <= README_QUOTA_RESERVE && now < self.reset_at).then(|| (self.reset_at - now) as u64)
+    }
+}
+
 /// GitHub Research Adapter
 #[derive(Debug)]
 pub struct GitHubAdapter {
     client: reqwest::Client,
     api_token: Option<String>,
     base_url: String,
+    readme: Option<ReadmeEnrichment>,
 }
 
 impl GitHubAdapter {
@@ -52,6 +99,120 @@ impl GitHubAdapter {
             client,
             api_token,
             base_url: "https://api.github.com".to_string(),
+            readme: None,
+        }
+    }
+
+    /// Enrich the top findings of each search with a README snippet
+    pub fn with_readme_enrichment(mut self, max_findings: usize) -> Self {
+        self.readme = Some(ReadmeEnrichment {
+            max_findings,
+            // Unauthenticated core API allows 60 requests/hour
+            rate_limiter: RateLimiter::new(30, 3600),
+            quota: Mutex::new(None),
+        });
+        self
+    }
+
+    /// Fetch the raw README of a repository, truncated to README_MAX_BYTES (None if
+    /// missing). Not sent while the core API quota is down to the reserve.
+    async fn fetch_readme(&self, readme: &ReadmeEnrichment, full_name: &str) -> ResearchResult<Option<String>> {
+        let quota = *readme.quota.lock().unwrap();
+        if let Some(retry_after) = quota.and_then(|quota| quota.exhausted_for(Utc::now().timestamp())) {
+            return Err(ResearchError::RateLimited { retry_after_secs: Some(retry_after) });
+        }
+
+        let url = format!("{}/repos/{}/readme", self.base_url, full_name);
+
+        let mut request = self.client.get(&url);
+        if let Some(token) = &self.api_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        request = request.header("Accept", "application/vnd.github.raw");
+
+        // Sent unmetered: send_metered buffers the whole body, and this one is
+        // read only up to the cap and metered per chunk instead
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+        network::meter().record(NetworkSubsystem::Research, url.len() as u64, 0);
+        if let Some(quota) = CoreQuota::from_headers(response.headers()) {
+            *readme.quota.lock().unwrap() = Some(quota);
+        }
+
+        match response.status().as_u16() {
+            403 | 429 => {
+                return Err(ResearchError::RateLimited { retry_after_secs: None });
+            }
+            404 => return Ok(None),
+            status if !(200..300).contains(&status) => {
+                return Err(ResearchError::ApiError {
+                    status,
+                    message: format!("Failed to fetch README for {}", full_name),
+                });
+            }
+            _ => {}
+        }
+
+        if response.content_length().is_some_and(|len| len as usize > README_MAX_BYTES) {
+            log::debug!("README of {} too large, skipping", full_name);
+            return Ok(None);
+        }
+
+        // Without a Content-Length, stop reading at the cap
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+            network::meter().record_received(NetworkSubsystem::Research, chunk.len() as u64);
+            body.extend_from_slice(&chunk);
+            if body.len() >= README_MAX_BYTES {
+                body.truncate(README_MAX_BYTES);
+                break;
+            }
+        }
+
+        Ok(Some(String::from_utf8_lossy(&body).to_string()))
+    }
+
+    /// Add README snippets to the top findings
+    async fn enrich_with_readmes(&self, findings: &mut [ResearchFinding], query: &str) {
+        let Some(readme) = &self.readme else { return };
+        let query_lower = query.to_lowercase();
+
+        for finding in findings.iter_mut().take(readme.max_findings) {
+            if !readme.rate_limiter.check().await {
+                log::debug!("README enrichment budget exhausted");
+                break;
+            }
+            readme.rate_limiter.record();
+
+            let markdown = match self.fetch_readme(readme, &finding.title).await {
+                Ok(Some(markdown)) => markdown,
+                Ok(None) => continue,
+                Err(ResearchError::RateLimited { .. }) => {
+                    log::info!("GitHub quota low, stopping README enrichment");
+                    break;
+                }
+                Err(e) => {
+                    log::debug!("README enrichment failed for {}: {}", finding.title, e);
+                    continue;
+                }
+            };
+
+            let snippet = extract_readme_snippet(&markdown, README_SNIPPET_CHARS);
+            if snippet.is_empty() {
+                continue;
+            }
+
+            if snippet.to_lowercase().contains(&query_lower) {
+                finding.relevance_score = (finding.relevance_score + 0.1).min(1.0);
+            }
+            finding.summary = format!("{}\n\n{}", finding.summary, snippet);
+            if let Some(metadata) = finding.metadata.as_object_mut() {
+                metadata.insert("readme_snippet".to_string(), serde_json::json!(snippet));
+            }
         }
     }
 
@@ -108,7 +269,7 @@ impl GitHubAdapter {
             score += 0.1;
         }
 
-        score.min(1.0).max(0.0)
+        score.clamp(0.0, 1.0)
     }
 
     /// Convert GitHub repo to ResearchFinding
@@ -140,6 +301,8 @@ impl GitHubAdapter {
                 "language": repo.language,
                 "created_at": repo.created_at,
             }),
+            score_breakdown: None,
+            scorer_version: None,
         }
     }
 }
@@ -163,7 +326,7 @@ impl ResearchAdapter for GitHubAdapter {
             request = request.header("Authorization", format!("Bearer {}", token));
         }
 
-        match request.send().await {
+        match request.send_cached(NetworkSubsystem::Research).await {
             Ok(response) => {
                 if response.status().is_success() {
                     Ok(())
@@ -203,14 +366,11 @@ impl ResearchAdapter for GitHubAdapter {
             }
         }
 
-        let url = format!(
-            "{}/search/repositories?q={}&per_page={}&{sort_key}={sort_value}",
-            self.base_url,
-            urlencoding::encode(&search_query),
-            limit,
-            sort_key = sort_key,
-            sort_value = sort_value
-        );
+        let url = QueryBuilder::new(format!("{}/search/repositories", self.base_url))
+            .param("q", &search_query)
+            .param("per_page", limit.to_string())
+            .param(sort_key, sort_value)
+            .build();
 
         let mut request = self.client.get(&url);
         if let Some(token) = &self.api_token {
@@ -218,7 +378,7 @@ impl ResearchAdapter for GitHubAdapter {
         }
         request = request.header("Accept", "application/vnd.github.v3+json");
 
-        let response = request.send().await.map_err(|e| {
+        let response = request.send_cached(NetworkSubsystem::Research).await.map_err(|e| {
             ResearchError::NetworkError(format!("GitHub API request failed: {}", e))
         })?;
 
@@ -273,6 +433,8 @@ impl ResearchAdapter for GitHubAdapter {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
+        self.enrich_with_readmes(&mut findings, query).await;
+
         Ok(findings)
     }
 
@@ -296,22 +458,126 @@ impl ResearchAdapter for GitHubAdapter {
     }
 }
 
-// URL encoding helper (minimal implementation)
-mod urlencoding {
-    pub fn encode(input: &str) -> String {
-        let mut encoded = String::new();
-        for byte in input.bytes() {
-            match byte {
-                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
-                    encoded.push(byte as char);
-                }
-                b' ' => encoded.push('+'),
-                _ => {
-                    encoded.push('%');
-                    encoded.push_str(&format!("{:02X}", byte));
-                }
+/// Extract the first meaningful paragraphs of a README
+///
+/// Skips headings, badges, images, HTML, code blocks and tables, and strips
+/// inline markdown so the text reads well in a summary.
+pub fn extract_readme_snippet(markdown: &str, max_chars: usize) -> String {
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_code = false;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+
+        let skip = in_code
+            || trimmed.starts_with('#')
+            || trimmed.starts_with("![")
+            || trimmed.starts_with("[![")
+            || trimmed.starts_with('<')
+            || trimmed.starts_with('|')
+            || trimmed.starts_with("---")
+            || trimmed.starts_with("===");
+
+        if trimmed.is_empty() || skip {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&strip_inline_markdown(trimmed));
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+
+    // Very short lines are usually taglines or navigation
+    let text = paragraphs
+        .into_iter()
+        .filter(|p| p.chars().filter(|c| c.is_alphabetic()).count() >= 40)
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    if text.chars().count() <= max_chars {
+        return text;
+    }
+
+    let truncated: String = text.chars().take(max_chars).collect();
+    match truncated.rfind(' ') {
+        Some(pos) => format!("{}…", &truncated[..pos]),
+        None => truncated,
+    }
+}
+
+/// Replace `[text](url)` with `text` and drop emphasis markers
+fn strip_inline_markdown(line: &str) -> String {
+    let line = line.trim_start_matches(['>', '-', '*', ' ']);
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+
+    while let Some(start) = rest.find('[') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match (after.find("]("), after.find(')')) {
+            (Some(close), Some(end)) if close < end => {
+                out.push_str(&after[..close]);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('[');
+                rest = after;
             }
         }
-        encoded
+    }
+    out.push_str(rest);
+
+    out.replace("**", "").replace("__", "").replace('`', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readme_quota_checked_before_sending() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", "10".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1000".parse().unwrap());
+        let quota = CoreQuota::from_headers(&headers).unwrap();
+
+        assert_eq!(quota.exhausted_for(400), Some(600));
+        assert_eq!(quota.exhausted_for(1000), None);
+        let plenty = CoreQuota { remaining: 11, ..quota };
+        assert_eq!(plenty.exhausted_for(400), None);
+        assert!(CoreQuota::from_headers(&reqwest::header::HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_extract_readme_snippet() {
+        let readme = "# Tokio\n\n[![Crates.io](https://img.shields.io/crates/v/tokio.svg)](https://crates.io)\n\nA runtime for writing **reliable**, asynchronous, and slim applications with\nthe [Rust programming language](https://rust-lang.org).\n\n```rust\nfn main() {}\n```\n\n## Overview\n\nTokio is an event-driven, non-blocking I/O platform for writing asynchronous applications.\n";
+        let snippet = extract_readme_snippet(readme, 600);
+
+        assert!(snippet.starts_with("A runtime for writing reliable, asynchronous"));
+        assert!(snippet.contains("the Rust programming language."));
+        assert!(snippet.contains("event-driven"));
+        assert!(!snippet.contains("fn main"));
+        assert!(!snippet.contains("shields.io"));
+    }
+
+    #[test]
+    fn test_snippet_truncated_at_word_boundary() {
+        let readme = "This paragraph is long enough to count as meaningful content for the summary.";
+        let snippet = extract_readme_snippet(readme, 30);
+        assert_eq!(snippet, "This paragraph is long enough…");
     }
 }
@@ -0,0 +1,340 @@
+// Lens Protocol Research Adapter
+// Searches posts through the public Lens GraphQL API
+
+use super::common::engagement_score;
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+
+/// Maximum pages fetched per search
+const MAX_PAGES: usize = 3;
+
+/// Publication search query (posts only)
+const SEARCH_QUERY: &str = r#"
+query SearchPublications($request: PublicationSearchRequest!) {
+  searchPublications(request: $request) {
+    items {
+      ... on Post {
+        id
+        createdAt
+        by { handle { localName } }
+        stats { reactions comments mirrors quotes }
+        metadata {
+          ... on TextOnlyMetadataV3 { content tags }
+          ... on ArticleMetadataV3 { title content tags }
+        }
+      }
+    }
+    pageInfo { next }
+  }
+}
+"#;
+
+/// Lens API response structures
+#[derive(Debug, Deserialize)]
+struct GraphQlResponse {
+    data: Option<SearchData>,
+    #[serde(default)]
+    errors: Vec<GraphQlError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphQlError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchData {
+    search_publications: SearchPage,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchPage {
+    items: Vec<LensPost>,
+    page_info: PageInfo,
+}
+
+#[derive(Debug, Deserialize)]
+struct PageInfo {
+    next: Option<String>,
+}
+
+/// Non-post publications deserialize with every field missing
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LensPost {
+    id: Option<String>,
+    created_at: Option<String>,
+    by: Option<LensProfile>,
+    stats: Option<LensStats>,
+    metadata: Option<LensMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LensProfile {
+    handle: Option<LensHandle>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LensHandle {
+    local_name: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LensStats {
+    #[serde(default)]
+    reactions: u32,
+    #[serde(default)]
+    comments: u32,
+    #[serde(default)]
+    mirrors: u32,
+    #[serde(default)]
+    quotes: u32,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LensMetadata {
+    title: Option<String>,
+    content: Option<String>,
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+/// Lens Protocol Research Adapter
+#[derive(Debug)]
+pub struct LensAdapter {
+    client: reqwest::Client,
+    access_token: Option<String>,
+    api_url: String,
+}
+
+impl LensAdapter {
+    /// Create a new Lens adapter (search works without an access token)
+    pub fn new(access_token: Option<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            access_token,
+            api_url: "https://api-v2.lens.dev".to_string(),
+        }
+    }
+
+    /// Fetch one page of search results
+    async fn fetch_page(&self, query: &str, cursor: Option<&str>) -> ResearchResult<SearchPage> {
+        let body = serde_json::json!({
+            "query": SEARCH_QUERY,
+            "variables": {
+                "request": {
+                    "query": query,
+                    "limit": "TwentyFive",
+                    "cursor": cursor,
+                    "where": { "publicationTypes": ["POST"] },
+                }
+            }
+        });
+
+        let mut request = self.client.post(&self.api_url).json(&body);
+        if let Some(token) = &self.access_token {
+            request = request.header("x-access-token", format!("Bearer {}", token));
+        }
+
+        let response = request
+            .send_metered(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(format!("Lens API request failed: {}", e)))?;
+
+        match response.status().as_u16() {
+            401 | 403 => return Err(ResearchError::ConfigError("Invalid Lens access token".to_string())),
+            429 => return Err(ResearchError::RateLimited { retry_after_secs: None }),
+            status if !(200..300).contains(&status) => {
+                let text = response.text().await.unwrap_or_default();
+                return Err(ResearchError::ApiError { status, message: text });
+            }
+            _ => {}
+        }
+
+        let body: GraphQlResponse = response.json().await.map_err(|e| {
+            ResearchError::ParseError(format!("Failed to parse Lens response: {}", e))
+        })?;
+
+        if let Some(error) = body.errors.first() {
+            return Err(ResearchError::ApiError { status: 200, message: error.message.clone() });
+        }
+
+        body.data
+            .map(|d| d.search_publications)
+            .ok_or_else(|| ResearchError::ParseError("Lens response has no data".to_string()))
+    }
+
+    /// Convert a post to a ResearchFinding (None for non-post publications)
+    fn post_to_finding(post: LensPost, query: &str) -> Option<ResearchFinding> {
+        let id = post.id?;
+        let metadata = post.metadata.unwrap_or_default();
+        let content = metadata.content.unwrap_or_default();
+        let stats = post.stats.unwrap_or_default();
+        let handle = post.by.and_then(|p| p.handle).map(|h| h.local_name);
+
+        let engagement = engagement_score(stats.reactions, stats.mirrors + stats.quotes, stats.comments);
+
+        // Engagement dominates; a literal query match adds a bonus
+        let mut relevance_score = engagement * 0.8;
+        if content.to_lowercase().contains(&query.to_lowercase()) {
+            relevance_score += 0.2;
+        }
+
+        let discovered_at = post
+            .created_at
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or_else(Utc::now);
+
+        let mut tags = metadata.tags.unwrap_or_default();
+        tags.push("lens".to_string());
+
+        let title = metadata.title.unwrap_or_else(|| {
+            content.lines().next().unwrap_or_default().chars().take(100).collect()
+        });
+        let title = match &handle {
+            Some(handle) => format!("@{}: {}", handle, title),
+            None => title,
+        };
+
+        Some(ResearchFinding {
+            id: format!("lens-{}", id),
+            source: ResearchSource::LensProtocol,
+            title,
+            summary: content,
+            relevance_score: relevance_score.min(1.0),
+            discovered_at,
+            tags,
+            url: Some(format!("https://hey.xyz/posts/{}", id)),
+            metadata: serde_json::json!({
+                "handle": handle,
+                "reactions": stats.reactions,
+                "comments": stats.comments,
+                "mirrors": stats.mirrors,
+                "quotes": stats.quotes,
+                "engagement_score": engagement,
+            }),
+            score_breakdown: None,
+            scorer_version: None,
+        })
+    }
+}
+
+#[async_trait]
+impl ResearchAdapter for LensAdapter {
+    fn name(&self) -> &str {
+        "Lens"
+    }
+
+    fn source(&self) -> ResearchSource {
+        ResearchSource::LensProtocol
+    }
+
+    async fn validate(&self) -> ResearchResult<()> {
+        self.fetch_page("lens", None).await.map(|_| ())
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> ResearchResult<Vec<ResearchFinding>> {
+        if query.trim().is_empty() {
+            return Err(ResearchError::InvalidQuery("Query cannot be empty".to_string()));
+        }
+
+        let limit = options.limit.unwrap_or(10);
+        let mut findings = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        // Follow the cursor until enough posts are collected
+        for _ in 0..MAX_PAGES {
+            let page = self.fetch_page(query, cursor.as_deref()).await?;
+            findings.extend(
+                page.items
+                    .into_iter()
+                    .filter_map(|post| Self::post_to_finding(post, query)),
+            );
+
+            cursor = page.page_info.next;
+            if findings.len() >= limit || cursor.is_none() {
+                break;
+            }
+        }
+
+        log::info!("Lens search returned {} posts", findings.len());
+
+        if let Some(since) = options.since_timestamp {
+            findings.retain(|f| f.discovered_at.timestamp() >= since);
+        }
+        if let Some(min_rel) = options.min_relevance {
+            findings.retain(|f| f.relevance_score >= min_rel);
+        }
+
+        match options.sort_by {
+            Some(SortOrder::DateDesc) => findings.sort_by(|a, b| b.discovered_at.cmp(&a.discovered_at)),
+            Some(SortOrder::DateAsc) => findings.sort_by(|a, b| a.discovered_at.cmp(&b.discovered_at)),
+            _ => findings.sort_by(|a, b| {
+                b.relevance_score
+                    .partial_cmp(&a.relevance_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+        }
+
+        findings.truncate(limit);
+        Ok(findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_response() {
+        let json = r#"{
+            "data": {
+                "searchPublications": {
+                    "items": [
+                        {
+                            "id": "0x01-0x02",
+                            "createdAt": "2024-05-01T12:00:00.000Z",
+                            "by": {"handle": {"localName": "stani"}},
+                            "stats": {"reactions": 40, "comments": 5, "mirrors": 10, "quotes": 2},
+                            "metadata": {"content": "Open social graphs for agents", "tags": ["ai"]}
+                        },
+                        {}
+                    ],
+                    "pageInfo": {"next": "cursor-2"}
+                }
+            }
+        }"#;
+        let response: GraphQlResponse = serde_json::from_str(json).unwrap();
+        let page = response.data.unwrap().search_publications;
+        assert_eq!(page.page_info.next.as_deref(), Some("cursor-2"));
+
+        let findings: Vec<_> = page
+            .items
+            .into_iter()
+            .filter_map(|post| LensAdapter::post_to_finding(post, "social graphs"))
+            .collect();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "@stani: Open social graphs for agents");
+        assert_eq!(findings[0].url.as_deref(), Some("https://hey.xyz/posts/0x01-0x02"));
+        assert!(findings[0].tags.contains(&"ai".to_string()));
+    }
+}
@@ -2,12 +2,34 @@
 // Concrete implementations of ResearchAdapter trait
 
 mod common;
+mod credentials;
 mod github;
 mod arxiv;
+mod feeds;
+mod rss;
+#[cfg(feature = "social")]
+mod farcaster;
+#[cfg(feature = "social")]
+mod lens;
+#[cfg(feature = "social")]
+mod reddit;
+#[cfg(feature = "social")]
+mod twitter;
 
 pub use common::{AdapterConfig, HttpHelper, RateLimiter};
+pub use credentials::CredentialsRegistry;
 pub use github::GitHubAdapter;
 pub use arxiv::ArXivAdapter;
+pub use feeds::{CustomFeed, FeedRegistry};
+pub use rss::RssAdapter;
+#[cfg(feature = "social")]
+pub use farcaster::FarcasterAdapter;
+#[cfg(feature = "social")]
+pub use lens::LensAdapter;
+#[cfg(feature = "social")]
+pub use reddit::RedditAdapter;
+#[cfg(feature = "social")]
+pub use twitter::TwitterAdapter;
 
 use crate::commander::ResearchSource;
 use crate::research::traits::{ResearchAdapter, ResearchResult, ResearchError};
@@ -32,15 +54,34 @@ impl ResearchAdapterRegistry {
     /// Create a registry with default adapters configured
     pub async fn with_defaults() -> ResearchResult<Self> {
         let registry = Self::new();
+        let credentials = CredentialsRegistry::default();
 
-        // Add GitHub adapter (no API key required for basic search)
-        let github = GitHubAdapter::new(None);
+        // Add GitHub adapter (token optional, raises the rate limit)
+        let github = GitHubAdapter::new(credentials.get("GitHub")).with_readme_enrichment(5);
         registry.register(github).await?;
 
         // Add ArXiv adapter (no API key required)
         let arxiv = ArXivAdapter::new();
         registry.register(arxiv).await?;
 
+        // One adapter per followed RSS/Atom feed
+        for feed in FeedRegistry::default().list() {
+            registry.register(RssAdapter::new(feed)).await?;
+        }
+
+        #[cfg(feature = "social")]
+        {
+            // Neynar requires an API key; skip Farcaster without one
+            if let Some(key) = credentials.get("Farcaster") {
+                registry.register(FarcasterAdapter::new(Some(key))).await?;
+            }
+            registry.register(LensAdapter::new(credentials.get("Lens"))).await?;
+            registry.register(RedditAdapter::new()).await?;
+            if let Some(twitter) = TwitterAdapter::from_credentials(&credentials) {
+                registry.register(twitter).await?;
+            }
+        }
+
         Ok(registry)
     }
 
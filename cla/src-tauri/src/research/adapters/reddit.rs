@@ -0,0 +1,402 @@
+// Reddit Research Adapter
+// Searches posts through Reddit's public JSON API; `r/name` terms in a query
+// scope the search to those subreddits
+
+use super::common::{engagement_score, QueryBuilder, RateLimiter};
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::http_cache::CachedSend;
+use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
+use crate::telemetry::network::NetworkSubsystem;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+/// Most posts Reddit returns per request
+const MAX_LIMIT: usize = 100;
+
+/// Longest summary kept from a self post
+const MAX_SUMMARY_CHARS: usize = 500;
+
+/// Reddit listing response structures
+#[derive(Debug, Deserialize)]
+struct Listing {
+    data: ListingData,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListingData {
+    #[serde(default)]
+    children: Vec<Thing>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thing {
+    kind: String,
+    data: RedditPost,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditPost {
+    id: String,
+    title: String,
+    #[serde(default)]
+    selftext: String,
+    subreddit: String,
+    #[serde(default)]
+    author: String,
+    #[serde(default)]
+    score: i64,
+    #[serde(default)]
+    num_comments: u32,
+    #[serde(default)]
+    upvote_ratio: Option<f32>,
+    created_utc: f64,
+    permalink: String,
+    /// Link target; the post itself for self posts
+    url: Option<String>,
+    link_flair_text: Option<String>,
+    #[serde(default)]
+    over_18: bool,
+    #[serde(default)]
+    stickied: bool,
+}
+
+/// Which of a subreddit's listings to read for trending posts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RedditListing {
+    /// Currently popular posts
+    Hot,
+    /// Highest-voted posts of the last day
+    Top,
+}
+
+impl RedditListing {
+    fn path(self) -> &'static str {
+        match self {
+            Self::Hot => "hot.json",
+            Self::Top => "top.json",
+        }
+    }
+}
+
+/// Reddit Research Adapter
+#[derive(Debug)]
+pub struct RedditAdapter {
+    client: reqwest::Client,
+    base_url: String,
+    rate_limiter: RateLimiter,
+}
+
+impl RedditAdapter {
+    /// Create a new Reddit adapter (no credentials required)
+    pub fn new() -> Self {
+        // Reddit throttles generic user agents hard
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0 (Cirkelline Local Agent)")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            base_url: "https://www.reddit.com".to_string(),
+            // Unauthenticated clients get about ten requests a minute
+            rate_limiter: RateLimiter::new(10, 60),
+        }
+    }
+
+    /// Trending posts of the given subreddits (r/popular when none)
+    pub async fn trending(
+        &self,
+        subreddits: &[String],
+        listing: RedditListing,
+        limit: usize,
+    ) -> ResearchResult<Vec<ResearchFinding>> {
+        let mut url = QueryBuilder::new(format!("{}/r/{}/{}", self.base_url, subreddit_path(subreddits), listing.path()))
+            .param("limit", limit.min(MAX_LIMIT).to_string());
+        if listing == RedditListing::Top {
+            url = url.param("t", "day");
+        }
+        let posts = self.fetch(&url.build()).await?;
+        Ok(posts.into_iter().filter_map(|post| Self::post_to_finding(post, "")).collect())
+    }
+
+    /// Fetch a listing, rate limited
+    async fn fetch(&self, url: &str) -> ResearchResult<Vec<RedditPost>> {
+        self.rate_limiter.acquire().await?;
+
+        let response = self
+            .client
+            .get(url)
+            .send_cached(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(format!("Reddit request failed: {}", e)))?;
+
+        match response.status().as_u16() {
+            429 => {
+                let retry_after_secs = response
+                    .headers()
+                    .get("x-ratelimit-reset")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<f64>().ok())
+                    .map(|secs| secs.ceil() as u64);
+                return Err(ResearchError::RateLimited { retry_after_secs });
+            }
+            // Private, banned or missing subreddits
+            403 | 404 => {
+                return Err(ResearchError::InvalidQuery(format!("Subreddit not available: {}", url)));
+            }
+            status if !(200..300).contains(&status) => {
+                let text = response.text().await.unwrap_or_default();
+                return Err(ResearchError::ApiError { status, message: text });
+            }
+            _ => {}
+        }
+
+        let listing: Listing = response.json().await.map_err(|e| {
+            ResearchError::ParseError(format!("Failed to parse Reddit response: {}", e))
+        })?;
+
+        Ok(listing
+            .data
+            .children
+            .into_iter()
+            .filter(|thing| thing.kind == "t3")
+            .map(|thing| thing.data)
+            .collect())
+    }
+
+    /// Convert a post to a ResearchFinding (None for pinned and NSFW posts)
+    fn post_to_finding(post: RedditPost, query: &str) -> Option<ResearchFinding> {
+        if post.stickied || post.over_18 {
+            return None;
+        }
+
+        let upvotes = post.score.max(0) as u32;
+        let engagement = engagement_score(upvotes, 0, post.num_comments);
+
+        // Engagement dominates; query terms in the title or text add a bonus
+        let text = format!("{} {}", post.title, post.selftext).to_lowercase();
+        let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+        let matched = terms.iter().filter(|t| text.contains(t.as_str())).count();
+        let mut relevance_score = engagement * 0.8;
+        if !terms.is_empty() {
+            relevance_score += 0.2 * matched as f32 / terms.len() as f32;
+        }
+
+        let summary = if post.selftext.chars().count() > MAX_SUMMARY_CHARS {
+            format!("{}...", post.selftext.chars().take(MAX_SUMMARY_CHARS).collect::<String>())
+        } else {
+            post.selftext.clone()
+        };
+
+        let mut tags = vec!["reddit".to_string(), format!("r/{}", post.subreddit)];
+        if let Some(flair) = post.link_flair_text.as_ref().filter(|f| !f.is_empty()) {
+            tags.push(flair.clone());
+        }
+
+        let permalink = format!("https://www.reddit.com{}", post.permalink);
+        let link = post.url.filter(|url| !url.contains(&post.permalink));
+
+        Some(ResearchFinding {
+            id: format!("reddit-{}", post.id),
+            source: ResearchSource::Reddit,
+            title: format!("r/{}: {}", post.subreddit, post.title),
+            summary,
+            relevance_score: relevance_score.min(1.0),
+            discovered_at: DateTime::from_timestamp(post.created_utc as i64, 0).unwrap_or_else(Utc::now),
+            tags,
+            url: Some(permalink),
+            metadata: serde_json::json!({
+                "subreddit": post.subreddit,
+                "author": post.author,
+                "upvotes": upvotes,
+                "num_comments": post.num_comments,
+                "upvote_ratio": post.upvote_ratio,
+                "link": link,
+                "engagement_score": engagement,
+            }),
+            score_breakdown: None,
+            scorer_version: None,
+        })
+    }
+}
+
+impl Default for RedditAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Split `r/name` terms off a query: (subreddits, remaining query)
+fn split_subreddits(query: &str) -> (Vec<String>, String) {
+    let mut subreddits = Vec::new();
+    let mut terms = Vec::new();
+    for term in query.split_whitespace() {
+        match term.strip_prefix("r/").or_else(|| term.strip_prefix("/r/")) {
+            Some(name) if is_subreddit_name(name) => subreddits.push(name.to_string()),
+            _ => terms.push(term),
+        }
+    }
+    (subreddits, terms.join(" "))
+}
+
+/// Reddit names are 2-21 letters, digits and underscores
+fn is_subreddit_name(name: &str) -> bool {
+    (2..=21).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Several subreddits are read at once as `a+b`
+fn subreddit_path(subreddits: &[String]) -> String {
+    if subreddits.is_empty() {
+        "popular".to_string()
+    } else {
+        subreddits.join("+")
+    }
+}
+
+#[async_trait]
+impl ResearchAdapter for RedditAdapter {
+    fn name(&self) -> &str {
+        "Reddit"
+    }
+
+    fn source(&self) -> ResearchSource {
+        ResearchSource::Reddit
+    }
+
+    async fn validate(&self) -> ResearchResult<()> {
+        self.trending(&[], RedditListing::Hot, 1).await.map(|_| ())
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> ResearchResult<Vec<ResearchFinding>> {
+        let (subreddits, terms) = split_subreddits(query);
+        if terms.is_empty() && subreddits.is_empty() {
+            return Err(ResearchError::InvalidQuery("Query cannot be empty".to_string()));
+        }
+
+        let limit = options.limit.unwrap_or(10);
+        // Over-fetch so filtering still leaves enough posts
+        let fetch_limit = (limit * 2).min(MAX_LIMIT);
+
+        let mut findings = if terms.is_empty() {
+            // Only subreddits: read their listing instead of searching
+            let listing = match options.sort_by {
+                Some(SortOrder::PopularityDesc) => RedditListing::Top,
+                _ => RedditListing::Hot,
+            };
+            self.trending(&subreddits, listing, fetch_limit).await?
+        } else {
+            let sort = match options.sort_by {
+                Some(SortOrder::DateDesc) | Some(SortOrder::DateAsc) => "new",
+                Some(SortOrder::PopularityDesc) => "top",
+                _ => "relevance",
+            };
+            let url = if subreddits.is_empty() {
+                QueryBuilder::new(format!("{}/search.json", self.base_url))
+            } else {
+                QueryBuilder::new(format!("{}/r/{}/search.json", self.base_url, subreddit_path(&subreddits)))
+                    .param("restrict_sr", "1")
+            };
+            let url = url
+                .param("q", &terms)
+                .param("sort", sort)
+                .param("t", "month")
+                .param("limit", fetch_limit.to_string())
+                .build();
+            self.fetch(&url)
+                .await?
+                .into_iter()
+                .filter_map(|post| Self::post_to_finding(post, &terms))
+                .collect()
+        };
+
+        log::info!("Reddit search returned {} posts", findings.len());
+
+        if let Some(since) = options.since_timestamp {
+            findings.retain(|f| f.discovered_at.timestamp() >= since);
+        }
+        if let Some(min_rel) = options.min_relevance {
+            findings.retain(|f| f.relevance_score >= min_rel);
+        }
+
+        match options.sort_by {
+            Some(SortOrder::DateDesc) => findings.sort_by(|a, b| b.discovered_at.cmp(&a.discovered_at)),
+            Some(SortOrder::DateAsc) => findings.sort_by(|a, b| a.discovered_at.cmp(&b.discovered_at)),
+            _ => findings.sort_by(|a, b| {
+                b.relevance_score
+                    .partial_cmp(&a.relevance_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+        }
+
+        findings.truncate(limit);
+        Ok(findings)
+    }
+
+    async fn get_trending(&self, limit: usize) -> ResearchResult<Vec<ResearchFinding>> {
+        self.trending(&[], RedditListing::Hot, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_subreddits() {
+        let (subreddits, terms) = split_subreddits("r/rust /r/programming async runtime r/x");
+        assert_eq!(subreddits, vec!["rust".to_string(), "programming".to_string()]);
+        assert_eq!(terms, "async runtime r/x");
+        assert_eq!(subreddit_path(&subreddits), "rust+programming");
+        assert_eq!(subreddit_path(&[]), "popular");
+    }
+
+    #[test]
+    fn test_parse_listing() {
+        let json = r#"{
+            "kind": "Listing",
+            "data": {
+                "after": "t3_abc",
+                "children": [
+                    {"kind": "t3", "data": {
+                        "id": "1abc", "title": "Tokio 2.0 released", "selftext": "Async runtime news",
+                        "subreddit": "rust", "author": "carllerche", "score": 950, "num_comments": 120,
+                        "upvote_ratio": 0.98, "created_utc": 1714564800.0,
+                        "permalink": "/r/rust/comments/1abc/tokio_20_released/",
+                        "url": "https://tokio.rs/blog/2024-05-tokio-2", "link_flair_text": "news"
+                    }},
+                    {"kind": "t3", "data": {
+                        "id": "1pin", "title": "Weekly questions thread", "subreddit": "rust",
+                        "score": 5, "num_comments": 300, "created_utc": 1714564800.0,
+                        "permalink": "/r/rust/comments/1pin/weekly/", "stickied": true
+                    }},
+                    {"kind": "t1", "data": {
+                        "id": "c1", "title": "", "subreddit": "rust", "created_utc": 0.0, "permalink": "/c1"
+                    }}
+                ]
+            }
+        }"#;
+        let listing: Listing = serde_json::from_str(json).unwrap();
+        let findings: Vec<_> = listing
+            .data
+            .children
+            .into_iter()
+            .filter(|thing| thing.kind == "t3")
+            .filter_map(|thing| RedditAdapter::post_to_finding(thing.data, "tokio runtime"))
+            .collect();
+
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+        assert_eq!(finding.title, "r/rust: Tokio 2.0 released");
+        assert_eq!(finding.url.as_deref(), Some("https://www.reddit.com/r/rust/comments/1abc/tokio_20_released/"));
+        assert_eq!(finding.metadata["upvotes"], 950);
+        assert_eq!(finding.metadata["link"], "https://tokio.rs/blog/2024-05-tokio-2");
+        assert!(finding.tags.contains(&"r/rust".to_string()));
+        assert!(finding.relevance_score > 0.8);
+    }
+}
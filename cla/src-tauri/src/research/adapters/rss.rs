@@ -0,0 +1,400 @@
+// RSS/Atom Research Adapter
+// Reads a followed feed; entries are scored mostly by how recent they are
+
+use super::feeds::CustomFeed;
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::http_cache::CachedSend;
+use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions};
+use crate::telemetry::network::NetworkSubsystem;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use feed_rs::model::{Entry, Link, Text};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Longest summary kept from an entry
+const MAX_SUMMARY_CHARS: usize = 500;
+
+/// A feed entry, from an RSS `<item>` or an Atom `<entry>`
+#[derive(Debug)]
+struct FeedEntry {
+    id: String,
+    title: String,
+    summary: String,
+    link: Option<String>,
+    published: Option<DateTime<Utc>>,
+    categories: Vec<String>,
+}
+
+/// Adapter for one RSS or Atom feed
+#[derive(Debug)]
+pub struct RssAdapter {
+    client: reqwest::Client,
+    feed: CustomFeed,
+}
+
+impl RssAdapter {
+    pub fn new(feed: CustomFeed) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self { client, feed }
+    }
+
+    async fn fetch(&self) -> ResearchResult<Vec<FeedEntry>> {
+        let response = self
+            .client
+            .get(&self.feed.url)
+            .send_cached(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(format!("Feed request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let text = response.text().await.unwrap_or_default();
+            return Err(ResearchError::ApiError { status, message: text });
+        }
+
+        let xml = response
+            .text()
+            .await
+            .map_err(|e| ResearchError::ParseError(format!("Failed to read feed: {}", e)))?;
+        Self::parse_feed(&xml)
+    }
+
+    /// Entries of an RSS 0.9x/1.0/2.0, Atom or JSON Feed document. Entries without
+    /// a guid or id are identified by their link, else their title.
+    fn parse_feed(xml: &str) -> ResearchResult<Vec<FeedEntry>> {
+        let parser = feed_rs::parser::Builder::new()
+            .id_generator(|links: &[Link], title: &Option<Text>, _uri: Option<&str>| {
+                links
+                    .first()
+                    .map(|link| link.href.clone())
+                    .or_else(|| title.as_ref().map(|title| title.content.clone()))
+                    .unwrap_or_default()
+            })
+            .build();
+        let feed = parser
+            .parse(xml.as_bytes())
+            .map_err(|e| ResearchError::ParseError(format!("Invalid feed: {}", e)))?;
+        Ok(feed.entries.into_iter().filter_map(feed_entry).collect())
+    }
+
+    /// Recency first, with a bonus for query terms in the title or summary
+    fn calculate_relevance(entry: &FeedEntry, query: &str, now: DateTime<Utc>) -> f32 {
+        let recency = match entry.published.map(|at| now - at) {
+            Some(age) if age < Duration::days(1) => 0.7,
+            Some(age) if age < Duration::days(7) => 0.55,
+            Some(age) if age < Duration::days(30) => 0.35,
+            Some(_) => 0.15,
+            // Undated entries are treated as about a week old
+            None => 0.45,
+        };
+
+        let text = format!("{} {}", entry.title, entry.summary).to_lowercase();
+        let matches = query
+            .to_lowercase()
+            .split_whitespace()
+            .filter(|term| text.contains(term))
+            .count();
+
+        (recency + (matches as f32 * 0.1).min(0.4)).min(1.0)
+    }
+
+    fn entry_to_finding(&self, entry: FeedEntry, query: &str, now: DateTime<Utc>) -> ResearchFinding {
+        let relevance_score = Self::calculate_relevance(&entry, query, now);
+        let summary = if entry.summary.chars().count() > MAX_SUMMARY_CHARS {
+            format!("{}...", entry.summary.chars().take(MAX_SUMMARY_CHARS).collect::<String>())
+        } else {
+            entry.summary
+        };
+        let mut tags = entry.categories;
+        tags.push("feed".to_string());
+
+        ResearchFinding {
+            id: format!("feed-{:016x}", xxh3_64(format!("{}\n{}", self.feed.url, entry.id).as_bytes())),
+            source: self.feed.source(),
+            title: entry.title,
+            summary,
+            relevance_score,
+            discovered_at: entry.published.unwrap_or(now),
+            tags,
+            url: entry.link,
+            metadata: serde_json::json!({
+                "feed": self.feed.name,
+                "feed_url": self.feed.url,
+                "entry_id": entry.id,
+                "published": entry.published,
+            }),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+}
+
+#[async_trait]
+impl ResearchAdapter for RssAdapter {
+    fn name(&self) -> &str {
+        &self.feed.name
+    }
+
+    fn source(&self) -> ResearchSource {
+        self.feed.source()
+    }
+
+    async fn validate(&self) -> ResearchResult<()> {
+        self.fetch().await.map(|_| ())
+    }
+
+    async fn search(&self, query: &str, options: &SearchOptions) -> ResearchResult<Vec<ResearchFinding>> {
+        let now = Utc::now();
+        let mut findings: Vec<ResearchFinding> = self
+            .fetch()
+            .await?
+            .into_iter()
+            .map(|entry| self.entry_to_finding(entry, query, now))
+            .collect();
+
+        if let Some(min_rel) = options.min_relevance {
+            findings.retain(|f| f.relevance_score >= min_rel);
+        }
+        if let Some(since) = options.since_timestamp {
+            let since_dt = DateTime::from_timestamp(since, 0).unwrap_or(now);
+            findings.retain(|f| f.discovered_at >= since_dt);
+        }
+        findings.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        findings.truncate(options.limit.unwrap_or(10));
+
+        log::info!("Feed {} returned {} entries", self.feed.name, findings.len());
+        Ok(findings)
+    }
+}
+
+/// An entry as the adapter needs it; entries without a title are skipped
+fn feed_entry(entry: Entry) -> Option<FeedEntry> {
+    let title = entry.title.map(|t| strip_html(&t.content)).filter(|t| !t.is_empty())?;
+    let summary = entry
+        .summary
+        .map(|s| s.content)
+        .or_else(|| entry.content.and_then(|c| c.body))
+        .map(|s| strip_html(&s))
+        .unwrap_or_default();
+    // The entry's page: the `alternate` link, or the first link without a `rel`
+    let link = entry
+        .links
+        .iter()
+        .find(|l| l.rel.as_deref() == Some("alternate"))
+        .or_else(|| entry.links.iter().find(|l| l.rel.is_none()))
+        .map(|l| l.href.clone());
+    Some(FeedEntry {
+        id: entry.id,
+        title,
+        summary,
+        link,
+        published: entry.published.or(entry.updated),
+        categories: entry.categories.into_iter().map(|c| c.term).collect(),
+    })
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest[..rest.len().min(10)].find(';') else {
+            decoded.push('&');
+            rest = &rest[1..];
+            continue;
+        };
+        let entity = &rest[1..semi];
+        let character = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            _ => entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match character {
+            Some(c) => {
+                decoded.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Plain text of an HTML fragment, whitespace collapsed
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => {
+                in_tag = false;
+                text.push(' ');
+            }
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    decode_entities(&text).split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>Rust Blog</title>
+    <item>
+      <title>Announcing Rust 1.80 &amp; more</title>
+      <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+      <guid isPermaLink="false">rust-1.80</guid>
+      <description><![CDATA[<p>The Rust team is happy to <b>announce</b> a new version.</p>]]></description>
+      <category>release</category>
+      <pubDate>Thu, 25 Jul 2024 12:00:00 +0000</pubDate>
+    </item>
+    <item>
+      <title>Undated note</title>
+      <description>Plain &lt;i&gt;text&lt;/i&gt; &#8211; done</description>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>Example</title>
+  <link href="https://example.com/"/>
+  <entry>
+    <title type="html">Agents in production</title>
+    <link rel="replies" href="https://example.com/agents#comments"/>
+    <link rel="alternate" href="https://example.com/agents"/>
+    <id>urn:uuid:1225c695</id>
+    <updated>2024-05-01T08:30:00Z</updated>
+    <category term="ai"/>
+    <summary>How &quot;agents&quot; run</summary>
+  </entry>
+</feed>"#;
+
+    const FEEDBURNER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss xmlns:atom="http://www.w3.org/2005/Atom" xmlns:feedburner="http://rssnamespace.org/feedburner/ext/1.0" version="2.0">
+  <channel>
+    <title>Tech News</title>
+    <atom:link rel="self" type="application/rss+xml" href="http://feeds.feedburner.com/technews"/>
+    <feedburner:info uri="technews"/>
+    <item>
+      <title>Local models get faster</title>
+      <link>http://feeds.feedburner.com/~r/technews/~3/abc/</link>
+      <feedburner:origLink>https://technews.example/local-models</feedburner:origLink>
+      <description>Quantized models &amp;nbsp;now run on laptops.</description>
+      <media:title xmlns:media="http://search.yahoo.com/mrss/">Thumbnail</media:title>
+      <pubDate>Mon, 06 May 2024 09:00:00 GMT</pubDate>
+    </item>
+    <item>
+      <title>Second story</title>
+      <link>https://technews.example/second</link>
+    </item>
+  </channel>
+</rss>"#;
+
+    const PREFIXED_ATOM: &str = r#"<atom:feed xmlns:atom="http://www.w3.org/2005/Atom">
+  <atom:title>Prefixed</atom:title>
+  <atom:entry>
+    <atom:title>Namespaced entry</atom:title>
+    <atom:link href="https://example.com/namespaced"/>
+    <atom:id>tag:example.com,2024:1</atom:id>
+    <atom:content type="xhtml"><div xmlns="http://www.w3.org/1999/xhtml"><p>Nested <b>markup</b></p></div></atom:content>
+  </atom:entry>
+</atom:feed>"#;
+
+    #[test]
+    fn test_parse_rss() {
+        let entries = RssAdapter::parse_feed(RSS).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "Announcing Rust 1.80 & more");
+        assert_eq!(entries[0].id, "rust-1.80");
+        assert_eq!(entries[0].summary, "The Rust team is happy to announce a new version.");
+        assert_eq!(entries[0].categories, vec!["release".to_string()]);
+        assert_eq!(entries[0].published.unwrap().to_rfc3339(), "2024-07-25T12:00:00+00:00");
+        assert_eq!(entries[1].summary, "Plain text – done");
+        assert!(entries[1].published.is_none());
+        assert!(RssAdapter::parse_feed("<html><body>Not a feed</body></html>").is_err());
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let entries = RssAdapter::parse_feed(ATOM).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].link.as_deref(), Some("https://example.com/agents"));
+        assert_eq!(entries[0].id, "urn:uuid:1225c695");
+        assert_eq!(entries[0].summary, "How \"agents\" run");
+        assert_eq!(entries[0].categories, vec!["ai".to_string()]);
+    }
+
+    #[test]
+    fn test_feedburner_rss_is_not_mistaken_for_atom() {
+        let entries = RssAdapter::parse_feed(FEEDBURNER).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].title, "Local models get faster");
+        assert_eq!(entries[0].link.as_deref(), Some("http://feeds.feedburner.com/~r/technews/~3/abc/"));
+        assert_eq!(entries[0].summary, "Quantized models now run on laptops.");
+        assert!(entries[0].published.is_some());
+        assert_eq!(entries[1].id, "https://technews.example/second");
+    }
+
+    #[test]
+    fn test_parse_prefixed_atom() {
+        let entries = RssAdapter::parse_feed(PREFIXED_ATOM).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, "Namespaced entry");
+        assert_eq!(entries[0].link.as_deref(), Some("https://example.com/namespaced"));
+        assert_eq!(entries[0].id, "tag:example.com,2024:1");
+        assert_eq!(entries[0].summary, "Nested markup");
+    }
+
+    #[test]
+    fn test_truncated_feed_is_rejected() {
+        let cut = &RSS[..RSS.find("<title>Undated").unwrap() + 10];
+        assert!(RssAdapter::parse_feed(cut).is_err());
+    }
+
+    #[test]
+    fn test_recent_entries_score_higher() {
+        let now = Utc::now();
+        let entry = |days: i64| FeedEntry {
+            id: String::new(),
+            title: "Rust release".to_string(),
+            summary: String::new(),
+            link: None,
+            published: Some(now - Duration::days(days)),
+            categories: Vec::new(),
+        };
+        let fresh = RssAdapter::calculate_relevance(&entry(0), "", now);
+        let old = RssAdapter::calculate_relevance(&entry(90), "", now);
+        assert!(fresh > old);
+        assert!(RssAdapter::calculate_relevance(&entry(90), "rust release", now) > old);
+    }
+}
@@ -0,0 +1,590 @@
+// Twitter/X Research Adapter
+// Searches through the official API (bearer token) or a self-hosted nitter instance
+
+use super::common::{encode_component, QueryBuilder};
+use super::credentials::CredentialsRegistry;
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::traits::{ResearchAdapter, ResearchError, ResearchResult, SearchOptions, SortOrder};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use crate::research::http_cache::CachedSend;
+use crate::telemetry::network::NetworkSubsystem;
+
+/// How long a failing backend is skipped before retrying
+const BACKOFF: Duration = Duration::from_secs(5 * 60);
+
+/// Engagement per hour that counts as maximum momentum
+const MAX_MOMENTUM_RATE: f64 = 500.0;
+
+/// Twitter API v2 response structures
+#[derive(Debug, Deserialize)]
+struct TweetSearchResponse {
+    #[serde(default)]
+    data: Vec<Tweet>,
+    includes: Option<TweetIncludes>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tweet {
+    id: String,
+    text: String,
+    created_at: Option<String>,
+    author_id: Option<String>,
+    public_metrics: Option<PublicMetrics>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PublicMetrics {
+    #[serde(default)]
+    retweet_count: u32,
+    #[serde(default)]
+    reply_count: u32,
+    #[serde(default)]
+    like_count: u32,
+    #[serde(default)]
+    quote_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetIncludes {
+    #[serde(default)]
+    users: Vec<TweetUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TweetUser {
+    id: String,
+    username: String,
+}
+
+/// A post from either backend
+#[derive(Debug, Clone)]
+struct Post {
+    id: String,
+    author: String,
+    text: String,
+    created_at: DateTime<Utc>,
+    url: String,
+    /// Engagement metrics (only available from the official API)
+    metrics: Option<(u32, u32, u32, u32)>,
+}
+
+/// Where posts are fetched from
+#[derive(Debug, Clone)]
+pub enum TwitterBackend {
+    /// Official API v2 with a bearer token
+    Api { bearer_token: String },
+    /// Self-hosted nitter instance (RSS)
+    Nitter { base_url: String },
+}
+
+impl TwitterBackend {
+    fn label(&self) -> &'static str {
+        match self {
+            TwitterBackend::Api { .. } => "api",
+            TwitterBackend::Nitter { .. } => "nitter",
+        }
+    }
+}
+
+/// Twitter/X Research Adapter
+#[derive(Debug)]
+pub struct TwitterAdapter {
+    client: reqwest::Client,
+    /// Backends in order of preference
+    backends: Vec<TwitterBackend>,
+    /// Keywords polled by get_trending
+    keywords: Vec<String>,
+    /// List ids polled by get_trending
+    lists: Vec<String>,
+    /// Backends skipped until the given time after a failure
+    unavailable_until: Mutex<HashMap<&'static str, Instant>>,
+}
+
+impl TwitterAdapter {
+    /// Create an adapter over the given backends
+    pub fn new(backends: Vec<TwitterBackend>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            backends,
+            keywords: Vec::new(),
+            lists: Vec::new(),
+            unavailable_until: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Build from stored credentials; None if neither a token nor nitter is configured.
+    /// Monitored keywords and lists come from TWITTER_KEYWORDS / TWITTER_LISTS.
+    pub fn from_credentials(credentials: &CredentialsRegistry) -> Option<Self> {
+        let mut backends = Vec::new();
+        if let Some(bearer_token) = credentials.get("Twitter") {
+            backends.push(TwitterBackend::Api { bearer_token });
+        }
+        if let Some(base_url) = credentials.get("Nitter") {
+            backends.push(TwitterBackend::Nitter {
+                base_url: base_url.trim_end_matches('/').to_string(),
+            });
+        }
+        if backends.is_empty() {
+            return None;
+        }
+
+        Some(Self::new(backends).with_monitoring(env_list("TWITTER_KEYWORDS"), env_list("TWITTER_LISTS")))
+    }
+
+    /// Keywords and list ids to monitor
+    pub fn with_monitoring(mut self, keywords: Vec<String>, lists: Vec<String>) -> Self {
+        self.keywords = keywords;
+        self.lists = lists;
+        self
+    }
+
+    /// Try each available backend in order until one succeeds
+    async fn fetch(&self, request: &FeedRequest<'_>) -> ResearchResult<Vec<Post>> {
+        let mut last_error = None;
+
+        for backend in &self.backends {
+            let label = backend.label();
+            if self
+                .unavailable_until
+                .lock()
+                .await
+                .get(label)
+                .map_or(false, |until| Instant::now() < *until)
+            {
+                continue;
+            }
+
+            let result = match backend {
+                TwitterBackend::Api { bearer_token } => self.fetch_api(bearer_token, request).await,
+                TwitterBackend::Nitter { base_url } => self.fetch_nitter(base_url, request).await,
+            };
+
+            match result {
+                Ok(posts) => return Ok(posts),
+                // Bad queries fail the same way on every backend
+                Err(e @ ResearchError::InvalidQuery(_)) => return Err(e),
+                Err(e) => {
+                    log::warn!("Twitter backend {} unavailable: {}", label, e);
+                    self.unavailable_until
+                        .lock()
+                        .await
+                        .insert(label, Instant::now() + BACKOFF);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ResearchError::AdapterUnavailable("Twitter (all backends backing off)".to_string())
+        }))
+    }
+
+    /// Fetch from the official API v2
+    async fn fetch_api(&self, token: &str, request: &FeedRequest<'_>) -> ResearchResult<Vec<Post>> {
+        let url = match request {
+            FeedRequest::Search { query, limit } => {
+                QueryBuilder::new("https://api.twitter.com/2/tweets/search/recent")
+                    .param("query", format!("{} -is:retweet", query))
+                    .param("max_results", (*limit).clamp(10, 100).to_string())
+            }
+            FeedRequest::List { id, limit } => {
+                QueryBuilder::new(format!("https://api.twitter.com/2/lists/{}/tweets", encode_component(id)))
+                    .param("max_results", (*limit).clamp(1, 100).to_string())
+            }
+        }
+        .param("tweet.fields", "created_at,public_metrics,author_id")
+        .param("expansions", "author_id")
+        .param("user.fields", "username");
+
+        let response = self
+            .client
+            .get(url.build())
+            .bearer_auth(token)
+            .send_cached(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+
+        match response.status().as_u16() {
+            400 => {
+                let text = response.text().await.unwrap_or_default();
+                return Err(ResearchError::InvalidQuery(text));
+            }
+            401 | 403 => return Err(ResearchError::ConfigError("Invalid Twitter bearer token".to_string())),
+            429 => {
+                let retry_after = response
+                    .headers()
+                    .get("x-rate-limit-reset")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(|reset| reset.saturating_sub(Utc::now().timestamp() as u64));
+                return Err(ResearchError::RateLimited { retry_after_secs: retry_after });
+            }
+            status if !(200..300).contains(&status) => {
+                let text = response.text().await.unwrap_or_default();
+                return Err(ResearchError::ApiError { status, message: text });
+            }
+            _ => {}
+        }
+
+        let body: TweetSearchResponse = response.json().await.map_err(|e| {
+            ResearchError::ParseError(format!("Failed to parse Twitter response: {}", e))
+        })?;
+
+        Ok(Self::api_posts(body))
+    }
+
+    fn api_posts(body: TweetSearchResponse) -> Vec<Post> {
+        let users: HashMap<String, String> = body
+            .includes
+            .map(|i| i.users.into_iter().map(|u| (u.id, u.username)).collect())
+            .unwrap_or_default();
+
+        body.data
+            .into_iter()
+            .map(|tweet| {
+                let author = tweet
+                    .author_id
+                    .and_then(|id| users.get(&id).cloned())
+                    .unwrap_or_else(|| "i".to_string());
+                let metrics = tweet.public_metrics.unwrap_or_default();
+
+                Post {
+                    url: format!("https://x.com/{}/status/{}", author, tweet.id),
+                    id: tweet.id,
+                    author,
+                    text: tweet.text,
+                    created_at: tweet
+                        .created_at
+                        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .unwrap_or_else(Utc::now),
+                    metrics: Some((
+                        metrics.like_count,
+                        metrics.retweet_count,
+                        metrics.reply_count,
+                        metrics.quote_count,
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    /// Fetch from a nitter instance's RSS feeds
+    async fn fetch_nitter(&self, base_url: &str, request: &FeedRequest<'_>) -> ResearchResult<Vec<Post>> {
+        let builder = match request {
+            FeedRequest::Search { query, .. } => self.client.get(
+                QueryBuilder::new(format!("{}/search/rss", base_url))
+                    .param("f", "tweets")
+                    .param("q", query)
+                    .build(),
+            ),
+            FeedRequest::List { id, .. } => {
+                self.client.get(format!("{}/i/lists/{}/rss", base_url, encode_component(id)))
+            }
+        };
+
+        let response = builder
+            .send_cached(NetworkSubsystem::Research)
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+
+        let status = response.status().as_u16();
+        if status == 429 {
+            return Err(ResearchError::RateLimited { retry_after_secs: None });
+        }
+        if !(200..300).contains(&status) {
+            return Err(ResearchError::ApiError {
+                status,
+                message: "Nitter instance unavailable".to_string(),
+            });
+        }
+
+        let xml = response
+            .text()
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+
+        let mut posts = parse_nitter_rss(&xml);
+        let limit = match request {
+            FeedRequest::Search { limit, .. } | FeedRequest::List { limit, .. } => *limit,
+        };
+        posts.truncate(limit);
+        Ok(posts)
+    }
+
+    /// Convert a post to a ResearchFinding with momentum scoring
+    fn post_to_finding(post: Post, query: &str) -> ResearchFinding {
+        let age_hours = ((Utc::now() - post.created_at).num_minutes() as f64 / 60.0).max(1.0);
+
+        let (momentum, engagement) = match post.metrics {
+            Some((likes, retweets, replies, quotes)) => {
+                let engagement = likes as u64 + retweets as u64 * 2 + replies as u64 * 3 + quotes as u64 * 2;
+                (momentum_score(engagement, age_hours), Some(engagement))
+            }
+            // Nitter feeds carry no metrics; only recency signals momentum
+            None => ((0.5 * (1.0 - age_hours / 48.0)).max(0.0) as f32, None),
+        };
+
+        let mut relevance_score = momentum * 0.8;
+        if !query.is_empty() && post.text.to_lowercase().contains(&query.to_lowercase()) {
+            relevance_score += 0.2;
+        }
+
+        let title: String = post.text.lines().next().unwrap_or_default().chars().take(100).collect();
+        let tags = post
+            .text
+            .split_whitespace()
+            .filter_map(|w| w.strip_prefix('#'))
+            .map(|t| t.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+            .filter(|t| !t.is_empty())
+            .chain(std::iter::once("twitter".to_string()))
+            .collect();
+
+        ResearchFinding {
+            id: format!("twitter-{}", post.id),
+            source: ResearchSource::Twitter,
+            title: format!("@{}: {}", post.author, title),
+            summary: post.text,
+            relevance_score: relevance_score.min(1.0),
+            discovered_at: post.created_at,
+            tags,
+            url: Some(post.url),
+            metadata: serde_json::json!({
+                "author": post.author,
+                "engagement": engagement,
+                "momentum": momentum,
+                "age_hours": age_hours,
+            }),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    fn to_findings(posts: Vec<Post>, query: &str, options: &SearchOptions) -> Vec<ResearchFinding> {
+        let mut findings: Vec<ResearchFinding> = posts
+            .into_iter()
+            .map(|post| Self::post_to_finding(post, query))
+            .collect();
+
+        if let Some(since) = options.since_timestamp {
+            findings.retain(|f| f.discovered_at.timestamp() >= since);
+        }
+        if let Some(min_rel) = options.min_relevance {
+            findings.retain(|f| f.relevance_score >= min_rel);
+        }
+
+        match options.sort_by {
+            Some(SortOrder::DateDesc) => findings.sort_by(|a, b| b.discovered_at.cmp(&a.discovered_at)),
+            Some(SortOrder::DateAsc) => findings.sort_by(|a, b| a.discovered_at.cmp(&b.discovered_at)),
+            _ => findings.sort_by(|a, b| {
+                b.relevance_score
+                    .partial_cmp(&a.relevance_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+        }
+
+        findings
+    }
+}
+
+/// A feed to fetch
+enum FeedRequest<'a> {
+    Search { query: &'a str, limit: usize },
+    List { id: &'a str, limit: usize },
+}
+
+/// Momentum (0.0-1.0): engagement per hour since posting, on a log scale
+pub fn momentum_score(engagement: u64, age_hours: f64) -> f32 {
+    let rate = engagement as f64 / age_hours.max(1.0);
+    ((1.0 + rate).ln() / (1.0 + MAX_MOMENTUM_RATE).ln()).min(1.0) as f32
+}
+
+/// Parse nitter RSS items into posts
+fn parse_nitter_rss(xml: &str) -> Vec<Post> {
+    xml.split("<item>")
+        .skip(1)
+        .filter_map(|item| {
+            let item = &item[..item.find("</item>").unwrap_or(item.len())];
+            let link = extract_tag(item, "link")?;
+            // Links look like https://nitter.example/user/status/123#m
+            let mut parts = link.split('/').rev();
+            let id = parts.next()?.trim_end_matches("#m").to_string();
+            parts.next();
+            let author = parts.next()?.to_string();
+
+            let text = extract_tag(item, "title").map(|t| decode_entities(&t)).unwrap_or_default();
+            let created_at = extract_tag(item, "pubDate")
+                .and_then(|d| DateTime::parse_from_rfc2822(&d).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(Utc::now);
+
+            Some(Post {
+                url: format!("https://x.com/{}/status/{}", author, id),
+                id,
+                author,
+                text,
+                created_at,
+                metrics: None,
+            })
+        })
+        .collect()
+}
+
+/// Extract content from an XML tag, unwrapping CDATA
+fn extract_tag(xml: &str, tag: &str) -> Option<String> {
+    let start_tag = format!("<{}>", tag);
+    let end_tag = format!("</{}>", tag);
+
+    let start = xml.find(&start_tag)? + start_tag.len();
+    let end = xml[start..].find(&end_tag)?;
+    let content = xml[start..start + end].trim();
+    let content = content
+        .strip_prefix("<![CDATA[")
+        .and_then(|c| c.strip_suffix("]]>"))
+        .unwrap_or(content);
+
+    Some(content.trim().to_string())
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn env_list(name: &str) -> Vec<String> {
+    std::env::var(name)
+        .map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl ResearchAdapter for TwitterAdapter {
+    fn name(&self) -> &str {
+        "Twitter"
+    }
+
+    fn source(&self) -> ResearchSource {
+        ResearchSource::Twitter
+    }
+
+    async fn validate(&self) -> ResearchResult<()> {
+        if self.backends.is_empty() {
+            return Err(ResearchError::ConfigError(
+                "Neither a Twitter bearer token nor a nitter endpoint is configured".to_string(),
+            ));
+        }
+        self.fetch(&FeedRequest::Search { query: "news", limit: 10 }).await.map(|_| ())
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> ResearchResult<Vec<ResearchFinding>> {
+        if query.trim().is_empty() {
+            return Err(ResearchError::InvalidQuery("Query cannot be empty".to_string()));
+        }
+
+        let limit = options.limit.unwrap_or(10);
+        let posts = self.fetch(&FeedRequest::Search { query, limit }).await?;
+        log::info!("Twitter search returned {} posts", posts.len());
+
+        let mut findings = Self::to_findings(posts, query, options);
+        findings.truncate(limit);
+        Ok(findings)
+    }
+
+    /// Poll monitored keywords and lists, ranked by momentum
+    async fn get_trending(&self, limit: usize) -> ResearchResult<Vec<ResearchFinding>> {
+        let mut findings = Vec::new();
+        let options = SearchOptions::default();
+
+        for keyword in &self.keywords {
+            match self.fetch(&FeedRequest::Search { query: keyword, limit }).await {
+                Ok(posts) => findings.extend(Self::to_findings(posts, keyword, &options)),
+                Err(e) => log::warn!("Twitter keyword '{}' skipped: {}", keyword, e),
+            }
+        }
+        for list in &self.lists {
+            match self.fetch(&FeedRequest::List { id: list, limit }).await {
+                Ok(posts) => findings.extend(Self::to_findings(posts, "", &options)),
+                Err(e) => log::warn!("Twitter list {} skipped: {}", list, e),
+            }
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        findings.retain(|f| seen.insert(f.id.clone()));
+        findings.sort_by(|a, b| {
+            let momentum = |f: &ResearchFinding| f.metadata["momentum"].as_f64().unwrap_or(0.0);
+            momentum(b).partial_cmp(&momentum(a)).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        findings.truncate(limit);
+        Ok(findings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_momentum_favours_fast_engagement() {
+        assert!(momentum_score(1000, 1.0) > momentum_score(1000, 24.0));
+        assert_eq!(momentum_score(0, 1.0), 0.0);
+        assert_eq!(momentum_score(1_000_000, 1.0), 1.0);
+    }
+
+    #[test]
+    fn test_parse_nitter_rss() {
+        let xml = r#"<rss><channel>
+            <item>
+                <title>Local models are getting fast &amp; small #LLM</title>
+                <pubDate>Wed, 01 May 2024 12:00:00 GMT</pubDate>
+                <link>https://nitter.local/karpathy/status/1785#m</link>
+            </item>
+        </channel></rss>"#;
+        let posts = parse_nitter_rss(xml);
+
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].author, "karpathy");
+        assert_eq!(posts[0].id, "1785");
+        assert_eq!(posts[0].text, "Local models are getting fast & small #LLM");
+
+        let finding = TwitterAdapter::post_to_finding(posts[0].clone(), "local models");
+        assert!(finding.tags.contains(&"llm".to_string()));
+        assert_eq!(finding.url.as_deref(), Some("https://x.com/karpathy/status/1785"));
+    }
+
+    #[tokio::test]
+    async fn test_unavailable_backend_degrades() {
+        let adapter = TwitterAdapter::new(vec![TwitterBackend::Nitter {
+            base_url: "http://127.0.0.1:9".to_string(),
+        }]);
+
+        assert!(adapter.search("rust", &SearchOptions::default()).await.is_err());
+        // Backend is now backing off and skipped without a request
+        assert!(matches!(
+            adapter.search("rust", &SearchOptions::default()).await,
+            Err(ResearchError::AdapterUnavailable(_))
+        ));
+    }
+}
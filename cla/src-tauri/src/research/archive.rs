@@ -0,0 +1,585 @@
+// Finding Archive - Local copies of linked pages/PDFs for offline reading
+// URLs rot, so high-relevance findings get a text extraction stored on disk
+
+use crate::commander::ResearchFinding;
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::sync::RwLock;
+use crate::telemetry::network::{self, NetworkSubsystem};
+use futures_util::StreamExt;
+
+/// Archive configuration
+#[derive(Debug, Clone)]
+pub struct ArchiveConfig {
+    /// Directory where extracted text is stored
+    pub archive_dir: PathBuf,
+    /// Maximum total size of archived text in bytes
+    pub quota_bytes: u64,
+    /// Only findings at or above this relevance are archived
+    pub min_relevance: f32,
+    /// Maximum download size per document in bytes
+    pub max_download_bytes: usize,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        let archive_dir = dirs::data_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cirkelline-cla")
+            .join("archive");
+
+        Self {
+            archive_dir,
+            quota_bytes: 100 * 1024 * 1024, // 100MB
+            min_relevance: 0.75,
+            max_download_bytes: 10 * 1024 * 1024,
+        }
+    }
+}
+
+/// Metadata for an archived finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedEntry {
+    pub finding_id: String,
+    pub url: String,
+    pub content_type: String,
+    /// SHA-256 of the downloaded document
+    pub content_hash: String,
+    pub text_bytes: u64,
+    pub archived_at: DateTime<Utc>,
+}
+
+/// Archived content served for offline reading
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchivedContent {
+    #[serde(flatten)]
+    pub entry: ArchivedEntry,
+    pub text: String,
+}
+
+/// Local archive of finding documents
+pub struct FindingArchive {
+    config: ArchiveConfig,
+    client: reqwest::Client,
+    index: RwLock<HashMap<String, ArchivedEntry>>,
+    index_file: JournaledFile,
+    /// Off until the user opts in; archived copies stay readable either way
+    enabled: AtomicBool,
+}
+
+impl FindingArchive {
+    /// Create an archive, loading any existing index from disk
+    pub fn new(config: ArchiveConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        let index_file = JournaledFile::new("findings_archive", config.archive_dir.join("index.json"));
+        // A damaged index is moved aside and reported; start empty
+        let index = index_file.load().ok().flatten().unwrap_or_default();
+
+        Self {
+            config,
+            client,
+            index: RwLock::new(index),
+            index_file,
+            enabled: AtomicBool::new(false),
+        }
+    }
+
+    /// Turn archiving of new findings on or off
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether a finding qualifies for archiving
+    pub fn should_archive(&self, finding: &ResearchFinding) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+            && finding.url.is_some()
+            && finding.relevance_score >= self.config.min_relevance
+    }
+
+    /// Fetch and archive a finding's linked document
+    pub async fn archive(&self, finding: &ResearchFinding) -> ResearchResult<ArchivedEntry> {
+        let url = finding
+            .url
+            .as_ref()
+            .ok_or_else(|| ResearchError::InvalidQuery("Finding has no URL".to_string()))?;
+
+        if let Some(existing) = self.index.read().await.get(&finding.id) {
+            return Ok(existing.clone());
+        }
+
+        // Sent unmetered: send_metered buffers the whole body, and this one is
+        // streamed under the download cap and metered per chunk instead
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+        network::meter().record(NetworkSubsystem::Research, url.len() as u64, 0);
+
+        if !response.status().is_success() {
+            return Err(ResearchError::ApiError {
+                status: response.status().as_u16(),
+                message: format!("Failed to fetch {}", url),
+            });
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("text/html")
+            .to_string();
+
+        let body = download_capped(response, self.config.max_download_bytes).await?;
+
+        let text = extract_text(&body, &content_type);
+        if text.trim().is_empty() {
+            return Err(ResearchError::ParseError("No text could be extracted".to_string()));
+        }
+
+        let entry = ArchivedEntry {
+            finding_id: finding.id.clone(),
+            url: url.clone(),
+            content_type,
+            content_hash: hex::encode(Sha256::digest(&body)),
+            text_bytes: text.len() as u64,
+            archived_at: Utc::now(),
+        };
+
+        self.store(entry.clone(), &text).await?;
+        log::info!("Archived finding {} ({} bytes)", finding.id, entry.text_bytes);
+
+        Ok(entry)
+    }
+
+    /// Store extracted text and update the index, evicting old entries over quota
+    pub async fn store(&self, entry: ArchivedEntry, text: &str) -> ResearchResult<()> {
+        if entry.text_bytes > self.config.quota_bytes {
+            return Err(ResearchError::ConfigError("Document exceeds archive quota".to_string()));
+        }
+
+        tokio::fs::create_dir_all(&self.config.archive_dir)
+            .await
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))?;
+        tokio::fs::write(self.text_path(&entry.finding_id), text)
+            .await
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))?;
+
+        self.index.write().await.insert(entry.finding_id.clone(), entry);
+
+        // Evict oldest entries until within quota
+        while self.used_bytes().await > self.config.quota_bytes {
+            let oldest = {
+                let mut index = self.index.write().await;
+                let oldest = index
+                    .values()
+                    .min_by_key(|e| e.archived_at)
+                    .map(|e| e.finding_id.clone());
+                oldest.and_then(|id| index.remove(&id))
+            };
+            let Some(removed) = oldest else { break };
+            let _ = tokio::fs::remove_file(self.text_path(&removed.finding_id)).await;
+            log::debug!("Evicted archived finding {} (quota)", removed.finding_id);
+        }
+
+        self.persist_index().await
+    }
+
+    /// Get archived content for offline reading
+    pub async fn get(&self, finding_id: &str) -> Option<ArchivedContent> {
+        let entry = self.index.read().await.get(finding_id).cloned()?;
+        let text = tokio::fs::read_to_string(self.text_path(finding_id)).await.ok()?;
+        Some(ArchivedContent { entry, text })
+    }
+
+    /// Total bytes currently archived
+    pub async fn used_bytes(&self) -> u64 {
+        self.index.read().await.values().map(|e| e.text_bytes).sum()
+    }
+
+    fn text_path(&self, finding_id: &str) -> PathBuf {
+        let safe: String = finding_id
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        self.config.archive_dir.join(format!("{}.txt", safe))
+    }
+
+    /// Write the index off the async runtime; the journaled write syncs to disk
+    async fn persist_index(&self) -> ResearchResult<()> {
+        let json = serde_json::to_string_pretty(&*self.index.read().await)
+            .map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        let file = self.index_file.clone();
+        tokio::task::spawn_blocking(move || file.write(json.as_bytes()))
+            .await
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))?
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))
+    }
+}
+
+impl Default for FindingArchive {
+    fn default() -> Self {
+        Self::new(ArchiveConfig::default())
+    }
+}
+
+/// Read a response body of at most `max` bytes, giving up as soon as it is known
+/// to exceed it; each chunk is metered as research traffic
+pub(crate) async fn download_capped(response: reqwest::Response, max: usize) -> ResearchResult<Vec<u8>> {
+    let too_large = |bytes: u64| ResearchError::ParseError(format!("Document too large: {} bytes", bytes));
+
+    if let Some(length) = response.content_length().filter(|&length| length > max as u64) {
+        return Err(too_large(length));
+    }
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+        network::meter().record_received(NetworkSubsystem::Research, chunk.len() as u64);
+        if body.len() + chunk.len() > max {
+            return Err(too_large((body.len() + chunk.len()) as u64));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Extract readable text from a downloaded document
+pub fn extract_text(body: &[u8], content_type: &str) -> String {
+    if content_type.contains("pdf") || body.starts_with(b"%PDF") {
+        extract_pdf_text(body)
+    } else {
+        html_to_text(&String::from_utf8_lossy(body))
+    }
+}
+
+/// Strip tags, scripts and styles from HTML
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let lower = html.to_ascii_lowercase();
+    let mut i = 0;
+
+    while i < html.len() {
+        if lower[i..].starts_with("<script") || lower[i..].starts_with("<style") {
+            let close = if lower[i..].starts_with("<script") { "</script>" } else { "</style>" };
+            i = lower[i..].find(close).map(|p| i + p + close.len()).unwrap_or(html.len());
+        } else if html[i..].starts_with('<') {
+            i = html[i..].find('>').map(|p| i + p + 1).unwrap_or(html.len());
+            text.push(' ');
+        } else {
+            let ch = html[i..].chars().next().unwrap_or(' ');
+            text.push(ch);
+            i += ch.len_utf8();
+        }
+    }
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"");
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Extract text from a PDF (best effort)
+///
+/// Inflates FlateDecode content streams and reads the strings shown by text
+/// operators. Fonts with custom encodings come out garbled.
+pub fn extract_pdf_text(body: &[u8]) -> String {
+    let mut parts = Vec::new();
+    let mut pos = 0;
+
+    while let Some(start) = find_bytes(&body[pos..], b"stream").map(|p| pos + p) {
+        // Skip "endstream" matches
+        if start >= 3 && &body[start - 3..start] == b"end" {
+            pos = start + 6;
+            continue;
+        }
+
+        let mut data_start = start + 6;
+        if body.get(data_start) == Some(&b'\r') {
+            data_start += 1;
+        }
+        if body.get(data_start) == Some(&b'\n') {
+            data_start += 1;
+        }
+        let Some(end) = find_bytes(&body[data_start..], b"endstream").map(|p| data_start + p) else {
+            break;
+        };
+
+        // The stream dictionary precedes the keyword
+        let dict_start = start.saturating_sub(512);
+        let dict = String::from_utf8_lossy(&body[dict_start..start]);
+        let dict = dict.rsplit("obj").next().unwrap_or("");
+        let raw = &body[data_start..end];
+
+        let data = if dict.contains("/FlateDecode") {
+            inflate(raw)
+        } else if dict.contains("/Filter") {
+            None // Other filters (images, fonts) carry no text
+        } else {
+            Some(raw.to_vec())
+        };
+
+        if let Some(data) = data {
+            let content = String::from_utf8_lossy(&data);
+            if content.contains("BT") {
+                let text = extract_text_operators(&content);
+                if !text.is_empty() {
+                    parts.push(text);
+                }
+            }
+        }
+
+        pos = end + 9;
+    }
+
+    // Not a stream-structured document, read operators directly
+    if parts.is_empty() {
+        return extract_text_operators(&String::from_utf8_lossy(body));
+    }
+
+    parts.join(" ")
+}
+
+/// Decompress a zlib stream
+fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Read;
+
+    let mut out = Vec::new();
+    flate2::read::ZlibDecoder::new(data)
+        .read_to_end(&mut out)
+        .ok()
+        .map(|_| out)
+        .filter(|out| !out.is_empty())
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Read strings shown by text operators in a content stream
+fn extract_text_operators(content: &str) -> String {
+    let mut text = String::new();
+    let mut chars = content.chars();
+    let mut token = String::new();
+    let mut in_text = false;
+
+    while let Some(c) = chars.next() {
+        if !(c.is_whitespace() || c == '[' || c == ']' || c == '(') {
+            token.push(c);
+            continue;
+        }
+
+        match token.as_str() {
+            "BT" => in_text = true,
+            "ET" => {
+                in_text = false;
+                text.push(' ');
+            }
+            "Td" | "TD" | "T*" | "Tm" | "'" | "\"" => text.push(' '),
+            // Large negative TJ kerning is an inter-word gap
+            t if in_text && t.parse::<f32>().is_ok_and(|n| n < -200.0) => text.push(' '),
+            _ => {}
+        }
+        token.clear();
+
+        if c == '(' && in_text {
+            let mut depth = 1;
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => match chars.next() {
+                        Some('n') | Some('r') => text.push(' '),
+                        Some(escaped) if !escaped.is_ascii_digit() => text.push(escaped),
+                        _ => {}
+                    },
+                    '(' => {
+                        depth += 1;
+                        text.push(c);
+                    }
+                    ')' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            break;
+                        }
+                        text.push(c);
+                    }
+                    _ if !c.is_control() => text.push(c),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(dir: &tempfile::TempDir, quota_bytes: u64) -> ArchiveConfig {
+        ArchiveConfig {
+            archive_dir: dir.path().to_path_buf(),
+            quota_bytes,
+            ..Default::default()
+        }
+    }
+
+    fn entry(id: &str, bytes: u64, age_secs: i64) -> ArchivedEntry {
+        ArchivedEntry {
+            finding_id: id.to_string(),
+            url: format!("https://example.com/{}", id),
+            content_type: "text/html".to_string(),
+            content_hash: String::new(),
+            text_bytes: bytes,
+            archived_at: Utc::now() - chrono::Duration::seconds(age_secs),
+        }
+    }
+
+    #[test]
+    fn test_html_to_text() {
+        let html = "<html><style>p{}</style><body><h1>Title</h1><p>Hello &amp; welcome</p><script>x()</script></body></html>";
+        assert_eq!(html_to_text(html), "Title Hello & welcome");
+    }
+
+    #[test]
+    fn test_extract_pdf_text_from_compressed_stream() {
+        use std::io::Write;
+
+        let content = b"BT /F1 12 Tf 72 700 Td [(Deep)-333(lear)10(ning)]TJ 0 -14 Td (works \\(well\\)) Tj ET";
+        let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(content).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut pdf = b"%PDF-1.5\n4 0 obj\n<< /Length 10 /Filter /FlateDecode >>\nstream\n".to_vec();
+        pdf.extend_from_slice(&compressed);
+        pdf.extend_from_slice(b"\nendstream\nendobj\n");
+
+        assert_eq!(extract_pdf_text(&pdf), "Deep learning works (well)");
+    }
+
+    /// Serve one response per entry of `responses`, then stop
+    fn serve(responses: Vec<String>) -> String {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/doc", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for (stream, response) in listener.incoming().zip(responses) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf);
+                // The client may hang up once it has seen enough
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+        url
+    }
+
+    fn finding(url: String) -> ResearchFinding {
+        ResearchFinding {
+            id: uuid::Uuid::new_v4().to_string(),
+            source: crate::commander::ResearchSource::GitHub,
+            title: "doc".to_string(),
+            summary: String::new(),
+            relevance_score: 0.9,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: Some(url),
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_download_cap_applies_before_buffering() {
+        let chunk = "x".repeat(600);
+        let chunked = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\ntransfer-encoding: chunked\r\nconnection: close\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+            chunk.len(), chunk, chunk.len(), chunk
+        );
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\ncontent-length: 5000000\r\nconnection: close\r\n\r\n".to_string(),
+            chunked,
+            "HTTP/1.1 200 OK\r\ncontent-type: text/plain\r\ncontent-length: 5\r\nconnection: close\r\n\r\nsmall".to_string(),
+        ]);
+        let dir = tempfile::tempdir().unwrap();
+        let archive = FindingArchive::new(ArchiveConfig {
+            max_download_bytes: 1000,
+            ..test_config(&dir, 1024)
+        });
+
+        // Refused from the declared length, then while streaming an undeclared one
+        let declared = archive.archive(&finding(url.clone())).await.unwrap_err();
+        assert!(declared.to_string().contains("5000000"));
+        let streamed = archive.archive(&finding(url.clone())).await.unwrap_err();
+        assert!(streamed.to_string().contains("too large"));
+
+        let entry = archive.archive(&finding(url)).await.unwrap();
+        assert_eq!(entry.text_bytes, 5);
+    }
+
+    #[tokio::test]
+    async fn test_store_and_get() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = FindingArchive::new(test_config(&dir, 1024));
+        archive.store(entry("a", 5, 0), "hello").await.unwrap();
+
+        let content = archive.get("a").await.unwrap();
+        assert_eq!(content.text, "hello");
+        assert!(archive.get("missing").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_quota_evicts_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = FindingArchive::new(test_config(&dir, 10));
+        archive.store(entry("old", 6, 60), "oldest").await.unwrap();
+        archive.store(entry("new", 6, 0), "newest").await.unwrap();
+
+        assert!(archive.get("old").await.is_none());
+        assert!(archive.get("new").await.is_some());
+        assert_eq!(archive.used_bytes().await, 6);
+    }
+
+    #[test]
+    fn test_archiving_is_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = FindingArchive::new(test_config(&dir, 1024));
+        let finding = ResearchFinding {
+            id: "f".to_string(),
+            source: crate::commander::ResearchSource::GitHub,
+            title: "Rust agents".to_string(),
+            summary: String::new(),
+            relevance_score: 0.9,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: Some("https://example.com/f".to_string()),
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        };
+
+        assert!(!archive.should_archive(&finding));
+        archive.set_enabled(true);
+        assert!(archive.should_archive(&finding));
+    }
+}
@@ -0,0 +1,196 @@
+// Findings Dedup - Canonical content hashing shared with the Python services
+// canonical_text/canonical_hash must stay identical to cirkelline_native's
+
+use crate::commander::ResearchFinding;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use xxhash_rust::xxh3::xxh3_64;
+
+/// Default number of hashes remembered by the dedup store
+const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Words that say nothing about a topic (English and Danish)
+const STOPWORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "that", "this", "are", "was", "you", "your", "our", "new",
+    "how", "why", "what", "using", "based", "via", "towards", "its", "not", "but", "can", "all", "now",
+    "og", "med", "fra", "til", "der", "det", "den", "som", "har", "ikke", "nye", "nyt", "hvordan",
+];
+
+/// Normalized text: lowercase, punctuation dropped, whitespace collapsed
+pub fn canonical_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+/// Words of the canonical text that can name a topic: three letters or more, no stopwords
+pub fn keywords(text: &str) -> Vec<String> {
+    canonical_text(text)
+        .split_whitespace()
+        .filter(|word| word.chars().count() >= 3 && !STOPWORDS.contains(word))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Keywords of a finding's title and tags, as used for trends and highlights
+pub fn finding_keywords(finding: &ResearchFinding) -> BTreeSet<String> {
+    let mut words: BTreeSet<String> = keywords(&finding.title).into_iter().collect();
+    for tag in &finding.tags {
+        words.extend(keywords(tag));
+    }
+    words
+}
+
+/// xxh3 of the canonical text
+pub fn canonical_hash(text: &str) -> u64 {
+    xxh3_64(canonical_text(text).as_bytes())
+}
+
+/// Content hash of a finding (title and summary)
+pub fn finding_hash(finding: &ResearchFinding) -> u64 {
+    canonical_hash(&format!("{}\n{}", finding.title, finding.summary))
+}
+
+/// URL with the parts that differ between links to the same page removed:
+/// scheme, `www.`, fragment, trailing slash and tracking parameters
+pub fn canonical_url(url: &str) -> Option<String> {
+    let parsed = reqwest::Url::parse(url.trim()).ok()?;
+    let host = parsed.host_str()?;
+    let host = host.strip_prefix("www.").unwrap_or(host);
+
+    let query: Vec<String> = parsed
+        .query_pairs()
+        .filter(|(key, _)| !is_tracking_param(key))
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    let path = parsed.path().trim_end_matches('/');
+
+    Some(match query.is_empty() {
+        true => format!("{}{}", host, path),
+        false => format!("{}{}?{}", host, path, query.join("&")),
+    })
+}
+
+fn is_tracking_param(key: &str) -> bool {
+    key.starts_with("utm_") || matches!(key, "ref" | "ref_src" | "fbclid" | "gclid" | "mc_cid" | "mc_eid")
+}
+
+/// 64-bit simhash of the canonical text's word pairs; near-identical texts
+/// differ in only a few bits
+pub fn simhash(text: &str) -> u64 {
+    let canonical = canonical_text(text);
+    let words: Vec<&str> = canonical.split_whitespace().collect();
+    let shingles: Vec<String> = match words.len() {
+        0 => return 0,
+        1 => vec![words[0].to_string()],
+        _ => words.windows(2).map(|pair| pair.join(" ")).collect(),
+    };
+
+    let mut weights = [0i32; 64];
+    for shingle in &shingles {
+        let hash = xxh3_64(shingle.as_bytes());
+        for (bit, weight) in weights.iter_mut().enumerate() {
+            *weight += if (hash >> bit) & 1 == 1 { 1 } else { -1 };
+        }
+    }
+    weights
+        .iter()
+        .enumerate()
+        .filter(|(_, weight)| **weight > 0)
+        .fold(0, |hash, (bit, _)| hash | (1 << bit))
+}
+
+/// Number of bits two simhashes differ in
+pub fn simhash_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Bounded set of content hashes already seen; oldest are forgotten first
+#[derive(Debug)]
+pub struct FindingsDedup {
+    seen: HashSet<u64>,
+    order: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl FindingsDedup {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            seen: HashSet::new(),
+            order: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record a hash; false if it was already seen
+    pub fn insert(&mut self, hash: u64) -> bool {
+        if !self.seen.insert(hash) {
+            return false;
+        }
+        self.order.push_back(hash);
+        if self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+impl Default for FindingsDedup {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Vectors shared with the Python native module tests
+    const VECTORS: &str = include_str!("../../../../cirkelline/native/test_vectors/canonical_hash.json");
+
+    #[test]
+    fn test_shared_vectors() {
+        let file: serde_json::Value = serde_json::from_str(VECTORS).unwrap();
+        let vectors = file["vectors"].as_array().unwrap();
+        assert!(!vectors.is_empty());
+
+        for vector in vectors {
+            let input = vector["input"].as_str().unwrap();
+            assert_eq!(canonical_text(input), vector["canonical"].as_str().unwrap(), "{:?}", input);
+            assert_eq!(canonical_hash(input), vector["hash"].as_u64().unwrap(), "{:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_dedup_forgets_oldest() {
+        let mut dedup = FindingsDedup::new(2);
+        assert!(dedup.insert(1));
+        assert!(!dedup.insert(1));
+        assert!(dedup.insert(2));
+        assert!(dedup.insert(3));
+        assert!(!dedup.insert(3));
+        // 1 was evicted by 3
+        assert!(dedup.insert(1));
+    }
+
+    #[test]
+    fn test_canonical_url_and_simhash() {
+        assert_eq!(
+            canonical_url("http://www.example.com/post/?utm_source=rss&id=7#comments").as_deref(),
+            Some("example.com/post?id=7")
+        );
+        assert_eq!(canonical_url("https://example.com/post"), canonical_url("https://EXAMPLE.com/post/"));
+        assert_eq!(canonical_url("not a url"), None);
+
+        let text = "Tokio 2.0 released with a new scheduler for async Rust services and lower tail latency";
+        let reworded = "Tokio 2.0 released with a new scheduler for async Rust services and much lower tail latency";
+        let other = "Survey of retrieval augmented generation for question answering over tables";
+        assert!(simhash_distance(simhash(text), simhash(reworded)) < simhash_distance(simhash(text), simhash(other)));
+        assert_eq!(simhash(text), simhash(&text.to_uppercase()));
+    }
+}
@@ -0,0 +1,302 @@
+// Deep Analysis - Full-text processing of papers picked for DeepAnalyze
+// Downloads the arXiv PDF, chunks and embeds it, and extracts key excerpts
+
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::inference::{shared_embedder, BenchmarkTask, InferenceEngine, InferenceLane};
+use crate::models::LocalKnowledgeChunk;
+use crate::research::archive::{download_capped, extract_pdf_text};
+use crate::research::dedup::finding_keywords;
+use crate::research::knowledge::KnowledgeStore;
+use crate::research::traits::{ResearchError, ResearchResult};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use crate::telemetry::network::{self, NetworkSubsystem};
+
+/// Target chunk size in characters
+const CHUNK_CHARS: usize = 1200;
+
+/// Overlap between consecutive chunks in characters
+const CHUNK_OVERLAP: usize = 200;
+
+/// Maximum PDF size downloaded for analysis
+const MAX_PDF_BYTES: usize = 20 * 1024 * 1024;
+
+/// Number of highlights attached to a finding
+const MAX_HIGHLIGHTS: usize = 3;
+
+/// Result of a deep analysis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepAnalysis {
+    pub finding_id: String,
+    pub chunks_stored: usize,
+    /// Whether chunks were embedded (requires the embedding model)
+    pub embedded: bool,
+    /// Key excerpts from the full text
+    pub highlights: Vec<String>,
+    pub analyzed_at: DateTime<Utc>,
+}
+
+/// Worker performing deep analysis of findings
+pub struct DeepAnalyzer {
+    client: reqwest::Client,
+    store: Arc<KnowledgeStore>,
+    inference: Option<Arc<RwLock<Option<InferenceEngine>>>>,
+}
+
+impl DeepAnalyzer {
+    pub fn new(store: Arc<KnowledgeStore>) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent("CLA-ResearchAdapter/1.0")
+            .timeout(Duration::from_secs(120))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            store,
+            inference: None,
+        }
+    }
+
+    /// Embed chunks with the shared inference engine when it is loaded
+    pub fn with_inference(mut self, inference: Arc<RwLock<Option<InferenceEngine>>>) -> Self {
+        self.inference = Some(inference);
+        self
+    }
+
+    /// Whether a finding can be deep-analyzed
+    pub fn supports(finding: &ResearchFinding) -> bool {
+        finding.source == ResearchSource::ArXiv && pdf_url(finding).is_some()
+    }
+
+    /// Download, chunk, embed and highlight a paper
+    pub async fn analyze(&self, finding: &ResearchFinding) -> ResearchResult<DeepAnalysis> {
+        let url = pdf_url(finding)
+            .ok_or_else(|| ResearchError::InvalidQuery(format!("No PDF for finding {}", finding.id)))?;
+
+        // Streamed under the size cap and metered per chunk, like archived documents
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| ResearchError::NetworkError(e.to_string()))?;
+        network::meter().record(NetworkSubsystem::Research, url.len() as u64, 0);
+
+        if !response.status().is_success() {
+            return Err(ResearchError::ApiError {
+                status: response.status().as_u16(),
+                message: format!("Failed to download {}", url),
+            });
+        }
+
+        let body = download_capped(response, MAX_PDF_BYTES).await?;
+
+        let text = extract_pdf_text(&body);
+        if text.split_whitespace().count() < 50 {
+            return Err(ResearchError::ParseError("No text could be extracted from PDF".to_string()));
+        }
+
+        let chunks = chunk_text(&text, CHUNK_CHARS, CHUNK_OVERLAP);
+        let (knowledge, embedded) = self.embed_chunks(finding, &chunks).await;
+        let chunks_stored = knowledge.len();
+        self.store.replace_source(&finding.id, knowledge).await?;
+
+        let highlights = extract_highlights(&chunks, &finding_keywords(finding), MAX_HIGHLIGHTS);
+
+        log::info!(
+            "Deep analysis of {} stored {} chunks ({} highlights)",
+            finding.id,
+            chunks_stored,
+            highlights.len()
+        );
+
+        Ok(DeepAnalysis {
+            finding_id: finding.id.clone(),
+            chunks_stored,
+            embedded,
+            highlights,
+            analyzed_at: Utc::now(),
+        })
+    }
+
+    /// Build knowledge chunks, embedding them if a model is loaded
+    async fn embed_chunks(
+        &self,
+        finding: &ResearchFinding,
+        chunks: &[String],
+    ) -> (Vec<LocalKnowledgeChunk>, bool) {
+        let embedder = match &self.inference {
+            Some(inference) => {
+                if let Some(estimate) = inference
+                    .read()
+                    .await
+                    .as_ref()
+                    .and_then(|e| e.estimate_duration(BenchmarkTask::Embedding, chunks.len()))
+                {
+                    log::info!(
+                        "Embedding {} chunks of {} (estimated {:.1}s)",
+                        chunks.len(),
+                        finding.id,
+                        estimate.as_secs_f32()
+                    );
+                }
+                shared_embedder(inference).await
+            }
+            None => None,
+        };
+
+        let mut embedded = embedder.is_some();
+        let mut knowledge = Vec::with_capacity(chunks.len());
+
+        for (index, content) in chunks.iter().enumerate() {
+            let embedding = match &embedder {
+                Some(embedder) => match embedder.embed_in(InferenceLane::Background, content).await {
+                    Ok(embedding) => embedding,
+                    Err(e) => {
+                        log::warn!("Embedding chunk {} of {} failed: {}", index, finding.id, e);
+                        embedded = false;
+                        Vec::new()
+                    }
+                },
+                None => Vec::new(),
+            };
+
+            knowledge.push(LocalKnowledgeChunk {
+                id: uuid::Uuid::new_v4(),
+                source_id: finding.id.clone(),
+                content: content.clone(),
+                embedding_local: embedding,
+                metadata: serde_json::json!({
+                    "title": finding.title,
+                    "url": finding.url,
+                    "chunk_index": index,
+                }),
+                priority: (finding.relevance_score * 10.0) as u8,
+                expires_at: None,
+            });
+        }
+
+        (knowledge, embedded)
+    }
+}
+
+/// PDF URL of an arXiv finding (abs page -> pdf)
+pub fn pdf_url(finding: &ResearchFinding) -> Option<String> {
+    if let Some(url) = finding.metadata.get("pdf_url").and_then(|v| v.as_str()) {
+        return Some(url.to_string());
+    }
+    finding
+        .url
+        .as_ref()
+        .filter(|url| url.contains("arxiv.org/abs/"))
+        .map(|url| url.replace("/abs/", "/pdf/").replace("http://", "https://"))
+}
+
+/// Split text into overlapping chunks, preferring sentence boundaries
+pub fn chunk_text(text: &str, chunk_chars: usize, overlap: usize) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < chars.len() {
+        let mut end = (start + chunk_chars).min(chars.len());
+
+        // Back up to the last sentence end in the second half of the chunk
+        if end < chars.len() {
+            if let Some(offset) = chars[start + chunk_chars / 2..end]
+                .iter()
+                .rposition(|c| matches!(c, '.' | '!' | '?'))
+            {
+                end = start + chunk_chars / 2 + offset + 1;
+            }
+        }
+
+        let chunk: String = chars[start..end].iter().collect();
+        let chunk = chunk.trim();
+        if !chunk.is_empty() {
+            chunks.push(chunk.to_string());
+        }
+
+        if end >= chars.len() {
+            break;
+        }
+        start = end.saturating_sub(overlap).max(start + 1);
+    }
+
+    chunks
+}
+
+/// Pick the sentences that best cover the keywords
+pub fn extract_highlights(chunks: &[String], keywords: &BTreeSet<String>, max: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut scored: Vec<(usize, String)> = chunks
+        .iter()
+        .flat_map(|chunk| chunk.split_inclusive(['.', '!', '?']))
+        .map(|s| s.trim().to_string())
+        .filter(|s| (60..=400).contains(&s.len()) && seen.insert(s.clone()))
+        .map(|sentence| {
+            let lower = sentence.to_lowercase();
+            let hits = keywords.iter().filter(|k| lower.contains(k.as_str())).count();
+            (hits, sentence)
+        })
+        .filter(|(hits, _)| *hits > 0)
+        .collect();
+
+    // Stable sort keeps document order among equally relevant sentences
+    scored.sort_by_key(|(hits, _)| std::cmp::Reverse(*hits));
+    scored.into_iter().take(max).map(|(_, s)| s).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(url: &str) -> ResearchFinding {
+        ResearchFinding {
+            id: "arxiv-2401.00001".to_string(),
+            source: ResearchSource::ArXiv,
+            title: "Sparse Attention for Long Documents".to_string(),
+            summary: String::new(),
+            relevance_score: 0.9,
+            discovered_at: Utc::now(),
+            tags: vec!["cs.CL".to_string()],
+            url: Some(url.to_string()),
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[test]
+    fn test_pdf_url_from_abs_link() {
+        let f = finding("http://arxiv.org/abs/2401.00001v1");
+        assert_eq!(pdf_url(&f).as_deref(), Some("https://arxiv.org/pdf/2401.00001v1"));
+        assert!(DeepAnalyzer::supports(&f));
+    }
+
+    #[test]
+    fn test_chunk_text_overlaps() {
+        let text = "One sentence here. ".repeat(200);
+        let chunks = chunk_text(&text, 300, 50);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.chars().count() <= 300));
+        assert!(chunks[0].ends_with('.'));
+    }
+
+    #[test]
+    fn test_highlights_prefer_keyword_sentences() {
+        let chunks = vec![
+            "We thank the reviewers for their helpful comments on this manuscript. Sparse attention reduces the cost of long documents to linear time in sequence length.".to_string(),
+        ];
+        let highlights = extract_highlights(&chunks, &finding_keywords(&finding("")), 3);
+
+        assert_eq!(highlights.len(), 1);
+        assert!(highlights[0].starts_with("Sparse attention"));
+    }
+}
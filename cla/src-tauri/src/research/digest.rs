@@ -0,0 +1,421 @@
+// Research Digest - Findings of a period grouped by topic
+// Findings are clustered agglomeratively on their embeddings, or on keyword
+// vectors when no embedding model is loaded; each topic gets a label from its
+// most telling words, its best findings and an aggregate score
+
+use crate::commander::ResearchFinding;
+use crate::inference::{cosine_similarity, embed_texts, InferenceEngine};
+use crate::research::dedup::keywords;
+use crate::research::processors::embedding_text;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// Findings a digest is built from, best first
+pub const MAX_DIGEST_FINDINGS: usize = 300;
+
+/// Topics in a digest
+const MAX_TOPICS: usize = 8;
+
+/// Findings shown per topic
+const TOP_FINDINGS_PER_TOPIC: usize = 3;
+
+/// Centroid similarity at which clusters are merged, per vector kind
+const EMBEDDING_THRESHOLD: f32 = 0.75;
+const KEYWORD_THRESHOLD: f32 = 0.35;
+
+/// Words in a topic label
+const LABEL_WORDS: usize = 3;
+
+/// Time a digest covers
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DigestPeriod {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl DigestPeriod {
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+            Self::Month => Duration::days(30),
+        }
+    }
+
+    fn spoken(self, is_danish: bool) -> &'static str {
+        match (self, is_danish) {
+            (Self::Day, true) => "det seneste døgn",
+            (Self::Day, false) => "the last day",
+            (Self::Week, true) => "den seneste uge",
+            (Self::Week, false) => "the last week",
+            (Self::Month, true) => "den seneste måned",
+            (Self::Month, false) => "the last month",
+        }
+    }
+}
+
+/// Findings about one topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DigestTopic {
+    pub label: String,
+    pub finding_count: usize,
+    /// Sum of the topic's relevance scores; topics are ordered by it
+    pub aggregate_score: f32,
+    /// Best first
+    pub top_findings: Vec<ResearchFinding>,
+}
+
+/// A period's findings grouped by topic
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResearchDigest {
+    pub period: DigestPeriod,
+    pub since: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    pub total_findings: usize,
+    /// Clustered on embeddings rather than keywords
+    pub semantic: bool,
+    /// Largest aggregate score first
+    pub topics: Vec<DigestTopic>,
+}
+
+impl ResearchDigest {
+    /// Short summary for text-to-speech
+    pub fn spoken_summary(&self, is_danish: bool) -> String {
+        let period = self.period.spoken(is_danish);
+        let Some(first) = self.topics.first() else {
+            return if is_danish {
+                format!("Ingen nye fund i {}.", period)
+            } else {
+                format!("No new findings in {}.", period)
+            };
+        };
+
+        let mut summary = if is_danish {
+            format!(
+                "Forskningsoversigt for {}: {} fund i {} emner. Største emne: {} med {} fund.",
+                period,
+                self.total_findings,
+                self.topics.len(),
+                first.label,
+                first.finding_count
+            )
+        } else {
+            format!(
+                "Research digest for {}: {} findings in {} topics. Top topic: {} with {} findings.",
+                period,
+                self.total_findings,
+                self.topics.len(),
+                first.label,
+                first.finding_count
+            )
+        };
+        if let Some(best) = first.top_findings.first() {
+            summary.push_str(&if is_danish {
+                format!(" Bedste fund: {}.", best.title)
+            } else {
+                format!(" Best finding: {}.", best.title)
+            });
+        }
+        let others: Vec<String> = self.topics[1..]
+            .iter()
+            .take(2)
+            .map(|topic| format!("{} ({})", topic.label, topic.finding_count))
+            .collect();
+        if !others.is_empty() {
+            summary.push_str(&if is_danish {
+                format!(" Derefter: {}.", others.join(", "))
+            } else {
+                format!(" Then: {}.", others.join(", "))
+            });
+        }
+        summary
+    }
+}
+
+/// Builds digests, embedding findings when a model is loaded
+pub struct DigestBuilder {
+    inference: Option<Arc<RwLock<Option<InferenceEngine>>>>,
+}
+
+impl DigestBuilder {
+    pub fn new() -> Self {
+        Self { inference: None }
+    }
+
+    /// Cluster on embeddings from the shared inference engine
+    pub fn with_inference(mut self, inference: Arc<RwLock<Option<InferenceEngine>>>) -> Self {
+        self.inference = Some(inference);
+        self
+    }
+
+    /// Digest of `findings` from the period starting at `since`
+    pub async fn build(
+        &self,
+        period: DigestPeriod,
+        since: DateTime<Utc>,
+        mut findings: Vec<ResearchFinding>,
+    ) -> ResearchDigest {
+        findings.sort_by(|a, b| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        findings.truncate(MAX_DIGEST_FINDINGS);
+
+        let (vectors, threshold, semantic) = match self.embed(&findings).await {
+            Some(embeddings) => (embeddings, EMBEDDING_THRESHOLD, true),
+            None => (keyword_vectors(&findings), KEYWORD_THRESHOLD, false),
+        };
+        let topics = topics(&findings, cluster(&vectors, threshold));
+
+        ResearchDigest {
+            period,
+            since,
+            generated_at: Utc::now(),
+            total_findings: findings.len(),
+            semantic,
+            topics,
+        }
+    }
+
+    async fn embed(&self, findings: &[ResearchFinding]) -> Option<Vec<Vec<f32>>> {
+        match embed_texts(self.inference.as_ref()?, findings.iter().map(embedding_text)).await? {
+            Ok(embeddings) => Some(embeddings),
+            Err(e) => {
+                log::warn!("Digest falls back to keyword clustering, embedding failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+impl Default for DigestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Agglomerative clustering: the two clusters with the most similar centroids are
+/// merged until no pair reaches `threshold`. Returns member indices per cluster.
+pub fn cluster(vectors: &[Vec<f32>], threshold: f32) -> Vec<Vec<usize>> {
+    let n = vectors.len();
+    let mut clusters: Vec<Option<(Vec<usize>, Vec<f32>)>> =
+        vectors.iter().enumerate().map(|(i, v)| Some((vec![i], v.clone()))).collect();
+    // Similarity of clusters i < j at [i][j]
+    let mut similarity = vec![vec![f32::MIN; n]; n];
+    for i in 0..n {
+        for j in i + 1..n {
+            similarity[i][j] = cosine_similarity(&vectors[i], &vectors[j]);
+        }
+    }
+
+    loop {
+        let mut best: Option<(usize, usize, f32)> = None;
+        for (i, row) in similarity.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate().skip(i + 1) {
+                let live = clusters[i].is_some() && clusters[j].is_some();
+                if live && value >= threshold && best.is_none_or(|(_, _, b)| value > b) {
+                    best = Some((i, j, value));
+                }
+            }
+        }
+        let Some((i, j, _)) = best else {
+            break;
+        };
+
+        let Some((merged_members, merged_centroid)) = clusters[j].take() else {
+            break;
+        };
+        let centroid = match clusters[i].as_mut() {
+            Some((members, centroid)) => {
+                let (a, b) = (members.len() as f32, merged_members.len() as f32);
+                for (value, other) in centroid.iter_mut().zip(&merged_centroid) {
+                    *value = (*value * a + other * b) / (a + b);
+                }
+                members.extend(merged_members);
+                centroid.clone()
+            }
+            None => break,
+        };
+        for (k, other) in clusters.iter().enumerate() {
+            if let Some((_, other_centroid)) = other.as_ref().filter(|_| k != i) {
+                let (low, high) = if k < i { (k, i) } else { (i, k) };
+                similarity[low][high] = cosine_similarity(&centroid, other_centroid);
+            }
+        }
+    }
+
+    clusters
+        .into_iter()
+        .flatten()
+        .map(|(mut members, _)| {
+            members.sort_unstable();
+            members
+        })
+        .collect()
+}
+
+/// Labelled topics from clusters of `findings` (sorted best first), largest aggregate score first
+fn topics(findings: &[ResearchFinding], clusters: Vec<Vec<usize>>) -> Vec<DigestTopic> {
+    let document_frequency = document_frequency(findings.iter().map(label_terms));
+    let mut topics: Vec<DigestTopic> = clusters
+        .into_iter()
+        .map(|members| {
+            let members: Vec<&ResearchFinding> = members.iter().map(|&i| &findings[i]).collect();
+            DigestTopic {
+                label: topic_label(&members, &document_frequency, findings.len()),
+                finding_count: members.len(),
+                aggregate_score: members.iter().map(|f| f.relevance_score).sum(),
+                top_findings: members.iter().take(TOP_FINDINGS_PER_TOPIC).map(|f| (*f).clone()).collect(),
+            }
+        })
+        .collect();
+    topics.sort_by(|a, b| {
+        b.aggregate_score
+            .partial_cmp(&a.aggregate_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    topics.truncate(MAX_TOPICS);
+    topics
+}
+
+/// Words shared by most of the topic's findings and few others; a lone finding is its own label
+fn topic_label(members: &[&ResearchFinding], overall: &HashMap<String, usize>, total: usize) -> String {
+    if members.len() == 1 {
+        return members[0].title.clone();
+    }
+    let in_topic = document_frequency(members.iter().map(|f| label_terms(f)));
+    let mut terms: Vec<(&String, f32)> = in_topic
+        .iter()
+        .map(|(term, &count)| {
+            let frequency = overall.get(term).copied().unwrap_or(count);
+            (term, count as f32 * idf(total, frequency))
+        })
+        .collect();
+    terms.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(b.0)));
+
+    let label: Vec<String> = terms.iter().take(LABEL_WORDS).map(|(term, _)| capitalize(term)).collect();
+    if label.is_empty() {
+        members[0].title.clone()
+    } else {
+        label.join(", ")
+    }
+}
+
+/// TF-IDF vectors over the words of titles, tags and summaries
+fn keyword_vectors(findings: &[ResearchFinding]) -> Vec<Vec<f32>> {
+    let terms: Vec<Vec<String>> = findings
+        .iter()
+        .map(|f| {
+            let mut terms = label_terms(f);
+            terms.extend(keywords(&f.summary));
+            terms
+        })
+        .collect();
+    let document_frequency = document_frequency(terms.iter().cloned());
+    let mut vocabulary: Vec<&String> = document_frequency.keys().collect();
+    vocabulary.sort();
+    let index: HashMap<&String, usize> = vocabulary.iter().enumerate().map(|(i, term)| (*term, i)).collect();
+
+    terms
+        .iter()
+        .map(|terms| {
+            let mut vector = vec![0.0; vocabulary.len()];
+            for term in terms {
+                vector[index[term]] += idf(findings.len(), document_frequency[term]);
+            }
+            vector
+        })
+        .collect()
+}
+
+/// Words a label may use: the title's and the tags
+fn label_terms(finding: &ResearchFinding) -> Vec<String> {
+    let mut terms = keywords(&finding.title);
+    for tag in &finding.tags {
+        terms.extend(keywords(tag));
+    }
+    terms
+}
+
+/// Number of documents each term occurs in
+fn document_frequency(documents: impl Iterator<Item = Vec<String>>) -> HashMap<String, usize> {
+    let mut frequency = HashMap::new();
+    for document in documents {
+        for term in document.into_iter().collect::<HashSet<_>>() {
+            *frequency.entry(term).or_insert(0) += 1;
+        }
+    }
+    frequency
+}
+
+fn idf(documents: usize, frequency: usize) -> f32 {
+    ((1 + documents) as f32 / (1 + frequency) as f32).ln() + 1.0
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commander::ResearchSource;
+
+    fn finding(title: &str, tags: &[&str], score: f32) -> ResearchFinding {
+        ResearchFinding {
+            id: title.to_string(),
+            source: ResearchSource::GitHub,
+            title: title.to_string(),
+            summary: String::new(),
+            relevance_score: score,
+            discovered_at: Utc::now(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[test]
+    fn test_cluster_merges_close_vectors() {
+        let vectors = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.95, 0.1], vec![0.1, 0.9], vec![-1.0, 0.0]];
+        let clusters = cluster(&vectors, 0.9);
+        assert_eq!(clusters, vec![vec![0, 2], vec![1, 3], vec![4]]);
+    }
+
+    #[tokio::test]
+    async fn test_keyword_digest_groups_and_labels_topics() {
+        let findings = vec![
+            finding("Tokio runtime scheduler rewrite", &["rust", "async"], 0.8),
+            finding("Async Rust runtime benchmarks", &["rust", "async"], 0.7),
+            finding("Rust async runtime for embedded", &["rust"], 0.6),
+            finding("Retrieval augmented generation survey", &["llm"], 0.9),
+        ];
+        let digest = DigestBuilder::new().build(DigestPeriod::Week, Utc::now(), findings).await;
+
+        assert!(!digest.semantic);
+        assert_eq!(digest.total_findings, 4);
+        assert_eq!(digest.topics.len(), 2);
+        let rust = &digest.topics[0];
+        assert_eq!(rust.finding_count, 3);
+        assert!((rust.aggregate_score - 2.1).abs() < 1e-5);
+        assert_eq!(rust.top_findings[0].title, "Tokio runtime scheduler rewrite");
+        assert!(rust.label.contains("Runtime"), "{}", rust.label);
+        assert_eq!(digest.topics[1].label, "Retrieval augmented generation survey");
+
+        let spoken = digest.spoken_summary(true);
+        assert!(spoken.starts_with("Forskningsoversigt for den seneste uge: 4 fund i 2 emner."), "{}", spoken);
+    }
+}
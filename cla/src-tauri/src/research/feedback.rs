@@ -0,0 +1,231 @@
+// Score Feedback - Users' useful / not useful ratings of findings and what the
+// relevance scorer learns from them. Each rating nudges the factor weights by a
+// gradient step towards the rating and moves the source's authority as a moving
+// average; the learned state is replayed from the stored ratings, so rating a
+// finding again replaces its earlier rating instead of counting twice.
+
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::processors::{RelevanceScorer, ScoreBreakdown, ScoringWeights};
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+/// Ratings kept; the oldest are dropped first
+const MAX_RATINGS: usize = 1000;
+/// Step size of the weight update
+const LEARNING_RATE: f32 = 0.05;
+/// How far one rating moves a source's authority
+const AUTHORITY_RATE: f32 = 0.1;
+/// No factor is learned away completely
+const MIN_WEIGHT: f32 = 0.02;
+const MIN_AUTHORITY: f32 = 0.05;
+
+/// A user's verdict on a finding, with the factor scores it had when rated
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingRating {
+    pub finding_id: String,
+    pub source: ResearchSource,
+    pub useful: bool,
+    pub breakdown: ScoreBreakdown,
+    pub rated_at: DateTime<Utc>,
+}
+
+/// Learned authority of one source
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SourceAuthority {
+    pub source: ResearchSource,
+    pub authority: f32,
+    pub ratings: usize,
+}
+
+/// Scoring learned from all ratings so far
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LearnedScoring {
+    pub weights: ScoringWeights,
+    /// Sources with ratings; the others keep their built-in authority
+    pub sources: Vec<SourceAuthority>,
+    pub ratings: usize,
+    pub useful: usize,
+}
+
+impl LearnedScoring {
+    /// Replay ratings, oldest first, from the built-in weights
+    pub fn from_ratings(ratings: &[FindingRating]) -> Self {
+        let mut learned = Self::default();
+        for rating in ratings {
+            learned.learn(rating);
+        }
+        learned
+    }
+
+    fn learn(&mut self, rating: &FindingRating) {
+        let target = if rating.useful { 1.0 } else { 0.0 };
+        let b = &rating.breakdown;
+
+        let index = match self.sources.iter().position(|s| s.source == rating.source) {
+            Some(index) => index,
+            None => {
+                self.sources.push(SourceAuthority {
+                    source: rating.source.clone(),
+                    authority: RelevanceScorer::builtin_authority(&rating.source),
+                    ratings: 0,
+                });
+                self.sources.len() - 1
+            }
+        };
+        let authority = self.sources[index].authority;
+
+        // Gradient step on the squared error between the score and the rating,
+        // with the authority the scorer would use now
+        let w = &mut self.weights;
+        let features = [b.keyword_match.raw, b.recency.raw, authority, b.engagement.raw];
+        let weights = [&mut w.keyword_match, &mut w.recency, &mut w.source_authority, &mut w.engagement];
+        let predicted: f32 = features.iter().zip(&weights).map(|(x, w)| x * **w).sum();
+        let error = target - predicted.clamp(0.0, 1.0);
+        let total: f32 = weights.iter().map(|w| **w).sum();
+        for (weight, x) in weights.into_iter().zip(features) {
+            *weight = (*weight + LEARNING_RATE * error * x).max(MIN_WEIGHT);
+        }
+        // Keep the weights' sum so scores stay on the same scale
+        let learned_total = w.keyword_match + w.recency + w.source_authority + w.engagement;
+        for weight in [&mut w.keyword_match, &mut w.recency, &mut w.source_authority, &mut w.engagement] {
+            *weight *= total / learned_total;
+        }
+
+        let source = &mut self.sources[index];
+        source.authority = (authority + AUTHORITY_RATE * (target - authority)).clamp(MIN_AUTHORITY, 1.0);
+        source.ratings += 1;
+        self.ratings += 1;
+        if rating.useful {
+            self.useful += 1;
+        }
+    }
+}
+
+/// Persisted ratings and the scoring learned from them
+pub struct ScoreFeedback {
+    file: JournaledFile,
+    ratings: RwLock<Vec<FindingRating>>,
+    learned: RwLock<LearnedScoring>,
+}
+
+impl ScoreFeedback {
+    /// Open the feedback store, loading existing ratings from disk
+    pub fn new(path: PathBuf) -> Self {
+        let file = JournaledFile::new("findings_feedback", path);
+        // A damaged store is moved aside and reported; start without ratings
+        let ratings: Vec<FindingRating> = file.load().ok().flatten().unwrap_or_default();
+        let learned = LearnedScoring::from_ratings(&ratings);
+
+        Self {
+            file,
+            ratings: RwLock::new(ratings),
+            learned: RwLock::new(learned),
+        }
+    }
+
+    /// Record whether a finding was useful and learn from it
+    pub async fn rate(&self, finding: &ResearchFinding, useful: bool) -> ResearchResult<LearnedScoring> {
+        let breakdown = finding
+            .score_breakdown
+            .clone()
+            .unwrap_or_else(|| RelevanceScorer::new().breakdown(finding));
+
+        let mut ratings = self.ratings.write().await;
+        ratings.retain(|r| r.finding_id != finding.id);
+        ratings.push(FindingRating {
+            finding_id: finding.id.clone(),
+            source: finding.source.clone(),
+            useful,
+            breakdown,
+            rated_at: Utc::now(),
+        });
+        if ratings.len() > MAX_RATINGS {
+            let excess = ratings.len() - MAX_RATINGS;
+            ratings.drain(..excess);
+        }
+
+        let learned = LearnedScoring::from_ratings(&ratings);
+        *self.learned.write().await = learned.clone();
+        let json = serde_json::to_string(&*ratings).map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        self.file
+            .write(json.as_bytes())
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))?;
+        Ok(learned)
+    }
+
+    /// Scoring learned so far
+    pub async fn learned(&self) -> LearnedScoring {
+        self.learned.read().await.clone()
+    }
+}
+
+impl Default for ScoreFeedback {
+    fn default() -> Self {
+        Self::new(
+            dirs::data_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("findings_feedback.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(id: &str, source: ResearchSource, title: &str) -> ResearchFinding {
+        let mut finding = ResearchFinding {
+            id: id.to_string(),
+            source,
+            title: title.to_string(),
+            summary: String::new(),
+            relevance_score: 0.0,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        };
+        RelevanceScorer::with_keywords(vec!["rust".to_string()]).apply(&mut finding, Utc::now());
+        finding
+    }
+
+    #[tokio::test]
+    async fn test_ratings_adjust_weights_and_authority() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("feedback.json");
+        let feedback = ScoreFeedback::new(path.clone());
+        let base = ScoringWeights::default();
+
+        // Keyword matches from GitHub keep being useful, keyword-less tweets do not
+        for n in 0..10 {
+            feedback.rate(&finding(&format!("g{}", n), ResearchSource::GitHub, "Rust crate"), true).await.unwrap();
+            feedback.rate(&finding(&format!("t{}", n), ResearchSource::Twitter, "Hot take"), false).await.unwrap();
+        }
+        let learned = feedback.learned().await;
+        assert_eq!((learned.ratings, learned.useful), (20, 10));
+        assert!(learned.weights.keyword_match > base.keyword_match);
+        let sum = |w: &ScoringWeights| w.keyword_match + w.recency + w.source_authority + w.engagement;
+        assert!((sum(&learned.weights) - sum(&base)).abs() < 1e-4);
+        let authority = |source: ResearchSource| learned.sources.iter().find(|s| s.source == source).unwrap().authority;
+        assert!(authority(ResearchSource::GitHub) > RelevanceScorer::builtin_authority(&ResearchSource::GitHub));
+        assert!(authority(ResearchSource::Twitter) < RelevanceScorer::builtin_authority(&ResearchSource::Twitter));
+
+        // Changing one's mind replaces the rating, and ratings survive a restart
+        feedback.rate(&finding("g0", ResearchSource::GitHub, "Rust crate"), false).await.unwrap();
+        let reopened = ScoreFeedback::new(path.clone());
+        assert_eq!(reopened.learned().await, feedback.learned().await);
+        assert_eq!((reopened.learned().await.ratings, reopened.learned().await.useful), (20, 9));
+
+        // The learned scorer ranks a tweet lower than the built-in one did
+        let tweet = finding("t-new", ResearchSource::Twitter, "Rust crate");
+        let learned_scorer = RelevanceScorer::with_keywords(vec!["rust".to_string()]).with_learned(&reopened.learned().await);
+        assert!(learned_scorer.breakdown(&tweet).source_authority.raw < tweet.score_breakdown.as_ref().unwrap().source_authority.raw);
+    }
+}
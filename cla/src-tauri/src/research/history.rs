@@ -0,0 +1,393 @@
+// Findings History - What each finding looked like over time
+// Powers "what changed since yesterday" reports without rescanning the full list
+
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::dedup::finding_hash;
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+
+/// Findings remembered; the least recently seen are dropped first
+const MAX_RECORDS: usize = 5_000;
+
+/// Score observations kept per finding
+const MAX_SCORE_POINTS: usize = 30;
+
+/// Smallest relevance change reported as a score movement
+const MIN_SCORE_DELTA: f32 = 0.05;
+
+/// Score of a finding at one observation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScorePoint {
+    pub at: DateTime<Utc>,
+    pub score: f32,
+}
+
+/// Everything remembered about one finding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingRecord {
+    pub id: String,
+    pub source: ResearchSource,
+    pub title: String,
+    pub url: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Canonical hash of title and summary at the last observation
+    pub content_hash: u64,
+    /// When the content hash last changed
+    pub content_updated_at: Option<DateTime<Utc>>,
+    pub scores: Vec<ScorePoint>,
+}
+
+impl FindingRecord {
+    fn current_score(&self) -> f32 {
+        self.scores.last().map_or(0.0, |p| p.score)
+    }
+
+    /// Last score observed before `since`
+    fn score_before(&self, since: DateTime<Utc>) -> Option<f32> {
+        self.scores.iter().rev().find(|p| p.at < since).map(|p| p.score)
+    }
+}
+
+/// A finding listed in a diff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffEntry {
+    pub id: String,
+    pub source: ResearchSource,
+    pub title: String,
+    pub url: Option<String>,
+    pub relevance_score: f32,
+}
+
+impl From<&FindingRecord> for DiffEntry {
+    fn from(record: &FindingRecord) -> Self {
+        Self {
+            id: record.id.clone(),
+            source: record.source.clone(),
+            title: record.title.clone(),
+            url: record.url.clone(),
+            relevance_score: record.current_score(),
+        }
+    }
+}
+
+/// Relevance movement of a finding seen on both sides of the cutoff
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreChange {
+    pub id: String,
+    pub source: ResearchSource,
+    pub title: String,
+    pub previous_score: f32,
+    pub current_score: f32,
+    pub delta: f32,
+}
+
+/// Changes in the findings since a point in time
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingsDiff {
+    pub since: DateTime<Utc>,
+    pub generated_at: DateTime<Utc>,
+    /// First seen after the cutoff, best first
+    pub new_findings: Vec<DiffEntry>,
+    /// Known before the cutoff, title or summary changed after it
+    pub updated_findings: Vec<DiffEntry>,
+    /// Largest movements first
+    pub score_changes: Vec<ScoreChange>,
+}
+
+impl FindingsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.new_findings.is_empty() && self.updated_findings.is_empty() && self.score_changes.is_empty()
+    }
+
+    /// Short summary for text-to-speech
+    pub fn spoken_summary(&self, is_danish: bool) -> String {
+        if self.is_empty() {
+            return if is_danish {
+                "Ingen ændringer i fund siden sidst.".to_string()
+            } else {
+                "No changes in findings since last time.".to_string()
+            };
+        }
+
+        let mut per_source: Vec<(&ResearchSource, usize)> = Vec::new();
+        for entry in &self.new_findings {
+            match per_source.iter_mut().find(|(source, _)| **source == entry.source) {
+                Some((_, count)) => *count += 1,
+                None => per_source.push((&entry.source, 1)),
+            }
+        }
+
+        let mut parts: Vec<String> = per_source
+            .into_iter()
+            .map(|(source, count)| format!("{} {}", count, new_noun(source, count, is_danish)))
+            .collect();
+        let updated = self.updated_findings.len();
+        if updated > 0 {
+            parts.push(match (is_danish, updated) {
+                (true, 1) => "1 opdateret fund".to_string(),
+                (true, n) => format!("{} opdaterede fund", n),
+                (false, 1) => "1 updated finding".to_string(),
+                (false, n) => format!("{} updated findings", n),
+            });
+        }
+        let moved = self.score_changes.len();
+        if moved > 0 {
+            parts.push(match (is_danish, moved) {
+                (true, 1) => "1 ændret score".to_string(),
+                (true, n) => format!("{} ændrede scorer", n),
+                (false, 1) => "1 score change".to_string(),
+                (false, n) => format!("{} score changes", n),
+            });
+        }
+
+        let mut summary = if is_danish {
+            format!("Siden sidst: {}.", parts.join(", "))
+        } else {
+            format!("Since last time: {}.", parts.join(", "))
+        };
+        if let Some(best) = self.new_findings.first() {
+            summary.push_str(&if is_danish {
+                format!(" Mest relevant nyt fund: {}.", best.title)
+            } else {
+                format!(" Most relevant new finding: {}.", best.title)
+            });
+        }
+        summary
+    }
+}
+
+/// Spoken name of `count` new findings from a source
+fn new_noun(source: &ResearchSource, count: usize, is_danish: bool) -> String {
+    let one = count == 1;
+    match (source, is_danish) {
+        (ResearchSource::GitHub, true) => if one { "nyt repository" } else { "nye repositories" }.to_string(),
+        (ResearchSource::GitHub, false) => if one { "new repository" } else { "new repositories" }.to_string(),
+        (ResearchSource::ArXiv, true) => if one { "ny artikel" } else { "nye artikler" }.to_string(),
+        (ResearchSource::ArXiv, false) => if one { "new paper" } else { "new papers" }.to_string(),
+        (ResearchSource::CustomFeed(name), true) => {
+            format!("{} fra {}", if one { "nyt fund" } else { "nye fund" }, name)
+        }
+        (ResearchSource::CustomFeed(name), false) => {
+            format!("{} from {}", if one { "new finding" } else { "new findings" }, name)
+        }
+        (other, true) => format!("{} fra {:?}", if one { "nyt opslag" } else { "nye opslag" }, other),
+        (other, false) => format!("{} from {:?}", if one { "new post" } else { "new posts" }, other),
+    }
+}
+
+/// Persisted history of every finding the scheduler has observed
+pub struct FindingsHistory {
+    file: JournaledFile,
+    records: RwLock<HashMap<String, FindingRecord>>,
+}
+
+impl FindingsHistory {
+    /// Open a history, loading existing records from disk
+    pub fn new(path: PathBuf) -> Self {
+        let file = JournaledFile::new("findings_history", path);
+        // A damaged history is moved aside and reported; start empty
+        let records = file.load().ok().flatten().unwrap_or_default();
+
+        Self {
+            file,
+            records: RwLock::new(records),
+        }
+    }
+
+    /// Record the current state of findings returned by a scan
+    pub async fn observe(&self, findings: &[ResearchFinding]) -> ResearchResult<()> {
+        self.observe_at(findings, Utc::now()).await
+    }
+
+    async fn observe_at(&self, findings: &[ResearchFinding], now: DateTime<Utc>) -> ResearchResult<()> {
+        let mut records = self.records.write().await;
+
+        for finding in findings {
+            let hash = finding_hash(finding);
+            let point = ScorePoint { at: now, score: finding.relevance_score };
+            let record = records.entry(finding.id.clone()).or_insert_with(|| FindingRecord {
+                id: finding.id.clone(),
+                source: finding.source.clone(),
+                title: finding.title.clone(),
+                url: finding.url.clone(),
+                first_seen: now,
+                last_seen: now,
+                content_hash: hash,
+                content_updated_at: None,
+                scores: Vec::new(),
+            });
+
+            if record.content_hash != hash {
+                record.content_hash = hash;
+                record.content_updated_at = Some(now);
+                record.title = finding.title.clone();
+            }
+            record.last_seen = now;
+            record.url = finding.url.clone();
+            record.scores.push(point);
+            if record.scores.len() > MAX_SCORE_POINTS {
+                record.scores.remove(0);
+            }
+        }
+
+        if records.len() > MAX_RECORDS {
+            let mut by_age: Vec<(DateTime<Utc>, String)> =
+                records.values().map(|r| (r.last_seen, r.id.clone())).collect();
+            by_age.sort();
+            for (_, id) in by_age.into_iter().take(records.len() - MAX_RECORDS) {
+                records.remove(&id);
+            }
+        }
+
+        self.persist(&records)
+    }
+
+    /// Changes since `since`
+    pub async fn diff(&self, since: DateTime<Utc>) -> FindingsDiff {
+        let records = self.records.read().await;
+
+        let mut new_findings = Vec::new();
+        let mut updated_findings = Vec::new();
+        let mut score_changes = Vec::new();
+
+        for record in records.values() {
+            if record.first_seen >= since {
+                new_findings.push(DiffEntry::from(record));
+                continue;
+            }
+            if record.content_updated_at.is_some_and(|at| at >= since) {
+                updated_findings.push(DiffEntry::from(record));
+            }
+            if let Some(previous) = record.score_before(since) {
+                let current = record.current_score();
+                if (current - previous).abs() >= MIN_SCORE_DELTA {
+                    score_changes.push(ScoreChange {
+                        id: record.id.clone(),
+                        source: record.source.clone(),
+                        title: record.title.clone(),
+                        previous_score: previous,
+                        current_score: current,
+                        delta: current - previous,
+                    });
+                }
+            }
+        }
+
+        let by_score = |a: &DiffEntry, b: &DiffEntry| {
+            b.relevance_score
+                .partial_cmp(&a.relevance_score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+        new_findings.sort_by(by_score);
+        updated_findings.sort_by(by_score);
+        score_changes.sort_by(|a, b| {
+            b.delta
+                .abs()
+                .partial_cmp(&a.delta.abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        FindingsDiff {
+            since,
+            generated_at: Utc::now(),
+            new_findings,
+            updated_findings,
+            score_changes,
+        }
+    }
+
+    fn persist(&self, records: &HashMap<String, FindingRecord>) -> ResearchResult<()> {
+        let json = serde_json::to_string(records).map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        self.file
+            .write(json.as_bytes())
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))
+    }
+}
+
+impl Default for FindingsHistory {
+    fn default() -> Self {
+        Self::new(
+            dirs::data_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("findings_history.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn finding(id: &str, source: ResearchSource, summary: &str, score: f32) -> ResearchFinding {
+        ResearchFinding {
+            id: id.to_string(),
+            source,
+            title: format!("Title of {}", id),
+            summary: summary.to_string(),
+            relevance_score: score,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_day_over_day_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+        let history = FindingsHistory::new(path.clone());
+        let yesterday = Utc::now() - Duration::days(1);
+        let cutoff = Utc::now() - Duration::hours(12);
+
+        history
+            .observe_at(
+                &[
+                    finding("gh:a", ResearchSource::GitHub, "repo", 0.6),
+                    finding("arxiv:1", ResearchSource::ArXiv, "v1 abstract", 0.7),
+                    finding("gh:steady", ResearchSource::GitHub, "same", 0.5),
+                ],
+                yesterday,
+            )
+            .await
+            .unwrap();
+        history
+            .observe(&[
+                finding("gh:a", ResearchSource::GitHub, "repo", 0.8),
+                finding("arxiv:1", ResearchSource::ArXiv, "v2 abstract", 0.7),
+                finding("gh:steady", ResearchSource::GitHub, "same", 0.52),
+                finding("gh:new", ResearchSource::GitHub, "fresh", 0.9),
+                finding("arxiv:2", ResearchSource::ArXiv, "fresh paper", 0.4),
+            ])
+            .await
+            .unwrap();
+
+        // Reload to include persistence
+        let diff = FindingsHistory::new(path.clone()).diff(cutoff).await;
+        let ids = |entries: &[DiffEntry]| entries.iter().map(|e| e.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.new_findings), vec!["gh:new", "arxiv:2"]);
+        assert_eq!(ids(&diff.updated_findings), vec!["arxiv:1"]);
+        assert_eq!(diff.score_changes.len(), 1);
+        assert_eq!(diff.score_changes[0].id, "gh:a");
+        assert!((diff.score_changes[0].delta - 0.2).abs() < 1e-6);
+
+        let summary = diff.spoken_summary(true);
+        assert!(summary.contains("1 nyt repository"));
+        assert!(summary.contains("1 ny artikel"));
+        assert!(summary.contains("1 opdateret fund"));
+        assert!(summary.contains("1 ændret score"));
+        assert!(summary.contains("Title of gh:new"));
+
+        assert!(history.diff(Utc::now()).await.is_empty());
+    }
+}
@@ -0,0 +1,381 @@
+// HTTP Cache - Disk-backed response cache for research adapters
+// Responses with an ETag or Last-Modified are revalidated instead of downloaded again
+
+use crate::storage::JournaledFile;
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Upper bound for cached bodies on disk
+const MAX_CACHE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Responses larger than this are never cached
+const MAX_ENTRY_BYTES: u64 = 5 * 1024 * 1024;
+
+const INDEX_FILE: &str = "index.json";
+
+/// A cached response, without its body
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    headers: Vec<(String, String)>,
+    size: u64,
+    last_used: DateTime<Utc>,
+}
+
+/// How well the cache has saved downloads since start
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct HttpCacheStats {
+    /// GET requests sent through the cache
+    pub requests: u64,
+    /// Requests sent with If-None-Match / If-Modified-Since
+    pub conditional: u64,
+    /// 304 responses answered from the cache
+    pub hits: u64,
+    /// Body bytes not downloaded thanks to hits
+    pub bytes_saved: u64,
+    pub entries: usize,
+    pub cached_bytes: u64,
+}
+
+/// Size-bounded response cache; least recently used entries are evicted first
+pub struct HttpCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    index_file: JournaledFile,
+    index: Mutex<HashMap<String, CacheEntry>>,
+    requests: AtomicU64,
+    conditional: AtomicU64,
+    hits: AtomicU64,
+    bytes_saved: AtomicU64,
+}
+
+impl HttpCache {
+    /// Open the cache in `dir`, keeping at most `max_bytes` of bodies
+    pub fn new(dir: PathBuf, max_bytes: u64) -> Self {
+        let index_file = JournaledFile::new("http_cache", dir.join(INDEX_FILE));
+        // A damaged index is moved aside and reported; responses are fetched again
+        let index = index_file
+            .load::<HashMap<String, CacheEntry>>()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(key, _)| body_path(&dir, key).exists())
+            .collect();
+        Self {
+            dir,
+            max_bytes,
+            index_file,
+            index: Mutex::new(index),
+            requests: AtomicU64::new(0),
+            conditional: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            bytes_saved: AtomicU64::new(0),
+        }
+    }
+
+    /// Hit counters since start, with the current size on disk
+    pub fn stats(&self) -> HttpCacheStats {
+        let index = self.index.lock().unwrap();
+        HttpCacheStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            conditional: self.conditional.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            bytes_saved: self.bytes_saved.load(Ordering::Relaxed),
+            entries: index.len(),
+            cached_bytes: index.values().map(|e| e.size).sum(),
+        }
+    }
+
+    /// Entry and body for `key`, if both are still present
+    fn lookup(&self, key: &str) -> Option<(CacheEntry, Vec<u8>)> {
+        let entry = self.index.lock().unwrap().get(key).cloned()?;
+        match std::fs::read(body_path(&self.dir, key)) {
+            Ok(body) => Some((entry, body)),
+            Err(_) => {
+                self.index.lock().unwrap().remove(key);
+                None
+            }
+        }
+    }
+
+    fn touch(&self, key: &str) {
+        let mut index = self.index.lock().unwrap();
+        if let Some(entry) = index.get_mut(key) {
+            entry.last_used = Utc::now();
+            self.persist(&index);
+        }
+    }
+
+    fn store(&self, key: &str, entry: CacheEntry, body: &[u8]) {
+        if entry.size > MAX_ENTRY_BYTES.min(self.max_bytes) {
+            return;
+        }
+        if let Err(e) = std::fs::create_dir_all(&self.dir).and_then(|_| std::fs::write(body_path(&self.dir, key), body)) {
+            log::warn!("Could not cache {}: {}", entry.url, e);
+            return;
+        }
+
+        let mut index = self.index.lock().unwrap();
+        index.insert(key.to_string(), entry);
+        let mut total: u64 = index.values().map(|e| e.size).sum();
+        while total > self.max_bytes {
+            let Some(oldest) = index.iter().min_by_key(|(_, e)| e.last_used).map(|(k, _)| k.clone()) else {
+                break;
+            };
+            if let Some(evicted) = index.remove(&oldest) {
+                total -= evicted.size;
+                let _ = std::fs::remove_file(body_path(&self.dir, &oldest));
+            }
+        }
+        self.persist(&index);
+    }
+
+    fn persist(&self, index: &HashMap<String, CacheEntry>) {
+        match serde_json::to_string(index) {
+            Ok(json) => {
+                if let Err(e) = self.index_file.write(json.as_bytes()) {
+                    log::warn!("Could not save HTTP cache index: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Could not serialize HTTP cache index: {}", e),
+        }
+    }
+}
+
+static CACHE: Lazy<HttpCache> = Lazy::new(|| {
+    let dir = dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cirkelline-cla")
+        .join("http");
+    HttpCache::new(dir, MAX_CACHE_BYTES)
+});
+
+/// Process-wide cache shared by the research adapters
+pub fn cache() -> &'static HttpCache {
+    &CACHE
+}
+
+/// `send_metered` that revalidates cached GET responses with
+/// If-None-Match / If-Modified-Since; a 304 is answered from the cache as a 200.
+/// Other methods are sent unchanged.
+#[async_trait]
+pub trait CachedSend {
+    async fn send_cached(self, subsystem: NetworkSubsystem) -> reqwest::Result<reqwest::Response>;
+}
+
+#[async_trait]
+impl CachedSend for reqwest::RequestBuilder {
+    async fn send_cached(self, subsystem: NetworkSubsystem) -> reqwest::Result<reqwest::Response> {
+        send_through(cache(), self, subsystem).await
+    }
+}
+
+async fn send_through(
+    cache: &HttpCache,
+    builder: reqwest::RequestBuilder,
+    subsystem: NetworkSubsystem,
+) -> reqwest::Result<reqwest::Response> {
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    if request.method() != Method::GET {
+        return reqwest::RequestBuilder::from_parts(client, request).send_metered(subsystem).await;
+    }
+
+    let key = cache_key(&request);
+    let cached = cache.lookup(&key);
+    cache.requests.fetch_add(1, Ordering::Relaxed);
+    if let Some((entry, _)) = &cached {
+        cache.conditional.fetch_add(1, Ordering::Relaxed);
+        let headers = request.headers_mut();
+        if let Some(value) = entry.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_NONE_MATCH, value);
+        }
+        if let Some(value) = entry.last_modified.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(IF_MODIFIED_SINCE, value);
+        }
+    }
+    let url = request.url().to_string();
+    let response = reqwest::RequestBuilder::from_parts(client, request).send_metered(subsystem).await?;
+
+    match (response.status(), cached) {
+        (StatusCode::NOT_MODIFIED, Some((entry, body))) => {
+            log::debug!("HTTP cache hit: {}", url);
+            cache.hits.fetch_add(1, Ordering::Relaxed);
+            cache.bytes_saved.fetch_add(body.len() as u64, Ordering::Relaxed);
+            cache.touch(&key);
+            Ok(rebuild(StatusCode::OK, &entry.headers, body))
+        }
+        (StatusCode::OK, _) => {
+            let etag = header_string(response.headers(), ETAG);
+            let last_modified = header_string(response.headers(), LAST_MODIFIED);
+            if etag.is_none() && last_modified.is_none() {
+                return Ok(response);
+            }
+            let headers: Vec<(String, String)> = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+                .collect();
+            let body = response.bytes().await?;
+            cache.store(
+                &key,
+                CacheEntry {
+                    url,
+                    etag,
+                    last_modified,
+                    headers: headers.clone(),
+                    size: body.len() as u64,
+                    last_used: Utc::now(),
+                },
+                &body,
+            );
+            Ok(rebuild(StatusCode::OK, &headers, body.to_vec()))
+        }
+        _ => Ok(response),
+    }
+}
+
+/// Requests with the same URL and headers share an entry
+fn cache_key(request: &reqwest::Request) -> String {
+    let mut headers: Vec<(&str, &[u8])> = request
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_bytes()))
+        .collect();
+    headers.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(request.url().as_str());
+    for (name, value) in headers {
+        hasher.update(b"\n");
+        hasher.update(name);
+        hasher.update(b":");
+        hasher.update(value);
+    }
+    hex::encode(hasher.finalize())
+}
+
+fn body_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.body", key))
+}
+
+fn header_string(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers.get(name)?.to_str().ok().map(str::to_string)
+}
+
+fn rebuild(status: StatusCode, headers: &[(String, String)], body: Vec<u8>) -> reqwest::Response {
+    let mut response = http::Response::new(body);
+    *response.status_mut() = status;
+    for (name, value) in headers {
+        if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::from_str(value)) {
+            response.headers_mut().append(name, value);
+        }
+    }
+    reqwest::Response::from(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Entries and cached bytes
+    fn stats(cache: &HttpCache) -> (usize, u64) {
+        let stats = cache.stats();
+        (stats.entries, stats.cached_bytes)
+    }
+
+    fn temp_cache(max_bytes: u64) -> (tempfile::TempDir, HttpCache) {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = HttpCache::new(dir.path().to_path_buf(), max_bytes);
+        (dir, cache)
+    }
+
+    fn entry(url: &str, size: u64) -> CacheEntry {
+        CacheEntry {
+            url: url.to_string(),
+            etag: Some("\"v1\"".to_string()),
+            last_modified: None,
+            headers: Vec::new(),
+            size,
+            last_used: Utc::now(),
+        }
+    }
+
+    /// Serve `count` requests: 200 with an ETag, then 304 when revalidated
+    fn serve(count: usize) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/search", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for stream in listener.incoming().take(count) {
+                let mut stream = stream.unwrap();
+                let mut buf = [0u8; 4096];
+                let n = stream.read(&mut buf).unwrap();
+                let request = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+                let response = if request.contains("if-none-match: \"v1\"") {
+                    "HTTP/1.1 304 Not Modified\r\netag: \"v1\"\r\nconnection: close\r\ncontent-length: 0\r\n\r\n".to_string()
+                } else {
+                    "HTTP/1.1 200 OK\r\netag: \"v1\"\r\nconnection: close\r\ncontent-length: 7\r\n\r\nresults".to_string()
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[tokio::test]
+    async fn test_revalidates_with_etag() {
+        let (dir, cache) = temp_cache(1024);
+        let (url, server) = serve(2);
+        let client = reqwest::Client::new();
+
+        let first = send_through(&cache, client.get(&url), NetworkSubsystem::Research).await.unwrap();
+        assert_eq!(first.text().await.unwrap(), "results");
+        let second = send_through(&cache, client.get(&url), NetworkSubsystem::Research).await.unwrap();
+        assert_eq!(second.status(), StatusCode::OK);
+        assert_eq!(second.text().await.unwrap(), "results");
+
+        let requests = server.join().unwrap();
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+        let counters = cache.stats();
+        assert_eq!((counters.requests, counters.conditional, counters.hits, counters.bytes_saved), (2, 1, 1, 7));
+
+        // The index survives a restart
+        assert_eq!(stats(&HttpCache::new(dir.path().to_path_buf(), 1024)), (1, 7));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let (_dir, cache) = temp_cache(10);
+        cache.store("a", entry("a", 4), b"aaaa");
+        cache.store("b", entry("b", 4), b"bbbb");
+        cache.touch("a");
+        cache.store("c", entry("c", 4), b"cccc");
+
+        assert!(cache.lookup("a").is_some());
+        assert!(cache.lookup("b").is_none());
+        assert_eq!(stats(&cache), (2, 8));
+
+        // Larger than the whole cache: not stored
+        cache.store("d", entry("d", 11), &[0u8; 11]);
+        assert!(cache.lookup("d").is_none());
+    }
+}
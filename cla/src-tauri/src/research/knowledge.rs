@@ -0,0 +1,207 @@
+// Knowledge Store - Local chunks of analyzed documents
+// Persisted as JSON next to the finding archive, embeddings quantized to int8
+
+use crate::inference::{dequantize_int8, quantize_int8};
+use crate::models::LocalKnowledgeChunk;
+use crate::research::traits::{ResearchError, ResearchResult};
+use crate::storage::JournaledFile;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// On-disk form of a chunk
+#[derive(Serialize, Deserialize)]
+struct StoredChunk {
+    id: Uuid,
+    source_id: String,
+    content: String,
+    /// Base64 int8 blob (see inference::quantize)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    embedding_int8: Option<String>,
+    /// Full-precision embedding written by older versions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    embedding_local: Vec<f32>,
+    metadata: serde_json::Value,
+    priority: u8,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<&LocalKnowledgeChunk> for StoredChunk {
+    fn from(chunk: &LocalKnowledgeChunk) -> Self {
+        let embedding_int8 = if chunk.embedding_local.is_empty() {
+            None
+        } else {
+            quantize_int8(std::slice::from_ref(&chunk.embedding_local))
+                .ok()
+                .map(|blob| BASE64.encode(blob))
+        };
+        Self {
+            id: chunk.id,
+            source_id: chunk.source_id.clone(),
+            content: chunk.content.clone(),
+            embedding_int8,
+            embedding_local: Vec::new(),
+            metadata: chunk.metadata.clone(),
+            priority: chunk.priority,
+            expires_at: chunk.expires_at,
+        }
+    }
+}
+
+impl From<StoredChunk> for LocalKnowledgeChunk {
+    fn from(stored: StoredChunk) -> Self {
+        let embedding_local = match stored.embedding_int8 {
+            Some(encoded) => BASE64
+                .decode(encoded)
+                .map_err(|e| e.to_string())
+                .and_then(|blob| dequantize_int8(&blob))
+                .map(|mut vectors| vectors.pop().unwrap_or_default())
+                .unwrap_or_else(|e| {
+                    log::warn!("Dropping unreadable embedding of chunk {}: {}", stored.id, e);
+                    Vec::new()
+                }),
+            None => stored.embedding_local,
+        };
+        Self {
+            id: stored.id,
+            source_id: stored.source_id,
+            content: stored.content,
+            embedding_local,
+            metadata: stored.metadata,
+            priority: stored.priority,
+            expires_at: stored.expires_at,
+        }
+    }
+}
+
+/// Local store of embedded document chunks
+pub struct KnowledgeStore {
+    file: JournaledFile,
+    chunks: RwLock<Vec<LocalKnowledgeChunk>>,
+}
+
+impl KnowledgeStore {
+    /// Open a store, loading existing chunks from disk
+    pub fn new(path: PathBuf) -> Self {
+        let file = JournaledFile::new("knowledge", path);
+        // A damaged store is moved aside and reported; start empty
+        let chunks = file
+            .load::<Vec<StoredChunk>>()
+            .ok()
+            .flatten()
+            .map(|stored| stored.into_iter().map(LocalKnowledgeChunk::from).collect())
+            .unwrap_or_default();
+
+        Self {
+            file,
+            chunks: RwLock::new(chunks),
+        }
+    }
+
+    /// Replace all chunks of a source
+    pub async fn replace_source(
+        &self,
+        source_id: &str,
+        new_chunks: Vec<LocalKnowledgeChunk>,
+    ) -> ResearchResult<()> {
+        let mut chunks = self.chunks.write().await;
+        chunks.retain(|c| c.source_id != source_id);
+        chunks.extend(new_chunks);
+        self.persist(&chunks)
+    }
+
+    /// Add chunks, replacing any with the same id
+    pub async fn insert(&self, new_chunks: Vec<LocalKnowledgeChunk>) -> ResearchResult<()> {
+        let mut chunks = self.chunks.write().await;
+        chunks.retain(|c| !new_chunks.iter().any(|n| n.id == c.id));
+        chunks.extend(new_chunks);
+        self.persist(&chunks)
+    }
+
+    /// Every chunk in the store
+    pub async fn all(&self) -> Vec<LocalKnowledgeChunk> {
+        self.chunks.read().await.clone()
+    }
+
+    /// Chunks belonging to a source
+    pub async fn chunks_for_source(&self, source_id: &str) -> Vec<LocalKnowledgeChunk> {
+        self.chunks
+            .read()
+            .await
+            .iter()
+            .filter(|c| c.source_id == source_id)
+            .cloned()
+            .collect()
+    }
+
+    fn persist(&self, chunks: &[LocalKnowledgeChunk]) -> ResearchResult<()> {
+        let stored: Vec<StoredChunk> = chunks.iter().map(StoredChunk::from).collect();
+        let json = serde_json::to_string(&stored).map_err(|e| ResearchError::ParseError(e.to_string()))?;
+        self.file
+            .write(json.as_bytes())
+            .map_err(|e| ResearchError::ConfigError(e.to_string()))
+    }
+}
+
+impl Default for KnowledgeStore {
+    fn default() -> Self {
+        Self::new(
+            dirs::data_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("cirkelline-cla")
+                .join("knowledge.json"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(embedding: Vec<f32>) -> LocalKnowledgeChunk {
+        LocalKnowledgeChunk {
+            id: Uuid::new_v4(),
+            source_id: "arxiv:1".to_string(),
+            content: "Attention is all you need".to_string(),
+            embedding_local: embedding,
+            metadata: serde_json::json!({ "chunk_index": 0 }),
+            priority: 5,
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_embeddings_stored_quantized() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.json");
+        let store = KnowledgeStore::new(path.clone());
+        store
+            .replace_source("arxiv:1", vec![chunk(vec![0.5, -0.25, 0.125]), chunk(Vec::new())])
+            .await
+            .unwrap();
+
+        let json = std::fs::read_to_string(&path).unwrap();
+        assert!(json.contains("embedding_int8"));
+        assert!(!json.contains("embedding_local"));
+
+        let reloaded = KnowledgeStore::new(path.clone()).chunks_for_source("arxiv:1").await;
+        assert_eq!(reloaded.len(), 2);
+        for (x, y) in reloaded[0].embedding_local.iter().zip([0.5, -0.25, 0.125]) {
+            assert!((x - y).abs() < 0.5 / 127.0);
+        }
+        assert!(reloaded[1].embedding_local.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_loads_full_precision_embeddings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("knowledge.json");
+        std::fs::write(&path, serde_json::to_string(&[chunk(vec![0.1, 0.2])]).unwrap()).unwrap();
+
+        let chunks = KnowledgeStore::new(path.clone()).chunks_for_source("arxiv:1").await;
+        assert_eq!(chunks[0].embedding_local, vec![0.1, 0.2]);
+    }
+}
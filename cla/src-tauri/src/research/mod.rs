@@ -2,11 +2,28 @@
 // Part of CLA FASE 6 - Autonomous research capabilities
 
 pub mod adapters;
+pub mod archive;
+pub mod deep_analysis;
+pub mod dedup;
+pub mod digest;
+pub mod feedback;
+pub mod history;
+pub mod http_cache;
+pub mod knowledge;
+pub mod orchestrator;
 pub mod processors;
+pub mod store;
 pub mod traits;
 
 pub use adapters::{
-    ArXivAdapter, GitHubAdapter, ResearchAdapterRegistry,
+    ArXivAdapter, FeedRegistry, GitHubAdapter, ResearchAdapterRegistry,
 };
+pub use archive::FindingArchive;
+pub use deep_analysis::DeepAnalyzer;
+pub use dedup::FindingsDedup;
+pub use feedback::ScoreFeedback;
+pub use history::FindingsHistory;
+pub use knowledge::KnowledgeStore;
+pub use orchestrator::SearchOrchestrator;
 pub use processors::{RelevanceScorer, SignalProcessor};
 pub use traits::ResearchAdapter;
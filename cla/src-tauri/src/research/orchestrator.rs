@@ -0,0 +1,228 @@
+// Search Orchestrator - Fans a query out to several research adapters at once
+// Each adapter runs in its own task under its own timeout; a slow or failing
+// source only loses its own results, and the rest are merged as usual
+
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::processors::{merge_findings, ProcessorConfig};
+use crate::research::traits::{ResearchAdapter, SearchOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+
+/// Time an adapter gets unless its source has its own timeout
+pub const DEFAULT_ADAPTER_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How one adapter fared in a search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdapterOutcome {
+    pub adapter: String,
+    pub source: ResearchSource,
+    /// Findings returned, before merging
+    pub findings: usize,
+    pub elapsed_ms: u64,
+    /// Why the adapter returned nothing, including timeouts
+    pub error: Option<String>,
+}
+
+/// Merged findings of a fan-out search
+#[derive(Debug)]
+pub struct OrchestratedSearch {
+    /// Deduplicated, best first
+    pub findings: Vec<ResearchFinding>,
+    /// One per adapter, by adapter name
+    pub outcomes: Vec<AdapterOutcome>,
+}
+
+/// Runs a query against several adapters concurrently
+pub struct SearchOrchestrator {
+    adapters: Vec<Arc<dyn ResearchAdapter>>,
+    default_timeout: Duration,
+    timeouts: HashMap<ResearchSource, Duration>,
+    /// Per adapter name: only return results newer than this (Unix timestamp)
+    resume_from: HashMap<String, i64>,
+    config: ProcessorConfig,
+}
+
+impl SearchOrchestrator {
+    pub fn new(adapters: Vec<Arc<dyn ResearchAdapter>>) -> Self {
+        Self {
+            adapters,
+            default_timeout: DEFAULT_ADAPTER_TIMEOUT,
+            timeouts: HashMap::new(),
+            resume_from: HashMap::new(),
+            config: ProcessorConfig::default(),
+        }
+    }
+
+    /// Give one source's adapter a different timeout
+    pub fn with_timeout(mut self, source: ResearchSource, timeout: Duration) -> Self {
+        self.timeouts.insert(source, timeout);
+        self
+    }
+
+    /// Resume one adapter's scan: it only returns results newer than `since`
+    pub fn with_resume_from(mut self, adapter: impl Into<String>, since: i64) -> Self {
+        self.resume_from.insert(adapter.into(), since);
+        self
+    }
+
+    /// Search every adapter at once and merge what came back in time
+    pub async fn search(&self, query: &str, options: &SearchOptions) -> OrchestratedSearch {
+        let mut tasks = JoinSet::new();
+        for adapter in &self.adapters {
+            let adapter = adapter.clone();
+            let query = query.to_string();
+            let mut options = options.clone();
+            if let Some(&since) = self.resume_from.get(adapter.name()) {
+                options.since_timestamp = Some(options.since_timestamp.map_or(since, |s| s.max(since)));
+            }
+            let timeout = self.timeouts.get(&adapter.source()).copied().unwrap_or(self.default_timeout);
+
+            tasks.spawn(async move {
+                let started = Instant::now();
+                let result = tokio::time::timeout(timeout, adapter.search(&query, &options)).await;
+                let result = match result {
+                    Ok(Ok(findings)) => Ok(findings),
+                    Ok(Err(e)) => Err(e.to_string()),
+                    Err(_) => Err(format!("Timed out after {}s", timeout.as_secs_f32())),
+                };
+                (adapter.name().to_string(), adapter.source(), started.elapsed(), result)
+            });
+        }
+
+        let mut results = Vec::new();
+        let mut outcomes = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (adapter, source, elapsed, result) = match joined {
+                Ok(done) => done,
+                // A panicking adapter only loses its own results
+                Err(e) => {
+                    log::error!("Research adapter task failed: {}", e);
+                    continue;
+                }
+            };
+            let (count, error) = match result {
+                Ok(findings) => {
+                    let count = findings.len();
+                    results.push(findings);
+                    (count, None)
+                }
+                Err(e) => {
+                    log::warn!("{} search for '{}' failed: {}", adapter, query, e);
+                    (0, Some(e))
+                }
+            };
+            outcomes.push(AdapterOutcome {
+                adapter,
+                source,
+                findings: count,
+                elapsed_ms: elapsed.as_millis() as u64,
+                error,
+            });
+        }
+        outcomes.sort_by(|a, b| a.adapter.cmp(&b.adapter));
+
+        OrchestratedSearch {
+            findings: merge_findings(results, &self.config).findings,
+            outcomes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::traits::{ResearchError, ResearchResult};
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    /// Answers after `delay` with one finding per title, or fails
+    #[derive(Debug)]
+    struct FakeAdapter {
+        name: &'static str,
+        source: ResearchSource,
+        delay: Duration,
+        titles: Vec<&'static str>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl ResearchAdapter for FakeAdapter {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn source(&self) -> ResearchSource {
+            self.source.clone()
+        }
+
+        async fn validate(&self) -> ResearchResult<()> {
+            Ok(())
+        }
+
+        async fn search(&self, _query: &str, _options: &SearchOptions) -> ResearchResult<Vec<ResearchFinding>> {
+            tokio::time::sleep(self.delay).await;
+            if self.fail {
+                return Err(ResearchError::NetworkError("connection reset".to_string()));
+            }
+            Ok(self
+                .titles
+                .iter()
+                .map(|title| ResearchFinding {
+                    id: format!("{}-{}", self.name, title),
+                    source: self.source.clone(),
+                    title: title.to_string(),
+                    summary: String::new(),
+                    relevance_score: 0.8,
+                    discovered_at: Utc::now(),
+                    tags: Vec::new(),
+                    url: None,
+                    metadata: serde_json::json!({}),
+                    score_breakdown: None,
+                    scorer_version: None,
+                })
+                .collect())
+        }
+    }
+
+    fn adapter(name: &'static str, source: ResearchSource, delay_ms: u64, titles: Vec<&'static str>) -> FakeAdapter {
+        FakeAdapter {
+            name,
+            source,
+            delay: Duration::from_millis(delay_ms),
+            titles,
+            fail: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_and_failing_adapters_are_isolated() {
+        let failing = FakeAdapter {
+            fail: true,
+            ..adapter("Lens", ResearchSource::LensProtocol, 0, Vec::new())
+        };
+        let orchestrator = SearchOrchestrator::new(vec![
+            Arc::new(adapter("GitHub", ResearchSource::GitHub, 20, vec!["tokio", "Rust agents"])),
+            Arc::new(adapter("ArXiv", ResearchSource::ArXiv, 20, vec!["Rust  Agents", "planning"])),
+            Arc::new(adapter("Reddit", ResearchSource::Reddit, 10_000, vec!["late"])),
+            Arc::new(failing),
+        ])
+        .with_timeout(ResearchSource::Reddit, Duration::from_millis(100));
+
+        let started = Instant::now();
+        let result = orchestrator.search("rust", &SearchOptions::default()).await;
+
+        // Concurrent: the slow adapter's timeout bounds the whole search
+        assert!(started.elapsed() < Duration::from_secs(2));
+        let names: Vec<_> = result.outcomes.iter().map(|o| o.adapter.as_str()).collect();
+        assert_eq!(names, vec!["ArXiv", "GitHub", "Lens", "Reddit"]);
+        assert!(result.outcomes[2].error.as_deref().unwrap().contains("connection reset"));
+        assert!(result.outcomes[3].error.as_deref().unwrap().contains("Timed out"));
+        assert_eq!(result.outcomes[1].findings, 2);
+
+        // "Rust agents" from both sources is merged into one
+        assert_eq!(result.findings.len(), 3);
+    }
+}
@@ -2,12 +2,17 @@
 // Post-processing components for research findings
 
 mod relevance_scorer;
+mod semantic_dedup;
 mod signal_processor;
 
 pub use relevance_scorer::RelevanceScorer;
-pub use signal_processor::SignalProcessor;
+pub use semantic_dedup::{embedding_text, SemanticDedup};
+pub use signal_processor::{KeywordHistory, SignalProcessor, TrendConfig};
 
 use crate::commander::ResearchFinding;
+use crate::research::dedup::canonical_text;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Processor configuration
@@ -34,8 +39,12 @@ impl Default for ProcessorConfig {
     }
 }
 
+/// Version of the scoring algorithm stored with every scored finding.
+/// Bump it and add an arm to `ScoringWeights::for_version` when weights or factors change.
+pub const SCORER_VERSION: u32 = 2;
+
 /// Weights for different scoring factors
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ScoringWeights {
     /// Weight for keyword match
     pub keyword_match: f32,
@@ -45,19 +54,82 @@ pub struct ScoringWeights {
     pub source_authority: f32,
     /// Weight for engagement metrics
     pub engagement: f32,
+    /// Weight for embedding similarity between query and finding. Without
+    /// embeddings the other weights are scaled up to take its share.
+    pub embedding_similarity: f32,
+}
+
+impl ScoringWeights {
+    /// Weights of a scorer version, if it exists
+    pub fn for_version(version: u32) -> Option<Self> {
+        match version {
+            1 => Some(Self {
+                keyword_match: 0.4,
+                recency: 0.2,
+                source_authority: 0.25,
+                engagement: 0.15,
+                embedding_similarity: 0.0,
+            }),
+            // Version 1 scaled to make room for semantic similarity; without an
+            // embedding model it scores exactly like version 1
+            2 => Some(Self {
+                keyword_match: 0.32,
+                recency: 0.16,
+                source_authority: 0.2,
+                engagement: 0.12,
+                embedding_similarity: 0.2,
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl Default for ScoringWeights {
     fn default() -> Self {
+        Self::for_version(SCORER_VERSION).expect("current scorer version has weights")
+    }
+}
+
+/// A single factor's contribution to a relevance score
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScoreComponent {
+    /// Factor score before weighting (0.0-1.0)
+    pub raw: f32,
+    /// Weight applied to the factor
+    pub weight: f32,
+    /// raw * weight
+    pub contribution: f32,
+}
+
+impl ScoreComponent {
+    pub fn new(raw: f32, weight: f32) -> Self {
         Self {
-            keyword_match: 0.4,
-            recency: 0.2,
-            source_authority: 0.25,
-            engagement: 0.15,
+            raw,
+            weight,
+            contribution: raw * weight,
         }
     }
 }
 
+/// Explanation of how a finding's relevance score was computed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScoreBreakdown {
+    pub keyword_match: ScoreComponent,
+    pub recency: ScoreComponent,
+    pub source_authority: ScoreComponent,
+    pub engagement: ScoreComponent,
+    /// Present only when both query and finding embeddings were available
+    pub embedding_similarity: Option<ScoreComponent>,
+    /// Final score, clamped to [0, 1]
+    pub total: f32,
+    /// Keywords the finding was matched against
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Point in time recency was measured from; rescoring reuses it
+    #[serde(default)]
+    pub scored_at: Option<DateTime<Utc>>,
+}
+
 /// Result of processing
 #[derive(Debug, Clone)]
 pub struct ProcessingResult {
@@ -108,7 +180,7 @@ pub fn merge_findings(
     
     if config.deduplicate {
         all.retain(|f| {
-            let normalized = normalize_title(&f.title);
+            let normalized = canonical_text(&f.title);
             if seen_titles.contains_key(&normalized) {
                 duplicates_removed += 1;
                 false
@@ -146,18 +218,6 @@ pub fn merge_findings(
     }
 }
 
-/// Normalize title for comparison
-fn normalize_title(title: &str) -> String {
-    title
-        .to_lowercase()
-        .chars()
-        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
-        .collect::<String>()
-        .split_whitespace()
-        .collect::<Vec<&str>>()
-        .join(" ")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,6 +235,8 @@ mod tests {
             tags: vec![],
             url: None,
             metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
         }
     }
     
@@ -1,10 +1,16 @@
 // Relevance Scorer - Calculates relevance scores for research findings
-// Uses multiple factors: keyword matching, recency, source authority
+// Uses multiple factors: keyword matching, recency, source authority, engagement
+// and, when the embedding model is loaded, semantic similarity to the query
 
 use crate::commander::{ResearchFinding, ResearchSource};
-use super::{ProcessorConfig, ScoringWeights, ProcessingResult, ProcessingStats, ResearchProcessor};
-use chrono::{Duration, Utc};
-use std::collections::HashSet;
+use crate::inference::cosine_similarity;
+use crate::research::feedback::LearnedScoring;
+use super::{
+    ProcessorConfig, ScoringWeights, ScoreBreakdown, ScoreComponent, ProcessingResult,
+    ProcessingStats, ResearchProcessor, SCORER_VERSION,
+};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, HashSet};
 
 /// Relevance scorer for research findings
 #[derive(Debug, Clone)]
@@ -15,6 +21,14 @@ pub struct RelevanceScorer {
     weights: ScoringWeights,
     /// Minimum threshold
     min_threshold: f32,
+    /// Embedding of the search query, for semantic similarity
+    query_embedding: Option<Vec<f32>>,
+    /// Embeddings of the findings being scored, by finding id
+    finding_embeddings: HashMap<String, Vec<f32>>,
+    /// Authority learned from user ratings, replacing the built-in value per source
+    source_authority: HashMap<ResearchSource, f32>,
+    /// Scoring algorithm version
+    version: u32,
 }
 
 impl RelevanceScorer {
@@ -24,6 +38,10 @@ impl RelevanceScorer {
             keywords: HashSet::new(),
             weights: ScoringWeights::default(),
             min_threshold: 0.3,
+            query_embedding: None,
+            finding_embeddings: HashMap::new(),
+            source_authority: HashMap::new(),
+            version: SCORER_VERSION,
         }
     }
 
@@ -33,21 +51,57 @@ impl RelevanceScorer {
             keywords: keywords.into_iter().map(|k| k.to_lowercase()).collect(),
             weights: ScoringWeights::default(),
             min_threshold: 0.3,
+            query_embedding: None,
+            finding_embeddings: HashMap::new(),
+            source_authority: HashMap::new(),
+            version: SCORER_VERSION,
         }
     }
 
+    /// Scorer of an earlier (or the current) algorithm version, if it exists
+    pub fn for_version(version: u32, keywords: Vec<String>) -> Option<Self> {
+        Some(Self {
+            weights: ScoringWeights::for_version(version)?,
+            version,
+            ..Self::with_keywords(keywords)
+        })
+    }
+
     /// Set custom weights
     pub fn with_weights(mut self, weights: ScoringWeights) -> Self {
         self.weights = weights;
         self
     }
 
+    /// Use the weights and source authority learned from user ratings
+    pub fn with_learned(mut self, learned: &LearnedScoring) -> Self {
+        self.weights = learned.weights.clone();
+        self.source_authority = learned
+            .sources
+            .iter()
+            .map(|s| (s.source.clone(), s.authority))
+            .collect();
+        self
+    }
+
     /// Set minimum threshold
     pub fn with_threshold(mut self, threshold: f32) -> Self {
         self.min_threshold = threshold;
         self
     }
 
+    /// Set the query embedding used for similarity scoring
+    pub fn with_query_embedding(mut self, embedding: Vec<f32>) -> Self {
+        self.query_embedding = Some(embedding);
+        self
+    }
+
+    /// Set the embeddings of the findings to be scored, by finding id
+    pub fn with_finding_embeddings(mut self, embeddings: HashMap<String, Vec<f32>>) -> Self {
+        self.finding_embeddings = embeddings;
+        self
+    }
+
     /// Add keywords
     pub fn add_keywords(&mut self, keywords: impl IntoIterator<Item = String>) {
         for kw in keywords {
@@ -75,10 +129,9 @@ impl RelevanceScorer {
         (matches as f32 / self.keywords.len() as f32).min(1.0)
     }
 
-    /// Calculate recency score
-    fn recency_score(&self, finding: &ResearchFinding) -> f32 {
-        let now = Utc::now();
-        let age = now.signed_duration_since(finding.discovered_at);
+    /// Calculate recency score as of `at`
+    fn recency_score(&self, finding: &ResearchFinding, at: DateTime<Utc>) -> f32 {
+        let age = at.signed_duration_since(finding.discovered_at);
 
         // Score decays over time
         // - Within 1 day: 1.0
@@ -98,12 +151,21 @@ impl RelevanceScorer {
 
     /// Calculate source authority score
     fn source_authority_score(&self, finding: &ResearchFinding) -> f32 {
-        match finding.source {
+        self.source_authority
+            .get(&finding.source)
+            .copied()
+            .unwrap_or_else(|| Self::builtin_authority(&finding.source))
+    }
+
+    /// Authority of a source before any ratings
+    pub fn builtin_authority(source: &ResearchSource) -> f32 {
+        match source {
             ResearchSource::ArXiv => 0.95,        // Peer-reviewed papers
             ResearchSource::GitHub => 0.85,       // Code repos
             ResearchSource::Twitter => 0.5,       // Social media
             ResearchSource::Farcaster => 0.6,     // Decentralized social
             ResearchSource::LensProtocol => 0.6,  // Web3 social
+            ResearchSource::Reddit => 0.55,       // Community discussion
             ResearchSource::CustomFeed(_) => 0.7, // Custom feeds
         }
     }
@@ -120,8 +182,15 @@ impl RelevanceScorer {
         let likes = finding.metadata.get("likes")
             .and_then(|v| v.as_u64())
             .unwrap_or(0);
+        let upvotes = finding.metadata.get("upvotes")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
+        let comments = finding.metadata.get("num_comments")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0);
 
-        let engagement = stars + (citations * 10) + likes;
+        // Comments signal discussion, so they weigh more than votes
+        let engagement = stars + (citations * 10) + likes + upvotes + (comments * 3);
 
         // Logarithmic scale
         if engagement == 0 {
@@ -131,30 +200,91 @@ impl RelevanceScorer {
         }.min(1.0)
     }
 
-    /// Calculate total relevance score
-    pub fn score(&self, finding: &ResearchFinding) -> f32 {
-        let keyword = self.keyword_score(finding);
-        let recency = self.recency_score(finding);
-        let authority = self.source_authority_score(finding);
-        let engagement = self.engagement_score(finding);
+    /// Similarity of the finding to the query, mapped from [-1, 1] to [0, 1]. A
+    /// finding scored before keeps the similarity measured then, so rescoring
+    /// does not need the embeddings again.
+    fn embedding_score(&self, finding: &ResearchFinding) -> Option<f32> {
+        let measured = self
+            .query_embedding
+            .as_ref()
+            .zip(self.finding_embeddings.get(&finding.id))
+            .filter(|(query, embedding)| !query.is_empty() && query.len() == embedding.len())
+            .map(|(query, embedding)| (cosine_similarity(query, embedding) + 1.0) / 2.0);
+        measured.or_else(|| {
+            finding
+                .score_breakdown
+                .as_ref()
+                .and_then(|b| b.embedding_similarity.as_ref())
+                .map(|similarity| similarity.raw)
+        })
+    }
+
+    /// Calculate the per-factor breakdown of a finding's score
+    pub fn breakdown(&self, finding: &ResearchFinding) -> ScoreBreakdown {
+        self.breakdown_at(finding, Utc::now())
+    }
+
+    /// Breakdown with recency measured at `at`, so a score can be reproduced later
+    pub fn breakdown_at(&self, finding: &ResearchFinding, at: DateTime<Utc>) -> ScoreBreakdown {
+        let embedding_similarity = self
+            .embedding_score(finding)
+            .map(|s| ScoreComponent::new(s, self.weights.embedding_similarity));
+        // Without a similarity the other factors share its weight
+        let scale = match embedding_similarity {
+            Some(_) => 1.0,
+            None if self.weights.embedding_similarity < 1.0 => 1.0 / (1.0 - self.weights.embedding_similarity),
+            None => 1.0,
+        };
+        let keyword_match = ScoreComponent::new(self.keyword_score(finding), self.weights.keyword_match * scale);
+        let recency = ScoreComponent::new(self.recency_score(finding, at), self.weights.recency * scale);
+        let source_authority =
+            ScoreComponent::new(self.source_authority_score(finding), self.weights.source_authority * scale);
+        let engagement = ScoreComponent::new(self.engagement_score(finding), self.weights.engagement * scale);
 
         // Weighted sum
-        let total = 
-            keyword * self.weights.keyword_match +
-            recency * self.weights.recency +
-            authority * self.weights.source_authority +
-            engagement * self.weights.engagement;
-
-        // Ensure score is in [0, 1]
-        total.max(0.0).min(1.0)
+        let total = keyword_match.contribution
+            + recency.contribution
+            + source_authority.contribution
+            + engagement.contribution
+            + embedding_similarity.as_ref().map(|e| e.contribution).unwrap_or(0.0);
+
+        ScoreBreakdown {
+            keyword_match,
+            recency,
+            source_authority,
+            engagement,
+            embedding_similarity,
+            // Ensure score is in [0, 1]
+            total: total.clamp(0.0, 1.0),
+            keywords: {
+                let mut keywords: Vec<String> = self.keywords.iter().cloned().collect();
+                keywords.sort();
+                keywords
+            },
+            scored_at: Some(at),
+        }
     }
 
-    /// Score all findings
+    /// Calculate total relevance score
+    pub fn score(&self, finding: &ResearchFinding) -> f32 {
+        self.breakdown(finding).total
+    }
+
+    /// Score all findings, attaching the breakdown and scorer version to each
     pub fn score_all(&self, findings: &mut [ResearchFinding]) {
+        let now = Utc::now();
         for finding in findings.iter_mut() {
-            finding.relevance_score = self.score(finding);
+            self.apply(finding, now);
         }
     }
+
+    /// Score a finding as of `at`
+    pub fn apply(&self, finding: &mut ResearchFinding, at: DateTime<Utc>) {
+        let breakdown = self.breakdown_at(finding, at);
+        finding.relevance_score = breakdown.total;
+        finding.score_breakdown = Some(breakdown);
+        finding.scorer_version = Some(self.version);
+    }
 }
 
 impl Default for RelevanceScorer {
@@ -219,6 +349,8 @@ mod tests {
             tags: tags.into_iter().map(|s| s.to_string()).collect(),
             url: None,
             metadata: serde_json::json!({"stars": 100}),
+            score_breakdown: None,
+            scorer_version: None,
         }
     }
 
@@ -251,6 +383,76 @@ mod tests {
         assert!(scorer.source_authority_score(&arxiv) > scorer.source_authority_score(&twitter));
     }
 
+    #[test]
+    fn test_breakdown_sums_to_total() {
+        let mut finding = make_finding("Rust Runtime", vec![]);
+        finding.metadata = serde_json::json!({"stars": 100});
+        let scorer = RelevanceScorer::with_keywords(vec!["rust".to_string()])
+            .with_query_embedding(vec![1.0, 0.0])
+            .with_finding_embeddings(HashMap::from([(finding.id.clone(), vec![1.0, 0.0])]));
+
+        let breakdown = scorer.breakdown(&finding);
+        let similarity = breakdown.embedding_similarity.clone().unwrap();
+        let sum = breakdown.keyword_match.contribution
+            + breakdown.recency.contribution
+            + breakdown.source_authority.contribution
+            + breakdown.engagement.contribution
+            + similarity.contribution;
+
+        assert!((breakdown.total - sum.min(1.0)).abs() < 1e-6);
+        assert_eq!(similarity.raw, 1.0);
+        assert!(similarity.weight > 0.0);
+
+        let mut findings = vec![finding];
+        scorer.score_all(&mut findings);
+        assert_eq!(findings[0].score_breakdown.as_ref().unwrap().total, findings[0].relevance_score);
+        assert_eq!(findings[0].scorer_version, Some(SCORER_VERSION));
+    }
+
+    #[test]
+    fn test_rescore_is_reproducible() {
+        let scorer = RelevanceScorer::with_keywords(vec!["rust".to_string()]);
+        let mut finding = make_finding("Rust Runtime", vec![]);
+        finding.discovered_at = Utc::now() - Duration::days(3);
+        scorer.score_all(std::slice::from_mut(&mut finding));
+        let original = finding.score_breakdown.clone().unwrap();
+
+        // Same version, keywords and reference time: same score, even days later
+        let rescorer = RelevanceScorer::for_version(SCORER_VERSION, original.keywords.clone()).unwrap();
+        let again = rescorer.breakdown_at(&finding, original.scored_at.unwrap());
+        assert_eq!(again, original);
+        assert!(rescorer.breakdown_at(&finding, Utc::now() + Duration::days(30)).total < original.total);
+
+        assert!(RelevanceScorer::for_version(SCORER_VERSION + 1, Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_semantic_similarity_weighs_in() {
+        let keywords = vec!["agents".to_string()];
+        let close = make_finding("LLM agents", vec![]);
+        let far = make_finding("LLM agents", vec![]);
+        let scorer = RelevanceScorer::with_keywords(keywords.clone())
+            .with_query_embedding(vec![1.0, 0.0])
+            .with_finding_embeddings(HashMap::from([
+                (close.id.clone(), vec![0.9, 0.1]),
+                (far.id.clone(), vec![-0.2, 1.0]),
+            ]));
+        assert!(scorer.score(&close) > scorer.score(&far));
+
+        // Without embeddings the current version scores like version 1
+        let at = Utc::now();
+        let v1 = RelevanceScorer::for_version(1, keywords.clone()).unwrap().breakdown_at(&close, at);
+        let plain = RelevanceScorer::with_keywords(keywords.clone()).breakdown_at(&close, at);
+        assert!((plain.total - v1.total).abs() < 1e-6);
+        assert!(plain.embedding_similarity.is_none());
+
+        // Rescoring keeps the similarity measured when the finding was first scored
+        let mut scored = close.clone();
+        scorer.apply(&mut scored, at);
+        let rescored = RelevanceScorer::for_version(SCORER_VERSION, keywords).unwrap().breakdown_at(&scored, at);
+        assert_eq!(rescored, scored.score_breakdown.unwrap());
+    }
+
     #[test]
     fn test_process() {
         let scorer = RelevanceScorer::new().with_threshold(0.0);
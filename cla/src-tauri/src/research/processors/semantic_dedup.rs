@@ -0,0 +1,152 @@
+// Semantic Dedup - Merges findings that say the same thing in different words
+// Titles and summaries are embedded with the local embedding model (by the task
+// scheduler, which scores with the same vectors); findings at or above the
+// similarity threshold are merged into the best-scored one
+
+use crate::commander::ResearchFinding;
+use crate::inference::cosine_similarity;
+
+/// Cosine similarity at which two findings are the same news
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.9;
+
+/// Embedding-based dedup pass over a scan's findings
+pub struct SemanticDedup {
+    threshold: f32,
+}
+
+impl SemanticDedup {
+    pub fn new() -> Self {
+        Self {
+            threshold: DEFAULT_SIMILARITY_THRESHOLD,
+        }
+    }
+
+    /// Merge semantic duplicates, best-scored first. `embeddings` holds one vector
+    /// per finding, in order; without one for each, the findings are returned unchanged.
+    pub fn merge(&self, findings: Vec<ResearchFinding>, embeddings: &[Vec<f32>]) -> Vec<ResearchFinding> {
+        if findings.len() < 2 || embeddings.len() != findings.len() {
+            return findings;
+        }
+        let (merged, removed) = merge_similar(findings, embeddings, self.threshold);
+        if removed > 0 {
+            log::info!("Semantic dedup merged {} near-duplicate findings", removed);
+        }
+        merged
+    }
+}
+
+impl Default for SemanticDedup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Merge findings whose embeddings are at least `threshold` similar into the
+/// highest-scored of them. The kept finding lists the ids it absorbed in its
+/// `merged_ids` metadata; returns the findings best first and how many were merged.
+pub fn merge_similar(
+    findings: Vec<ResearchFinding>,
+    embeddings: &[Vec<f32>],
+    threshold: f32,
+) -> (Vec<ResearchFinding>, usize) {
+    let mut ranked: Vec<(ResearchFinding, &Vec<f32>)> = findings.into_iter().zip(embeddings).collect();
+    ranked.sort_by(|a, b| {
+        b.0.relevance_score
+            .partial_cmp(&a.0.relevance_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    let mut kept: Vec<(ResearchFinding, &Vec<f32>)> = Vec::with_capacity(ranked.len());
+    let mut removed = 0;
+    for (finding, embedding) in ranked {
+        let duplicate_of = kept
+            .iter_mut()
+            .find(|(_, kept_embedding)| cosine_similarity(kept_embedding, embedding) >= threshold);
+        match duplicate_of {
+            Some((original, _)) => {
+                record_merge(original, &finding);
+                removed += 1;
+            }
+            None => kept.push((finding, embedding)),
+        }
+    }
+
+    (kept.into_iter().map(|(finding, _)| finding).collect(), removed)
+}
+
+/// Add `duplicate` and whatever it had absorbed to `original`'s merged ids
+fn record_merge(original: &mut ResearchFinding, duplicate: &ResearchFinding) {
+    let mut ids: Vec<serde_json::Value> = vec![duplicate.id.clone().into()];
+    if let Some(earlier) = duplicate.metadata.get("merged_ids").and_then(|v| v.as_array()) {
+        ids.extend(earlier.iter().cloned());
+    }
+
+    if !original.metadata.is_object() {
+        original.metadata = serde_json::json!({});
+    }
+    if let Some(metadata) = original.metadata.as_object_mut() {
+        let merged = metadata
+            .entry("merged_ids")
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        if let Some(merged) = merged.as_array_mut() {
+            merged.extend(ids);
+        }
+    }
+}
+
+/// Text a finding is embedded from: title and summary, of which `embed_texts` keeps the start
+pub fn embedding_text(finding: &ResearchFinding) -> String {
+    format!("{}. {}", finding.title, finding.summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commander::ResearchSource;
+    use chrono::Utc;
+
+    fn finding(id: &str, title: &str, score: f32) -> ResearchFinding {
+        ResearchFinding {
+            id: id.to_string(),
+            source: ResearchSource::Reddit,
+            title: title.to_string(),
+            summary: String::new(),
+            relevance_score: score,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: None,
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[test]
+    fn test_similar_findings_merge_into_best_scored() {
+        let findings = vec![
+            finding("reddit", "GPT-5 released", 0.6),
+            finding("github", "Tokio 2.0", 0.7),
+            finding("twitter", "OpenAI launches GPT-5", 0.9),
+            finding("arxiv", "GPT-5 is out", 0.5),
+        ];
+        let embeddings = vec![
+            vec![0.9, 0.1, 0.0],
+            vec![0.0, 0.2, 1.0],
+            vec![1.0, 0.0, 0.1],
+            vec![0.95, 0.05, 0.05],
+        ];
+
+        let (merged, removed) = merge_similar(findings, &embeddings, DEFAULT_SIMILARITY_THRESHOLD);
+        assert_eq!(removed, 2);
+        assert_eq!(merged.iter().map(|f| f.id.as_str()).collect::<Vec<_>>(), vec!["twitter", "github"]);
+        assert_eq!(merged[0].metadata["merged_ids"], serde_json::json!(["reddit", "arxiv"]));
+        assert!(merged[1].metadata.get("merged_ids").is_none());
+    }
+
+    #[test]
+    fn test_without_embeddings_findings_pass_through() {
+        let findings = vec![finding("a", "GPT-5 released", 0.6), finding("b", "GPT-5 released", 0.5)];
+        let result = SemanticDedup::new().merge(findings, &[vec![1.0, 0.0]]);
+        assert_eq!(result.len(), 2);
+    }
+}
@@ -1,7 +1,65 @@
 // Signal Processor - Converts research findings to Commander signals
-// Part of the OODA loop integration
+// Part of the OODA loop integration. Besides one signal per finding it tracks how
+// often keywords are mentioned across scans and reports sudden spikes as trends
 
 use crate::commander::{ResearchFinding, ResearchSource, Signal};
+use crate::research::dedup::finding_keywords;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Trend signals reported per scan, strongest first
+const MAX_TRENDS_PER_SCAN: usize = 3;
+
+/// When a keyword counts as trending
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct TrendConfig {
+    /// Hours of recent mentions compared against the baseline
+    pub window_hours: u32,
+    /// Hours of history the baseline rate is taken from, including the window
+    pub baseline_hours: u32,
+    /// New findings in the window that must mention the keyword
+    pub min_mentions: u32,
+    /// Times the baseline rate the window must reach
+    pub spike_ratio: f32,
+}
+
+impl Default for TrendConfig {
+    fn default() -> Self {
+        Self {
+            window_hours: 24,
+            baseline_hours: 7 * 24,
+            min_mentions: 3,
+            spike_ratio: 3.0,
+        }
+    }
+}
+
+impl TrendConfig {
+    /// Check the config before it is used
+    pub fn validate(&self) -> Result<(), String> {
+        if self.window_hours == 0 {
+            return Err("Trendvinduet skal være mindst én time".to_string());
+        }
+        if !(self.spike_ratio.is_finite() && self.spike_ratio > 0.0) {
+            return Err("Trendforholdet skal være større end 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Keyword mentions across scans, kept in the local database between runs
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct KeywordHistory {
+    /// When findings mentioning each keyword were seen, oldest first
+    pub mentions: HashMap<String, VecDeque<DateTime<Utc>>>,
+    /// When a trend was last reported for each keyword
+    pub reported: HashMap<String, DateTime<Utc>>,
+    /// First scan observed; trends need a full window of history before them
+    pub since: Option<DateTime<Utc>>,
+}
 
 /// Signal processor for converting findings to commander signals
 #[derive(Debug, Clone)]
@@ -10,6 +68,9 @@ pub struct SignalProcessor {
     pub relevance_threshold: f32,
     /// Minimum stars/popularity for tech detection
     pub popularity_threshold: u32,
+    pub trend: TrendConfig,
+    /// Shared by clones, so every scan adds to the same history
+    history: Arc<Mutex<KeywordHistory>>,
 }
 
 impl Default for SignalProcessor {
@@ -17,6 +78,8 @@ impl Default for SignalProcessor {
         Self {
             relevance_threshold: 0.6,
             popularity_threshold: 100,
+            trend: TrendConfig::default(),
+            history: Arc::new(Mutex::new(KeywordHistory::default())),
         }
     }
 }
@@ -27,9 +90,21 @@ impl SignalProcessor {
         Self {
             relevance_threshold,
             popularity_threshold,
+            ..Default::default()
         }
     }
 
+    /// Continue from keyword history recorded by an earlier run
+    pub fn with_history(mut self, history: KeywordHistory) -> Self {
+        self.history = Arc::new(Mutex::new(history));
+        self
+    }
+
+    /// Keyword history so far, for persisting
+    pub fn history(&self) -> KeywordHistory {
+        self.history.lock().map(|history| history.clone()).unwrap_or_default()
+    }
+
     /// Process a finding and generate appropriate signal
     pub fn process(&self, finding: &ResearchFinding) -> Option<Signal> {
         // Skip low relevance findings
@@ -47,7 +122,10 @@ impl SignalProcessor {
         let signal = match &finding.source {
             ResearchSource::GitHub => self.process_github_finding(finding),
             ResearchSource::ArXiv => self.process_arxiv_finding(finding),
-            ResearchSource::Twitter | ResearchSource::Farcaster | ResearchSource::LensProtocol => {
+            ResearchSource::Twitter
+            | ResearchSource::Farcaster
+            | ResearchSource::LensProtocol
+            | ResearchSource::Reddit => {
                 self.process_social_finding(finding)
             }
             ResearchSource::CustomFeed(_) => self.process_custom_finding(finding),
@@ -122,10 +200,18 @@ impl SignalProcessor {
             .and_then(|v| v.as_u64())
             .unwrap_or(0) as u32;
 
-        if engagement > 1000 || finding.relevance_score >= 0.75 {
+        // Adapters that measure engagement velocity report it as momentum
+        let momentum = finding
+            .metadata
+            .get("momentum")
+            .and_then(|v| v.as_f64())
+            .map(|m| m as f32)
+            .unwrap_or(finding.relevance_score);
+
+        if engagement > 1000 || finding.relevance_score >= 0.75 || momentum >= 0.75 {
             Some(Signal::SocialTrend {
                 topic: finding.title.clone(),
-                momentum: finding.relevance_score,
+                momentum,
                 platform: format!("{:?}", finding.source),
             })
         } else {
@@ -154,6 +240,112 @@ impl SignalProcessor {
             .filter_map(|f| self.process(f))
             .collect()
     }
+
+    /// Record the keywords of a scan's new findings and return a signal for each
+    /// keyword mentioned far more often than its baseline: a SocialTrend when social
+    /// sources mention it most, otherwise NewTechnologyDetected
+    pub fn detect_trends(&self, findings: &[ResearchFinding], at: DateTime<Utc>) -> Vec<Signal> {
+        let mentioned: Vec<BTreeSet<String>> = findings.iter().map(finding_keywords).collect();
+        let window_start = at - Duration::hours(self.trend.window_hours as i64);
+        let baseline_start = at - Duration::hours(self.trend.baseline_hours.max(self.trend.window_hours) as i64);
+        // Number of windows the baseline spans, for the expected mentions per window
+        let baseline_windows = (self.trend.baseline_hours.saturating_sub(self.trend.window_hours) as f32
+            / self.trend.window_hours.max(1) as f32)
+            .max(1.0);
+
+        let Ok(mut history) = self.history.lock() else {
+            return Vec::new();
+        };
+        let since = *history.since.get_or_insert(at);
+        for keyword in mentioned.iter().flatten() {
+            history.mentions.entry(keyword.clone()).or_default().push_back(at);
+        }
+        history.mentions.retain(|_, times| {
+            while times.front().is_some_and(|time| *time < baseline_start) {
+                times.pop_front();
+            }
+            !times.is_empty()
+        });
+        history.reported.retain(|_, time| *time > window_start);
+        if since > window_start {
+            return Vec::new();
+        }
+
+        let mut trends: Vec<(String, f32)> = Vec::new();
+        for keyword in mentioned.iter().flatten().collect::<BTreeSet<_>>() {
+            if history.reported.contains_key(keyword) {
+                continue;
+            }
+            let Some(times) = history.mentions.get(keyword) else {
+                continue;
+            };
+            let recent = times.iter().filter(|time| **time > window_start).count();
+            let baseline = times.len() - recent;
+            // One extra mention keeps a keyword never seen before from an infinite ratio
+            let ratio = recent as f32 / ((baseline + 1) as f32 / baseline_windows);
+            if recent >= self.trend.min_mentions as usize && ratio >= self.trend.spike_ratio {
+                trends.push((keyword.clone(), (1.0 - 1.0 / ratio).clamp(0.0, 1.0)));
+            }
+        }
+        trends.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal).then(a.0.cmp(&b.0)));
+        trends.truncate(MAX_TRENDS_PER_SCAN);
+
+        trends
+            .into_iter()
+            .map(|(keyword, momentum)| {
+                history.reported.insert(keyword.clone(), at);
+                let about: Vec<&ResearchFinding> = findings
+                    .iter()
+                    .zip(&mentioned)
+                    .filter(|(_, words)| words.contains(&keyword))
+                    .map(|(finding, _)| finding)
+                    .collect();
+                log::info!("Trend detected: \"{}\" in {} new findings (momentum {:.2})", keyword, about.len(), momentum);
+                trend_signal(keyword, momentum, &about)
+            })
+            .collect()
+    }
+}
+
+/// Typed signal for a trending keyword from the findings that mention it
+fn trend_signal(keyword: String, momentum: f32, about: &[&ResearchFinding]) -> Signal {
+    let is_social = |source: &ResearchSource| {
+        matches!(
+            source,
+            ResearchSource::Twitter | ResearchSource::Farcaster | ResearchSource::LensProtocol | ResearchSource::Reddit
+        )
+    };
+    let social = about.iter().filter(|f| is_social(&f.source)).count();
+    let social_majority = social * 2 > about.len();
+
+    // The source mentioning the keyword most, among the majority kind
+    let mut counts: Vec<(&ResearchSource, usize)> = Vec::new();
+    for finding in about.iter().filter(|f| is_social(&f.source) == social_majority) {
+        match counts.iter_mut().find(|(source, _)| **source == finding.source) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&finding.source, 1)),
+        }
+    }
+    let source = counts
+        .iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(source, _)| format!("{:?}", source))
+        .unwrap_or_default();
+
+    if social_majority {
+        Signal::SocialTrend {
+            topic: keyword,
+            momentum,
+            platform: source,
+        }
+    } else {
+        let relevance_score = about.iter().map(|f| f.relevance_score).sum::<f32>() / about.len().max(1) as f32;
+        Signal::NewTechnologyDetected {
+            name: keyword,
+            relevance_score,
+            source,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -172,6 +364,8 @@ mod tests {
             tags: vec!["cs.AI".to_string()],
             url: None,
             metadata: serde_json::json!({"stars": 500}),
+            score_breakdown: None,
+            scorer_version: None,
         }
     }
 
@@ -205,6 +399,74 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_social_finding_uses_momentum() {
+        let processor = SignalProcessor::default();
+        let mut finding = create_finding("Trending thread", ResearchSource::Twitter, 0.65);
+        finding.metadata = serde_json::json!({"momentum": 0.9});
+
+        match processor.process(&finding) {
+            Some(Signal::SocialTrend { momentum, .. }) => assert_eq!(momentum, 0.9),
+            other => panic!("Expected SocialTrend signal, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_keyword_spike_becomes_trend_signal() {
+        let processor = SignalProcessor::default();
+        let start = Utc::now() - Duration::days(3);
+        let scan = |titles: &[&str], source: ResearchSource| -> Vec<ResearchFinding> {
+            titles
+                .iter()
+                .map(|title| {
+                    let mut finding = create_finding(title, source.clone(), 0.7);
+                    finding.tags.clear();
+                    finding
+                })
+                .collect()
+        };
+
+        // Two quiet days in which "rust" comes up every scan and "mcp" once
+        for day in 0..2 {
+            let at = start + Duration::days(day);
+            let findings = scan(&["Rust web framework", "Rust compiler release"], ResearchSource::GitHub);
+            assert!(processor.detect_trends(&findings, at).is_empty());
+        }
+        let at = start + Duration::days(1) + Duration::hours(1);
+        assert!(processor
+            .detect_trends(&scan(&["Writing an MCP server"], ResearchSource::Reddit), at)
+            .is_empty());
+
+        // Then "mcp" is everywhere on social media, "rust" as usual
+        let at = start + Duration::days(2) + Duration::hours(6);
+        let findings = scan(
+            &["MCP servers everywhere", "Rust MCP SDK", "Why MCP matters", "Rust borrow checker tips"],
+            ResearchSource::Reddit,
+        );
+        let signals = processor.detect_trends(&findings, at);
+        assert_eq!(signals.len(), 1, "{:?}", signals);
+        match &signals[0] {
+            Signal::SocialTrend { topic, momentum, platform } => {
+                assert_eq!(topic, "mcp");
+                assert!(*momentum > 0.5);
+                assert_eq!(platform, "Reddit");
+            }
+            other => panic!("Expected SocialTrend signal, got {:?}", other),
+        }
+
+        // A trend is reported once per window
+        let later = at + Duration::hours(1);
+        assert!(processor.detect_trends(&scan(&["MCP again"], ResearchSource::Reddit), later).is_empty());
+    }
+
+    #[test]
+    fn test_trend_config_is_validated() {
+        assert!(TrendConfig::default().validate().is_ok());
+        assert!(TrendConfig { window_hours: 0, ..Default::default() }.validate().is_err());
+        assert!(TrendConfig { spike_ratio: 0.0, ..Default::default() }.validate().is_err());
+        assert!(TrendConfig { spike_ratio: f32::NAN, ..Default::default() }.validate().is_err());
+    }
+
     #[test]
     fn test_low_relevance_skipped() {
         let processor = SignalProcessor::default();
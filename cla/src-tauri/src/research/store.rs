@@ -0,0 +1,151 @@
+// Finding Store - Findings kept across scans in the local database
+// A finding that matches a stored one by URL, title or near-identical text is
+// merged into it instead of stored again; old findings are archived, then deleted
+
+use crate::commander::{ResearchFinding, ResearchSource};
+use crate::research::dedup::{canonical_hash, canonical_text, canonical_url, simhash};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Most bits two simhashes may differ in for the findings to be duplicates
+pub const MAX_SIMHASH_DISTANCE: u32 = 3;
+
+/// Titles shorter than this many words are too generic to match on alone
+const MIN_TITLE_WORDS: usize = 3;
+
+/// Texts shorter than this many words are too short to match by simhash
+const MIN_SIMHASH_WORDS: usize = 8;
+
+/// A stored finding with when and how often scans returned it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredFinding {
+    pub finding: ResearchFinding,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub times_seen: u32,
+    /// Not seen for a while; left out of searches unless asked for
+    pub archived: bool,
+}
+
+/// Which stored findings to return; empty fields match everything
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FindingFilter {
+    pub sources: Vec<ResearchSource>,
+    pub min_relevance: Option<f32>,
+    /// First seen at or after
+    pub since: Option<DateTime<Utc>>,
+    /// First seen before
+    pub until: Option<DateTime<Utc>>,
+    pub tag: Option<String>,
+    pub include_archived: bool,
+    /// Defaults to 50
+    pub limit: Option<u32>,
+    pub offset: u32,
+}
+
+/// How long stored findings are kept after a scan last returned them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindingRetention {
+    pub archive_after_days: u32,
+    /// None keeps archived findings forever
+    pub delete_after_days: Option<u32>,
+}
+
+impl FindingRetention {
+    /// Findings last seen before this are archived
+    pub fn archive_before(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now - Duration::days(self.archive_after_days as i64)
+    }
+
+    /// Findings last seen before this are deleted
+    pub fn delete_before(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.delete_after_days.map(|days| now - Duration::days(days as i64))
+    }
+}
+
+impl Default for FindingRetention {
+    fn default() -> Self {
+        Self {
+            archive_after_days: 90,
+            delete_after_days: Some(365),
+        }
+    }
+}
+
+/// What a finding is matched against stored findings by
+#[derive(Debug, Clone, PartialEq)]
+pub struct FindingKeys {
+    pub canonical_url: Option<String>,
+    /// Canonical hash of the title; None for titles too short to match on
+    pub title_hash: Option<u64>,
+    /// Simhash of title and summary; None for texts too short to match on
+    pub simhash: Option<u64>,
+}
+
+impl FindingKeys {
+    pub fn of(finding: &ResearchFinding) -> Self {
+        let title = canonical_text(&finding.title);
+        let text = format!("{} {}", finding.title, finding.summary);
+
+        Self {
+            canonical_url: finding.url.as_deref().and_then(canonical_url),
+            title_hash: (title.split_whitespace().count() >= MIN_TITLE_WORDS).then(|| canonical_hash(&title)),
+            simhash: (canonical_text(&text).split_whitespace().count() >= MIN_SIMHASH_WORDS)
+                .then(|| simhash(&text)),
+        }
+    }
+}
+
+/// The four 16-bit bands of a simhash. Hashes within MAX_SIMHASH_DISTANCE bits
+/// of each other share at least one band, so bands can be looked up by index.
+pub fn simhash_bands(hash: u64) -> [u16; 4] {
+    [0, 16, 32, 48].map(|shift| (hash >> shift) as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::research::dedup::simhash_distance;
+
+    fn finding(title: &str, summary: &str, url: Option<&str>) -> ResearchFinding {
+        ResearchFinding {
+            id: "f".to_string(),
+            source: ResearchSource::GitHub,
+            title: title.to_string(),
+            summary: summary.to_string(),
+            relevance_score: 0.5,
+            discovered_at: Utc::now(),
+            tags: Vec::new(),
+            url: url.map(str::to_string),
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        }
+    }
+
+    #[test]
+    fn test_short_texts_get_no_fuzzy_keys() {
+        let keys = FindingKeys::of(&finding("v1.0", "", Some("https://github.com/a/b/")));
+        assert_eq!(keys.canonical_url.as_deref(), Some("github.com/a/b"));
+        assert_eq!(keys.title_hash, None);
+        assert_eq!(keys.simhash, None);
+
+        let keys = FindingKeys::of(&finding(
+            "Rust agents in production",
+            "How a small team runs autonomous agents written in Rust",
+            None,
+        ));
+        assert!(keys.title_hash.is_some());
+        assert!(keys.simhash.is_some());
+    }
+
+    #[test]
+    fn test_close_simhashes_share_a_band() {
+        let hash = 0x0123_4567_89ab_cdef_u64;
+        let close = hash ^ (1 << 3) ^ (1 << 20) ^ (1 << 40);
+        assert!(simhash_distance(hash, close) <= MAX_SIMHASH_DISTANCE);
+        let shared = simhash_bands(hash).iter().zip(simhash_bands(close)).filter(|(a, b)| **a == *b).count();
+        assert_eq!(shared, 1);
+    }
+}
@@ -0,0 +1,131 @@
+// Command Rate Limits - Per-command call budgets enforced before a command runs
+// Keeps a runaway frontend script from hammering expensive commands
+
+use super::RateLimiter;
+use crate::models::CommandBudget;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use tauri::ipc::Invoke;
+use tauri::Runtime;
+
+/// Commands that change the budgets themselves. Their budget is fixed, so a
+/// runaway script cannot lift its own limits.
+pub const SETTINGS_COMMANDS: [&str; 2] = ["update_settings", "reset_settings"];
+
+/// Calls a webview may make to each settings command
+pub const SETTINGS_BUDGET: CommandBudget = CommandBudget {
+    max_calls: 10,
+    window_secs: 60,
+};
+
+/// Call budgets for commands, tracked per webview origin
+pub struct CommandLimiter {
+    limiters: RwLock<HashMap<String, (CommandBudget, RateLimiter)>>,
+}
+
+impl CommandLimiter {
+    pub fn new(budgets: &HashMap<String, CommandBudget>) -> Self {
+        let limiter = Self {
+            limiters: RwLock::new(HashMap::new()),
+        };
+        limiter.configure(budgets);
+        limiter
+    }
+
+    /// Replace the budgets. Commands whose budget is unchanged keep the calls
+    /// already counted; changed budgets start from zero.
+    pub fn configure(&self, budgets: &HashMap<String, CommandBudget>) {
+        let budgets: HashMap<&str, CommandBudget> = budgets
+            .iter()
+            .map(|(command, budget)| (command.as_str(), *budget))
+            .chain(SETTINGS_COMMANDS.map(|command| (command, SETTINGS_BUDGET)))
+            .collect();
+
+        let mut limiters = self.limiters.write().unwrap();
+        limiters.retain(|command, (budget, _)| budgets.get(command.as_str()) == Some(budget));
+        for (command, budget) in budgets {
+            limiters
+                .entry(command.to_string())
+                .or_insert_with(|| (budget, RateLimiter::new(budget.max_calls, budget.window_secs)));
+        }
+    }
+
+    /// Count a call; commands without a budget are always allowed
+    pub fn check(&self, command: &str, origin: &str) -> Result<(), String> {
+        let limiters = self.limiters.read().unwrap();
+        match limiters.get(command) {
+            Some((_, limiter)) if !limiter.check(origin) => {
+                log::warn!("Rate limited {} from {}", command, origin);
+                Err(format!("For mange kald til {}. Prøv igen om lidt.", command))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Wrap an invoke handler so calls over budget are rejected before they run
+pub fn rate_limited<R: Runtime>(
+    limiter: Arc<CommandLimiter>,
+    handler: impl Fn(Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let origin = invoke
+            .message
+            .webview_ref()
+            .url()
+            .map(|url| url.origin().ascii_serialization())
+            .unwrap_or_else(|_| invoke.message.webview_ref().label().to_string());
+        if let Err(message) = limiter.check(invoke.message.command(), &origin) {
+            invoke.resolver.reject(message);
+            return true;
+        }
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_budget_per_command_and_origin() {
+        let budgets = HashMap::from([("transcribe_audio".to_string(), CommandBudget { max_calls: 2, window_secs: 60 })]);
+        let limiter = CommandLimiter::new(&budgets);
+
+        assert!(limiter.check("transcribe_audio", "tauri://localhost").is_ok());
+        assert!(limiter.check("transcribe_audio", "tauri://localhost").is_ok());
+        assert!(limiter.check("transcribe_audio", "tauri://localhost").is_err());
+        assert!(limiter.check("transcribe_audio", "http://localhost:1420").is_ok());
+        assert!(limiter.check("get_settings", "tauri://localhost").is_ok());
+
+        // An unchanged budget keeps its count; a new one starts from zero
+        limiter.configure(&budgets);
+        assert!(limiter.check("transcribe_audio", "tauri://localhost").is_err());
+        let raised = HashMap::from([("transcribe_audio".to_string(), CommandBudget { max_calls: 3, window_secs: 60 })]);
+        limiter.configure(&raised);
+        assert!(limiter.check("transcribe_audio", "tauri://localhost").is_ok());
+    }
+
+    #[test]
+    fn test_settings_commands_keep_their_own_budget() {
+        let budgets = HashMap::from([("update_settings".to_string(), CommandBudget { max_calls: 1000, window_secs: 1 })]);
+        let limiter = CommandLimiter::new(&budgets);
+
+        for _ in 0..SETTINGS_BUDGET.max_calls {
+            assert!(limiter.check("update_settings", "tauri://localhost").is_ok());
+        }
+        assert!(limiter.check("update_settings", "tauri://localhost").is_err());
+
+        // Reconfiguring does not reset the settings budget
+        limiter.configure(&HashMap::new());
+        assert!(limiter.check("update_settings", "tauri://localhost").is_err());
+    }
+
+    #[test]
+    fn test_default_budgets_cover_expensive_commands() {
+        let budgets = crate::models::Settings::default().command_budgets;
+        for command in ["transcribe_audio", "download_model", "run_benchmark"] {
+            assert!(budgets.contains_key(command), "{} has no budget", command);
+        }
+    }
+}
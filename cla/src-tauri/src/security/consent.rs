@@ -0,0 +1,201 @@
+// Capability Consent - Ask once before CLA first uses the mic, user folders or autonomous research
+// Decisions are persisted; commands call `require` at their entry
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+const GRANTS_FILE: &str = "capabilities.json";
+
+/// Capabilities that need the user's consent before first use
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    Microphone,
+    FolderAccess,
+    AutonomousResearch,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 3] = [Self::Microphone, Self::FolderAccess, Self::AutonomousResearch];
+
+    /// Parse a capability name as sent by the frontend
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Microphone => "microphone",
+            Self::FolderAccess => "folder_access",
+            Self::AutonomousResearch => "autonomous_research",
+        }
+    }
+
+    /// Text shown in the permission prompt
+    pub fn prompt(&self) -> ConsentPrompt {
+        let (title, message) = match self {
+            Self::Microphone => (
+                "Adgang til mikrofonen",
+                "CLA vil lytte efter stemmekommandoer. Lyd behandles lokalt på denne enhed.",
+            ),
+            Self::FolderAccess => (
+                "Adgang til dine filer",
+                "CLA vil læse filer fra dine mapper for at behandle dem i pipelines.",
+            ),
+            Self::AutonomousResearch => (
+                "Selvstændig research",
+                "CLA vil søge efter nyt om dine emner i baggrunden og hente resultater fra nettet.",
+            ),
+        };
+        ConsentPrompt {
+            capability: *self,
+            title: title.to_string(),
+            message: message.to_string(),
+            allow_label: "Tillad".to_string(),
+            deny_label: "Afvis".to_string(),
+        }
+    }
+}
+
+/// Permission prompt the frontend shows before answering with `respond`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ConsentPrompt {
+    pub capability: Capability,
+    pub title: String,
+    pub message: String,
+    pub allow_label: String,
+    pub deny_label: String,
+}
+
+/// A remembered decision
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CapabilityGrant {
+    pub capability: Capability,
+    pub granted: bool,
+    pub decided_at: DateTime<Utc>,
+}
+
+/// Answer to `request`: a remembered decision, or a prompt to show
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum CapabilityRequest {
+    Granted,
+    Denied,
+    Prompt(ConsentPrompt),
+}
+
+/// Persisted capability decisions
+pub struct ConsentStore {
+    path: PathBuf,
+    grants: Mutex<HashMap<Capability, CapabilityGrant>>,
+}
+
+impl ConsentStore {
+    /// Load decisions stored in `data_dir`
+    pub fn load(data_dir: &Path) -> Self {
+        let path = data_dir.join(GRANTS_FILE);
+        let grants = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|json| {
+                serde_json::from_str::<Vec<CapabilityGrant>>(&json)
+                    .map_err(|e| log::warn!("Ignoring invalid capability grants: {}", e))
+                    .ok()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .map(|grant| (grant.capability, grant))
+            .collect();
+        Self {
+            path,
+            grants: Mutex::new(grants),
+        }
+    }
+
+    /// Remembered decision, or the prompt to show when there is none yet
+    pub fn request(&self, capability: Capability) -> CapabilityRequest {
+        match self.grants.lock().unwrap().get(&capability) {
+            Some(grant) if grant.granted => CapabilityRequest::Granted,
+            Some(_) => CapabilityRequest::Denied,
+            None => CapabilityRequest::Prompt(capability.prompt()),
+        }
+    }
+
+    /// Remember the user's answer to a prompt
+    pub fn respond(&self, capability: Capability, granted: bool) -> Result<CapabilityGrant, String> {
+        let grant = CapabilityGrant {
+            capability,
+            granted,
+            decided_at: Utc::now(),
+        };
+        let mut grants = self.grants.lock().unwrap();
+        grants.insert(capability, grant.clone());
+        self.persist(&grants)?;
+        log::info!("Capability {} {}", capability.name(), if granted { "granted" } else { "denied" });
+        Ok(grant)
+    }
+
+    /// Forget a decision so the user is asked again
+    pub fn revoke(&self, capability: Capability) -> Result<bool, String> {
+        let mut grants = self.grants.lock().unwrap();
+        let removed = grants.remove(&capability).is_some();
+        self.persist(&grants)?;
+        Ok(removed)
+    }
+
+    pub fn grants(&self) -> Vec<CapabilityGrant> {
+        let mut grants: Vec<CapabilityGrant> = self.grants.lock().unwrap().values().cloned().collect();
+        grants.sort_by_key(|grant| grant.capability.name());
+        grants
+    }
+
+    /// Entry check for commands: Ok only when the capability was granted
+    pub fn require(&self, capability: Capability) -> Result<(), String> {
+        match self.request(capability) {
+            CapabilityRequest::Granted => Ok(()),
+            CapabilityRequest::Denied => Err(format!(
+                "{} er afvist. Du kan ændre det under indstillinger.",
+                capability.prompt().title
+            )),
+            CapabilityRequest::Prompt(prompt) => Err(format!("{} kræver din tilladelse først.", prompt.title)),
+        }
+    }
+
+    fn persist(&self, grants: &HashMap<Capability, CapabilityGrant>) -> Result<(), String> {
+        let mut list: Vec<&CapabilityGrant> = grants.values().collect();
+        list.sort_by_key(|grant| grant.capability.name());
+        let json = serde_json::to_string_pretty(&list).map_err(|e| e.to_string())?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| format!("Kunne ikke oprette mappe: {}", e))?;
+        }
+        std::fs::write(&self.path, json).map_err(|e| format!("Kunne ikke gemme tilladelser: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ask_once_and_remember() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = ConsentStore::load(dir.path());
+
+        assert!(matches!(store.request(Capability::Microphone), CapabilityRequest::Prompt(_)));
+        assert!(store.require(Capability::Microphone).is_err());
+
+        store.respond(Capability::Microphone, true).unwrap();
+        store.respond(Capability::AutonomousResearch, false).unwrap();
+
+        // Decisions survive a restart
+        let store = ConsentStore::load(dir.path());
+        assert_eq!(store.request(Capability::Microphone), CapabilityRequest::Granted);
+        assert_eq!(store.request(Capability::AutonomousResearch), CapabilityRequest::Denied);
+        assert!(store.require(Capability::Microphone).is_ok());
+
+        assert!(store.revoke(Capability::Microphone).unwrap());
+        assert!(matches!(store.request(Capability::Microphone), CapabilityRequest::Prompt(_)));
+    }
+}
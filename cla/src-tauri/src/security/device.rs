@@ -0,0 +1,297 @@
+// Device identity - Ed25519 keypair that tells this device apart from the user's others
+// The secret key lives in the OS keychain; CKC sees the device id, metadata and a request signature
+// covering method, path, time and body
+
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use chrono::Utc;
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+/// Keychain service and account holding the secret key
+const KEYCHAIN_SERVICE: &str = "cirkelline-cla";
+const KEYCHAIN_ACCOUNT: &str = "device-signing-key";
+
+/// Metadata sent to CKC with every sync request
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DeviceInfo {
+    pub device_id: String,
+    pub name: String,
+    pub platform: String,
+    pub app_version: String,
+    /// Base64 Ed25519 public key
+    pub public_key: String,
+}
+
+/// This device's signing identity
+pub struct DeviceIdentity {
+    signing_key: SigningKey,
+    info: DeviceInfo,
+}
+
+impl DeviceIdentity {
+    /// Load the keypair from the keychain, generating it on first run.
+    /// When the keychain has no key, an existing key file in `data_dir` moves into
+    /// it, and a new key stays in the file if the keychain cannot store it. When
+    /// the keychain cannot be reached at all (no secret service, locked), the key
+    /// file is read or created instead, so the device keeps one identity across
+    /// restarts. Only if neither works is the key for this session only.
+    pub fn load_or_create(data_dir: &Path) -> Self {
+        let key_file = data_dir.join("device_key");
+        let secret = match keychain_entry().and_then(|entry| load_or_store(&entry, &key_file)) {
+            Err(DeviceError::Keychain(e)) => {
+                log::warn!("Keychain unavailable ({}), using the device key file", e);
+                load_or_create_file(&key_file)
+            }
+            result => result,
+        }
+        .unwrap_or_else(|e| {
+            log::error!("Device key could not be stored ({}), using a temporary identity", e);
+            generate_secret()
+        });
+        Self::from_secret(secret)
+    }
+
+    pub fn from_secret(secret: [u8; 32]) -> Self {
+        let signing_key = SigningKey::from_bytes(&secret);
+        let public_key = signing_key.verifying_key();
+        let name = sysinfo::System::host_name().unwrap_or_else(|| "Ukendt enhed".to_string());
+        Self {
+            info: DeviceInfo {
+                device_id: device_id(&public_key),
+                name,
+                platform: std::env::consts::OS.to_string(),
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                public_key: BASE64.encode(public_key.as_bytes()),
+            },
+            signing_key,
+        }
+    }
+
+    pub fn info(&self) -> &DeviceInfo {
+        &self.info
+    }
+
+    pub fn device_id(&self) -> &str {
+        &self.info.device_id
+    }
+
+    /// Headers identifying this device and signing `method path` with `body` at the current time
+    pub fn request_headers(&self, method: &str, path: &str, body: &[u8]) -> Vec<(&'static str, String)> {
+        let timestamp = Utc::now().timestamp().to_string();
+        let signature = self.signing_key.sign(signing_payload(method, path, &timestamp, body).as_bytes());
+        vec![
+            ("X-Device-Id", self.info.device_id.clone()),
+            ("X-Device-Name", self.info.name.clone()),
+            ("X-Device-Platform", self.info.platform.clone()),
+            ("X-Device-Version", self.info.app_version.clone()),
+            ("X-Device-Key", self.info.public_key.clone()),
+            ("X-Device-Timestamp", timestamp),
+            ("X-Device-Signature", BASE64.encode(signature.to_bytes())),
+        ]
+    }
+
+    /// Attach the device headers to a request sending `body` (empty when it has none)
+    pub fn sign_request(
+        &self,
+        request: reqwest::RequestBuilder,
+        method: &str,
+        path: &str,
+        body: &[u8],
+    ) -> reqwest::RequestBuilder {
+        self.request_headers(method, path, body)
+            .into_iter()
+            .fold(request, |request, (name, value)| request.header(name, value))
+    }
+}
+
+/// Default location of the fallback key file
+pub fn data_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("cirkelline-cla")
+}
+
+/// `METHOD\npath\ntimestamp\nhex sha256 of the body`
+fn signing_payload(method: &str, path: &str, timestamp: &str, body: &[u8]) -> String {
+    format!(
+        "{}\n{}\n{}\n{}",
+        method.to_uppercase(),
+        path,
+        timestamp,
+        hex::encode(Sha256::digest(body))
+    )
+}
+
+/// Stable id derived from the public key
+fn device_id(public_key: &VerifyingKey) -> String {
+    hex::encode(&Sha256::digest(public_key.as_bytes())[..16])
+}
+
+/// Whether `id` has the shape of a device id: 32 hex characters
+pub fn is_device_id(id: &str) -> bool {
+    id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn generate_secret() -> [u8; 32] {
+    SigningKey::generate(&mut rand::rngs::OsRng).to_bytes()
+}
+
+fn decode_secret(encoded: &str) -> Result<[u8; 32], DeviceError> {
+    BASE64
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(DeviceError::InvalidKey)
+}
+
+fn keychain_entry() -> Result<keyring::Entry, DeviceError> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).map_err(|e| DeviceError::Keychain(e.to_string()))
+}
+
+fn load_or_store(entry: &keyring::Entry, key_file: &Path) -> Result<[u8; 32], DeviceError> {
+    match entry.get_password() {
+        Ok(encoded) => decode_secret(&encoded),
+        Err(keyring::Error::NoEntry) => {
+            let existing = std::fs::read_to_string(key_file).ok();
+            let secret = match &existing {
+                Some(encoded) => decode_secret(encoded)?,
+                None => generate_secret(),
+            };
+            match entry.set_password(&BASE64.encode(secret)) {
+                Ok(()) if existing.is_some() => {
+                    log::info!("Moved device key from data directory into the keychain");
+                    let _ = std::fs::remove_file(key_file);
+                }
+                Ok(()) => log::info!("Generated new device identity"),
+                Err(e) if existing.is_none() => {
+                    log::warn!("Keychain cannot store the device key ({}), keeping it in data directory", e);
+                    store_file(key_file, &secret)?;
+                }
+                Err(e) => log::warn!("Keychain cannot store the device key ({}), keeping the key file", e),
+            }
+            Ok(secret)
+        }
+        Err(e) => Err(DeviceError::Keychain(e.to_string())),
+    }
+}
+
+/// Key from the key file, creating the file on first run
+fn load_or_create_file(key_file: &Path) -> Result<[u8; 32], DeviceError> {
+    match std::fs::read_to_string(key_file) {
+        Ok(encoded) => decode_secret(&encoded),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let secret = generate_secret();
+            store_file(key_file, &secret)?;
+            Ok(secret)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write a new key file, readable by the owner only from the moment it exists
+fn store_file(path: &Path, secret: &[u8; 32]) -> Result<(), DeviceError> {
+    use std::io::Write;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(BASE64.encode(secret).as_bytes())?;
+    log::info!("Generated new device identity");
+    Ok(())
+}
+
+/// Device identity errors
+#[derive(Debug, thiserror::Error)]
+pub enum DeviceError {
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+    #[error("Stored device key is invalid")]
+    InvalidKey,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+
+    /// Check a device signature the way CKC does
+    fn verify_signature(public_key: &str, method: &str, path: &str, body: &[u8], timestamp: &str, signature: &str) -> bool {
+        let Some(key) = BASE64
+            .decode(public_key)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+        else {
+            return false;
+        };
+        let Some(signature) = BASE64
+            .decode(signature)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        else {
+            return false;
+        };
+        key.verify(signing_payload(method, path, timestamp, body).as_bytes(), &signature).is_ok()
+    }
+
+    #[test]
+    fn test_signed_headers_verify() {
+        let identity = DeviceIdentity::from_secret([7u8; 32]);
+        let body = br#"{"ids":[]}"#;
+        let headers: std::collections::HashMap<_, _> =
+            identity.request_headers("post", "/api/devices", body).into_iter().collect();
+
+        assert_eq!(headers["X-Device-Id"], identity.device_id());
+        assert!(is_device_id(identity.device_id()));
+        assert!(!is_device_id("../../api/cla/sync"));
+        assert!(!is_device_id(&"g".repeat(32)));
+        let verify = |method: &str, body: &[u8]| {
+            verify_signature(
+                &headers["X-Device-Key"],
+                method,
+                "/api/devices",
+                body,
+                &headers["X-Device-Timestamp"],
+                &headers["X-Device-Signature"],
+            )
+        };
+        assert!(verify("POST", body));
+        assert!(!verify("DELETE", body));
+        // A replayed signature does not carry over to another body
+        assert!(!verify("POST", br#"{"ids":["x"]}"#));
+    }
+
+    #[test]
+    fn test_key_file_is_private_and_never_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("device_key");
+        let secret = generate_secret();
+        store_file(&path, &secret).unwrap();
+        assert_eq!(decode_secret(&std::fs::read_to_string(&path).unwrap()).unwrap(), secret);
+        assert!(store_file(&path, &generate_secret()).is_err());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn test_key_file_keeps_identity_without_keychain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("device_key");
+        let first = load_or_create_file(&path).unwrap();
+        assert_eq!(load_or_create_file(&path).unwrap(), first);
+    }
+}
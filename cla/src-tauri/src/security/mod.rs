@@ -4,6 +4,10 @@
 pub mod encryption;
 pub mod auth;
 pub mod validation;
+pub mod privacy;
+pub mod device;
+pub mod command_limits;
+pub mod consent;
 
 pub use encryption::{Encryptor, EncryptedData};
 pub use auth::{AuthManager, AuthToken, AuthError};
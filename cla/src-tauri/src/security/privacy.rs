@@ -0,0 +1,195 @@
+// Privacy Mode - One switch that suspends every capture subsystem
+// Subsystems check `is_active` or watch `subscribe`; the mode expires on its own
+
+use crate::telemetry::TelemetryService;
+use chrono::{DateTime, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// Health component that shows the privacy mode
+const HEALTH_COMPONENT: &str = "privacy_mode";
+
+/// Subsystems that capture user data
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureSubsystem {
+    /// Hotword detection, voice commands and live transcription
+    Microphone,
+    /// The Commander's background research scans
+    ResearchScans,
+}
+
+impl CaptureSubsystem {
+    pub const ALL: [CaptureSubsystem; 2] = [Self::Microphone, Self::ResearchScans];
+}
+
+/// Current privacy mode state
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PrivacyStatus {
+    pub active: bool,
+    pub since: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Subsystems suspended while active
+    pub suspended: Vec<CaptureSubsystem>,
+}
+
+#[derive(Debug, Default)]
+struct PrivacyState {
+    since: Option<DateTime<Utc>>,
+    expires_at: Option<DateTime<Utc>>,
+    /// Bumped on every change so a stale expiry timer does nothing
+    generation: u64,
+}
+
+/// Global privacy mode shared by all capture subsystems
+pub struct PrivacyMode {
+    state: Mutex<PrivacyState>,
+    active_tx: watch::Sender<bool>,
+    telemetry: Option<Arc<TelemetryService>>,
+}
+
+impl PrivacyMode {
+    pub fn new() -> Self {
+        let (active_tx, _) = watch::channel(false);
+        Self {
+            state: Mutex::new(PrivacyState::default()),
+            active_tx,
+            telemetry: None,
+        }
+    }
+
+    /// Show the mode as a health component
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Whether capture is currently suspended
+    pub fn is_active(&self) -> bool {
+        let state = self.state.lock().unwrap();
+        state.since.is_some() && state.expires_at.is_none_or(|at| Utc::now() < at)
+    }
+
+    /// Watch the mode; the value is true while capture is suspended
+    pub fn subscribe(&self) -> watch::Receiver<bool> {
+        self.active_tx.subscribe()
+    }
+
+    pub fn status(&self) -> PrivacyStatus {
+        let active = self.is_active();
+        let state = self.state.lock().unwrap();
+        PrivacyStatus {
+            active,
+            since: state.since.filter(|_| active),
+            expires_at: state.expires_at.filter(|_| active),
+            suspended: if active { CaptureSubsystem::ALL.to_vec() } else { Vec::new() },
+        }
+    }
+
+    /// Suspend all capture for `duration`
+    pub async fn enable(self: &Arc<Self>, duration: std::time::Duration) -> PrivacyStatus {
+        let expires_at = Utc::now() + Duration::from_std(duration).unwrap_or_else(|_| Duration::hours(1));
+        let generation = {
+            let mut state = self.state.lock().unwrap();
+            state.since = Some(state.since.unwrap_or_else(Utc::now));
+            state.expires_at = Some(expires_at);
+            state.generation += 1;
+            state.generation
+        };
+        self.active_tx.send_replace(true);
+        log::info!("Privacy mode enabled until {}", expires_at);
+        self.report(Some(expires_at)).await;
+
+        let mode = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            mode.disable_generation(Some(generation)).await;
+        });
+
+        self.status()
+    }
+
+    /// Resume capture
+    pub async fn disable(&self) -> PrivacyStatus {
+        self.disable_generation(None).await;
+        self.status()
+    }
+
+    /// Turn the mode off, unless `generation` is given and no longer current
+    async fn disable_generation(&self, generation: Option<u64>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            if state.since.is_none() || generation.is_some_and(|g| g != state.generation) {
+                return;
+            }
+            *state = PrivacyState {
+                generation: state.generation + 1,
+                ..Default::default()
+            };
+        }
+        self.active_tx.send_replace(false);
+        log::info!("Privacy mode ended");
+        self.report(None).await;
+    }
+
+    async fn report(&self, expires_at: Option<DateTime<Utc>>) {
+        if let Some(telemetry) = &self.telemetry {
+            telemetry
+                .update_component_health(HEALTH_COMPONENT, |health| {
+                    health.message = expires_at.map(|at| {
+                        format!("Privat tilstand aktiv til {}", at.with_timezone(&Local).format("%H:%M"))
+                    });
+                })
+                .await;
+        }
+    }
+}
+
+impl Default for PrivacyMode {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration as StdDuration;
+
+    #[tokio::test]
+    async fn test_enable_disable_notifies_watchers() {
+        let mode = Arc::new(PrivacyMode::new());
+        let mut rx = mode.subscribe();
+
+        let status = mode.enable(StdDuration::from_secs(600)).await;
+        assert!(status.active);
+        assert_eq!(status.suspended, CaptureSubsystem::ALL);
+        assert!(*rx.borrow_and_update());
+
+        assert!(!mode.disable().await.active);
+        assert!(rx.has_changed().unwrap());
+        assert!(!*rx.borrow_and_update());
+    }
+
+    #[tokio::test]
+    async fn test_expires_automatically() {
+        let mode = Arc::new(PrivacyMode::new());
+        mode.enable(StdDuration::from_millis(20)).await;
+        assert!(mode.is_active());
+
+        tokio::time::sleep(StdDuration::from_millis(60)).await;
+        assert!(!mode.is_active());
+        assert!(!*mode.subscribe().borrow());
+    }
+
+    #[tokio::test]
+    async fn test_extending_ignores_earlier_expiry() {
+        let mode = Arc::new(PrivacyMode::new());
+        mode.enable(StdDuration::from_millis(20)).await;
+        mode.enable(StdDuration::from_secs(600)).await;
+
+        tokio::time::sleep(StdDuration::from_millis(60)).await;
+        assert!(mode.is_active());
+    }
+}
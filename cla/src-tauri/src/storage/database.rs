@@ -0,0 +1,1469 @@
+// Local Database - SQLite store of memories, sessions, the task queue, the
+// Commander's decision log, research findings and keyword trend history
+// Schema changes are numbered migrations tracked in `PRAGMA user_version`; the
+// database runs in WAL mode and is checked at startup like the JSON stores.
+
+use super::{check_dir, quarantine, read_records, record, StoreStatus};
+use crate::commander::policy::{ApprovalState, ApprovalTransition, DecisionFilter, DecisionRecord};
+use crate::commander::{Decision, ResearchFinding};
+use crate::error::StorageError;
+use crate::models::{LocalMemory, LocalSession, PendingTask, TaskStatus, TaskType};
+use crate::research::dedup::simhash_distance;
+use crate::research::processors::KeywordHistory;
+use crate::research::store::{simhash_bands, FindingFilter, FindingKeys, FindingRetention, StoredFinding, MAX_SIMHASH_DISTANCE};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, params_from_iter, Connection, ErrorCode, OptionalExtension, Row};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Mutex;
+use uuid::Uuid;
+
+/// Name of the database in the storage health report
+pub(super) const STORE_NAME: &str = "database";
+
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Schema migrations; entry `n` takes the schema from version `n` to `n + 1`
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE memories (
+        id TEXT PRIMARY KEY,
+        content TEXT NOT NULL,
+        memory_type TEXT NOT NULL,
+        topics TEXT NOT NULL,
+        embedding BLOB,
+        importance REAL NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        synced_at TEXT,
+        cloud_id TEXT,
+        pending_sync INTEGER NOT NULL
+    );
+    CREATE INDEX memories_updated_at ON memories (updated_at);
+    CREATE TABLE sessions (
+        id TEXT PRIMARY KEY,
+        session_type TEXT NOT NULL,
+        context TEXT NOT NULL,
+        messages TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        synced_at TEXT,
+        cloud_id TEXT
+    );
+    CREATE INDEX sessions_updated_at ON sessions (updated_at);",
+    "CREATE TABLE tasks (
+        id TEXT PRIMARY KEY,
+        task_type TEXT NOT NULL,
+        priority INTEGER NOT NULL,
+        payload TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        retry_count INTEGER NOT NULL,
+        max_retries INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        error TEXT,
+        not_before TEXT,
+        last_error TEXT
+    );
+    CREATE INDEX tasks_due ON tasks (status, priority DESC, created_at);",
+    "CREATE TABLE decisions (
+        id TEXT PRIMARY KEY,
+        signal_type TEXT NOT NULL,
+        signal TEXT,
+        action TEXT NOT NULL,
+        confidence REAL NOT NULL,
+        rationale TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        requires_approval INTEGER NOT NULL,
+        state TEXT NOT NULL
+    );
+    CREATE INDEX decisions_created_at ON decisions (created_at);
+    CREATE TABLE decision_transitions (
+        decision_id TEXT NOT NULL,
+        state TEXT NOT NULL,
+        reason TEXT,
+        at TEXT NOT NULL
+    );
+    CREATE INDEX decision_transitions_decision ON decision_transitions (decision_id, at);",
+    "CREATE TABLE findings (
+        id TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        finding TEXT NOT NULL,
+        relevance_score REAL NOT NULL,
+        tags TEXT NOT NULL,
+        canonical_url TEXT,
+        title_hash INTEGER,
+        simhash INTEGER,
+        simhash_band0 INTEGER,
+        simhash_band1 INTEGER,
+        simhash_band2 INTEGER,
+        simhash_band3 INTEGER,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL,
+        times_seen INTEGER NOT NULL,
+        archived INTEGER NOT NULL
+    );
+    CREATE INDEX findings_canonical_url ON findings (canonical_url);
+    CREATE INDEX findings_title_hash ON findings (title_hash);
+    CREATE INDEX findings_simhash_band0 ON findings (simhash_band0);
+    CREATE INDEX findings_simhash_band1 ON findings (simhash_band1);
+    CREATE INDEX findings_simhash_band2 ON findings (simhash_band2);
+    CREATE INDEX findings_simhash_band3 ON findings (simhash_band3);
+    CREATE INDEX findings_last_seen ON findings (last_seen);
+    CREATE VIRTUAL TABLE findings_fts USING fts5(
+        id UNINDEXED, title, summary, tags,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );",
+    "CREATE TABLE keyword_mentions (
+        keyword TEXT NOT NULL,
+        seen_at TEXT NOT NULL
+    );
+    CREATE INDEX keyword_mentions_keyword ON keyword_mentions (keyword, seen_at);
+    CREATE TABLE keyword_trends (
+        keyword TEXT PRIMARY KEY,
+        reported_at TEXT NOT NULL
+    );
+    CREATE TABLE keyword_history (
+        id INTEGER PRIMARY KEY CHECK (id = 0),
+        since TEXT NOT NULL
+    );",
+];
+
+const MEMORY_COLUMNS: &str =
+    "id, content, memory_type, topics, embedding, importance, created_at, updated_at, synced_at, cloud_id, pending_sync";
+const SESSION_COLUMNS: &str = "id, session_type, context, messages, created_at, updated_at, synced_at, cloud_id";
+const TASK_COLUMNS: &str =
+    "id, task_type, priority, payload, created_at, retry_count, max_retries, status, error, not_before, last_error";
+const DECISION_COLUMNS: &str =
+    "id, signal_type, signal, action, confidence, rationale, created_at, requires_approval, state";
+const FINDING_COLUMNS: &str = "finding, first_seen, last_seen, times_seen, archived";
+
+/// Decisions returned by `list_decisions` when the filter sets no limit
+const DEFAULT_DECISION_LIMIT: u32 = 100;
+
+/// Findings returned by `search_findings` when the filter sets no limit
+const DEFAULT_FINDING_LIMIT: u32 = 50;
+
+/// SQLite database of local memories, sessions, queued tasks and logged decisions
+pub struct LocalDatabase {
+    conn: Mutex<Connection>,
+    /// Size limit for new writes, from `Settings::max_disk_mb`
+    quota_mb: AtomicU32,
+}
+
+impl LocalDatabase {
+    /// Open the database, creating and migrating it as needed. A damaged database
+    /// is moved aside and replaced by an empty one.
+    pub fn open(path: &Path, quota_mb: u32) -> Result<Self, StorageError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| StorageError::WriteError {
+                message: format!("{}: {}", dir.display(), e),
+            })?;
+        }
+
+        apply_staged_restore(path);
+        let conn = match open_checked(path) {
+            Ok(conn) => {
+                record(STORE_NAME, path, StoreStatus::Healthy, None, None);
+                conn
+            }
+            Err(StorageError::CorruptedData { message }) => {
+                log::error!("Database {:?} is damaged: {}", path, message);
+                let quarantined = quarantine_database(path);
+                record(
+                    STORE_NAME,
+                    path,
+                    StoreStatus::Corrupted,
+                    Some(format!(
+                        "{}. Vælg en sikkerhedskopi at gendanne fra, eller start forfra med tomme data.",
+                        message
+                    )),
+                    quarantined,
+                );
+                open_checked(path)?
+            }
+            Err(error) => return Err(error),
+        };
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            quota_mb: AtomicU32::new(quota_mb),
+        })
+    }
+
+    /// Empty database kept in memory, for when the data directory is unusable
+    pub fn in_memory(quota_mb: u32) -> Self {
+        let conn = Connection::open_in_memory().expect("Failed to open in-memory database");
+        migrate(&conn).expect("Failed to create database schema");
+        Self {
+            conn: Mutex::new(conn),
+            quota_mb: AtomicU32::new(quota_mb),
+        }
+    }
+
+    /// Default location of the database
+    pub fn default_path() -> PathBuf {
+        crate::security::device::data_dir().join("local.db")
+    }
+
+    pub fn set_quota_mb(&self, quota_mb: u32) {
+        self.quota_mb.store(quota_mb, Ordering::Relaxed);
+    }
+
+    /// Move records from a `<id>.json` directory written by older versions into
+    /// the database. The directory is renamed afterwards so this runs once.
+    pub fn import_legacy_dirs(&self, memories_dir: &Path, sessions_dir: &Path) {
+        let conn = self.conn.lock().unwrap();
+        if memories_dir.is_dir() {
+            check_dir::<LocalMemory>("memories", memories_dir);
+            let memories: Vec<LocalMemory> = read_records(memories_dir);
+            let imported = memories.iter().filter(|m| upsert_memory(&conn, m).is_ok()).count();
+            finish_import(memories_dir, imported, memories.len());
+        }
+        if sessions_dir.is_dir() {
+            check_dir::<LocalSession>("sessions", sessions_dir);
+            let sessions: Vec<LocalSession> = read_records(sessions_dir);
+            let imported = sessions.iter().filter(|s| upsert_session(&conn, s).is_ok()).count();
+            finish_import(sessions_dir, imported, sessions.len());
+        }
+    }
+
+    /// Insert or replace a memory
+    pub fn save_memory(&self, memory: &LocalMemory) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let size = memory.content.len() + memory.embedding_local.as_ref().map_or(0, |e| e.len() * 4);
+        self.check_quota(&conn, size)?;
+        upsert_memory(&conn, memory)
+    }
+
+    pub fn get_memory(&self, id: Uuid) -> Result<Option<LocalMemory>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM memories WHERE id = ?1", MEMORY_COLUMNS),
+            [id.to_string()],
+            memory_from_row,
+        )
+        .optional()
+        .map_err(db_error)
+    }
+
+    /// Memories, most recently changed first; `limit` None returns all
+    pub fn list_memories(&self, limit: Option<u32>, offset: u32) -> Result<Vec<LocalMemory>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {} FROM memories ORDER BY updated_at DESC LIMIT ?1 OFFSET ?2",
+                MEMORY_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![limit.map_or(-1, i64::from), offset], memory_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Memories whose content or topics contain every word of `query`,
+    /// most important first
+    pub fn search_memories(&self, query: &str, limit: u32) -> Result<Vec<LocalMemory>, StorageError> {
+        let patterns: Vec<String> = query
+            .split_whitespace()
+            .map(|word| format!("%{}%", escape_like(word)))
+            .collect();
+        if patterns.is_empty() {
+            return self.list_memories(Some(limit), 0);
+        }
+
+        let conditions: Vec<String> = (1..=patterns.len())
+            .map(|n| format!("(content LIKE ?{n} ESCAPE '\\' OR topics LIKE ?{n} ESCAPE '\\')"))
+            .collect();
+        let sql = format!(
+            "SELECT {} FROM memories WHERE {} ORDER BY importance DESC, updated_at DESC LIMIT {}",
+            MEMORY_COLUMNS,
+            conditions.join(" AND "),
+            limit
+        );
+
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(&sql).map_err(db_error)?;
+        let rows = statement
+            .query_map(params_from_iter(patterns.iter()), memory_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Delete a memory; false if it did not exist
+    pub fn delete_memory(&self, id: Uuid) -> Result<bool, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let deleted = conn
+            .execute("DELETE FROM memories WHERE id = ?1", [id.to_string()])
+            .map_err(db_error)?;
+        Ok(deleted > 0)
+    }
+
+    /// Insert or replace a session
+    pub fn save_session(&self, session: &LocalSession) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let size = session.messages.iter().map(|m| m.content.len()).sum::<usize>();
+        self.check_quota(&conn, size)?;
+        upsert_session(&conn, session)
+    }
+
+    pub fn get_session(&self, id: Uuid) -> Result<Option<LocalSession>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM sessions WHERE id = ?1", SESSION_COLUMNS),
+            [id.to_string()],
+            session_from_row,
+        )
+        .optional()
+        .map_err(db_error)
+    }
+
+    /// Sessions, most recently changed first; `limit` None returns all
+    pub fn list_sessions(&self, limit: Option<u32>, offset: u32) -> Result<Vec<LocalSession>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT {} FROM sessions ORDER BY updated_at DESC LIMIT ?1 OFFSET ?2",
+                SESSION_COLUMNS
+            ))
+            .map_err(db_error)?;
+        let rows = statement
+            .query_map(params![limit.map_or(-1, i64::from), offset], session_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Add a task to the queue
+    pub fn insert_task(&self, task: &PendingTask) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        self.check_quota(&conn, task.payload.to_string().len())?;
+        upsert_task(&conn, task)
+    }
+
+    /// Store a task's new status; not subject to the quota so running tasks can always finish
+    pub fn update_task(&self, task: &PendingTask) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        upsert_task(&conn, task)
+    }
+
+    pub fn get_task(&self, id: Uuid) -> Result<Option<PendingTask>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM tasks WHERE id = ?1", TASK_COLUMNS),
+            [id.to_string()],
+            task_from_row,
+        )
+        .optional()
+        .map_err(db_error)
+    }
+
+    /// All tasks, newest first
+    pub fn list_tasks(&self) -> Result<Vec<PendingTask>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn
+            .prepare(&format!("SELECT {} FROM tasks ORDER BY created_at DESC", TASK_COLUMNS))
+            .map_err(db_error)?;
+        let rows = statement.query_map([], task_from_row).map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// The queued task to run next at `now`: highest priority, then oldest.
+    /// Tasks of the `skip` types are left in the queue.
+    pub fn next_due_task(&self, now: DateTime<Utc>, skip: &[TaskType]) -> Result<Option<PendingTask>, StorageError> {
+        let skipped: Vec<String> = skip.iter().map(task_type_text).collect();
+        let placeholders: Vec<String> = (2..skipped.len() + 2).map(|n| format!("?{n}")).collect();
+        let sql = format!(
+            "SELECT {} FROM tasks
+             WHERE status = 'queued' AND (not_before IS NULL OR not_before <= ?1) AND task_type NOT IN ({})
+             ORDER BY priority DESC, created_at ASC LIMIT 1",
+            TASK_COLUMNS,
+            placeholders.join(", ")
+        );
+        let values = std::iter::once(timestamp(now)).chain(skipped);
+
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(&sql, params_from_iter(values), task_from_row)
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// Put tasks that were running when the app stopped back in the queue
+    pub fn requeue_running_tasks(&self) -> Result<usize, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("UPDATE tasks SET status = 'queued' WHERE status = 'running'", [])
+            .map_err(db_error)
+    }
+
+    /// Delete completed, failed and cancelled tasks
+    pub fn delete_finished_tasks(&self) -> Result<usize, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute("DELETE FROM tasks WHERE status IN ('completed', 'failed', 'cancelled')", [])
+            .map_err(db_error)
+    }
+
+    /// Log a decision with its first approval state. Like task updates this is not
+    /// subject to the quota, so the audit trail has no gaps.
+    pub fn record_decision(
+        &self,
+        decision: &Decision,
+        state: ApprovalState,
+        reason: Option<&str>,
+    ) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        transaction
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO decisions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                    DECISION_COLUMNS
+                ),
+                params![
+                    decision.id,
+                    decision.signal_type,
+                    decision.signal.as_ref().map(json_text),
+                    json_text(&decision.action),
+                    decision.confidence,
+                    decision.rationale,
+                    timestamp(decision.timestamp),
+                    decision.requires_approval,
+                    json_text(&state),
+                ],
+            )
+            .map_err(db_error)?;
+        insert_transition(&transaction, &decision.id, state, reason)?;
+        transaction.commit().map_err(db_error)
+    }
+
+    /// Move a logged decision to a new approval state; false if it was never logged
+    pub fn record_approval_transition(
+        &self,
+        decision_id: &str,
+        state: ApprovalState,
+        reason: Option<&str>,
+    ) -> Result<bool, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        let updated = transaction
+            .execute(
+                "UPDATE decisions SET state = ?2 WHERE id = ?1",
+                params![decision_id, json_text(&state)],
+            )
+            .map_err(db_error)?;
+        if updated == 0 {
+            return Ok(false);
+        }
+        insert_transition(&transaction, decision_id, state, reason)?;
+        transaction.commit().map_err(db_error)?;
+        Ok(true)
+    }
+
+    pub fn get_decision(&self, id: &str) -> Result<Option<DecisionRecord>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let record = conn
+            .query_row(
+                &format!("SELECT {} FROM decisions WHERE id = ?1", DECISION_COLUMNS),
+                [id],
+                decision_from_row,
+            )
+            .optional()
+            .map_err(db_error)?;
+        record.map(|record| with_transitions(&conn, record)).transpose()
+    }
+
+    /// Logged decisions matching `filter`, newest first
+    pub fn list_decisions(&self, filter: &DecisionFilter) -> Result<Vec<DecisionRecord>, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        let mut condition = |sql: &str, value: String| {
+            values.push(value);
+            conditions.push(format!("{} ?{}", sql, values.len()));
+        };
+        if let Some(since) = filter.since {
+            condition("created_at >=", timestamp(since));
+        }
+        if let Some(until) = filter.until {
+            condition("created_at <", timestamp(until));
+        }
+        if let Some(action) = &filter.action {
+            condition("action =", json_text(action));
+        }
+        if let Some(state) = &filter.state {
+            condition("state =", json_text(state));
+        }
+        if let Some(signal_type) = &filter.signal_type {
+            condition("signal_type =", signal_type.clone());
+        }
+        let sql = format!(
+            "SELECT {} FROM decisions {} ORDER BY created_at DESC LIMIT {} OFFSET {}",
+            DECISION_COLUMNS,
+            if conditions.is_empty() { String::new() } else { format!("WHERE {}", conditions.join(" AND ")) },
+            filter.limit.unwrap_or(DEFAULT_DECISION_LIMIT),
+            filter.offset
+        );
+
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(&sql).map_err(db_error)?;
+        let records = statement
+            .query_map(params_from_iter(values.iter()), decision_from_row)
+            .map_err(db_error)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_error)?;
+        records.into_iter().map(|record| with_transitions(&conn, record)).collect()
+    }
+
+    /// Store findings from a scan. A finding with the same URL or title as a stored
+    /// one, or near-identical text, is merged into it; returns for each finding the
+    /// id of the stored finding it duplicates, None for new ones.
+    pub fn store_findings(
+        &self,
+        findings: &[ResearchFinding],
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Option<String>>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let size = findings.iter().map(|f| f.title.len() + f.summary.len() + f.metadata.to_string().len()).sum();
+        self.check_quota(&conn, size)?;
+
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        let mut duplicates = Vec::with_capacity(findings.len());
+        for finding in findings {
+            let keys = FindingKeys::of(finding);
+            let duplicate = match find_duplicate(&transaction, &finding.id, &keys)? {
+                Some(id) => {
+                    transaction
+                        .execute(
+                            "UPDATE findings SET last_seen = ?2, times_seen = times_seen + 1,
+                             relevance_score = MAX(relevance_score, ?3), archived = 0 WHERE id = ?1",
+                            params![id, timestamp(now), finding.relevance_score],
+                        )
+                        .map_err(db_error)?;
+                    Some(id)
+                }
+                None => {
+                    insert_finding(&transaction, finding, &keys, now)?;
+                    None
+                }
+            };
+            duplicates.push(duplicate);
+        }
+        transaction.commit().map_err(db_error)?;
+        Ok(duplicates)
+    }
+
+    pub fn get_finding(&self, id: &str) -> Result<Option<StoredFinding>, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            &format!("SELECT {} FROM findings WHERE id = ?1", FINDING_COLUMNS),
+            [id],
+            finding_from_row,
+        )
+        .optional()
+        .map_err(db_error)
+    }
+
+    /// Replace a stored finding's content, such as a new score or highlights,
+    /// keeping when and how often it was seen; false if it is not stored
+    pub fn update_finding(&self, finding: &ResearchFinding) -> Result<bool, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        let updated = transaction
+            .execute(
+                "UPDATE findings SET finding = ?2, relevance_score = ?3, tags = ?4 WHERE id = ?1",
+                params![finding.id, json_text(finding), finding.relevance_score, json_text(&finding.tags)],
+            )
+            .map_err(db_error)?;
+        transaction
+            .execute(
+                "UPDATE findings_fts SET tags = ?2 WHERE id = ?1",
+                params![finding.id, finding.tags.join(" ")],
+            )
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)?;
+        Ok(updated > 0)
+    }
+
+    /// Stored findings matching `filter` whose title, summary or tags contain every
+    /// word of `query`, best match first; an empty query returns the most recently seen
+    pub fn search_findings(&self, query: &str, filter: &FindingFilter) -> Result<Vec<StoredFinding>, StorageError> {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        let mut condition = |sql: &str, value: String| {
+            values.push(value);
+            conditions.push(sql.replace('?', &format!("?{}", values.len())));
+        };
+
+        let match_query = fts_query(query);
+        if let Some(match_query) = &match_query {
+            condition("findings_fts MATCH ?", match_query.clone());
+        }
+        if !filter.sources.is_empty() {
+            let sources: Vec<String> = filter.sources.iter().map(json_text).collect();
+            condition("f.source IN (SELECT value FROM json_each(?))", json_text(&sources));
+        }
+        if let Some(min_relevance) = filter.min_relevance {
+            condition("f.relevance_score >= ?", min_relevance.to_string());
+        }
+        if let Some(since) = filter.since {
+            condition("f.first_seen >= ?", timestamp(since));
+        }
+        if let Some(until) = filter.until {
+            condition("f.first_seen < ?", timestamp(until));
+        }
+        if let Some(tag) = &filter.tag {
+            condition("EXISTS (SELECT 1 FROM json_each(f.tags) WHERE value = ?)", tag.clone());
+        }
+        if !filter.include_archived {
+            conditions.push("f.archived = 0".to_string());
+        }
+
+        let columns = FINDING_COLUMNS
+            .split(", ")
+            .map(|column| format!("f.{}", column))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!(
+            "SELECT {} FROM findings f {} {} ORDER BY {} LIMIT {} OFFSET {}",
+            columns,
+            if match_query.is_some() { "JOIN findings_fts ON findings_fts.id = f.id" } else { "" },
+            if conditions.is_empty() { String::new() } else { format!("WHERE {}", conditions.join(" AND ")) },
+            if match_query.is_some() { "bm25(findings_fts), f.last_seen DESC" } else { "f.last_seen DESC" },
+            filter.limit.unwrap_or(DEFAULT_FINDING_LIMIT),
+            filter.offset
+        );
+
+        let conn = self.conn.lock().unwrap();
+        let mut statement = conn.prepare(&sql).map_err(db_error)?;
+        let rows = statement
+            .query_map(params_from_iter(values.iter()), finding_from_row)
+            .map_err(db_error)?;
+        rows.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    /// Archive findings no scan has returned for a while and delete long-archived
+    /// ones; returns how many were archived and deleted
+    pub fn apply_finding_retention(
+        &self,
+        retention: &FindingRetention,
+        now: DateTime<Utc>,
+    ) -> Result<(usize, usize), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        let mut deleted = 0;
+        if let Some(before) = retention.delete_before(now) {
+            transaction
+                .execute(
+                    "DELETE FROM findings_fts WHERE id IN (SELECT id FROM findings WHERE last_seen < ?1)",
+                    [timestamp(before)],
+                )
+                .map_err(db_error)?;
+            deleted = transaction
+                .execute("DELETE FROM findings WHERE last_seen < ?1", [timestamp(before)])
+                .map_err(db_error)?;
+        }
+        let archived = transaction
+            .execute(
+                "UPDATE findings SET archived = 1 WHERE archived = 0 AND last_seen < ?1",
+                [timestamp(retention.archive_before(now))],
+            )
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)?;
+        Ok((archived, deleted))
+    }
+
+    /// Keyword mentions and reported trends recorded by earlier scans
+    pub fn load_keyword_history(&self) -> Result<KeywordHistory, StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let mut history = KeywordHistory {
+            since: conn
+                .query_row("SELECT since FROM keyword_history WHERE id = 0", [], |row| time_column(row, 0))
+                .optional()
+                .map_err(db_error)?,
+            ..Default::default()
+        };
+
+        let mut statement = conn
+            .prepare("SELECT keyword, seen_at FROM keyword_mentions ORDER BY keyword, seen_at")
+            .map_err(db_error)?;
+        let mentions = statement
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, time_column(row, 1)?)))
+            .map_err(db_error)?;
+        for mention in mentions {
+            let (keyword, seen_at) = mention.map_err(db_error)?;
+            history.mentions.entry(keyword).or_default().push_back(seen_at);
+        }
+
+        let mut statement = conn.prepare("SELECT keyword, reported_at FROM keyword_trends").map_err(db_error)?;
+        let reported = statement
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, time_column(row, 1)?)))
+            .map_err(db_error)?;
+        history.reported = reported.collect::<Result<_, _>>().map_err(db_error)?;
+        Ok(history)
+    }
+
+    /// Replace the stored keyword history; the history prunes itself to the
+    /// baseline period, so this stays small
+    pub fn save_keyword_history(&self, history: &KeywordHistory) -> Result<(), StorageError> {
+        let conn = self.conn.lock().unwrap();
+        let size: usize = history
+            .mentions
+            .iter()
+            .map(|(keyword, times)| (keyword.len() + 32) * times.len())
+            .sum();
+        self.check_quota(&conn, size)?;
+
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        transaction
+            .execute_batch("DELETE FROM keyword_mentions; DELETE FROM keyword_trends; DELETE FROM keyword_history;")
+            .map_err(db_error)?;
+        if let Some(since) = history.since {
+            transaction
+                .execute("INSERT INTO keyword_history (id, since) VALUES (0, ?1)", [timestamp(since)])
+                .map_err(db_error)?;
+        }
+        {
+            let mut insert = transaction
+                .prepare("INSERT INTO keyword_mentions (keyword, seen_at) VALUES (?1, ?2)")
+                .map_err(db_error)?;
+            for (keyword, times) in &history.mentions {
+                for time in times {
+                    insert.execute(params![keyword, timestamp(*time)]).map_err(db_error)?;
+                }
+            }
+            let mut insert = transaction
+                .prepare("INSERT INTO keyword_trends (keyword, reported_at) VALUES (?1, ?2)")
+                .map_err(db_error)?;
+            for (keyword, time) in &history.reported {
+                insert.execute(params![keyword, timestamp(*time)]).map_err(db_error)?;
+            }
+        }
+        transaction.commit().map_err(db_error)
+    }
+
+    /// Refuse a write of about `adding` bytes that would take the database over quota
+    fn check_quota(&self, conn: &Connection, adding: usize) -> Result<(), StorageError> {
+        let limit_mb = self.quota_mb.load(Ordering::Relaxed) as u64;
+        let used: i64 = conn
+            .query_row(
+                "SELECT (p.page_count - f.freelist_count) * s.page_size
+                 FROM pragma_page_count() p, pragma_freelist_count() f, pragma_page_size() s",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        let used = used.max(0) as u64;
+        if used + adding as u64 > limit_mb * BYTES_PER_MB {
+            return Err(StorageError::QuotaExceeded {
+                used_mb: used / BYTES_PER_MB,
+                limit_mb,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Check a backup and stage it to replace the database at `path` on the next start;
+/// the open database cannot be swapped while it is in use
+pub(super) fn stage_restore(path: &Path, backup: &Path) -> Result<(), StorageError> {
+    let conn = Connection::open_with_flags(backup, rusqlite::OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(db_error)?;
+    integrity_check(&conn)?;
+    drop(conn);
+    fs::copy(backup, journal_file(path, ".restore")).map_err(|e| StorageError::WriteError {
+        message: format!("{}: {}", backup.display(), e),
+    })?;
+    Ok(())
+}
+
+/// Move a damaged database aside together with its write-ahead log, which
+/// belongs to it; returns where it went
+fn quarantine_database(path: &Path) -> Option<String> {
+    let target = quarantine(path)?;
+    for suffix in ["-wal", "-shm"] {
+        let journal = journal_file(path, suffix);
+        if journal.exists() {
+            if let Err(e) = fs::rename(&journal, journal_file(Path::new(&target), suffix)) {
+                log::warn!("Could not move {:?} aside: {}", journal, e);
+            }
+        }
+    }
+    Some(target)
+}
+
+/// Swap in a backup staged by `stage_restore`
+fn apply_staged_restore(path: &Path) {
+    let staged = journal_file(path, ".restore");
+    if !staged.exists() {
+        return;
+    }
+    for suffix in ["-wal", "-shm"] {
+        let _ = fs::remove_file(journal_file(path, suffix));
+    }
+    match fs::rename(&staged, path) {
+        Ok(()) => log::info!("Database restored from backup"),
+        Err(e) => log::warn!("Could not restore database from backup: {}", e),
+    }
+}
+
+/// Open, check and migrate the database at `path`
+fn open_checked(path: &Path) -> Result<Connection, StorageError> {
+    let conn = Connection::open(path).map_err(db_error)?;
+    let mode: String = conn
+        .pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get(0))
+        .map_err(db_error)?;
+    if !mode.eq_ignore_ascii_case("wal") {
+        log::warn!("Database is in {} mode instead of WAL", mode);
+    }
+    conn.pragma_update(None, "synchronous", "NORMAL").map_err(db_error)?;
+    integrity_check(&conn)?;
+    migrate(&conn)?;
+    Ok(conn)
+}
+
+fn integrity_check(conn: &Connection) -> Result<(), StorageError> {
+    // The full check, not quick_check: it also verifies that every index matches
+    // its table, and it only runs at startup and before a restore
+    let result: String = conn
+        .pragma_query_value(None, "integrity_check", |row| row.get(0))
+        .map_err(db_error)?;
+    if result == "ok" {
+        Ok(())
+    } else {
+        Err(StorageError::CorruptedData { message: result })
+    }
+}
+
+/// Apply the migrations the database has not had yet
+fn migrate(conn: &Connection) -> Result<(), StorageError> {
+    let version: usize = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(db_error)?;
+    if version > MIGRATIONS.len() {
+        return Err(StorageError::DatabaseError {
+            message: format!("Databasen er fra en nyere version af CLA (skema {})", version),
+        });
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let transaction = conn.unchecked_transaction().map_err(db_error)?;
+        transaction.execute_batch(sql).map_err(db_error)?;
+        transaction
+            .pragma_update(None, "user_version", index + 1)
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)?;
+        log::info!("Database migrated to schema {}", index + 1);
+    }
+    Ok(())
+}
+
+fn upsert_memory(conn: &Connection, memory: &LocalMemory) -> Result<(), StorageError> {
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO memories ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            MEMORY_COLUMNS
+        ),
+        params![
+            memory.id.to_string(),
+            memory.content,
+            memory.memory_type,
+            serde_json::to_string(&memory.topics).unwrap_or_else(|_| "[]".to_string()),
+            memory.embedding_local.as_deref().map(embedding_to_blob),
+            memory.importance,
+            timestamp(memory.created_at),
+            timestamp(memory.updated_at),
+            memory.synced_at.map(timestamp),
+            memory.cloud_id,
+            memory.pending_sync,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn upsert_session(conn: &Connection, session: &LocalSession) -> Result<(), StorageError> {
+    let messages = serde_json::to_string(&session.messages).map_err(|e| StorageError::WriteError {
+        message: e.to_string(),
+    })?;
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO sessions ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            SESSION_COLUMNS
+        ),
+        params![
+            session.id.to_string(),
+            session.session_type,
+            session.context.to_string(),
+            messages,
+            timestamp(session.created_at),
+            timestamp(session.updated_at),
+            session.synced_at.map(timestamp),
+            session.cloud_id,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+fn upsert_task(conn: &Connection, task: &PendingTask) -> Result<(), StorageError> {
+    let (status, error) = match &task.status {
+        TaskStatus::Queued => ("queued", None),
+        TaskStatus::Running => ("running", None),
+        TaskStatus::Completed => ("completed", None),
+        TaskStatus::Failed { error } => ("failed", Some(error.as_str())),
+        TaskStatus::Cancelled => ("cancelled", None),
+    };
+    conn.execute(
+        &format!(
+            "INSERT OR REPLACE INTO tasks ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            TASK_COLUMNS
+        ),
+        params![
+            task.id.to_string(),
+            task_type_text(&task.task_type),
+            task.priority,
+            task.payload.to_string(),
+            timestamp(task.created_at),
+            task.retry_count,
+            task.max_retries,
+            status,
+            error,
+            task.not_before.map(timestamp),
+            task.last_error,
+        ],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// The stored finding `keys` match: same id, URL or title first, then the
+/// closest near-identical text
+fn find_duplicate(conn: &Connection, id: &str, keys: &FindingKeys) -> Result<Option<String>, StorageError> {
+    let exact = conn
+        .query_row(
+            "SELECT id FROM findings WHERE id = ?1 OR canonical_url = ?2 OR title_hash = ?3 LIMIT 1",
+            params![id, keys.canonical_url, keys.title_hash.map(|hash| hash as i64)],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(db_error)?;
+    if exact.is_some() {
+        return Ok(exact);
+    }
+
+    let Some(hash) = keys.simhash else {
+        return Ok(None);
+    };
+    let bands = simhash_bands(hash);
+    let mut statement = conn
+        .prepare(
+            "SELECT id, simhash FROM findings WHERE simhash_band0 = ?1 OR simhash_band1 = ?2
+             OR simhash_band2 = ?3 OR simhash_band3 = ?4",
+        )
+        .map_err(db_error)?;
+    let candidates = statement
+        .query_map(params![bands[0], bands[1], bands[2], bands[3]], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as u64))
+        })
+        .map_err(db_error)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_error)?;
+    Ok(candidates
+        .into_iter()
+        .map(|(id, other)| (simhash_distance(hash, other), id))
+        .filter(|(distance, _)| *distance <= MAX_SIMHASH_DISTANCE)
+        .min()
+        .map(|(_, id)| id))
+}
+
+fn insert_finding(
+    conn: &Connection,
+    finding: &ResearchFinding,
+    keys: &FindingKeys,
+    now: DateTime<Utc>,
+) -> Result<(), StorageError> {
+    let bands = keys.simhash.map(simhash_bands);
+    conn.execute(
+        "INSERT INTO findings (id, source, finding, relevance_score, tags, canonical_url, title_hash, simhash,
+         simhash_band0, simhash_band1, simhash_band2, simhash_band3, first_seen, last_seen, times_seen, archived)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?13, 1, 0)",
+        params![
+            finding.id,
+            json_text(&finding.source),
+            json_text(finding),
+            finding.relevance_score,
+            json_text(&finding.tags),
+            keys.canonical_url,
+            keys.title_hash.map(|hash| hash as i64),
+            keys.simhash.map(|hash| hash as i64),
+            bands.map(|b| b[0]),
+            bands.map(|b| b[1]),
+            bands.map(|b| b[2]),
+            bands.map(|b| b[3]),
+            timestamp(now),
+        ],
+    )
+    .map_err(db_error)?;
+    conn.execute(
+        "INSERT INTO findings_fts (id, title, summary, tags) VALUES (?1, ?2, ?3, ?4)",
+        params![finding.id, finding.title, finding.summary, finding.tags.join(" ")],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// FTS5 query matching every word of `query`, the last one as a prefix so
+/// results follow typing; None for a query without words
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    let last = words.last()?;
+    Some(format!("{} {}*", words[..words.len() - 1].join(" "), last).trim_start().to_string())
+}
+
+fn insert_transition(
+    conn: &Connection,
+    decision_id: &str,
+    state: ApprovalState,
+    reason: Option<&str>,
+) -> Result<(), StorageError> {
+    conn.execute(
+        "INSERT INTO decision_transitions (decision_id, state, reason, at) VALUES (?1, ?2, ?3, ?4)",
+        params![decision_id, json_text(&state), reason, timestamp(Utc::now())],
+    )
+    .map_err(db_error)?;
+    Ok(())
+}
+
+/// Fill in the approval history of a decision read without it
+fn with_transitions(conn: &Connection, mut record: DecisionRecord) -> Result<DecisionRecord, StorageError> {
+    let mut statement = conn
+        .prepare_cached(
+            "SELECT state, reason, at FROM decision_transitions WHERE decision_id = ?1 ORDER BY at, rowid",
+        )
+        .map_err(db_error)?;
+    let transitions = statement
+        .query_map([&record.decision.id], |row| {
+            Ok(ApprovalTransition {
+                state: json_column(row, 0)?,
+                reason: row.get(1)?,
+                at: time_column(row, 2)?,
+            })
+        })
+        .map_err(db_error)?;
+    record.transitions = transitions.collect::<Result<_, _>>().map_err(db_error)?;
+    Ok(record)
+}
+
+fn decision_from_row(row: &Row) -> rusqlite::Result<DecisionRecord> {
+    let signal = match row.get::<_, Option<String>>(2)? {
+        Some(_) => Some(json_column(row, 2)?),
+        None => None,
+    };
+    Ok(DecisionRecord {
+        decision: Decision {
+            id: row.get(0)?,
+            signal_type: row.get(1)?,
+            action: json_column(row, 3)?,
+            confidence: row.get(4)?,
+            rationale: row.get(5)?,
+            timestamp: time_column(row, 6)?,
+            requires_approval: row.get(7)?,
+            signal,
+        },
+        state: json_column(row, 8)?,
+        transitions: Vec::new(),
+    })
+}
+
+fn finding_from_row(row: &Row) -> rusqlite::Result<StoredFinding> {
+    Ok(StoredFinding {
+        finding: json_column(row, 0)?,
+        first_seen: time_column(row, 1)?,
+        last_seen: time_column(row, 2)?,
+        times_seen: row.get(3)?,
+        archived: row.get(4)?,
+    })
+}
+
+fn memory_from_row(row: &Row) -> rusqlite::Result<LocalMemory> {
+    Ok(LocalMemory {
+        id: uuid_column(row, 0)?,
+        content: row.get(1)?,
+        memory_type: row.get(2)?,
+        topics: json_column(row, 3)?,
+        embedding_local: row.get::<_, Option<Vec<u8>>>(4)?.map(|blob| blob_to_embedding(&blob)),
+        importance: row.get(5)?,
+        created_at: time_column(row, 6)?,
+        updated_at: time_column(row, 7)?,
+        synced_at: optional_time_column(row, 8)?,
+        cloud_id: row.get(9)?,
+        pending_sync: row.get(10)?,
+    })
+}
+
+fn session_from_row(row: &Row) -> rusqlite::Result<LocalSession> {
+    Ok(LocalSession {
+        id: uuid_column(row, 0)?,
+        session_type: row.get(1)?,
+        context: json_column(row, 2)?,
+        messages: json_column(row, 3)?,
+        created_at: time_column(row, 4)?,
+        updated_at: time_column(row, 5)?,
+        synced_at: optional_time_column(row, 6)?,
+        cloud_id: row.get(7)?,
+    })
+}
+
+fn task_from_row(row: &Row) -> rusqlite::Result<PendingTask> {
+    let status = match row.get::<_, String>(7)?.as_str() {
+        "queued" => TaskStatus::Queued,
+        "running" => TaskStatus::Running,
+        "completed" => TaskStatus::Completed,
+        "failed" => TaskStatus::Failed {
+            error: row.get::<_, Option<String>>(8)?.unwrap_or_default(),
+        },
+        "cancelled" => TaskStatus::Cancelled,
+        other => {
+            return Err(rusqlite::Error::FromSqlConversionFailure(
+                7,
+                rusqlite::types::Type::Text,
+                format!("unknown task status {}", other).into(),
+            ))
+        }
+    };
+    Ok(PendingTask {
+        id: uuid_column(row, 0)?,
+        task_type: json_column(row, 1)?,
+        priority: row.get(2)?,
+        payload: json_column(row, 3)?,
+        created_at: time_column(row, 4)?,
+        retry_count: row.get(5)?,
+        max_retries: row.get(6)?,
+        status,
+        not_before: optional_time_column(row, 9)?,
+        last_error: row.get(10)?,
+    })
+}
+
+/// Task type as stored, the JSON string of the enum variant
+fn task_type_text(task_type: &TaskType) -> String {
+    serde_json::to_string(task_type).unwrap_or_default()
+}
+
+/// An enum or other value as stored, its JSON text
+fn json_text<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Fixed-width UTC so text order is time order; full precision so versions compare equal
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+fn conversion_error(index: usize, error: impl std::error::Error + Send + Sync + 'static) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(error))
+}
+
+fn uuid_column(row: &Row, index: usize) -> rusqlite::Result<Uuid> {
+    Uuid::parse_str(&row.get::<_, String>(index)?).map_err(|e| conversion_error(index, e))
+}
+
+fn time_column(row: &Row, index: usize) -> rusqlite::Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(&row.get::<_, String>(index)?)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|e| conversion_error(index, e))
+}
+
+fn optional_time_column(row: &Row, index: usize) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    match row.get::<_, Option<String>>(index)? {
+        Some(_) => time_column(row, index).map(Some),
+        None => Ok(None),
+    }
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    serde_json::from_str(&row.get::<_, String>(index)?).map_err(|e| conversion_error(index, e))
+}
+
+fn embedding_to_blob(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn blob_to_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+/// Make `%`, `_` and `\` match literally in a LIKE pattern
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// `<db>-wal`, `<db>-shm` or `<db>.restore` next to the database
+fn journal_file(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+fn finish_import(dir: &Path, imported: usize, found: usize) {
+    log::info!("Imported {} of {} records from {:?} into the database", imported, found, dir);
+    if imported < found {
+        // Keep the directory so the remaining records can be imported next time
+        return;
+    }
+    let mut done = dir.as_os_str().to_os_string();
+    done.push(".imported");
+    if let Err(e) = fs::rename(dir, &done) {
+        log::warn!("Could not rename imported directory {:?}: {}", dir, e);
+    }
+}
+
+fn db_error(error: rusqlite::Error) -> StorageError {
+    match error.sqlite_error_code() {
+        Some(ErrorCode::DatabaseCorrupt | ErrorCode::NotADatabase) => StorageError::CorruptedData {
+            message: error.to_string(),
+        },
+        _ => StorageError::DatabaseError {
+            message: error.to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commander::{Action, Signal};
+    use crate::memory::new_memory;
+
+    fn temp_db(quota_mb: u32) -> (tempfile::TempDir, LocalDatabase) {
+        let dir = tempfile::tempdir().unwrap();
+        let db = LocalDatabase::open(&dir.path().join("local.db"), quota_mb).unwrap();
+        (dir, db)
+    }
+
+    #[test]
+    fn test_memory_round_trip_and_search() {
+        let (_dir, db) = temp_db(100);
+        let mut budget = new_memory("Møde om budget".to_string(), "note", vec!["økonomi".to_string()], 0.8);
+        budget.embedding_local = Some(vec![0.25, -1.0, 3.5]);
+        let lunch = new_memory("Frokost med 100% hygge".to_string(), "note", vec!["social".to_string()], 0.2);
+        db.save_memory(&budget).unwrap();
+        db.save_memory(&lunch).unwrap();
+
+        let loaded = db.get_memory(budget.id).unwrap().unwrap();
+        assert_eq!(loaded.content, budget.content);
+        assert_eq!(loaded.embedding_local, budget.embedding_local);
+        assert_eq!(loaded.updated_at, budget.updated_at);
+        assert_eq!(db.list_memories(None, 0).unwrap().len(), 2);
+
+        let found = db.search_memories("møde økonomi", 10).unwrap();
+        assert_eq!(found.iter().map(|m| m.id).collect::<Vec<_>>(), vec![budget.id]);
+        assert_eq!(db.search_memories("100%", 10).unwrap().len(), 1);
+        assert!(db.search_memories("50%", 10).unwrap().is_empty());
+
+        assert!(db.delete_memory(lunch.id).unwrap());
+        assert!(db.get_memory(lunch.id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_quota_refuses_writes() {
+        let (_dir, db) = temp_db(0);
+        let memory = new_memory("For stor".to_string(), "note", Vec::new(), 0.5);
+        assert!(matches!(db.save_memory(&memory), Err(StorageError::QuotaExceeded { limit_mb: 0, .. })));
+
+        db.set_quota_mb(10);
+        db.save_memory(&memory).unwrap();
+    }
+
+    #[test]
+    fn test_decision_log_filters_and_transitions() {
+        let (_dir, db) = temp_db(0);
+        let decision = |id: &str, action: Action, minutes_ago: i64| Decision {
+            id: id.to_string(),
+            signal_type: "research".to_string(),
+            action,
+            confidence: 0.7,
+            rationale: "Relevant for Cirkelline".to_string(),
+            timestamp: Utc::now() - chrono::Duration::minutes(minutes_ago),
+            requires_approval: false,
+            signal: Some(Signal::ResearchPublished {
+                title: "Agents".to_string(),
+                relevance_score: 0.7,
+                domain: "ai".to_string(),
+            }),
+        };
+        // The audit log is written even when the quota is used up
+        db.record_decision(&decision("old", Action::Archive, 60), ApprovalState::AutoExecuted, None)
+            .unwrap();
+        db.record_decision(&decision("new", Action::DeepAnalyze, 1), ApprovalState::Pending, Some("Medium risk"))
+            .unwrap();
+        assert!(db.record_approval_transition("new", ApprovalState::Approved, None).unwrap());
+        assert!(!db.record_approval_transition("missing", ApprovalState::Approved, None).unwrap());
+
+        let all = db.list_decisions(&DecisionFilter::default()).unwrap();
+        assert_eq!(all.iter().map(|r| r.decision.id.as_str()).collect::<Vec<_>>(), vec!["new", "old"]);
+        assert!(matches!(all[0].decision.signal, Some(Signal::ResearchPublished { .. })));
+        assert_eq!(all[0].transitions.len(), 2);
+
+        let approved = DecisionFilter {
+            state: Some(ApprovalState::Approved),
+            ..Default::default()
+        };
+        assert_eq!(db.list_decisions(&approved).unwrap().len(), 1);
+        let recent_archives = DecisionFilter {
+            since: Some(Utc::now() - chrono::Duration::minutes(30)),
+            action: Some(Action::Archive),
+            ..Default::default()
+        };
+        assert!(db.list_decisions(&recent_archives).unwrap().is_empty());
+        assert!(db.get_decision("missing").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_findings_merge_across_scans_and_search() {
+        use crate::commander::ResearchSource;
+
+        let (_dir, db) = temp_db(100);
+        let finding = |id: &str, title: &str, summary: &str, url: Option<&str>| ResearchFinding {
+            id: id.to_string(),
+            source: ResearchSource::ArXiv,
+            title: title.to_string(),
+            summary: summary.to_string(),
+            relevance_score: 0.6,
+            discovered_at: Utc::now(),
+            tags: vec!["agents".to_string()],
+            url: url.map(str::to_string),
+            metadata: serde_json::json!({}),
+            score_breakdown: None,
+            scorer_version: None,
+        };
+        let long_ago = Utc::now() - chrono::Duration::days(120);
+        let first = db
+            .store_findings(
+                &[
+                    finding("a", "Planning with language agents", "Agents plan tool calls", Some("https://arxiv.org/abs/1")),
+                    finding("b", "Kvantitativ analyse af rødgrød", "Fløde og bær", None),
+                ],
+                long_ago,
+            )
+            .unwrap();
+        assert_eq!(first, vec![None, None]);
+
+        // Same paper under a tracking link, and the same title from another mirror
+        let second = db
+            .store_findings(
+                &[
+                    finding("c", "Planning agents", "", Some("http://arxiv.org/abs/1/?utm_source=rss")),
+                    finding("d", "Kvantitativ analyse af rødgrød", "", None),
+                    finding("e", "Retrieval for tables", "", None),
+                ],
+                Utc::now(),
+            )
+            .unwrap();
+        assert_eq!(second, vec![Some("a".to_string()), Some("b".to_string()), None]);
+        let merged = db.get_finding("a").unwrap().unwrap();
+        assert_eq!(merged.times_seen, 2);
+        assert_eq!(merged.first_seen, long_ago);
+
+        let found = db.search_findings("plann", &FindingFilter::default()).unwrap();
+        assert_eq!(found.iter().map(|f| f.finding.id.as_str()).collect::<Vec<_>>(), vec!["a"]);
+        assert_eq!(db.search_findings("RØDGRØD", &FindingFilter::default()).unwrap().len(), 1);
+
+        let mut rescored = merged.finding.clone();
+        rescored.relevance_score = 0.9;
+        rescored.scorer_version = Some(2);
+        assert!(db.update_finding(&rescored).unwrap());
+        let updated = db.get_finding("a").unwrap().unwrap();
+        assert_eq!(updated.finding.scorer_version, Some(2));
+        assert_eq!(updated.times_seen, 2);
+        let relevant = FindingFilter {
+            min_relevance: Some(0.8),
+            ..Default::default()
+        };
+        assert_eq!(db.search_findings("", &relevant).unwrap().len(), 1);
+        assert!(!db.update_finding(&finding("z", "Never stored", "", None)).unwrap());
+        let tagged = FindingFilter {
+            sources: vec![ResearchSource::ArXiv],
+            tag: Some("agents".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(db.search_findings("", &tagged).unwrap().len(), 3);
+        assert!(db.search_findings("\"unbalanced", &FindingFilter::default()).unwrap().is_empty());
+
+        // b was last seen now (merged), a too; only untouched findings age out
+        let retention = FindingRetention {
+            archive_after_days: 1,
+            delete_after_days: Some(30),
+        };
+        assert_eq!(db.apply_finding_retention(&retention, Utc::now() + chrono::Duration::days(2)).unwrap(), (3, 0));
+        assert!(db.search_findings("", &FindingFilter::default()).unwrap().is_empty());
+        let archived = FindingFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+        assert_eq!(db.search_findings("", &archived).unwrap().len(), 3);
+        assert_eq!(db.apply_finding_retention(&retention, Utc::now() + chrono::Duration::days(31)).unwrap(), (0, 3));
+        assert!(db.get_finding("e").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_keyword_history_survives_reopen() {
+        let (dir, db) = temp_db(100);
+        assert_eq!(db.load_keyword_history().unwrap(), KeywordHistory::default());
+
+        let now = Utc::now();
+        let mut history = KeywordHistory {
+            since: Some(now - chrono::Duration::days(3)),
+            ..Default::default()
+        };
+        history.mentions.insert("mcp".to_string(), [now - chrono::Duration::hours(2), now].into());
+        history.mentions.insert("rust".to_string(), [now].into());
+        history.reported.insert("mcp".to_string(), now);
+        db.save_keyword_history(&history).unwrap();
+        drop(db);
+
+        let db = LocalDatabase::open(&dir.path().join("local.db"), 100).unwrap();
+        assert_eq!(db.load_keyword_history().unwrap(), history);
+
+        // Saving replaces what was stored
+        history.mentions.remove("rust");
+        db.save_keyword_history(&history).unwrap();
+        assert_eq!(db.load_keyword_history().unwrap(), history);
+    }
+
+    #[test]
+    fn test_damaged_database_is_replaced() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("local.db");
+        fs::write(&path, b"not a database at all, just some bytes that fill a header").unwrap();
+
+        let db = LocalDatabase::open(&path, 100).unwrap();
+        assert!(db.list_memories(None, 0).unwrap().is_empty());
+        let quarantined = fs::read_dir(dir.path())
+            .unwrap()
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().starts_with("local.db.corrupt-"));
+        assert!(quarantined);
+    }
+
+    #[test]
+    fn test_quarantine_keeps_the_write_ahead_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("local.db");
+        fs::write(&path, b"damaged").unwrap();
+        fs::write(journal_file(&path, "-wal"), b"log of the damaged file").unwrap();
+
+        let target = PathBuf::from(quarantine_database(&path).unwrap());
+        assert!(!path.exists());
+        assert!(!journal_file(&path, "-wal").exists());
+        assert_eq!(fs::read(journal_file(&target, "-wal")).unwrap(), b"log of the damaged file");
+    }
+}
@@ -0,0 +1,401 @@
+// Local Storage - Crash-safe store files, startup integrity checks and guided recovery
+// A write goes to `<file>.wal`, is synced to disk and then renamed over the store;
+// the previous version is kept as `<file>.bak` so a damaged store can be repaired
+
+mod database;
+
+pub use database::LocalDatabase;
+
+use crate::error::StorageError;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Health of every store checked since startup, by name
+static HEALTH: Lazy<Mutex<HashMap<String, (PathBuf, StoreHealth)>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StoreStatus {
+    Healthy,
+    /// Damage was found and repaired automatically
+    Repaired,
+    /// Damage could not be repaired; the store started empty and needs a decision
+    Corrupted,
+}
+
+/// Result of a store's integrity check
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StoreHealth {
+    pub name: String,
+    pub status: StoreStatus,
+    /// What was found and, for corrupted stores, what the user can do
+    pub detail: Option<String>,
+    /// Where the damaged file was moved, so nothing is deleted
+    pub quarantined: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// User's choice for a corrupted store
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum StoreRecovery {
+    /// Install a copy of the store, e.g. from the user's own backup
+    RestoreFile { path: String },
+    /// Keep the new, empty store
+    Reset,
+}
+
+/// A JSON store file written through a write-ahead file
+#[derive(Debug, Clone)]
+pub struct JournaledFile {
+    name: &'static str,
+    path: PathBuf,
+    private: bool,
+}
+
+impl JournaledFile {
+    pub fn new(name: &'static str, path: PathBuf) -> Self {
+        Self {
+            name,
+            path,
+            private: false,
+        }
+    }
+
+    /// Create the file readable by the owner only, e.g. for secrets
+    pub fn private(mut self) -> Self {
+        self.private = true;
+        self
+    }
+
+    /// Load the store at startup. An interrupted write is finished or discarded,
+    /// and a damaged store is restored from its backup when that is intact.
+    /// Unrepairable damage returns `CorruptedData` after moving the file aside.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>, StorageError> {
+        replay_wal::<T>(&self.path);
+
+        let error = match read_json::<T>(&self.path) {
+            Ok(value) => {
+                record(self.name, &self.path, StoreStatus::Healthy, None, None);
+                return Ok(value);
+            }
+            Err(error) => error,
+        };
+
+        let quarantined = quarantine(&self.path);
+        let backup = sibling(&self.path, "bak");
+        if let Ok(Some(value)) = read_json::<T>(&backup) {
+            if fs::copy(&backup, &self.path).is_ok() {
+                log::warn!("Store {} was damaged and has been restored from its backup", self.name);
+                record(
+                    self.name,
+                    &self.path,
+                    StoreStatus::Repaired,
+                    Some("Gendannet fra seneste sikkerhedskopi; de seneste ændringer kan mangle".to_string()),
+                    quarantined,
+                );
+                return Ok(Some(value));
+            }
+        }
+
+        log::error!("Store {} is damaged and has no usable backup: {}", self.name, error);
+        record(
+            self.name,
+            &self.path,
+            StoreStatus::Corrupted,
+            Some(format!(
+                "{}. Vælg en sikkerhedskopi at gendanne fra, eller start forfra med tomme data.",
+                error
+            )),
+            quarantined,
+        );
+        Err(error)
+    }
+
+    /// Replace the store's contents
+    pub fn write(&self, contents: &[u8]) -> Result<(), StorageError> {
+        write_journaled(&self.path, contents, true, self.private)
+    }
+
+    /// Delete the store with its backup, so a one-shot file is not restored later
+    pub fn remove(&self) {
+        for path in [self.path.clone(), sibling(&self.path, "bak")] {
+            if let Err(e) = fs::remove_file(&path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    log::warn!("Could not remove {:?}: {}", path, e);
+                }
+            }
+        }
+    }
+}
+
+/// Startup check of a directory with one JSON record per file: interrupted writes
+/// are finished or discarded and unreadable records are moved to `corrupt/`
+pub fn check_dir<T: DeserializeOwned>(name: &'static str, dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let files: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    for wal in files.iter().filter(|p| p.extension().is_some_and(|e| e == "wal")) {
+        replay_wal::<T>(&wal.with_extension(""));
+    }
+
+    let mut damaged = 0;
+    for path in files.iter().filter(|p| p.extension().is_some_and(|e| e == "json")) {
+        if read_json::<T>(path).is_err() {
+            let corrupt_dir = dir.join("corrupt");
+            let moved = fs::create_dir_all(&corrupt_dir)
+                .and_then(|_| fs::rename(path, corrupt_dir.join(path.file_name().unwrap_or_default())));
+            if let Err(e) = moved {
+                log::warn!("Could not move damaged record {:?}: {}", path, e);
+            }
+            damaged += 1;
+        }
+    }
+
+    if damaged > 0 {
+        log::warn!("{} damaged records in {} moved aside", damaged, name);
+        record(
+            name,
+            dir,
+            StoreStatus::Repaired,
+            Some(format!("{} beskadigede poster blev flyttet til side", damaged)),
+            Some(dir.join("corrupt").display().to_string()),
+        );
+    } else {
+        record(name, dir, StoreStatus::Healthy, None, None);
+    }
+}
+
+/// Every readable record in a directory with one JSON record per file
+pub fn read_records<T: DeserializeOwned>(dir: &Path) -> Vec<T> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| read_json::<T>(&p).ok().flatten())
+        .collect()
+}
+
+/// Health of every store checked since startup
+pub fn health() -> Vec<StoreHealth> {
+    let mut stores: Vec<StoreHealth> = HEALTH.lock().unwrap().values().map(|(_, h)| h.clone()).collect();
+    stores.sort_by(|a, b| a.name.cmp(&b.name));
+    stores
+}
+
+/// Apply the user's choice for a corrupted store. The store must be reloaded
+/// (by restarting) for a restored file to take effect.
+pub fn recover(name: &str, action: StoreRecovery) -> Result<StoreHealth, StorageError> {
+    let (path, health) = HEALTH
+        .lock()
+        .unwrap()
+        .get(name)
+        .cloned()
+        .ok_or_else(|| StorageError::NotFound { key: name.to_string() })?;
+    if health.status != StoreStatus::Corrupted {
+        return Ok(health);
+    }
+
+    let detail = match action {
+        StoreRecovery::RestoreFile { path: source } if name == database::STORE_NAME => {
+            database::stage_restore(&path, Path::new(&source))?;
+            format!("Gendannet fra {}", source)
+        }
+        StoreRecovery::RestoreFile { path: source } => {
+            let source = PathBuf::from(source);
+            read_json::<serde_json::Value>(&source)?.ok_or_else(|| StorageError::NotFound {
+                key: source.display().to_string(),
+            })?;
+            let contents = fs::read(&source).map_err(|e| StorageError::ReadError { message: e.to_string() })?;
+            write_journaled(&path, &contents, false, false)?;
+            format!("Gendannet fra {}", source.display())
+        }
+        StoreRecovery::Reset => "Startet forfra med tomme data".to_string(),
+    };
+    Ok(record(name, &path, StoreStatus::Healthy, Some(detail), health.quarantined))
+}
+
+fn record(
+    name: &str,
+    path: &Path,
+    status: StoreStatus,
+    detail: Option<String>,
+    quarantined: Option<String>,
+) -> StoreHealth {
+    let health = StoreHealth {
+        name: name.to_string(),
+        status,
+        detail,
+        quarantined,
+        checked_at: Utc::now(),
+    };
+    HEALTH
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), (path.to_path_buf(), health.clone()));
+    health
+}
+
+fn write_journaled(path: &Path, contents: &[u8], keep_backup: bool, private: bool) -> Result<(), StorageError> {
+    let write_error = |e: std::io::Error| StorageError::WriteError {
+        message: format!("{}: {}", path.display(), e),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(write_error)?;
+    }
+
+    let wal = sibling(path, "wal");
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    let mut file = options.open(&wal).map_err(write_error)?;
+    file.write_all(contents).map_err(write_error)?;
+    file.sync_all().map_err(write_error)?;
+    drop(file);
+
+    // Checkpoint: keep the current version, then switch to the new one in one step
+    if keep_backup && path.exists() {
+        fs::copy(path, sibling(path, "bak")).map_err(write_error)?;
+    }
+    fs::rename(&wal, path).map_err(write_error)?;
+    sync_dir(path);
+    Ok(())
+}
+
+/// Finish a write that was synced before a crash, or drop one that was torn
+fn replay_wal<T: DeserializeOwned>(path: &Path) {
+    let wal = sibling(path, "wal");
+    if !wal.exists() {
+        return;
+    }
+    let complete = matches!(read_json::<T>(&wal), Ok(Some(_)));
+    let result = if complete {
+        log::info!("Completing interrupted write of {:?}", path);
+        fs::rename(&wal, path)
+    } else {
+        log::warn!("Discarding torn write of {:?}", path);
+        fs::remove_file(&wal)
+    };
+    if let Err(e) = result {
+        log::warn!("Could not replay {:?}: {}", wal, e);
+    }
+}
+
+/// Parse a store file; `Ok(None)` when it does not exist
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, StorageError> {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(StorageError::ReadError { message: e.to_string() }),
+    };
+    serde_json::from_slice(&bytes).map(Some).map_err(|e| StorageError::CorruptedData {
+        message: format!("{}: {}", path.display(), e),
+    })
+}
+
+/// Move a damaged file aside; returns where it went
+fn quarantine(path: &Path) -> Option<String> {
+    if !path.exists() {
+        return None;
+    }
+    let target = sibling(path, &format!("corrupt-{}", Utc::now().format("%Y%m%d%H%M%S")));
+    match fs::rename(path, &target) {
+        Ok(()) => Some(target.display().to_string()),
+        Err(e) => {
+            log::warn!("Could not move damaged store {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// `<file>.<suffix>` next to `path`
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Make the rename itself durable
+fn sync_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_journal_replay_and_repair() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = JournaledFile::new("test_journal", dir.path().join("store.json"));
+        file.write(b"[1]").unwrap();
+        file.write(b"[1,2]").unwrap();
+        assert_eq!(fs::read(dir.path().join("store.json.bak")).unwrap(), b"[1]");
+
+        // A synced write interrupted before the rename is completed
+        fs::write(dir.path().join("store.json.wal"), b"[1,2,3]").unwrap();
+        assert_eq!(file.load::<Vec<u32>>().unwrap(), Some(vec![1, 2, 3]));
+
+        // A torn write is dropped
+        fs::write(dir.path().join("store.json.wal"), b"[1,2,").unwrap();
+        assert_eq!(file.load::<Vec<u32>>().unwrap(), Some(vec![1, 2, 3]));
+        assert!(!dir.path().join("store.json.wal").exists());
+
+        // A damaged store is restored from the backup
+        fs::write(dir.path().join("store.json"), b"{garbage").unwrap();
+        assert_eq!(file.load::<Vec<u32>>().unwrap(), Some(vec![1]));
+        assert_eq!(health_of("test_journal").status, StoreStatus::Repaired);
+    }
+
+    #[test]
+    fn test_corrupted_store_recovery() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = JournaledFile::new("test_corrupted", dir.path().join("store.json"));
+        fs::write(dir.path().join("store.json"), b"{garbage").unwrap();
+
+        let err = file.load::<Vec<u32>>().unwrap_err();
+        assert!(matches!(err, StorageError::CorruptedData { .. }));
+        let health = health_of("test_corrupted");
+        assert_eq!(health.status, StoreStatus::Corrupted);
+        assert!(health.quarantined.is_some());
+
+        // A bad replacement is refused, a good one is installed
+        fs::write(dir.path().join("bad.json"), b"nope").unwrap();
+        let restore = |name: &str| StoreRecovery::RestoreFile {
+            path: dir.path().join(name).display().to_string(),
+        };
+        assert!(recover("test_corrupted", restore("bad.json")).is_err());
+        fs::write(dir.path().join("copy.json"), b"[7]").unwrap();
+        assert_eq!(recover("test_corrupted", restore("copy.json")).unwrap().status, StoreStatus::Healthy);
+        assert_eq!(file.load::<Vec<u32>>().unwrap(), Some(vec![7]));
+    }
+
+    fn health_of(name: &str) -> StoreHealth {
+        health().into_iter().find(|h| h.name == name).unwrap()
+    }
+}
@@ -0,0 +1,422 @@
+// Offline Task Queue - Background work that survives restarts
+// Tasks are kept in the local database, run highest priority first once the
+// resource limits allow, and failed attempts are retried with exponential backoff
+
+use crate::commands::commander::CommanderState;
+use crate::commands::resource::check_execution;
+use crate::error::retry::RetryConfig;
+use crate::error::{ClaError, StorageError};
+use crate::inference::{InferenceLane, WhisperTask};
+use crate::models::{PendingTask, SyncResult, TaskStatus, TaskType};
+use crate::storage::LocalDatabase;
+use crate::utils::Heartbeat;
+use crate::AppState;
+use chrono::Utc;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// How long the worker sleeps when nothing is due
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Longest a single task may run before it is abandoned and retried later
+const MAX_TASK_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// Why an attempt failed
+#[derive(Debug, Clone)]
+pub enum TaskFailure {
+    /// Worth another attempt later (model not loaded, network down)
+    Retry(String),
+    /// Will fail again the same way (bad payload, missing file)
+    Permanent(String),
+}
+
+/// Expected CPU percent, RAM in MB and whether a GPU is required
+fn requirements(task_type: TaskType) -> (u8, u64, bool) {
+    match task_type {
+        TaskType::GenerateEmbedding => (20, 512, false),
+        TaskType::TranscribeAudio => (50, 1024, false),
+        TaskType::ExtractText => (30, 512, false),
+        TaskType::SyncMemory => (5, 64, false),
+        TaskType::PreloadKnowledge => (10, 256, false),
+    }
+}
+
+/// Persistent queue of background tasks
+pub struct TaskQueue {
+    database: Arc<LocalDatabase>,
+    backoff: RetryConfig,
+    /// Wakes the worker when a task is added
+    added: Notify,
+}
+
+impl TaskQueue {
+    /// Queue backed by `database`. Tasks that were running when the app stopped
+    /// are queued again.
+    pub fn new(database: Arc<LocalDatabase>) -> Self {
+        let queue = Self {
+            database,
+            backoff: RetryConfig::gentle(),
+            added: Notify::new(),
+        };
+        queue.requeue_interrupted();
+        queue
+    }
+
+    /// Queue tasks left running by a worker that is gone, after a shutdown or
+    /// a watchdog restart
+    pub fn requeue_interrupted(&self) {
+        match self.database.requeue_running_tasks() {
+            Ok(0) => {}
+            Ok(count) => log::info!("Requeued {} interrupted tasks", count),
+            Err(e) => log::warn!("Could not requeue interrupted tasks: {}", e),
+        }
+    }
+
+    pub fn enqueue(
+        &self,
+        task_type: TaskType,
+        payload: serde_json::Value,
+        priority: u8,
+        max_retries: u8,
+    ) -> Result<PendingTask, StorageError> {
+        let task = PendingTask {
+            id: Uuid::new_v4(),
+            task_type,
+            priority,
+            payload,
+            created_at: Utc::now(),
+            retry_count: 0,
+            max_retries,
+            status: TaskStatus::Queued,
+            not_before: None,
+            last_error: None,
+        };
+        self.database.insert_task(&task)?;
+        self.added.notify_one();
+        Ok(task)
+    }
+
+    pub fn list(&self) -> Result<Vec<PendingTask>, StorageError> {
+        self.database.list_tasks()
+    }
+
+    /// The task to run next, if one is due. Tasks that talk to CKC wait while offline.
+    pub fn next_due(&self, offline: bool) -> Result<Option<PendingTask>, StorageError> {
+        let skip: &[TaskType] = if offline {
+            &[TaskType::SyncMemory, TaskType::PreloadKnowledge]
+        } else {
+            &[]
+        };
+        self.database.next_due_task(Utc::now(), skip)
+    }
+
+    pub fn start(&self, task: &mut PendingTask) -> Result<(), StorageError> {
+        task.status = TaskStatus::Running;
+        self.database.update_task(task)
+    }
+
+    /// Record the outcome of an attempt. A retryable failure is queued again
+    /// after a backoff delay until `max_retries` is used up.
+    pub fn finish(&self, task: &mut PendingTask, outcome: Result<(), TaskFailure>) -> Result<(), StorageError> {
+        match outcome {
+            Ok(()) => {
+                task.status = TaskStatus::Completed;
+                task.not_before = None;
+            }
+            Err(TaskFailure::Retry(error)) if task.retry_count < task.max_retries => {
+                let delay = self.backoff.delay_for_attempt(task.retry_count as u32);
+                task.retry_count += 1;
+                task.status = TaskStatus::Queued;
+                task.not_before = chrono::Duration::from_std(delay).ok().map(|delay| Utc::now() + delay);
+                task.last_error = Some(error);
+            }
+            Err(TaskFailure::Retry(error) | TaskFailure::Permanent(error)) => {
+                task.status = TaskStatus::Failed { error: error.clone() };
+                task.not_before = None;
+                task.last_error = Some(error);
+            }
+        }
+        self.database.update_task(task)
+    }
+
+    /// Cancel a task that has not started yet
+    pub fn cancel(&self, id: Uuid) -> Result<PendingTask, String> {
+        let mut task = self.get(id)?;
+        if task.status != TaskStatus::Queued {
+            return Err("Kun ventende opgaver kan annulleres".to_string());
+        }
+        task.status = TaskStatus::Cancelled;
+        task.not_before = None;
+        self.database.update_task(&task).map_err(|e| ClaError::Storage(e).user_message())?;
+        Ok(task)
+    }
+
+    /// Queue a failed or cancelled task again with a fresh set of retries
+    pub fn retry(&self, id: Uuid) -> Result<PendingTask, String> {
+        let mut task = self.get(id)?;
+        if !matches!(task.status, TaskStatus::Failed { .. } | TaskStatus::Cancelled) {
+            return Err("Kun fejlede eller annullerede opgaver kan prøves igen".to_string());
+        }
+        task.status = TaskStatus::Queued;
+        task.retry_count = 0;
+        task.not_before = None;
+        self.database.update_task(&task).map_err(|e| ClaError::Storage(e).user_message())?;
+        self.added.notify_one();
+        Ok(task)
+    }
+
+    /// Remove completed, failed and cancelled tasks
+    pub fn clear_finished(&self) -> Result<usize, StorageError> {
+        self.database.delete_finished_tasks()
+    }
+
+    /// Wait until a task is added or `timeout` passes
+    async fn wait(&self, timeout: Duration) {
+        let _ = tokio::time::timeout(timeout, self.added.notified()).await;
+    }
+
+    fn get(&self, id: Uuid) -> Result<PendingTask, String> {
+        self.database
+            .get_task(id)
+            .map_err(|e| ClaError::Storage(e).user_message())?
+            .ok_or_else(|| "Opgave ikke fundet".to_string())
+    }
+}
+
+/// Run queued tasks one at a time within the resource limits
+pub async fn start_task_worker(app_handle: tauri::AppHandle, heartbeat: Heartbeat) {
+    // The watchdog restarts this worker by aborting it mid-task; whatever it was
+    // running is queued again instead of staying Running forever
+    if let Some(state) = app_handle.try_state::<AppState>() {
+        state.task_queue.requeue_interrupted();
+    }
+
+    loop {
+        heartbeat.beat(POLL_INTERVAL + MAX_TASK_DURATION);
+        let Some(state) = app_handle.try_state::<AppState>() else {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        };
+
+        let (paused, offline) = {
+            let settings = state.settings.read().await;
+            (settings.paused, settings.offline_mode)
+        };
+        if paused || !state.onboarding.is_ready() {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+
+        let mut task = match state.task_queue.next_due(offline) {
+            Ok(Some(task)) => task,
+            Ok(None) => {
+                state.task_queue.wait(POLL_INTERVAL).await;
+                continue;
+            }
+            Err(e) => {
+                log::warn!("Could not read the task queue: {}", e);
+                tokio::time::sleep(POLL_INTERVAL).await;
+                continue;
+            }
+        };
+
+        // Wait for room within the resource limits before starting
+        let check = {
+            let settings = state.settings.read().await;
+            let metrics = state.resource_monitor.read().await.get_current_metrics();
+            let (cpu, ram_mb, gpu) = requirements(task.task_type);
+            check_execution(&settings, &metrics, cpu, ram_mb, gpu)
+        };
+        if !check.can_execute {
+            log::debug!("Task {} waiting: {}", task.id, check.reason.unwrap_or_default());
+            let wait = check.estimated_wait_seconds.map_or(POLL_INTERVAL, |s| Duration::from_secs(s as u64));
+            tokio::time::sleep(wait.min(POLL_INTERVAL)).await;
+            continue;
+        }
+
+        if let Err(e) = state.task_queue.start(&mut task) {
+            log::warn!("Could not start task {}: {}", task.id, e);
+            tokio::time::sleep(POLL_INTERVAL).await;
+            continue;
+        }
+        let _ = app_handle.emit("task-updated", &task);
+
+        log::info!("Running task {} ({:?})", task.id, task.task_type);
+        let outcome = tokio::time::timeout(MAX_TASK_DURATION, run_task(&app_handle, &state, &task))
+            .await
+            .unwrap_or_else(|_| {
+                Err(TaskFailure::Retry(format!(
+                    "Opgaven tog mere end {} minutter",
+                    MAX_TASK_DURATION.as_secs() / 60
+                )))
+            });
+        if let Err(failure) = &outcome {
+            log::warn!("Task {} failed: {:?}", task.id, failure);
+        }
+        if let Err(e) = state.task_queue.finish(&mut task, outcome) {
+            log::error!("Could not record the result of task {}: {}", task.id, e);
+        }
+        let _ = app_handle.emit("task-updated", &task);
+    }
+}
+
+#[derive(Deserialize)]
+struct MemoryPayload {
+    memory_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct FilePayload {
+    path: String,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+fn payload<T: serde::de::DeserializeOwned>(task: &PendingTask) -> Result<T, TaskFailure> {
+    serde_json::from_value(task.payload.clone())
+        .map_err(|e| TaskFailure::Permanent(format!("Ugyldige opgavedata: {}", e)))
+}
+
+async fn run_task(app_handle: &tauri::AppHandle, state: &AppState, task: &PendingTask) -> Result<(), TaskFailure> {
+    let storage_error = |e: StorageError| TaskFailure::Retry(e.to_string());
+
+    match task.task_type {
+        TaskType::GenerateEmbedding => {
+            let MemoryPayload { memory_id } = payload(task)?;
+            let mut memory = state
+                .database
+                .get_memory(memory_id)
+                .map_err(storage_error)?
+                .ok_or_else(|| TaskFailure::Permanent("Hukommelse ikke fundet".to_string()))?;
+            let engine = state.inference_engine.read().await;
+            let engine = engine.as_ref().ok_or_else(|| TaskFailure::Retry("Inference engine ikke startet".to_string()))?;
+            let embedding = engine
+                .generate_embedding_in(InferenceLane::Background, &memory.content)
+                .await
+                .map_err(TaskFailure::Retry)?;
+            memory.embedding_local = Some(embedding);
+            state.database.save_memory(&memory).map_err(storage_error)
+        }
+        TaskType::TranscribeAudio | TaskType::ExtractText => {
+            let FilePayload { path, language } = payload(task)?;
+            if !std::path::Path::new(&path).exists() {
+                return Err(TaskFailure::Permanent(format!("Filen findes ikke: {}", path)));
+            }
+            let engine = state.inference_engine.read().await;
+            let engine = engine.as_ref().ok_or_else(|| TaskFailure::Retry("Inference engine ikke startet".to_string()))?;
+            let (text, memory_type) = if task.task_type == TaskType::TranscribeAudio {
+                let output = engine
+                    .transcribe_in(InferenceLane::Background, &path, language.as_deref(), WhisperTask::Transcribe)
+                    .await
+                    .map_err(TaskFailure::Retry)?;
+                (output.text, "transcription")
+            } else {
+                let output = engine.extract_text(&path).await.map_err(TaskFailure::Retry)?;
+                (output.text, "document")
+            };
+            if text.trim().is_empty() {
+                return Ok(());
+            }
+            let memory = crate::memory::new_memory(text.trim().to_string(), memory_type, Vec::new(), 0.5);
+            state.database.save_memory(&memory).map_err(storage_error)
+        }
+        TaskType::SyncMemory | TaskType::PreloadKnowledge => {
+            let knowledge = match (task.task_type, app_handle.try_state::<CommanderState>()) {
+                (TaskType::PreloadKnowledge, Some(commander)) => Some(commander.unit.read().await.knowledge()),
+                _ => None,
+            };
+            match crate::commands::sync::perform_sync(state, knowledge).await {
+                SyncResult::Failed { error } => Err(TaskFailure::Retry(error)),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue() -> TaskQueue {
+        let mut queue = TaskQueue::new(Arc::new(LocalDatabase::in_memory(100)));
+        queue.backoff = RetryConfig {
+            initial_delay_ms: 60_000,
+            max_delay_ms: 600_000,
+            jitter_factor: 0.0,
+            ..RetryConfig::default()
+        };
+        queue
+    }
+
+    #[test]
+    fn test_higher_priority_runs_first() {
+        let queue = queue();
+        let low = queue.enqueue(TaskType::GenerateEmbedding, serde_json::json!({}), 1, 3).unwrap();
+        let high = queue.enqueue(TaskType::ExtractText, serde_json::json!({}), 9, 3).unwrap();
+        let sync = queue.enqueue(TaskType::SyncMemory, serde_json::json!({}), 10, 3).unwrap();
+
+        assert_eq!(queue.next_due(false).unwrap().unwrap().id, sync.id);
+        assert_eq!(queue.next_due(true).unwrap().unwrap().id, high.id);
+
+        queue.cancel(high.id).unwrap();
+        assert_eq!(queue.next_due(true).unwrap().unwrap().id, low.id);
+    }
+
+    #[test]
+    fn test_retry_backs_off_until_max_retries() {
+        let queue = queue();
+        let mut task = queue.enqueue(TaskType::GenerateEmbedding, serde_json::json!({}), 5, 1).unwrap();
+
+        queue.start(&mut task).unwrap();
+        queue.finish(&mut task, Err(TaskFailure::Retry("ikke klar".to_string()))).unwrap();
+        assert_eq!(task.status, TaskStatus::Queued);
+        assert_eq!(task.retry_count, 1);
+        assert!(task.not_before.unwrap() > Utc::now() + chrono::Duration::seconds(50));
+        // Not due until the backoff has passed
+        assert!(queue.next_due(false).unwrap().is_none());
+
+        queue.finish(&mut task, Err(TaskFailure::Retry("stadig ikke klar".to_string()))).unwrap();
+        assert_eq!(task.status, TaskStatus::Failed { error: "stadig ikke klar".to_string() });
+
+        let retried = queue.retry(task.id).unwrap();
+        assert_eq!(retried.retry_count, 0);
+        assert_eq!(queue.next_due(false).unwrap().unwrap().id, task.id);
+    }
+
+    #[test]
+    fn test_running_tasks_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("local.db");
+        let id = {
+            let queue = TaskQueue::new(Arc::new(LocalDatabase::open(&path, 100).unwrap()));
+            let mut task = queue
+                .enqueue(TaskType::TranscribeAudio, serde_json::json!({ "path": "/tmp/møde.wav" }), 5, 3)
+                .unwrap();
+            queue.start(&mut task).unwrap();
+            task.id
+        };
+
+        let queue = TaskQueue::new(Arc::new(LocalDatabase::open(&path, 100).unwrap()));
+        let task = queue.next_due(false).unwrap().unwrap();
+        assert_eq!(task.id, id);
+        assert_eq!(task.status, TaskStatus::Queued);
+        assert_eq!(task.payload["path"], "/tmp/møde.wav");
+    }
+
+    #[test]
+    fn test_restarted_worker_requeues_running_tasks() {
+        let queue = queue();
+        let mut task = queue.enqueue(TaskType::ExtractText, serde_json::json!({}), 5, 3).unwrap();
+        queue.start(&mut task).unwrap();
+        assert!(queue.next_due(false).unwrap().is_none());
+
+        // A worker aborted mid-task leaves it Running until the next worker starts
+        queue.requeue_interrupted();
+        let task = queue.next_due(false).unwrap().unwrap();
+        assert_eq!(task.status, TaskStatus::Queued);
+    }
+}
@@ -0,0 +1,246 @@
+// Anonymization pass applied to telemetry reports before upload
+// Buckets continuous values and drops rare combinations (k-anonymity)
+
+use super::{TelemetryEvent, TelemetryReport};
+use std::collections::HashMap;
+
+/// Default minimum occurrences for a categorical combination to be reported
+pub const DEFAULT_K_THRESHOLD: usize = 3;
+
+/// Duration bucket boundaries in milliseconds
+const DURATION_BUCKETS_MS: &[u64] = &[10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000];
+
+/// Uptime bucket boundaries in seconds (1h, 4h, 12h, 1d, 1w)
+const UPTIME_BUCKETS_SECS: &[u64] = &[3_600, 14_400, 43_200, 86_400, 604_800];
+
+/// Round down to the nearest bucket boundary
+fn bucket(value: u64, boundaries: &[u64]) -> u64 {
+    boundaries
+        .iter()
+        .rev()
+        .find(|b| value >= **b)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Bucket a duration in milliseconds
+pub fn bucket_duration_ms(ms: u64) -> u64 {
+    bucket(ms, DURATION_BUCKETS_MS)
+}
+
+/// Bucket an uptime in seconds
+pub fn bucket_uptime_secs(secs: u64) -> u64 {
+    bucket(secs, UPTIME_BUCKETS_SECS)
+}
+
+/// Bucket a byte count to the nearest lower power of two
+pub fn bucket_bytes(bytes: u64) -> u64 {
+    if bytes == 0 {
+        0
+    } else {
+        1 << (63 - bytes.leading_zeros())
+    }
+}
+
+/// Bucket a count to the nearest lower power of two
+pub fn bucket_count(count: u32) -> u32 {
+    bucket_bytes(count as u64) as u32
+}
+
+/// Round a percentage to steps of 10
+pub fn bucket_percent(percent: f32) -> f32 {
+    (percent / 10.0).round() * 10.0
+}
+
+/// Round an SNR to steps of 5dB
+pub fn bucket_snr_db(db: f32) -> f32 {
+    (db / 5.0).round() * 5.0
+}
+
+/// Round a throughput to two significant digits
+pub fn bucket_throughput(value: f32) -> f32 {
+    if value <= 0.0 || !value.is_finite() {
+        return 0.0;
+    }
+    let scale = 10f32.powi(value.log10().floor() as i32 - 1);
+    (value / scale).round() * scale
+}
+
+/// Truncate a version string to major.minor
+pub fn truncate_version(version: &str) -> String {
+    version
+        .split(['.', '-', '+'])
+        .take(2)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Categorical key used for k-anonymity, if the event carries one
+fn categorical_key(event: &TelemetryEvent) -> Option<String> {
+    match event {
+        TelemetryEvent::InferenceCompleted { model_id, task_type, .. } => {
+            Some(format!("inference:{}:{}", model_id, task_type))
+        }
+        TelemetryEvent::Error { error_type, error_code, .. } => {
+            Some(format!("error:{}:{}", error_type, error_code.as_deref().unwrap_or("")))
+        }
+        TelemetryEvent::FeatureUsed { feature, .. } => Some(format!("feature:{}", feature)),
+        _ => None,
+    }
+}
+
+/// Bucket continuous values of a single event
+fn bucket_event(event: &mut TelemetryEvent) {
+    match event {
+        TelemetryEvent::AppStarted { version, .. } => {
+            *version = truncate_version(version);
+        }
+        TelemetryEvent::AppStopped { uptime_seconds, .. } => {
+            *uptime_seconds = bucket_uptime_secs(*uptime_seconds);
+        }
+        TelemetryEvent::InferenceCompleted { duration_ms, .. } => {
+            *duration_ms = bucket_duration_ms(*duration_ms);
+        }
+        TelemetryEvent::SyncCompleted {
+            items_count,
+            bytes_transferred,
+            duration_ms,
+            ..
+        } => {
+            *items_count = bucket_count(*items_count);
+            *bytes_transferred = bucket_bytes(*bytes_transferred);
+            *duration_ms = bucket_duration_ms(*duration_ms);
+        }
+        TelemetryEvent::ResourceSnapshot {
+            avg_cpu_percent,
+            avg_ram_percent,
+            avg_gpu_percent,
+            idle_hours,
+            ..
+        } => {
+            *avg_cpu_percent = bucket_percent(*avg_cpu_percent);
+            *avg_ram_percent = bucket_percent(*avg_ram_percent);
+            *avg_gpu_percent = avg_gpu_percent.map(bucket_percent);
+            *idle_hours = idle_hours.round();
+        }
+        TelemetryEvent::FeatureUsed { count, .. } => {
+            *count = bucket_count(*count);
+        }
+        TelemetryEvent::NoiseSuppression { snr_before_db, snr_after_db, .. } => {
+            *snr_before_db = bucket_snr_db(*snr_before_db);
+            *snr_after_db = bucket_snr_db(*snr_after_db);
+        }
+        TelemetryEvent::HardwareBenchmark {
+            cpu_cores,
+            memory_gb,
+            embeddings_per_sec,
+            whisper_rtf,
+            ocr_pages_per_min,
+            disk_read_mb_s,
+            disk_write_mb_s,
+            ..
+        } => {
+            *cpu_cores = bucket_count(*cpu_cores);
+            *memory_gb = bucket_count(*memory_gb);
+            *embeddings_per_sec = embeddings_per_sec.map(bucket_throughput);
+            *whisper_rtf = whisper_rtf.map(bucket_throughput);
+            *ocr_pages_per_min = ocr_pages_per_min.map(bucket_throughput);
+            *disk_read_mb_s = bucket_throughput(*disk_read_mb_s);
+            *disk_write_mb_s = bucket_throughput(*disk_write_mb_s);
+        }
+        TelemetryEvent::Error { .. } | TelemetryEvent::TaskOverrun { .. } => {}
+    }
+}
+
+/// Anonymize a report in place before upload
+pub fn anonymize_report(report: &mut TelemetryReport, k_threshold: usize) {
+    report.version = truncate_version(&report.version);
+
+    // Count categorical combinations and drop the rare ones
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for key in report.events.iter().filter_map(categorical_key) {
+        *counts.entry(key).or_insert(0) += 1;
+    }
+
+    let before = report.events.len();
+    report.events.retain(|event| {
+        categorical_key(event).is_none_or(|key| counts.get(&key).copied().unwrap_or(0) >= k_threshold)
+    });
+    if report.events.len() < before {
+        log::debug!(
+            "Anonymization dropped {} events below k={}",
+            before - report.events.len(),
+            k_threshold
+        );
+    }
+
+    for event in report.events.iter_mut() {
+        bucket_event(event);
+    }
+
+    // Bucket the aggregated metrics too
+    let metrics = &mut report.metrics;
+    metrics.inference.avg_ms = bucket_duration_ms(metrics.inference.avg_ms as u64) as f64;
+    metrics.sync.avg_ms = bucket_duration_ms(metrics.sync.avg_ms as u64) as f64;
+    metrics.resources.avg_cpu_percent = bucket_percent(metrics.resources.avg_cpu_percent);
+    metrics.resources.avg_ram_percent = bucket_percent(metrics.resources.avg_ram_percent);
+    metrics.resources.max_cpu_percent = bucket_percent(metrics.resources.max_cpu_percent);
+    metrics.resources.max_ram_percent = bucket_percent(metrics.resources.max_ram_percent);
+    metrics.resources.idle_percentage = bucket_percent(metrics.resources.idle_percentage);
+    metrics.errors.retain(|_, count| *count as usize >= k_threshold);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_version() {
+        assert_eq!(truncate_version("1.4.2"), "1.4");
+        assert_eq!(truncate_version("0.1.0-beta.3"), "0.1");
+        assert_eq!(truncate_version("2"), "2");
+    }
+
+    #[test]
+    fn test_buckets() {
+        assert_eq!(bucket_duration_ms(7), 0);
+        assert_eq!(bucket_duration_ms(730), 500);
+        assert_eq!(bucket_uptime_secs(20_000), 14_400);
+        assert_eq!(bucket_bytes(1500), 1024);
+        assert_eq!(bucket_percent(43.0), 40.0);
+        assert_eq!(bucket_throughput(1234.0), 1200.0);
+        assert!((bucket_throughput(0.437) - 0.44).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rare_combinations_dropped() {
+        let event = |feature: &str| TelemetryEvent::FeatureUsed {
+            feature: feature.to_string(),
+            count: 5,
+            timestamp: chrono::Utc::now(),
+        };
+        let mut report = TelemetryReport {
+            schema_version: crate::telemetry::schema::SCHEMA_VERSION,
+            session_id: "s".to_string(),
+            version: "1.2.3".to_string(),
+            platform: "linux".to_string(),
+            timestamp: chrono::Utc::now(),
+            metrics: crate::telemetry::MetricsSummary {
+                inference: Default::default(),
+                sync: Default::default(),
+                resources: Default::default(),
+                errors: Default::default(),
+                network: Default::default(),
+                timestamp: chrono::Utc::now(),
+            },
+            events: vec![event("voice"), event("voice"), event("voice"), event("ocr")],
+            sequence: 0,
+        };
+
+        anonymize_report(&mut report, 3);
+
+        assert_eq!(report.version, "1.2");
+        assert_eq!(report.events.len(), 3);
+        assert!(matches!(report.events[0], TelemetryEvent::FeatureUsed { count: 4, .. }));
+    }
+}
@@ -4,6 +4,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use super::network::{MeteredSend, NetworkSubsystem};
 
 /// Overall health status
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -155,6 +156,7 @@ fn default_components() -> HashMap<String, ComponentHealth> {
     components.insert("inference_engine".to_string(), ComponentHealth::healthy());
     components.insert("sync_service".to_string(), ComponentHealth::healthy());
     components.insert("resource_monitor".to_string(), ComponentHealth::healthy());
+    components.insert("task_queue".to_string(), ComponentHealth::healthy());
 
     components
 }
@@ -240,7 +242,7 @@ impl HealthCheck for SyncHealthCheck {
         match reqwest::Client::new()
             .get(&format!("{}/health", self.endpoint))
             .timeout(std::time::Duration::from_secs(5))
-            .send()
+            .send_metered(NetworkSubsystem::Sync)
             .await
         {
             Ok(resp) if resp.status().is_success() => ComponentHealth::healthy(),
@@ -0,0 +1,319 @@
+// Metrics History - Daily rollups kept on disk for long-term trend reports
+// Local only; never part of telemetry reports
+
+use chrono::{Datelike, Duration, IsoWeek, Local, NaiveDate, Weekday};
+use crate::storage::JournaledFile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Days of rollups kept
+const MAX_DAYS: i64 = 180;
+
+/// Minimum time between writes of the rollup file
+const SAVE_INTERVAL_SECS: u64 = 60;
+
+/// Error types listed as hot-spots in a report
+const MAX_HOTSPOTS: usize = 5;
+
+/// Counters for one local calendar day
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DailyRollup {
+    pub date: NaiveDate,
+    pub resource_samples: u64,
+    pub idle_samples: u64,
+    pub inferences: u64,
+    pub failed_inferences: u64,
+    pub inferences_by_task: HashMap<String, u64>,
+    pub syncs: u64,
+    pub failed_syncs: u64,
+    pub sync_items: u64,
+    pub sync_bytes: u64,
+    pub errors: HashMap<String, u64>,
+}
+
+/// An error type and how often it occurred
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorHotspot {
+    pub error_type: String,
+    pub count: u64,
+}
+
+/// Summary of one ISO week
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WeeklyReport {
+    /// ISO week, e.g. "2026-W42"
+    pub week: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub days_with_data: usize,
+    pub avg_idle_percent: f32,
+    pub inferences: u64,
+    pub failed_inferences: u64,
+    pub inferences_by_task: HashMap<String, u64>,
+    pub syncs: u64,
+    pub failed_syncs: u64,
+    pub sync_items: u64,
+    pub sync_bytes: u64,
+    pub errors: u64,
+    /// Most frequent error types, most frequent first
+    pub error_hotspots: Vec<ErrorHotspot>,
+    /// The report as one paragraph, for display or narration
+    pub summary: String,
+}
+
+/// Persisted daily rollups
+pub struct MetricsHistory {
+    file: Option<JournaledFile>,
+    days: Mutex<BTreeMap<NaiveDate, DailyRollup>>,
+    last_saved: Mutex<Option<Instant>>,
+}
+
+impl MetricsHistory {
+    /// Load rollups stored at `path`
+    pub fn load(path: PathBuf) -> Self {
+        let file = JournaledFile::new("metrics_history", path);
+        // A damaged history is moved aside and reported; start without rollups
+        let days = file
+            .load::<Vec<DailyRollup>>()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|day| (day.date, day))
+            .collect();
+        Self {
+            file: Some(file),
+            days: Mutex::new(days),
+            last_saved: Mutex::new(None),
+        }
+    }
+
+    /// History that is never written to disk
+    pub fn in_memory() -> Self {
+        Self {
+            file: None,
+            days: Mutex::new(BTreeMap::new()),
+            last_saved: Mutex::new(None),
+        }
+    }
+
+    /// Default location next to the other local data
+    pub fn default_path() -> PathBuf {
+        crate::security::device::data_dir().join("metrics_history.json")
+    }
+
+    pub fn record_resource_sample(&self, idle: bool) {
+        self.update(|day| {
+            day.resource_samples += 1;
+            if idle {
+                day.idle_samples += 1;
+            }
+        });
+    }
+
+    pub fn record_inference(&self, task_type: &str, success: bool) {
+        self.update(|day| {
+            day.inferences += 1;
+            if !success {
+                day.failed_inferences += 1;
+            }
+            *day.inferences_by_task.entry(task_type.to_string()).or_insert(0) += 1;
+        });
+    }
+
+    pub fn record_sync(&self, items: u32, bytes: u64, success: bool) {
+        self.update(|day| {
+            day.syncs += 1;
+            if !success {
+                day.failed_syncs += 1;
+            }
+            day.sync_items += items as u64;
+            day.sync_bytes += bytes;
+        });
+    }
+
+    pub fn record_error(&self, error_type: &str) {
+        self.update(|day| *day.errors.entry(error_type.to_string()).or_insert(0) += 1);
+    }
+
+    /// Report for the ISO week containing `day`
+    pub fn weekly_report(&self, day: NaiveDate) -> WeeklyReport {
+        let from = day - Duration::days(day.weekday().num_days_from_monday() as i64);
+        let to = from + Duration::days(6);
+        let days = self.days.lock().unwrap();
+        let week: Vec<&DailyRollup> = days.range(from..=to).map(|(_, d)| d).collect();
+
+        let sum = |f: fn(&DailyRollup) -> u64| week.iter().map(|d| f(d)).sum::<u64>();
+        let samples = sum(|d| d.resource_samples);
+        let mut inferences_by_task = HashMap::new();
+        let mut errors: HashMap<String, u64> = HashMap::new();
+        for day in &week {
+            for (task, count) in &day.inferences_by_task {
+                *inferences_by_task.entry(task.clone()).or_insert(0) += count;
+            }
+            for (error_type, count) in &day.errors {
+                *errors.entry(error_type.clone()).or_insert(0) += count;
+            }
+        }
+        let mut error_hotspots: Vec<ErrorHotspot> = errors
+            .into_iter()
+            .map(|(error_type, count)| ErrorHotspot { error_type, count })
+            .collect();
+        error_hotspots.sort_by(|a, b| b.count.cmp(&a.count).then(a.error_type.cmp(&b.error_type)));
+
+        let mut report = WeeklyReport {
+            week: week_label(day.iso_week()),
+            from,
+            to,
+            days_with_data: week.len(),
+            avg_idle_percent: if samples > 0 {
+                sum(|d| d.idle_samples) as f32 / samples as f32 * 100.0
+            } else {
+                0.0
+            },
+            inferences: sum(|d| d.inferences),
+            failed_inferences: sum(|d| d.failed_inferences),
+            inferences_by_task,
+            syncs: sum(|d| d.syncs),
+            failed_syncs: sum(|d| d.failed_syncs),
+            sync_items: sum(|d| d.sync_items),
+            sync_bytes: sum(|d| d.sync_bytes),
+            errors: error_hotspots.iter().map(|h| h.count).sum(),
+            error_hotspots: error_hotspots.into_iter().take(MAX_HOTSPOTS).collect(),
+            summary: String::new(),
+        };
+        report.summary = summarize(&report);
+        report
+    }
+
+    /// Write the rollups now
+    pub fn save(&self) {
+        let Some(file) = &self.file else {
+            return;
+        };
+        let days: Vec<DailyRollup> = self.days.lock().unwrap().values().cloned().collect();
+        let result = serde_json::to_string(&days)
+            .map_err(|e| e.to_string())
+            .and_then(|json| file.write(json.as_bytes()).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            log::warn!("Could not save metrics history: {}", e);
+        }
+        *self.last_saved.lock().unwrap() = Some(Instant::now());
+    }
+
+    fn update(&self, change: impl FnOnce(&mut DailyRollup)) {
+        let today = Local::now().date_naive();
+        {
+            let mut days = self.days.lock().unwrap();
+            let day = days.entry(today).or_insert_with(|| DailyRollup {
+                date: today,
+                ..Default::default()
+            });
+            change(day);
+            let cutoff = today - Duration::days(MAX_DAYS);
+            days.retain(|date, _| *date > cutoff);
+        }
+
+        let due = self
+            .last_saved
+            .lock()
+            .unwrap()
+            .is_none_or(|at| at.elapsed().as_secs() >= SAVE_INTERVAL_SECS);
+        if due {
+            self.save();
+        }
+    }
+}
+
+/// Parse an ISO week such as "2026-W42" into its Monday
+pub fn parse_week(week: &str) -> Option<NaiveDate> {
+    let (year, number) = week.trim().split_once("-W")?;
+    NaiveDate::from_isoywd_opt(year.parse().ok()?, number.parse().ok()?, Weekday::Mon)
+}
+
+fn week_label(week: IsoWeek) -> String {
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+fn summarize(report: &WeeklyReport) -> String {
+    let week = report.from.iso_week().week();
+    if report.days_with_data == 0 {
+        return format!("Ingen data for uge {}.", week);
+    }
+    let mut summary = format!(
+        "Uge {}: Computeren var ledig {:.0} procent af tiden. CLA kørte {} modelopgaver",
+        week,
+        report.avg_idle_percent,
+        report.inferences
+    );
+    if report.failed_inferences > 0 {
+        summary.push_str(&format!(", heraf {} fejlede", report.failed_inferences));
+    }
+    summary.push_str(&format!(
+        ", og synkroniserede {} gange ({:.1} MB).",
+        report.syncs,
+        report.sync_bytes as f64 / (1024.0 * 1024.0)
+    ));
+    match report.error_hotspots.first() {
+        Some(top) => summary.push_str(&format!(
+            " Der var {} fejl; oftest {} ({} gange).",
+            report.errors, top.error_type, top.count
+        )),
+        None => summary.push_str(" Der var ingen fejl."),
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weekly_report() {
+        let history = MetricsHistory::in_memory();
+        let today = Local::now().date_naive();
+        {
+            // A day in the previous week must not be counted
+            let mut days = history.days.lock().unwrap();
+            let last_week = today - Duration::days(7);
+            days.insert(
+                last_week,
+                DailyRollup {
+                    date: last_week,
+                    inferences: 100,
+                    ..Default::default()
+                },
+            );
+        }
+        history.record_resource_sample(true);
+        history.record_resource_sample(false);
+        history.record_resource_sample(true);
+        history.record_resource_sample(true);
+        history.record_inference("transcription", true);
+        history.record_inference("embedding", false);
+        history.record_sync(3, 2048, true);
+        history.record_error("network");
+        history.record_error("network");
+        history.record_error("disk");
+
+        let report = history.weekly_report(today);
+        assert_eq!(report.days_with_data, 1);
+        assert_eq!(report.avg_idle_percent, 75.0);
+        assert_eq!((report.inferences, report.failed_inferences), (2, 1));
+        assert_eq!(report.inferences_by_task["transcription"], 1);
+        assert_eq!((report.syncs, report.sync_items, report.sync_bytes), (1, 3, 2048));
+        assert_eq!(report.errors, 3);
+        assert_eq!(report.error_hotspots[0], ErrorHotspot { error_type: "network".to_string(), count: 2 });
+        assert!(report.summary.contains("75 procent"));
+    }
+
+    #[test]
+    fn test_parse_week() {
+        assert_eq!(parse_week("2026-W42"), NaiveDate::from_ymd_opt(2026, 10, 12));
+        assert_eq!(week_label(parse_week("2026-W01").unwrap().iso_week()), "2026-W01");
+        assert_eq!(parse_week("2026-42"), None);
+    }
+}
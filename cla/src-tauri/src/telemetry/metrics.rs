@@ -115,6 +115,7 @@ impl MetricsCollector {
             sync: self.get_sync_stats().await,
             resources: self.get_resource_stats().await,
             errors: self.get_error_stats().await,
+            network: super::network::meter().usage(&Default::default()).subsystems,
             timestamp: Utc::now(),
         }
     }
@@ -183,6 +184,9 @@ pub struct MetricsSummary {
     pub sync: LatencyStats,
     pub resources: ResourceStats,
     pub errors: std::collections::HashMap<String, u64>,
+    /// Network traffic per subsystem
+    #[serde(default)]
+    pub network: Vec<super::network::SubsystemUsage>,
     pub timestamp: DateTime<Utc>,
 }
 
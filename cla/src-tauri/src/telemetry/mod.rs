@@ -1,9 +1,13 @@
 // Telemetry module for CLA
 // Privacy-respecting usage analytics and health monitoring
 
+pub mod anonymize;
 pub mod metrics;
 pub mod health;
 pub mod reporter;
+pub mod schema;
+pub mod network;
+pub mod history;
 
 pub use metrics::*;
 pub use health::*;
@@ -27,6 +31,27 @@ pub struct TelemetryConfig {
     pub detailed_metrics: bool,
     /// Maximum events to buffer before sending
     pub max_buffer_size: usize,
+    /// Schema version accepted by the endpoint (None = current)
+    #[serde(default)]
+    pub endpoint_schema_version: Option<u32>,
+    /// Minimum occurrences for a categorical value to be reported
+    #[serde(default = "default_k_threshold")]
+    pub k_anonymity_threshold: usize,
+}
+
+fn default_k_threshold() -> usize {
+    anonymize::DEFAULT_K_THRESHOLD
+}
+
+impl TelemetryConfig {
+    /// Reporting as the user has consented to, sent to their CKC endpoint
+    pub fn from_settings(settings: &crate::models::Settings) -> Self {
+        Self {
+            enabled: settings.telemetry_enabled,
+            endpoint: settings.ckc_endpoint.clone(),
+            ..Self::default()
+        }
+    }
 }
 
 impl Default for TelemetryConfig {
@@ -37,6 +62,8 @@ impl Default for TelemetryConfig {
             report_interval_seconds: 3600, // 1 hour
             detailed_metrics: false,
             max_buffer_size: 1000,
+            endpoint_schema_version: None,
+            k_anonymity_threshold: anonymize::DEFAULT_K_THRESHOLD,
         }
     }
 }
@@ -98,6 +125,33 @@ pub enum TelemetryEvent {
         count: u32,
         timestamp: DateTime<Utc>,
     },
+
+    /// Voice input noise suppression effect
+    NoiseSuppression {
+        snr_before_db: f32,
+        snr_after_db: f32,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// Hardware benchmark result (shared only on request)
+    HardwareBenchmark {
+        cpu_cores: u32,
+        memory_gb: u32,
+        embeddings_per_sec: Option<f32>,
+        whisper_rtf: Option<f32>,
+        ocr_pages_per_min: Option<f32>,
+        disk_read_mb_s: f32,
+        disk_write_mb_s: f32,
+        timestamp: DateTime<Utc>,
+    },
+
+    /// Background work that exceeded its granted duration
+    TaskOverrun {
+        task_kind: String,
+        granted_seconds: u32,
+        action: String, // "aborted" | "rescheduled"
+        timestamp: DateTime<Utc>,
+    },
 }
 
 /// Main telemetry service
@@ -106,6 +160,8 @@ pub struct TelemetryService {
     events: Arc<RwLock<Vec<TelemetryEvent>>>,
     metrics: Arc<RwLock<AggregatedMetrics>>,
     health: Arc<RwLock<HealthStatus>>,
+    /// Daily rollups for weekly reports; kept whether or not reporting is enabled
+    history: history::MetricsHistory,
     session_id: String,
 }
 
@@ -116,10 +172,17 @@ impl TelemetryService {
             events: Arc::new(RwLock::new(Vec::new())),
             metrics: Arc::new(RwLock::new(AggregatedMetrics::default())),
             health: Arc::new(RwLock::new(HealthStatus::default())),
+            history: history::MetricsHistory::in_memory(),
             session_id: generate_session_id(),
         }
     }
 
+    /// Keep daily rollups in `history` instead of memory only
+    pub fn with_history(mut self, history: history::MetricsHistory) -> Self {
+        self.history = history;
+        self
+    }
+
     /// Record a telemetry event
     pub async fn record(&self, event: TelemetryEvent) {
         let config = self.config.read().await;
@@ -127,6 +190,13 @@ impl TelemetryService {
             return;
         }
 
+        // Never buffer events the ingestion pipeline would reject
+        if let Err(e) = schema::validate_event(&event) {
+            log::warn!("Dropping invalid telemetry event: {}", e);
+            self.metrics.write().await.rejected_events += 1;
+            return;
+        }
+
         let mut events = self.events.write().await;
 
         // Buffer management
@@ -163,6 +233,7 @@ impl TelemetryService {
             timestamp: Utc::now(),
         })
         .await;
+        self.history.record_inference(task_type, success);
 
         // Update aggregated metrics
         let mut metrics = self.metrics.write().await;
@@ -191,6 +262,7 @@ impl TelemetryService {
             timestamp: Utc::now(),
         })
         .await;
+        self.history.record_sync(items, bytes, success);
 
         // Update aggregated metrics
         let mut metrics = self.metrics.write().await;
@@ -210,6 +282,7 @@ impl TelemetryService {
             timestamp: Utc::now(),
         })
         .await;
+        self.history.record_error(error_type);
 
         // Update health status
         let mut health = self.health.write().await;
@@ -217,11 +290,70 @@ impl TelemetryService {
         health.last_error = Some(error_type.to_string());
     }
 
+    /// Count a resource monitor sample towards the idle share
+    pub fn record_resource_sample(&self, idle: bool) {
+        self.history.record_resource_sample(idle);
+    }
+
+    /// Summary of the ISO week containing `day`
+    pub fn weekly_report(&self, day: chrono::NaiveDate) -> history::WeeklyReport {
+        self.history.weekly_report(day)
+    }
+
+    /// Record the SNR effect of voice noise suppression
+    pub async fn record_noise_suppression(&self, snr_before_db: f32, snr_after_db: f32) {
+        self.record(TelemetryEvent::NoiseSuppression {
+            snr_before_db,
+            snr_after_db,
+            timestamp: Utc::now(),
+        })
+        .await;
+    }
+
+    /// Record a hardware benchmark result
+    pub async fn record_hardware_benchmark(&self, profile: &crate::inference::HardwareProfile) {
+        self.record(TelemetryEvent::HardwareBenchmark {
+            cpu_cores: profile.cpu_cores,
+            memory_gb: (profile.total_memory_mb / 1024) as u32,
+            embeddings_per_sec: profile.embeddings_per_sec,
+            whisper_rtf: profile.whisper_rtf,
+            ocr_pages_per_min: profile.ocr_pages_per_min,
+            disk_read_mb_s: profile.disk_read_mb_s,
+            disk_write_mb_s: profile.disk_write_mb_s,
+            timestamp: Utc::now(),
+        })
+        .await;
+    }
+
+    /// Record work that overran its time budget
+    pub async fn record_task_overrun(
+        &self,
+        work: crate::utils::timebox::TimeboxedWork,
+        granted: std::time::Duration,
+        action: crate::utils::timebox::OverrunAction,
+    ) {
+        self.record(TelemetryEvent::TaskOverrun {
+            task_kind: work.as_str().to_string(),
+            granted_seconds: granted.as_secs() as u32,
+            action: action.as_str().to_string(),
+            timestamp: Utc::now(),
+        })
+        .await;
+    }
+
     /// Get current health status
     pub async fn get_health(&self) -> HealthStatus {
         self.health.read().await.clone()
     }
 
+    /// Update the health of one component
+    pub async fn update_component_health(&self, name: &str, update: impl FnOnce(&mut ComponentHealth)) {
+        let mut health = self.health.write().await;
+        let mut component = health.get_component(name).cloned().unwrap_or_default();
+        update(&mut component);
+        health.set_component_health(name, component);
+    }
+
     /// Get aggregated metrics
     pub async fn get_metrics(&self) -> AggregatedMetrics {
         self.metrics.read().await.clone()
@@ -274,6 +406,9 @@ pub struct AggregatedMetrics {
     pub total_bytes_transferred: u64,
     pub total_uptime_seconds: u64,
     pub total_idle_seconds: u64,
+    /// Events dropped by schema validation
+    #[serde(default)]
+    pub rejected_events: u64,
 }
 
 impl AggregatedMetrics {
@@ -356,6 +491,19 @@ mod tests {
         assert!((metrics.inference_success_rate() - 0.666).abs() < 0.01);
     }
 
+    #[tokio::test]
+    async fn test_invalid_event_rejected() {
+        let config = TelemetryConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let service = TelemetryService::new(config);
+        service.record_error(&"e".repeat(300), None, true).await;
+
+        assert!(service.get_buffered_events().await.is_empty());
+        assert_eq!(service.get_metrics().await.rejected_events, 1);
+    }
+
     #[test]
     fn test_session_id_uniqueness() {
         let id1 = generate_session_id();
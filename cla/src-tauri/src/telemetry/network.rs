@@ -0,0 +1,230 @@
+// Network accounting - Bytes sent and received per subsystem
+// Requests go through `send_metered`; usage is kept in hourly buckets for 30 days
+
+use crate::activity::ActivityRange;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, DurationRound, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// How long hourly buckets are kept
+const RETENTION_DAYS: i64 = 30;
+
+/// Part of the app that caused network traffic
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkSubsystem {
+    Sync,
+    ModelDownload,
+    Research,
+    Inference,
+    Telemetry,
+}
+
+/// Traffic of one subsystem within a range
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SubsystemUsage {
+    pub subsystem: NetworkSubsystem,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub requests: u64,
+    /// Share of all bytes in the range
+    pub share_percent: f32,
+}
+
+/// Traffic within a range, largest subsystem first
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct NetworkUsage {
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub subsystems: Vec<SubsystemUsage>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    sent: u64,
+    received: u64,
+    requests: u64,
+}
+
+/// Hourly traffic counters per subsystem
+pub struct NetworkMeter {
+    buckets: Mutex<BTreeMap<(DateTime<Utc>, NetworkSubsystem), Counters>>,
+}
+
+impl NetworkMeter {
+    pub fn new() -> Self {
+        Self {
+            buckets: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Count one request
+    pub fn record(&self, subsystem: NetworkSubsystem, sent: u64, received: u64) {
+        self.add(Utc::now(), subsystem, sent, received, 1);
+    }
+
+    /// Count bytes of a request that is already counted (e.g. download chunks)
+    pub fn record_received(&self, subsystem: NetworkSubsystem, received: u64) {
+        self.add(Utc::now(), subsystem, 0, received, 0);
+    }
+
+    fn add(&self, at: DateTime<Utc>, subsystem: NetworkSubsystem, sent: u64, received: u64, requests: u64) {
+        let hour = at.duration_trunc(Duration::hours(1)).unwrap_or(at);
+        let mut buckets = self.buckets.lock().unwrap();
+        let counters = buckets.entry((hour, subsystem)).or_default();
+        counters.sent += sent;
+        counters.received += received;
+        counters.requests += requests;
+
+        let cutoff = hour - Duration::days(RETENTION_DAYS);
+        while buckets.first_key_value().is_some_and(|((bucket, _), _)| *bucket < cutoff) {
+            buckets.pop_first();
+        }
+    }
+
+    /// Usage per subsystem for buckets starting within `range`
+    pub fn usage(&self, range: &ActivityRange) -> NetworkUsage {
+        let mut totals: BTreeMap<NetworkSubsystem, Counters> = BTreeMap::new();
+        for ((hour, subsystem), counters) in self.buckets.lock().unwrap().iter() {
+            if range.contains(*hour) {
+                let total = totals.entry(*subsystem).or_default();
+                total.sent += counters.sent;
+                total.received += counters.received;
+                total.requests += counters.requests;
+            }
+        }
+
+        let bytes_sent: u64 = totals.values().map(|c| c.sent).sum();
+        let bytes_received: u64 = totals.values().map(|c| c.received).sum();
+        let all = (bytes_sent + bytes_received).max(1) as f32;
+        let mut subsystems: Vec<SubsystemUsage> = totals
+            .into_iter()
+            .map(|(subsystem, c)| SubsystemUsage {
+                subsystem,
+                bytes_sent: c.sent,
+                bytes_received: c.received,
+                requests: c.requests,
+                share_percent: (c.sent + c.received) as f32 / all * 100.0,
+            })
+            .collect();
+        subsystems.sort_by_key(|s| std::cmp::Reverse(s.bytes_sent + s.bytes_received));
+
+        NetworkUsage {
+            bytes_sent,
+            bytes_received,
+            subsystems,
+        }
+    }
+}
+
+impl Default for NetworkMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static METER: Lazy<NetworkMeter> = Lazy::new(NetworkMeter::new);
+
+/// Process-wide meter shared by every HTTP client
+pub fn meter() -> &'static NetworkMeter {
+    &METER
+}
+
+/// `send` that counts request and response bytes against a subsystem.
+/// The response body is read up front, so use plain `send` plus
+/// `record_received` for streamed downloads.
+#[async_trait]
+pub trait MeteredSend {
+    async fn send_metered(self, subsystem: NetworkSubsystem) -> reqwest::Result<reqwest::Response>;
+}
+
+#[async_trait]
+impl MeteredSend for reqwest::RequestBuilder {
+    async fn send_metered(self, subsystem: NetworkSubsystem) -> reqwest::Result<reqwest::Response> {
+        let (client, request) = self.build_split();
+        let request = request?;
+        let sent = request_size(&request);
+
+        let response = match client.execute(request).await {
+            Ok(response) => response,
+            Err(e) => {
+                meter().record(subsystem, sent, 0);
+                return Err(e);
+            }
+        };
+
+        let status = response.status();
+        let version = response.version();
+        let headers = response.headers().clone();
+        let header_bytes = headers_size(&headers);
+        let body = match response.bytes().await {
+            Ok(body) => body,
+            Err(e) => {
+                meter().record(subsystem, sent, header_bytes);
+                return Err(e);
+            }
+        };
+        meter().record(subsystem, sent, header_bytes + body.len() as u64);
+
+        let mut buffered = http::Response::new(body);
+        *buffered.status_mut() = status;
+        *buffered.version_mut() = version;
+        *buffered.headers_mut() = headers;
+        Ok(reqwest::Response::from(buffered))
+    }
+}
+
+/// Request line, headers and body
+fn request_size(request: &reqwest::Request) -> u64 {
+    let line = request.method().as_str().len() + request.url().as_str().len() + 12;
+    let body = request.body().and_then(|b| b.as_bytes()).map_or(0, |b| b.len());
+    (line + body) as u64 + headers_size(request.headers())
+}
+
+fn headers_size(headers: &reqwest::header::HeaderMap) -> u64 {
+    headers
+        .iter()
+        .map(|(name, value)| (name.as_str().len() + value.len() + 4) as u64)
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_per_subsystem() {
+        let meter = NetworkMeter::new();
+        meter.record(NetworkSubsystem::Sync, 100, 300);
+        meter.record(NetworkSubsystem::ModelDownload, 50, 0);
+        meter.record_received(NetworkSubsystem::ModelDownload, 1_000);
+        meter.record(NetworkSubsystem::Research, 10, 40);
+
+        let usage = meter.usage(&ActivityRange::default());
+        assert_eq!(usage.bytes_sent, 160);
+        assert_eq!(usage.bytes_received, 1_340);
+        assert_eq!(usage.subsystems[0].subsystem, NetworkSubsystem::ModelDownload);
+        assert_eq!(usage.subsystems[0].requests, 1);
+        assert!((usage.subsystems[0].share_percent - 70.0).abs() < 0.01);
+
+        let future = ActivityRange {
+            from: Some(Utc::now() + Duration::hours(2)),
+            to: None,
+        };
+        assert!(meter.usage(&future).subsystems.is_empty());
+    }
+
+    #[test]
+    fn test_old_buckets_expire() {
+        let meter = NetworkMeter::new();
+        meter.add(Utc::now() - Duration::days(RETENTION_DAYS + 1), NetworkSubsystem::Sync, 10, 10, 1);
+        meter.record(NetworkSubsystem::Telemetry, 1, 1);
+
+        let usage = meter.usage(&ActivityRange::default());
+        assert_eq!(usage.subsystems.len(), 1);
+        assert_eq!(usage.subsystems[0].subsystem, NetworkSubsystem::Telemetry);
+    }
+}
@@ -8,11 +8,18 @@ use tokio::sync::RwLock;
 use tokio::time::{interval, Duration};
 
 use super::{TelemetryConfig, TelemetryEvent, TelemetryService, MetricsSummary};
+use super::anonymize;
+use super::network::{self, MeteredSend, NetworkSubsystem};
+use crate::activity::ActivityRange;
+use super::schema::{self, SCHEMA_VERSION};
 use crate::error::{ClaError, ClaResult};
 
 /// Telemetry report structure
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TelemetryReport {
+    /// Payload schema version (absent in v1 reports)
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: u32,
     /// Anonymous session ID
     pub session_id: String,
     /// CLA version
@@ -29,6 +36,10 @@ pub struct TelemetryReport {
     pub sequence: u64,
 }
 
+fn legacy_schema_version() -> u32 {
+    1
+}
+
 /// Telemetry reporter service
 pub struct TelemetryReporter {
     config: Arc<RwLock<TelemetryConfig>>,
@@ -100,18 +111,19 @@ impl TelemetryReporter {
         let endpoint = config.endpoint.clone().ok_or_else(|| {
             ClaError::Config(crate::error::ConfigError::MissingRequired { key: "telemetry_endpoint".to_string() })
         })?;
+        let target_version = config.endpoint_schema_version.unwrap_or(SCHEMA_VERSION);
+        let k_threshold = config.k_anonymity_threshold;
 
         drop(config);
 
-        // Build report
-        let report = self.build_report().await?;
+        let payload = outgoing_payload(self.build_report().await?, k_threshold, target_version)?;
 
         // Send report
         let response = self
             .http_client
-            .post(&format!("{}/api/cla/telemetry", endpoint))
-            .json(&report)
-            .send()
+            .post(format!("{}/api/cla/telemetry", endpoint))
+            .json(&payload)
+            .send_metered(NetworkSubsystem::Telemetry)
             .await
             .map_err(|e| ClaError::Network(crate::error::NetworkError::ConnectionFailed {
                 url: endpoint.clone(),
@@ -164,10 +176,17 @@ impl TelemetryReporter {
                 idle_percentage: metrics.idle_percentage() as f32,
             },
             errors: std::collections::HashMap::new(),
+            network: network::meter()
+                .usage(&ActivityRange {
+                    from: Some(Utc::now() - chrono::Duration::hours(1)),
+                    to: None,
+                })
+                .subsystems,
             timestamp: Utc::now(),
         };
 
         Ok(TelemetryReport {
+            schema_version: SCHEMA_VERSION,
             session_id: self.telemetry_service.session_id().to_string(),
             version: self.version.clone(),
             platform: std::env::consts::OS.to_string(),
@@ -193,6 +212,23 @@ impl TelemetryReporter {
     }
 }
 
+/// What leaves the machine: the report stripped of fingerprintable detail, then
+/// down-converted for older endpoints
+fn outgoing_payload(
+    mut report: TelemetryReport,
+    k_threshold: usize,
+    target_version: u32,
+) -> ClaResult<serde_json::Value> {
+    anonymize::anonymize_report(&mut report, k_threshold);
+    schema::downconvert_report(&report, target_version).map_err(|e| {
+        ClaError::Config(crate::error::ConfigError::InvalidValue {
+            key: "endpoint_schema_version".to_string(),
+            value: target_version.to_string(),
+            reason: e.to_string(),
+        })
+    })
+}
+
 /// Telemetry consent manager
 pub struct ConsentManager {
     consent_given: Arc<RwLock<bool>>,
@@ -297,6 +333,47 @@ mod tests {
         assert!(!manager.has_consent().await);
     }
 
+    fn report() -> TelemetryReport {
+        TelemetryReport {
+            schema_version: SCHEMA_VERSION,
+            session_id: "s".to_string(),
+            version: "1.0.0".to_string(),
+            platform: "linux".to_string(),
+            timestamp: Utc::now(),
+            metrics: MetricsSummary {
+                inference: Default::default(),
+                sync: Default::default(),
+                resources: Default::default(),
+                errors: Default::default(),
+                network: Default::default(),
+                timestamp: Utc::now(),
+            },
+            events: vec![],
+            sequence: 0,
+        }
+    }
+
+    #[test]
+    fn test_downconvert_to_v1() {
+        let report = report();
+
+        let v1 = schema::downconvert_report(&report, 1).unwrap();
+        assert!(v1.get("schema_version").is_none());
+        let current = schema::downconvert_report(&report, SCHEMA_VERSION).unwrap();
+        assert_eq!(current["schema_version"], SCHEMA_VERSION);
+        assert!(schema::downconvert_report(&report, 99).is_err());
+    }
+
+    #[test]
+    fn test_outgoing_payload_is_anonymized() {
+        let mut beta = report();
+        beta.version = "1.4.2-beta+build7".to_string();
+
+        let payload = outgoing_payload(beta, 5, SCHEMA_VERSION).unwrap();
+        assert_eq!(payload["version"], "1.4");
+        assert!(outgoing_payload(report(), 5, 99).is_err());
+    }
+
     #[test]
     fn test_privacy_info_not_empty() {
         assert!(!PRIVACY_INFO.is_empty());
@@ -0,0 +1,371 @@
+// Telemetry schema definition and validation
+// Versioned so the ingestion pipeline can evolve without breaking older endpoints
+
+use super::{TelemetryEvent, TelemetryReport};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+
+/// Current telemetry schema version
+/// v1: original unversioned format
+/// v2: adds `schema_version` to reports
+/// v3: adds `NoiseSuppression` events
+/// v4: adds `HardwareBenchmark` events
+/// v5: adds `TaskOverrun` events
+pub const SCHEMA_VERSION: u32 = 5;
+
+/// Oldest schema version the reporter can down-convert to
+pub const MIN_SUPPORTED_VERSION: u32 = 1;
+
+/// Maximum length of string fields (guards against leaking free-form text)
+const MAX_STRING_LEN: usize = 128;
+
+/// Schema of a single event type
+#[derive(Debug, Clone, Copy)]
+pub struct EventSchema {
+    /// Value of the `type` tag
+    pub event_type: &'static str,
+    /// Schema version that introduced the event
+    pub since_version: u32,
+    /// Fields that must be present and non-null
+    pub required_fields: &'static [&'static str],
+    /// Numeric fields that must lie within 0-100
+    pub percent_fields: &'static [&'static str],
+}
+
+/// All known event types
+pub const EVENT_SCHEMAS: &[EventSchema] = &[
+    EventSchema {
+        event_type: "AppStarted",
+        since_version: 1,
+        required_fields: &["version", "platform", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "AppStopped",
+        since_version: 1,
+        required_fields: &["uptime_seconds", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "InferenceCompleted",
+        since_version: 1,
+        required_fields: &["model_id", "task_type", "duration_ms", "success", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "SyncCompleted",
+        since_version: 1,
+        required_fields: &[
+            "direction",
+            "items_count",
+            "bytes_transferred",
+            "duration_ms",
+            "success",
+            "timestamp",
+        ],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "ResourceSnapshot",
+        since_version: 1,
+        required_fields: &["avg_cpu_percent", "avg_ram_percent", "idle_hours", "timestamp"],
+        percent_fields: &["avg_cpu_percent", "avg_ram_percent", "avg_gpu_percent"],
+    },
+    EventSchema {
+        event_type: "Error",
+        since_version: 1,
+        required_fields: &["error_type", "recoverable", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "FeatureUsed",
+        since_version: 1,
+        required_fields: &["feature", "count", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "NoiseSuppression",
+        since_version: 3,
+        required_fields: &["snr_before_db", "snr_after_db", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "HardwareBenchmark",
+        since_version: 4,
+        required_fields: &["cpu_cores", "memory_gb", "disk_read_mb_s", "disk_write_mb_s", "timestamp"],
+        percent_fields: &[],
+    },
+    EventSchema {
+        event_type: "TaskOverrun",
+        since_version: 5,
+        required_fields: &["task_kind", "granted_seconds", "action", "timestamp"],
+        percent_fields: &[],
+    },
+];
+
+/// Schema validation errors
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaError {
+    UnknownEventType(String),
+    MissingField { event_type: String, field: String },
+    OutOfRange { event_type: String, field: String },
+    FieldTooLong { event_type: String, field: String },
+    TimestampInFuture { event_type: String },
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownEventType(t) => write!(f, "Unknown event type: {}", t),
+            Self::MissingField { event_type, field } => {
+                write!(f, "{}: missing field '{}'", event_type, field)
+            }
+            Self::OutOfRange { event_type, field } => {
+                write!(f, "{}: field '{}' out of range", event_type, field)
+            }
+            Self::FieldTooLong { event_type, field } => {
+                write!(f, "{}: field '{}' exceeds {} characters", event_type, field, MAX_STRING_LEN)
+            }
+            Self::TimestampInFuture { event_type } => {
+                write!(f, "{}: timestamp is in the future", event_type)
+            }
+            Self::UnsupportedVersion(v) => write!(f, "Unsupported schema version: {}", v),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Look up the schema for an event type
+pub fn schema_for(event_type: &str) -> Option<&'static EventSchema> {
+    EVENT_SCHEMAS.iter().find(|s| s.event_type == event_type)
+}
+
+/// Validate an event against its schema
+pub fn validate_event(event: &TelemetryEvent) -> Result<(), SchemaError> {
+    let value = serde_json::to_value(event)
+        .map_err(|e| SchemaError::UnknownEventType(e.to_string()))?;
+    validate_event_value(&value)
+}
+
+/// Validate a serialized event against its schema
+pub fn validate_event_value(value: &Value) -> Result<(), SchemaError> {
+    let event_type = value
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+
+    let schema = schema_for(&event_type)
+        .ok_or_else(|| SchemaError::UnknownEventType(event_type.clone()))?;
+
+    for field in schema.required_fields {
+        if value.get(field).is_none_or(|v| v.is_null()) {
+            return Err(SchemaError::MissingField {
+                event_type,
+                field: field.to_string(),
+            });
+        }
+    }
+
+    for field in schema.percent_fields {
+        if let Some(v) = value.get(field).and_then(|v| v.as_f64()) {
+            if !(0.0..=100.0).contains(&v) {
+                return Err(SchemaError::OutOfRange {
+                    event_type,
+                    field: field.to_string(),
+                });
+            }
+        }
+    }
+
+    if let Some(obj) = value.as_object() {
+        for (field, v) in obj {
+            if v.as_str().is_some_and(|s| s.chars().count() > MAX_STRING_LEN) {
+                return Err(SchemaError::FieldTooLong {
+                    event_type,
+                    field: field.clone(),
+                });
+            }
+        }
+    }
+
+    let in_future = value
+        .get("timestamp")
+        .and_then(|t| t.as_str())
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t.with_timezone(&Utc) > Utc::now() + Duration::minutes(5));
+    if in_future {
+        return Err(SchemaError::TimestampInFuture { event_type });
+    }
+
+    Ok(())
+}
+
+/// JSON Schema of a report at the current version, generated from `EVENT_SCHEMAS`.
+/// Published as schemas/telemetry-report.schema.json for server-side validation.
+pub fn report_json_schema() -> Value {
+    let event_rules: Vec<Value> = EVENT_SCHEMAS
+        .iter()
+        .map(|schema| {
+            let percent: serde_json::Map<String, Value> = schema
+                .percent_fields
+                .iter()
+                .map(|field| {
+                    (
+                        field.to_string(),
+                        serde_json::json!({ "type": ["number", "null"], "minimum": 0, "maximum": 100 }),
+                    )
+                })
+                .collect();
+            serde_json::json!({
+                "if": { "properties": { "type": { "const": schema.event_type } } },
+                "then": { "required": schema.required_fields, "properties": percent },
+            })
+        })
+        .collect();
+    let event_types: Vec<&str> = EVENT_SCHEMAS.iter().map(|s| s.event_type).collect();
+
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "CLA telemetry report",
+        "type": "object",
+        "required": ["session_id", "version", "platform", "timestamp", "metrics", "events", "sequence"],
+        "properties": {
+            "schema_version": { "type": "integer", "minimum": MIN_SUPPORTED_VERSION, "maximum": SCHEMA_VERSION },
+            "session_id": { "type": "string", "maxLength": MAX_STRING_LEN },
+            "version": { "type": "string", "maxLength": MAX_STRING_LEN },
+            "platform": { "type": "string", "maxLength": MAX_STRING_LEN },
+            "timestamp": { "type": "string" },
+            "metrics": { "type": "object" },
+            "sequence": { "type": "integer", "minimum": 0 },
+            "events": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["type", "timestamp"],
+                    "properties": { "type": { "enum": event_types } },
+                    "additionalProperties": { "maxLength": MAX_STRING_LEN },
+                    "allOf": event_rules,
+                },
+            },
+        },
+    })
+}
+
+/// Convert a report to the payload format of an older schema version
+pub fn downconvert_report(report: &TelemetryReport, target_version: u32) -> Result<Value, SchemaError> {
+    if !(MIN_SUPPORTED_VERSION..=SCHEMA_VERSION).contains(&target_version) {
+        return Err(SchemaError::UnsupportedVersion(target_version));
+    }
+
+    let mut value = serde_json::to_value(report)
+        .map_err(|e| SchemaError::UnknownEventType(e.to_string()))?;
+
+    // Drop events the target version doesn't know about
+    if let Some(events) = value.get_mut("events").and_then(|e| e.as_array_mut()) {
+        events.retain(|event| {
+            event
+                .get("type")
+                .and_then(|t| t.as_str())
+                .and_then(schema_for)
+                .is_some_and(|s| s.since_version <= target_version)
+        });
+    }
+
+    if let Some(obj) = value.as_object_mut() {
+        if target_version < 2 {
+            obj.remove("schema_version");
+        } else {
+            obj.insert("schema_version".to_string(), Value::from(target_version));
+        }
+    }
+
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_event() {
+        let event = TelemetryEvent::FeatureUsed {
+            feature: "voice".to_string(),
+            count: 1,
+            timestamp: Utc::now(),
+        };
+        assert!(validate_event(&event).is_ok());
+    }
+
+    #[test]
+    fn test_percent_out_of_range() {
+        let event = TelemetryEvent::ResourceSnapshot {
+            avg_cpu_percent: 140.0,
+            avg_ram_percent: 20.0,
+            avg_gpu_percent: None,
+            idle_hours: 1.0,
+            timestamp: Utc::now(),
+        };
+        assert!(matches!(validate_event(&event), Err(SchemaError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_long_strings_rejected() {
+        let event = TelemetryEvent::Error {
+            error_type: "x".repeat(500),
+            error_code: None,
+            recoverable: true,
+            timestamp: Utc::now(),
+        };
+        assert!(matches!(validate_event(&event), Err(SchemaError::FieldTooLong { .. })));
+    }
+
+    #[test]
+    fn test_downconvert_drops_newer_events() {
+        let report = TelemetryReport {
+            schema_version: SCHEMA_VERSION,
+            session_id: "s".to_string(),
+            version: "1.0".to_string(),
+            platform: "linux".to_string(),
+            timestamp: Utc::now(),
+            metrics: crate::telemetry::MetricsSummary {
+                inference: Default::default(),
+                sync: Default::default(),
+                resources: Default::default(),
+                errors: Default::default(),
+                network: Default::default(),
+                timestamp: Utc::now(),
+            },
+            events: vec![TelemetryEvent::NoiseSuppression {
+                snr_before_db: 5.0,
+                snr_after_db: 15.0,
+                timestamp: Utc::now(),
+            }],
+            sequence: 0,
+        };
+
+        let v2 = downconvert_report(&report, 2).unwrap();
+        assert_eq!(v2["schema_version"], 2);
+        assert!(v2["events"].as_array().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_published_json_schema_is_current() {
+        let published: Value =
+            serde_json::from_str(include_str!("../../schemas/telemetry-report.schema.json")).unwrap();
+        assert_eq!(
+            published,
+            report_json_schema(),
+            "Regenerate schemas/telemetry-report.schema.json from report_json_schema()"
+        );
+    }
+
+    #[test]
+    fn test_every_event_type_has_schema() {
+        let value = serde_json::json!({"type": "Unknown", "timestamp": Utc::now()});
+        assert!(matches!(validate_event_value(&value), Err(SchemaError::UnknownEventType(_))));
+        assert!(EVENT_SCHEMAS.iter().all(|s| s.since_version <= SCHEMA_VERSION));
+    }
+}
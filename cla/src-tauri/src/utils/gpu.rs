@@ -0,0 +1,287 @@
+// GPU monitoring - Utilization and VRAM of the machine's main GPU
+// NVIDIA: NVML, AMD: amdgpu sysfs (Linux), Apple: IOAccelerator statistics,
+// other GPUs on Windows: DXGI (memory only)
+
+/// One reading of the GPU
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuSample {
+    pub name: String,
+    pub usage_percent: Option<f32>,
+    pub memory_used_mb: Option<u64>,
+    pub memory_total_mb: Option<u64>,
+}
+
+/// A way of reading the GPU
+pub trait GpuSource: Send + Sync {
+    /// Current reading, or None if the GPU can't be read right now
+    fn sample(&self) -> Option<GpuSample>;
+
+    /// Backend name for logs
+    fn name(&self) -> &'static str;
+}
+
+/// The first backend that can read a GPU on this machine
+pub fn detect() -> Option<Box<dyn GpuSource>> {
+    candidates().into_iter().find(|source| source.sample().is_some())
+}
+
+#[cfg(target_os = "linux")]
+fn candidates() -> Vec<Box<dyn GpuSource>> {
+    let nvml = nvml::Nvml::load().map(|source| Box::new(source) as Box<dyn GpuSource>);
+    nvml.into_iter().chain([Box::new(amdgpu::Amdgpu) as Box<dyn GpuSource>]).collect()
+}
+
+#[cfg(target_os = "windows")]
+fn candidates() -> Vec<Box<dyn GpuSource>> {
+    let nvml = nvml::Nvml::load().map(|source| Box::new(source) as Box<dyn GpuSource>);
+    nvml.into_iter().chain([Box::new(dxgi::Dxgi) as Box<dyn GpuSource>]).collect()
+}
+
+#[cfg(target_os = "macos")]
+fn candidates() -> Vec<Box<dyn GpuSource>> {
+    vec![Box::new(apple::IoAccelerator)]
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+fn candidates() -> Vec<Box<dyn GpuSource>> {
+    Vec::new()
+}
+
+fn bytes_to_mb(bytes: u64) -> u64 {
+    bytes / 1024 / 1024
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+mod nvml {
+    use super::{bytes_to_mb, GpuSample, GpuSource};
+
+    /// First NVIDIA GPU, read through the driver's NVML library
+    pub struct Nvml {
+        nvml: nvml_wrapper::Nvml,
+        name: String,
+    }
+
+    impl Nvml {
+        /// None when the NVIDIA driver (libnvidia-ml.so.1 / nvml.dll) is not installed
+        pub fn load() -> Option<Self> {
+            let nvml = nvml_wrapper::Nvml::init().ok()?;
+            let name = nvml
+                .device_by_index(0)
+                .ok()?
+                .name()
+                .unwrap_or_else(|_| "NVIDIA GPU".to_string());
+            Some(Self { nvml, name })
+        }
+    }
+
+    impl GpuSource for Nvml {
+        fn sample(&self) -> Option<GpuSample> {
+            let device = self.nvml.device_by_index(0).ok()?;
+            let utilization = device.utilization_rates().ok();
+            let memory = device.memory_info().ok();
+            if utilization.is_none() && memory.is_none() {
+                return None;
+            }
+            Some(GpuSample {
+                name: self.name.clone(),
+                usage_percent: utilization.map(|utilization| utilization.gpu as f32),
+                memory_used_mb: memory.as_ref().map(|memory| bytes_to_mb(memory.used)),
+                memory_total_mb: memory.map(|memory| bytes_to_mb(memory.total)),
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "NVML"
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod amdgpu {
+    use super::{bytes_to_mb, GpuSample, GpuSource};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    const AMD_VENDOR_ID: &str = "0x1002";
+
+    /// AMD GPU driven by amdgpu, read from /sys/class/drm
+    pub struct Amdgpu;
+
+    fn read_number(path: &Path) -> Option<u64> {
+        fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
+    /// Device directory of the first AMD card
+    fn find_device() -> Option<PathBuf> {
+        let mut cards: Vec<PathBuf> = fs::read_dir("/sys/class/drm")
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("card") && !name.contains('-'))
+            })
+            .map(|card| card.join("device"))
+            .collect();
+        cards.sort();
+        cards.into_iter().find(|device| {
+            fs::read_to_string(device.join("vendor")).is_ok_and(|vendor| vendor.trim() == AMD_VENDOR_ID)
+                && device.join("gpu_busy_percent").exists()
+        })
+    }
+
+    impl GpuSource for Amdgpu {
+        fn sample(&self) -> Option<GpuSample> {
+            let device = find_device()?;
+            let name = fs::read_to_string(device.join("product_name"))
+                .map(|name| name.trim().to_string())
+                .ok()
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "AMD GPU".to_string());
+            Some(GpuSample {
+                name,
+                usage_percent: read_number(&device.join("gpu_busy_percent")).map(|percent| percent as f32),
+                memory_used_mb: read_number(&device.join("mem_info_vram_used")).map(bytes_to_mb),
+                memory_total_mb: read_number(&device.join("mem_info_vram_total")).map(bytes_to_mb),
+            })
+        }
+
+        fn name(&self) -> &'static str {
+            "amdgpu"
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod dxgi {
+    use super::{bytes_to_mb, GpuSample, GpuSource};
+    use windows::core::Interface;
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIAdapter1, IDXGIAdapter3, IDXGIFactory1, DXGI_ADAPTER_DESC1, DXGI_ADAPTER_FLAG_SOFTWARE,
+        DXGI_MEMORY_SEGMENT_GROUP_LOCAL, DXGI_QUERY_VIDEO_MEMORY_INFO,
+    };
+
+    /// Hardware adapter with the most dedicated memory. DXGI reports memory
+    /// but not utilization.
+    pub struct Dxgi;
+
+    impl GpuSource for Dxgi {
+        fn sample(&self) -> Option<GpuSample> {
+            // SAFETY: plain DXGI calls; the bindings release every COM object
+            unsafe {
+                let factory: IDXGIFactory1 = CreateDXGIFactory1().ok()?;
+
+                let mut best: Option<(IDXGIAdapter1, DXGI_ADAPTER_DESC1)> = None;
+                for index in 0.. {
+                    // Fails with DXGI_ERROR_NOT_FOUND after the last adapter
+                    let Ok(adapter) = factory.EnumAdapters1(index) else {
+                        break;
+                    };
+                    let Ok(desc) = adapter.GetDesc1() else {
+                        continue;
+                    };
+                    if desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0 {
+                        continue;
+                    }
+                    if best.as_ref().is_none_or(|(_, best)| desc.DedicatedVideoMemory > best.DedicatedVideoMemory) {
+                        best = Some((adapter, desc));
+                    }
+                }
+                let (adapter, desc) = best?;
+
+                let name_len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+                // IDXGIAdapter3 (Windows 10+) reports current usage
+                let memory_used_mb = adapter.cast::<IDXGIAdapter3>().ok().and_then(|adapter3| {
+                    let mut info = DXGI_QUERY_VIDEO_MEMORY_INFO::default();
+                    adapter3
+                        .QueryVideoMemoryInfo(0, DXGI_MEMORY_SEGMENT_GROUP_LOCAL, &mut info)
+                        .ok()
+                        .map(|()| bytes_to_mb(info.CurrentUsage))
+                });
+
+                Some(GpuSample {
+                    name: String::from_utf16_lossy(&desc.Description[..name_len]),
+                    usage_percent: None,
+                    memory_used_mb,
+                    memory_total_mb: Some(bytes_to_mb(desc.DedicatedVideoMemory as u64)),
+                })
+            }
+        }
+
+        fn name(&self) -> &'static str {
+            "DXGI"
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod apple {
+    use super::{GpuSample, GpuSource};
+    use std::process::Command;
+
+    /// Apple GPU statistics from the IOAccelerator registry entry. Memory is
+    /// unified with the system's, so there is no VRAM total.
+    pub struct IoAccelerator;
+
+    impl GpuSource for IoAccelerator {
+        fn sample(&self) -> Option<GpuSample> {
+            let output = Command::new("ioreg")
+                .args(["-r", "-d", "1", "-w", "0", "-c", "IOAccelerator"])
+                .output()
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            super::parse_ioreg(&String::from_utf8_lossy(&output.stdout))
+        }
+
+        fn name(&self) -> &'static str {
+            "IOAccelerator"
+        }
+    }
+}
+
+/// GPU reading from `ioreg -c IOAccelerator` output
+#[cfg_attr(not(any(target_os = "macos", test)), allow(dead_code))]
+fn parse_ioreg(output: &str) -> Option<GpuSample> {
+    use once_cell::sync::Lazy;
+    use regex::Regex;
+
+    static UTILIZATION: Lazy<Regex> = Lazy::new(|| Regex::new(r#""Device Utilization %"=(\d+)"#).unwrap());
+    static MEMORY_IN_USE: Lazy<Regex> = Lazy::new(|| Regex::new(r#""In use system memory"=(\d+)"#).unwrap());
+    static MODEL: Lazy<Regex> = Lazy::new(|| Regex::new(r#""model" = "([^"]+)""#).unwrap());
+
+    let number = |regex: &Regex| regex.captures(output).and_then(|captures| captures[1].parse::<u64>().ok());
+    let usage_percent = number(&UTILIZATION)?;
+    Some(GpuSample {
+        name: MODEL.captures(output).map_or_else(|| "Apple GPU".to_string(), |captures| captures[1].to_string()),
+        usage_percent: Some(usage_percent as f32),
+        memory_used_mb: number(&MEMORY_IN_USE).map(bytes_to_mb),
+        memory_total_mb: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ioreg_statistics_are_parsed() {
+        let output = r#"+-o AGXAcceleratorG13X  <class AGXAcceleratorG13X>
+    {
+      "model" = "Apple M1 Pro"
+      "PerformanceStatistics" = {"In use system memory"=1073741824,"Device Utilization %"=37,"Renderer Utilization %"=35}
+    }"#;
+        let sample = parse_ioreg(output).unwrap();
+        assert_eq!(sample.name, "Apple M1 Pro");
+        assert_eq!(sample.usage_percent, Some(37.0));
+        assert_eq!(sample.memory_used_mb, Some(1024));
+        assert_eq!(sample.memory_total_mb, None);
+    }
+
+    #[test]
+    fn ioreg_without_statistics_is_no_gpu() {
+        assert_eq!(parse_ioreg("+-o IOAccelerator\n"), None);
+    }
+}
@@ -0,0 +1,287 @@
+// Idle detection - Time since the user last used keyboard or mouse, from the OS
+// Windows: GetLastInputInfo, macOS: CGEventSource, Linux: XScreenSaver or logind
+
+/// Source of the time since the last user input
+pub trait IdleSource: Send + Sync {
+    /// Seconds since the last keyboard/mouse input, or None if unknown right now
+    fn idle_seconds(&self) -> Option<u32>;
+
+    /// Short name for logs
+    fn name(&self) -> &'static str;
+}
+
+/// The OS idle source for this session, if one answers
+pub fn platform_source() -> Option<Box<dyn IdleSource>> {
+    let candidates = platform_candidates();
+    candidates.into_iter().find(|source| source.idle_seconds().is_some())
+}
+
+#[cfg(target_os = "windows")]
+fn platform_candidates() -> Vec<Box<dyn IdleSource>> {
+    vec![Box::new(windows::LastInputInfo)]
+}
+
+#[cfg(target_os = "macos")]
+fn platform_candidates() -> Vec<Box<dyn IdleSource>> {
+    vec![Box::new(macos::EventSource)]
+}
+
+#[cfg(target_os = "linux")]
+fn platform_candidates() -> Vec<Box<dyn IdleSource>> {
+    let x11 = linux::XScreenSaver::open().map(|source| Box::new(source) as Box<dyn IdleSource>);
+    let logind: Box<dyn IdleSource> = Box::new(linux::Logind::for_current_session());
+    // Under Wayland, XScreenSaver only sees input to X11 apps
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        std::iter::once(logind).chain(x11).collect()
+    } else {
+        x11.into_iter().chain(std::iter::once(logind)).collect()
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+fn platform_candidates() -> Vec<Box<dyn IdleSource>> {
+    Vec::new()
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use super::IdleSource;
+
+    #[repr(C)]
+    struct RawLastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut RawLastInputInfo) -> i32;
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    pub struct LastInputInfo;
+
+    impl IdleSource for LastInputInfo {
+        fn idle_seconds(&self) -> Option<u32> {
+            let mut info = RawLastInputInfo {
+                cb_size: std::mem::size_of::<RawLastInputInfo>() as u32,
+                dw_time: 0,
+            };
+            // SAFETY: `info` is a valid LASTINPUTINFO with cbSize set
+            if unsafe { GetLastInputInfo(&mut info) } == 0 {
+                return None;
+            }
+            // Both are milliseconds since boot and wrap after 49.7 days
+            let idle_ms = unsafe { GetTickCount() }.wrapping_sub(info.dw_time);
+            Some(idle_ms / 1000)
+        }
+
+        fn name(&self) -> &'static str {
+            "GetLastInputInfo"
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use super::IdleSource;
+
+    /// kCGEventSourceStateHIDSystemState: input from all processes
+    const HID_SYSTEM_STATE: i32 = 1;
+    /// kCGAnyInputEventType
+    const ANY_INPUT_EVENT: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(state: i32, event_type: u32) -> f64;
+    }
+
+    pub struct EventSource;
+
+    impl IdleSource for EventSource {
+        fn idle_seconds(&self) -> Option<u32> {
+            // SAFETY: plain function call with constant arguments
+            let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT) };
+            (seconds.is_finite() && seconds >= 0.0).then_some(seconds as u32)
+        }
+
+        fn name(&self) -> &'static str {
+            "CGEventSource"
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::IdleSource;
+    use libloading::Library;
+    use std::ffi::{c_char, c_int, c_ulong, c_void};
+    use std::process::Command;
+    use std::sync::Mutex;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct XScreenSaverInfo {
+        window: c_ulong,
+        state: c_int,
+        kind: c_int,
+        til_or_since: c_ulong,
+        /// Milliseconds since the last input
+        idle: c_ulong,
+        event_mask: c_ulong,
+    }
+
+    type XOpenDisplayFn = unsafe extern "C" fn(*const c_char) -> *mut c_void;
+    type XDefaultRootWindowFn = unsafe extern "C" fn(*mut c_void) -> c_ulong;
+    type XCloseDisplayFn = unsafe extern "C" fn(*mut c_void) -> c_int;
+    type XScreenSaverQueryInfoFn = unsafe extern "C" fn(*mut c_void, c_ulong, *mut XScreenSaverInfo) -> c_int;
+
+    /// Open X display; Xlib calls on it are serialized by the mutex
+    struct Display(*mut c_void);
+
+    // SAFETY: the display is only used while holding the mutex
+    unsafe impl Send for Display {}
+
+    pub struct XScreenSaver {
+        display: Mutex<Display>,
+        root: c_ulong,
+        query_info: XScreenSaverQueryInfoFn,
+        close_display: XCloseDisplayFn,
+        // Loaded at runtime so the agent still starts on systems without libXss;
+        // kept loaded while their functions are in use
+        _x11: Library,
+        _xss: Library,
+    }
+
+    impl XScreenSaver {
+        /// Connect to $DISPLAY, or None without X11 or libXss
+        pub fn open() -> Option<Self> {
+            std::env::var_os("DISPLAY")?;
+            // SAFETY: loading Xlib and libXss runs no initialization code with
+            // preconditions, and the function types match their prototypes
+            unsafe {
+                let x11 = Library::new("libX11.so.6").ok()?;
+                let xss = Library::new("libXss.so.1").ok()?;
+                let open_display = *x11.get::<XOpenDisplayFn>(b"XOpenDisplay\0").ok()?;
+                let root_window = *x11.get::<XDefaultRootWindowFn>(b"XDefaultRootWindow\0").ok()?;
+                let close_display = *x11.get::<XCloseDisplayFn>(b"XCloseDisplay\0").ok()?;
+                let query_info = *xss.get::<XScreenSaverQueryInfoFn>(b"XScreenSaverQueryInfo\0").ok()?;
+
+                let display = open_display(std::ptr::null());
+                if display.is_null() {
+                    return None;
+                }
+                Some(Self {
+                    root: root_window(display),
+                    display: Mutex::new(Display(display)),
+                    query_info,
+                    close_display,
+                    _x11: x11,
+                    _xss: xss,
+                })
+            }
+        }
+    }
+
+    impl Drop for XScreenSaver {
+        fn drop(&mut self) {
+            if let Ok(display) = self.display.get_mut() {
+                // SAFETY: opened in `open` and not used after this
+                unsafe { (self.close_display)(display.0) };
+            }
+        }
+    }
+
+    impl IdleSource for XScreenSaver {
+        fn idle_seconds(&self) -> Option<u32> {
+            let display = self.display.lock().ok()?;
+            let mut info = XScreenSaverInfo::default();
+            // SAFETY: display is open and `info` has the C layout
+            let status = unsafe { (self.query_info)(display.0, self.root, &mut info) };
+            (status != 0).then_some((info.idle / 1000) as u32)
+        }
+
+        fn name(&self) -> &'static str {
+            "XScreenSaver"
+        }
+    }
+
+    /// systemd-logind's idle hint, set by the desktop (GNOME, KDE) on Wayland and X11.
+    /// Coarse: it only turns on after the desktop's own idle delay, so idle time
+    /// reads 0 until then.
+    pub struct Logind {
+        session: String,
+    }
+
+    impl Logind {
+        pub fn for_current_session() -> Self {
+            Self {
+                session: std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "auto".to_string()),
+            }
+        }
+    }
+
+    impl IdleSource for Logind {
+        fn idle_seconds(&self) -> Option<u32> {
+            let output = Command::new("loginctl")
+                .args(["show-session", &self.session, "-p", "IdleHint", "-p", "IdleSinceHint"])
+                .output()
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            let now_us = chrono::Utc::now().timestamp_micros();
+            super::parse_logind_idle(&String::from_utf8_lossy(&output.stdout), now_us)
+        }
+
+        fn name(&self) -> &'static str {
+            "logind"
+        }
+    }
+}
+
+/// Idle seconds from `loginctl show-session -p IdleHint -p IdleSinceHint` output.
+/// IdleSinceHint is wall-clock microseconds.
+#[cfg_attr(not(any(target_os = "linux", test)), allow(dead_code))]
+fn parse_logind_idle(output: &str, now_us: i64) -> Option<u32> {
+    let mut idle_hint = None;
+    let mut idle_since = None;
+    for line in output.lines() {
+        match line.split_once('=') {
+            Some(("IdleHint", value)) => idle_hint = Some(value.trim() == "yes"),
+            Some(("IdleSinceHint", value)) => idle_since = value.trim().parse::<i64>().ok(),
+            _ => {}
+        }
+    }
+    match (idle_hint?, idle_since) {
+        (false, _) => Some(0),
+        (true, Some(since)) if since > 0 => Some((now_us.saturating_sub(since).max(0) / 1_000_000) as u32),
+        (true, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logind_active_session_is_not_idle() {
+        assert_eq!(parse_logind_idle("IdleHint=no\nIdleSinceHint=1700000000000000\n", 1_700_000_900_000_000), Some(0));
+    }
+
+    #[test]
+    fn logind_idle_time_counts_from_hint() {
+        let output = "IdleHint=yes\nIdleSinceHint=1700000000000000\n";
+        assert_eq!(parse_logind_idle(output, 1_700_000_300_500_000), Some(300));
+    }
+
+    #[test]
+    fn logind_without_hint_is_unknown() {
+        assert_eq!(parse_logind_idle("", 0), None);
+        assert_eq!(parse_logind_idle("IdleHint=yes\nIdleSinceHint=0\n", 10), None);
+    }
+}
@@ -1,19 +1,40 @@
 // Utility modules for Cirkelline Local Agent
 
+pub mod gpu;
+pub mod idle;
+pub mod sync;
+pub mod timebox;
+pub mod watchdog;
+
 use crate::models::SystemMetrics;
 use chrono::Utc;
-use sysinfo::{System, Disks};
+use sysinfo::{Pid, System, Disks};
 use std::time::{Duration, Instant};
 use tauri::{Manager, Emitter};
 
+pub use gpu::{GpuSample, GpuSource};
+pub use idle::IdleSource;
+pub use watchdog::{Heartbeat, Watchdog};
+
+/// CPU usage below which the fallback idle estimate counts the machine as idle
+const IDLE_CPU_PERCENT: f32 = 5.0;
+
 /// Resource monitor that tracks system metrics
 pub struct ResourceMonitor {
     system: System,
     disks: Disks,
     last_update: Instant,
     cached_metrics: Option<SystemMetrics>,
+    /// OS idle time; None falls back to the CPU estimate
+    idle_source: Option<Box<dyn IdleSource>>,
+    idle_threshold_seconds: u32,
+    /// Start of the current stretch of low CPU usage
     idle_start: Option<Instant>,
     last_cpu_usage: f32,
+    own_usage: OwnUsage,
+    /// None when no GPU backend found a GPU
+    gpu_source: Option<Box<dyn GpuSource>>,
+    last_gpu_sample: Option<GpuSample>,
 }
 
 impl ResourceMonitor {
@@ -23,16 +44,38 @@ impl ResourceMonitor {
 
         let disks = Disks::new_with_refreshed_list();
 
+        let idle_source = idle::platform_source();
+        match &idle_source {
+            Some(source) => log::info!("Idle detection: {}", source.name()),
+            None => log::warn!("No OS idle detection available, estimating idle time from CPU usage"),
+        }
+
+        let gpu_source = gpu::detect();
+        let last_gpu_sample = gpu_source.as_ref().and_then(|source| source.sample());
+        if let (Some(source), Some(sample)) = (&gpu_source, &last_gpu_sample) {
+            log::info!("GPU monitoring: {} via {}", sample.name, source.name());
+        }
+
         Self {
             system,
             disks,
             last_update: Instant::now(),
             cached_metrics: None,
+            idle_source,
+            idle_threshold_seconds: crate::models::Settings::default().idle_threshold_seconds,
             idle_start: Some(Instant::now()),
             last_cpu_usage: 0.0,
+            own_usage: OwnUsage::default(),
+            gpu_source,
+            last_gpu_sample,
         }
     }
 
+    /// Idle time after which `is_idle` is reported
+    pub fn set_idle_threshold(&mut self, seconds: u32) {
+        self.idle_threshold_seconds = seconds;
+    }
+
     /// Update system metrics (call periodically)
     pub fn refresh(&mut self) {
         self.system.refresh_all();
@@ -41,6 +84,19 @@ impl ResourceMonitor {
 
         // Update CPU usage cache
         self.last_cpu_usage = self.system.global_cpu_info().cpu_usage();
+        if self.last_cpu_usage < IDLE_CPU_PERCENT {
+            self.idle_start.get_or_insert(self.last_update);
+        } else {
+            self.idle_start = None;
+        }
+
+        if let Some(source) = &self.gpu_source {
+            self.last_gpu_sample = source.sample();
+        }
+
+        if let Ok(own_pid) = sysinfo::get_current_pid() {
+            self.own_usage = OwnUsage::measure(&self.system, own_pid);
+        }
     }
 
     /// Get current system metrics
@@ -57,12 +113,12 @@ impl ResourceMonitor {
         // Get disk info for app data directory
         let (disk_used, disk_available) = self.get_disk_usage();
 
-        // Estimate idle time (simplified - in production use platform APIs)
-        let idle_seconds = self.estimate_idle_time();
-        let is_idle = idle_seconds >= 120; // 2 minutes default threshold
+        let idle_seconds = self.idle_seconds();
+        let is_idle = idle_seconds >= self.idle_threshold_seconds;
 
         // Check power status
         let (on_battery, battery_percent) = self.get_power_status();
+        let gpu = self.last_gpu_sample.as_ref();
 
         SystemMetrics {
             cpu_usage_percent: cpu_usage,
@@ -70,10 +126,13 @@ impl ResourceMonitor {
             ram_used_mb: used_memory,
             ram_total_mb: total_memory,
             ram_usage_percent: ram_percent,
-            gpu_available: self.check_gpu_available(),
-            gpu_usage_percent: self.get_gpu_usage(),
-            gpu_memory_used_mb: None, // TODO: Implement GPU memory tracking
-            gpu_memory_total_mb: None,
+            process_cpu_percent: self.own_usage.cpu_percent,
+            process_ram_mb: self.own_usage.ram_mb,
+            process_count: self.own_usage.process_count,
+            gpu_available: self.gpu_source.is_some(),
+            gpu_usage_percent: gpu.and_then(|sample| sample.usage_percent),
+            gpu_memory_used_mb: gpu.and_then(|sample| sample.memory_used_mb),
+            gpu_memory_total_mb: gpu.and_then(|sample| sample.memory_total_mb),
             disk_used_mb: disk_used,
             disk_available_mb: disk_available,
             on_battery,
@@ -99,22 +158,17 @@ impl ResourceMonitor {
         (0, 0)
     }
 
-    fn estimate_idle_time(&self) -> u32 {
-        // Simple heuristic: low CPU = idle
-        // In production, use platform-specific APIs:
-        // - Windows: GetLastInputInfo
-        // - macOS: CGEventSourceSecondsSinceLastEventType
-        // - Linux: XScreenSaverQueryInfo or libinput
-        let cpu_usage = self.last_cpu_usage;
-
-        if cpu_usage < 5.0 {
-            // Very low CPU, probably idle
-            if let Some(start) = self.idle_start {
-                return start.elapsed().as_secs() as u32;
-            }
-        }
+    /// Seconds since the last user input, from the OS when it can tell
+    fn idle_seconds(&self) -> u32 {
+        self.idle_source
+            .as_ref()
+            .and_then(|source| source.idle_seconds())
+            .unwrap_or_else(|| self.estimate_idle_time())
+    }
 
-        0
+    /// Fallback: how long CPU usage has stayed low. Any background spike resets it.
+    fn estimate_idle_time(&self) -> u32 {
+        self.idle_start.map_or(0, |start| start.elapsed().as_secs() as u32)
     }
 
     fn get_power_status(&self) -> (bool, Option<u8>) {
@@ -123,38 +177,77 @@ impl ResourceMonitor {
         (false, None)
     }
 
-    fn check_gpu_available(&self) -> bool {
-        // TODO: Check for CUDA/WebGPU availability
-        // For now, assume not available
-        false
-    }
+}
+
+/// CPU and RAM of CLA's process tree (the app plus sidecars and tools it spawned)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct OwnUsage {
+    /// Share of the whole machine, like `cpu_usage_percent`
+    cpu_percent: f32,
+    ram_mb: u64,
+    process_count: u32,
+}
+
+impl OwnUsage {
+    fn measure(system: &System, own_pid: Pid) -> Self {
+        // Threads are listed as processes too; their usage is already in their process
+        let processes: Vec<_> = system.processes().values().filter(|p| p.thread_kind().is_none()).collect();
+
+        let mut tree = vec![own_pid];
+        let mut next = 0;
+        while next < tree.len() {
+            let parent = tree[next];
+            tree.extend(processes.iter().filter(|p| p.parent() == Some(parent)).map(|p| p.pid()));
+            next += 1;
+        }
 
-    fn get_gpu_usage(&self) -> Option<f32> {
-        // TODO: Use nvml or similar for GPU monitoring
-        None
+        let members: Vec<_> = processes.iter().filter(|p| tree.contains(&p.pid())).collect();
+        // Per-process CPU is in % of one core
+        let cpus = system.cpus().len().max(1) as f32;
+        Self {
+            cpu_percent: members.iter().map(|p| p.cpu_usage()).sum::<f32>() / cpus,
+            ram_mb: members.iter().map(|p| p.memory()).sum::<u64>() / 1024 / 1024,
+            process_count: members.len() as u32,
+        }
     }
 }
 
+/// Time a sync may take after its interval before the loop counts as stalled
+const SYNC_STALL_GRACE: Duration = Duration::from_secs(300);
+
 /// Start the resource monitoring loop
-pub async fn start_resource_monitor(app_handle: tauri::AppHandle) {
+pub async fn start_resource_monitor(app_handle: tauri::AppHandle, heartbeat: Heartbeat) {
     let mut interval = tokio::time::interval(Duration::from_secs(5));
 
     loop {
         interval.tick().await;
+        heartbeat.beat(Duration::from_secs(30));
 
         if let Some(state) = app_handle.try_state::<crate::AppState>() {
+            let settings = state.settings.read().await.clone();
             let mut monitor = state.resource_monitor.write().await;
+            monitor.set_idle_threshold(settings.idle_threshold_seconds);
             monitor.refresh();
 
             // Emit metrics to frontend
             let metrics = monitor.get_current_metrics();
+            drop(monitor);
+            state.telemetry.record_resource_sample(metrics.is_idle);
             let _ = app_handle.emit("system-metrics", &metrics);
+
+            // Free RAM held by idle models when memory runs short
+            if let Some(engine) = state.inference_engine.read().await.as_ref() {
+                let unloaded = engine.enforce_memory_budget(&metrics, &settings);
+                if !unloaded.is_empty() {
+                    log::info!("Unloaded idle models under memory pressure: {:?}", unloaded);
+                }
+            }
         }
     }
 }
 
 /// Start the sync loop
-pub async fn start_sync_loop(app_handle: tauri::AppHandle) {
+pub async fn start_sync_loop(app_handle: tauri::AppHandle, heartbeat: Heartbeat) {
     // Wait for initial startup
     tokio::time::sleep(Duration::from_secs(10)).await;
 
@@ -163,8 +256,9 @@ pub async fn start_sync_loop(app_handle: tauri::AppHandle) {
         let interval_minutes = if let Some(state) = app_handle.try_state::<crate::AppState>() {
             let settings = state.settings.read().await;
 
-            // Skip if paused or offline
-            if settings.paused || settings.offline_mode {
+            // Skip if paused, offline or not set up yet
+            if settings.paused || settings.offline_mode || !state.onboarding.is_ready() {
+                heartbeat.beat(Duration::from_secs(60) + SYNC_STALL_GRACE);
                 tokio::time::sleep(Duration::from_secs(60)).await;
                 continue;
             }
@@ -175,7 +269,9 @@ pub async fn start_sync_loop(app_handle: tauri::AppHandle) {
         };
 
         // Wait for interval
-        tokio::time::sleep(Duration::from_secs(interval_minutes as u64 * 60)).await;
+        let interval = Duration::from_secs(interval_minutes as u64 * 60);
+        heartbeat.beat(interval + SYNC_STALL_GRACE);
+        tokio::time::sleep(interval).await;
 
         // Check if we can sync (respecting resource limits)
         if let Some(state) = app_handle.try_state::<crate::AppState>() {
@@ -201,23 +297,46 @@ pub async fn start_sync_loop(app_handle: tauri::AppHandle) {
 
             log::info!("Starting scheduled sync");
 
-            let mut status = state.sync_status.write().await;
-            status.is_syncing = true;
-            drop(status);
-
             // Emit sync start event
             let _ = app_handle.emit("sync-started", ());
 
-            // TODO: Actual sync implementation
-            tokio::time::sleep(Duration::from_secs(2)).await; // Simulated sync
-
-            let mut status = state.sync_status.write().await;
-            status.is_syncing = false;
-            status.last_sync = Some(Utc::now());
-            status.last_sync_result = Some(crate::models::SyncResult::Success);
+            let knowledge = match app_handle.try_state::<crate::commands::commander::CommanderState>() {
+                Some(commander) => Some(commander.unit.read().await.knowledge()),
+                None => None,
+            };
+            let result = crate::commands::sync::perform_sync(&state, knowledge).await;
+            state
+                .activity
+                .record(
+                    crate::activity::ActivityCategory::Sync,
+                    crate::commands::sync::sync_summary(&result),
+                    Some("Planlagt synkronisering"),
+                )
+                .await;
 
             // Emit sync complete event
+            let status = state.sync_status.read().await;
             let _ = app_handle.emit("sync-completed", &*status);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn own_usage_includes_child_processes() {
+        let mut child = std::process::Command::new("sleep").arg("5").spawn().expect("spawn sleep");
+        let mut system = System::new();
+        system.refresh_all();
+
+        let usage = OwnUsage::measure(&system, sysinfo::get_current_pid().unwrap());
+        child.kill().ok();
+        child.wait().ok();
+
+        assert!(usage.process_count >= 2);
+        assert!(usage.ram_mb > 0);
+    }
+}
@@ -0,0 +1,709 @@
+// CKC Sync - Delta sync of local memories, sessions and knowledge chunks with CKC
+// The server's manifest of record versions is diffed against the local records and
+// only changed records move, in batches. Each batch is written locally before the
+// next one is sent, so an interrupted sync picks up where it stopped.
+
+use crate::models::{ConflictResolution, DataType, LocalKnowledgeChunk, LocalMemory, LocalSession, SyncConflict, SyncResult, SyncStatus};
+use crate::research::KnowledgeStore;
+use crate::security::device::DeviceIdentity;
+use crate::error::ClaError;
+use crate::error::StorageError;
+use crate::storage::LocalDatabase;
+use crate::telemetry::network::{MeteredSend, NetworkSubsystem};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Records per upload or download request
+pub const BATCH_SIZE: usize = 50;
+
+/// The server's version of a record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteEntry {
+    pub data_type: DataType,
+    pub id: Uuid,
+    /// CKC keeps the `updated_at` of the uploaded record
+    pub updated_at: DateTime<Utc>,
+}
+
+/// The local version of a record, as the diff sees it
+#[derive(Debug, Clone)]
+pub struct LocalEntry {
+    pub id: Uuid,
+    /// None for records that never change once written (knowledge chunks)
+    pub version: Option<DateTime<Utc>>,
+    pub synced_at: Option<DateTime<Utc>>,
+    /// Changed locally since the last sync
+    pub changed: bool,
+}
+
+/// What a sync has to do for one data type
+#[derive(Debug, Default, PartialEq)]
+pub struct SyncPlan {
+    pub uploads: Vec<Uuid>,
+    pub downloads: Vec<Uuid>,
+    /// (id, local version, remote version) of records changed on both sides
+    pub conflicts: Vec<(Uuid, DateTime<Utc>, DateTime<Utc>)>,
+}
+
+/// Diff local records against the server's entries of the same data type
+pub fn plan(local: &[LocalEntry], remote: &[RemoteEntry]) -> SyncPlan {
+    let remote_by_id: HashMap<Uuid, &RemoteEntry> = remote.iter().map(|entry| (entry.id, entry)).collect();
+    let mut plan = SyncPlan::default();
+
+    for entry in local {
+        let Some(theirs) = remote_by_id.get(&entry.id) else {
+            if entry.changed {
+                plan.uploads.push(entry.id);
+            }
+            continue;
+        };
+        let Some(ours) = entry.version else {
+            continue;
+        };
+        if ours == theirs.updated_at {
+            continue;
+        }
+        let remote_changed = entry.synced_at.is_none_or(|synced| theirs.updated_at > synced);
+        match (entry.changed, remote_changed) {
+            (true, false) => plan.uploads.push(entry.id),
+            (false, true) => plan.downloads.push(entry.id),
+            (true, true) => plan.conflicts.push((entry.id, ours, theirs.updated_at)),
+            (false, false) => {}
+        }
+    }
+
+    let local_ids: HashSet<Uuid> = local.iter().map(|entry| entry.id).collect();
+    plan.downloads.extend(remote.iter().map(|entry| entry.id).filter(|id| !local_ids.contains(id)));
+    plan
+}
+
+/// Outcome of a sync run
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub uploaded: u32,
+    pub downloaded: u32,
+    pub bytes_uploaded: u64,
+    pub bytes_downloaded: u64,
+    pub conflicts: Vec<SyncConflict>,
+    pub errors: Vec<String>,
+}
+
+impl SyncReport {
+    pub fn result(&self) -> SyncResult {
+        match self.errors.first() {
+            None => SyncResult::Success,
+            Some(_) if self.uploaded + self.downloaded > 0 => SyncResult::PartialSuccess {
+                errors: self.errors.clone(),
+            },
+            Some(error) => SyncResult::Failed { error: error.clone() },
+        }
+    }
+}
+
+/// A database record that syncs with CKC
+trait SyncRecord: Serialize + DeserializeOwned + Clone {
+    const DATA_TYPE: DataType;
+    /// Path segment of the record type in the CKC sync API
+    const KIND: &'static str;
+
+    fn load(database: &LocalDatabase, id: Uuid) -> Result<Option<Self>, StorageError>;
+    fn load_all(database: &LocalDatabase) -> Result<Vec<Self>, StorageError>;
+    fn save(&self, database: &LocalDatabase) -> Result<(), StorageError>;
+
+    fn id(&self) -> Uuid;
+    fn updated_at(&self) -> DateTime<Utc>;
+    fn entry(&self) -> LocalEntry;
+    /// Record the current version as the one CKC has
+    fn mark_synced(&mut self, cloud_id: Option<String>);
+    fn describe_conflict(&self) -> String;
+    /// Combine both sides of a conflict; the result is a new local change
+    fn merge(self, remote: Self) -> Self;
+}
+
+impl SyncRecord for LocalMemory {
+    const DATA_TYPE: DataType = DataType::Memory;
+    const KIND: &'static str = "memories";
+
+    fn load(database: &LocalDatabase, id: Uuid) -> Result<Option<Self>, StorageError> {
+        database.get_memory(id)
+    }
+
+    fn load_all(database: &LocalDatabase) -> Result<Vec<Self>, StorageError> {
+        database.list_memories(None, 0)
+    }
+
+    fn save(&self, database: &LocalDatabase) -> Result<(), StorageError> {
+        database.save_memory(self)
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn entry(&self) -> LocalEntry {
+        LocalEntry {
+            id: self.id,
+            version: Some(self.updated_at),
+            synced_at: self.synced_at,
+            changed: self.pending_sync || self.synced_at.is_none_or(|synced| self.updated_at > synced),
+        }
+    }
+
+    fn mark_synced(&mut self, cloud_id: Option<String>) {
+        self.synced_at = Some(self.updated_at);
+        self.pending_sync = false;
+        if cloud_id.is_some() {
+            self.cloud_id = cloud_id;
+        }
+    }
+
+    fn describe_conflict(&self) -> String {
+        let preview: String = self.content.chars().take(60).collect();
+        format!("Hukommelsen \"{}\" er ændret både lokalt og i CKC", preview)
+    }
+
+    fn merge(self, remote: Self) -> Self {
+        let remote_version = remote.updated_at;
+        let (mut merged, other) = if self.updated_at >= remote.updated_at { (self, remote) } else { (remote, self) };
+        for topic in other.topics {
+            if !merged.topics.contains(&topic) {
+                merged.topics.push(topic);
+            }
+        }
+        merged.importance = merged.importance.max(other.importance);
+        merged.cloud_id = merged.cloud_id.or(other.cloud_id);
+        merged.updated_at = Utc::now();
+        merged.synced_at = Some(remote_version);
+        merged.pending_sync = true;
+        merged
+    }
+}
+
+impl SyncRecord for LocalSession {
+    const DATA_TYPE: DataType = DataType::Session;
+    const KIND: &'static str = "sessions";
+
+    fn load(database: &LocalDatabase, id: Uuid) -> Result<Option<Self>, StorageError> {
+        database.get_session(id)
+    }
+
+    fn load_all(database: &LocalDatabase) -> Result<Vec<Self>, StorageError> {
+        database.list_sessions(None, 0)
+    }
+
+    fn save(&self, database: &LocalDatabase) -> Result<(), StorageError> {
+        database.save_session(self)
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.updated_at
+    }
+
+    fn entry(&self) -> LocalEntry {
+        LocalEntry {
+            id: self.id,
+            version: Some(self.updated_at),
+            synced_at: self.synced_at,
+            changed: self.synced_at.is_none_or(|synced| self.updated_at > synced),
+        }
+    }
+
+    fn mark_synced(&mut self, cloud_id: Option<String>) {
+        self.synced_at = Some(self.updated_at);
+        if cloud_id.is_some() {
+            self.cloud_id = cloud_id;
+        }
+    }
+
+    fn describe_conflict(&self) -> String {
+        format!("Sessionen ({}) er ændret både lokalt og i CKC", self.session_type)
+    }
+
+    fn merge(self, remote: Self) -> Self {
+        let remote_version = remote.updated_at;
+        let (mut merged, other) = if self.updated_at >= remote.updated_at { (self, remote) } else { (remote, self) };
+        for message in other.messages {
+            let duplicate = merged.messages.iter().any(|m| {
+                m.timestamp == message.timestamp && m.role == message.role && m.content == message.content
+            });
+            if !duplicate {
+                merged.messages.push(message);
+            }
+        }
+        merged.messages.sort_by_key(|m| m.timestamp);
+        merged.cloud_id = merged.cloud_id.or(other.cloud_id);
+        merged.updated_at = Utc::now();
+        merged.synced_at = Some(remote_version);
+        merged
+    }
+}
+
+fn knowledge_entry(chunk: &LocalKnowledgeChunk) -> LocalEntry {
+    LocalEntry {
+        id: chunk.id,
+        version: None,
+        synced_at: None,
+        changed: true,
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    entries: Vec<RemoteEntry>,
+}
+
+#[derive(Deserialize)]
+struct Accepted {
+    id: Uuid,
+    #[serde(default)]
+    cloud_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct UploadResponse {
+    accepted: Vec<Accepted>,
+}
+
+#[derive(Deserialize)]
+struct FetchResponse<T> {
+    items: Vec<T>,
+}
+
+/// A response and the bytes it took each way
+struct Transfer<T> {
+    value: T,
+    sent: u64,
+    received: u64,
+}
+
+/// Sync engine for one CKC endpoint
+pub struct SyncEngine {
+    http: reqwest::Client,
+    endpoint: String,
+    api_key: Option<String>,
+    device: Arc<DeviceIdentity>,
+    database: Arc<LocalDatabase>,
+    knowledge: Option<Arc<KnowledgeStore>>,
+}
+
+impl SyncEngine {
+    pub fn new(endpoint: &str, api_key: Option<&str>, device: Arc<DeviceIdentity>, database: Arc<LocalDatabase>) -> Self {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            http,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_key: api_key.filter(|key| !key.is_empty()).map(str::to_string),
+            device,
+            database,
+            knowledge: None,
+        }
+    }
+
+    /// Also sync the knowledge chunks in `store`
+    pub fn with_knowledge(mut self, store: Arc<KnowledgeStore>) -> Self {
+        self.knowledge = Some(store);
+        self
+    }
+
+    /// Diff against CKC and move every changed record. Conflicts are reported, not
+    /// transferred. Returns Err only when the manifest could not be fetched.
+    pub async fn run(&self, status: &RwLock<SyncStatus>) -> Result<SyncReport, String> {
+        let manifest: Transfer<Manifest> = self.call(reqwest::Method::GET, "/api/v1/sync/manifest", None).await?;
+        let remote = |data_type: DataType| -> Vec<RemoteEntry> {
+            manifest.value.entries.iter().filter(|e| e.data_type == data_type).cloned().collect()
+        };
+
+        let memories = LocalMemory::load_all(&self.database).map_err(storage_message)?;
+        let sessions = LocalSession::load_all(&self.database).map_err(storage_message)?;
+        let chunks = match &self.knowledge {
+            Some(store) => store.all().await,
+            None => Vec::new(),
+        };
+
+        let memory_plan = plan(&memories.iter().map(SyncRecord::entry).collect::<Vec<_>>(), &remote(DataType::Memory));
+        let session_plan = plan(&sessions.iter().map(SyncRecord::entry).collect::<Vec<_>>(), &remote(DataType::Session));
+        let knowledge_plan = match &self.knowledge {
+            Some(_) => plan(&chunks.iter().map(knowledge_entry).collect::<Vec<_>>(), &remote(DataType::Knowledge)),
+            None => SyncPlan::default(),
+        };
+
+        let mut report = SyncReport {
+            bytes_downloaded: manifest.received,
+            ..SyncReport::default()
+        };
+        report.conflicts.extend(conflicts(&memories, &memory_plan));
+        report.conflicts.extend(conflicts(&sessions, &session_plan));
+
+        {
+            let plans = [&memory_plan, &session_plan, &knowledge_plan];
+            let mut status = status.write().await;
+            status.pending_uploads = plans.iter().map(|p| p.uploads.len() as u32).sum();
+            status.pending_downloads = plans.iter().map(|p| p.downloads.len() as u32).sum();
+            status.conflicts = report.conflicts.clone();
+            status.bytes_downloaded += manifest.received;
+        }
+
+        self.transfer_records(&memories, &memory_plan, status, &mut report).await;
+        self.transfer_records(&sessions, &session_plan, status, &mut report).await;
+        if let Some(store) = &self.knowledge {
+            self.transfer_knowledge(store, &chunks, &knowledge_plan, status, &mut report).await;
+        }
+
+        log::info!(
+            "Sync finished: {} uploaded, {} downloaded, {} conflicts, {} errors",
+            report.uploaded,
+            report.downloaded,
+            report.conflicts.len(),
+            report.errors.len()
+        );
+        Ok(report)
+    }
+
+    /// Settle a conflict found by `run`
+    pub async fn resolve(&self, conflict: &SyncConflict, resolution: &ConflictResolution) -> Result<(), String> {
+        match conflict.data_type {
+            DataType::Memory => self.resolve_record::<LocalMemory>(conflict.id, resolution).await,
+            DataType::Session => self.resolve_record::<LocalSession>(conflict.id, resolution).await,
+            _ => Err("Konflikten kan ikke løses her".to_string()),
+        }
+    }
+
+    async fn resolve_record<R: SyncRecord>(&self, id: Uuid, resolution: &ConflictResolution) -> Result<(), String> {
+        let local = R::load(&self.database, id)
+            .map_err(storage_message)?
+            .ok_or_else(|| "Posten findes ikke længere lokalt".to_string())?;
+        match resolution {
+            ConflictResolution::KeepLocal => self.upload_and_mark(&[local]).await.map(|_| ()),
+            ConflictResolution::KeepRemote => {
+                let mut remote = self.fetch_one::<R>(id).await?;
+                remote.mark_synced(None);
+                remote.save(&self.database).map_err(storage_message)
+            }
+            ConflictResolution::Merge => {
+                let remote = self.fetch_one::<R>(id).await?;
+                let merged = local.merge(remote);
+                merged.save(&self.database).map_err(storage_message)?;
+                self.upload_and_mark(&[merged]).await.map(|_| ())
+            }
+            ConflictResolution::Manual => Ok(()),
+        }
+    }
+
+    async fn transfer_records<R: SyncRecord>(
+        &self,
+        records: &[R],
+        plan: &SyncPlan,
+        status: &RwLock<SyncStatus>,
+        report: &mut SyncReport,
+    ) {
+        let by_id: HashMap<Uuid, &R> = records.iter().map(|r| (r.id(), r)).collect();
+        for batch in plan.uploads.chunks(BATCH_SIZE) {
+            let items: Vec<R> = batch.iter().filter_map(|id| by_id.get(id)).map(|r| (*r).clone()).collect();
+            match self.upload_and_mark(&items).await {
+                Ok(sent) => record_progress(status, report, batch.len(), 0, sent, 0).await,
+                Err(error) => {
+                    log::warn!("Uploading {} failed: {}", R::KIND, error);
+                    report.errors.push(error);
+                    break;
+                }
+            }
+        }
+
+        for batch in plan.downloads.chunks(BATCH_SIZE) {
+            let fetched = match self.fetch::<R>(batch).await {
+                Ok(fetched) => fetched,
+                Err(error) => {
+                    log::warn!("Downloading {} failed: {}", R::KIND, error);
+                    report.errors.push(error);
+                    break;
+                }
+            };
+            for mut item in fetched.value.items {
+                // Changed locally since the plan was made; the next sync sees the conflict
+                if R::load(&self.database, item.id()).is_ok_and(|local| local.is_some_and(|l| l.entry().changed)) {
+                    continue;
+                }
+                item.mark_synced(None);
+                if let Err(error) = item.save(&self.database) {
+                    report.errors.push(storage_message(error));
+                }
+            }
+            record_progress(status, report, 0, batch.len(), 0, fetched.received).await;
+        }
+    }
+
+    async fn transfer_knowledge(
+        &self,
+        store: &KnowledgeStore,
+        chunks: &[LocalKnowledgeChunk],
+        plan: &SyncPlan,
+        status: &RwLock<SyncStatus>,
+        report: &mut SyncReport,
+    ) {
+        let by_id: HashMap<Uuid, &LocalKnowledgeChunk> = chunks.iter().map(|c| (c.id, c)).collect();
+        for batch in plan.uploads.chunks(BATCH_SIZE) {
+            let items: Vec<&LocalKnowledgeChunk> = batch.iter().filter_map(|id| by_id.get(id).copied()).collect();
+            match self.upload("knowledge", &items).await {
+                Ok(uploaded) => record_progress(status, report, batch.len(), 0, uploaded.sent, 0).await,
+                Err(error) => {
+                    report.errors.push(error);
+                    break;
+                }
+            }
+        }
+
+        for batch in plan.downloads.chunks(BATCH_SIZE) {
+            let fetched = match self.fetch_kind::<LocalKnowledgeChunk>("knowledge", batch).await {
+                Ok(fetched) => fetched,
+                Err(error) => {
+                    report.errors.push(error);
+                    break;
+                }
+            };
+            if let Err(error) = store.insert(fetched.value.items).await {
+                report.errors.push(format!("Viden kunne ikke gemmes: {}", error));
+                break;
+            }
+            record_progress(status, report, 0, batch.len(), 0, fetched.received).await;
+        }
+    }
+
+    /// Upload records and mark the ones CKC accepted as synced; returns bytes sent
+    async fn upload_and_mark<R: SyncRecord>(&self, items: &[R]) -> Result<u64, String> {
+        let uploaded = self.upload(R::KIND, items).await?;
+        let versions: HashMap<Uuid, DateTime<Utc>> = items.iter().map(|r| (r.id(), r.updated_at())).collect();
+        for accepted in uploaded.value.accepted {
+            // Re-read: a record edited during the upload still needs to go up
+            let Ok(Some(mut current)) = R::load(&self.database, accepted.id) else {
+                continue;
+            };
+            if versions.get(&accepted.id) == Some(&current.updated_at()) {
+                current.mark_synced(accepted.cloud_id);
+                current.save(&self.database).map_err(storage_message)?;
+            }
+        }
+        Ok(uploaded.sent)
+    }
+
+    async fn upload<T: Serialize>(&self, kind: &str, items: &[T]) -> Result<Transfer<UploadResponse>, String> {
+        let body = serde_json::to_vec(&serde_json::json!({ "items": items })).map_err(|e| e.to_string())?;
+        self.call(reqwest::Method::POST, &format!("/api/v1/sync/{}", kind), Some(body)).await
+    }
+
+    async fn fetch<R: SyncRecord>(&self, ids: &[Uuid]) -> Result<Transfer<FetchResponse<R>>, String> {
+        self.fetch_kind(R::KIND, ids).await
+    }
+
+    async fn fetch_one<R: SyncRecord>(&self, id: Uuid) -> Result<R, String> {
+        self.fetch::<R>(&[id])
+            .await?
+            .value
+            .items
+            .into_iter()
+            .find(|item| item.id() == id)
+            .ok_or_else(|| "Posten findes ikke længere i CKC".to_string())
+    }
+
+    async fn fetch_kind<T: DeserializeOwned>(&self, kind: &str, ids: &[Uuid]) -> Result<Transfer<FetchResponse<T>>, String> {
+        let body = serde_json::to_vec(&serde_json::json!({ "ids": ids })).map_err(|e| e.to_string())?;
+        self.call(reqwest::Method::POST, &format!("/api/v1/sync/{}/fetch", kind), Some(body)).await
+    }
+
+    async fn call<T: DeserializeOwned>(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<Vec<u8>>,
+    ) -> Result<Transfer<T>, String> {
+        let url = format!("{}{}", self.endpoint, path);
+        let sent = body.as_ref().map_or(0, |b| b.len() as u64);
+
+        let mut request = self.http.request(method.clone(), &url);
+        if let Some(key) = &self.api_key {
+            request = request.bearer_auth(key);
+        }
+        request = self
+            .device
+            .sign_request(request, method.as_str(), path, body.as_deref().unwrap_or_default());
+        if let Some(body) = body {
+            request = request.header(reqwest::header::CONTENT_TYPE, "application/json").body(body);
+        }
+        let response = request
+            .send_metered(NetworkSubsystem::Sync)
+            .await
+            .map_err(|e| format!("Kunne ikke forbinde til server: {}", e))?;
+
+        match response.status().as_u16() {
+            401 | 403 => return Err("CKC afviste API-nøglen".to_string()),
+            status if !(200..300).contains(&status) => {
+                return Err(format!("Server svarede med status: {}", response.status()));
+            }
+            _ => {}
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Svaret fra serveren blev afbrudt: {}", e))?;
+        let value = serde_json::from_slice(&bytes).map_err(|e| format!("Ugyldigt svar fra CKC: {}", e))?;
+        Ok(Transfer {
+            value,
+            sent,
+            received: bytes.len() as u64,
+        })
+    }
+}
+
+fn conflicts<R: SyncRecord>(records: &[R], plan: &SyncPlan) -> Vec<SyncConflict> {
+    plan.conflicts
+        .iter()
+        .filter_map(|(id, local_version, remote_version)| {
+            let record = records.iter().find(|r| r.id() == *id)?;
+            Some(SyncConflict {
+                id: *id,
+                data_type: R::DATA_TYPE,
+                local_version: *local_version,
+                remote_version: *remote_version,
+                description: record.describe_conflict(),
+                resolution_options: vec![
+                    ConflictResolution::KeepLocal,
+                    ConflictResolution::KeepRemote,
+                    ConflictResolution::Merge,
+                    ConflictResolution::Manual,
+                ],
+            })
+        })
+        .collect()
+}
+
+async fn record_progress(
+    status: &RwLock<SyncStatus>,
+    report: &mut SyncReport,
+    uploaded: usize,
+    downloaded: usize,
+    sent: u64,
+    received: u64,
+) {
+    report.uploaded += uploaded as u32;
+    report.downloaded += downloaded as u32;
+    report.bytes_uploaded += sent;
+    report.bytes_downloaded += received;
+
+    let mut status = status.write().await;
+    status.pending_uploads = status.pending_uploads.saturating_sub(uploaded as u32);
+    status.pending_downloads = status.pending_downloads.saturating_sub(downloaded as u32);
+    status.bytes_uploaded += sent;
+    status.bytes_downloaded += received;
+}
+
+fn storage_message(error: StorageError) -> String {
+    ClaError::Storage(error).user_message()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn local(id: Uuid, version: DateTime<Utc>, synced_at: Option<DateTime<Utc>>) -> LocalEntry {
+        LocalEntry {
+            id,
+            version: Some(version),
+            synced_at,
+            changed: synced_at.is_none_or(|synced| version > synced),
+        }
+    }
+
+    fn remote(id: Uuid, updated_at: DateTime<Utc>) -> RemoteEntry {
+        RemoteEntry {
+            data_type: DataType::Memory,
+            id,
+            updated_at,
+        }
+    }
+
+    #[test]
+    fn plan_moves_one_sided_changes() {
+        let t0 = Utc::now();
+        let (new_local, edited_local, edited_remote, new_remote, unchanged) =
+            (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let locals = vec![
+            local(new_local, t0, None),
+            local(edited_local, t0 + Duration::minutes(5), Some(t0)),
+            local(edited_remote, t0, Some(t0)),
+            local(unchanged, t0, Some(t0)),
+        ];
+        let remotes = vec![
+            remote(edited_local, t0),
+            remote(edited_remote, t0 + Duration::minutes(5)),
+            remote(new_remote, t0),
+            remote(unchanged, t0),
+        ];
+
+        let plan = plan(&locals, &remotes);
+        assert_eq!(plan.uploads, vec![new_local, edited_local]);
+        assert_eq!(plan.downloads, vec![edited_remote, new_remote]);
+        assert!(plan.conflicts.is_empty());
+    }
+
+    #[test]
+    fn plan_reports_changes_on_both_sides_as_conflict() {
+        let t0 = Utc::now();
+        let id = Uuid::new_v4();
+        let plan = plan(
+            &[local(id, t0 + Duration::minutes(1), Some(t0))],
+            &[remote(id, t0 + Duration::minutes(2))],
+        );
+        assert_eq!(plan.conflicts, vec![(id, t0 + Duration::minutes(1), t0 + Duration::minutes(2))]);
+        assert!(plan.uploads.is_empty() && plan.downloads.is_empty());
+    }
+
+    #[test]
+    fn plan_treats_unversioned_records_on_both_sides_as_synced() {
+        let chunk = Uuid::new_v4();
+        let entry = LocalEntry {
+            id: chunk,
+            version: None,
+            synced_at: None,
+            changed: true,
+        };
+        assert_eq!(plan(&[entry.clone()], &[remote(chunk, Utc::now())]), SyncPlan::default());
+        assert_eq!(plan(&[entry], &[]).uploads, vec![chunk]);
+    }
+
+    #[test]
+    fn merged_memory_keeps_newest_content_and_all_topics() {
+        let mut ours = crate::memory::new_memory("Lokal".to_string(), "note", vec!["a".to_string()], 0.4);
+        let mut theirs = ours.clone();
+        theirs.content = "Fjern".to_string();
+        theirs.topics = vec!["b".to_string()];
+        theirs.importance = 0.9;
+        theirs.updated_at = ours.updated_at + Duration::minutes(1);
+        ours.synced_at = Some(ours.created_at);
+
+        let remote_version = theirs.updated_at;
+        let merged = ours.merge(theirs);
+        assert_eq!(merged.content, "Fjern");
+        assert_eq!(merged.topics, vec!["b".to_string(), "a".to_string()]);
+        assert_eq!(merged.importance, 0.9);
+        assert_eq!(merged.synced_at, Some(remote_version));
+        assert!(merged.entry().changed);
+    }
+}
@@ -0,0 +1,122 @@
+// Time-boxing - Enforces the duration granted to background work
+// Work that overruns its budget is aborted (or rescheduled by the caller) and reported
+
+use crate::telemetry::TelemetryService;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+
+/// Kind of work that runs under a time budget
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeboxedWork {
+    /// A single local model call (embedding, transcription, OCR)
+    Inference,
+    /// One research task scan
+    Scan,
+    /// Deep analysis of a finding (download, extraction, embedding)
+    Ingestion,
+}
+
+impl TimeboxedWork {
+    /// Granted duration
+    pub fn budget(self) -> Duration {
+        match self {
+            Self::Inference => Duration::from_secs(300),
+            Self::Scan => Duration::from_secs(120),
+            Self::Ingestion => Duration::from_secs(600),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Inference => "inference",
+            Self::Scan => "scan",
+            Self::Ingestion => "ingestion",
+        }
+    }
+}
+
+/// What happened to work that overran
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OverrunAction {
+    Aborted,
+    /// Put back in the queue at lower priority
+    Rescheduled,
+}
+
+impl OverrunAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Aborted => "aborted",
+            Self::Rescheduled => "rescheduled",
+        }
+    }
+}
+
+/// Work that did not finish within its granted duration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overrun {
+    pub work: TimeboxedWork,
+    pub label: String,
+    pub granted: Duration,
+}
+
+impl std::fmt::Display for Overrun {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} overskred sin tidsramme på {}s", self.label, self.granted.as_secs())
+    }
+}
+
+impl std::error::Error for Overrun {}
+
+/// Run `work` for at most `granted`; the future is dropped when time runs out
+pub async fn run_timeboxed<F: Future>(
+    work: TimeboxedWork,
+    label: &str,
+    granted: Duration,
+    future: F,
+) -> Result<F::Output, Overrun> {
+    tokio::time::timeout(granted, future).await.map_err(|_| {
+        log::warn!("{} ({}) exceeded its {:?} budget", label, work.as_str(), granted);
+        Overrun {
+            work,
+            label: label.to_string(),
+            granted,
+        }
+    })
+}
+
+/// Report an overrun to telemetry, if available
+pub async fn report_overrun(telemetry: Option<&TelemetryService>, overrun: &Overrun, action: OverrunAction) {
+    if let Some(telemetry) = telemetry {
+        telemetry.record_task_overrun(overrun.work, overrun.granted, action).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_finishes_within_budget() {
+        let result = run_timeboxed(TimeboxedWork::Scan, "scan", Duration::from_millis(100), async { 7 }).await;
+        assert_eq!(result, Ok(7));
+    }
+
+    #[tokio::test]
+    async fn test_overrun_drops_work() {
+        let result = run_timeboxed(
+            TimeboxedWork::Ingestion,
+            "analyse",
+            Duration::from_millis(10),
+            tokio::time::sleep(Duration::from_secs(5)),
+        )
+        .await;
+
+        let overrun = result.unwrap_err();
+        assert_eq!(overrun.work, TimeboxedWork::Ingestion);
+        assert_eq!(overrun.to_string(), "analyse overskred sin tidsramme på 0s");
+    }
+}
@@ -0,0 +1,353 @@
+// Watchdog - Supervises background loops so they cannot die silently
+// Crashed or stalled loops are restarted with backoff and reported to health
+
+use crate::telemetry::{ComponentHealth, TelemetryService};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::AbortHandle;
+
+/// Restart and stall detection settings
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+    /// Delay before the first restart; doubles per consecutive restart
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// How often heartbeats are checked
+    pub check_interval: Duration,
+    /// Time after (re)start before the first heartbeat is due
+    pub startup_grace: Duration,
+    /// Running this long after a restart clears the restart streak
+    pub stable_after: Duration,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(300),
+            check_interval: Duration::from_secs(5),
+            startup_grace: Duration::from_secs(60),
+            stable_after: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Liveness signal a supervised loop sends every iteration
+#[derive(Debug, Clone)]
+pub struct Heartbeat {
+    /// Unix millis by which the next beat is due
+    deadline_ms: Arc<AtomicI64>,
+}
+
+impl Heartbeat {
+    fn new(grace: Duration) -> Self {
+        let heartbeat = Self { deadline_ms: Arc::new(AtomicI64::new(0)) };
+        heartbeat.beat(grace);
+        heartbeat
+    }
+
+    /// Heartbeat of a loop that runs without supervision
+    pub fn detached() -> Self {
+        Self::new(Duration::ZERO)
+    }
+
+    /// Report progress; the loop counts as stalled if it does not beat again within `next_within`
+    pub fn beat(&self, next_within: Duration) {
+        let deadline = Utc::now().timestamp_millis() + next_within.as_millis() as i64;
+        self.deadline_ms.store(deadline, Ordering::Relaxed);
+    }
+
+    fn is_overdue(&self) -> bool {
+        Utc::now().timestamp_millis() > self.deadline_ms.load(Ordering::Relaxed)
+    }
+}
+
+/// State of one supervised loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoopStatus {
+    pub name: String,
+    /// Health component the loop reports to
+    pub component: String,
+    pub running: bool,
+    pub started_at: DateTime<Utc>,
+    pub total_restarts: u32,
+    /// Restarts since the loop last ran stably
+    pub consecutive_restarts: u32,
+    pub last_restart_reason: Option<String>,
+}
+
+struct Supervised {
+    status: LoopStatus,
+    supervisor: AbortHandle,
+    task: Option<AbortHandle>,
+}
+
+/// Registry of supervised background loops
+pub struct Watchdog {
+    config: WatchdogConfig,
+    loops: Mutex<HashMap<String, Supervised>>,
+    telemetry: Option<Arc<TelemetryService>>,
+}
+
+impl Watchdog {
+    pub fn new(config: WatchdogConfig) -> Self {
+        Self {
+            config,
+            loops: Mutex::new(HashMap::new()),
+            telemetry: None,
+        }
+    }
+
+    /// Report restarts to telemetry and component health
+    pub fn with_telemetry(mut self, telemetry: Arc<TelemetryService>) -> Self {
+        self.telemetry = Some(telemetry);
+        self
+    }
+
+    /// Run a loop under supervision, replacing any loop registered under the same name.
+    ///
+    /// `factory` creates the loop future; it is called again for every restart.
+    /// A loop that returns normally is considered finished and is not restarted.
+    pub fn supervise<F, Fut>(self: &Arc<Self>, name: &str, component: &str, factory: F)
+    where
+        F: Fn(Heartbeat) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        // Hold the registry until the entry exists, so the supervisor always finds it
+        let mut loops = self.loops.lock().unwrap();
+        let watchdog = self.clone();
+        let loop_name = name.to_string();
+        let supervisor = tokio::spawn(async move { watchdog.run_supervisor(&loop_name, factory).await });
+
+        let previous = loops.insert(
+            name.to_string(),
+            Supervised {
+                status: LoopStatus {
+                    name: name.to_string(),
+                    component: component.to_string(),
+                    running: true,
+                    started_at: Utc::now(),
+                    total_restarts: 0,
+                    consecutive_restarts: 0,
+                    last_restart_reason: None,
+                },
+                supervisor: supervisor.abort_handle(),
+                task: None,
+            },
+        );
+        drop(loops);
+        if let Some(previous) = previous {
+            previous.supervisor.abort();
+            if let Some(task) = previous.task {
+                task.abort();
+            }
+        }
+    }
+
+    /// Status of all supervised loops
+    pub fn status(&self) -> Vec<LoopStatus> {
+        let mut loops: Vec<LoopStatus> = self.loops.lock().unwrap().values().map(|l| l.status.clone()).collect();
+        loops.sort_by(|a, b| a.name.cmp(&b.name));
+        loops
+    }
+
+    async fn run_supervisor<F, Fut>(&self, name: &str, factory: F)
+    where
+        F: Fn(Heartbeat) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        loop {
+            let heartbeat = Heartbeat::new(self.config.startup_grace);
+            let mut task = tokio::spawn(factory(heartbeat.clone()));
+            let started = tokio::time::Instant::now();
+            if !self.update(name, |l| {
+                l.task = Some(task.abort_handle());
+                l.status.running = true;
+                l.status.started_at = Utc::now();
+            }) {
+                task.abort();
+                return;
+            }
+
+            let mut check = tokio::time::interval(self.config.check_interval);
+            let reason = loop {
+                tokio::select! {
+                    result = &mut task => {
+                        break match result {
+                            Ok(()) => None,
+                            Err(e) if e.is_panic() => Some(format!("panicked: {}", panic_message(e.into_panic()))),
+                            Err(_) => Some("was cancelled".to_string()),
+                        };
+                    }
+                    _ = check.tick() => {
+                        if heartbeat.is_overdue() {
+                            task.abort();
+                            break Some("stopped sending heartbeats".to_string());
+                        }
+                        if started.elapsed() >= self.config.stable_after {
+                            self.mark_stable(name).await;
+                        }
+                    }
+                }
+            };
+
+            let Some(reason) = reason else {
+                log::info!("Background loop '{}' finished", name);
+                self.update(name, |l| l.status.running = false);
+                return;
+            };
+
+            let mut restarts = 0;
+            let mut component = String::new();
+            self.update(name, |l| {
+                l.status.total_restarts += 1;
+                l.status.consecutive_restarts += 1;
+                l.status.last_restart_reason = Some(reason.clone());
+                restarts = l.status.consecutive_restarts;
+                component = l.status.component.clone();
+            });
+
+            let backoff = self
+                .config
+                .initial_backoff
+                .saturating_mul(2u32.saturating_pow(restarts.saturating_sub(1)))
+                .min(self.config.max_backoff);
+            log::error!(
+                "Background loop '{}' {}; restarting in {:?} (restart {} in a row)",
+                name,
+                reason,
+                backoff,
+                restarts
+            );
+            if let Some(telemetry) = &self.telemetry {
+                telemetry.record_error("background_loop_restart", Some(name), true).await;
+                telemetry
+                    .update_component_health(&component, |health| {
+                        health.record_failure(&format!("Loop '{}' {}", name, reason))
+                    })
+                    .await;
+            }
+
+            tokio::time::sleep(backoff).await;
+        }
+    }
+
+    /// Clear the restart streak once a restarted loop has run long enough
+    async fn mark_stable(&self, name: &str) {
+        let mut recovered = None;
+        self.update(name, |l| {
+            if l.status.consecutive_restarts > 0 {
+                l.status.consecutive_restarts = 0;
+                recovered = Some(l.status.component.clone());
+            }
+        });
+        if let (Some(component), Some(telemetry)) = (recovered, &self.telemetry) {
+            log::info!("Background loop '{}' is stable again", name);
+            telemetry.update_component_health(&component, ComponentHealth::record_success).await;
+        }
+    }
+
+    /// Apply `f` to a registered loop; false if it was replaced or removed
+    fn update(&self, name: &str, f: impl FnOnce(&mut Supervised)) -> bool {
+        let mut loops = self.loops.lock().unwrap();
+        match loops.get_mut(name) {
+            Some(supervised) => {
+                f(supervised);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Default for Watchdog {
+    fn default() -> Self {
+        Self::new(WatchdogConfig::default())
+    }
+}
+
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{HealthState, TelemetryConfig};
+    use std::sync::atomic::AtomicU32;
+
+    fn config() -> WatchdogConfig {
+        WatchdogConfig {
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(20),
+            check_interval: Duration::from_millis(10),
+            startup_grace: Duration::from_millis(50),
+            stable_after: Duration::from_secs(60),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_restarts_panicking_loop_and_marks_unhealthy() {
+        let telemetry = Arc::new(TelemetryService::new(TelemetryConfig::default()));
+        let watchdog = Arc::new(Watchdog::new(config()).with_telemetry(telemetry.clone()));
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        watchdog.supervise("sync", "sync_service", move |_| {
+            let counter = counter.clone();
+            async move {
+                // Crash three times, then finish normally
+                if counter.fetch_add(1, Ordering::SeqCst) < 3 {
+                    panic!("boom");
+                }
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+
+        let status = &watchdog.status()[0];
+        assert!(!status.running);
+        assert_eq!(status.total_restarts, 3);
+        assert_eq!(status.last_restart_reason.as_deref(), Some("panicked: boom"));
+
+        let health = telemetry.get_health().await;
+        assert_eq!(health.get_component("sync_service").unwrap().state, HealthState::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn test_restarts_stalled_loop() {
+        let watchdog = Arc::new(Watchdog::new(config()));
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let counter = runs.clone();
+        watchdog.supervise("voice", "voice_control", move |heartbeat| {
+            let counter = counter.clone();
+            async move {
+                let run = counter.fetch_add(1, Ordering::SeqCst);
+                loop {
+                    // The first run hangs without beating
+                    if run > 0 {
+                        heartbeat.beat(Duration::from_millis(50));
+                    }
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        let status = &watchdog.status()[0];
+        assert!(status.running);
+        assert_eq!(status.last_restart_reason.as_deref(), Some("stopped sending heartbeats"));
+    }
+}
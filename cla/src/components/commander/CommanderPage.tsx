@@ -330,6 +330,13 @@ export function CommanderPage() {
                   : "Aldrig"}
               </p>
             </div>
+            <div className="col-span-2">
+              <p className="text-gray-500 dark:text-gray-400">Svar fra cache</p>
+              <p className="font-medium text-gray-900 dark:text-white">
+                {syncStats.http_cache.hits} af {syncStats.http_cache.requests} forespørgsler
+                ({(syncStats.http_cache.bytes_saved / 1024).toFixed(0)} KB sparet)
+              </p>
+            </div>
           </div>
         </div>
       )}
@@ -54,6 +54,24 @@ export function SettingsPage() {
           unit=" MB"
           onChange={(value) => updateSettings({ max_disk_mb: value })}
         />
+        <SliderSetting
+          label="Vent når computerens CPU-brug overstiger"
+          value={settings.max_system_cpu_percent}
+          min={50}
+          max={100}
+          step={5}
+          unit="%"
+          onChange={(value) => updateSettings({ max_system_cpu_percent: value })}
+        />
+        <SliderSetting
+          label="Vent når computerens RAM-brug overstiger"
+          value={settings.max_system_ram_percent}
+          min={50}
+          max={100}
+          step={5}
+          unit="%"
+          onChange={(value) => updateSettings({ max_system_ram_percent: value })}
+        />
       </SettingsSection>
 
       {/* Behavior */}
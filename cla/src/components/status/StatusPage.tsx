@@ -73,15 +73,15 @@ export function StatusPage() {
         <div className="space-y-3">
           <ResourceBar
             icon={<Cpu className="w-4 h-4" />}
-            label="CPU"
-            value={metrics?.cpu_usage_percent || 0}
+            label="CPU (CLA)"
+            value={metrics?.process_cpu_percent || 0}
             max={settings.max_cpu_percent}
             unit="%"
           />
           <ResourceBar
             icon={<HardDrive className="w-4 h-4" />}
-            label="RAM"
-            value={metrics?.ram_usage_percent || 0}
+            label="RAM (CLA)"
+            value={metrics?.ram_total_mb ? (metrics.process_ram_mb / metrics.ram_total_mb) * 100 : 0}
             max={settings.max_ram_percent}
             unit="%"
           />
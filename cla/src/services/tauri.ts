@@ -6,10 +6,17 @@ import type {
   SystemMetrics,
   SyncStatus,
   ModelInfo,
+  ModelStatus,
+  WarmupStatus,
   EmbeddingResult,
+  BatchEmbeddingResult,
+  TextGenerationResult,
   TranscriptionResult,
   TextExtractionResult,
   ConnectionStatus,
+  LocalMemory,
+  PendingTask,
+  TaskType,
 } from "../types";
 
 // Settings commands
@@ -55,6 +62,8 @@ export interface ResourceLimits {
   max_ram_percent: number;
   max_gpu_percent: number;
   max_disk_mb: number;
+  max_system_cpu_percent: number;
+  max_system_ram_percent: number;
   idle_only: boolean;
   idle_threshold_seconds: number;
 }
@@ -93,6 +102,18 @@ export async function generateEmbedding(text: string): Promise<EmbeddingResult>
   return invoke<EmbeddingResult>("generate_embedding", { text });
 }
 
+export async function generateEmbeddingsBatch(texts: string[]): Promise<BatchEmbeddingResult> {
+  return invoke<BatchEmbeddingResult>("generate_embeddings_batch", { texts });
+}
+
+export async function generateText(
+  prompt: string,
+  maxTokens?: number,
+  temperature?: number
+): Promise<TextGenerationResult> {
+  return invoke<TextGenerationResult>("generate_text", { prompt, maxTokens, temperature });
+}
+
 export async function transcribeAudio(
   audioPath: string,
   language?: string
@@ -111,3 +132,66 @@ export async function getModelStatus(): Promise<ModelInfo[]> {
 export async function downloadModel(modelId: string): Promise<void> {
   return invoke("download_model", { modelId });
 }
+
+export async function unloadModel(modelId: string): Promise<boolean> {
+  return invoke<boolean>("unload_model", { modelId });
+}
+
+export async function reloadModels(): Promise<ModelStatus[]> {
+  return invoke<ModelStatus[]>("reload_models");
+}
+
+export async function getInferenceWarmupStatus(): Promise<WarmupStatus> {
+  return invoke<WarmupStatus>("get_inference_warmup_status");
+}
+
+// Local memory commands
+export interface MemoryInput {
+  id?: string;
+  content: string;
+  memory_type: string;
+  topics?: string[];
+  importance?: number;
+}
+
+export async function saveMemory(memory: MemoryInput): Promise<LocalMemory> {
+  return invoke<LocalMemory>("save_memory", { memory });
+}
+
+export async function listMemories(limit?: number, offset?: number): Promise<LocalMemory[]> {
+  return invoke<LocalMemory[]>("list_memories", { limit, offset });
+}
+
+export async function searchMemories(query: string, limit?: number): Promise<LocalMemory[]> {
+  return invoke<LocalMemory[]>("search_memories", { query, limit });
+}
+
+export async function deleteMemory(id: string): Promise<void> {
+  return invoke("delete_memory", { id });
+}
+
+// Task queue commands
+export async function listTasks(): Promise<PendingTask[]> {
+  return invoke<PendingTask[]>("list_tasks");
+}
+
+export async function enqueueTask(
+  taskType: TaskType,
+  payload?: Record<string, unknown>,
+  priority?: number,
+  maxRetries?: number
+): Promise<PendingTask> {
+  return invoke<PendingTask>("enqueue_task", { taskType, payload, priority, maxRetries });
+}
+
+export async function cancelTask(id: string): Promise<PendingTask> {
+  return invoke<PendingTask>("cancel_task", { id });
+}
+
+export async function retryTask(id: string): Promise<PendingTask> {
+  return invoke<PendingTask>("retry_task", { id });
+}
+
+export async function clearFinishedTasks(): Promise<number> {
+  return invoke<number>("clear_finished_tasks");
+}
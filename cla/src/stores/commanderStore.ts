@@ -25,6 +25,7 @@ export interface CommanderConfig {
   alert_on_critical: boolean;
   sync_to_cosmic_library: boolean;
   offline_mode_enabled: boolean;
+  archive_findings?: boolean;
 }
 
 export interface QueueStatus {
@@ -40,10 +41,20 @@ export interface QueueStatus {
   };
 }
 
+export interface HttpCacheStats {
+  requests: number;
+  conditional: number;
+  hits: number;
+  bytes_saved: number;
+  entries: number;
+  cached_bytes: number;
+}
+
 export interface SyncStats {
   status: SyncStatus;
   queue_size: number;
   last_sync: string | null;
+  http_cache: HttpCacheStats;
 }
 
 export interface ResearchFinding {
@@ -91,6 +102,7 @@ const defaultConfig: CommanderConfig = {
   alert_on_critical: true,
   sync_to_cosmic_library: true,
   offline_mode_enabled: true,
+  archive_findings: false,
 };
 
 export const useCommanderStore = create<CommanderState>((set, get) => ({
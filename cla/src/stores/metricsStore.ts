@@ -10,6 +10,11 @@ export interface SystemMetrics {
   ram_total_mb: number;
   ram_usage_percent: number;
 
+  // CLA's own process and its child processes
+  process_cpu_percent: number;
+  process_ram_mb: number;
+  process_count: number;
+
   // GPU
   gpu_available: boolean;
   gpu_usage_percent: number | null;
@@ -1,13 +1,20 @@
 import { create } from "zustand";
 import { invoke } from "@tauri-apps/api/core";
 
+/** Local vs. CKC (cloud) inference for a task type */
+export type InferencePreference = "prefer_local" | "prefer_cloud" | "local_only" | "cloud_only";
+
 export interface Settings {
-  // Resource limits
+  // Resource limits (CLA's own use, in % of the machine)
   max_cpu_percent: number;
   max_ram_percent: number;
   max_gpu_percent: number;
   max_disk_mb: number;
 
+  // Machine-wide use above which CLA waits
+  max_system_cpu_percent: number;
+  max_system_ram_percent: number;
+
   // Behavior
   idle_only: boolean;
   idle_threshold_seconds: number;
@@ -24,9 +31,12 @@ export interface Settings {
   // Model settings
   enable_transcription: boolean;
   enable_ocr: boolean;
+  ocr_language: string;
   enable_embeddings: boolean;
   download_tier2_models: boolean;
   download_tier3_models: boolean;
+  embedding_inference: InferencePreference;
+  transcription_inference: InferencePreference;
 
   // Connection
   ckc_endpoint: string;
@@ -48,6 +58,8 @@ const defaultSettings: Settings = {
   max_ram_percent: 20,
   max_gpu_percent: 30,
   max_disk_mb: 2000,
+  max_system_cpu_percent: 90,
+  max_system_ram_percent: 90,
   idle_only: true,
   idle_threshold_seconds: 120,
   paused: false,
@@ -59,9 +71,12 @@ const defaultSettings: Settings = {
   offline_mode: false,
   enable_transcription: true,
   enable_ocr: true,
+  ocr_language: "eng",
   enable_embeddings: true,
   download_tier2_models: false,
   download_tier3_models: false,
+  embedding_inference: "local_only",
+  transcription_inference: "local_only",
   ckc_endpoint: "https://ckc.cirkelline.com",
   api_key: null,
 };
@@ -16,6 +16,8 @@ vi.mock('@tauri-apps/api/core', () => ({
           max_ram_percent: 20,
           max_gpu_percent: 30,
           max_disk_mb: 2000,
+          max_system_cpu_percent: 90,
+          max_system_ram_percent: 90,
           idle_only: true,
           idle_threshold_seconds: 120,
           paused: false,
@@ -27,9 +29,12 @@ vi.mock('@tauri-apps/api/core', () => ({
           offline_mode: false,
           enable_transcription: true,
           enable_ocr: true,
+          ocr_language: 'eng',
           enable_embeddings: true,
           download_tier2_models: false,
           download_tier3_models: false,
+          embedding_inference: 'local_only',
+          transcription_inference: 'local_only',
           ckc_endpoint: 'https://ckc.cirkelline.com',
           api_key: null,
         });
@@ -41,6 +46,9 @@ vi.mock('@tauri-apps/api/core', () => ({
           ram_used_mb: 4096,
           ram_total_mb: 16384,
           ram_usage_percent: 25,
+          process_cpu_percent: 2,
+          process_ram_mb: 350,
+          process_count: 1,
           gpu_available: false,
           gpu_usage_percent: null,
           gpu_memory_used_mb: null,
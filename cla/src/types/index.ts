@@ -55,6 +55,8 @@ export interface PendingTask {
   retry_count: number;
   max_retries: number;
   status: TaskStatus;
+  not_before?: string | null;
+  last_error?: string | null;
 }
 
 export type TaskType =
@@ -82,15 +84,67 @@ export interface ModelInfo {
   version: string;
 }
 
+export type ModelLoadState = "loaded" | "unloaded" | "missing" | "failed";
+
+export interface ModelStatus {
+  model_id: string;
+  state: ModelLoadState;
+  memory_mb: number;
+  idle_seconds: number;
+  in_use: boolean;
+}
+
+export type WarmupState = "not_started" | "missing" | "cold" | "warming_up" | "ready" | "failed";
+
+export interface WarmupStatus {
+  model_id: string;
+  state: WarmupState;
+  warmup_ms: number | null;
+  error: string | null;
+}
+
+export interface ModelStateChange {
+  model_id: string;
+  state: ModelLoadState;
+  reason: string;
+}
+
 export interface EmbeddingResult {
   embedding: number[];
   model_used: string;
   processing_time_ms: number;
 }
 
+export interface BatchEmbeddingResult {
+  embeddings: EmbeddingResult[];
+  model_used: string;
+  processing_time_ms: number;
+}
+
+export type FinishReason = "stop" | "length" | "cancelled";
+
+export interface TextGenerationResult {
+  request_id: string;
+  text: string;
+  prompt_tokens: number;
+  completion_tokens: number;
+  finish_reason: FinishReason;
+  /** Why generation was cancelled, e.g. a resource limit that stayed exceeded */
+  stop_reason: string | null;
+  processing_time_ms: number;
+}
+
+/** Payload of the `llm-token` event */
+export interface LlmToken {
+  request_id: string;
+  token: string;
+}
+
 export interface TranscriptionResult {
   text: string;
   language: string | null;
+  /** Language identified from the audio when none was given */
+  detected_language: string | null;
   confidence: number;
   segments: TranscriptionSegment[];
   processing_time_ms: number;
@@ -0,0 +1,304 @@
+"""
+NativeCache and CacheManager from cirkelline.native.
+
+The same tests run against the Rust cache and the Python fallback, so only
+behaviour both promise is asserted: which entry a full cache drops is up to
+the implementation, that it stays within capacity is not.
+"""
+
+import sys
+import threading
+import time
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import CacheManager, NativeCache
+
+
+def wait_past(seconds):
+    time.sleep(seconds + 0.1)
+
+
+def test_set_with_ttl_expires_entry():
+    cache = NativeCache(max_size=100, ttl_seconds=300)
+    cache.set_with_ttl("short", "value", 1)
+    cache.set("long", "value")
+    assert cache.get("short") == "value"
+
+    wait_past(1)
+    assert cache.get("short") is None
+    assert not cache.exists("short")
+    assert cache.get("long") == "value"
+
+
+def test_expire_shortens_existing_entry():
+    cache = NativeCache(max_size=100, ttl_seconds=300)
+    cache.set("key", "value")
+    assert cache.expire("key", 1)
+    assert not cache.expire("missing", 1)
+
+    wait_past(1)
+    assert cache.get("key") is None
+
+
+def test_max_bytes_bounds_total_size():
+    cache = NativeCache(max_bytes=100)
+    for i in range(20):
+        cache.set(f"key{i:02d}", "x" * 15)
+
+    stats = cache.get_stats()
+    assert stats["max_bytes"] == 100
+    assert stats["bytes"] <= 100
+    assert 0 < stats["size"] < 20
+
+
+def test_max_bytes_rejects_oversized_value():
+    cache = NativeCache(max_bytes=50)
+    cache.set("big", "small")
+    cache.set("big", "x" * 100)
+    assert cache.get("big") is None
+
+
+def test_expire_keeps_byte_capacity():
+    cache = NativeCache(max_bytes=64)
+    cache.set("key", "value")
+    cache.set("other", "x" * 40)
+    assert cache.expire("key", 60)
+
+    stats = cache.get_stats()
+    assert stats["bytes"] <= 64
+    assert cache.get("key") == "value"
+
+
+def test_batch_ops_count_hits_and_misses():
+    cache = NativeCache()
+    cache.set_many({"a": "1", "b": "2", "c": "3"})
+
+    assert cache.get_many(["a", "b", "missing"]) == {"a": "1", "b": "2"}
+    stats = cache.get_stats()
+    assert stats["hits"] == 2
+    assert stats["misses"] == 1
+
+    assert cache.delete_many(["a", "c", "missing"]) == 2
+    assert cache.get_many(["a", "b", "c"]) == {"b": "2"}
+
+
+def test_batch_commits_on_exit():
+    cache = NativeCache()
+    cache.set("old", "value")
+    with cache.batch() as batch:
+        batch.set("new", "value")
+        batch.delete("old")
+        assert batch.get("new") == "value"
+        assert batch.get("old") is None
+        assert len(batch) == 2
+        # Nothing is applied before the block exits
+        assert cache.get("old") == "value"
+        assert cache.get("new") is None
+
+    assert cache.get("new") == "value"
+    assert cache.get("old") is None
+
+
+def test_batch_rolls_back_on_exception():
+    cache = NativeCache()
+    with pytest.raises(KeyError):
+        with cache.batch() as batch:
+            batch.set("key", "value")
+            raise KeyError("boom")
+    assert cache.get("key") is None
+
+
+def test_multi_key_reads_never_see_a_partial_batch():
+    cache = NativeCache()
+    cache.set_many({"a": "0", "b": "0"})
+    torn = []
+    done = threading.Event()
+
+    def read():
+        while not done.is_set():
+            values = cache.get_many(["a", "b"])
+            if values["a"] != values["b"]:
+                torn.append(values)
+
+    reader = threading.Thread(target=read)
+    reader.start()
+    try:
+        for i in range(1, 500):
+            with cache.batch() as batch:
+                batch.set("a", str(i))
+                batch.set("b", str(i))
+    finally:
+        done.set()
+        reader.join()
+    assert torn == []
+
+
+def test_partitions_are_isolated():
+    cache = NativeCache()
+    users = cache.partition("users")
+    posts = cache.partition("posts")
+
+    users.set("1", "alice")
+    posts.set("1", "hello")
+    assert users.get("1") == "alice"
+    assert posts.get("1") == "hello"
+    assert cache.get("users:1") == "alice"
+    assert cache.partitions() == ["posts", "users"]
+
+    assert users.clear() == 1
+    assert users.get("1") is None
+    assert posts.get("1") == "hello"
+
+
+def test_partition_stats_and_batch_ops():
+    cache = NativeCache()
+    users = cache.partition("users")
+    users.set_many({"1": "alice", "2": "bob"})
+
+    assert users.get_many(["1", "2", "3"]) == {"1": "alice", "2": "bob"}
+    assert users.delete_many(["2", "3"]) == 1
+
+    stats = users.get_stats()
+    assert stats["name"] == "users"
+    assert stats["hits"] == 2
+    assert stats["misses"] == 1
+    assert stats["size"] == 1
+    assert stats["bytes"] == len("users:1") + len("alice")
+
+
+def test_partition_name_is_validated():
+    cache = NativeCache()
+    with pytest.raises(ValueError):
+        cache.partition("")
+    with pytest.raises(ValueError):
+        cache.partition("a:b")
+
+
+def test_on_evict_reports_expired_entries():
+    evicted = []
+    cache = NativeCache(on_evict=lambda key, value, cause: evicted.append((key, value, cause)))
+    cache.set_with_ttl("key", "value", 1)
+    cache.set("kept", "value")
+    cache.delete("kept")
+
+    wait_past(1)
+    assert cache.get("key") is None
+    cache.get_stats()
+    assert evicted == [("key", "value", "expired")]
+    assert cache.get_stats()["evictions"] == 1
+
+
+def test_on_evict_reports_size_evictions():
+    evicted = []
+    cache = NativeCache(max_size=5, on_evict=lambda key, value, cause: evicted.append(cause))
+    for i in range(20):
+        cache.set(f"key{i}", "value")
+
+    stats = cache.get_stats()
+    assert stats["size"] <= 5
+    assert evicted
+    assert set(evicted) == {"size"}
+    assert stats["evictions"] == len(evicted)
+
+
+def test_on_evict_errors_do_not_break_the_cache():
+    def failing(key, value, cause):
+        raise RuntimeError("callback failed")
+
+    cache = NativeCache(max_size=2, on_evict=failing)
+    for i in range(10):
+        cache.set(f"key{i}", "value")
+    assert cache.get_stats()["size"] <= 2
+
+
+def test_on_evict_can_use_the_manager():
+    manager = CacheManager()
+    evicted = []
+
+    def on_evict(key, value, cause):
+        # Creating a cache takes the manager's lock; the callback must not run under it
+        manager.cache("evicted").set(key, value)
+        evicted.append(key)
+
+    cache = manager.cache("sessions", on_evict=on_evict)
+    cache.set_with_ttl("key", "value", 1)
+    wait_past(1)
+
+    manager.get_stats()
+    manager.metrics_prometheus()
+    assert evicted == ["key"]
+    assert manager.get("evicted").get("key") == "value"
+
+
+def test_metrics_prometheus_format():
+    cache = NativeCache()
+    cache.set("key", "value")
+    cache.get("key")
+    cache.get("missing")
+
+    text = cache.metrics_prometheus("sessions")
+    assert "# TYPE cirkelline_cache_hits_total counter" in text
+    assert 'cirkelline_cache_hits_total{cache="sessions"} 1' in text
+    assert 'cirkelline_cache_misses_total{cache="sessions"} 1' in text
+    assert 'cirkelline_cache_entries{cache="sessions"} 1' in text
+    assert 'cirkelline_cache_hit_ratio{cache="sessions"} 0.5' in text
+    assert text.endswith("\n")
+
+    with pytest.raises(ValueError):
+        cache.metrics_prometheus(prefix="1bad")
+
+
+def test_manager_metrics_label_every_cache():
+    manager = CacheManager()
+    manager.cache("a").set("key", "value")
+    manager.cache("b").get("missing")
+
+    text = manager.metrics_prometheus(prefix="app_cache")
+    assert 'app_cache_entries{cache="a"} 1' in text
+    assert 'app_cache_misses_total{cache="b"} 1' in text
+
+
+def test_manager_keeps_policy_of_existing_cache():
+    manager = CacheManager()
+    first = manager.cache("sessions", max_size=10, ttl_seconds=60)
+    again = manager.cache("sessions", max_size=99)
+    again.set("key", "value")
+
+    assert first.get("key") == "value"
+    assert first.get_stats()["max_size"] == 10
+    assert manager.names() == ["sessions"]
+    assert manager.get("missing") is None
+
+
+def test_manager_stats_aggregate_caches():
+    manager = CacheManager()
+    manager.cache("a").set("key", "value")
+    manager.cache("a").get("key")
+    manager.cache("b").get("missing")
+
+    stats = manager.get_stats()
+    assert stats["cache_count"] == 2
+    assert stats["hits"] == 1
+    assert stats["misses"] == 1
+    assert stats["size"] == 1
+    assert stats["caches"]["a"]["hits"] == 1
+
+    manager.clear_all()
+    assert manager.get_stats()["size"] == 0
+    assert manager.remove("a")
+    assert not manager.remove("a")
+
+
+def test_reconfigure_keeps_entries():
+    cache = NativeCache(max_size=100, ttl_seconds=300)
+    cache.set("key", "value")
+    cache.reconfigure(max_size=50, ttl_seconds=60)
+
+    stats = cache.get_stats()
+    assert stats["max_size"] == 50
+    assert stats["ttl_seconds"] == 60
+    assert cache.get("key") == "value"
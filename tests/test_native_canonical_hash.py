@@ -0,0 +1,48 @@
+"""
+Canonical hash parity between cirkelline.native and the CLA agent.
+
+Both sides are checked against the same vectors in
+cirkelline/native/test_vectors/canonical_hash.json.
+"""
+
+import importlib.util
+import json
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import NATIVE_AVAILABLE, canonical_hash, canonical_text
+
+VECTORS = json.loads(
+    (
+        Path(__file__).parent.parent
+        / "cirkelline"
+        / "native"
+        / "test_vectors"
+        / "canonical_hash.json"
+    ).read_text(encoding="utf-8")
+)["vectors"]
+
+HASH_AVAILABLE = NATIVE_AVAILABLE or importlib.util.find_spec("xxhash") is not None
+
+
+@pytest.mark.parametrize("vector", VECTORS, ids=lambda v: repr(v["input"]))
+def test_canonical_text_matches_vectors(vector):
+    assert canonical_text(vector["input"]) == vector["canonical"]
+
+
+@pytest.mark.skipif(not HASH_AVAILABLE, reason="needs native module or xxhash")
+@pytest.mark.parametrize("vector", VECTORS, ids=lambda v: repr(v["input"]))
+def test_canonical_hash_matches_vectors(vector):
+    assert canonical_hash(vector["input"]) == vector["hash"]
+
+
+def test_equivalent_titles_share_hash():
+    if not HASH_AVAILABLE:
+        pytest.skip("needs native module or xxhash")
+    assert canonical_hash("Attention Is All You Need") == canonical_hash(
+        "attention is all you need."
+    )
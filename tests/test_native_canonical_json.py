@@ -0,0 +1,51 @@
+"""
+canonical_json: semantically-equal JSON payloads must produce the same text
+and hash, with the native module and the Python fallback alike.
+"""
+
+import importlib.util
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import NATIVE_AVAILABLE, canonical_json, canonical_json_hash
+
+HASH_AVAILABLE = NATIVE_AVAILABLE or importlib.util.find_spec("xxhash") is not None
+
+
+@pytest.mark.parametrize(
+    "payload,expected",
+    [
+        ('{ "b": 1, "a": 2 }', '{"a":2,"b":1}'),
+        ('{"outer": {"z": null, "y": [true, false]}}', '{"outer":{"y":[true,false],"z":null}}'),
+        ("[1.0, 1, 1e0, 2.50, -0.0]", "[1,1,1,2.5,0]"),
+        ("[1e21, 1e20, 1e-7, 0.000001, 1.5e300]", "[1e+21,100000000000000000000,1e-7,0.000001,1.5e+300]"),
+        ("[9007199254740993, 18446744073709551615]", "[9007199254740993,18446744073709551615]"),
+        ('"line\\nbreak \\u00e9"', '"line\\nbreak é"'),
+    ],
+)
+def test_canonical_json(payload, expected):
+    assert canonical_json(payload) == expected
+
+
+def test_canonical_json_is_idempotent():
+    text = canonical_json('{"b": [3.25, {"d": 1, "c": 2}], "a": "x"}')
+    assert canonical_json(text) == text
+
+
+@pytest.mark.parametrize("payload", ["{bad", "NaN", "[Infinity]", ""])
+def test_canonical_json_rejects_invalid(payload):
+    with pytest.raises(ValueError):
+        canonical_json(payload)
+
+
+def test_equal_payloads_share_hash():
+    if not HASH_AVAILABLE:
+        pytest.skip("needs native module or xxhash")
+    assert canonical_json_hash('{"model": "x", "temperature": 1}') == canonical_json_hash(
+        '{"temperature": 1.0,\n "model": "x"}'
+    )
+    assert canonical_json_hash('{"a": 1}') != canonical_json_hash('{"a": 2}')
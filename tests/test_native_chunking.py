@@ -0,0 +1,60 @@
+"""
+Token counting and RAG chunking with cirkelline.native.
+
+The native module and the Python fallback must split text identically.
+"""
+
+import re
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import chunk_text, chunk_texts, count_tokens
+
+TEXT = (
+    "Local models can summarize papers. They run while the computer is idle! "
+    "Does that work offline? Yes.\nFindings\nSupercalifragilisticexpialidocious words get cut."
+)
+
+
+def test_count_tokens():
+    assert count_tokens("") == 0
+    assert count_tokens("the cat") == 2
+    # Four letters per token, punctuation counts on its own
+    assert count_tokens("tokenization,") == 4
+    assert count_tokens("  \n\t ") == 0
+
+
+def test_chunks_respect_limit_and_keep_all_text():
+    chunks = chunk_text(TEXT, 12)
+    assert all(count_tokens(chunk) <= 12 for chunk in chunks)
+    assert re.sub(r"\s+", "", "".join(chunks)) == re.sub(r"\s+", "", TEXT)
+
+
+def test_chunks_prefer_sentence_ends():
+    chunks = chunk_text(TEXT, 12)
+    assert chunks[0] == "Local models can summarize papers."
+    assert chunks[2] == "Does that work offline? Yes.\nFindings"
+
+
+def test_overlap_repeats_tail_of_previous_chunk():
+    assert chunk_text("a b c d e f g h", 4, overlap=2) == ["a b c d", "c d e f", "e f g h"]
+
+
+def test_long_words_are_cut():
+    chunks = chunk_text("x" * 40, 3)
+    assert chunks == ["x" * 12, "x" * 12, "x" * 12, "x" * 4]
+
+
+def test_batch_matches_single():
+    texts = [TEXT, "", "short"]
+    assert chunk_texts(texts, 10, overlap=3) == [chunk_text(t, 10, 3) for t in texts]
+
+
+@pytest.mark.parametrize("max_tokens,overlap", [(0, 0), (5, 5), (5, -1)])
+def test_invalid_arguments(max_tokens, overlap):
+    with pytest.raises((ValueError, OverflowError)):
+        chunk_text(TEXT, max_tokens, overlap)
@@ -0,0 +1,65 @@
+"""
+Embedding re-ranking with cirkelline.native.top_k_similar and friends.
+
+Scores follow the CLA vector search: cosine similarity, 0.0 for zero vectors.
+"""
+
+import array
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import (
+    batch_cosine_similarity,
+    cosine_similarity,
+    cosine_topk,
+    top_k_similar,
+)
+
+QUERY = [1.0, 0.0, 0.0]
+MATRIX = [
+    [1.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0],
+    [0.5, 0.5, 0.0],
+    [0.0, 0.0, 0.0],
+    [-1.0, 0.0, 0.0],
+]
+
+
+def test_topk_order_and_scores():
+    result = cosine_topk(QUERY, MATRIX, 3)
+    assert [index for index, _ in result] == [0, 2, 1]
+    assert result[0][1] == pytest.approx(1.0)
+    assert result[1][1] == pytest.approx(0.70710678, rel=1e-5)
+
+
+def test_flat_buffer_matches_lists():
+    flat = array.array("f", [x for row in MATRIX for x in row])
+    assert [i for i, _ in cosine_topk(QUERY, flat, 5)] == [
+        i for i, _ in cosine_topk(QUERY, MATRIX, 5)
+    ]
+
+
+def test_batch_scores_in_row_order():
+    scores = batch_cosine_similarity(QUERY, MATRIX)
+    assert scores == pytest.approx([1.0, 0.0, 0.70710678, 0.0, -1.0], rel=1e-5)
+    flat = array.array("f", [x for row in MATRIX for x in row])
+    shaped = memoryview(flat).cast("B").cast("f", shape=[len(MATRIX), 3])
+    assert batch_cosine_similarity(array.array("f", QUERY), shaped) == scores
+
+
+def test_top_k_similar_matches_cosine_topk():
+    assert top_k_similar(QUERY, MATRIX, 3) == cosine_topk(QUERY, MATRIX, 3)
+    a, b = array.array("f", [1.0, 2.0]), array.array("f", [2.0, 4.0])
+    assert cosine_similarity(a, b) == pytest.approx(1.0)
+
+
+def test_zero_vector_and_dimension_mismatch():
+    assert cosine_similarity([0.0, 0.0], [1.0, 2.0]) == 0.0
+    with pytest.raises(ValueError, match="Dimension mismatch"):
+        cosine_topk(QUERY, [[1.0, 2.0]], 1)
+    with pytest.raises(ValueError, match="Dimension mismatch"):
+        batch_cosine_similarity(QUERY, [[1.0, 2.0]])
@@ -0,0 +1,83 @@
+"""
+Validation of CLA telemetry reports with cirkelline.native.validate_json_schema.
+
+The schema is generated by the CLA reporter (telemetry::schema::report_json_schema)
+and published at cla/src-tauri/schemas/telemetry-report.schema.json.
+"""
+
+import importlib.util
+import json
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import NATIVE_AVAILABLE, SchemaValidator, validate_json_schema
+
+SCHEMA = (
+    Path(__file__).parent.parent
+    / "cla"
+    / "src-tauri"
+    / "schemas"
+    / "telemetry-report.schema.json"
+).read_text(encoding="utf-8")
+
+pytestmark = pytest.mark.skipif(
+    not NATIVE_AVAILABLE and importlib.util.find_spec("jsonschema") is None,
+    reason="needs native module or jsonschema",
+)
+
+
+def _report(events):
+    return json.dumps(
+        {
+            "schema_version": 4,
+            "session_id": "0f3c",
+            "version": "0.1",
+            "platform": "linux",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "metrics": {},
+            "events": events,
+            "sequence": 1,
+        }
+    )
+
+
+def test_valid_report():
+    report = _report(
+        [
+            {
+                "type": "FeatureUsed",
+                "feature": "voice",
+                "count": 4,
+                "timestamp": "2026-01-01T00:00:00Z",
+            }
+        ]
+    )
+    assert validate_json_schema(report, SCHEMA) == []
+
+
+def test_percent_out_of_range():
+    report = _report(
+        [
+            {
+                "type": "ResourceSnapshot",
+                "avg_cpu_percent": 140,
+                "avg_ram_percent": 20,
+                "idle_hours": 1,
+                "timestamp": "2026-01-01T00:00:00Z",
+            }
+        ]
+    )
+    errors = validate_json_schema(report, SCHEMA)
+    assert len(errors) == 1
+    assert errors[0].startswith("/events/0/avg_cpu_percent")
+
+
+def test_unknown_event_and_missing_fields():
+    validator = SchemaValidator(SCHEMA)
+    report = _report([{"type": "Unknown", "timestamp": "2026-01-01T00:00:00Z"}])
+    assert not validator.is_valid(report)
+    assert not validator.is_valid(json.dumps({"events": []}))
@@ -0,0 +1,36 @@
+"""
+Columnar NDJSON parsing with cirkelline.native.parse_ndjson.
+"""
+
+import json
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import parse_ndjson
+
+
+def test_columns_and_missing_keys():
+    data = b'{"id": 1, "score": 0.5, "tags": ["a"]}\n\n{"id": 2}\n'
+    columns = parse_ndjson(data, ["id", "score", "tags"])
+    assert columns == {
+        "id": [1, 2],
+        "score": [0.5, None],
+        "tags": [["a"], None],
+    }
+
+
+def test_matches_json_loads():
+    records = [{"id": i, "score": i / 3, "name": f"r{i}"} for i in range(1000)]
+    data = "\n".join(json.dumps(r) for r in records).encode()
+    columns = parse_ndjson(data, ["score", "name"])
+    assert columns["score"] == [r["score"] for r in records]
+    assert columns["name"] == [r["name"] for r in records]
+
+
+def test_invalid_line_reports_line_number():
+    with pytest.raises(ValueError, match="line 3"):
+        parse_ndjson(b'{"id": 1}\n{"id": 2}\n{"id": \n', ["id"])
@@ -0,0 +1,52 @@
+"""
+Embedding quantization shared with the CLA knowledge store.
+
+Vectors in cirkelline/native/test_vectors/embedding_quantization.json are also
+checked by the CLA agent (inference::quantize), so blobs decode identically.
+"""
+
+import json
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import dequantize_embeddings, quantize_embeddings
+
+VECTORS = json.loads(
+    (
+        Path(__file__).parent.parent
+        / "cirkelline"
+        / "native"
+        / "test_vectors"
+        / "embedding_quantization.json"
+    ).read_text(encoding="utf-8")
+)
+
+
+@pytest.mark.parametrize("case", VECTORS["int8"])
+def test_shared_int8_vectors(case):
+    blob = quantize_embeddings(case["vectors"], "int8")
+    assert blob.hex() == case["blob"]
+    assert dequantize_embeddings(blob, "int8") == case["decoded"]
+
+
+def test_float32_roundtrip():
+    vectors = [[0.5, -1.25, 3.0], [0.0, 0.0, 0.0]]
+    blob = quantize_embeddings(vectors, "float32")
+    assert len(blob) == 9 + 2 * 3 * 4
+    assert dequantize_embeddings(blob, "float32") == vectors
+
+
+def test_rejects_bad_input():
+    with pytest.raises(ValueError, match="same dimension"):
+        quantize_embeddings([[1.0], [1.0, 2.0]])
+    with pytest.raises(ValueError, match="scheme"):
+        quantize_embeddings([[1.0]], "int4")
+    blob = quantize_embeddings([[0.5, -0.5]])
+    with pytest.raises(ValueError):
+        dequantize_embeddings(blob[:-1])
+    with pytest.raises(ValueError):
+        dequantize_embeddings(blob, "float32")
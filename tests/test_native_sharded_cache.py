@@ -0,0 +1,141 @@
+"""
+ShardedCache from cirkelline.native, shared between processes.
+
+Each process serves the shards it claims over a unix socket in the cache
+directory; the others reach them through it. A second Python process stands
+in for another uvicorn worker.
+"""
+
+import os
+import subprocess
+import sys
+import textwrap
+from pathlib import Path
+
+import pytest
+
+sys.path.insert(0, str(Path(__file__).parent.parent))
+
+from cirkelline.native import ShardedCache
+
+pytestmark = pytest.mark.skipif(sys.platform == "win32", reason="shards are served over unix sockets")
+
+WORKER = textwrap.dedent(
+    """
+    import sys
+    from cirkelline.native import ShardedCache
+
+    cache = ShardedCache(sys.argv[1], shards=4)
+    for line in sys.stdin:
+        op, _, rest = line.strip().partition(" ")
+        if op == "set":
+            key, _, value = rest.partition(" ")
+            cache.set(key, value)
+            print("ok", flush=True)
+        elif op == "get":
+            print(cache.get(rest), flush=True)
+        elif op == "owned":
+            print(",".join(map(str, cache.owned_shards())), flush=True)
+        elif op == "exit":
+            break
+    """
+)
+
+
+class Worker:
+    """Another process using the same cache directory, driven over stdin."""
+
+    def __init__(self, path):
+        env = dict(os.environ, PYTHONPATH=os.pathsep.join(sys.path))
+        self.process = subprocess.Popen(
+            [sys.executable, "-c", WORKER, str(path)],
+            stdin=subprocess.PIPE,
+            stdout=subprocess.PIPE,
+            text=True,
+            env=env,
+        )
+
+    def ask(self, line):
+        self.process.stdin.write(line + "\n")
+        self.process.stdin.flush()
+        return self.process.stdout.readline().strip()
+
+    def exit(self):
+        self.process.stdin.write("exit\n")
+        self.process.stdin.close()
+        self.process.wait(timeout=10)
+
+
+@pytest.fixture
+def worker(tmp_path):
+    worker = Worker(tmp_path)
+    yield worker
+    if worker.process.poll() is None:
+        worker.process.kill()
+        worker.process.wait()
+
+
+def test_single_process_operations(tmp_path):
+    cache = ShardedCache(str(tmp_path), shards=4)
+    cache.set("a", "1")
+    cache.set_with_ttl("b", "2", 60)
+
+    assert cache.get("a") == "1"
+    assert cache.get("missing") is None
+    assert cache.exists("b")
+    assert cache.size() == 2
+    assert cache.delete("a")
+    assert not cache.delete("a")
+
+    # Alone, this process ends up serving every shard it touched
+    assert cache.owned_shards()
+    stats = cache.get_stats()
+    assert stats["hits"] == 1
+    assert stats["misses"] == 1
+    assert stats["shards"] == 4
+
+    cache.clear()
+    assert cache.size() == 0
+
+
+def test_keys_spread_over_shards(tmp_path):
+    cache = ShardedCache(str(tmp_path), shards=4)
+    shards = [cache.shard_for(f"key{i}") for i in range(200)]
+
+    assert set(shards) == {0, 1, 2, 3}
+    assert all(cache.shard_for(f"key{i}") == shard for i, shard in enumerate(shards))
+
+
+def test_shards_must_be_positive(tmp_path):
+    with pytest.raises(ValueError):
+        ShardedCache(str(tmp_path), shards=0)
+
+
+def test_writes_are_seen_by_other_processes(tmp_path, worker):
+    cache = ShardedCache(str(tmp_path), shards=4)
+    assert worker.ask("owned") != ""
+
+    keys = [f"key{i}" for i in range(20)]
+    for key in keys:
+        assert worker.ask(f"set {key} from-worker") == "ok"
+    assert all(cache.get(key) == "from-worker" for key in keys)
+
+    cache.set("key0", "from-test")
+    cache.delete("key1")
+    assert worker.ask("get key0") == "from-test"
+    assert worker.ask("get key1") == "None"
+
+
+def test_shards_are_taken_over_when_owner_exits(tmp_path, worker):
+    cache = ShardedCache(str(tmp_path), shards=4)
+    worker_shards = {int(index) for index in worker.ask("owned").split(",")}
+    key = next(f"key{i}" for i in range(1000) if cache.shard_for(f"key{i}") in worker_shards)
+    assert worker.ask(f"set {key} value") == "ok"
+    assert cache.get(key) == "value"
+
+    worker.exit()
+    # The shard comes back empty, served by this process
+    assert cache.get(key) is None
+    cache.set(key, "again")
+    assert cache.get(key) == "again"
+    assert cache.shard_for(key) in cache.owned_shards()